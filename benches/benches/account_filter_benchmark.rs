@@ -0,0 +1,115 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use ethrex_blockchain::vm::StoreVmDatabase;
+use ethrex_common::{
+    Address, U256,
+    types::{
+        Block, BlockBody, BlockHeader, EIP1559Transaction, Genesis, GenesisAccount, Transaction,
+        TxKind,
+    },
+};
+use ethrex_l2_rpc::signer::{LocalSigner, Signable, Signer};
+use ethrex_storage::{EngineType, Store};
+use ethrex_vm::{Evm, SimulatedHeader, SimulationChain};
+use secp256k1::SecretKey;
+
+const SENDERS: u8 = 16;
+const TXS_PER_SENDER: u64 = 128;
+
+/// Returns an airdrop-style block: every transaction funds a fresh account.
+async fn setup(store: &Store) -> Block {
+    let genesis_file = include_bytes!("../../fixtures/genesis/execution-api.json");
+    let mut genesis: Genesis = serde_json::from_slice(genesis_file).unwrap();
+    let chain_id = genesis.config.chain_id;
+
+    let signers: Vec<Signer> = (1..=SENDERS)
+        .map(|i| LocalSigner::new(SecretKey::from_byte_array(&[i; 32]).unwrap()).into())
+        .collect();
+    for signer in &signers {
+        genesis.alloc.insert(
+            signer.address(),
+            GenesisAccount {
+                code: Default::default(),
+                storage: Default::default(),
+                balance: U256::from(10).pow(U256::from(20)),
+                nonce: 0,
+            },
+        );
+    }
+
+    let mut store = store.clone();
+    store.add_initial_state(genesis).await.unwrap();
+
+    let mut transactions = Vec::new();
+    let mut recipient = 0xa1d0_0000;
+    for nonce in 0..TXS_PER_SENDER {
+        for signer in &signers {
+            let mut tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+                chain_id,
+                nonce,
+                to: TxKind::Call(Address::from_low_u64_be(recipient)),
+                value: U256::one(),
+                gas_limit: 21_000,
+                max_fee_per_gas: 10_000_000_000,
+                max_priority_fee_per_gas: 1_000_000_000,
+                ..Default::default()
+            });
+            tx.sign_inplace(signer).await.unwrap();
+            transactions.push(tx);
+            recipient += 1;
+        }
+    }
+
+    let header = SimulationChain::from_evm(&new_evm(&store), genesis_header(&store))
+        .unwrap()
+        .next_header(&SimulatedHeader::default(), &transactions);
+    Block::new(
+        header,
+        BlockBody {
+            transactions,
+            ommers: Vec::new(),
+            withdrawals: Some(Vec::new()),
+        },
+    )
+}
+
+fn genesis_header(store: &Store) -> BlockHeader {
+    store.get_block_header(0).unwrap().unwrap()
+}
+
+fn new_evm(store: &Store) -> Evm {
+    let vm_db = StoreVmDatabase::new(store.clone(), genesis_header(store)).unwrap();
+    Evm::new_for_l1(vm_db)
+}
+
+fn execute(store: &Store, block: &Block) {
+    let mut evm = new_evm(store);
+    evm.warm_cache();
+    evm.execute_block(block).unwrap();
+}
+
+fn account_filter_benchmark(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let _guard = runtime.enter();
+
+    let plain_path = tempfile::TempDir::new().unwrap();
+    let plain_store = Store::new(plain_path.path(), EngineType::RocksDB).unwrap();
+    let block = runtime.block_on(setup(&plain_store));
+
+    let filtered_path = tempfile::TempDir::new().unwrap();
+    let filtered_store = Store::new(filtered_path.path(), EngineType::RocksDB).unwrap();
+    runtime.block_on(setup(&filtered_store));
+    filtered_store.enable_account_filter().unwrap();
+
+    let mut group = c.benchmark_group("airdrop_block");
+    group.sample_size(20);
+    group.bench_function("account_filter_off", |b| {
+        b.iter(|| execute(&plain_store, &block))
+    });
+    group.bench_function("account_filter_on", |b| {
+        b.iter(|| execute(&filtered_store, &block))
+    });
+    group.finish();
+}
+
+criterion_group!(account_filter, account_filter_benchmark);
+criterion_main!(account_filter);
@@ -0,0 +1,70 @@
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use ethrex_blockchain::vm::StoreVmDatabase;
+use ethrex_common::types::{BlockHeader, Genesis};
+use ethrex_storage::{EngineType, Store};
+use ethrex_vm::VmDatabase;
+
+/// Canonical blocks on top of genesis, more than the BLOCKHASH window
+const CHAIN_LENGTH: u64 = 300;
+
+/// Times a contract reads every ancestor reachable through BLOCKHASH
+const LOOPS: usize = 4;
+
+async fn setup_chain(store: &Store) -> BlockHeader {
+    let genesis_file = include_bytes!("../../fixtures/genesis/l1.json");
+    let genesis: Genesis = serde_json::from_slice(genesis_file).unwrap();
+    let mut store = store.clone();
+    store.add_initial_state(genesis).await.unwrap();
+
+    let mut headers: Vec<BlockHeader> = Vec::new();
+    let genesis_header = store.get_block_header(0).unwrap().unwrap();
+    for _ in 0..CHAIN_LENGTH {
+        let parent = headers.last().unwrap_or(&genesis_header);
+        headers.push(BlockHeader {
+            number: parent.number + 1,
+            parent_hash: parent.hash(),
+            state_root: parent.state_root,
+            timestamp: parent.timestamp + 12,
+            ..Default::default()
+        });
+    }
+    store.add_block_headers(headers.clone()).await.unwrap();
+
+    let head = headers.last().unwrap().clone();
+    store
+        .forkchoice_update(
+            headers.iter().map(|h| (h.number, h.hash())).collect(),
+            head.number,
+            head.hash(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    head
+}
+
+fn block_hash_window_benchmark(c: &mut Criterion) {
+    let storage_path = tempfile::TempDir::new().unwrap();
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let _guard = runtime.enter();
+    let store = Store::new(storage_path.path(), EngineType::RocksDB).unwrap();
+    let head = runtime.block_on(setup_chain(&store));
+    let window_start = head.number - 255;
+
+    c.bench_function("blockhash_loop_256_ancestors", |b| {
+        b.iter(|| {
+            let vm_db = StoreVmDatabase::new(store.clone(), head.clone()).unwrap();
+            for _ in 0..LOOPS {
+                for number in window_start..=head.number {
+                    black_box(vm_db.get_block_hash(black_box(number)).unwrap());
+                }
+            }
+        })
+    });
+}
+
+criterion_group!(block_hash_window, block_hash_window_benchmark);
+criterion_main!(block_hash_window);
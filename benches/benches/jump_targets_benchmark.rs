@@ -0,0 +1,37 @@
+use std::hint::black_box;
+
+use bytes::Bytes;
+use criterion::{Criterion, criterion_group, criterion_main};
+use ethrex_common::{H256, types::Code};
+
+/// EIP-170 contract size limit
+const MAX_CODE_SIZE: usize = 0x6000;
+
+/// Builds a contract at the size limit mixing JUMPDESTs with PUSH data that looks like one.
+fn large_contract() -> Bytes {
+    // JUMPDEST, PUSH2 0x5b5b, ADD, JUMP
+    let chunk = [0x5b, 0x61, 0x5b, 0x5b, 0x01, 0x56];
+    chunk
+        .iter()
+        .copied()
+        .cycle()
+        .take(MAX_CODE_SIZE)
+        .collect::<Vec<u8>>()
+        .into()
+}
+
+fn jump_targets_benchmark(c: &mut Criterion) {
+    let bytecode = large_contract();
+    let code = Code::from_bytecode_unchecked(bytecode.clone(), H256::zero());
+    let last_target = code.jump_targets.last().copied().unwrap_or_default();
+
+    c.bench_function("compute_jump_targets_max_code_size", |b| {
+        b.iter(|| Code::from_bytecode_unchecked(black_box(bytecode.clone()), H256::zero()))
+    });
+    c.bench_function("lookup_jump_target_max_code_size", |b| {
+        b.iter(|| code.jump_targets.binary_search(black_box(&last_target)))
+    });
+}
+
+criterion_group!(jump_targets, jump_targets_benchmark);
+criterion_main!(jump_targets);
@@ -0,0 +1,203 @@
+use std::{hint::black_box, sync::Arc};
+
+use bytes::Bytes;
+use criterion::{Criterion, criterion_group, criterion_main};
+use ethrex_blockchain::vm::StoreVmDatabase;
+use ethrex_common::{
+    Address, H256, U256,
+    types::{
+        Block, BlockBody, BlockHeader, EIP1559Transaction, Genesis, GenesisAccount, Transaction,
+        TxKind,
+    },
+    utils::keccak,
+};
+use ethrex_l2_rpc::signer::{LocalSigner, Signable, Signer};
+use ethrex_levm::db::{CacheStats, CachingDatabase, KeccakCache, KeccakCacheLimits};
+use ethrex_storage::{EngineType, Store};
+use ethrex_vm::{Evm, SimulatedHeader, SimulationChain};
+use secp256k1::SecretKey;
+
+// Moves one unit of a token from the caller to the address given in calldata:
+// mstore(0, caller); mstore(32, 0); s = keccak(0, 64); sstore(s, sload(s) - 1)
+// mstore(0, calldataload(0)); s = keccak(0, 64); sstore(s, sload(s) + 1)
+const TRANSFER_CODE: &[u8] = &[
+    0x33, 0x60, 0x00, 0x52, 0x60, 0x00, 0x60, 0x20, 0x52, 0x60, 0x40, 0x60, 0x00, 0x20, 0x80, 0x54,
+    0x60, 0x01, 0x90, 0x03, 0x90, 0x55, 0x60, 0x00, 0x35, 0x60, 0x00, 0x52, 0x60, 0x40, 0x60, 0x00,
+    0x20, 0x80, 0x54, 0x60, 0x01, 0x01, 0x90, 0x55, 0x00,
+];
+const SENDERS: u8 = 50;
+const TXS_PER_SENDER: u64 = 10;
+/// Recipients the transfers are spread over, so most of them are hit many times.
+const RECIPIENTS: u64 = 20;
+
+/// Returns a block of 500 token transfers between overlapping accounts.
+async fn setup(store: &Store) -> Block {
+    let genesis_file = include_bytes!("../../fixtures/genesis/execution-api.json");
+    let mut genesis: Genesis = serde_json::from_slice(genesis_file).unwrap();
+    let chain_id = genesis.config.chain_id;
+
+    let token = Address::from_low_u64_be(0xe2c20);
+    genesis.alloc.insert(
+        token,
+        GenesisAccount {
+            code: Bytes::from_static(TRANSFER_CODE),
+            storage: Default::default(),
+            balance: U256::zero(),
+            nonce: 1,
+        },
+    );
+
+    let signers: Vec<Signer> = (1..=SENDERS)
+        .map(|i| LocalSigner::new(SecretKey::from_byte_array(&[i; 32]).unwrap()).into())
+        .collect();
+    for signer in &signers {
+        genesis.alloc.insert(
+            signer.address(),
+            GenesisAccount {
+                code: Bytes::new(),
+                storage: Default::default(),
+                balance: U256::from(10).pow(U256::from(20)),
+                nonce: 0,
+            },
+        );
+    }
+
+    let mut store = store.clone();
+    store.add_initial_state(genesis).await.unwrap();
+
+    let mut transactions = Vec::new();
+    for nonce in 0..TXS_PER_SENDER {
+        for signer in &signers {
+            let recipient =
+                Address::from_low_u64_be(0x1000 + transactions.len() as u64 % RECIPIENTS);
+            let mut tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+                chain_id,
+                nonce,
+                to: TxKind::Call(token),
+                data: Bytes::copy_from_slice(H256::from(recipient).as_bytes()),
+                gas_limit: 100_000,
+                max_fee_per_gas: 10_000_000_000,
+                max_priority_fee_per_gas: 1_000_000_000,
+                ..Default::default()
+            });
+            tx.sign_inplace(signer).await.unwrap();
+            transactions.push(tx);
+        }
+    }
+
+    let header = SimulationChain::from_evm(&new_evm(&store), genesis_header(&store))
+        .unwrap()
+        .next_header(&SimulatedHeader::default(), &transactions);
+    Block::new(
+        header,
+        BlockBody {
+            transactions,
+            ommers: Vec::new(),
+            withdrawals: Some(Vec::new()),
+        },
+    )
+}
+
+fn genesis_header(store: &Store) -> BlockHeader {
+    store.get_block_header(0).unwrap().unwrap()
+}
+
+fn new_evm(store: &Store) -> Evm {
+    let vm_db = StoreVmDatabase::new(store.clone(), genesis_header(store)).unwrap();
+    Evm::new_for_l1(vm_db)
+}
+
+/// Executes `block` through a fresh [`CachingDatabase`] whose keccak cache
+/// is bounded by `limits`.
+fn execute(store: &Store, block: &Block, limits: KeccakCacheLimits) -> CacheStats {
+    let mut evm = new_evm(store);
+    let cache = Arc::new(CachingDatabase::with_keccak_limits(
+        evm.db.store.clone(),
+        limits,
+    ));
+    evm.db.store = cache.clone();
+    evm.execute_block(block).unwrap();
+    cache.stats()
+}
+
+fn keccak_cache_benchmark(c: &mut Criterion) {
+    let storage_path = tempfile::TempDir::new().unwrap();
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let _guard = runtime.enter();
+    let store = Store::new(storage_path.path(), EngineType::RocksDB).unwrap();
+    let block = runtime.block_on(setup(&store));
+
+    let stats = execute(&store, &block, KeccakCacheLimits::default());
+    println!(
+        "keccak cache served {}/{} hashes ({}%) for {} txs",
+        stats.keccak_hits,
+        stats.keccak_lookups,
+        stats.keccak_hit_rate_percent(),
+        block.body.transactions.len()
+    );
+
+    let mut group = c.benchmark_group("erc20_transfer_block");
+    group.sample_size(20);
+    group.bench_function("keccak_cache_off", |b| {
+        b.iter(|| execute(&store, &block, KeccakCacheLimits::DISABLED))
+    });
+    group.bench_function("keccak_cache_on", |b| {
+        b.iter(|| execute(&store, &block, KeccakCacheLimits::default()))
+    });
+    group.finish();
+}
+
+/// Mapping slot preimages, (left-padded key, slot), all distinct for `i < distinct`.
+fn preimages(count: u64, distinct: u64, slot: u64) -> Vec<[u8; 64]> {
+    (0..count)
+        .map(|i| {
+            let mut preimage = [0u8; 64];
+            preimage[24..32].copy_from_slice(&(i % distinct).to_be_bytes());
+            preimage[56..64].copy_from_slice(&slot.to_be_bytes());
+            preimage
+        })
+        .collect()
+}
+
+/// Hashes `preimages` on `threads` threads sharing a fresh cache.
+fn hash_shared(preimages: &[[u8; 64]], threads: usize) {
+    let cache = KeccakCache::new(KeccakCacheLimits::default());
+    std::thread::scope(|scope| {
+        for chunk in preimages.chunks(preimages.len().div_ceil(threads)) {
+            let cache = &cache;
+            scope.spawn(move || {
+                for preimage in chunk {
+                    black_box(cache.hash(preimage));
+                }
+            });
+        }
+    });
+}
+
+/// Cost per hash of a block's worth of 64-byte preimages.
+fn keccak_hash_benchmark(c: &mut Criterion) {
+    const HASHES: u64 = 5_000;
+    let misses = preimages(HASHES, HASHES, 0);
+    let repeated = preimages(HASHES, 500, 0);
+    let four_threads: Vec<_> = (0..4)
+        .flat_map(|slot| preimages(HASHES / 4, HASHES, slot))
+        .collect();
+
+    let mut group = c.benchmark_group("keccak_cache_5000_hashes");
+    group.bench_function("uncached", |b| {
+        b.iter(|| {
+            for preimage in &misses {
+                black_box(keccak(preimage));
+            }
+        })
+    });
+    group.bench_function("all_misses", |b| b.iter(|| hash_shared(&misses, 1)));
+    group.bench_function("500_distinct", |b| b.iter(|| hash_shared(&repeated, 1)));
+    group.bench_function("all_misses_4_threads", |b| {
+        b.iter(|| hash_shared(&four_threads, 4))
+    });
+    group.finish();
+}
+
+criterion_group!(keccak_cache, keccak_cache_benchmark, keccak_hash_benchmark);
+criterion_main!(keccak_cache);
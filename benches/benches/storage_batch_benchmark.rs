@@ -0,0 +1,75 @@
+use std::hint::black_box;
+
+use bytes::Bytes;
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use ethrex_blockchain::vm::StoreVmDatabase;
+use ethrex_common::{
+    Address, H256, U256,
+    types::{BlockHeader, Genesis, GenesisAccount},
+};
+use ethrex_storage::{EngineType, Store};
+use ethrex_vm::VmDatabase;
+
+/// Storage slots of the distributor contract
+const DISTRIBUTOR_SLOTS: u64 = 2_000;
+
+/// Slots a single claim-style transaction reads
+const READS: [u64; 3] = [16, 128, 512];
+
+/// Sets up a genesis with a distributor-style contract that holds one slot per
+/// recipient, and returns the genesis header and the contract's address.
+async fn setup_distributor(store: &Store) -> (BlockHeader, Address) {
+    let genesis_file = include_bytes!("../../fixtures/genesis/l1.json");
+    let mut genesis: Genesis = serde_json::from_slice(genesis_file).unwrap();
+    let distributor = Address::from_low_u64_be(0xd157);
+    genesis.alloc.insert(
+        distributor,
+        GenesisAccount {
+            code: Bytes::from_static(&[0x00]),
+            storage: (1..=DISTRIBUTOR_SLOTS)
+                .map(|slot| (U256::from(slot), U256::from(slot * 1_000)))
+                .collect(),
+            balance: U256::zero(),
+            nonce: 1,
+        },
+    );
+    let mut store = store.clone();
+    store.add_initial_state(genesis).await.unwrap();
+    (store.get_block_header(0).unwrap().unwrap(), distributor)
+}
+
+fn storage_batch_benchmark(c: &mut Criterion) {
+    let storage_path = tempfile::TempDir::new().unwrap();
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let _guard = runtime.enter();
+    let store = Store::new(storage_path.path(), EngineType::RocksDB).unwrap();
+    let (header, distributor) = runtime.block_on(setup_distributor(&store));
+
+    let mut group = c.benchmark_group("storage_reads");
+    for reads in READS {
+        // Spread the reads over the whole storage trie
+        let step = DISTRIBUTOR_SLOTS / reads;
+        let keys: Vec<H256> = (1..=reads)
+            .map(|i| H256::from_low_u64_be(i * step))
+            .collect();
+
+        group.bench_with_input(BenchmarkId::new("per_slot", reads), &keys, |b, keys| {
+            b.iter(|| {
+                let vm_db = StoreVmDatabase::new(store.clone(), header.clone()).unwrap();
+                for key in keys {
+                    black_box(vm_db.get_storage_slot(distributor, *key).unwrap());
+                }
+            })
+        });
+        group.bench_with_input(BenchmarkId::new("batched", reads), &keys, |b, keys| {
+            b.iter(|| {
+                let vm_db = StoreVmDatabase::new(store.clone(), header.clone()).unwrap();
+                black_box(vm_db.get_storage_slots(distributor, keys).unwrap());
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(storage_batch, storage_batch_benchmark);
+criterion_main!(storage_batch);
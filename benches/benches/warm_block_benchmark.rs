@@ -0,0 +1,144 @@
+use bytes::Bytes;
+use criterion::{Criterion, criterion_group, criterion_main};
+use ethrex_blockchain::vm::StoreVmDatabase;
+use ethrex_common::{
+    Address, U256,
+    types::{
+        Block, BlockBody, BlockHeader, EIP1559Transaction, Genesis, GenesisAccount, Transaction,
+        TxKind,
+    },
+};
+use ethrex_l2_rpc::signer::{LocalSigner, Signable, Signer};
+use ethrex_storage::{EngineType, Store};
+use ethrex_vm::backends::levm::LEVM;
+use ethrex_vm::{Evm, SimulatedHeader, SimulationChain};
+use secp256k1::SecretKey;
+
+// Reads 64 consecutive storage slots starting at the slot given in calldata
+const SLOAD_LOOP_CODE: &[u8] = &[
+    0x60, 0x00, 0x35, 0x60, 0x40, 0x5b, 0x80, 0x15, 0x60, 0x1a, 0x57, 0x81, 0x54, 0x50, 0x90, 0x60,
+    0x01, 0x01, 0x90, 0x60, 0x01, 0x90, 0x03, 0x60, 0x05, 0x56, 0x5b, 0x00,
+];
+const SLOTS_PER_TX: u64 = 64;
+const SENDERS: u8 = 16;
+const TXS_PER_SENDER: u64 = 8;
+
+/// Stores a contract with a distinct slot for every transaction to read and
+/// returns a block of storage-heavy calls to it.
+async fn setup(store: &Store) -> Block {
+    let genesis_file = include_bytes!("../../fixtures/genesis/execution-api.json");
+    let mut genesis: Genesis = serde_json::from_slice(genesis_file).unwrap();
+    let chain_id = genesis.config.chain_id;
+    let total_txs = u64::from(SENDERS) * TXS_PER_SENDER;
+
+    let contract = Address::from_low_u64_be(0x5104d);
+    genesis.alloc.insert(
+        contract,
+        GenesisAccount {
+            code: Bytes::from_static(SLOAD_LOOP_CODE),
+            storage: (0..total_txs * SLOTS_PER_TX)
+                .map(|slot| (U256::from(slot), U256::from(slot + 1)))
+                .collect(),
+            balance: U256::zero(),
+            nonce: 1,
+        },
+    );
+
+    let signers: Vec<Signer> = (1..=SENDERS)
+        .map(|i| LocalSigner::new(SecretKey::from_byte_array(&[i; 32]).unwrap()).into())
+        .collect();
+    for signer in &signers {
+        genesis.alloc.insert(
+            signer.address(),
+            GenesisAccount {
+                code: Bytes::new(),
+                storage: Default::default(),
+                balance: U256::from(10).pow(U256::from(20)),
+                nonce: 0,
+            },
+        );
+    }
+
+    let mut store = store.clone();
+    store.add_initial_state(genesis).await.unwrap();
+
+    let mut transactions = Vec::new();
+    let mut start = 0;
+    for nonce in 0..TXS_PER_SENDER {
+        for signer in &signers {
+            let mut tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+                chain_id,
+                nonce,
+                to: TxKind::Call(contract),
+                data: Bytes::from(U256::from(start).to_big_endian().to_vec()),
+                gas_limit: 500_000,
+                max_fee_per_gas: 10_000_000_000,
+                max_priority_fee_per_gas: 1_000_000_000,
+                ..Default::default()
+            });
+            tx.sign_inplace(signer).await.unwrap();
+            transactions.push(tx);
+            start += SLOTS_PER_TX;
+        }
+    }
+
+    let header = SimulationChain::from_evm(&new_evm(&store), genesis_header(&store))
+        .unwrap()
+        .next_header(&SimulatedHeader::default(), &transactions);
+    Block::new(
+        header,
+        BlockBody {
+            transactions,
+            ommers: Vec::new(),
+            withdrawals: Some(Vec::new()),
+        },
+    )
+}
+
+fn genesis_header(store: &Store) -> BlockHeader {
+    store.get_block_header(0).unwrap().unwrap()
+}
+
+fn new_evm(store: &Store) -> Evm {
+    let vm_db = StoreVmDatabase::new(store.clone(), genesis_header(store)).unwrap();
+    Evm::new_for_l1(vm_db)
+}
+
+/// Executes `block` on a fresh cache, warming it concurrently when `warm` is
+/// set like the block import pipeline does.
+fn execute(store: &Store, block: &Block, warm: bool) -> u64 {
+    let mut evm = new_evm(store);
+    let cache = evm.warm_cache();
+    std::thread::scope(|s| {
+        if warm {
+            let cache = cache.clone();
+            let vm_type = evm.vm_type;
+            let _ = s.spawn(move || LEVM::warm_block(block, cache, vm_type));
+        }
+        evm.execute_block(block).unwrap();
+    });
+    cache.stats().warmer_hits
+}
+
+fn warm_block_benchmark(c: &mut Criterion) {
+    let storage_path = tempfile::TempDir::new().unwrap();
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let _guard = runtime.enter();
+    let store = Store::new(storage_path.path(), EngineType::RocksDB).unwrap();
+    let block = runtime.block_on(setup(&store));
+
+    let warmer_hits = execute(&store, &block, true);
+    println!(
+        "warmer served {warmer_hits} executor reads for {} txs",
+        block.body.transactions.len()
+    );
+
+    let mut group = c.benchmark_group("storage_heavy_block");
+    group.sample_size(20);
+    group.bench_function("warmup_off", |b| b.iter(|| execute(&store, &block, false)));
+    group.bench_function("warmup_on", |b| b.iter(|| execute(&store, &block, true)));
+    group.finish();
+}
+
+criterion_group!(warm_block, warm_block_benchmark);
+criterion_main!(warm_block);
@@ -297,6 +297,30 @@ pub struct Options {
         help_heading = "Node options"
     )]
     pub precompute_witnesses: bool,
+    #[arg(
+        long = "keccak-cache",
+        action = ArgAction::SetTrue,
+        default_value = "false",
+        help = "Memoizes the KECCAK256 of repeated 32 and 64-byte preimages while executing a block",
+        help_heading = "Node options"
+    )]
+    pub keccak_cache: bool,
+    #[arg(
+        long = "history.retention",
+        value_name = "BLOCKS",
+        help = "Number of recent blocks whose bodies and receipts are kept. Older ones are pruned periodically, while headers and the genesis block are always kept. On L2, blocks of the latest batch are never pruned. If not set, the full history is kept.",
+        help_heading = "Node options",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub history_retention: Option<u64>,
+    #[arg(
+        long = "account-filter",
+        action = ArgAction::SetTrue,
+        default_value = "false",
+        help = "Keeps a bloom filter of the accounts in the state, so looking up a missing account doesn't walk the state trie. Built at startup and after snap sync by scanning the whole state.",
+        help_heading = "Node options"
+    )]
+    pub account_filter: bool,
 }
 
 impl Options {
@@ -374,6 +398,9 @@ impl Default for Options {
             gas_limit: DEFAULT_BUILDER_GAS_CEIL,
             max_blobs_per_block: None,
             precompute_witnesses: false,
+            keccak_cache: false,
+            history_retention: None,
+            account_filter: false,
         }
     }
 }
@@ -7,7 +7,7 @@ use crate::{
 };
 use ethrex_blockchain::{Blockchain, BlockchainOptions, BlockchainType};
 use ethrex_common::fd_limit::raise_fd_limit;
-use ethrex_common::types::Genesis;
+use ethrex_common::types::{BlockNumber, Genesis};
 use ethrex_config::networks::Network;
 
 use ethrex_metrics::profiling::{FunctionProfilingLayer, initialize_block_processing_profile};
@@ -34,7 +34,7 @@ use std::{
     net::{IpAddr, SocketAddr},
     path::{Path, PathBuf},
     sync::Arc,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 use tokio_util::{sync::CancellationToken, task::TaskTracker};
 #[cfg(not(feature = "l2"))]
@@ -148,6 +148,20 @@ pub async fn init_store(datadir: impl AsRef<Path>, genesis: Genesis) -> Result<S
     Ok(store)
 }
 
+/// Builds the account filter of the Store if enabled through `--account-filter`
+pub async fn init_account_filter(opts: &Options, store: &Store) -> Result<(), StoreError> {
+    if !opts.account_filter {
+        return Ok(());
+    }
+    info!("Building the account filter");
+    let store = store.clone();
+    let accounts = tokio::task::spawn_blocking(move || store.enable_account_filter())
+        .await
+        .map_err(|err| StoreError::Custom(format!("Account filter task failed: {err}")))??;
+    info!("Account filter built for {accounts} accounts");
+    Ok(())
+}
+
 /// Initializes a pre-existing Store
 pub async fn load_store(datadir: &Path) -> Result<Store, StoreError> {
     let store = open_store(datadir)?;
@@ -303,6 +317,44 @@ pub async fn init_dev_network(opts: &Options, store: &Store, tracker: TaskTracke
     tracker.spawn(block_producer_engine);
 }
 
+/// Interval between history pruning passes
+pub const HISTORY_PRUNING_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Periodically prunes the bodies and receipts of blocks older than `retention`
+/// blocks behind the head.
+pub fn init_history_pruning(store: Store, retention: u64, tracker: TaskTracker) {
+    info!("Keeping bodies and receipts of the last {retention} blocks");
+    tracker.spawn(async move {
+        let mut interval = tokio::time::interval(HISTORY_PRUNING_INTERVAL);
+        loop {
+            interval.tick().await;
+            let latest_block_number = match store.get_latest_block_number().await {
+                Ok(number) => number,
+                Err(err) => {
+                    warn!("Failed to get latest block number for history pruning: {err}");
+                    continue;
+                }
+            };
+            prune_history(&store, latest_block_number.saturating_sub(retention)).await;
+        }
+    });
+}
+
+/// Prunes the bodies and receipts of blocks below `prune_before`, logging
+/// any failure.
+pub async fn prune_history(store: &Store, prune_before: BlockNumber) {
+    match store.prune_bodies_before(prune_before).await {
+        Ok(0) => {}
+        Ok(pruned) => debug!("Pruned bodies of {pruned} blocks before {prune_before}"),
+        Err(err) => warn!("Failed to prune block bodies: {err}"),
+    }
+    match store.prune_receipts_before(prune_before).await {
+        Ok(0) => {}
+        Ok(pruned) => debug!("Pruned receipts of {pruned} blocks before {prune_before}"),
+        Err(err) => warn!("Failed to prune receipts: {err}"),
+    }
+}
+
 pub fn get_network(opts: &Options) -> Network {
     let default = if opts.dev {
         Network::LocalDevnet
@@ -476,6 +528,8 @@ pub async fn init_l1(
         store.generate_flatkeyvalue()?;
     }
 
+    init_account_filter(&opts, &store).await?;
+
     #[cfg(feature = "sync-test")]
     set_sync_block(&store).await;
 
@@ -487,6 +541,7 @@ pub async fn init_l1(
             r#type: BlockchainType::L1,
             max_blobs_per_block: opts.max_blobs_per_block,
             precompute_witnesses: opts.precompute_witnesses,
+            keccak_cache: opts.keccak_cache,
         },
     );
 
@@ -505,6 +560,10 @@ pub async fn init_l1(
 
     let cancel_token = tokio_util::sync::CancellationToken::new();
 
+    if let Some(retention) = opts.history_retention {
+        init_history_pruning(store.clone(), retention, tracker.clone());
+    }
+
     let p2p_context = P2PContext::new(
         local_p2p_node.clone(),
         tracker.clone(),
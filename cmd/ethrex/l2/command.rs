@@ -14,14 +14,16 @@ use ethrex_blockchain::{
     Blockchain, BlockchainOptions, BlockchainType, L2Config, fork_choice::apply_fork_choice,
 };
 use ethrex_common::{
-    Address, U256,
+    Address, H256, U256,
     types::{BYTES_PER_BLOB, Block, blobs_bundle, bytes_from_blob, fee_config::FeeConfig},
 };
 use ethrex_common::{types::BlobsBundle, utils::keccak};
 use ethrex_config::networks::Network;
-use ethrex_l2::utils::state_reconstruct::get_batch;
+use ethrex_l2::{sequencer::utils::get_git_commit_hash, utils::state_reconstruct::get_batch};
 use ethrex_l2_common::calldata::Value;
+use ethrex_l2_common::withdrawals::{WithdrawalProver, verify_withdrawal_proof};
 use ethrex_l2_sdk::call_contract;
+use ethrex_prover_lib::{ExecBackend, accounting::AccountingStore, elf_manifest::ElfManifest};
 use ethrex_rlp::decode::RLPDecode as _;
 use ethrex_rpc::{
     EthClient, clients::beacon::BeaconClient, types::block_identifier::BlockIdentifier,
@@ -257,6 +259,96 @@ pub enum Command {
         )]
         network: Option<Network>,
     },
+    #[command(
+        about = "Re-executes a stored batch statelessly and natively, reporting the first divergence."
+    )]
+    CheckBatch {
+        #[arg(long, help = "Number of the batch to check.")]
+        batch: u64,
+        #[arg(
+            long = "datadir",
+            value_name = "DATABASE_DIRECTORY",
+            default_value = default_datadir().into_os_string(),
+            help = "Receives the name of the directory where the Database is located.",
+            env = "ETHREX_DATADIR"
+        )]
+        datadir: PathBuf,
+        #[arg(
+            long = "network",
+            value_name = "GENESIS_FILE_PATH",
+            help = "Receives a `Genesis` struct in json format.",
+            env = "ETHREX_NETWORK",
+            value_parser = clap::value_parser!(Network),
+        )]
+        network: Network,
+        #[arg(
+            long = "prover-version",
+            value_name = "COMMIT_HASH",
+            help = "Version the prover input was stored with. Defaults to this binary's commit hash."
+        )]
+        prover_version: Option<String>,
+    },
+    #[command(
+        about = "Builds merkle proofs of L2 to L1 messages (withdrawals) of a stored batch.",
+        group = clap::ArgGroup::new("withdrawal").required(true)
+    )]
+    WithdrawalProof {
+        #[arg(long, help = "Number of the batch that includes the withdrawal.")]
+        batch: u64,
+        #[arg(
+            long = "message-id",
+            help = "Id of the message to prove.",
+            group = "withdrawal"
+        )]
+        message_id: Option<u64>,
+        #[arg(
+            long = "tx-hash",
+            help = "Hash of the transaction whose messages are proven.",
+            group = "withdrawal"
+        )]
+        tx_hash: Option<H256>,
+        #[arg(
+            long = "datadir",
+            value_name = "DATABASE_DIRECTORY",
+            default_value = default_datadir().into_os_string(),
+            help = "Receives the name of the directory where the Database is located.",
+            env = "ETHREX_DATADIR"
+        )]
+        datadir: PathBuf,
+        #[arg(
+            long = "network",
+            value_name = "GENESIS_FILE_PATH",
+            help = "Receives a `Genesis` struct in json format.",
+            env = "ETHREX_NETWORK",
+            value_parser = clap::value_parser!(Network),
+        )]
+        network: Network,
+    },
+    #[command(
+        about = "Checks a guest program ELF's hash and embedded build info against a manifest."
+    )]
+    VerifyElf {
+        #[arg(long, value_name = "ELF_PATH", help = "Path to the guest program ELF.")]
+        elf: PathBuf,
+        #[arg(
+            long,
+            value_name = "MANIFEST_PATH",
+            help = "TOML manifest with the expected program_id, backend, elf_hash and build_info."
+        )]
+        manifest: PathBuf,
+    },
+    #[command(about = "Reports the proofs generated by a prover per program per day.")]
+    ProverReport {
+        #[arg(
+            long = "accounting-dir",
+            value_name = "PATH",
+            env = "PROVER_CLIENT_ACCOUNTING_DIR",
+            help = "Directory where the prover stores its proof accounting records."
+        )]
+        accounting_dir: PathBuf,
+        #[arg(long, help = "Only report the proofs of this program.")]
+        program: Option<String>,
+    },
     #[command(about = "Pause L1 contracts")]
     Pause {
         #[command(flatten)]
@@ -610,6 +702,32 @@ impl Command {
                     info!("Unpaused OnChainProposer contract");
                 }
             }
+            Command::CheckBatch {
+                batch,
+                datadir,
+                network,
+                prover_version,
+            } => {
+                check_batch(batch, &datadir, network, prover_version).await?;
+            }
+            Command::WithdrawalProof {
+                batch,
+                message_id,
+                tx_hash,
+                datadir,
+                network,
+            } => {
+                withdrawal_proof(batch, message_id, tx_hash, &datadir, network).await?;
+            }
+            Command::VerifyElf { elf, manifest } => {
+                verify_elf(&elf, &manifest)?;
+            }
+            Command::ProverReport {
+                accounting_dir,
+                program,
+            } => {
+                prover_report(&accounting_dir, program.as_deref())?;
+            }
             Command::Pause {
                 contract_call_options: opts,
             } => {
@@ -695,6 +813,159 @@ async fn delete_batch_from_rollup_store(batch: u64, rollup_store_dir: &Path) ->
     Ok(last_kept_block)
 }
 
+async fn check_batch(
+    batch: u64,
+    datadir: &Path,
+    network: Network,
+    prover_version: Option<String>,
+) -> eyre::Result<()> {
+    let store = init_store(datadir, network.get_genesis()?).await?;
+    let rollup_store = l2::initializers::init_rollup_store(&datadir.join("rollup_store")).await;
+    let prover_version = prover_version.unwrap_or_else(get_git_commit_hash);
+
+    let input = rollup_store
+        .get_prover_input_by_batch_and_version(batch, &prover_version)
+        .await?
+        .ok_or_else(|| {
+            eyre::eyre!("No prover input stored for batch {batch} (version {prover_version})")
+        })?;
+    let mut native_fee_configs = Vec::with_capacity(input.blocks.len());
+    for block in &input.blocks {
+        let fee_config = rollup_store
+            .get_fee_config_by_block(block.header.number)
+            .await?
+            .ok_or_else(|| eyre::eyre!("No fee config stored for block {}", block.header.number))?;
+        native_fee_configs.push(fee_config);
+    }
+
+    info!(
+        "Checking batch {batch} ({} blocks) against native execution...",
+        input.blocks.len()
+    );
+    match ExecBackend::new().differential(&input, &store, &native_fee_configs)? {
+        Some(divergence) => {
+            println!("{divergence}");
+            Err(eyre::eyre!("Batch {batch} diverged from native execution"))
+        }
+        None => {
+            info!("Batch {batch}: guest and native execution agree");
+            Ok(())
+        }
+    }
+}
+
+async fn withdrawal_proof(
+    batch: u64,
+    message_id: Option<u64>,
+    tx_hash: Option<H256>,
+    datadir: &Path,
+    network: Network,
+) -> eyre::Result<()> {
+    let store = init_store(datadir, network.get_genesis()?).await?;
+    let rollup_store = l2::initializers::init_rollup_store(&datadir.join("rollup_store")).await;
+
+    let block_numbers = rollup_store
+        .get_block_numbers_by_batch(batch)
+        .await?
+        .ok_or_else(|| eyre::eyre!("Batch {batch} not found"))?;
+    let mut blocks = Vec::with_capacity(block_numbers.len());
+    let mut receipts = Vec::with_capacity(block_numbers.len());
+    for block_number in block_numbers {
+        let block = store
+            .get_block_by_number(block_number)
+            .await?
+            .ok_or_else(|| eyre::eyre!("Block {block_number} not found"))?;
+        receipts.push(store.get_receipts_for_block(&block.hash()).await?);
+        blocks.push(block);
+    }
+    let prover = WithdrawalProver::from_blocks(batch, &blocks, &receipts);
+
+    let root = prover.root();
+    if let Some(stored_hashes) = rollup_store
+        .get_l1_out_message_hashes_by_batch(batch)
+        .await?
+        && stored_hashes != prover.message_hashes()
+    {
+        return Err(eyre::eyre!(
+            "Rebuilt message tree of batch {batch} doesn't match the committed one"
+        ));
+    }
+
+    let proofs: Vec<_> = match (message_id, tx_hash) {
+        (Some(message_id), _) => prover
+            .proof_by_message_id(U256::from(message_id))
+            .into_iter()
+            .collect(),
+        (None, Some(tx_hash)) => prover.proofs_by_tx_hash(tx_hash),
+        (None, None) => Vec::new(),
+    };
+    if proofs.is_empty() {
+        return Err(eyre::eyre!("No matching message in batch {batch}"));
+    }
+    for proof in &proofs {
+        if !verify_withdrawal_proof(proof, root) {
+            return Err(eyre::eyre!(
+                "Proof of message {} doesn't verify against root {root:#x}",
+                proof.message_id
+            ));
+        }
+    }
+    info!("Batch {batch} L1 out messages root: {root:#x}");
+    println!("{}", serde_json::to_string_pretty(&proofs)?);
+    Ok(())
+}
+
+fn verify_elf(elf_path: &Path, manifest_path: &Path) -> eyre::Result<()> {
+    let manifest = ElfManifest::load(manifest_path)?;
+    let elf = std::fs::read(elf_path)
+        .map_err(|e| eyre::eyre!("Failed to read {}: {e}", elf_path.display()))?;
+
+    let build_info = manifest.verify(&elf)?;
+    println!(
+        "{} matches the {} ({}) manifest, elf_hash {}",
+        elf_path.display(),
+        manifest.program_id,
+        manifest.backend,
+        manifest.elf_hash
+    );
+    match build_info {
+        Some(info) => println!("Built from {info}"),
+        None => println!("No embedded build info"),
+    }
+    Ok(())
+}
+
+fn prover_report(accounting_dir: &Path, program: Option<&str>) -> eyre::Result<()> {
+    let store = AccountingStore::open(accounting_dir)
+        .map_err(|e| eyre::eyre!("Failed to open {}: {e}", accounting_dir.display()))?;
+    let stats: Vec<_> = store
+        .daily_stats()
+        .into_iter()
+        .filter(|stats| program.is_none_or(|program| stats.program_id == program))
+        .collect();
+    if stats.is_empty() {
+        println!("No proofs recorded in {}", accounting_dir.display());
+        return Ok(());
+    }
+
+    println!(
+        "{:<16} {:<10} {:>8} {:>14} {:>16} {:>10}",
+        "program", "date", "proofs", "total time", "estimated cost", "unpriced"
+    );
+    for stats in stats {
+        println!(
+            "{:<16} {:<10} {:>8} {:>14} {:>16.6} {:>10}",
+            stats.program_id,
+            stats.date,
+            stats.proofs,
+            format!("{:.2?}", Duration::from_millis(stats.total_time_ms)),
+            stats.estimated_cost,
+            stats.unpriced_proofs
+        );
+    }
+    Ok(())
+}
+
 async fn delete_blocks_from_batch(
     datadir: &Path,
     network: Option<Network>,
@@ -1,7 +1,7 @@
 use crate::cli::Options as L1Options;
 use crate::initializers::{
     self, get_authrpc_socket_addr, get_http_socket_addr, get_local_node_record, get_local_p2p_node,
-    get_network, get_signer, init_blockchain, init_network, init_store,
+    get_network, get_signer, init_account_filter, init_blockchain, init_network, init_store,
 };
 use crate::l2::{L2Options, SequencerOptions};
 use crate::utils::{
@@ -23,7 +23,7 @@ use ethrex_p2p::{
     types::{Node, NodeRecord},
 };
 use ethrex_storage::Store;
-use ethrex_storage_rollup::{EngineTypeRollup, StoreRollup};
+use ethrex_storage_rollup::{EngineTypeRollup, RollupStoreError, StoreRollup};
 use eyre::OptionExt;
 use secp256k1::SecretKey;
 use spawned_concurrency::tasks::GenServerHandle;
@@ -105,6 +105,55 @@ pub async fn init_rollup_store(datadir: &Path) -> StoreRollup {
     rollup_store
 }
 
+/// Periodically prunes the bodies and receipts of blocks older than `retention`
+/// blocks behind the head, like [`initializers::init_history_pruning`]. Blocks
+/// of the latest batch and later ones are always kept, since the committer and
+/// state regeneration still read them.
+fn init_history_pruning(
+    store: Store,
+    rollup_store: StoreRollup,
+    retention: u64,
+    tracker: TaskTracker,
+) {
+    info!("Keeping bodies and receipts of the last {retention} blocks");
+    tracker.spawn(async move {
+        let mut interval = tokio::time::interval(initializers::HISTORY_PRUNING_INTERVAL);
+        loop {
+            interval.tick().await;
+            let latest_block_number = match store.get_latest_block_number().await {
+                Ok(number) => number,
+                Err(err) => {
+                    warn!("Failed to get latest block number for history pruning: {err}");
+                    continue;
+                }
+            };
+            let latest_batch_start = match latest_batch_first_block(&rollup_store).await {
+                Ok(number) => number,
+                Err(err) => {
+                    warn!("Failed to get the latest batch for history pruning: {err}");
+                    continue;
+                }
+            };
+            let prune_before = latest_block_number
+                .saturating_sub(retention)
+                .min(latest_batch_start);
+            initializers::prune_history(&store, prune_before).await;
+        }
+    });
+}
+
+/// First block of the latest batch, 0 if there are no batches yet.
+async fn latest_batch_first_block(rollup_store: &StoreRollup) -> Result<u64, RollupStoreError> {
+    let Some(batch_number) = rollup_store.get_batch_number().await? else {
+        return Ok(0);
+    };
+    Ok(rollup_store
+        .get_block_numbers_by_batch(batch_number)
+        .await?
+        .and_then(|numbers| numbers.first().copied())
+        .unwrap_or_default())
+}
+
 fn init_metrics(opts: &L1Options, network: &str, tracker: TaskTracker) {
     // Initialize node version metrics
     ethrex_metrics::node::MetricsNode::init(
@@ -191,10 +240,11 @@ pub async fn init_l2(
 
     let genesis = network.get_genesis()?;
     let store = init_store(&datadir, genesis.clone()).await?;
+    init_account_filter(&opts.node_opts, &store).await?;
     let rollup_store = init_rollup_store(&rollup_store_dir).await;
 
     let operator_fee_config = get_operator_fee_config(&opts.sequencer_opts)?;
-    let l1_fee_config = get_l1_fee_config(&opts.sequencer_opts);
+    let l1_fee_config = get_l1_fee_config(&opts.sequencer_opts)?;
 
     let fee_config = FeeConfig {
         base_fee_vault: opts
@@ -204,6 +254,9 @@ pub async fn init_l2(
         operator_fee_config,
         l1_fee_config,
     };
+    fee_config
+        .validate(&genesis.config)
+        .map_err(|err| eyre::eyre!("Invalid fee configuration: {err}"))?;
 
     // We wrap fee_config in an Arc<RwLock> to let the watcher
     // update the L1 fee periodically.
@@ -217,6 +270,7 @@ pub async fn init_l2(
         perf_logs_enabled: true,
         max_blobs_per_block: None, // L2 doesn't support blob transactions
         precompute_witnesses: opts.node_opts.precompute_witnesses,
+        keccak_cache: opts.node_opts.keccak_cache,
     };
 
     let blockchain = init_blockchain(store.clone(), blockchain_opts.clone());
@@ -318,6 +372,15 @@ pub async fn init_l2(
     );
 
     // Initialize metrics if enabled
+    if let Some(retention) = opts.node_opts.history_retention {
+        init_history_pruning(
+            store.clone(),
+            rollup_store.clone(),
+            retention,
+            tracker.clone(),
+        );
+    }
+
     if opts.node_opts.metrics_enabled {
         init_metrics(&opts.node_opts, &network.to_string(), tracker);
     }
@@ -367,19 +430,27 @@ pub async fn init_l2(
     Ok(())
 }
 
-pub fn get_l1_fee_config(sequencer_opts: &SequencerOptions) -> Option<L1FeeConfig> {
+pub fn get_l1_fee_config(sequencer_opts: &SequencerOptions) -> eyre::Result<Option<L1FeeConfig>> {
     if sequencer_opts.based {
         // If based is enabled, skip L1 fee configuration
-        return None;
+        return Ok(None);
     }
 
-    sequencer_opts
-        .block_producer_opts
-        .l1_fee_vault_address
-        .map(|addr| L1FeeConfig {
-            l1_fee_vault: addr,
-            l1_fee_per_blob_gas: 0, // This is set by the L1 watcher
-        })
+    let Some(l1_fee_vault) = sequencer_opts.block_producer_opts.l1_fee_vault_address else {
+        return Ok(None);
+    };
+
+    // The L1 fee pays for the blobs the committer posts, which validiums don't
+    if sequencer_opts.validium {
+        return Err(eyre::eyre!(
+            "An L1 fee vault can't be set in validium mode, as no blobs are posted to L1"
+        ));
+    }
+
+    Ok(Some(L1FeeConfig {
+        l1_fee_vault,
+        l1_fee_per_blob_gas: 0, // This is set by the L1 watcher
+    }))
 }
 
 pub fn get_operator_fee_config(
@@ -10,6 +10,7 @@ use ethrex_l2::{
     L1WatcherConfig, ProofCoordinatorConfig, SequencerConfig, StateUpdaterConfig,
     sequencer::configs::{AdminConfig, AlignedConfig, MonitorConfig},
 };
+use ethrex_l2_common::prover::ProofFormat;
 use ethrex_l2_rpc::signer::{LocalSigner, RemoteSigner, Signer};
 use ethrex_prover_lib::{backend::BackendType, config::ProverConfig};
 use ethrex_rpc::clients::eth::{
@@ -18,7 +19,8 @@ use ethrex_rpc::clients::eth::{
 use reqwest::Url;
 use secp256k1::{PublicKey, SecretKey};
 use std::{
-    net::{IpAddr, Ipv4Addr},
+    net::{IpAddr, Ipv4Addr, SocketAddr},
+    path::PathBuf,
     str::FromStr,
 };
 use tracing::Level;
@@ -176,9 +178,17 @@ impl TryFrom<SequencerOptions> for SequencerConfig {
                 first_wake_up_time_ms: opts.committer_opts.first_wake_up_time_ms.unwrap_or(0),
                 commit_time_ms: opts.committer_opts.commit_time_ms,
                 batch_gas_limit: opts.committer_opts.batch_gas_limit,
+                min_batch_gas: opts.committer_opts.min_batch_gas,
+                max_batch_gas: opts.committer_opts.max_batch_gas,
+                target_proving_time_percentage: opts.committer_opts.target_proving_time_percentage,
+                batch_gas_hysteresis_percentage: opts
+                    .committer_opts
+                    .batch_gas_hysteresis_percentage,
                 arbitrary_base_blob_gas_price: opts.committer_opts.arbitrary_base_blob_gas_price,
                 signer: committer_signer,
                 validium: opts.validium,
+                ordering_commitments_dir: opts.committer_opts.ordering_commitments_dir.clone(),
+                publish_block_summaries: opts.committer_opts.publish_block_summaries,
             },
             eth: EthConfig {
                 rpc_url: opts.eth_opts.rpc_url,
@@ -216,6 +226,11 @@ impl TryFrom<SequencerOptions> for SequencerConfig {
                 qpl_tool_path: opts.proof_coordinator_opts.proof_coordinator_qpl_tool_path,
                 validium: opts.validium,
                 guest_program_id: opts.proof_coordinator_opts.guest_program_id,
+                max_base_fee_per_gas: opts.proof_coordinator_opts.max_base_fee_per_gas,
+                max_priority_fee_per_gas: opts.proof_coordinator_opts.max_priority_fee_per_gas,
+                submission_deadline_secs: opts.proof_coordinator_opts.submission_deadline_secs,
+                stuck_tx_timeout_secs: opts.proof_coordinator_opts.stuck_tx_timeout_secs,
+                fee_bump_percentage: opts.proof_coordinator_opts.fee_bump_percentage,
             },
             based: BasedConfig {
                 enabled: opts.based,
@@ -634,6 +649,42 @@ pub struct CommitterOptions {
         help = "Maximum gas limit for the batch"
     )]
     pub batch_gas_limit: Option<u64>,
+    #[arg(
+        long = "committer.min-batch-gas",
+        value_name = "UINT64",
+        env = "ETHREX_COMMITTER_MIN_BATCH_GAS",
+        help_heading = "L1 Committer options",
+        help = "Lower bound of the batch gas limit when it's tuned from the provers' feedback. Tuning is enabled when both bounds are set.",
+        requires = "max_batch_gas"
+    )]
+    pub min_batch_gas: Option<u64>,
+    #[arg(
+        long = "committer.max-batch-gas",
+        value_name = "UINT64",
+        env = "ETHREX_COMMITTER_MAX_BATCH_GAS",
+        help_heading = "L1 Committer options",
+        help = "Upper bound of the batch gas limit when it's tuned from the provers' feedback.",
+        requires = "min_batch_gas"
+    )]
+    pub max_batch_gas: Option<u64>,
+    #[arg(
+        long = "committer.target-proving-time-percentage",
+        default_value = "80",
+        value_name = "UINT64",
+        env = "ETHREX_COMMITTER_TARGET_PROVING_TIME_PERCENTAGE",
+        help_heading = "L1 Committer options",
+        help = "Proving time tuned batches are sized for, in percent of the commit time."
+    )]
+    pub target_proving_time_percentage: u64,
+    #[arg(
+        long = "committer.batch-gas-hysteresis-percentage",
+        default_value = "10",
+        value_name = "UINT64",
+        env = "ETHREX_COMMITTER_BATCH_GAS_HYSTERESIS_PERCENTAGE",
+        help_heading = "L1 Committer options",
+        help = "Deviation from the target proving time tolerated before tuned batches are resized, in percent."
+    )]
+    pub batch_gas_hysteresis_percentage: u64,
     #[arg(
         long = "committer.first-wake-up-time",
         value_name = "UINT64",
@@ -650,6 +701,23 @@ pub struct CommitterOptions {
         help_heading = "L1 Committer options"
     )]
     pub arbitrary_base_blob_gas_price: u64,
+    #[arg(
+        long = "committer.ordering-commitments-dir",
+        value_name = "PATH",
+        env = "ETHREX_COMMITTER_ORDERING_COMMITMENTS_DIR",
+        help_heading = "L1 Committer options",
+        help = "Directory the preconfirmation gateway writes batch ordering commitments to, as `batch_<number>.json`. When a batch has one, the prover checks the promised ordering and the commitment is sent to the L1 with the batch."
+    )]
+    pub ordering_commitments_dir: Option<PathBuf>,
+    #[arg(
+        long = "committer.publish-block-summaries",
+        action = clap::ArgAction::SetTrue,
+        default_value = "false",
+        env = "ETHREX_COMMITTER_PUBLISH_BLOCK_SUMMARIES",
+        help_heading = "L1 Committer options",
+        help = "Make provers commit to a hash of the gas used, transaction count, receipts root and state root of each block in a batch. The hash is sent to the L1 with the batch and checked as part of its public inputs. Batches are capped at 256 blocks. Ignored in based mode."
+    )]
+    pub publish_block_summaries: bool,
 }
 
 impl Default for CommitterOptions {
@@ -663,10 +731,16 @@ impl Default for CommitterOptions {
             timelock_address: None,
             commit_time_ms: 60000,
             batch_gas_limit: None,
+            min_batch_gas: None,
+            max_batch_gas: None,
+            target_proving_time_percentage: 80,
+            batch_gas_hysteresis_percentage: 10,
             first_wake_up_time_ms: None,
             arbitrary_base_blob_gas_price: 1_000_000_000,
             committer_remote_signer_url: None,
             committer_remote_signer_public_key: None,
+            ordering_commitments_dir: None,
+            publish_block_summaries: false,
         }
     }
 }
@@ -690,9 +764,15 @@ impl CommitterOptions {
             .or(defaults.on_chain_proposer_address);
         self.timelock_address = self.timelock_address.or(defaults.timelock_address);
         self.batch_gas_limit = self.batch_gas_limit.or(defaults.batch_gas_limit);
+        self.min_batch_gas = self.min_batch_gas.or(defaults.min_batch_gas);
+        self.max_batch_gas = self.max_batch_gas.or(defaults.max_batch_gas);
         self.first_wake_up_time_ms = self
             .first_wake_up_time_ms
             .or(defaults.first_wake_up_time_ms);
+        self.ordering_commitments_dir = self
+            .ordering_commitments_dir
+            .clone()
+            .or(defaults.ordering_commitments_dir.clone());
     }
 }
 
@@ -785,6 +865,49 @@ pub struct ProofCoordinatorOptions {
         help_heading = "Proof coordinator options"
     )]
     pub guest_program_id: String,
+    #[arg(
+        long = "proof-coordinator.max-base-fee-per-gas",
+        value_name = "UINT64",
+        env = "ETHREX_PROOF_COORDINATOR_MAX_BASE_FEE_PER_GAS",
+        help = "Defer sending proofs while the L1 base fee is above this value in wei.",
+        help_heading = "Proof coordinator options"
+    )]
+    pub max_base_fee_per_gas: Option<u64>,
+    #[arg(
+        long = "proof-coordinator.max-priority-fee-per-gas",
+        value_name = "UINT64",
+        env = "ETHREX_PROOF_COORDINATOR_MAX_PRIORITY_FEE_PER_GAS",
+        help = "Defer sending proofs while the L1 priority fee is above this value in wei.",
+        help_heading = "Proof coordinator options"
+    )]
+    pub max_priority_fee_per_gas: Option<u64>,
+    #[arg(
+        long = "proof-coordinator.submission-deadline",
+        default_value = "3600",
+        value_name = "UINT64",
+        env = "ETHREX_PROOF_COORDINATOR_SUBMISSION_DEADLINE",
+        help = "Seconds after a batch's proofs are ready from which they are sent regardless of L1 fees.",
+        help_heading = "Proof coordinator options"
+    )]
+    pub submission_deadline_secs: u64,
+    #[arg(
+        long = "proof-coordinator.stuck-tx-timeout",
+        default_value = "120",
+        value_name = "UINT64",
+        env = "ETHREX_PROOF_COORDINATOR_STUCK_TX_TIMEOUT",
+        help = "Seconds without inclusion after which a verify transaction is replaced with higher fees.",
+        help_heading = "Proof coordinator options"
+    )]
+    pub stuck_tx_timeout_secs: u64,
+    #[arg(
+        long = "proof-coordinator.fee-bump-percentage",
+        default_value = "20",
+        value_name = "UINT64",
+        env = "ETHREX_PROOF_COORDINATOR_FEE_BUMP_PERCENTAGE",
+        help = "Fee increase of replacement verify transactions, in percent.",
+        help_heading = "Proof coordinator options"
+    )]
+    pub fee_bump_percentage: u64,
 }
 
 impl Default for ProofCoordinatorOptions {
@@ -805,6 +928,11 @@ impl Default for ProofCoordinatorOptions {
                 DEFAULT_PROOF_COORDINATOR_QPL_TOOL_PATH.to_string(),
             ),
             guest_program_id: "evm-l2".to_string(),
+            max_base_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            submission_deadline_secs: 3600,
+            stuck_tx_timeout_secs: 120,
+            fee_bump_percentage: 20,
         }
     }
 }
@@ -1117,6 +1245,73 @@ pub struct ProverClientOptions {
         help_heading = "Prover client options"
     )]
     pub programs_config: Option<String>,
+    #[arg(
+        long = "input-cache-dir",
+        value_name = "PATH",
+        env = "PROVER_CLIENT_INPUT_CACHE_DIR",
+        help = "Directory where batch inputs are cached. Enables prefetching the inputs of the next batches while proving.",
+        help_heading = "Prover client options"
+    )]
+    pub input_cache_dir: Option<String>,
+    #[arg(
+        long = "prefetch-depth",
+        value_name = "UINT64",
+        env = "PROVER_CLIENT_PREFETCH_DEPTH",
+        help = "Number of batches after the one being proven whose inputs are prefetched",
+        help_heading = "Prover client options",
+        default_value_t = 2
+    )]
+    pub prefetch_depth: u64,
+    #[arg(
+        long = "input-cache-size",
+        value_name = "MIB",
+        env = "PROVER_CLIENT_INPUT_CACHE_SIZE",
+        help = "Size budget in MiB of the input cache of each proof coordinator",
+        help_heading = "Prover client options",
+        default_value_t = 4096
+    )]
+    pub input_cache_size_mb: u64,
+    #[arg(
+        long = "accounting-dir",
+        value_name = "PATH",
+        env = "PROVER_CLIENT_ACCOUNTING_DIR",
+        help = "Directory where proof accounting records are stored. Enables per-program proving statistics.",
+        help_heading = "Prover client options"
+    )]
+    pub accounting_dir: Option<String>,
+    #[arg(
+        long = "status-addr",
+        value_name = "ADDRESS",
+        env = "PROVER_CLIENT_STATUS_ADDR",
+        help = "Address to serve the prover status on, e.g. 127.0.0.1:3901",
+        help_heading = "Prover client options"
+    )]
+    pub status_addr: Option<SocketAddr>,
+    #[arg(
+        long = "status-health-window",
+        value_name = "SECONDS",
+        env = "PROVER_CLIENT_STATUS_HEALTH_WINDOW",
+        help = "The health check of the status endpoint fails when no proof coordinator answered for this long, unless a batch is being proven",
+        help_heading = "Prover client options",
+        default_value_t = 300
+    )]
+    pub status_health_window_secs: u64,
+    #[arg(
+        long = "enforce-capabilities",
+        default_value_t = false,
+        env = "PROVER_CLIENT_ENFORCE_CAPABILITIES",
+        help = "Refuse to start when the hardware checks find the backend can't prove on this machine (no CUDA device for a GPU build, not enough memory) instead of only warning. Ignored when proving remotely.",
+        help_heading = "Prover client options"
+    )]
+    pub enforce_capabilities: bool,
+    #[arg(
+        long = "proof-format",
+        value_name = "FORMAT",
+        env = "PROVER_CLIENT_PROOF_FORMAT",
+        help = "Only proof format to offer proof coordinators, groth16 or compressed. The prover refuses to start if the backend can't produce it. Defaults to every format the backend supports.",
+        help_heading = "Prover client options"
+    )]
+    pub proof_format: Option<ProofFormat>,
 }
 
 impl From<ProverClientOptions> for ProverConfig {
@@ -1129,6 +1324,14 @@ impl From<ProverClientOptions> for ProverConfig {
             #[cfg(all(feature = "sp1", feature = "gpu"))]
             sp1_server: config.sp1_server,
             programs_config_path: config.programs_config,
+            input_cache_dir: config.input_cache_dir,
+            prefetch_depth: config.prefetch_depth,
+            input_cache_size_mb: config.input_cache_size_mb,
+            accounting_dir: config.accounting_dir,
+            status_addr: config.status_addr,
+            status_health_window_secs: config.status_health_window_secs,
+            enforce_capabilities: config.enforce_capabilities,
+            proof_format: config.proof_format,
         }
     }
 }
@@ -1146,6 +1349,14 @@ impl Default for ProverClientOptions {
             #[cfg(all(feature = "sp1", feature = "gpu"))]
             sp1_server: None,
             programs_config: None,
+            input_cache_dir: None,
+            prefetch_depth: 2,
+            input_cache_size_mb: 4096,
+            accounting_dir: None,
+            status_addr: None,
+            status_health_window_secs: 300,
+            enforce_capabilities: false,
+            proof_format: None,
         }
     }
 }
@@ -51,7 +51,7 @@ pub mod tracing;
 pub mod vm;
 
 use ::tracing::{debug, info, instrument, warn};
-use constants::{MAX_INITCODE_SIZE, MAX_TRANSACTION_DATA_SIZE, POST_OSAKA_GAS_LIMIT_CAP};
+use constants::{MAX_INITCODE_SIZE, MAX_TRANSACTION_DATA_SIZE};
 use error::MempoolError;
 use error::{ChainError, InvalidBlockError};
 use ethrex_common::constants::{EMPTY_TRIE_HASH, MIN_BASE_FEE_PER_BLOB_GAS};
@@ -59,12 +59,14 @@ use ethrex_common::constants::{EMPTY_TRIE_HASH, MIN_BASE_FEE_PER_BLOB_GAS};
 // Re-export stateless validation functions for backwards compatibility
 #[cfg(feature = "c-kzg")]
 use ethrex_common::types::EIP4844Transaction;
+#[cfg(feature = "c-kzg")]
+use ethrex_common::types::blobs;
 use ethrex_common::types::block_access_list::BlockAccessList;
 use ethrex_common::types::block_execution_witness::ExecutionWitness;
 use ethrex_common::types::fee_config::FeeConfig;
 use ethrex_common::types::{
     AccountInfo, AccountState, AccountUpdate, Block, BlockHash, BlockHeader, BlockNumber,
-    ChainConfig, Code, Receipt, Transaction, WrappedEIP4844Transaction,
+    ChainConfig, Code, Receipt, Transaction, WrappedEIP4844Transaction, merge_updates,
 };
 use ethrex_common::types::{ELASTICITY_MULTIPLIER, P2PTransaction};
 use ethrex_common::types::{Fork, MempoolTransaction};
@@ -72,7 +74,8 @@ use ethrex_common::utils::keccak;
 use ethrex_common::{Address, H256, TrieLogger, U256};
 pub use ethrex_common::{
     get_total_blob_gas, validate_block, validate_block_access_list_hash, validate_gas_used,
-    validate_receipts_root, validate_requests_hash,
+    validate_logs_bloom, validate_post_execution_commitments, validate_receipts_root,
+    validate_requests_hash,
 };
 use ethrex_metrics::metrics;
 use ethrex_rlp::constants::RLP_NULL;
@@ -83,9 +86,9 @@ use ethrex_storage::{
 };
 use ethrex_trie::node::{BranchNode, ExtensionNode, LeafNode};
 use ethrex_trie::{Nibbles, Node, NodeRef, Trie, TrieError, TrieNode};
-use ethrex_vm::backends::CachingDatabase;
-use ethrex_vm::backends::levm::LEVM;
+use ethrex_vm::backends::CacheStats;
 use ethrex_vm::backends::levm::db::DatabaseLogger;
+use ethrex_vm::backends::levm::{LEVM, effective_gas_limit};
 use ethrex_vm::{BlockExecutionResult, DynVmDatabase, Evm, EvmError};
 use mempool::Mempool;
 use payload::PayloadOrTask;
@@ -98,7 +101,7 @@ use std::sync::{
     atomic::{AtomicBool, AtomicUsize, Ordering},
     mpsc::{Receiver, channel},
 };
-use std::time::{Duration, Instant};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::Mutex as TokioMutex;
 use tokio_util::sync::CancellationToken;
 
@@ -121,6 +124,7 @@ type BlockExecutionPipelineResult = (
     usize,        // max queue length
     [Instant; 6], // timing instants
     Duration,     // warmer duration
+    CacheStats,   // warm cache stats
 );
 
 //TODO: Implement a struct Chain or BlockChain to encapsulate
@@ -205,6 +209,8 @@ pub struct BlockchainOptions {
     pub max_blobs_per_block: Option<u32>,
     /// If true, computes execution witnesses upon receiving newPayload messages and stores them in local storage
     pub precompute_witnesses: bool,
+    /// If true, block execution memoizes the KECCAK256 of repeated 32 and 64-byte preimages
+    pub keccak_cache: bool,
 }
 
 impl Default for BlockchainOptions {
@@ -215,6 +221,7 @@ impl Default for BlockchainOptions {
             r#type: BlockchainType::default(),
             max_blobs_per_block: None,
             precompute_witnesses: false,
+            keccak_cache: false,
         }
     }
 }
@@ -323,17 +330,15 @@ impl Blockchain {
         let account_updates = vm.get_state_transitions()?;
 
         // Validate execution went alright
-        validate_gas_used(execution_result.block_gas_used, &block.header)?;
-        validate_receipts_root(&block.header, &execution_result.receipts)?;
-        validate_requests_hash(&block.header, &chain_config, &execution_result.requests)?;
-        if let Some(bal) = &bal {
-            validate_block_access_list_hash(
-                &block.header,
-                &chain_config,
-                bal,
-                block.body.transactions.len(),
-            )?;
-        }
+        validate_post_execution_commitments(
+            &block.header,
+            &chain_config,
+            execution_result.block_gas_used,
+            &execution_result.receipts,
+            &execution_result.requests,
+            bal.as_ref(),
+            block.body.transactions.len(),
+        )?;
 
         Ok((execution_result, account_updates))
     }
@@ -392,51 +397,50 @@ impl Blockchain {
 
         // Wrap the store with CachingDatabase so both warming and execution
         // can benefit from shared caching of state lookups
-        let original_store = vm.db.store.clone();
-        let caching_store: Arc<dyn ethrex_vm::backends::LevmDatabase> =
-            Arc::new(CachingDatabase::new(original_store));
-
-        // Replace the VM's store with the caching version
-        vm.db.store = caching_store.clone();
+        let warm_cache = if self.options.keccak_cache {
+            vm.warm_cache_with_keccak()
+        } else {
+            vm.warm_cache()
+        };
 
         let (execution_result, merkleization_result, warmer_duration) =
             std::thread::scope(|s| -> Result<_, ChainError> {
                 let vm_type = vm.vm_type;
+                let warmer_cache = warm_cache.clone();
                 let warm_handle = std::thread::Builder::new()
                     .name("block_executor_warmer".to_string())
                     .spawn_scoped(s, move || {
                         // Warming uses the same caching store, sharing cached state with execution
                         let start = Instant::now();
-                        let _ = LEVM::warm_block(block, caching_store, vm_type);
+                        let _ = LEVM::warm_block(block, warmer_cache, vm_type);
                         start.elapsed()
                     })
                     .map_err(|e| {
                         ChainError::Custom(format!("Failed to spawn warmer thread: {e}"))
                     })?;
                 let max_queue_length_ref = &mut max_queue_length;
+                let warm_cache_ref = &warm_cache;
                 let (tx, rx) = channel();
                 let execution_handle = std::thread::Builder::new()
                     .name("block_executor_execution".to_string())
                     .spawn_scoped(s, move || -> Result<_, ChainError> {
-                        let (execution_result, bal) =
-                            vm.execute_block_pipeline(block, tx, queue_length_ref)?;
+                        let (execution_result, bal) = vm.execute_block_pipeline(
+                            block,
+                            Some(warm_cache_ref),
+                            tx,
+                            queue_length_ref,
+                        )?;
 
                         // Validate execution went alright
-                        validate_gas_used(execution_result.block_gas_used, &block.header)?;
-                        validate_receipts_root(&block.header, &execution_result.receipts)?;
-                        validate_requests_hash(
+                        validate_post_execution_commitments(
                             &block.header,
                             &chain_config,
+                            execution_result.block_gas_used,
+                            &execution_result.receipts,
                             &execution_result.requests,
+                            bal.as_ref(),
+                            block.body.transactions.len(),
                         )?;
-                        if let Some(bal) = &bal {
-                            validate_block_access_list_hash(
-                                &block.header,
-                                &chain_config,
-                                bal,
-                                block.body.transactions.len(),
-                            )?;
-                        }
 
                         let exec_end_instant = Instant::now();
                         Ok((execution_result, exec_end_instant))
@@ -502,6 +506,7 @@ impl Blockchain {
                 exec_merkle_end_instant,
             ],
             warmer_duration,
+            warm_cache.stats(),
         ))
     }
 
@@ -542,28 +547,15 @@ impl Blockchain {
         let mut hashed_address_cache: FxHashMap<Address, H256> = Default::default();
 
         // Accumulator for witness generation (only used if precompute_witnesses is true)
-        let mut accumulator: Option<FxHashMap<Address, AccountUpdate>> =
-            if self.options.precompute_witnesses {
-                Some(FxHashMap::default())
-            } else {
-                None
-            };
+        let mut accumulator: Option<Vec<Vec<AccountUpdate>>> =
+            self.options.precompute_witnesses.then(Vec::new);
 
         for updates in rx {
             let current_length = queue_length.fetch_sub(1, Ordering::Acquire);
             *max_queue_length = current_length.max(*max_queue_length);
             // Accumulate updates for witness generation if enabled
             if let Some(acc) = &mut accumulator {
-                for update in updates.clone() {
-                    match acc.entry(update.address) {
-                        Entry::Vacant(e) => {
-                            e.insert(update);
-                        }
-                        Entry::Occupied(mut e) => {
-                            e.get_mut().merge(update);
-                        }
-                    }
-                }
+                acc.push(updates.clone());
             }
 
             for update in updates {
@@ -690,7 +682,7 @@ impl Blockchain {
                 *EMPTY_TRIE_HASH
             };
 
-        let accumulated_updates = accumulator.map(|acc| acc.into_values().collect());
+        let accumulated_updates = accumulator.map(merge_updates);
 
         Ok((
             AccountUpdatesList {
@@ -910,17 +902,15 @@ impl Blockchain {
         validate_block(block, parent_header, chain_config, ELASTICITY_MULTIPLIER)?;
         let (execution_result, bal) = vm.execute_block(block)?;
         // Validate execution went alright
-        validate_gas_used(execution_result.block_gas_used, &block.header)?;
-        validate_receipts_root(&block.header, &execution_result.receipts)?;
-        validate_requests_hash(&block.header, chain_config, &execution_result.requests)?;
-        if let Some(bal) = &bal {
-            validate_block_access_list_hash(
-                &block.header,
-                chain_config,
-                bal,
-                block.body.transactions.len(),
-            )?;
-        }
+        validate_post_execution_commitments(
+            &block.header,
+            chain_config,
+            execution_result.block_gas_used,
+            &execution_result.receipts,
+            &execution_result.requests,
+            bal.as_ref(),
+            block.body.transactions.len(),
+        )?;
 
         Ok(execution_result)
     }
@@ -1616,6 +1606,7 @@ impl Blockchain {
             merkle_queue_length,
             instants,
             warmer_duration,
+            cache_stats,
         ) = self.execute_block_pipeline(&block, &parent_header, &mut vm)?;
 
         let (gas_used, gas_limit, block_number, transactions_count) = (
@@ -1659,6 +1650,7 @@ impl Blockchain {
                 transactions_count,
                 merkle_queue_length,
                 warmer_duration,
+                cache_stats,
                 instants,
             );
         }
@@ -1728,6 +1720,7 @@ impl Blockchain {
         transactions_count: usize,
         merkle_queue_length: usize,
         warmer_duration: Duration,
+        cache_stats: CacheStats,
         [
             start_instant,
             block_validated_instant,
@@ -1859,10 +1852,16 @@ impl Blockchain {
             bottleneck_marker("store")
         );
         info!(
-            "  `- warmer:   {:>4} ms         [finished: {} ms {}]",
+            "  `- warmer:   {:>4} ms         [finished: {} ms {}, served: {}/{} exec reads, {} negative hits, keccak cache: {}/{} ({}%)]",
             warmer_ms,
             warmer_early_ms.unsigned_abs(),
             warmer_relation,
+            cache_stats.warmer_hits,
+            cache_stats.executor_reads,
+            cache_stats.negative_hits,
+            cache_stats.keccak_hits,
+            cache_stats.keccak_lookups,
+            cache_stats.keccak_hit_rate_percent(),
         );
 
         // Set prometheus metrics
@@ -2045,17 +2044,28 @@ impl Blockchain {
         transaction: EIP4844Transaction,
         blobs_bundle: BlobsBundle,
     ) -> Result<H256, MempoolError> {
-        let fork = self.current_fork().await?;
-
         let transaction = Transaction::EIP4844Transaction(transaction);
         let hash = transaction.hash();
         if self.mempool.contains_tx(hash)? {
             return Ok(hash);
         }
 
-        // Validate blobs bundle after checking if it's already added.
+        // Validate blobs and bundle after checking if it's already added.
         if let Transaction::EIP4844Transaction(transaction) = &transaction {
-            blobs_bundle.validate(transaction, fork)?;
+            let latest_block_number = self.storage.get_latest_block_number().await?;
+            let header = self
+                .storage
+                .get_block_header(latest_block_number)?
+                .ok_or(MempoolError::NoBlockHeaderError)?;
+            // The transaction goes in the next block, which can't be built before now
+            let next_timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|now| now.as_secs())
+                .unwrap_or_default()
+                .max(header.timestamp + 1);
+            let chain_config = self.storage.get_chain_config();
+            blobs::validate_blob_tx(transaction, &header, next_timestamp, &chain_config)?;
+            blobs_bundle.validate(transaction, chain_config.fork(next_timestamp))?;
         }
 
         let sender = transaction.sender()?;
@@ -2180,9 +2190,15 @@ impl Blockchain {
             return Err(MempoolError::TxMaxDataSizeError);
         }
 
-        if config.is_osaka_activated(header.timestamp) && tx.gas_limit() > POST_OSAKA_GAS_LIMIT_CAP
+        // https://eips.ethereum.org/EIPS/eip-7825
+        // Same check as the one done when simulating, so a gas estimate is never rejected here
+        if effective_gas_limit(
+            Some(tx.gas_limit()),
+            header.gas_limit,
+            config.fork(header.timestamp),
+        )
+        .is_err()
         {
-            // https://eips.ethereum.org/EIPS/eip-7825
             return Err(MempoolError::TxMaxGasLimitExceededError(
                 tx.hash(),
                 tx.gas_limit(),
@@ -2281,11 +2297,9 @@ impl Blockchain {
                     )));
                 };
 
-                P2PTransaction::EIP4844TransactionWithBlobs(WrappedEIP4844Transaction {
-                    tx: itx,
-                    wrapper_version: (bundle.version != 0).then_some(bundle.version),
-                    blobs_bundle: bundle,
-                })
+                P2PTransaction::EIP4844TransactionWithBlobs(WrappedEIP4844Transaction::new(
+                    itx, bundle,
+                ))
             }
             Transaction::EIP7702Transaction(itx) => P2PTransaction::EIP7702Transaction(itx),
             // Exclude privileged transactions as they are only created
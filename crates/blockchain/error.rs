@@ -1,6 +1,6 @@
 use ethrex_common::{
     H256,
-    types::{BlobsBundleError, BlockHash},
+    types::{BlobsBundleError, BlockHash, blobs::BlobTxValidationError},
 };
 use ethrex_rlp::error::RLPDecodeError;
 use ethrex_storage::error::StoreError;
@@ -44,6 +44,9 @@ impl From<EvmError> for ChainError {
             EvmError::Transaction(err) => {
                 ChainError::InvalidBlock(InvalidBlockError::InvalidTransaction(err))
             }
+            EvmError::TxValidation(err) => {
+                ChainError::InvalidBlock(InvalidBlockError::InvalidTransaction(err.to_string()))
+            }
             EvmError::InvalidDepositRequest => ChainError::InvalidBlock(
                 InvalidBlockError::InvalidTransaction("Invalid deposit request layout".to_string()),
             ),
@@ -79,6 +82,8 @@ pub enum MempoolError {
     StoreError(#[from] StoreError),
     #[error("BlobsBundle error: {0}")]
     BlobsBundleError(#[from] BlobsBundleError),
+    #[error("Invalid blob transaction: {0}")]
+    BlobTxValidation(#[from] BlobTxValidationError),
     #[error("Transaction max init code size exceeded")]
     TxMaxInitCodeSizeError,
     #[error("Transaction max data size exceeded")]
@@ -123,6 +128,18 @@ pub enum MempoolError {
     FeeTokenTxNotSupported,
 }
 
+impl MempoolError {
+    /// Returns true if a blob transaction was rejected because of its blobs or sidecar,
+    /// which means the peer that sent it is misbehaving.
+    pub fn is_invalid_blobs_bundle(&self) -> bool {
+        match self {
+            MempoolError::BlobsBundleError(_) => true,
+            MempoolError::BlobTxValidation(err) => err.is_invalid_sidecar(),
+            _ => false,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum ForkChoiceElement {
     Head,
@@ -3,19 +3,13 @@ use std::{
     sync::RwLock,
 };
 
-use crate::{
-    constants::{
-        TX_ACCESS_LIST_ADDRESS_GAS, TX_ACCESS_LIST_STORAGE_KEY_GAS, TX_CREATE_GAS_COST,
-        TX_DATA_NON_ZERO_GAS, TX_DATA_NON_ZERO_GAS_EIP2028, TX_DATA_ZERO_GAS_COST, TX_GAS_COST,
-        TX_INIT_CODE_WORD_GAS_COST,
-    },
-    error::MempoolError,
-};
+use crate::error::MempoolError;
 use ethrex_common::{
     Address, H160, H256, U256,
-    types::{BlobsBundle, BlockHeader, ChainConfig, MempoolTransaction, Transaction, TxType},
+    types::{BlobsBundle, BlockHeader, ChainConfig, Fork, MempoolTransaction, Transaction, TxType},
 };
 use ethrex_storage::error::StoreError;
+use ethrex_vm::intrinsic_gas;
 use std::collections::HashSet;
 use tracing::warn;
 
@@ -422,63 +416,20 @@ pub struct PendingTxFilter {
     pub only_blob_txs: bool,
 }
 
+/// Lowest gas limit `tx` can have to be included on top of `header`, which must cover the
+/// EIP-7623 floor since Prague. Priced with the same function as the VM charges it.
 pub fn transaction_intrinsic_gas(
     tx: &Transaction,
     header: &BlockHeader,
     config: &ChainConfig,
 ) -> Result<u64, MempoolError> {
-    let is_contract_creation = tx.is_contract_creation();
-
-    let mut gas = if is_contract_creation {
-        TX_CREATE_GAS_COST
+    // Forks are floored at Paris, only the calldata cost changes before it
+    let fork = if config.is_istanbul_activated(header.number) {
+        config.get_fork(header.timestamp)
     } else {
-        TX_GAS_COST
+        Fork::Petersburg
     };
-
-    let data_len = tx.data().len() as u64;
-
-    if data_len > 0 {
-        let non_zero_gas_cost = if config.is_istanbul_activated(header.number) {
-            TX_DATA_NON_ZERO_GAS_EIP2028
-        } else {
-            TX_DATA_NON_ZERO_GAS
-        };
-
-        let non_zero_count = tx.data().iter().filter(|&&x| x != 0u8).count() as u64;
-
-        gas = gas
-            .checked_add(non_zero_count * non_zero_gas_cost)
-            .ok_or(MempoolError::TxGasOverflowError)?;
-
-        let zero_count = data_len - non_zero_count;
-
-        gas = gas
-            .checked_add(zero_count * TX_DATA_ZERO_GAS_COST)
-            .ok_or(MempoolError::TxGasOverflowError)?;
-
-        if is_contract_creation && config.is_shanghai_activated(header.timestamp) {
-            // Len in 32 bytes sized words
-            let len_in_words = data_len.saturating_add(31) / 32;
-
-            gas = gas
-                .checked_add(len_in_words * TX_INIT_CODE_WORD_GAS_COST)
-                .ok_or(MempoolError::TxGasOverflowError)?;
-        }
-    }
-
-    let storage_keys_count: u64 = tx
-        .access_list()
-        .iter()
-        .map(|(_, keys)| keys.len() as u64)
-        .sum();
-
-    gas = gas
-        .checked_add(tx.access_list().len() as u64 * TX_ACCESS_LIST_ADDRESS_GAS)
-        .ok_or(MempoolError::TxGasOverflowError)?;
-
-    gas = gas
-        .checked_add(storage_keys_count * TX_ACCESS_LIST_STORAGE_KEY_GAS)
-        .ok_or(MempoolError::TxGasOverflowError)?;
-
-    Ok(gas)
+    let intrinsic_gas =
+        intrinsic_gas(tx.into(), fork).map_err(|_| MempoolError::TxGasOverflowError)?;
+    Ok(intrinsic_gas.min_gas_limit())
 }
@@ -18,6 +18,9 @@ pub struct Metrics {
     batch_commitment_gas: IntGaugeVec,
     batch_commitment_blob_gas: IntGaugeVec,
     batch_tx_count: IntGaugeVec,
+    proof_submission_queue_depth: IntGauge,
+    batch_proof_submission_deferral: IntGaugeVec,
+    proof_submission_cost: IntGauge,
 }
 
 impl Default for Metrics {
@@ -108,6 +111,24 @@ impl Metrics {
                 &["batch_number"],
             )
             .unwrap(),
+            proof_submission_queue_depth: IntGauge::new(
+                "proof_submission_queue_depth",
+                "Batches with complete proofs waiting to be verified in L1",
+            )
+            .unwrap(),
+            batch_proof_submission_deferral: IntGaugeVec::new(
+                Opts::new(
+                    "batch_proof_submission_deferral",
+                    "Time the proof submission was deferred because of high L1 fees in seconds, labeled by batch number",
+                ),
+                &["batch_number"],
+            )
+            .unwrap(),
+            proof_submission_cost: IntGauge::new(
+                "proof_submission_cost",
+                "Total cost of the proof verification transactions in L1 in gwei",
+            )
+            .unwrap(),
         }
     }
 
@@ -237,6 +258,27 @@ impl Metrics {
         Ok(())
     }
 
+    pub fn set_proof_submission_queue_depth(&self, depth: i64) {
+        self.proof_submission_queue_depth.set(depth);
+    }
+
+    pub fn set_batch_proof_submission_deferral(
+        &self,
+        batch_number: u64,
+        deferral: i64,
+    ) -> Result<(), MetricsError> {
+        let builder = self
+            .batch_proof_submission_deferral
+            .get_metric_with_label_values(&[&batch_number.to_string()])
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+        builder.set(deferral);
+        Ok(())
+    }
+
+    pub fn add_proof_submission_cost(&self, cost_gwei: i64) {
+        self.proof_submission_cost.add(cost_gwei);
+    }
+
     pub fn gather_metrics(&self) -> Result<String, MetricsError> {
         let r = Registry::new();
 
@@ -264,6 +306,12 @@ impl Metrics {
             .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
         r.register(Box::new(self.batch_tx_count.clone()))
             .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+        r.register(Box::new(self.proof_submission_queue_depth.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+        r.register(Box::new(self.batch_proof_submission_deferral.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+        r.register(Box::new(self.proof_submission_cost.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
 
         let encoder = TextEncoder::new();
         let metric_families = r.gather();
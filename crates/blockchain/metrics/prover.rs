@@ -0,0 +1,88 @@
+use prometheus::{Encoder, IntGauge, Registry, TextEncoder};
+use std::sync::LazyLock;
+
+use crate::MetricsError;
+
+pub static METRICS_PROVER: LazyLock<MetricsProver> = LazyLock::new(MetricsProver::default);
+
+#[derive(Debug, Clone)]
+pub struct MetricsProver {
+    input_cache_hits: IntGauge,
+    input_cache_misses: IntGauge,
+    input_cache_bytes_saved: IntGauge,
+    input_cache_invalidations: IntGauge,
+}
+
+impl Default for MetricsProver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MetricsProver {
+    pub fn new() -> Self {
+        MetricsProver {
+            input_cache_hits: IntGauge::new(
+                "prover_input_cache_hits",
+                "Assigned batches whose input was served from the prefetch cache",
+            )
+            .expect("Failed to create input_cache_hits metric"),
+            input_cache_misses: IntGauge::new(
+                "prover_input_cache_misses",
+                "Assigned batches whose input had to be downloaded",
+            )
+            .expect("Failed to create input_cache_misses metric"),
+            input_cache_bytes_saved: IntGauge::new(
+                "prover_input_cache_bytes_saved",
+                "Bytes of batch inputs served from the prefetch cache",
+            )
+            .expect("Failed to create input_cache_bytes_saved metric"),
+            input_cache_invalidations: IntGauge::new(
+                "prover_input_cache_invalidations",
+                "Cached batch inputs replaced because the proof coordinator advertised a new hash",
+            )
+            .expect("Failed to create input_cache_invalidations metric"),
+        }
+    }
+
+    pub fn set_input_cache_hits(&self, hits: i64) {
+        self.input_cache_hits.set(hits);
+    }
+
+    pub fn set_input_cache_misses(&self, misses: i64) {
+        self.input_cache_misses.set(misses);
+    }
+
+    pub fn set_input_cache_bytes_saved(&self, bytes: i64) {
+        self.input_cache_bytes_saved.set(bytes);
+    }
+
+    pub fn set_input_cache_invalidations(&self, invalidations: i64) {
+        self.input_cache_invalidations.set(invalidations);
+    }
+
+    pub fn gather_metrics(&self) -> Result<String, MetricsError> {
+        let r = Registry::new();
+
+        r.register(Box::new(self.input_cache_hits.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+        r.register(Box::new(self.input_cache_misses.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+        r.register(Box::new(self.input_cache_bytes_saved.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+        r.register(Box::new(self.input_cache_invalidations.clone()))
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+
+        let encoder = TextEncoder::new();
+        let metric_families = r.gather();
+
+        let mut buffer = Vec::new();
+        encoder
+            .encode(&metric_families, &mut buffer)
+            .map_err(|e| MetricsError::PrometheusErr(e.to_string()))?;
+
+        let res = String::from_utf8(buffer)?;
+
+        Ok(res)
+    }
+}
@@ -13,8 +13,8 @@ use ethrex_common::{
         AccountUpdate, BlobsBundle, Block, BlockBody, BlockHash, BlockHeader, BlockNumber,
         ChainConfig, MempoolTransaction, Receipt, Transaction, TxKind, TxType, Withdrawal,
         block_access_list::BlockAccessList,
-        bloom_from_logs, calc_excess_blob_gas, calculate_base_fee_per_blob_gas,
-        calculate_base_fee_per_gas, compute_receipts_root, compute_transactions_root,
+        calc_excess_blob_gas, calculate_base_fee_per_blob_gas, calculate_base_fee_per_gas,
+        compute_logs_bloom, compute_receipts_root, compute_transactions_root,
         compute_withdrawals_root,
         requests::{EncodedRequests, compute_requests_hash},
     },
@@ -112,6 +112,9 @@ impl BuildPayloadArgs {
         if let Some(beacon_root) = self.beacon_root {
             hasher.update(beacon_root);
         }
+        if let Some(slot_number) = self.slot_number {
+            hasher.update(slot_number.to_be_bytes());
+        }
         let res = &mut hasher.finalize()[..8];
         res[0] = self.version;
         Ok(u64::from_be_bytes(res.try_into().map_err(|_| {
@@ -208,6 +211,19 @@ pub fn calc_gas_limit(parent_gas_limit: u64, builder_gas_ceil: u64) -> u64 {
     limit
 }
 
+/// Best guess of the PREVRANDAO the next block on top of `parent` will expose,
+/// for simulations that need a stable seed against the pending block.
+///
+/// The L2 sequencer always builds with a zero `random`, so that is exact. On L1
+/// the value comes from the consensus layer and cannot be known in advance, so
+/// the parent's value is reused.
+pub fn pending_block_randao(parent: &BlockHeader, blockchain_type: &BlockchainType) -> H256 {
+    match blockchain_type {
+        BlockchainType::L1 => parent.prev_randao,
+        BlockchainType::L2(_) => H256::zero(),
+    }
+}
+
 #[derive(Clone)]
 pub struct PayloadBuildContext {
     pub payload: Block,
@@ -250,11 +266,10 @@ impl PayloadBuildContext {
         let vm_db = StoreVmDatabase::new(storage.clone(), parent_header)?;
         let mut vm = new_evm(blockchain_type, vm_db)?;
 
-        // Enable BAL recording for Amsterdam and later forks (EIP-7928)
+        // Enable BAL recording for Amsterdam and later forks (EIP-7928).
+        // Contributions are kept per index so evicted transactions can be retracted.
         if config.is_amsterdam_activated(payload.header.timestamp) {
-            vm.enable_bal_recording();
-            // Set index 0 for pre-execution phase (system contracts)
-            vm.set_bal_index(0);
+            vm.begin_payload_bal();
         }
 
         let payload_size = payload.length() as u64;
@@ -603,11 +618,11 @@ impl Blockchain {
                 continue;
             }
 
-            // Set BAL index for this transaction (1-indexed per EIP-7928)
+            // Start recording BAL for this transaction (1-indexed per EIP-7928)
             // Index is based on current transaction count + 1
             #[allow(clippy::cast_possible_truncation)]
             let tx_index = (context.payload.body.transactions.len() + 1) as u16;
-            context.vm.set_bal_index(tx_index);
+            context.vm.record_tx(tx_index);
 
             // Record tx sender and recipient for BAL
             if let Some(recorder) = context.vm.db.bal_recorder_mut() {
@@ -628,6 +643,8 @@ impl Blockchain {
                 Err(e) => {
                     debug!("Failed to execute transaction: {tx_hash:x}, {e}");
                     metrics!(METRICS_TX.inc_tx_errors(e.to_metric()));
+                    // The next transaction reuses this index, drop what was recorded for it
+                    context.vm.remove_tx(tx_index);
                     txs.pop();
                     continue;
                 }
@@ -701,8 +718,8 @@ impl Blockchain {
     }
 
     pub fn finalize_payload(&self, context: &mut PayloadBuildContext) -> Result<(), ChainError> {
-        // Take BAL from VM before getting state transitions (which clears state)
-        let block_access_list = context.vm.take_bal();
+        // Build BAL from VM before getting state transitions (which clears state)
+        let block_access_list = context.vm.finalize_payload_bal();
 
         let account_updates = context.vm.get_state_transitions()?;
 
@@ -729,14 +746,7 @@ impl Blockchain {
             block_access_list.as_ref().map(|bal| bal.compute_hash());
         context.block_access_list = block_access_list;
 
-        let mut logs = vec![];
-        for receipt in context.receipts.iter().cloned() {
-            for log in receipt.logs {
-                logs.push(log);
-            }
-        }
-
-        context.payload.header.logs_bloom = bloom_from_logs(&logs);
+        context.payload.header.logs_bloom = compute_logs_bloom(&context.receipts);
         Ok(())
     }
 }
@@ -3,7 +3,11 @@ use std::{
     time::Duration,
 };
 
-use ethrex_common::{H256, tracing::CallTrace, types::Block};
+use ethrex_common::{
+    H256,
+    tracing::{CallTrace, NativePrestate},
+    types::Block,
+};
 use ethrex_storage::Store;
 use ethrex_vm::{Evm, EvmError};
 
@@ -82,6 +86,61 @@ impl Blockchain {
         Ok(call_traces)
     }
 
+    /// Outputs the state the given transaction read before executing, enough to re-execute it standalone
+    /// May need to re-execute blocks in order to rebuild the transaction's prestate, up to the amount given by `reexec`
+    pub async fn trace_transaction_prestate(
+        &self,
+        tx_hash: H256,
+        reexec: u32,
+        timeout: Duration,
+    ) -> Result<NativePrestate, ChainError> {
+        let Some((_, block_hash, tx_index)) =
+            self.storage.get_transaction_location(tx_hash).await?
+        else {
+            return Err(ChainError::Custom("Transaction not Found".to_string()));
+        };
+        let tx_index = tx_index as usize;
+        let Some(block) = self.storage.get_block_by_hash(block_hash).await? else {
+            return Err(ChainError::Custom("Block not Found".to_string()));
+        };
+        let mut vm = self
+            .rebuild_parent_state(block.header.parent_hash, reexec)
+            .await?;
+        vm.rerun_block(&block, Some(tx_index))?;
+        timeout_trace_operation(timeout, move || vm.trace_tx_prestate(&block, tx_index)).await
+    }
+
+    /// Outputs the prestate of each transaction in the block along with the transaction's hash
+    /// May need to re-execute blocks in order to rebuild the transaction's prestate, up to the amount given by `reexec`
+    /// Returns transaction prestates from oldest to newest
+    pub async fn trace_block_prestate(
+        &self,
+        block: Block,
+        reexec: u32,
+        timeout: Duration,
+    ) -> Result<Vec<(H256, NativePrestate)>, ChainError> {
+        let mut vm = self
+            .rebuild_parent_state(block.header.parent_hash, reexec)
+            .await?;
+        vm.rerun_block(&block, Some(0))?;
+        let vm = Arc::new(Mutex::new(vm));
+        let block = Arc::new(block);
+        let mut prestates = vec![];
+        for index in 0..block.body.transactions.len() {
+            let block = block.clone();
+            let vm = vm.clone();
+            let tx_hash = block.as_ref().body.transactions[index].hash();
+            let prestate = timeout_trace_operation(timeout, move || {
+                vm.lock()
+                    .map_err(|_| EvmError::Custom("Unexpected Runtime Error".to_string()))?
+                    .trace_tx_prestate(block.as_ref(), index)
+            })
+            .await?;
+            prestates.push((tx_hash, prestate));
+        }
+        Ok(prestates)
+    }
+
     /// Rebuild the parent state for a block given its parent hash, returning an `Evm` instance with all changes cached
     /// Will re-execute all ancestor block's which's state is not stored up to a maximum given by `reexec`
     async fn rebuild_parent_state(
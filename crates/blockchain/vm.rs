@@ -8,10 +8,16 @@ use ethrex_vm::{EvmError, VmDatabase};
 use std::{
     cmp::Ordering,
     collections::BTreeMap,
-    sync::{Arc, Mutex},
+    sync::{
+        Arc, Mutex,
+        atomic::{self, AtomicBool},
+    },
 };
 use tracing::instrument;
 
+/// Number of ancestors reachable through the BLOCKHASH opcode.
+const BLOCK_HASH_WINDOW: u64 = 256;
+
 #[derive(Clone)]
 pub struct StoreVmDatabase {
     pub store: Store,
@@ -20,6 +26,9 @@ pub struct StoreVmDatabase {
     // We will also pre-load this when executing blocks in batches, as we will only add the blocks at the end
     // and may need to access hashes of blocks previously executed in the batch
     pub block_hash_cache: Arc<Mutex<BTreeMap<BlockNumber, BlockHash>>>,
+    // Whether the canonical hashes of the BLOCKHASH window were already loaded into the cache
+    pub block_hash_window_loaded: Arc<AtomicBool>,
+    pub block_number: BlockNumber,
     pub state_root: H256,
 }
 
@@ -39,6 +48,8 @@ impl StoreVmDatabase {
             store,
             block_hash: block_header.hash(),
             block_hash_cache: Arc::new(Mutex::new(BTreeMap::new())),
+            block_hash_window_loaded: Arc::new(AtomicBool::new(false)),
+            block_number: block_header.number,
             state_root: block_header.state_root,
         })
     }
@@ -59,6 +70,8 @@ impl StoreVmDatabase {
             store,
             block_hash: block_header.hash(),
             block_hash_cache: Arc::new(Mutex::new(block_hash_cache)),
+            block_hash_window_loaded: Arc::new(AtomicBool::new(false)),
+            block_number: block_header.number,
             state_root: block_header.state_root,
         })
     }
@@ -72,6 +85,10 @@ impl VmDatabase for StoreVmDatabase {
         fields(namespace = "block_execution")
     )]
     fn get_account_state(&self, address: Address) -> Result<Option<AccountState>, EvmError> {
+        // Fresh accounts are common (e.g. first transfers), rule them out without a trie walk
+        if !self.store.account_may_exist(address) {
+            return Ok(None);
+        }
         self.store
             .get_account_state_by_root(self.state_root, address)
             .map_err(|e| EvmError::DB(e.to_string()))
@@ -84,11 +101,33 @@ impl VmDatabase for StoreVmDatabase {
         fields(namespace = "block_execution")
     )]
     fn get_storage_slot(&self, address: Address, key: H256) -> Result<Option<U256>, EvmError> {
+        if !self.store.account_may_exist(address) {
+            return Ok(None);
+        }
         self.store
             .get_storage_at_root(self.state_root, address, key)
             .map_err(|e| EvmError::DB(e.to_string()))
     }
 
+    #[instrument(
+        level = "trace",
+        name = "Storage batch read",
+        skip_all,
+        fields(namespace = "block_execution")
+    )]
+    fn get_storage_slots(
+        &self,
+        address: Address,
+        keys: &[H256],
+    ) -> Result<Vec<Option<U256>>, EvmError> {
+        if !self.store.account_may_exist(address) {
+            return Ok(vec![None; keys.len()]);
+        }
+        self.store
+            .get_storage_values_at_root(self.state_root, address, keys)
+            .map_err(|e| EvmError::DB(e.to_string()))
+    }
+
     #[instrument(
         level = "trace",
         name = "Block hash read",
@@ -110,6 +149,30 @@ impl VmDatabase for StoreVmDatabase {
             .is_canonical_sync(self.block_hash)
             .map_err(|err| EvmError::DB(err.to_string()))?
         {
+            // Load the whole BLOCKHASH window in a single read the first time one of its blocks
+            // is requested, so contracts looping over ancestors don't query the store for each one
+            let window_start = self
+                .block_number
+                .saturating_sub(BLOCK_HASH_WINDOW.saturating_sub(1));
+            if (window_start..=self.block_number).contains(&block_number)
+                && !self
+                    .block_hash_window_loaded
+                    .swap(true, atomic::Ordering::Relaxed)
+            {
+                let window = self
+                    .store
+                    .get_canonical_block_hashes_range_sync(
+                        window_start..self.block_number.saturating_add(1),
+                    )
+                    .map_err(|err| EvmError::DB(err.to_string()))?;
+                for (number, hash) in window {
+                    // Hashes preloaded by the caller take precedence
+                    block_hash_cache.entry(number).or_insert(hash);
+                }
+                if let Some(block_hash) = block_hash_cache.get(&block_number) {
+                    return Ok(*block_hash);
+                }
+            }
             if let Some(hash) = self
                 .store
                 .get_canonical_block_hash_sync(block_number)
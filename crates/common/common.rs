@@ -14,8 +14,9 @@ pub mod rkyv_utils;
 pub mod tracing;
 pub mod utils;
 
-pub use errors::{EcdsaError, InvalidBlockError};
+pub use errors::{CommitmentMismatch, EcdsaError, InvalidBlockError};
 pub use validation::{
     get_total_blob_gas, validate_block, validate_block_access_list_hash, validate_gas_used,
-    validate_receipts_root, validate_requests_hash,
+    validate_logs_bloom, validate_post_execution_commitments, validate_receipts_root,
+    validate_requests_hash,
 };
@@ -1,7 +1,7 @@
 use crate::{H160, H256};
 use ethrex_crypto::keccak::keccak_hash;
 use ethrex_rlp::constants::RLP_NULL;
-use std::{str::FromStr, sync::LazyLock};
+use std::sync::LazyLock;
 
 /// SYSTEM_ADDRESS used for system contract calls and BAL filtering.
 /// 0xfffffffffffffffffffffffffffffffffffffffe
@@ -10,6 +10,34 @@ pub const SYSTEM_ADDRESS: H160 = H160([
     0xFF, 0xFF, 0xFF, 0xFE,
 ]);
 
+/// Default EIP-4788 beacon roots contract address.
+/// 0x000f3df6d732807ef1319fb7b8bb8522d0beac02
+pub const DEFAULT_BEACON_ROOTS_ADDRESS: H160 = H160([
+    0x00, 0x0F, 0x3D, 0xF6, 0xD7, 0x32, 0x80, 0x7E, 0xF1, 0x31, 0x9F, 0xB7, 0xB8, 0xBB, 0x85, 0x22,
+    0xD0, 0xBE, 0xAC, 0x02,
+]);
+
+/// Default EIP-2935 history storage contract address.
+/// 0x0000f90827f1c53a10cb7a02335b175320002935
+pub const DEFAULT_HISTORY_STORAGE_ADDRESS: H160 = H160([
+    0x00, 0x00, 0xF9, 0x08, 0x27, 0xF1, 0xC5, 0x3A, 0x10, 0xCB, 0x7A, 0x02, 0x33, 0x5B, 0x17, 0x53,
+    0x20, 0x00, 0x29, 0x35,
+]);
+
+/// Default EIP-7002 withdrawal request predeploy address.
+/// 0x00000961ef480eb55e80d19ad83579a64c007002
+pub const DEFAULT_WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS: H160 = H160([
+    0x00, 0x00, 0x09, 0x61, 0xEF, 0x48, 0x0E, 0xB5, 0x5E, 0x80, 0xD1, 0x9A, 0xD8, 0x35, 0x79, 0xA6,
+    0x4C, 0x00, 0x70, 0x02,
+]);
+
+/// Default EIP-7251 consolidation request predeploy address.
+/// 0x0000bbddc7ce488642fb579f8b00f3a590007251
+pub const DEFAULT_CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS: H160 = H160([
+    0x00, 0x00, 0xBB, 0xDD, 0xC7, 0xCE, 0x48, 0x86, 0x42, 0xFB, 0x57, 0x9F, 0x8B, 0x00, 0xF3, 0xA5,
+    0x90, 0x00, 0x72, 0x51,
+]);
+
 // = Keccak256(RLP([])) as of EIP-3675
 pub static DEFAULT_OMMERS_HASH: LazyLock<H256> = LazyLock::new(|| {
     H256::from_slice(
@@ -44,12 +72,6 @@ pub static EMPTY_KECCACK_HASH: LazyLock<H256> = LazyLock::new(|| {
 
 pub static EMPTY_TRIE_HASH: LazyLock<H256> = LazyLock::new(|| H256(keccak_hash([RLP_NULL])));
 
-// Request related
-pub static DEPOSIT_TOPIC: LazyLock<H256> = LazyLock::new(|| {
-    H256::from_str("649bbc62d0e31342afea4e5cd82d4049e7e1ee912fc0889aa790803be39038c5")
-        .expect("Failed to decode hex from string")
-});
-
 // = Keccak256(RLP([])) as of EIP-7928
 pub static EMPTY_BLOCK_ACCESS_LIST_HASH: LazyLock<H256> = LazyLock::new(|| {
     H256::from_slice(
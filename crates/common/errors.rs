@@ -1,3 +1,5 @@
+use ethereum_types::{Bloom, H256};
+
 use crate::types::{InvalidBlockBodyError, InvalidBlockHeaderError};
 
 #[derive(thiserror::Error, Debug)]
@@ -25,16 +27,12 @@ pub enum EcdsaError {
 /// These are validation errors that don't require storage access to detect.
 #[derive(Debug, thiserror::Error)]
 pub enum InvalidBlockError {
-    #[error("Requests hash does not match the one in the header after executing")]
-    RequestsHashMismatch,
-    #[error("Block access list hash does not match the one in the header after executing")]
-    BlockAccessListHashMismatch,
+    #[error(transparent)]
+    CommitmentMismatch(#[from] CommitmentMismatch),
     #[error("Block access list contains index {index} exceeding max valid index {max}")]
     BlockAccessListIndexOutOfBounds { index: u16, max: u16 },
     #[error("World State Root does not match the one in the header after executing")]
     StateRootMismatch,
-    #[error("Receipts Root does not match the one in the header after executing")]
-    ReceiptsRootMismatch,
     #[error("Invalid Header, validation failed pre-execution: {0}")]
     InvalidHeader(#[from] InvalidBlockHeaderError),
     #[error("Invalid Body, validation failed pre-execution: {0}")]
@@ -43,8 +41,6 @@ pub enum InvalidBlockError {
     ExceededMaxBlobGasPerBlock,
     #[error("Exceeded MAX_BLOB_NUMBER_PER_BLOCK")]
     ExceededMaxBlobNumberPerBlock,
-    #[error("Gas used doesn't match value in header. Used: {0}, Expected: {1}")]
-    GasUsedMismatch(u64, u64),
     #[error("Blob gas used doesn't match value in header")]
     BlobGasUsedMismatch,
     #[error("Invalid transaction: {0}")]
@@ -54,3 +50,39 @@ pub enum InvalidBlockError {
     #[error("Invalid block fork")]
     InvalidBlockFork,
 }
+
+/// A header commitment that doesn't match the value computed by executing the block.
+///
+/// `expected` is the value in the header, `actual` the one computed after executing.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum CommitmentMismatch {
+    #[error("gas_used mismatch: header has {expected}, execution used {actual}")]
+    GasUsed { expected: u64, actual: u64 },
+    #[error("receipts_root mismatch: header has {expected:#x}, execution computed {actual:#x}")]
+    ReceiptsRoot { expected: H256, actual: H256 },
+    #[error("logs_bloom mismatch: header has {expected:#x}, execution computed {actual:#x}")]
+    LogsBloom { expected: Bloom, actual: Bloom },
+    #[error("requests_hash mismatch: header has {expected:?}, execution computed {actual:#x}")]
+    RequestsHash {
+        expected: Option<H256>,
+        actual: H256,
+    },
+    #[error("bal_hash mismatch: header has {expected:?}, execution computed {actual:#x}")]
+    BlockAccessListHash {
+        expected: Option<H256>,
+        actual: H256,
+    },
+}
+
+impl CommitmentMismatch {
+    /// Name of the mismatched header field.
+    pub fn field(&self) -> &'static str {
+        match self {
+            CommitmentMismatch::GasUsed { .. } => "gas_used",
+            CommitmentMismatch::ReceiptsRoot { .. } => "receipts_root",
+            CommitmentMismatch::LogsBloom { .. } => "logs_bloom",
+            CommitmentMismatch::RequestsHash { .. } => "requests_hash",
+            CommitmentMismatch::BlockAccessListHash { .. } => "bal_hash",
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use ethereum_types::Address;
+use ethereum_types::{Address, H256};
 use ethrex_crypto::keccak::keccak_hash;
 use ethrex_rlp::encode::RLPEncode;
 
@@ -6,8 +6,27 @@ use ethrex_rlp::encode::RLPEncode;
 /// opcode as follows:
 ///
 /// address = keccak256(rlp([sender_address,sender_nonce]))[12:]
-pub fn calculate_create_address(sender_address: Address, sender_nonce: u64) -> Address {
+pub fn compute_create_address(sender_address: Address, sender_nonce: u64) -> Address {
     let mut encoded = Vec::new();
     (sender_address, sender_nonce).encode(&mut encoded);
     Address::from_slice(&keccak_hash(encoded)[12..])
 }
+
+/// Calculates the address of a new contract using the CREATE2
+/// opcode as follows:
+///
+/// address = keccak256(0xff || sender_address || salt || init_code_hash)[12:]
+pub fn compute_create2_address(
+    sender_address: Address,
+    salt: H256,
+    init_code_hash: H256,
+) -> Address {
+    let preimage = [
+        &[0xff],
+        sender_address.as_bytes(),
+        salt.as_bytes(),
+        init_code_hash.as_bytes(),
+    ]
+    .concat();
+    Address::from_slice(&keccak_hash(preimage)[12..])
+}
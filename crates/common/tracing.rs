@@ -1,7 +1,11 @@
+use std::collections::{BTreeMap, BTreeSet};
+
 use bytes::Bytes;
 use ethereum_types::H256;
 use ethereum_types::{Address, U256};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+
+use crate::types::ChainConfig;
 
 /// Collection of traces of each call frame as defined in geth's `callTracer` output
 /// https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers#call-tracer
@@ -42,6 +46,64 @@ pub struct CallTraceFrame {
     /// Logs (if enabled)
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub logs: Vec<CallLog>,
+    /// How many frames up the storage context of this call was already active, if it was.
+    /// Not part of geth's output.
+    #[serde(skip)]
+    pub reentrancy: Option<usize>,
+    /// Account whose code ran for this call, if the callee delegates to it (EIP-7702).
+    /// Not part of geth's output.
+    #[serde(skip)]
+    pub delegated_to: Option<Address>,
+    /// Why the contract creation of this frame failed, for CREATE and CREATE2 frames.
+    /// For [`CreateFailure::InitCodeSizeExceeded`] no frame is entered, it is set on the frame
+    /// that executed the opcode and halted.
+    /// Not part of geth's output.
+    #[serde(skip)]
+    pub create_failure: Option<CreateFailure>,
+}
+
+/// Cause of a failed CREATE or CREATE2.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CreateFailure {
+    /// The target address already has code, a nonzero nonce or storage.
+    AddressCollision { address: Address },
+    /// The init code is bigger than the limit of EIP-3860.
+    InitCodeSizeExceeded { size: usize, max_size: usize },
+    /// The deployer can't afford the endowment.
+    InsufficientBalance { balance: U256, value: U256 },
+    /// The call depth limit was reached.
+    DepthLimit,
+    /// The deployer nonce can't be incremented.
+    NonceOverflow,
+    /// The init code executed REVERT.
+    InitCodeReverted { data: Bytes },
+}
+
+impl std::fmt::Display for CreateFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::AddressCollision { address } => {
+                write!(
+                    f,
+                    "address collision: {address:#x} already has code, nonce or storage"
+                )
+            }
+            Self::InitCodeSizeExceeded { size, max_size } => {
+                write!(f, "init code size {size} exceeds the limit of {max_size}")
+            }
+            Self::InsufficientBalance { balance, value } => {
+                write!(
+                    f,
+                    "insufficient balance for endowment: have {balance}, want {value}"
+                )
+            }
+            Self::DepthLimit => write!(f, "call depth limit reached"),
+            Self::NonceOverflow => write!(f, "deployer nonce overflow"),
+            Self::InitCodeReverted { data } => {
+                write!(f, "init code reverted with 0x{}", hex::encode(data))
+            }
+        }
+    }
 }
 
 #[derive(Serialize, Debug, Default)]
@@ -65,3 +127,62 @@ pub struct CallLog {
     pub data: Bytes,
     pub position: u64,
 }
+
+/// State read by a transaction, per account, as defined in geth's `prestateTracer` output
+/// https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers#prestate-tracer
+pub type PrestateTrace = BTreeMap<Address, PrestateAccount>;
+
+/// Account as it was before the transaction modified it, as defined in geth's `prestateTracer` output
+/// https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers#prestate-tracer
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PrestateAccount {
+    pub balance: U256,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub nonce: u64,
+    #[serde(
+        default,
+        with = "crate::serde_utils::bytes",
+        skip_serializing_if = "Bytes::is_empty"
+    )]
+    pub code: Bytes,
+    /// Storage slots read by the transaction
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub storage: BTreeMap<H256, H256>,
+}
+
+/// Everything a transaction read from the state before executing, enough to re-execute it
+/// without the rest of the chain.
+/// Extends geth's `prestateTracer` output with the reads that are not tied to an account.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct NativePrestate {
+    pub chain_config: ChainConfig,
+    /// Geth compatible part of the trace
+    pub accounts: PrestateTrace,
+    /// Accounts with non-empty storage, relevant for contract creation collisions
+    #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+    pub accounts_with_storage: BTreeSet<Address>,
+    /// Hashes read through the BLOCKHASH opcode
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub block_hashes: BTreeMap<u64, H256>,
+}
+
+/// Geth's output doesn't tell which accounts have storage, those with a non-zero slot read surely do.
+impl From<PrestateTrace> for NativePrestate {
+    fn from(accounts: PrestateTrace) -> Self {
+        NativePrestate {
+            accounts_with_storage: accounts
+                .iter()
+                .filter(|(_, account)| account.storage.values().any(|value| !value.is_zero()))
+                .map(|(address, _)| *address)
+                .collect(),
+            accounts,
+            ..Default::default()
+        }
+    }
+}
+
+fn is_zero(value: &u64) -> bool {
+    *value == 0
+}
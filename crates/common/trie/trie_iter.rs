@@ -137,16 +137,24 @@ impl TrieIterator {
 impl Iterator for TrieIterator {
     type Item = (Nibbles, Node);
 
+    /// Iteration stops at the first node that can't be read, see [`TrieIterator::try_next`]
+    /// to tell it apart from the end of the trie.
     fn next(&mut self) -> Option<Self::Item> {
-        if self.stack.is_empty() {
-            return None;
-        };
+        self.try_next().ok().flatten()
+    }
+}
+
+impl TrieIterator {
+    /// Like [`Iterator::next`], but fails if a node can't be read instead of ending the
+    /// iteration.
+    pub fn try_next(&mut self) -> Result<Option<(Nibbles, Node)>, TrieError> {
         // Fetch the last node in the stack
-        let (mut path, next_node_ref) = self.stack.pop()?;
+        let Some((mut path, next_node_ref)) = self.stack.pop() else {
+            return Ok(None);
+        };
         let next_node = next_node_ref
-            .get_node_checked(self.db.as_ref(), path.clone())
-            .ok()
-            .flatten()?;
+            .get_node_checked(self.db.as_ref(), path.clone())?
+            .ok_or_else(|| TrieError::Verify(format!("Node at path {path:?} not found")))?;
         match &(*next_node) {
             Node::Branch(branch_node) => {
                 // Add all children to the stack (in reverse order so we process first child frist)
@@ -169,11 +177,9 @@ impl Iterator for TrieIterator {
                 path.extend(&leaf.partial);
             }
         }
-        Some((path, (*next_node).clone()))
+        Ok(Some((path, (*next_node).clone())))
     }
-}
 
-impl TrieIterator {
     // TODO: construct path from nibbles
     pub fn content(self) -> impl Iterator<Item = (PathRLP, ValueRLP)> {
         self.filter_map(|(p, n)| match n {
@@ -14,7 +14,10 @@ use ethrex_rlp::{
     structs::{Decoder, Encoder},
 };
 
-use super::GenesisAccount;
+use super::{
+    GenesisAccount,
+    eof::{EOF_MAGIC, EofCache, EofContainer, EofError},
+};
 use crate::{
     constants::{EMPTY_KECCACK_HASH, EMPTY_TRIE_HASH},
     utils::keccak,
@@ -34,6 +37,8 @@ pub struct Code {
     // this does not apply to previous forks. This is tested in the EEST tests, which would
     // panic in debug mode.
     pub jump_targets: Vec<u32>,
+    #[serde(skip)]
+    pub eof: EofCache,
 }
 
 impl Code {
@@ -46,6 +51,7 @@ impl Code {
             hash,
             bytecode: code,
             jump_targets,
+            eof: EofCache::default(),
         }
     }
 
@@ -55,11 +61,24 @@ impl Code {
             hash: keccak(code.as_ref()),
             bytecode: code,
             jump_targets,
+            eof: EofCache::default(),
         }
     }
 
+    /// Returns the EOF container of the code, validated as deployed code, or
+    /// `None` if the code doesn't start with the EOF magic.
+    ///
+    /// The container is parsed on first access and cached alongside the code.
+    pub fn parse_eof(&self) -> Option<Result<&EofContainer, &EofError>> {
+        self.eof.get_or_parse(&self.bytecode)
+    }
+
     fn compute_jump_targets(code: &[u8]) -> Vec<u32> {
         debug_assert!(code.len() <= u32::MAX as usize);
+        // EOF code has no JUMPDESTs, only relative jumps validated on deployment.
+        if code.starts_with(&EOF_MAGIC) {
+            return Vec::new();
+        }
         let mut targets = Vec::new();
         let mut i = 0;
         while i < code.len() {
@@ -93,7 +112,7 @@ impl Code {
         let hash_size = size_of::<H256>();
         let bytes_size = size_of::<Bytes>();
         let vec_size = size_of::<Vec<u32>>() + self.jump_targets.len() * size_of::<u32>();
-        hash_size + bytes_size + vec_size
+        hash_size + bytes_size + vec_size + size_of::<EofCache>()
     }
 }
 
@@ -167,6 +186,7 @@ impl Default for Code {
             bytecode: Bytes::new(),
             hash: *EMPTY_KECCACK_HASH,
             jump_targets: Vec::new(),
+            eof: EofCache::default(),
         }
     }
 }
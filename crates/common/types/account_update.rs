@@ -1,9 +1,11 @@
 use crate::{
     Address, H256, U256,
-    types::{AccountInfo, Code},
+    constants::EMPTY_KECCACK_HASH,
+    types::{Account, AccountInfo, Code},
 };
 use rustc_hash::FxHashMap;
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
 
 #[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct AccountUpdate {
@@ -36,17 +38,130 @@ impl AccountUpdate {
         }
     }
 
-    pub fn merge(&mut self, other: AccountUpdate) {
-        self.removed = other.removed;
-        self.removed_storage |= other.removed_storage;
-        if let Some(info) = other.info {
+    /// Merges `later`, an update of the same account applied after `self`, so that
+    /// applying the result is equivalent to applying both updates in order:
+    /// - If `later` removes the account, the result is a plain removal.
+    /// - If `self` removed the account and `later` recreates it, the result clears the
+    ///   storage and sets the info of the recreated account (the default one if `later`
+    ///   doesn't carry any), along with `later`'s code and storage.
+    /// - Otherwise, if `later` clears the storage, the slots written by `self` are dropped.
+    ///   Storage writes are combined with `later`'s taking precedence, and `later`'s info
+    ///   and code replace `self`'s when present. `self`'s code is dropped if `later`
+    ///   changes the code hash without carrying the new code.
+    pub fn merge(&mut self, later: AccountUpdate) {
+        if later.removed {
+            *self = AccountUpdate::removed(self.address);
+            return;
+        }
+
+        if self.removed {
+            self.removed = false;
+            self.removed_storage = true;
+            self.info = Some(later.info.unwrap_or_default());
+            self.code = later.code;
+            self.added_storage = later.added_storage;
+            return;
+        }
+
+        if later.removed_storage {
+            self.removed_storage = true;
+            self.added_storage.clear();
+        }
+        self.added_storage.extend(later.added_storage);
+        if let Some(info) = later.info {
+            let code_hash_changed = self
+                .info
+                .as_ref()
+                .is_some_and(|current| current.code_hash != info.code_hash);
+            if code_hash_changed && later.code.is_none() {
+                self.code = None;
+            }
             self.info = Some(info);
         }
-        if let Some(code) = other.code {
+        if let Some(code) = later.code {
             self.code = Some(code);
         }
-        for (key, value) in other.added_storage {
-            self.added_storage.insert(key, value);
+    }
+
+    fn is_empty(&self) -> bool {
+        !self.removed
+            && !self.removed_storage
+            && self.info.is_none()
+            && self.code.is_none()
+            && self.added_storage.is_empty()
+    }
+}
+
+/// Merges a sequence of update lists (e.g. one per transaction or block) into a single
+/// list, equivalent to applying them in order. Accounts are kept in the order in which
+/// they first appear.
+pub fn merge_updates(updates: Vec<Vec<AccountUpdate>>) -> Vec<AccountUpdate> {
+    let mut merged: Vec<AccountUpdate> = Vec::new();
+    let mut positions: FxHashMap<Address, usize> = FxHashMap::default();
+    for update in updates.into_iter().flatten() {
+        match positions.entry(update.address) {
+            Entry::Occupied(position) => {
+                if let Some(current) = merged.get_mut(*position.get()) {
+                    current.merge(update);
+                }
+            }
+            Entry::Vacant(position) => {
+                position.insert(merged.len());
+                merged.push(update);
+            }
         }
     }
+    merged
+}
+
+/// Returns the updates turning the accounts in `before` into the ones in `after`,
+/// sorted by address. Both maps hold full account states, including storage, and
+/// accounts missing from `after` are removed.
+pub fn diff_states(
+    before: &FxHashMap<Address, Account>,
+    after: &FxHashMap<Address, Account>,
+) -> Vec<AccountUpdate> {
+    let mut addresses: Vec<Address> = before.keys().chain(after.keys()).copied().collect();
+    addresses.sort_unstable();
+    addresses.dedup();
+
+    addresses
+        .into_iter()
+        .filter_map(|address| {
+            let Some(new) = after.get(&address) else {
+                return Some(AccountUpdate::removed(address));
+            };
+            let old = before.get(&address);
+            let mut update = AccountUpdate::new(address);
+
+            // New accounts always carry their info, so that they are created even if empty.
+            if old.is_none_or(|old| old.info != new.info) {
+                update.info = Some(new.info.clone());
+            }
+            // Empty code is implied by its hash and never stored.
+            let old_code_hash = old.map_or(*EMPTY_KECCACK_HASH, |old| old.info.code_hash);
+            if old_code_hash != new.info.code_hash && new.info.code_hash != *EMPTY_KECCACK_HASH {
+                update.code = Some(new.code.clone());
+            }
+
+            let old_storage = old.map(|old| &old.storage);
+            let old_value = |key: &H256| {
+                old_storage
+                    .and_then(|storage| storage.get(key).copied())
+                    .unwrap_or_default()
+            };
+            for (key, value) in &new.storage {
+                if old_value(key) != *value {
+                    update.added_storage.insert(*key, *value);
+                }
+            }
+            for (key, value) in old_storage.into_iter().flatten() {
+                if !value.is_zero() && !new.storage.contains_key(key) {
+                    update.added_storage.insert(*key, U256::zero());
+                }
+            }
+
+            (!update.is_empty()).then_some(update)
+        })
+        .collect()
 }
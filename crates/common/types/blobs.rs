@@ -0,0 +1,143 @@
+//! Validation of EIP-4844 blob transactions.
+//!
+//! These checks are shared by the mempool and LEVM's transaction validation so that
+//! both accept and reject the same type-3 transactions. Blobs bundles are checked by
+//! [`BlobsBundle::validate`](super::BlobsBundle::validate).
+
+use crate::{H256, U256};
+
+use super::{
+    BlockHeader, ChainConfig, EIP4844Transaction, Fork, calc_excess_blob_gas,
+    calculate_base_fee_per_blob_gas,
+};
+
+/// Versioned hash versions accepted in a type-3 transaction.
+pub const VALID_BLOB_PREFIXES: [u8; 2] = [0x01, 0x02];
+/// Max blob count per tx (introduced by Osaka fork).
+pub const MAX_BLOB_COUNT_TX: usize = 6;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BlobTxValidationError {
+    #[error("Type 3 transactions are not supported before the Cancun fork")]
+    PreFork,
+    #[error("Type 3 transaction without blobs")]
+    ZeroBlobs,
+    #[error("Invalid blob versioned hash at index {index}: unsupported version {version:#04x}")]
+    InvalidVersionedHash { index: usize, version: u8 },
+    #[error(
+        "Blob count exceeded. Max blob count: {max_blob_count}, actual blob count: {actual_blob_count}"
+    )]
+    BlobCountExceeded {
+        max_blob_count: usize,
+        actual_blob_count: usize,
+    },
+    #[error(
+        "Insufficient max fee per blob gas. Expected at least {base_fee_per_blob_gas}, got: {tx_max_fee_per_blob_gas}"
+    )]
+    InsufficientMaxFeePerBlobGas {
+        base_fee_per_blob_gas: U256,
+        tx_max_fee_per_blob_gas: U256,
+    },
+}
+
+impl BlobTxValidationError {
+    /// Returns true if the error is caused by the blobs themselves (hashes or count)
+    /// rather than by the chain state, i.e. the sender of the transaction is at fault.
+    pub fn is_invalid_sidecar(&self) -> bool {
+        !matches!(
+            self,
+            Self::PreFork | Self::InsufficientMaxFeePerBlobGas { .. }
+        )
+    }
+}
+
+/// Returns the blob base fee of the block at `timestamp` built on top of `parent_header`,
+/// or `None` if blobs aren't enabled at `timestamp`.
+/// The blob schedule and fork are the child block's, so a schedule change applies from
+/// the first block of its fork.
+pub fn next_blob_base_fee(
+    parent_header: &BlockHeader,
+    timestamp: u64,
+    config: &ChainConfig,
+) -> Option<U256> {
+    let schedule = config.get_fork_blob_schedule(timestamp)?;
+    let excess_blob_gas = calc_excess_blob_gas(parent_header, schedule, config.fork(timestamp));
+    Some(calculate_base_fee_per_blob_gas(
+        excess_blob_gas,
+        schedule.base_fee_update_fraction,
+    ))
+}
+
+/// Validates a type-3 transaction against the block at `timestamp` built on top of
+/// `parent_header`: fork activation, versioned hashes, blob count and max fee per blob gas.
+pub fn validate_blob_tx(
+    tx: &EIP4844Transaction,
+    parent_header: &BlockHeader,
+    timestamp: u64,
+    config: &ChainConfig,
+) -> Result<(), BlobTxValidationError> {
+    let fork = config.fork(timestamp);
+    let max_blobs_per_block = config
+        .get_fork_blob_schedule(timestamp)
+        .map(|schedule| schedule.max as usize)
+        .unwrap_or_default();
+
+    validate_blob_versioned_hashes(&tx.blob_versioned_hashes, fork, max_blobs_per_block)?;
+    validate_max_fee_per_blob_gas(
+        tx.max_fee_per_blob_gas,
+        next_blob_base_fee(parent_header, timestamp, config).unwrap_or_default(),
+    )
+}
+
+/// Validates the versioned hashes of a type-3 transaction.
+/// `max_blobs_per_block` comes from the blob schedule of `fork`.
+pub fn validate_blob_versioned_hashes(
+    blob_versioned_hashes: &[H256],
+    fork: Fork,
+    max_blobs_per_block: usize,
+) -> Result<(), BlobTxValidationError> {
+    if fork < Fork::Cancun {
+        return Err(BlobTxValidationError::PreFork);
+    }
+
+    if blob_versioned_hashes.is_empty() {
+        return Err(BlobTxValidationError::ZeroBlobs);
+    }
+
+    for (index, blob_hash) in blob_versioned_hashes.iter().enumerate() {
+        let version = blob_hash.as_bytes()[0];
+        if !VALID_BLOB_PREFIXES.contains(&version) {
+            return Err(BlobTxValidationError::InvalidVersionedHash { index, version });
+        }
+    }
+
+    let blob_count = blob_versioned_hashes.len();
+    if blob_count > max_blobs_per_block {
+        return Err(BlobTxValidationError::BlobCountExceeded {
+            max_blob_count: max_blobs_per_block,
+            actual_blob_count: blob_count,
+        });
+    }
+    if fork >= Fork::Osaka && blob_count > MAX_BLOB_COUNT_TX {
+        return Err(BlobTxValidationError::BlobCountExceeded {
+            max_blob_count: MAX_BLOB_COUNT_TX,
+            actual_blob_count: blob_count,
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks that the max fee per blob gas covers the blob base fee.
+pub fn validate_max_fee_per_blob_gas(
+    tx_max_fee_per_blob_gas: U256,
+    base_fee_per_blob_gas: U256,
+) -> Result<(), BlobTxValidationError> {
+    if tx_max_fee_per_blob_gas < base_fee_per_blob_gas {
+        return Err(BlobTxValidationError::InsufficientMaxFeePerBlobGas {
+            base_fee_per_blob_gas,
+            tx_max_fee_per_blob_gas,
+        });
+    }
+    Ok(())
+}
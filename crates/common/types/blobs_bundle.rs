@@ -1,7 +1,6 @@
 use std::ops::AddAssign;
 
 use crate::serde_utils;
-#[cfg(feature = "c-kzg")]
 use crate::types::Fork;
 use crate::types::constants::VERSIONED_HASH_VERSION_KZG;
 use crate::{Bytes, H256};
@@ -117,6 +116,35 @@ impl BlobsBundle {
         })
     }
 
+    /// Wrapper version of the bundles the network expects at `fork`: 0 (one
+    /// KZG proof per blob) before Osaka, 1 (EIP-7594 cell proofs) from Osaka on.
+    pub fn version_for_fork(fork: Fork) -> u8 {
+        if fork >= Fork::Osaka { 1 } else { 0 }
+    }
+
+    /// Builds a bundle with the proof format required at `fork`.
+    #[cfg(feature = "c-kzg")]
+    pub fn create_for_fork(blobs: &Vec<Blob>, fork: Fork) -> Result<Self, BlobsBundleError> {
+        Self::create_from_blobs(blobs, Some(Self::version_for_fork(fork)))
+    }
+
+    /// Converts the bundle to wrapper `version`, recomputing its proofs.
+    ///
+    /// Commitments don't depend on the version, so the converted bundle
+    /// matches the same versioned hashes.  Fails if the current commitments
+    /// are not the blobs' commitments.
+    #[cfg(feature = "c-kzg")]
+    pub fn into_version(self, version: u8) -> Result<Self, BlobsBundleError> {
+        if self.version == version {
+            return Ok(self);
+        }
+        let converted = Self::create_from_blobs(&self.blobs, Some(version))?;
+        if converted.commitments != self.commitments {
+            return Err(BlobsBundleError::BlobToCommitmentAndProofError);
+        }
+        Ok(converted)
+    }
+
     pub fn generate_versioned_hashes(&self) -> Vec<H256> {
         self.commitments
             .iter()
@@ -138,7 +166,7 @@ impl BlobsBundle {
     /// Verifies KZG cryptographic proofs against the blobs and commitments.
     /// Dispatches to cell-proof or standard verification based on bundle version.
     #[cfg(feature = "c-kzg")]
-    fn verify_kzg_proofs(&self) -> Result<(), BlobsBundleError> {
+    pub(crate) fn verify_kzg_proofs(&self) -> Result<(), BlobsBundleError> {
         let valid = if self.version != 0 {
             ethrex_crypto::kzg::verify_cell_kzg_proof_batch(
                 &self.blobs,
@@ -181,7 +209,7 @@ impl BlobsBundle {
             return Err(BlobsBundleError::BlobBundleEmptyError);
         }
 
-        if (self.version == 0 && fork >= Fork::Osaka) || (self.version != 0 && fork < Fork::Osaka) {
+        if self.version != Self::version_for_fork(fork) {
             return Err(BlobsBundleError::InvalidBlobVersionForFork);
         }
 
@@ -584,4 +612,130 @@ mod tests {
             Err(crate::types::BlobsBundleError::InvalidBlobVersionForFork)
         ));
     }
+
+    #[test]
+    fn version_follows_osaka_activation() {
+        use crate::types::{BlobsBundle, Fork};
+
+        assert_eq!(BlobsBundle::version_for_fork(Fork::Cancun), 0);
+        assert_eq!(BlobsBundle::version_for_fork(Fork::Prague), 0);
+        assert_eq!(BlobsBundle::version_for_fork(Fork::Osaka), 1);
+        assert_eq!(BlobsBundle::version_for_fork(Fork::Amsterdam), 1);
+
+        let v1 = BlobsBundle {
+            version: 1,
+            ..Default::default()
+        };
+        let wrapped = crate::types::WrappedEIP4844Transaction::new(Default::default(), v1);
+        assert_eq!(wrapped.wrapper_version, Some(1));
+        let wrapped =
+            crate::types::WrappedEIP4844Transaction::new(Default::default(), BlobsBundle::empty());
+        assert_eq!(wrapped.wrapper_version, None);
+    }
+
+    #[test]
+    #[cfg(feature = "c-kzg")]
+    fn bundles_convert_between_versions() {
+        use crate::types::{BlobsBundle, CELLS_PER_EXT_BLOB, Fork};
+
+        let blobs = vec!["Hello, world!".as_bytes(), "Goodbye, world!".as_bytes()]
+            .into_iter()
+            .map(|data| {
+                crate::types::blobs_bundle::blob_from_bytes(data.into())
+                    .expect("Failed to create blob")
+            })
+            .collect();
+        let v0 = BlobsBundle::create_for_fork(&blobs, Fork::Prague).expect("Failed to create v0");
+        let tx = crate::types::EIP4844Transaction {
+            blob_versioned_hashes: v0.generate_versioned_hashes(),
+            ..Default::default()
+        };
+
+        let v1 = v0.clone().into_version(1).expect("Failed to convert to v1");
+        assert_eq!(v1.version, 1);
+        assert_eq!(v1.commitments, v0.commitments);
+        assert_eq!(v1.proofs.len(), 2 * CELLS_PER_EXT_BLOB);
+        assert_eq!(
+            v1,
+            BlobsBundle::create_for_fork(&blobs, Fork::Osaka).expect("Failed to create v1")
+        );
+
+        // Both versions match the same versioned hashes
+        assert!(v0.validate(&tx, Fork::Prague).is_ok());
+        assert!(v1.validate(&tx, Fork::Osaka).is_ok());
+
+        assert_eq!(v1.into_version(0).expect("Failed to convert to v0"), v0);
+    }
+
+    #[test]
+    #[cfg(feature = "c-kzg")]
+    fn conversion_rejects_foreign_commitments() {
+        let blob = crate::types::blobs_bundle::blob_from_bytes("Im a Blob".as_bytes().into())
+            .expect("Failed to create blob");
+        let mut bundle = crate::types::BlobsBundle::create_from_blobs(&vec![blob], None)
+            .expect("Failed to create blobs bundle");
+        bundle.commitments[0][1] ^= 1;
+
+        assert!(matches!(
+            bundle.into_version(1),
+            Err(crate::types::BlobsBundleError::BlobToCommitmentAndProofError)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "c-kzg")]
+    fn validate_rejects_cell_proofs_of_other_blob() {
+        let blobs: Vec<_> = ["first blob", "second blob"]
+            .into_iter()
+            .map(|data| {
+                crate::types::blobs_bundle::blob_from_bytes(data.as_bytes().into())
+                    .expect("Failed to create blob")
+            })
+            .collect();
+        let mut bundle = crate::types::BlobsBundle::create_from_blobs(&blobs, Some(1))
+            .expect("Failed to create blobs bundle");
+        let tx = crate::types::EIP4844Transaction {
+            blob_versioned_hashes: bundle.generate_versioned_hashes(),
+            ..Default::default()
+        };
+        let cells = crate::types::CELLS_PER_EXT_BLOB;
+        let second_blob_proofs = bundle.proofs[cells..].to_vec();
+        bundle.proofs[..cells].copy_from_slice(&second_blob_proofs);
+
+        assert!(matches!(
+            bundle.validate(&tx, crate::types::Fork::Osaka),
+            Err(crate::types::BlobsBundleError::BlobToCommitmentAndProofError)
+        ));
+
+        bundle.proofs.pop();
+        assert!(matches!(
+            bundle.validate(&tx, crate::types::Fork::Osaka),
+            Err(crate::types::BlobsBundleError::BlobsBundleWrongLen)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "c-kzg")]
+    fn zero_blob_matches_reference_vectors() {
+        // The zero polynomial commits to the point at infinity, and so does
+        // every proof of its cells (EIP-7594 reference tests,
+        // compute_cells_and_kzg_proofs_case_valid_zero).
+        let mut infinity = [0u8; 48];
+        infinity[0] = 0xc0;
+
+        let bundle = crate::types::BlobsBundle::create_from_blobs(
+            &vec![[0; crate::types::BYTES_PER_BLOB]],
+            Some(1),
+        )
+        .expect("Failed to create blobs bundle");
+
+        assert_eq!(bundle.commitments, vec![infinity]);
+        assert_eq!(bundle.proofs.len(), crate::types::CELLS_PER_EXT_BLOB);
+        assert!(bundle.proofs.iter().all(|proof| *proof == infinity));
+        let tx = crate::types::EIP4844Transaction {
+            blob_versioned_hashes: bundle.generate_versioned_hashes(),
+            ..Default::default()
+        };
+        assert!(bundle.validate(&tx, crate::types::Fork::Osaka).is_ok());
+    }
 }
@@ -613,6 +613,9 @@ pub enum InvalidBlockHeaderError {
     ParentBeaconBlockRootNotPresent,
     #[error("Requests hash is not present")]
     RequestsHashNotPresent,
+    // Amsterdam fork errors
+    #[error("Slot number is not present")]
+    SlotNumberNotPresent,
     // Other fork errors
     #[error("Excess blob gas is present")]
     ExcessBlobGasPresent,
@@ -622,6 +625,8 @@ pub enum InvalidBlockHeaderError {
     ParentBeaconBlockRootPresent,
     #[error("Requests hash is present")]
     RequestsHashPresent,
+    #[error("Slot number is present")]
+    SlotNumberPresent,
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -752,6 +757,22 @@ pub fn validate_prague_header_fields(
     Ok(())
 }
 
+/// Validates that the slot number (EIP-7843) is present in Amsterdam blocks, and
+/// only in them
+pub fn validate_slot_number_field(
+    header: &BlockHeader,
+    chain_config: &ChainConfig,
+) -> Result<(), InvalidBlockHeaderError> {
+    if chain_config.is_amsterdam_activated(header.timestamp) {
+        if header.slot_number.is_none() {
+            return Err(InvalidBlockHeaderError::SlotNumberNotPresent);
+        }
+    } else if header.slot_number.is_some() {
+        return Err(InvalidBlockHeaderError::SlotNumberPresent);
+    }
+    Ok(())
+}
+
 /// Validates that only the required field are present for a Cancun block
 /// Also validates excess_blob_gas value against parent's header
 pub fn validate_cancun_header_fields(
@@ -971,6 +992,30 @@ mod test {
         assert_eq!(block.encode_to_vec().len(), block.length());
     }
 
+    #[test]
+    fn test_validate_slot_number_field() {
+        let chain_config = ChainConfig {
+            amsterdam_time: Some(1000),
+            ..Default::default()
+        };
+        let header = |timestamp, slot_number| BlockHeader {
+            timestamp,
+            slot_number,
+            ..Default::default()
+        };
+
+        assert!(validate_slot_number_field(&header(1000, Some(7)), &chain_config).is_ok());
+        assert!(matches!(
+            validate_slot_number_field(&header(1000, None), &chain_config),
+            Err(InvalidBlockHeaderError::SlotNumberNotPresent)
+        ));
+        assert!(validate_slot_number_field(&header(999, None), &chain_config).is_ok());
+        assert!(matches!(
+            validate_slot_number_field(&header(999, Some(7)), &chain_config),
+            Err(InvalidBlockHeaderError::SlotNumberPresent)
+        ));
+    }
+
     #[test]
     fn test_compute_transactions_root() {
         let encoded_transactions = [
@@ -1186,4 +1186,108 @@ impl BlockAccessListRecorder {
             }
         }
     }
+
+    /// Merges the contributions recorded by `other`, which must not share any block access
+    /// index with `self`. Recorders must be absorbed in index order.
+    fn absorb(&mut self, mut other: BlockAccessListRecorder) {
+        other.filter_net_zero_storage();
+        other.filter_net_zero_code();
+
+        self.touched_addresses.append(&mut other.touched_addresses);
+        for (address, reads) in other.storage_reads {
+            self.storage_reads.entry(address).or_default().extend(reads);
+        }
+        for (address, slots) in other.storage_writes {
+            let writes = self.storage_writes.entry(address).or_default();
+            for (slot, mut changes) in slots {
+                writes.entry(slot).or_default().append(&mut changes);
+            }
+        }
+        for (address, balance) in other.initial_balances {
+            self.initial_balances.entry(address).or_insert(balance);
+        }
+        for (address, mut changes) in other.balance_changes {
+            self.balance_changes
+                .entry(address)
+                .or_default()
+                .append(&mut changes);
+        }
+        for (address, mut changes) in other.nonce_changes {
+            self.nonce_changes
+                .entry(address)
+                .or_default()
+                .append(&mut changes);
+        }
+        for (address, mut changes) in other.code_changes {
+            self.code_changes
+                .entry(address)
+                .or_default()
+                .append(&mut changes);
+        }
+        self.addresses_with_initial_code
+            .append(&mut other.addresses_with_initial_code);
+
+        // A slot read by one index and written by another is only a write. Each recorder
+        // only knows about its own writes, so promotions are resolved here instead.
+        for (address, reads) in self.storage_reads.iter_mut() {
+            if let Some(writes) = self.storage_writes.get(address) {
+                reads.retain(|slot| !writes.contains_key(slot));
+            }
+        }
+        self.storage_reads.retain(|_, reads| !reads.is_empty());
+        self.reads_promoted_to_writes.clear();
+    }
+}
+
+/// Builds the Block Access List of a payload under construction (EIP-7928).
+///
+/// Unlike a single [`BlockAccessListRecorder`], which merges everything it records, the
+/// contributions of each block access index are kept apart so that a transaction evicted
+/// from the payload can be retracted. Each index is recorded by its own recorder and the
+/// recorders are merged when the payload is finalized, producing the same list as recording
+/// the final block in one go.
+#[derive(Debug, Default, Clone)]
+pub struct PayloadBalBuilder {
+    /// Finished recorders, by block access index.
+    recorders: BTreeMap<u16, BlockAccessListRecorder>,
+}
+
+impl PayloadBalBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores the contributions of a finished index, merging them with any previously
+    /// stored for the same index.
+    pub fn push(&mut self, recorder: BlockAccessListRecorder) {
+        if recorder.is_empty() {
+            return;
+        }
+        let index = recorder.current_index();
+        match self.recorders.remove(&index) {
+            Some(mut previous) => {
+                previous.absorb(recorder);
+                self.recorders.insert(index, previous);
+            }
+            None => {
+                self.recorders.insert(index, recorder);
+            }
+        }
+    }
+
+    /// Retracts every contribution recorded for `index`.
+    /// Returns true if there was anything to retract.
+    pub fn remove(&mut self, index: u16) -> bool {
+        self.recorders.remove(&index).is_some()
+    }
+
+    /// Merges the contributions of all indices into the final BlockAccessList.
+    pub fn build(self) -> BlockAccessList {
+        let mut merged = BlockAccessListRecorder::new();
+        for recorder in self.recorders.into_values() {
+            merged.absorb(recorder);
+        }
+        merged.build()
+    }
 }
@@ -0,0 +1,811 @@
+//! EVM Object Format (EOF) v1 containers.
+//!
+//! Implements the container layout of [EIP-3540](https://eips.ethereum.org/EIPS/eip-3540)
+//! and deploy-time code validation per EIP-3670 (opcodes and immediates),
+//! EIP-4200 (static relative jumps), EIP-4750 (functions), EIP-5450 (stack
+//! validation), EIP-6206 (JUMPF), EIP-7480 (data section) and EIP-7620
+//! (EOFCREATE/RETURNCONTRACT subcontainers).
+//!
+//! Only parsing and validation live here, execution semantics are up to the VM.
+
+use std::{
+    collections::{BTreeSet, VecDeque},
+    fmt,
+    hash::{Hash, Hasher},
+    sync::{Arc, OnceLock},
+};
+
+use bytes::Bytes;
+
+pub const EOF_MAGIC: [u8; 2] = [0xef, 0x00];
+pub const EOF_VERSION: u8 = 0x01;
+
+const KIND_TYPES: u8 = 0x01;
+const KIND_CODE: u8 = 0x02;
+const KIND_CONTAINER: u8 = 0x03;
+const KIND_DATA: u8 = 0xff;
+const TERMINATOR: u8 = 0x00;
+
+const MAX_CODE_SECTIONS: usize = 1024;
+const MAX_CONTAINER_SECTIONS: usize = 256;
+/// Maximum number of inputs or outputs of a code section.
+const MAX_SECTION_IO: u8 = 0x7f;
+/// `outputs` value marking a code section that never returns to its caller.
+pub const NON_RETURNING_SECTION: u8 = 0x80;
+const MAX_STACK_INCREASE: u16 = 0x03ff;
+const STACK_LIMIT: usize = 1024;
+
+// TODO: we don't use the constants from the vm module to avoid a circular dependency
+const STOP: u8 = 0x00;
+const PUSH1: u8 = 0x60;
+const PUSH32: u8 = 0x7f;
+const DUP1: u8 = 0x80;
+const DUP16: u8 = 0x8f;
+const SWAP1: u8 = 0x90;
+const SWAP16: u8 = 0x9f;
+const LOG0: u8 = 0xa0;
+const LOG4: u8 = 0xa4;
+const DATALOADN: u8 = 0xd1;
+const RJUMP: u8 = 0xe0;
+const RJUMPI: u8 = 0xe1;
+const RJUMPV: u8 = 0xe2;
+const CALLF: u8 = 0xe3;
+const RETF: u8 = 0xe4;
+const JUMPF: u8 = 0xe5;
+const DUPN: u8 = 0xe6;
+const SWAPN: u8 = 0xe7;
+const EXCHANGE: u8 = 0xe8;
+const EOFCREATE: u8 = 0xec;
+const RETURNCONTRACT: u8 = 0xee;
+const RETURN: u8 = 0xf3;
+const REVERT: u8 = 0xfd;
+const INVALID: u8 = 0xfe;
+
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum EofError {
+    #[error("Invalid EOF magic")]
+    InvalidMagic,
+    #[error("Unsupported EOF version")]
+    InvalidVersion,
+    #[error("EOF header is truncated")]
+    IncompleteHeader,
+    #[error("Missing type section header")]
+    MissingTypeHeader,
+    #[error("Missing code section header")]
+    MissingCodeHeader,
+    #[error("Missing data section header")]
+    MissingDataHeader,
+    #[error("Missing header terminator")]
+    MissingTerminator,
+    #[error("Type section size doesn't match the number of code sections")]
+    InvalidTypeSectionSize,
+    #[error("Section count or size is zero")]
+    ZeroSectionSize,
+    #[error("Too many code sections")]
+    TooManyCodeSections,
+    #[error("Too many container sections")]
+    TooManyContainerSections,
+    #[error("Section bodies don't match the sizes in the header")]
+    InvalidSectionBodiesSize,
+    #[error("Data section is shorter than declared")]
+    TruncatedData,
+    #[error("First code section must have 0 inputs and be non-returning")]
+    InvalidFirstSectionType,
+    #[error("Code section inputs or outputs above limit")]
+    InputsOutputsAboveLimit,
+    #[error("Code section max stack increase above limit")]
+    MaxStackIncreaseAboveLimit,
+    #[error("Undefined instruction {opcode:#04x} at offset {offset}")]
+    UndefinedInstruction { opcode: u8, offset: usize },
+    #[error("Truncated immediate at offset {0}")]
+    TruncatedImmediate(usize),
+    #[error("Invalid relative jump destination at offset {0}")]
+    InvalidRjumpDestination(usize),
+    #[error("Invalid code section index at offset {0}")]
+    InvalidCodeSectionIndex(usize),
+    #[error("Invalid container section index at offset {0}")]
+    InvalidContainerSectionIndex(usize),
+    #[error("DATALOADN reads past the data section at offset {0}")]
+    InvalidDataloadnIndex(usize),
+    #[error("CALLF to a non-returning code section at offset {0}")]
+    CallfToNonReturning(usize),
+    #[error("JUMPF to a code section with incompatible outputs at offset {0}")]
+    JumpfDestinationIncompatibleOutputs(usize),
+    #[error("Code section {0} returning flag doesn't match its code")]
+    InvalidNonReturningFlag(usize),
+    #[error("Instruction at offset {0} isn't allowed in this container kind")]
+    IncompatibleContainerKind(usize),
+    #[error("Code section {0} ends without a terminating instruction")]
+    NoTerminatingInstruction(usize),
+    #[error("Unreachable instruction at offset {0}")]
+    UnreachableInstructions(usize),
+    #[error("Code section {0} is unreachable")]
+    UnreachableCodeSections(usize),
+    #[error("Stack underflow at offset {0}")]
+    StackUnderflow(usize),
+    #[error("Stack overflow at offset {0}")]
+    StackOverflow(usize),
+    #[error("Stack height mismatch at offset {0}")]
+    StackHeightMismatch(usize),
+    #[error("Code section {0} declares a wrong max stack increase")]
+    InvalidMaxStackIncrease(usize),
+    #[error("Container section {0} is not referenced")]
+    OrphanSubcontainer(usize),
+    #[error("Container section {0} is referenced by both EOFCREATE and RETURNCONTRACT")]
+    AmbiguousSubcontainer(usize),
+    #[error("Invalid container section {index}: {error}")]
+    InvalidSubcontainer { index: usize, error: Box<EofError> },
+}
+
+/// How a container is going to be used, which restricts the terminating
+/// instructions it may contain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContainerKind {
+    /// Initcode run by EOFCREATE or a creation transaction: ends with
+    /// RETURNCONTRACT, never with STOP or RETURN.
+    Initcode,
+    /// Deployed code: may STOP or RETURN, never RETURNCONTRACT.
+    Runtime,
+}
+
+/// Entry of the type section describing a code section.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EofTypes {
+    pub inputs: u8,
+    /// Number of outputs, [`NON_RETURNING_SECTION`] if it never returns.
+    pub outputs: u8,
+    pub max_stack_increase: u16,
+}
+
+impl EofTypes {
+    pub fn is_returning(&self) -> bool {
+        self.outputs != NON_RETURNING_SECTION
+    }
+
+    fn max_stack_height(&self) -> usize {
+        usize::from(self.inputs) + usize::from(self.max_stack_increase)
+    }
+}
+
+/// A parsed EOF v1 container. Sections are slices of the original bytecode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EofContainer {
+    pub types: Vec<EofTypes>,
+    pub code_sections: Vec<Bytes>,
+    pub container_sections: Vec<Bytes>,
+    pub data: Bytes,
+    /// Data section size declared in the header, which may exceed `data.len()`
+    /// for containers that get aux data appended when deployed.
+    pub data_size: u16,
+}
+
+impl EofContainer {
+    /// Parses the header and splits the body into sections without validating
+    /// the code. A data section shorter than declared is accepted.
+    pub fn parse(bytecode: &Bytes) -> Result<Self, EofError> {
+        let mut header = Reader::new(bytecode, EofError::IncompleteHeader);
+        if header.take(2)? != EOF_MAGIC {
+            return Err(EofError::InvalidMagic);
+        }
+        if header.u8()? != EOF_VERSION {
+            return Err(EofError::InvalidVersion);
+        }
+
+        if header.u8()? != KIND_TYPES {
+            return Err(EofError::MissingTypeHeader);
+        }
+        let types_size = usize::from(header.u16()?);
+
+        if header.u8()? != KIND_CODE {
+            return Err(EofError::MissingCodeHeader);
+        }
+        let code_sizes =
+            header.section_sizes(MAX_CODE_SECTIONS, EofError::TooManyCodeSections, |reader| {
+                reader.u16().map(usize::from)
+            })?;
+        if types_size != code_sizes.len() * 4 {
+            return Err(EofError::InvalidTypeSectionSize);
+        }
+
+        let mut kind = header.u8()?;
+        let container_sizes = if kind == KIND_CONTAINER {
+            let sizes = header.section_sizes(
+                MAX_CONTAINER_SECTIONS,
+                EofError::TooManyContainerSections,
+                |reader| reader.u32().map(|size| size as usize),
+            )?;
+            kind = header.u8()?;
+            sizes
+        } else {
+            Vec::new()
+        };
+
+        if kind != KIND_DATA {
+            return Err(EofError::MissingDataHeader);
+        }
+        let data_size = header.u16()?;
+        if header.u8()? != TERMINATOR {
+            return Err(EofError::MissingTerminator);
+        }
+
+        let mut body = Reader {
+            bytes: bytecode,
+            pos: header.pos,
+            error: EofError::InvalidSectionBodiesSize,
+        };
+        let types = (0..code_sizes.len())
+            .map(|_| {
+                Ok(EofTypes {
+                    inputs: body.u8()?,
+                    outputs: body.u8()?,
+                    max_stack_increase: body.u16()?,
+                })
+            })
+            .collect::<Result<Vec<_>, EofError>>()?;
+        let code_sections = code_sizes
+            .into_iter()
+            .map(|size| body.slice(size))
+            .collect::<Result<Vec<_>, _>>()?;
+        let container_sections = container_sizes
+            .into_iter()
+            .map(|size| body.slice(size))
+            .collect::<Result<Vec<_>, _>>()?;
+        let remaining = bytecode.len() - body.pos;
+        if remaining > usize::from(data_size) {
+            return Err(EofError::InvalidSectionBodiesSize);
+        }
+        let data = body.slice(remaining)?;
+
+        for (index, types) in types.iter().enumerate() {
+            if index == 0 && (types.inputs != 0 || types.outputs != NON_RETURNING_SECTION) {
+                return Err(EofError::InvalidFirstSectionType);
+            }
+            if types.inputs > MAX_SECTION_IO || types.outputs > NON_RETURNING_SECTION {
+                return Err(EofError::InputsOutputsAboveLimit);
+            }
+            if types.max_stack_increase > MAX_STACK_INCREASE {
+                return Err(EofError::MaxStackIncreaseAboveLimit);
+            }
+        }
+
+        Ok(Self {
+            types,
+            code_sections,
+            container_sections,
+            data,
+            data_size,
+        })
+    }
+
+    /// Parses `bytecode` and validates it, including its subcontainers, to be
+    /// used as `kind`.
+    pub fn from_bytes(bytecode: &Bytes, kind: ContainerKind) -> Result<Self, EofError> {
+        let container = Self::parse(bytecode)?;
+        container.validate(kind)?;
+        Ok(container)
+    }
+
+    /// Whether the data section is shorter than declared in the header.
+    pub fn is_data_truncated(&self) -> bool {
+        self.data.len() < usize::from(self.data_size)
+    }
+
+    /// Validates the code sections and, recursively, the subcontainers.
+    /// Only containers deployed through RETURNCONTRACT may have truncated data,
+    /// so a top-level container must be complete.
+    pub fn validate(&self, kind: ContainerKind) -> Result<(), EofError> {
+        if self.is_data_truncated() {
+            return Err(EofError::TruncatedData);
+        }
+        self.validate_body(kind)
+    }
+
+    fn validate_body(&self, kind: ContainerKind) -> Result<(), EofError> {
+        let mut refs = References::default();
+        for index in 0..self.code_sections.len() {
+            self.validate_code_section(index, kind, &mut refs)?;
+        }
+
+        // Every code section must be reachable from the first one.
+        let mut visited = vec![false; self.code_sections.len()];
+        let mut queue = VecDeque::from([0]);
+        while let Some(index) = queue.pop_front() {
+            if std::mem::replace(&mut visited[index], true) {
+                continue;
+            }
+            queue.extend(refs.callees[index].iter().copied());
+        }
+        if let Some(index) = visited.iter().position(|visited| !visited) {
+            return Err(EofError::UnreachableCodeSections(index));
+        }
+
+        for (index, bytecode) in self.container_sections.iter().enumerate() {
+            let by_eofcreate = refs.eofcreate.contains(&index);
+            let by_returncontract = refs.returncontract.contains(&index);
+            let result = match (by_eofcreate, by_returncontract) {
+                (false, false) => return Err(EofError::OrphanSubcontainer(index)),
+                (true, true) => return Err(EofError::AmbiguousSubcontainer(index)),
+                (true, false) => Self::parse(bytecode)
+                    .and_then(|container| container.validate(ContainerKind::Initcode)),
+                (false, true) => Self::parse(bytecode)
+                    .and_then(|container| container.validate_body(ContainerKind::Runtime)),
+            };
+            result.map_err(|error| EofError::InvalidSubcontainer {
+                index,
+                error: Box::new(error),
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Checks instructions and their immediates, then runs stack validation.
+    fn validate_code_section(
+        &self,
+        section: usize,
+        kind: ContainerKind,
+        refs: &mut References,
+    ) -> Result<(), EofError> {
+        let code = &self.code_sections[section];
+        let types = self.types[section];
+        let mut is_instruction = vec![false; code.len()];
+        let mut rjump_targets = Vec::new();
+        let mut callees = BTreeSet::new();
+        let mut returns = false;
+
+        let mut pos = 0;
+        while pos < code.len() {
+            let opcode = code[pos];
+            let info = instruction(opcode).ok_or(EofError::UndefinedInstruction {
+                opcode,
+                offset: pos,
+            })?;
+            let immediate = immediate_size(code, pos, info)?;
+            is_instruction[pos] = true;
+
+            match opcode {
+                RJUMP | RJUMPI | RJUMPV => {
+                    rjump_targets.extend(relative_targets(code, pos, immediate).map(|t| (pos, t)))
+                }
+                CALLF | JUMPF => {
+                    let target = usize::from(read_u16(code, pos + 1));
+                    let target_types = self
+                        .types
+                        .get(target)
+                        .ok_or(EofError::InvalidCodeSectionIndex(pos))?;
+                    if opcode == CALLF && !target_types.is_returning() {
+                        return Err(EofError::CallfToNonReturning(pos));
+                    }
+                    if opcode == JUMPF && target_types.is_returning() {
+                        // Jumping into a returning section returns from this one.
+                        if !types.is_returning() || target_types.outputs > types.outputs {
+                            return Err(EofError::JumpfDestinationIncompatibleOutputs(pos));
+                        }
+                        returns = true;
+                    }
+                    callees.insert(target);
+                }
+                RETF => {
+                    if !types.is_returning() {
+                        return Err(EofError::InvalidNonReturningFlag(section));
+                    }
+                    returns = true;
+                }
+                DATALOADN => {
+                    let offset = usize::from(read_u16(code, pos + 1));
+                    if offset + 32 > usize::from(self.data_size) {
+                        return Err(EofError::InvalidDataloadnIndex(pos));
+                    }
+                }
+                EOFCREATE | RETURNCONTRACT => {
+                    let index = usize::from(code[pos + 1]);
+                    if index >= self.container_sections.len() {
+                        return Err(EofError::InvalidContainerSectionIndex(pos));
+                    }
+                    if opcode == EOFCREATE {
+                        refs.eofcreate.insert(index);
+                    } else if kind == ContainerKind::Runtime {
+                        return Err(EofError::IncompatibleContainerKind(pos));
+                    } else {
+                        refs.returncontract.insert(index);
+                    }
+                }
+                STOP | RETURN if kind == ContainerKind::Initcode => {
+                    return Err(EofError::IncompatibleContainerKind(pos));
+                }
+                _ => {}
+            }
+            pos += 1 + immediate;
+        }
+
+        if types.is_returning() && !returns {
+            return Err(EofError::InvalidNonReturningFlag(section));
+        }
+        for (pos, target) in rjump_targets {
+            if !usize::try_from(target)
+                .is_ok_and(|target| is_instruction.get(target) == Some(&true))
+            {
+                return Err(EofError::InvalidRjumpDestination(pos));
+            }
+        }
+        refs.callees.push(callees.into_iter().collect());
+
+        self.validate_stack(section)
+    }
+
+    /// Computes the stack height range at every instruction in a single pass,
+    /// as all jumps are relative and backward jumps must keep the height
+    /// (EIP-5450).
+    fn validate_stack(&self, section: usize) -> Result<(), EofError> {
+        let code = &self.code_sections[section];
+        let types = self.types[section];
+        let inputs = usize::from(types.inputs);
+        let mut heights: Vec<Option<(usize, usize)>> = vec![None; code.len()];
+        heights[0] = Some((inputs, inputs));
+        let mut max_height = inputs;
+
+        let mut pos = 0;
+        while pos < code.len() {
+            let opcode = code[pos];
+            // Instructions and immediates were checked by the first pass.
+            let info = instruction(opcode).ok_or(EofError::UndefinedInstruction {
+                opcode,
+                offset: pos,
+            })?;
+            let immediate = immediate_size(code, pos, info)?;
+            let (min, max) = heights[pos].ok_or(EofError::UnreachableInstructions(pos))?;
+
+            let (pops, pushes) = match opcode {
+                CALLF | JUMPF => {
+                    let target = self.types[usize::from(read_u16(code, pos + 1))];
+                    if max + usize::from(target.max_stack_increase) > STACK_LIMIT {
+                        return Err(EofError::StackOverflow(pos));
+                    }
+                    if opcode == JUMPF && target.is_returning() {
+                        let expected = usize::from(types.outputs) + usize::from(target.inputs)
+                            - usize::from(target.outputs);
+                        if min != max || max != expected {
+                            return Err(EofError::StackHeightMismatch(pos));
+                        }
+                    }
+                    // JUMPF doesn't return here, the outputs are checked above.
+                    let pushes = if opcode == CALLF {
+                        usize::from(target.outputs)
+                    } else {
+                        0
+                    };
+                    (usize::from(target.inputs), pushes)
+                }
+                RETF => {
+                    let outputs = usize::from(types.outputs);
+                    if min != max || max != outputs {
+                        return Err(EofError::StackHeightMismatch(pos));
+                    }
+                    (outputs, 0)
+                }
+                DUPN => {
+                    let n = usize::from(code[pos + 1]);
+                    (n + 1, n + 2)
+                }
+                SWAPN => {
+                    let n = usize::from(code[pos + 1]);
+                    (n + 2, n + 2)
+                }
+                EXCHANGE => {
+                    let n = usize::from(code[pos + 1] >> 4) + 1;
+                    let m = usize::from(code[pos + 1] & 0x0f) + 1;
+                    (n + m + 1, n + m + 1)
+                }
+                _ => (usize::from(info.pops), usize::from(info.pushes)),
+            };
+            if min < pops {
+                return Err(EofError::StackUnderflow(pos));
+            }
+            let next_heights = (min - pops + pushes, max - pops + pushes);
+            if next_heights.1 >= STACK_LIMIT {
+                return Err(EofError::StackOverflow(pos));
+            }
+            max_height = max_height.max(next_heights.1);
+
+            let next = pos + 1 + immediate;
+            let falls_through = !info.terminating && opcode != RJUMP;
+            if falls_through {
+                if next >= code.len() {
+                    return Err(EofError::NoTerminatingInstruction(section));
+                }
+                merge_heights(&mut heights[next], next_heights);
+            }
+            if matches!(opcode, RJUMP | RJUMPI | RJUMPV) {
+                for target in relative_targets(code, pos, immediate) {
+                    // Targets were checked to be instruction starts in the first pass.
+                    let target = usize::try_from(target)
+                        .map_err(|_| EofError::InvalidRjumpDestination(pos))?;
+                    if target > pos {
+                        merge_heights(&mut heights[target], next_heights);
+                    } else if heights[target] != Some(next_heights) {
+                        return Err(EofError::StackHeightMismatch(pos));
+                    }
+                }
+            }
+            pos = next;
+        }
+
+        if max_height != types.max_stack_height() {
+            return Err(EofError::InvalidMaxStackIncrease(section));
+        }
+        Ok(())
+    }
+}
+
+/// Subcontainers and code sections referenced by a container's code.
+#[derive(Default)]
+struct References {
+    /// Code sections entered through CALLF or JUMPF, by calling section.
+    callees: Vec<Vec<usize>>,
+    eofcreate: BTreeSet<usize>,
+    returncontract: BTreeSet<usize>,
+}
+
+fn merge_heights(heights: &mut Option<(usize, usize)>, (min, max): (usize, usize)) {
+    *heights = Some(match *heights {
+        Some((current_min, current_max)) => (current_min.min(min), current_max.max(max)),
+        None => (min, max),
+    });
+}
+
+/// Absolute targets of the relative jump at `pos`, which may be out of bounds.
+fn relative_targets(code: &[u8], pos: usize, immediate: usize) -> impl Iterator<Item = i64> {
+    let next = (pos + 1 + immediate) as i64;
+    let offsets = match code[pos] {
+        RJUMPV => pos + 2..pos + 1 + immediate,
+        _ => pos + 1..pos + 3,
+    };
+    offsets
+        .step_by(2)
+        .map(move |offset| next + i64::from(i16::from_be_bytes([code[offset], code[offset + 1]])))
+}
+
+fn read_u16(code: &[u8], pos: usize) -> u16 {
+    u16::from_be_bytes([code[pos], code[pos + 1]])
+}
+
+/// Size of the immediate of the instruction at `pos`, checking it fits in the code.
+fn immediate_size(code: &[u8], pos: usize, info: Instruction) -> Result<usize, EofError> {
+    let size = if code[pos] == RJUMPV {
+        let max_index = *code.get(pos + 1).ok_or(EofError::TruncatedImmediate(pos))?;
+        1 + (usize::from(max_index) + 1) * 2
+    } else {
+        usize::from(info.immediate)
+    };
+    if pos + size >= code.len() {
+        return Err(EofError::TruncatedImmediate(pos));
+    }
+    Ok(size)
+}
+
+#[derive(Clone, Copy)]
+struct Instruction {
+    pops: u8,
+    pushes: u8,
+    immediate: u8,
+    terminating: bool,
+}
+
+const fn op(pops: u8, pushes: u8) -> Option<Instruction> {
+    Some(Instruction {
+        pops,
+        pushes,
+        immediate: 0,
+        terminating: false,
+    })
+}
+
+const fn with_immediate(pops: u8, pushes: u8, immediate: u8) -> Option<Instruction> {
+    Some(Instruction {
+        pops,
+        pushes,
+        immediate,
+        terminating: false,
+    })
+}
+
+const fn terminating(pops: u8, immediate: u8) -> Option<Instruction> {
+    Some(Instruction {
+        pops,
+        pushes: 0,
+        immediate,
+        terminating: true,
+    })
+}
+
+/// Stack effect and immediate size of the instructions valid in EOF code.
+/// Legacy instructions that inspect code or gas, JUMP/JUMPI/PC, the legacy
+/// calls and creates, CALLCODE and SELFDESTRUCT are undefined.
+///
+/// CALLF, JUMPF, RETF, DUPN, SWAPN and EXCHANGE have their stack effect
+/// computed from their immediate or target section during stack validation,
+/// and RJUMPV's immediate size from its first immediate byte.
+fn instruction(opcode: u8) -> Option<Instruction> {
+    match opcode {
+        STOP => terminating(0, 0),
+        // ADD..SIGNEXTEND
+        0x01..=0x07 | 0x0a | 0x0b => op(2, 1),
+        // ADDMOD, MULMOD
+        0x08 | 0x09 => op(3, 1),
+        // LT..EQ
+        0x10..=0x14 => op(2, 1),
+        // ISZERO
+        0x15 => op(1, 1),
+        // AND, OR, XOR
+        0x16..=0x18 => op(2, 1),
+        // NOT
+        0x19 => op(1, 1),
+        // BYTE, SHL, SHR, SAR, KECCAK256
+        0x1a..=0x1d | 0x20 => op(2, 1),
+        // ADDRESS, ORIGIN, CALLER, CALLVALUE, CALLDATASIZE, GASPRICE, RETURNDATASIZE
+        0x30 | 0x32..=0x34 | 0x36 | 0x3a | 0x3d => op(0, 1),
+        // BALANCE, CALLDATALOAD
+        0x31 | 0x35 => op(1, 1),
+        // CALLDATACOPY, RETURNDATACOPY
+        0x37 | 0x3e => op(3, 0),
+        // BLOCKHASH
+        0x40 => op(1, 1),
+        // COINBASE..BASEFEE
+        0x41..=0x48 => op(0, 1),
+        // BLOBHASH
+        0x49 => op(1, 1),
+        // BLOBBASEFEE
+        0x4a => op(0, 1),
+        // POP
+        0x50 => op(1, 0),
+        // MLOAD, SLOAD, TLOAD
+        0x51 | 0x54 | 0x5c => op(1, 1),
+        // MSTORE, MSTORE8, SSTORE, TSTORE
+        0x52 | 0x53 | 0x55 | 0x5d => op(2, 0),
+        // MSIZE, PUSH0
+        0x59 | 0x5f => op(0, 1),
+        // NOP (JUMPDEST)
+        0x5b => op(0, 0),
+        // MCOPY
+        0x5e => op(3, 0),
+        PUSH1..=PUSH32 => with_immediate(0, 1, opcode - PUSH1 + 1),
+        DUP1..=DUP16 => op(opcode - DUP1 + 1, opcode - DUP1 + 2),
+        SWAP1..=SWAP16 => op(opcode - SWAP1 + 2, opcode - SWAP1 + 2),
+        LOG0..=LOG4 => op(opcode - LOG0 + 2, 0),
+        // DATALOAD
+        0xd0 => op(1, 1),
+        DATALOADN => with_immediate(0, 1, 2),
+        // DATASIZE
+        0xd2 => op(0, 1),
+        // DATACOPY
+        0xd3 => op(3, 0),
+        RJUMP => terminating(0, 2),
+        RJUMPI => with_immediate(1, 0, 2),
+        RJUMPV => with_immediate(1, 0, 0),
+        CALLF => with_immediate(0, 0, 2),
+        RETF => terminating(0, 0),
+        JUMPF => terminating(0, 2),
+        DUPN | SWAPN | EXCHANGE => with_immediate(0, 0, 1),
+        EOFCREATE => with_immediate(4, 1, 1),
+        RETURNCONTRACT => terminating(2, 1),
+        RETURN | REVERT => terminating(2, 0),
+        // RETURNDATALOAD
+        0xf7 => op(1, 1),
+        // EXTCALL
+        0xf8 => op(4, 1),
+        // EXTDELEGATECALL, EXTSTATICCALL
+        0xf9 | 0xfb => op(3, 1),
+        INVALID => terminating(0, 0),
+        _ => None,
+    }
+}
+
+/// Bounds-checked cursor over the container bytes.
+struct Reader<'a> {
+    bytes: &'a Bytes,
+    pos: usize,
+    /// Error returned when reading past the end.
+    error: EofError,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a Bytes, error: EofError) -> Self {
+        Self {
+            bytes,
+            pos: 0,
+            error,
+        }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], EofError> {
+        let bytes = self
+            .bytes
+            .get(self.pos..self.pos + len)
+            .ok_or_else(|| self.error.clone())?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn slice(&mut self, len: usize) -> Result<Bytes, EofError> {
+        let start = self.pos;
+        self.take(len)?;
+        Ok(self.bytes.slice(start..self.pos))
+    }
+
+    fn u8(&mut self) -> Result<u8, EofError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, EofError> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Result<u32, EofError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Reads a section count followed by that many non-zero sizes.
+    fn section_sizes(
+        &mut self,
+        max_count: usize,
+        too_many: EofError,
+        read_size: impl Fn(&mut Self) -> Result<usize, EofError>,
+    ) -> Result<Vec<usize>, EofError> {
+        let count = usize::from(self.u16()?);
+        if count == 0 {
+            return Err(EofError::ZeroSectionSize);
+        }
+        if count > max_count {
+            return Err(too_many);
+        }
+        let sizes = (0..count)
+            .map(|_| read_size(self))
+            .collect::<Result<Vec<_>, _>>()?;
+        if sizes.contains(&0) {
+            return Err(EofError::ZeroSectionSize);
+        }
+        Ok(sizes)
+    }
+}
+
+/// Lazily parsed EOF container of a [`Code`](super::Code).
+///
+/// It's derived from the bytecode, so it's ignored when comparing, hashing
+/// and serializing code.
+#[derive(Clone, Default)]
+pub struct EofCache(OnceLock<Option<Result<Arc<EofContainer>, EofError>>>);
+
+impl EofCache {
+    pub(crate) fn get_or_parse(
+        &self,
+        bytecode: &Bytes,
+    ) -> Option<Result<&EofContainer, &EofError>> {
+        self.0
+            .get_or_init(|| {
+                bytecode.starts_with(&EOF_MAGIC).then(|| {
+                    EofContainer::from_bytes(bytecode, ContainerKind::Runtime).map(Arc::new)
+                })
+            })
+            .as_ref()
+            .map(|result| result.as_ref().map(|container| &**container))
+    }
+}
+
+impl fmt::Debug for EofCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("EofCache").finish_non_exhaustive()
+    }
+}
+
+impl PartialEq for EofCache {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl Eq for EofCache {}
+
+impl Hash for EofCache {
+    fn hash<H: Hasher>(&self, _state: &mut H) {}
+}
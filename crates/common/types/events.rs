@@ -0,0 +1,421 @@
+//! Typed decoding of the events of the protocol contracts.
+//!
+//! Each event is declared once as an [`EventDescriptor`], holding its topic and parameters,
+//! so that code extracting deposit requests or L2 messages from receipts shares the same
+//! topic hashes. Descriptors only match logs with the exact number of topics of the event
+//! and well-formed data, so near-misses such as a different event with the same name are
+//! not decoded.
+
+use bytes::Bytes;
+use ethereum_types::{Address, H256, U256};
+use hex_literal::hex;
+
+use super::{Log, Receipt, requests::Deposit};
+
+/// ABI type of an event parameter, only the types used by the protocol events.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventParamType {
+    Address,
+    Uint256,
+    Bytes32,
+    /// Dynamic `bytes`, never indexed by the protocol events.
+    Bytes,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventParam {
+    pub name: &'static str,
+    pub ty: EventParamType,
+    pub indexed: bool,
+}
+
+/// Decoded value of an event parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EventValue {
+    Address(Address),
+    Uint256(U256),
+    Bytes32(H256),
+    Bytes(Bytes),
+}
+
+/// An event of a protocol contract. `topic` is the hash of `signature`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventDescriptor {
+    pub name: &'static str,
+    pub signature: &'static str,
+    pub topic: H256,
+    pub params: &'static [EventParam],
+}
+
+const fn param(name: &'static str, ty: EventParamType, indexed: bool) -> EventParam {
+    EventParam { name, ty, indexed }
+}
+
+/// `DepositEvent` of the beacon chain deposit contract, the source of EIP-6110 deposit
+/// requests.
+pub const DEPOSIT_EVENT: EventDescriptor = EventDescriptor {
+    name: "DepositEvent",
+    signature: "DepositEvent(bytes,bytes,bytes,bytes,bytes)",
+    topic: H256(hex!(
+        "649bbc62d0e31342afea4e5cd82d4049e7e1ee912fc0889aa790803be39038c5"
+    )),
+    params: &[
+        param("pubkey", EventParamType::Bytes, false),
+        param("withdrawal_credentials", EventParamType::Bytes, false),
+        param("amount", EventParamType::Bytes, false),
+        param("signature", EventParamType::Bytes, false),
+        param("index", EventParamType::Bytes, false),
+    ],
+};
+
+/// `L1Message` of the L2 messenger, a message to the L1 such as a withdrawal.
+pub const L1_MESSAGE_EVENT: EventDescriptor = EventDescriptor {
+    name: "L1Message",
+    signature: "L1Message(address,bytes32,uint256)",
+    topic: H256(hex!(
+        "18d7b705344d616d1b61daa6a8ccfcf9f10c27ade007cc45cf870d1e121f1a9d"
+    )),
+    params: &[
+        param("senderOnL2", EventParamType::Address, true),
+        param("data", EventParamType::Bytes32, true),
+        param("messageId", EventParamType::Uint256, true),
+    ],
+};
+
+/// `L2Message` of the L2 messenger, a message to another L2.
+pub const L2_MESSAGE_EVENT: EventDescriptor = EventDescriptor {
+    name: "L2Message",
+    signature: "L2Message(uint256,address,address,uint256,uint256,uint256,bytes)",
+    topic: H256(hex!(
+        "3ef10406a64e753aa81a376641c320b01c700331082f9444a84f437ac1917795"
+    )),
+    params: &[
+        param("chainId", EventParamType::Uint256, true),
+        param("from", EventParamType::Address, false),
+        param("to", EventParamType::Address, false),
+        param("value", EventParamType::Uint256, false),
+        param("gasLimit", EventParamType::Uint256, false),
+        param("txId", EventParamType::Uint256, false),
+        param("data", EventParamType::Bytes, false),
+    ],
+};
+
+/// `WithdrawalInitiated` of the L2 bridge, emitted along the `L1Message` of a withdrawal.
+pub const WITHDRAWAL_INITIATED_EVENT: EventDescriptor = EventDescriptor {
+    name: "WithdrawalInitiated",
+    signature: "WithdrawalInitiated(address,address,uint256)",
+    topic: H256(hex!(
+        "bb2689ff876f7ef453cf8865dde5ab10349d222e2e1383c5152fbdb083f02da2"
+    )),
+    params: &[
+        param("senderOnL2", EventParamType::Address, true),
+        param("receiverOnL1", EventParamType::Address, true),
+        param("amount", EventParamType::Uint256, true),
+    ],
+};
+
+/// `PrivilegedTxSent` of the L1 bridge, a transaction to include in the L2.
+pub const PRIVILEGED_TX_SENT_EVENT: EventDescriptor = EventDescriptor {
+    name: "PrivilegedTxSent",
+    signature: "PrivilegedTxSent(address,address,address,uint256,uint256,uint256,bytes)",
+    topic: H256(hex!(
+        "7d76dd36798b00b9c38def780dc4741f49a0f441afba4260388a8f5634eac186"
+    )),
+    params: &[
+        param("l1From", EventParamType::Address, true),
+        param("from", EventParamType::Address, false),
+        param("to", EventParamType::Address, false),
+        param("transactionId", EventParamType::Uint256, false),
+        param("value", EventParamType::Uint256, false),
+        param("gasLimit", EventParamType::Uint256, false),
+        param("data", EventParamType::Bytes, false),
+    ],
+};
+
+/// Every event decoded by [`decode_known_events`].
+pub const KNOWN_EVENTS: [&EventDescriptor; 5] = [
+    &DEPOSIT_EVENT,
+    &L1_MESSAGE_EVENT,
+    &L2_MESSAGE_EVENT,
+    &WITHDRAWAL_INITIATED_EVENT,
+    &PRIVILEGED_TX_SENT_EVENT,
+];
+
+impl EventDescriptor {
+    fn indexed_params(&self) -> usize {
+        self.params.iter().filter(|param| param.indexed).count()
+    }
+
+    /// Whether `log` has the topic of the event and one topic per indexed parameter.
+    pub fn matches(&self, log: &Log) -> bool {
+        log.topics.first() == Some(&self.topic)
+            && log.topics.len() == self.indexed_params().saturating_add(1)
+    }
+
+    /// Decodes the parameters of the event from `log`, in declaration order. `None` if the
+    /// log is not of this event or its data is malformed.
+    pub fn decode(&self, log: &Log) -> Option<Vec<EventValue>> {
+        if !self.matches(log) {
+            return None;
+        }
+        let mut topics = log.topics.iter().skip(1);
+        let mut head: usize = 0;
+        let mut values = Vec::with_capacity(self.params.len());
+        for param in self.params {
+            let value = if param.indexed {
+                decode_word(param.ty, topics.next()?)?
+            } else {
+                let word = data_word(&log.data, head)?;
+                head = head.checked_add(32)?;
+                match param.ty {
+                    EventParamType::Bytes => {
+                        EventValue::Bytes(decode_dynamic_bytes(&log.data, &word)?)
+                    }
+                    ty => decode_word(ty, &word)?,
+                }
+            };
+            values.push(value);
+        }
+        Some(values)
+    }
+}
+
+fn data_word(data: &[u8], offset: usize) -> Option<H256> {
+    data.get(offset..offset.checked_add(32)?)
+        .map(H256::from_slice)
+}
+
+/// Decodes a static type from its 32-byte word, rejecting addresses with dirty padding.
+fn decode_word(ty: EventParamType, word: &H256) -> Option<EventValue> {
+    match ty {
+        EventParamType::Address => {
+            let (padding, address) = word.as_bytes().split_at(12);
+            padding
+                .iter()
+                .all(|byte| *byte == 0)
+                .then(|| EventValue::Address(Address::from_slice(address)))
+        }
+        EventParamType::Uint256 => {
+            Some(EventValue::Uint256(U256::from_big_endian(word.as_bytes())))
+        }
+        EventParamType::Bytes32 => Some(EventValue::Bytes32(*word)),
+        // Indexed dynamic values are hashed, their value can't be recovered
+        EventParamType::Bytes => None,
+    }
+}
+
+/// Decodes the `bytes` whose offset into `data` is `offset`.
+fn decode_dynamic_bytes(data: &[u8], offset: &H256) -> Option<Bytes> {
+    let offset = word_to_usize(offset, data.len())?;
+    let len = word_to_usize(&data_word(data, offset)?, data.len())?;
+    let start = offset.checked_add(32)?;
+    data.get(start..start.checked_add(len)?)
+        .map(Bytes::copy_from_slice)
+}
+
+/// Reads a word as an offset or length into data of `max` bytes.
+fn word_to_usize(word: &H256, max: usize) -> Option<usize> {
+    let value = U256::from_big_endian(word.as_bytes());
+    if value > U256::from(max) {
+        return None;
+    }
+    usize::try_from(value.low_u64()).ok()
+}
+
+/// A message to the L1, see [`L1_MESSAGE_EVENT`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct L1MessageEvent {
+    pub from: Address,
+    pub data_hash: H256,
+    pub message_id: U256,
+}
+
+impl L1MessageEvent {
+    pub fn from_log(log: &Log) -> Option<Self> {
+        let values = L1_MESSAGE_EVENT.decode(log)?;
+        let [
+            EventValue::Address(from),
+            EventValue::Bytes32(data_hash),
+            EventValue::Uint256(message_id),
+        ] = values.as_slice()
+        else {
+            return None;
+        };
+        Some(Self {
+            from: *from,
+            data_hash: *data_hash,
+            message_id: *message_id,
+        })
+    }
+}
+
+/// A message to another L2, see [`L2_MESSAGE_EVENT`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct L2MessageEvent {
+    pub dest_chain_id: U256,
+    pub from: Address,
+    pub to: Address,
+    pub value: U256,
+    pub gas_limit: U256,
+    pub tx_id: U256,
+    pub data: Bytes,
+}
+
+impl L2MessageEvent {
+    pub fn from_log(log: &Log) -> Option<Self> {
+        let values = L2_MESSAGE_EVENT.decode(log)?;
+        let [
+            EventValue::Uint256(dest_chain_id),
+            EventValue::Address(from),
+            EventValue::Address(to),
+            EventValue::Uint256(value),
+            EventValue::Uint256(gas_limit),
+            EventValue::Uint256(tx_id),
+            EventValue::Bytes(data),
+        ] = values.as_slice()
+        else {
+            return None;
+        };
+        Some(Self {
+            dest_chain_id: *dest_chain_id,
+            from: *from,
+            to: *to,
+            value: *value,
+            gas_limit: *gas_limit,
+            tx_id: *tx_id,
+            data: data.clone(),
+        })
+    }
+}
+
+/// A withdrawal from the L2 bridge, see [`WITHDRAWAL_INITIATED_EVENT`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WithdrawalInitiatedEvent {
+    pub sender: Address,
+    pub receiver: Address,
+    pub amount: U256,
+}
+
+impl WithdrawalInitiatedEvent {
+    pub fn from_log(log: &Log) -> Option<Self> {
+        let values = WITHDRAWAL_INITIATED_EVENT.decode(log)?;
+        let [
+            EventValue::Address(sender),
+            EventValue::Address(receiver),
+            EventValue::Uint256(amount),
+        ] = values.as_slice()
+        else {
+            return None;
+        };
+        Some(Self {
+            sender: *sender,
+            receiver: *receiver,
+            amount: *amount,
+        })
+    }
+}
+
+/// A transaction sent to the L2 through the L1 bridge, see [`PRIVILEGED_TX_SENT_EVENT`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrivilegedTxSentEvent {
+    pub l1_from: Address,
+    pub from: Address,
+    pub to: Address,
+    pub transaction_id: U256,
+    pub value: U256,
+    pub gas_limit: U256,
+    pub data: Bytes,
+}
+
+impl PrivilegedTxSentEvent {
+    pub fn from_log(log: &Log) -> Option<Self> {
+        let values = PRIVILEGED_TX_SENT_EVENT.decode(log)?;
+        let [
+            EventValue::Address(l1_from),
+            EventValue::Address(from),
+            EventValue::Address(to),
+            EventValue::Uint256(transaction_id),
+            EventValue::Uint256(value),
+            EventValue::Uint256(gas_limit),
+            EventValue::Bytes(data),
+        ] = values.as_slice()
+        else {
+            return None;
+        };
+        Some(Self {
+            l1_from: *l1_from,
+            from: *from,
+            to: *to,
+            transaction_id: *transaction_id,
+            value: *value,
+            gas_limit: *gas_limit,
+            data: data.clone(),
+        })
+    }
+}
+
+impl Deposit {
+    /// Decodes a `DepositEvent`, with the strict layout consensus requires of deposit
+    /// requests.
+    pub fn from_log(log: &Log) -> Option<Self> {
+        if !DEPOSIT_EVENT.matches(log) {
+            return None;
+        }
+        Deposit::from_abi_byte_array(&log.data)
+    }
+}
+
+/// A protocol event decoded from a log.
+#[derive(Debug, Clone)]
+pub enum ProtocolEvent {
+    Deposit(Deposit),
+    L1Message(L1MessageEvent),
+    L2Message(L2MessageEvent),
+    WithdrawalInitiated(WithdrawalInitiatedEvent),
+    PrivilegedTxSent(PrivilegedTxSentEvent),
+}
+
+impl ProtocolEvent {
+    /// Decodes `log` as any of the [`KNOWN_EVENTS`].
+    pub fn from_log(log: &Log) -> Option<Self> {
+        let topic = log.topics.first()?;
+        if *topic == DEPOSIT_EVENT.topic {
+            Deposit::from_log(log).map(ProtocolEvent::Deposit)
+        } else if *topic == L1_MESSAGE_EVENT.topic {
+            L1MessageEvent::from_log(log).map(ProtocolEvent::L1Message)
+        } else if *topic == L2_MESSAGE_EVENT.topic {
+            L2MessageEvent::from_log(log).map(ProtocolEvent::L2Message)
+        } else if *topic == WITHDRAWAL_INITIATED_EVENT.topic {
+            WithdrawalInitiatedEvent::from_log(log).map(ProtocolEvent::WithdrawalInitiated)
+        } else if *topic == PRIVILEGED_TX_SENT_EVENT.topic {
+            PrivilegedTxSentEvent::from_log(log).map(ProtocolEvent::PrivilegedTxSent)
+        } else {
+            None
+        }
+    }
+}
+
+/// A protocol event along the contract that emitted it. Events are only meaningful when
+/// emitted by their contract, which callers must check.
+#[derive(Debug, Clone)]
+pub struct KnownEvent {
+    pub emitter: Address,
+    pub log_index: usize,
+    pub event: ProtocolEvent,
+}
+
+/// Decodes the protocol events among the logs of `receipt`, in log order.
+pub fn decode_known_events(receipt: &Receipt) -> Vec<KnownEvent> {
+    receipt
+        .logs
+        .iter()
+        .enumerate()
+        .filter_map(|(log_index, log)| {
+            Some(KnownEvent {
+                emitter: log.address,
+                log_index,
+                event: ProtocolEvent::from_log(log)?,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,303 @@
+use ethereum_types::U256;
+use serde::{Deserialize, Serialize};
+
+use crate::constants::GAS_PER_BLOB;
+
+use super::{
+    BlockHeader, ChainConfig, ELASTICITY_MULTIPLIER, INITIAL_BASE_FEE, blobs::next_blob_base_fee,
+    calc_excess_blob_gas, calculate_base_fee_per_gas,
+};
+
+/// Seconds between blocks assumed when projecting future block timestamps.
+pub const DEFAULT_FORECAST_BLOCK_TIME: u64 = 12;
+
+/// Projected fees for a single future block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeeProjection {
+    pub block_number: u64,
+    pub timestamp: u64,
+    pub base_fee_per_gas: u64,
+    /// `None` when blobs are not enabled at the projected timestamp.
+    pub base_fee_per_blob_gas: Option<U256>,
+}
+
+/// Projects EIP-1559 base fees and EIP-4844 blob base fees of upcoming blocks.
+///
+/// All computations go through the same functions used to validate block
+/// headers ([`calculate_base_fee_per_gas`], [`calc_excess_blob_gas`]) and
+/// blob transactions ([`next_blob_base_fee`]), so forecasts always match what
+/// consensus will accept for the given parent.
+#[derive(Debug, Clone, Copy)]
+pub struct FeeForecaster {
+    chain_config: ChainConfig,
+    elasticity_multiplier: u64,
+    block_time: u64,
+}
+
+impl FeeForecaster {
+    pub fn new(chain_config: ChainConfig) -> Self {
+        Self {
+            chain_config,
+            elasticity_multiplier: ELASTICITY_MULTIPLIER,
+            block_time: DEFAULT_FORECAST_BLOCK_TIME,
+        }
+    }
+
+    /// Overrides the EIP-1559 elasticity multiplier (L2s may configure their own).
+    pub fn with_elasticity_multiplier(mut self, elasticity_multiplier: u64) -> Self {
+        self.elasticity_multiplier = elasticity_multiplier;
+        self
+    }
+
+    /// Overrides the block time used to project future timestamps.
+    pub fn with_block_time(mut self, block_time: u64) -> Self {
+        self.block_time = block_time;
+        self
+    }
+
+    /// Base fee of the block following `parent`, assuming it keeps the parent's gas limit.
+    ///
+    /// Falls back to the parent's base fee if the parent gas limit is below
+    /// the protocol minimum, in which case no valid child exists.
+    pub fn next_base_fee(&self, parent: &BlockHeader) -> u64 {
+        let parent_base_fee = parent.base_fee_per_gas.unwrap_or(INITIAL_BASE_FEE);
+        calculate_base_fee_per_gas(
+            parent.gas_limit,
+            parent.gas_limit,
+            parent.gas_used,
+            parent_base_fee,
+            self.elasticity_multiplier,
+        )
+        .unwrap_or(parent_base_fee)
+    }
+
+    /// Blob base fee of the block following `parent`, or `None` if blobs are
+    /// not enabled for it.
+    pub fn next_blob_base_fee(&self, parent: &BlockHeader) -> Option<U256> {
+        next_blob_base_fee(parent, self.next_timestamp(parent), &self.chain_config)
+    }
+
+    /// Excess blob gas of the block following `parent`, or `None` if blobs
+    /// are not enabled for it.
+    pub fn next_excess_blob_gas(&self, parent: &BlockHeader) -> Option<u64> {
+        let timestamp = self.next_timestamp(parent);
+        let schedule = self.chain_config.get_fork_blob_schedule(timestamp)?;
+        Some(calc_excess_blob_gas(
+            parent,
+            schedule,
+            self.chain_config.fork(timestamp),
+        ))
+    }
+
+    /// Projects fees for the next `n_blocks` blocks after `parent`.
+    ///
+    /// Every projected block is assumed to use `assumed_fullness` (clamped to
+    /// `0.0..=1.0`) of its gas limit and of its fork's maximum blob count.
+    pub fn project(
+        &self,
+        parent: &BlockHeader,
+        n_blocks: usize,
+        assumed_fullness: f64,
+    ) -> Vec<FeeProjection> {
+        let fullness = assumed_fullness.clamp(0.0, 1.0);
+        let mut projections = Vec::with_capacity(n_blocks);
+        let mut parent = parent.clone();
+
+        for _ in 0..n_blocks {
+            let timestamp = self.next_timestamp(&parent);
+            let base_fee_per_gas = self.next_base_fee(&parent);
+            let schedule = self.chain_config.get_fork_blob_schedule(timestamp);
+            let excess_blob_gas = self.next_excess_blob_gas(&parent);
+            let base_fee_per_blob_gas = self.next_blob_base_fee(&parent);
+            let blob_gas_used = schedule.map(|schedule| {
+                (f64::from(schedule.max) * fullness).round() as u64 * u64::from(GAS_PER_BLOB)
+            });
+
+            projections.push(FeeProjection {
+                block_number: parent.number + 1,
+                timestamp,
+                base_fee_per_gas,
+                base_fee_per_blob_gas,
+            });
+
+            parent = BlockHeader {
+                number: parent.number + 1,
+                timestamp,
+                gas_limit: parent.gas_limit,
+                gas_used: (parent.gas_limit as f64 * fullness) as u64,
+                base_fee_per_gas: Some(base_fee_per_gas),
+                excess_blob_gas,
+                blob_gas_used,
+                ..Default::default()
+            };
+        }
+
+        projections
+    }
+
+    /// Timestamp of the block following `parent`, one block time later.
+    pub fn next_timestamp(&self, parent: &BlockHeader) -> u64 {
+        parent.timestamp + self.block_time
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::types::{BlobSchedule, ForkBlobSchedule};
+
+    fn cancun_config() -> ChainConfig {
+        ChainConfig {
+            london_block: Some(0),
+            shanghai_time: Some(0),
+            cancun_time: Some(0),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn next_base_fee_london_activation() {
+        // Mainnet block 12965000 (London activation) -> 12965001
+        let parent = BlockHeader {
+            number: 12965000,
+            gas_limit: 30029122,
+            gas_used: 30025257,
+            base_fee_per_gas: Some(1000000000),
+            ..Default::default()
+        };
+        let forecaster = FeeForecaster::new(cancun_config());
+        assert_eq!(forecaster.next_base_fee(&parent), 1124967822);
+    }
+
+    #[test]
+    fn next_base_fee_matches_sepolia_block() {
+        // Sepolia block 6029872, see test_calculate_base_fee_per_gas_big_numbers
+        let parent = BlockHeader {
+            gas_limit: 30000000,
+            gas_used: 1981764,
+            base_fee_per_gas: Some(1478077008012),
+            ..Default::default()
+        };
+        let forecaster = FeeForecaster::new(cancun_config());
+        assert_eq!(forecaster.next_base_fee(&parent), 1317727380375);
+    }
+
+    #[test]
+    fn next_blob_base_fee_before_cancun_is_none() {
+        let config = ChainConfig {
+            london_block: Some(0),
+            shanghai_time: Some(0),
+            cancun_time: Some(1000),
+            ..Default::default()
+        };
+        let parent = BlockHeader {
+            timestamp: 100,
+            ..Default::default()
+        };
+        let forecaster = FeeForecaster::new(config);
+        assert_eq!(forecaster.next_blob_base_fee(&parent), None);
+    }
+
+    #[test]
+    fn next_blob_base_fee_post_osaka_bpo1() {
+        // Same parent as test_calc_blob_fee_post_osaka_bpo1, excess becomes 5617366
+        let config = ChainConfig {
+            london_block: Some(0),
+            shanghai_time: Some(0),
+            cancun_time: Some(0),
+            prague_time: Some(0),
+            osaka_time: Some(0),
+            bpo1_time: Some(0),
+            blob_schedule: BlobSchedule {
+                bpo1: ForkBlobSchedule {
+                    target: 9,
+                    max: 14,
+                    base_fee_update_fraction: 8832827,
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let parent = BlockHeader {
+            excess_blob_gas: Some(5149252),
+            blob_gas_used: Some(1310720),
+            base_fee_per_gas: Some(30),
+            ..Default::default()
+        };
+        let forecaster = FeeForecaster::new(config);
+        assert_eq!(forecaster.next_blob_base_fee(&parent), Some(U256::from(1)));
+    }
+
+    #[test]
+    fn next_blob_base_fee_cancun_high_excess() {
+        let parent = BlockHeader {
+            excess_blob_gas: Some(10_000_000),
+            blob_gas_used: Some(786432),
+            base_fee_per_gas: Some(10_000_000_000),
+            ..Default::default()
+        };
+        let forecaster = FeeForecaster::new(cancun_config());
+        // excess = 10_000_000 + 786432 - 393216 = 10393216
+        // fake_exponential(1, 10393216, 3338477) = 22
+        assert_eq!(forecaster.next_blob_base_fee(&parent), Some(U256::from(22)));
+    }
+
+    #[test]
+    fn project_at_target_keeps_base_fee() {
+        let parent = BlockHeader {
+            number: 10,
+            timestamp: 120,
+            gas_limit: 30_000_000,
+            gas_used: 15_000_000,
+            base_fee_per_gas: Some(7_000_000_000),
+            excess_blob_gas: Some(0),
+            blob_gas_used: Some(0),
+            ..Default::default()
+        };
+        let forecaster = FeeForecaster::new(cancun_config());
+        let projections = forecaster.project(&parent, 3, 0.5);
+        assert_eq!(projections.len(), 3);
+        for (i, projection) in projections.iter().enumerate() {
+            assert_eq!(projection.block_number, 11 + i as u64);
+            assert_eq!(projection.timestamp, 120 + 12 * (i as u64 + 1));
+            assert_eq!(projection.base_fee_per_gas, 7_000_000_000);
+            assert_eq!(projection.base_fee_per_blob_gas, Some(U256::from(1)));
+        }
+    }
+
+    #[test]
+    fn project_full_blocks_compounds_increases() {
+        let parent = BlockHeader {
+            gas_limit: 30_000_000,
+            gas_used: 30_000_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            excess_blob_gas: Some(0),
+            blob_gas_used: Some(0),
+            ..Default::default()
+        };
+        let forecaster = FeeForecaster::new(cancun_config());
+        let fees: Vec<u64> = forecaster
+            .project(&parent, 3, 1.0)
+            .iter()
+            .map(|p| p.base_fee_per_gas)
+            .collect();
+        assert_eq!(fees, vec![1_125_000_000, 1_265_625_000, 1_423_828_125]);
+    }
+
+    #[test]
+    fn project_empty_blocks_compounds_decreases() {
+        let parent = BlockHeader {
+            gas_limit: 30_000_000,
+            gas_used: 0,
+            base_fee_per_gas: Some(1_000_000_000),
+            ..Default::default()
+        };
+        let forecaster = FeeForecaster::new(cancun_config()).with_elasticity_multiplier(2);
+        let fees: Vec<u64> = forecaster
+            .project(&parent, 2, 0.0)
+            .iter()
+            .map(|p| p.base_fee_per_gas)
+            .collect();
+        assert_eq!(fees, vec![875_000_000, 765_625_000]);
+    }
+}
@@ -17,7 +17,11 @@ use super::{
     compute_receipts_root, compute_transactions_root, compute_withdrawals_root,
 };
 use crate::{
-    constants::{DEFAULT_OMMERS_HASH, DEFAULT_REQUESTS_HASH, EMPTY_BLOCK_ACCESS_LIST_HASH},
+    constants::{
+        DEFAULT_BEACON_ROOTS_ADDRESS, DEFAULT_CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS,
+        DEFAULT_HISTORY_STORAGE_ADDRESS, DEFAULT_OMMERS_HASH, DEFAULT_REQUESTS_HASH,
+        DEFAULT_WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS, EMPTY_BLOCK_ACCESS_LIST_HASH,
+    },
     rkyv_utils,
 };
 
@@ -258,6 +262,10 @@ pub struct ChainConfig {
     pub bpo4_time: Option<u64>,
     pub bpo5_time: Option<u64>,
     pub amsterdam_time: Option<u64>,
+    /// Timestamp from which EOF containers can be deployed, on devnets
+    /// scheduling EOF independently of a named fork.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eof_time: Option<u64>,
 
     /// Amount of total difficulty reached by the network that triggers the consensus upgrade.
     pub terminal_total_difficulty: Option<u128>,
@@ -283,6 +291,178 @@ pub struct ChainConfig {
     /// Used to compute the scale factor: 10^(18 - l1_decimals).
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub native_token_l1_decimals: Option<u8>,
+
+    /// Addresses of the contracts invoked through system calls.
+    /// Omitted from serialization when they match the mainnet defaults.
+    #[serde(default, skip_serializing_if = "SystemContractsConfig::is_default")]
+    pub system_contracts: SystemContractsConfig,
+}
+
+/// Addresses of the system contracts called at the start of a block
+/// (EIP-4788, EIP-2935) and when collecting requests (EIP-7002, EIP-7251).
+///
+/// A missing field falls back to the mainnet address, while an explicit
+/// `null` disables the corresponding system call.
+#[derive(
+    Clone, Copy, Debug, Serialize, Deserialize, PartialEq, RSerialize, RDeserialize, Archive,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemContractsConfig {
+    #[serde(default = "default_beacon_roots_address")]
+    #[rkyv(with = rkyv_utils::OptionH160Wrapper)]
+    pub beacon_roots: Option<Address>,
+    #[serde(default = "default_history_storage_address")]
+    #[rkyv(with = rkyv_utils::OptionH160Wrapper)]
+    pub history_storage: Option<Address>,
+    #[serde(default = "default_withdrawal_request_address")]
+    #[rkyv(with = rkyv_utils::OptionH160Wrapper)]
+    pub withdrawal_request: Option<Address>,
+    #[serde(default = "default_consolidation_request_address")]
+    #[rkyv(with = rkyv_utils::OptionH160Wrapper)]
+    pub consolidation_request: Option<Address>,
+    /// Chain-specific system calls, run in order after the protocol ones.
+    #[serde(default, skip_serializing_if = "ExtraSystemCalls::is_empty")]
+    pub extra: ExtraSystemCalls,
+}
+
+/// Maximum number of extra system calls a chain config can define.
+pub const MAX_EXTRA_SYSTEM_CALLS: usize = 4;
+
+/// A chain-specific system call, executed before the block's transactions
+/// with the same semantics as the protocol system calls.
+#[derive(
+    Clone, Copy, Debug, Serialize, Deserialize, PartialEq, RSerialize, RDeserialize, Archive,
+)]
+#[serde(rename_all = "camelCase")]
+pub struct ExtraSystemCallConfig {
+    #[rkyv(with = rkyv_utils::H160Wrapper)]
+    pub address: Address,
+    /// Timestamp of the first block the call runs in, 0 to run from genesis.
+    #[serde(default)]
+    pub activation_time: u64,
+    #[serde(default)]
+    pub input: SystemCallInput,
+}
+
+/// Calldata of an extra system call, taken from the header of the block
+/// being executed. Values are passed as a single 32-byte word.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    Eq,
+    RSerialize,
+    RDeserialize,
+    Archive,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum SystemCallInput {
+    #[default]
+    Empty,
+    ParentHash,
+    ParentBeaconBlockRoot,
+    BlockNumber,
+    Timestamp,
+}
+
+/// Ordered extra system calls of a chain. They are held inline, so that
+/// [`ChainConfig`] stays `Copy`, and (de)serialized as a list.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Serialize,
+    Deserialize,
+    PartialEq,
+    RSerialize,
+    RDeserialize,
+    Archive,
+)]
+#[serde(
+    try_from = "Vec<ExtraSystemCallConfig>",
+    into = "Vec<ExtraSystemCallConfig>"
+)]
+pub struct ExtraSystemCalls {
+    calls: [Option<ExtraSystemCallConfig>; MAX_EXTRA_SYSTEM_CALLS],
+}
+
+impl ExtraSystemCalls {
+    pub fn iter(&self) -> impl Iterator<Item = &ExtraSystemCallConfig> {
+        self.calls.iter().flatten()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+}
+
+impl TryFrom<Vec<ExtraSystemCallConfig>> for ExtraSystemCalls {
+    type Error = String;
+
+    fn try_from(configs: Vec<ExtraSystemCallConfig>) -> Result<Self, Self::Error> {
+        if configs.len() > MAX_EXTRA_SYSTEM_CALLS {
+            return Err(format!(
+                "at most {MAX_EXTRA_SYSTEM_CALLS} extra system calls are supported, got {}",
+                configs.len()
+            ));
+        }
+        let mut calls = [None; MAX_EXTRA_SYSTEM_CALLS];
+        for (slot, config) in calls.iter_mut().zip(configs) {
+            *slot = Some(config);
+        }
+        Ok(Self { calls })
+    }
+}
+
+impl From<ExtraSystemCalls> for Vec<ExtraSystemCallConfig> {
+    fn from(extra: ExtraSystemCalls) -> Self {
+        extra.iter().copied().collect()
+    }
+}
+
+impl SystemContractsConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Returns true if `address` is one of the request predeploys (EIP-7002, EIP-7251),
+    /// whose empty code must invalidate the block.
+    pub fn is_request_predeploy(&self, address: Address) -> bool {
+        self.withdrawal_request == Some(address) || self.consolidation_request == Some(address)
+    }
+}
+
+impl Default for SystemContractsConfig {
+    fn default() -> Self {
+        SystemContractsConfig {
+            beacon_roots: default_beacon_roots_address(),
+            history_storage: default_history_storage_address(),
+            withdrawal_request: default_withdrawal_request_address(),
+            consolidation_request: default_consolidation_request_address(),
+            extra: ExtraSystemCalls::default(),
+        }
+    }
+}
+
+fn default_beacon_roots_address() -> Option<Address> {
+    Some(DEFAULT_BEACON_ROOTS_ADDRESS)
+}
+
+fn default_history_storage_address() -> Option<Address> {
+    Some(DEFAULT_HISTORY_STORAGE_ADDRESS)
+}
+
+fn default_withdrawal_request_address() -> Option<Address> {
+    Some(DEFAULT_WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS)
+}
+
+fn default_consolidation_request_address() -> Option<Address> {
+    Some(DEFAULT_CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS)
 }
 
 lazy_static::lazy_static! {
@@ -369,6 +549,10 @@ impl ChainConfig {
             .is_some_and(|time| time <= block_timestamp)
     }
 
+    pub fn is_eof_activated(&self, block_timestamp: u64) -> bool {
+        self.eof_time.is_some_and(|time| time <= block_timestamp)
+    }
+
     pub fn is_bpo5_activated(&self, block_timestamp: u64) -> bool {
         self.bpo5_time.is_some_and(|time| time <= block_timestamp)
     }
@@ -698,6 +882,7 @@ impl ChainConfig {
             self.bpo4_time,
             self.bpo5_time,
             self.amsterdam_time,
+            self.eof_time,
             self.verkle_time,
         ]
         .into_iter()
@@ -1318,4 +1503,152 @@ mod tests {
         assert!(!json.contains("nativeTokenL1Address"));
         assert!(!json.contains("nativeTokenL1Decimals"));
     }
+
+    #[test]
+    fn mainnet_genesis_uses_default_system_contracts() {
+        let file = File::open("../../cmd/ethrex/networks/mainnet/genesis.json")
+            .expect("Failed to open genesis file");
+        let reader = BufReader::new(file);
+        let genesis: Genesis =
+            serde_json::from_reader(reader).expect("Failed to deserialize genesis file");
+        let system_contracts = genesis.config.system_contracts;
+        assert!(system_contracts.is_default());
+        assert_eq!(
+            system_contracts.beacon_roots,
+            Some(H160::from_str("0x000f3df6d732807ef1319fb7b8bb8522d0beac02").unwrap())
+        );
+        assert_eq!(
+            system_contracts.history_storage,
+            Some(H160::from_str("0x0000f90827f1c53a10cb7a02335b175320002935").unwrap())
+        );
+        assert_eq!(
+            system_contracts.withdrawal_request,
+            Some(H160::from_str("0x00000961ef480eb55e80d19ad83579a64c007002").unwrap())
+        );
+        assert_eq!(
+            system_contracts.consolidation_request,
+            Some(H160::from_str("0x0000bbddc7ce488642fb579f8b00f3a590007251").unwrap())
+        );
+    }
+
+    #[test]
+    fn deserialize_chain_config_with_system_contracts() {
+        let json = r#"
+            {
+                "chainId": 123,
+                "depositContractAddress": "0x4242424242424242424242424242424242424242",
+                "systemContracts": {
+                    "beaconRoots": "0x1111111111111111111111111111111111111111",
+                    "historyStorage": null
+                }
+            }
+            "#;
+        let config: ChainConfig = serde_json::from_str(json).expect("Failed to deserialize");
+        let system_contracts = config.system_contracts;
+        assert_eq!(
+            system_contracts.beacon_roots,
+            Some(H160::from_str("0x1111111111111111111111111111111111111111").unwrap())
+        );
+        // Explicit null disables the system call
+        assert_eq!(system_contracts.history_storage, None);
+        // Missing fields keep the mainnet addresses
+        assert_eq!(
+            system_contracts.withdrawal_request,
+            SystemContractsConfig::default().withdrawal_request
+        );
+        assert_eq!(
+            system_contracts.consolidation_request,
+            SystemContractsConfig::default().consolidation_request
+        );
+    }
+
+    #[test]
+    fn serialize_chain_config_system_contracts_roundtrip() {
+        let config = ChainConfig {
+            chain_id: 1,
+            deposit_contract_address: H160::zero(),
+            ..Default::default()
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(!json.contains("systemContracts"));
+
+        let config = ChainConfig {
+            system_contracts: SystemContractsConfig {
+                withdrawal_request: None,
+                ..Default::default()
+            },
+            ..config
+        };
+        let json = serde_json::to_string(&config).unwrap();
+        assert!(json.contains("systemContracts"));
+        let decoded: ChainConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, config);
+    }
+
+    #[test]
+    fn deserialize_extra_system_calls() {
+        let json = r#"
+            {
+                "chainId": 123,
+                "depositContractAddress": "0x4242424242424242424242424242424242424242",
+                "systemContracts": {
+                    "extra": [
+                        { "address": "0x3333333333333333333333333333333333333333" },
+                        {
+                            "address": "0x4444444444444444444444444444444444444444",
+                            "activationTime": 100,
+                            "input": "parentHash"
+                        }
+                    ]
+                }
+            }
+            "#;
+        let config: ChainConfig = serde_json::from_str(json).expect("Failed to deserialize");
+        let extra: Vec<_> = config.system_contracts.extra.iter().copied().collect();
+        assert_eq!(
+            extra,
+            vec![
+                ExtraSystemCallConfig {
+                    address: H160::from_str("0x3333333333333333333333333333333333333333").unwrap(),
+                    activation_time: 0,
+                    input: SystemCallInput::Empty,
+                },
+                ExtraSystemCallConfig {
+                    address: H160::from_str("0x4444444444444444444444444444444444444444").unwrap(),
+                    activation_time: 100,
+                    input: SystemCallInput::ParentHash,
+                },
+            ]
+        );
+
+        let json = serde_json::to_string(&config).unwrap();
+        let decoded: ChainConfig = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, config);
+    }
+
+    #[test]
+    fn too_many_extra_system_calls_are_rejected() {
+        let calls = vec![
+            ExtraSystemCallConfig {
+                address: H160::zero(),
+                activation_time: 0,
+                input: SystemCallInput::Empty,
+            };
+            MAX_EXTRA_SYSTEM_CALLS + 1
+        ];
+        assert!(ExtraSystemCalls::try_from(calls).is_err());
+    }
+
+    #[test]
+    fn request_predeploys_follow_config() {
+        let custom = H160::from_str("0x2222222222222222222222222222222222222222").unwrap();
+        let config = SystemContractsConfig {
+            consolidation_request: Some(custom),
+            ..Default::default()
+        };
+        assert!(config.is_request_predeploy(custom));
+        assert!(config.is_request_predeploy(DEFAULT_WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS));
+        assert!(!config.is_request_predeploy(DEFAULT_CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS));
+        assert!(!config.is_request_predeploy(DEFAULT_BEACON_ROOTS_ADDRESS));
+    }
 }
@@ -1,13 +1,24 @@
+use std::fmt;
+
 use bytes::Bytes;
 use ethereum_types::Address;
 use rkyv::{Archive, Deserialize as RDeserialize, Serialize as RSerialize};
 use serde::{Deserialize, Serialize};
 
+use crate::constants::{GAS_PER_BLOB, SYSTEM_ADDRESS};
 use crate::rkyv_utils::{H160Wrapper, OptionH160Wrapper};
+use crate::types::ChainConfig;
+
+/// Highest address of the range holding the precompiles and the L2 system
+/// contracts, which can't be used as fee vaults.
+const MAX_RESERVED_ADDRESS: u64 = 0xffff;
 
+/// Unset fields default to no fee, and unknown fields are ignored so that
+/// configs written for newer versions still load.
 #[derive(
     Serialize, Deserialize, RDeserialize, RSerialize, Archive, Clone, Copy, Debug, Default,
 )]
+#[serde(default)]
 pub struct FeeConfig {
     /// If set, the base fee is sent to this address instead of being burned.
     #[rkyv(with=OptionH160Wrapper)]
@@ -33,9 +44,29 @@ pub struct OperatorFeeConfig {
 pub struct L1FeeConfig {
     #[rkyv(with=H160Wrapper)]
     pub l1_fee_vault: Address,
+    /// Kept up to date with L1 by the sequencer, so it may be left unset.
+    #[serde(default)]
     pub l1_fee_per_blob_gas: u64,
 }
 
+/// Fee vaults of a [`FeeConfig`], to tell which one is misconfigured.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeVault {
+    BaseFee,
+    OperatorFee,
+    L1Fee,
+}
+
+impl fmt::Display for FeeVault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FeeVault::BaseFee => write!(f, "base fee vault"),
+            FeeVault::OperatorFee => write!(f, "operator fee vault"),
+            FeeVault::L1Fee => write!(f, "L1 fee vault"),
+        }
+    }
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum FeeConfigError {
     #[error("Encoding error: {0}")]
@@ -46,6 +77,17 @@ pub enum FeeConfigError {
     InvalidFeeConfigType(u8),
     #[error("DecoderError error: {0}")]
     DecoderError(#[from] DecoderError),
+    #[error("The {0} is the zero address, leave it unset to disable the fee instead")]
+    ZeroAddressVault(FeeVault),
+    #[error(
+        "The {vault} {address:#x} is reserved for precompiles and system contracts, fees sent to it can't be recovered"
+    )]
+    ReservedAddressVault { vault: FeeVault, address: Address },
+    #[error(
+        "The L1 fee per blob gas {0} overflows when priced per blob, the maximum is {max}",
+        max = u64::MAX / u64::from(GAS_PER_BLOB)
+    )]
+    L1FeePerBlobGasTooHigh(u64),
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -86,6 +128,40 @@ impl FeeConfigType {
 }
 
 impl FeeConfig {
+    /// Checks the config can be charged on the chain with `chain_config`, so
+    /// that a misconfiguration fails when the config is loaded instead of
+    /// when executing transactions.
+    pub fn validate(&self, chain_config: &ChainConfig) -> Result<(), FeeConfigError> {
+        let vaults = [
+            self.base_fee_vault.map(|vault| (FeeVault::BaseFee, vault)),
+            self.operator_fee_config
+                .map(|config| (FeeVault::OperatorFee, config.operator_fee_vault)),
+            self.l1_fee_config
+                .map(|config| (FeeVault::L1Fee, config.l1_fee_vault)),
+        ];
+        for (vault, address) in vaults.into_iter().flatten() {
+            if address.is_zero() {
+                return Err(FeeConfigError::ZeroAddressVault(vault));
+            }
+            if is_reserved_address(address, chain_config) {
+                return Err(FeeConfigError::ReservedAddressVault { vault, address });
+            }
+        }
+
+        if let Some(l1_fee_config) = self.l1_fee_config
+            && l1_fee_config
+                .l1_fee_per_blob_gas
+                .checked_mul(GAS_PER_BLOB.into())
+                .is_none()
+        {
+            return Err(FeeConfigError::L1FeePerBlobGasTooHigh(
+                l1_fee_config.l1_fee_per_blob_gas,
+            ));
+        }
+
+        Ok(())
+    }
+
     pub fn to_vec(&self) -> Vec<u8> {
         let version = 0u8;
         let mut encoded: Vec<u8> = Vec::new();
@@ -178,6 +254,21 @@ impl FeeConfig {
     }
 }
 
+/// Whether `address` is a precompile, an L2 system contract or one of the
+/// system contracts of the chain.
+fn is_reserved_address(address: Address, chain_config: &ChainConfig) -> bool {
+    let system_contracts = chain_config.system_contracts;
+    address <= Address::from_low_u64_be(MAX_RESERVED_ADDRESS)
+        || address == SYSTEM_ADDRESS
+        || [
+            system_contracts.beacon_roots,
+            system_contracts.history_storage,
+            system_contracts.withdrawal_request,
+            system_contracts.consolidation_request,
+        ]
+        .contains(&Some(address))
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum DecoderError {
     #[error("Decoder failed to deserialize: {0}")]
@@ -245,3 +336,136 @@ impl Decoder {
         Ok(res)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::constants::DEFAULT_BEACON_ROOTS_ADDRESS;
+
+    fn vault(n: u64) -> Address {
+        Address::from_low_u64_be(0xfee0_0000 + n)
+    }
+
+    fn full_config() -> FeeConfig {
+        FeeConfig {
+            base_fee_vault: Some(vault(1)),
+            operator_fee_config: Some(OperatorFeeConfig {
+                operator_fee_vault: vault(2),
+                operator_fee_per_gas: 1_000_000_000,
+            }),
+            l1_fee_config: Some(L1FeeConfig {
+                l1_fee_vault: vault(3),
+                l1_fee_per_blob_gas: 0,
+            }),
+        }
+    }
+
+    /// Valid config with the vault of `kind` replaced by `address`.
+    fn with_vault(kind: FeeVault, address: Address) -> FeeConfig {
+        let mut config = full_config();
+        match kind {
+            FeeVault::BaseFee => config.base_fee_vault = Some(address),
+            FeeVault::OperatorFee => {
+                if let Some(operator) = config.operator_fee_config.as_mut() {
+                    operator.operator_fee_vault = address;
+                }
+            }
+            FeeVault::L1Fee => {
+                if let Some(l1) = config.l1_fee_config.as_mut() {
+                    l1.l1_fee_vault = address;
+                }
+            }
+        }
+        config
+    }
+
+    const VAULTS: [FeeVault; 3] = [FeeVault::BaseFee, FeeVault::OperatorFee, FeeVault::L1Fee];
+
+    #[test]
+    fn valid_configs_pass() {
+        let chain_config = ChainConfig::default();
+        assert!(FeeConfig::default().validate(&chain_config).is_ok());
+        assert!(full_config().validate(&chain_config).is_ok());
+    }
+
+    #[test]
+    fn zero_address_vaults_are_rejected() {
+        for kind in VAULTS {
+            let result = with_vault(kind, Address::zero()).validate(&ChainConfig::default());
+            assert!(
+                matches!(result, Err(FeeConfigError::ZeroAddressVault(vault)) if vault == kind),
+                "{kind}: {result:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn reserved_address_vaults_are_rejected() {
+        let chain_config = ChainConfig::default();
+        let reserved = [
+            Address::from_low_u64_be(0x01),
+            Address::from_low_u64_be(0x100),
+            Address::from_low_u64_be(MAX_RESERVED_ADDRESS),
+            SYSTEM_ADDRESS,
+            DEFAULT_BEACON_ROOTS_ADDRESS,
+        ];
+        for kind in VAULTS {
+            for address in reserved {
+                let result = with_vault(kind, address).validate(&chain_config);
+                assert!(
+                    matches!(
+                        result,
+                        Err(FeeConfigError::ReservedAddressVault { vault, address: rejected })
+                            if vault == kind && rejected == address
+                    ),
+                    "{kind} {address:#x}: {result:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn disabled_system_contract_address_is_allowed() {
+        let mut chain_config = ChainConfig::default();
+        chain_config.system_contracts.beacon_roots = None;
+        let config = with_vault(FeeVault::BaseFee, DEFAULT_BEACON_ROOTS_ADDRESS);
+        assert!(config.validate(&chain_config).is_ok());
+    }
+
+    #[test]
+    fn l1_fee_per_blob_gas_is_bounded() {
+        let max = u64::MAX / u64::from(GAS_PER_BLOB);
+        let mut config = full_config();
+        for (fee, valid) in [(max, true), (max + 1, false), (u64::MAX, false)] {
+            if let Some(l1) = config.l1_fee_config.as_mut() {
+                l1.l1_fee_per_blob_gas = fee;
+            }
+            let result = config.validate(&ChainConfig::default());
+            if valid {
+                assert!(result.is_ok(), "{fee}: {result:?}");
+            } else {
+                assert!(
+                    matches!(result, Err(FeeConfigError::L1FeePerBlobGasTooHigh(too_high)) if too_high == fee),
+                    "{fee}: {result:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn serde_defaults_and_ignores_unknown_fields() {
+        let config: FeeConfig = serde_json::from_str("{}").unwrap();
+        assert!(config.base_fee_vault.is_none());
+        assert!(config.operator_fee_config.is_none());
+        assert!(config.l1_fee_config.is_none());
+
+        let config: FeeConfig = serde_json::from_str(&format!(
+            r#"{{"l1_fee_config": {{"l1_fee_vault": "{:#x}"}}, "fee_token": "0x01"}}"#,
+            vault(3)
+        ))
+        .unwrap();
+        let l1_fee_config = config.l1_fee_config.unwrap();
+        assert_eq!(l1_fee_config.l1_fee_vault, vault(3));
+        assert_eq!(l1_fee_config.l1_fee_per_blob_gas, 0);
+    }
+}
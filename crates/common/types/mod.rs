@@ -1,10 +1,14 @@
 mod account;
 mod account_update;
+pub mod blobs;
 pub mod blobs_bundle;
 mod block;
 pub mod block_access_list;
 pub mod block_execution_witness;
 mod constants;
+pub mod eof;
+pub mod events;
+mod fee_forecast;
 mod fork_id;
 mod genesis;
 pub mod l2;
@@ -19,6 +23,7 @@ pub use account_update::*;
 pub use blobs_bundle::*;
 pub use block::*;
 pub use constants::*;
+pub use fee_forecast::*;
 pub use fork_id::*;
 pub use genesis::*;
 pub use l2::*;
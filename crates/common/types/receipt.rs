@@ -65,6 +65,15 @@ impl Receipt {
 }
 
 pub fn bloom_from_logs(logs: &[Log]) -> Bloom {
+    accrue_logs(logs.iter())
+}
+
+/// Computes the logs bloom of a block header from the block's receipts.
+pub fn compute_logs_bloom(receipts: &[Receipt]) -> Bloom {
+    accrue_logs(receipts.iter().flat_map(|receipt| &receipt.logs))
+}
+
+fn accrue_logs<'a>(logs: impl Iterator<Item = &'a Log>) -> Bloom {
     let mut bloom = Bloom::zero();
     for log in logs {
         let address_hash = keccak_hash(log.address);
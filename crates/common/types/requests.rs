@@ -6,8 +6,7 @@ use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use tracing::error;
 
-use super::{Bytes48, Receipt};
-use crate::constants::DEPOSIT_TOPIC;
+use super::{Bytes48, Receipt, events::DEPOSIT_EVENT};
 use crate::serde_utils;
 
 pub type Bytes32 = [u8; 32];
@@ -93,10 +92,7 @@ impl Requests {
         for r in receipts {
             for log in &r.logs {
                 if log.address == deposit_contract_address
-                    && log
-                        .topics
-                        .first()
-                        .is_some_and(|topic| topic == &*DEPOSIT_TOPIC)
+                    && log.topics.first() == Some(&DEPOSIT_EVENT.topic)
                 {
                     deposits.push(Deposit::from_abi_byte_array(&log.data)?);
                 }
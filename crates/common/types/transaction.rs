@@ -9,6 +9,7 @@ use rkyv::{Archive, Deserialize as RDeserialize, Serialize as RSerialize};
 use serde::{Serialize, ser::SerializeStruct};
 pub use serde_impl::{
     AccessListEntry, AuthorizationTupleEntry, GenericTransaction, GenericTransactionError,
+    NormalizationContext,
 };
 
 /// The serialized length of a default eip1559 transaction
@@ -126,6 +127,18 @@ pub struct WrappedEIP4844Transaction {
     pub blobs_bundle: BlobsBundle,
 }
 
+impl WrappedEIP4844Transaction {
+    /// Wraps `tx` with its bundle, setting the wrapper version from the
+    /// bundle's (omitted for version 0 bundles).
+    pub fn new(tx: EIP4844Transaction, blobs_bundle: BlobsBundle) -> Self {
+        Self {
+            tx,
+            wrapper_version: (blobs_bundle.version != 0).then_some(blobs_bundle.version),
+            blobs_bundle,
+        }
+    }
+}
+
 impl RLPEncode for WrappedEIP4844Transaction {
     fn encode(&self, buf: &mut dyn bytes::BufMut) {
         let encoder = Encoder::new(buf);
@@ -1777,7 +1790,7 @@ mod serde_impl {
 
     #[cfg(feature = "c-kzg")]
     use crate::types::BYTES_PER_BLOB;
-    use crate::types::{AccessListItem, AuthorizationTuple, BlobsBundleError};
+    use crate::types::{AccessListItem, AuthorizationTuple, BlobsBundleError, Fork};
 
     use super::*;
 
@@ -2508,6 +2521,25 @@ mod serde_impl {
         BlobBundleError(#[from] BlobsBundleError),
         #[error("Missing field: {0}")]
         MissingField(String),
+        #[error("Both gasPrice and (maxFeePerGas or maxPriorityFeePerGas) specified")]
+        ConflictingFeeFields,
+        #[error("maxFeePerGas and maxPriorityFeePerGas are not valid before London is active")]
+        DynamicFeeBeforeLondon,
+        #[error(
+            "maxFeePerGas ({max_fee_per_gas}) < maxPriorityFeePerGas ({max_priority_fee_per_gas})"
+        )]
+        PriorityFeeAboveMaxFee {
+            max_fee_per_gas: u64,
+            max_priority_fee_per_gas: u64,
+        },
+        #[error("Chain id {got} doesn't match the node's chain id {expected}")]
+        ChainIdMismatch { expected: u64, got: u64 },
+        #[error("Blob transactions cannot create contracts")]
+        BlobTxCreate,
+        #[error("SetCode transactions cannot create contracts")]
+        SetCodeTxCreate,
+        #[error("SetCode transactions must have at least one authorization")]
+        EmptyAuthorizationList,
     }
 
     /// Unsigned Transaction struct generic to all types which may not contain all required transaction fields
@@ -2586,6 +2618,94 @@ mod serde_impl {
         Ok(Bytes::from(bytes))
     }
 
+    /// Chain state a GenericTransaction is normalized against, usually taken from the block it will be executed on top of
+    #[derive(Debug, Clone, Copy)]
+    pub struct NormalizationContext {
+        pub chain_id: u64,
+        /// Base fee of the block the transaction is executed on
+        pub base_fee: u64,
+        /// Next nonce of the sender
+        pub nonce: u64,
+        /// Fork of the block the transaction is executed on
+        pub fork: Fork,
+    }
+
+    impl GenericTransaction {
+        /// Fills the missing fields of the transaction and validates the given ones, the same way for every caller,
+        /// so the transaction is executed as the node would execute it
+        /// - Missing nonce and chain id are taken from the context, a chain id different from the context's is rejected
+        /// - Setting both the legacy gas price and any EIP-1559 fee field is rejected. A missing priority fee
+        ///   defaults to zero and a missing max fee to twice the base fee plus the priority fee
+        /// - The gas limit is left as given, it's resolved against the block by the VM's `effective_gas_limit`
+        /// - Blob fields and authorization lists are rejected on contract creations
+        ///
+        /// See https://github.com/ethereum/go-ethereum/blob/v1.16.0/internal/ethapi/transaction_args.go
+        pub fn normalize(
+            &mut self,
+            ctx: &NormalizationContext,
+        ) -> Result<(), GenericTransactionError> {
+            self.nonce.get_or_insert(ctx.nonce);
+            match self.chain_id {
+                Some(chain_id) if chain_id != ctx.chain_id => {
+                    return Err(GenericTransactionError::ChainIdMismatch {
+                        expected: ctx.chain_id,
+                        got: chain_id,
+                    });
+                }
+                Some(_) => {}
+                None => self.chain_id = Some(ctx.chain_id),
+            }
+            self.normalize_fees(ctx)?;
+            let has_blob_fields = !self.blob_versioned_hashes.is_empty()
+                || !self.blobs.is_empty()
+                || self.max_fee_per_blob_gas.is_some();
+            if has_blob_fields && self.to == TxKind::Create {
+                return Err(GenericTransactionError::BlobTxCreate);
+            }
+            if let Some(authorization_list) = &self.authorization_list {
+                if self.to == TxKind::Create {
+                    return Err(GenericTransactionError::SetCodeTxCreate);
+                }
+                if authorization_list.is_empty() {
+                    return Err(GenericTransactionError::EmptyAuthorizationList);
+                }
+            }
+            Ok(())
+        }
+
+        /// Resolves the fee fields following geth's rules. If no fee is given none is set,
+        /// so the transaction is executed without paying for gas
+        fn normalize_fees(
+            &mut self,
+            ctx: &NormalizationContext,
+        ) -> Result<(), GenericTransactionError> {
+            let has_dynamic_fee =
+                self.max_fee_per_gas.is_some() || self.max_priority_fee_per_gas.is_some();
+            if !has_dynamic_fee {
+                return Ok(());
+            }
+            if self.gas_price != 0 {
+                return Err(GenericTransactionError::ConflictingFeeFields);
+            }
+            if ctx.fork < Fork::London {
+                return Err(GenericTransactionError::DynamicFeeBeforeLondon);
+            }
+            let max_priority_fee_per_gas = *self.max_priority_fee_per_gas.get_or_insert(0);
+            let max_fee_per_gas = *self.max_fee_per_gas.get_or_insert_with(|| {
+                ctx.base_fee
+                    .saturating_mul(2)
+                    .saturating_add(max_priority_fee_per_gas)
+            });
+            if max_fee_per_gas < max_priority_fee_per_gas {
+                return Err(GenericTransactionError::PriorityFeeAboveMaxFee {
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas,
+                });
+            }
+            Ok(())
+        }
+    }
+
     impl From<EIP1559Transaction> for GenericTransaction {
         fn from(value: EIP1559Transaction) -> Self {
             Self {
@@ -3089,8 +3209,10 @@ mod mempool {
 mod tests {
 
     use super::*;
+    use crate::constants::POST_OSAKA_GAS_LIMIT_CAP;
     use crate::types::{
-        AuthorizationTuple, BlockBody, Receipt, compute_receipts_root, compute_transactions_root,
+        AuthorizationTuple, BlockBody, Fork, Receipt, compute_receipts_root,
+        compute_transactions_root,
     };
     use ethereum_types::H160;
     use hex_literal::hex;
@@ -3613,6 +3735,211 @@ mod tests {
         assert_eq!(generic_tx.access_list[0].storage_keys, access_list[0].1);
     }
 
+    fn normalization_context(fork: Fork) -> NormalizationContext {
+        NormalizationContext {
+            chain_id: 1,
+            base_fee: 10,
+            nonce: 7,
+            fork,
+        }
+    }
+
+    fn generic_call() -> GenericTransaction {
+        GenericTransaction {
+            to: TxKind::Call(Address::from_low_u64_be(0xc0de)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_normalize_generic_transaction_defaults() {
+        // (case, fork, transaction, normalized transaction)
+        let cases = [
+            (
+                "missing fields are filled",
+                Fork::Prague,
+                generic_call(),
+                GenericTransaction {
+                    nonce: Some(7),
+                    chain_id: Some(1),
+                    ..generic_call()
+                },
+            ),
+            (
+                "given fields are kept",
+                Fork::Prague,
+                GenericTransaction {
+                    nonce: Some(3),
+                    chain_id: Some(1),
+                    gas: Some(POST_OSAKA_GAS_LIMIT_CAP + 1),
+                    gas_price: 5,
+                    ..generic_call()
+                },
+                GenericTransaction {
+                    nonce: Some(3),
+                    chain_id: Some(1),
+                    gas: Some(POST_OSAKA_GAS_LIMIT_CAP + 1),
+                    gas_price: 5,
+                    ..generic_call()
+                },
+            ),
+            (
+                "max fee defaults to twice the base fee plus the priority fee",
+                Fork::London,
+                GenericTransaction {
+                    max_priority_fee_per_gas: Some(2),
+                    ..generic_call()
+                },
+                GenericTransaction {
+                    nonce: Some(7),
+                    chain_id: Some(1),
+                    max_priority_fee_per_gas: Some(2),
+                    max_fee_per_gas: Some(22),
+                    ..generic_call()
+                },
+            ),
+            (
+                "priority fee defaults to zero",
+                Fork::London,
+                GenericTransaction {
+                    max_fee_per_gas: Some(30),
+                    ..generic_call()
+                },
+                GenericTransaction {
+                    nonce: Some(7),
+                    chain_id: Some(1),
+                    max_priority_fee_per_gas: Some(0),
+                    max_fee_per_gas: Some(30),
+                    ..generic_call()
+                },
+            ),
+            (
+                "blob fields are allowed on calls",
+                Fork::Cancun,
+                GenericTransaction {
+                    blob_versioned_hashes: vec![H256::zero()],
+                    ..generic_call()
+                },
+                GenericTransaction {
+                    nonce: Some(7),
+                    chain_id: Some(1),
+                    blob_versioned_hashes: vec![H256::zero()],
+                    ..generic_call()
+                },
+            ),
+        ];
+        for (case, fork, mut tx, expected) in cases {
+            tx.normalize(&normalization_context(fork))
+                .unwrap_or_else(|err| panic!("{case}: {err}"));
+            assert_eq!(tx, expected, "{case}");
+        }
+    }
+
+    #[test]
+    fn test_normalize_generic_transaction_errors() {
+        let authorization = AuthorizationTupleEntry::from(&AuthorizationTuple::default());
+        // (case, fork, transaction, error)
+        let cases = [
+            (
+                "gas price and max fee",
+                Fork::Prague,
+                GenericTransaction {
+                    gas_price: 5,
+                    max_fee_per_gas: Some(30),
+                    ..generic_call()
+                },
+                GenericTransactionError::ConflictingFeeFields,
+            ),
+            (
+                "gas price and priority fee",
+                Fork::Prague,
+                GenericTransaction {
+                    gas_price: 5,
+                    max_priority_fee_per_gas: Some(2),
+                    ..generic_call()
+                },
+                GenericTransactionError::ConflictingFeeFields,
+            ),
+            (
+                "dynamic fee before london",
+                Fork::Berlin,
+                GenericTransaction {
+                    max_fee_per_gas: Some(30),
+                    ..generic_call()
+                },
+                GenericTransactionError::DynamicFeeBeforeLondon,
+            ),
+            (
+                "priority fee above max fee",
+                Fork::Prague,
+                GenericTransaction {
+                    max_fee_per_gas: Some(2),
+                    max_priority_fee_per_gas: Some(3),
+                    ..generic_call()
+                },
+                GenericTransactionError::PriorityFeeAboveMaxFee {
+                    max_fee_per_gas: 2,
+                    max_priority_fee_per_gas: 3,
+                },
+            ),
+            (
+                "chain id mismatch",
+                Fork::Prague,
+                GenericTransaction {
+                    chain_id: Some(5),
+                    ..generic_call()
+                },
+                GenericTransactionError::ChainIdMismatch {
+                    expected: 1,
+                    got: 5,
+                },
+            ),
+            (
+                "blob hashes on create",
+                Fork::Cancun,
+                GenericTransaction {
+                    to: TxKind::Create,
+                    blob_versioned_hashes: vec![H256::zero()],
+                    ..Default::default()
+                },
+                GenericTransactionError::BlobTxCreate,
+            ),
+            (
+                "blob fee on create",
+                Fork::Cancun,
+                GenericTransaction {
+                    to: TxKind::Create,
+                    max_fee_per_blob_gas: Some(U256::one()),
+                    ..Default::default()
+                },
+                GenericTransactionError::BlobTxCreate,
+            ),
+            (
+                "authorization list on create",
+                Fork::Prague,
+                GenericTransaction {
+                    to: TxKind::Create,
+                    authorization_list: Some(vec![authorization]),
+                    ..Default::default()
+                },
+                GenericTransactionError::SetCodeTxCreate,
+            ),
+            (
+                "empty authorization list",
+                Fork::Prague,
+                GenericTransaction {
+                    authorization_list: Some(vec![]),
+                    ..generic_call()
+                },
+                GenericTransactionError::EmptyAuthorizationList,
+            ),
+        ];
+        for (case, fork, mut tx, expected) in cases {
+            let err = tx.normalize(&normalization_context(fork)).expect_err(case);
+            assert_eq!(err.to_string(), expected.to_string(), "{case}");
+        }
+    }
+
     #[test]
     fn recover_address_rejects_high_s_signatures() {
         use k256::ecdsa::SigningKey;
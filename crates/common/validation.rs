@@ -4,12 +4,13 @@
 //! storage dependencies, making them suitable for use in zkVM guest programs.
 
 use crate::constants::{GAS_PER_BLOB, MAX_RLP_BLOCK_SIZE, POST_OSAKA_GAS_LIMIT_CAP};
-use crate::errors::InvalidBlockError;
+use crate::errors::{CommitmentMismatch, InvalidBlockError};
+use crate::types::block_access_list::BlockAccessList;
 use crate::types::requests::{EncodedRequests, Requests, compute_requests_hash};
 use crate::types::{
-    Block, BlockHeader, ChainConfig, EIP4844Transaction, Receipt, compute_receipts_root,
-    validate_block_header, validate_cancun_header_fields, validate_prague_header_fields,
-    validate_pre_cancun_header_fields,
+    Block, BlockHeader, ChainConfig, EIP4844Transaction, Receipt, compute_logs_bloom,
+    compute_receipts_root, validate_block_header, validate_cancun_header_fields,
+    validate_prague_header_fields, validate_pre_cancun_header_fields, validate_slot_number_field,
 };
 use ethrex_rlp::encode::RLPEncode;
 
@@ -49,6 +50,7 @@ pub fn validate_block(
     } else {
         validate_pre_cancun_header_fields(&block.header)?;
     }
+    validate_slot_number_field(&block.header, chain_config)?;
 
     Ok(())
 }
@@ -61,10 +63,11 @@ pub fn validate_gas_used(
     block_header: &BlockHeader,
 ) -> Result<(), InvalidBlockError> {
     if block_gas_used != block_header.gas_used {
-        return Err(InvalidBlockError::GasUsedMismatch(
-            block_gas_used,
-            block_header.gas_used,
-        ));
+        return Err(CommitmentMismatch::GasUsed {
+            expected: block_header.gas_used,
+            actual: block_gas_used,
+        }
+        .into());
     }
     Ok(())
 }
@@ -79,7 +82,29 @@ pub fn validate_receipts_root(
     if receipts_root == block_header.receipts_root {
         Ok(())
     } else {
-        Err(InvalidBlockError::ReceiptsRootMismatch)
+        Err(CommitmentMismatch::ReceiptsRoot {
+            expected: block_header.receipts_root,
+            actual: receipts_root,
+        }
+        .into())
+    }
+}
+
+/// Validates that the logs bloom matches the block header.
+pub fn validate_logs_bloom(
+    block_header: &BlockHeader,
+    receipts: &[Receipt],
+) -> Result<(), InvalidBlockError> {
+    let logs_bloom = compute_logs_bloom(receipts);
+
+    if logs_bloom == block_header.logs_bloom {
+        Ok(())
+    } else {
+        Err(CommitmentMismatch::LogsBloom {
+            expected: block_header.logs_bloom,
+            actual: logs_bloom,
+        }
+        .into())
     }
 }
 
@@ -95,13 +120,13 @@ pub fn validate_requests_hash(
 
     let encoded_requests: Vec<EncodedRequests> = requests.iter().map(|r| r.encode()).collect();
     let computed_requests_hash = compute_requests_hash(&encoded_requests);
-    let valid = header
-        .requests_hash
-        .map(|requests_hash| requests_hash == computed_requests_hash)
-        .unwrap_or(false);
 
-    if !valid {
-        return Err(InvalidBlockError::RequestsHashMismatch);
+    if header.requests_hash != Some(computed_requests_hash) {
+        return Err(CommitmentMismatch::RequestsHash {
+            expected: header.requests_hash,
+            actual: computed_requests_hash,
+        }
+        .into());
     }
 
     Ok(())
@@ -128,7 +153,7 @@ fn validate_bal_indices(
 pub fn validate_block_access_list_hash(
     header: &BlockHeader,
     chain_config: &ChainConfig,
-    computed_bal: &crate::types::block_access_list::BlockAccessList,
+    computed_bal: &BlockAccessList,
     transaction_count: usize,
 ) -> Result<(), InvalidBlockError> {
     // BAL validation only applies to Amsterdam+ forks
@@ -172,18 +197,45 @@ pub fn validate_block_access_list_hash(
     }
 
     let computed_hash = computed_bal.compute_hash();
-    let valid = header
-        .block_access_list_hash
-        .map(|expected_hash| expected_hash == computed_hash)
-        .unwrap_or(false);
 
-    if !valid {
-        return Err(InvalidBlockError::BlockAccessListHashMismatch);
+    if header.block_access_list_hash != Some(computed_hash) {
+        return Err(CommitmentMismatch::BlockAccessListHash {
+            expected: header.block_access_list_hash,
+            actual: computed_hash,
+        }
+        .into());
     }
 
     Ok(())
 }
 
+/// Validates every header commitment to the results of executing the block:
+/// gas used, receipts root, logs bloom, requests hash (Prague+) and block
+/// access list hash (Amsterdam+, only when a BAL was recorded).
+pub fn validate_post_execution_commitments(
+    header: &BlockHeader,
+    chain_config: &ChainConfig,
+    block_gas_used: u64,
+    receipts: &[Receipt],
+    requests: &[Requests],
+    block_access_list: Option<&BlockAccessList>,
+    transaction_count: usize,
+) -> Result<(), InvalidBlockError> {
+    validate_gas_used(block_gas_used, header)?;
+    validate_receipts_root(header, receipts)?;
+    validate_logs_bloom(header, receipts)?;
+    validate_requests_hash(header, chain_config, requests)?;
+    if let Some(block_access_list) = block_access_list {
+        validate_block_access_list_hash(
+            header,
+            chain_config,
+            block_access_list,
+            transaction_count,
+        )?;
+    }
+    Ok(())
+}
+
 /// Perform validations over the block's blob gas usage.
 /// Must be called only if the block has cancun activated.
 fn verify_blob_gas_usage(block: &Block, config: &ChainConfig) -> Result<(), InvalidBlockError> {
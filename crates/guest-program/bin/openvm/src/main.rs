@@ -9,6 +9,8 @@ use rkyv::rancor::Error;
 openvm::init!();
 
 pub fn main() {
+    ethrex_guest_program::embed_build_info!();
+
     openvm::io::println("start reading input");
     let input = openvm::io::read_vec();
     let input = rkyv::from_bytes::<ProgramInput, Error>(&input).unwrap();
@@ -9,6 +9,8 @@ use risc0_zkvm::guest::env;
 use rkyv::rancor::Error;
 
 fn main() {
+    ethrex_guest_program::embed_build_info!();
+
     println!("start reading input");
     let start = env::cycle_count();
     let mut input = Vec::new();
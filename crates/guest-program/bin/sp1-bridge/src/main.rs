@@ -13,6 +13,8 @@ sp1_zkvm::entrypoint!(main);
 ///
 /// This produces significantly faster proofs compared to evm-l2.
 pub fn main() {
+    ethrex_guest_program::embed_build_info!();
+
     println!("cycle-tracker-report-start: read_input");
     let input = sp1_zkvm::io::read_vec();
     let input = rkyv::from_bytes::<AppProgramInput, Error>(&input).unwrap();
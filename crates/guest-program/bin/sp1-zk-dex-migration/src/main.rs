@@ -0,0 +1,31 @@
+#![no_main]
+
+use ethrex_common::{Address, H160};
+use ethrex_guest_program::common::migration::{MigrationInput, migrate_state};
+use ethrex_guest_program::programs::zk_dex::migration::NoteFormatMigration;
+use rkyv::rancor::Error;
+
+sp1_zkvm::entrypoint!(main);
+
+/// DEX contract address on the L2 (build-time placeholder).
+const DEX_CONTRACT_ADDRESS: Address = H160([0xDE; 20]);
+
+pub fn main() {
+    ethrex_guest_program::embed_build_info!();
+
+    println!("cycle-tracker-report-start: read_input");
+    let input = sp1_zkvm::io::read_vec();
+    let input = rkyv::from_bytes::<MigrationInput, Error>(&input).unwrap();
+    println!("cycle-tracker-report-end: read_input");
+
+    println!("cycle-tracker-report-start: migration");
+    let migration = NoteFormatMigration {
+        contract_address: DEX_CONTRACT_ADDRESS,
+    };
+    let output = migrate_state(&migration, input).unwrap();
+    println!("cycle-tracker-report-end: migration");
+
+    println!("cycle-tracker-report-start: commit_public_inputs");
+    sp1_zkvm::io::commit_slice(&output.encode());
+    println!("cycle-tracker-report-end: commit_public_inputs");
+}
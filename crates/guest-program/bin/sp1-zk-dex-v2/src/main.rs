@@ -0,0 +1,34 @@
+#![no_main]
+
+use ethrex_common::{Address, H160};
+use ethrex_guest_program::common::app_execution::execute_app_circuit;
+use ethrex_guest_program::common::app_types::AppProgramInput;
+use ethrex_guest_program::programs::zk_dex::circuit::DexCircuit;
+use ethrex_guest_program::programs::zk_dex::migration::NOTE_FORMAT_V2;
+use rkyv::rancor::Error;
+
+sp1_zkvm::entrypoint!(main);
+
+/// DEX contract address on the L2 (build-time placeholder).
+const DEX_CONTRACT_ADDRESS: Address = H160([0xDE; 20]);
+
+pub fn main() {
+    ethrex_guest_program::embed_build_info!();
+
+    println!("cycle-tracker-report-start: read_input");
+    let input = sp1_zkvm::io::read_vec();
+    let input = rkyv::from_bytes::<AppProgramInput, Error>(&input).unwrap();
+    println!("cycle-tracker-report-end: read_input");
+
+    println!("cycle-tracker-report-start: execution");
+    let circuit = DexCircuit {
+        contract_address: DEX_CONTRACT_ADDRESS,
+        note_format: Some(NOTE_FORMAT_V2),
+    };
+    let output = execute_app_circuit(&circuit, input).unwrap();
+    println!("cycle-tracker-report-end: execution");
+
+    println!("cycle-tracker-report-start: commit_public_inputs");
+    sp1_zkvm::io::commit_slice(&output.encode());
+    println!("cycle-tracker-report-end: commit_public_inputs");
+}
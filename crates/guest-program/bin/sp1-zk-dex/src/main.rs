@@ -12,6 +12,8 @@ sp1_zkvm::entrypoint!(main);
 const DEX_CONTRACT_ADDRESS: Address = H160([0xDE; 20]);
 
 pub fn main() {
+    ethrex_guest_program::embed_build_info!();
+
     println!("cycle-tracker-report-start: read_input");
     let input = sp1_zkvm::io::read_vec();
     let input = rkyv::from_bytes::<AppProgramInput, Error>(&input).unwrap();
@@ -20,6 +22,7 @@ pub fn main() {
     println!("cycle-tracker-report-start: execution");
     let circuit = DexCircuit {
         contract_address: DEX_CONTRACT_ADDRESS,
+        note_format: None,
     };
     let output = execute_app_circuit(&circuit, input).unwrap();
     println!("cycle-tracker-report-end: execution");
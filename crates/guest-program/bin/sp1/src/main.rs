@@ -10,6 +10,8 @@ use rkyv::rancor::Error;
 sp1_zkvm::entrypoint!(main);
 
 pub fn main() {
+    ethrex_guest_program::embed_build_info!();
+
     println!("cycle-tracker-report-start: read_input");
     let input = sp1_zkvm::io::read_vec();
     let input = rkyv::from_bytes::<ProgramInput, Error>(&input).unwrap();
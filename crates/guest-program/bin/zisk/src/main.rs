@@ -11,6 +11,8 @@ use sha2::{Digest, Sha256};
 ziskos::entrypoint!(main);
 
 pub fn main() {
+    ethrex_guest_program::embed_build_info!();
+
     println!("start reading input");
     let input = ziskos::read_input();
     let input = rkyv::from_bytes::<ProgramInput, Error>(&input).unwrap();
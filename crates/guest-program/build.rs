@@ -43,6 +43,19 @@ fn main() {
         ensure_elf_placeholder("./bin/sp1-zk-dex");
     }
 
+    // zk-dex v0.2 takes over from v0.1 through the migration guest, so both
+    // are built together.
+    if programs.contains(&"zk-dex-v2".to_string()) {
+        #[cfg(all(not(clippy), feature = "sp1"))]
+        {
+            build_sp1_guest_program("sp1-zk-dex-v2");
+            build_sp1_guest_program("sp1-zk-dex-migration");
+        }
+    } else {
+        ensure_elf_placeholder("./bin/sp1-zk-dex-v2");
+        ensure_elf_placeholder("./bin/sp1-zk-dex-migration");
+    }
+
     if programs.contains(&"tokamon".to_string()) {
         #[cfg(all(not(clippy), feature = "sp1"))]
         build_sp1_guest_program("sp1-tokamon");
@@ -59,14 +72,19 @@ fn main() {
 }
 
 /// Create an empty placeholder ELF file if it doesn't already exist.
-/// This prevents `include_bytes!` from failing during `cargo check`
-/// when a guest program wasn't included in the build.
+/// This prevents `include_bytes!` and `include_str!` from failing during
+/// `cargo check` when a guest program wasn't included in the build.
 fn ensure_elf_placeholder(bin_dir: &str) {
     let out_dir = format!("{bin_dir}/out");
-    let elf_path = format!("{out_dir}/riscv32im-succinct-zkvm-elf");
-    if !std::path::Path::new(&elf_path).exists() {
-        let _ = std::fs::create_dir_all(&out_dir);
-        let _ = std::fs::write(&elf_path, b"");
+    for file in [
+        "riscv32im-succinct-zkvm-elf",
+        "riscv32im-succinct-zkvm-vk-bn254",
+    ] {
+        let path = format!("{out_dir}/{file}");
+        if !std::path::Path::new(&path).exists() {
+            let _ = std::fs::create_dir_all(&out_dir);
+            let _ = std::fs::write(&path, b"");
+        }
     }
 }
 
@@ -81,6 +99,12 @@ fn build_risc0_program() {
         vec![]
     };
 
+    export_build_info(
+        "./bin/risc0",
+        &toolchain_version("risc0 v3.0.3", "risc0"),
+        &features,
+    );
+
     let guest_options = if option_env!("PROVER_REPRODUCIBLE_BUILD").is_some() {
         let docker_options = DockerOptionsBuilder::default()
             .root_dir(format!("{}/../../../", env!("CARGO_MANIFEST_DIR")))
@@ -129,6 +153,12 @@ fn build_sp1_program() {
         vec![]
     };
 
+    export_build_info(
+        "./bin/sp1",
+        &toolchain_version("sp1 v5.0.8", "succinct"),
+        &features,
+    );
+
     sp1_build::build_program_with_args(
         "./bin/sp1",
         sp1_build::BuildArgs {
@@ -178,6 +208,13 @@ fn build_zisk_program() {
     // when building in a GitHub CI environment. This command is not required if we won't generate a proof
     // so we skip it under the `ci` feature flag.
 
+    let features = if cfg!(feature = "l2") {
+        vec!["l2".to_string()]
+    } else {
+        vec![]
+    };
+    export_build_info("./bin/zisk", &toolchain_version("zisk", "zisk"), &features);
+
     let mut build_command = std::process::Command::new("cargo");
     #[cfg(not(feature = "ci"))]
     let mut setup_command = std::process::Command::new("cargo-zisk");
@@ -261,6 +298,8 @@ fn build_openvm_program() {
         process::{Command, Stdio},
     };
 
+    export_build_info("./bin/openvm", "openvm", &[]);
+
     let status = Command::new("cargo")
         .arg("openvm")
         .arg("build")
@@ -301,6 +340,12 @@ fn build_sp1_guest_program(name: &str) {
         vec![]
     };
 
+    export_build_info(
+        &bin_dir,
+        &toolchain_version("sp1 v5.0.8", "succinct"),
+        &features,
+    );
+
     sp1_build::build_program_with_args(
         &bin_dir,
         sp1_build::BuildArgs {
@@ -351,3 +396,60 @@ pub fn rustc_path(toolchain: &str) -> std::path::PathBuf {
         .join("bin")
         .join("rustc")
 }
+
+/// Export the metadata guests embed with `ethrex_guest_program::embed_build_info!`.
+///
+/// The zkVM build tools spawn cargo with this process' environment, so the
+/// variables reach the guest's `option_env!` calls.  Docker builds
+/// (`PROVER_REPRODUCIBLE_BUILD`) don't forward them and embed empty values.
+#[cfg(all(
+    not(clippy),
+    any(
+        feature = "risc0",
+        feature = "sp1",
+        feature = "zisk",
+        feature = "openvm"
+    )
+))]
+fn export_build_info(bin_dir: &str, toolchain: &str, features: &[String]) {
+    use sha2::{Digest, Sha256};
+
+    let git = |args: &[&str]| {
+        std::process::Command::new("git")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+    let mut commit = git(&["rev-parse", "HEAD"]).unwrap_or_default();
+    if git(&["status", "--porcelain"]).is_some_and(|status| !status.is_empty()) {
+        commit.push_str("-dirty");
+    }
+    let cargo_lock_hash = std::fs::read(format!("{bin_dir}/Cargo.lock"))
+        .map(|lock| hex::encode(Sha256::digest(lock)))
+        .unwrap_or_default();
+
+    // SAFETY: build scripts are single-threaded, nothing reads the
+    // environment concurrently.
+    unsafe {
+        std::env::set_var("ETHREX_BUILD_GIT_COMMIT", commit);
+        std::env::set_var("ETHREX_BUILD_CARGO_LOCK_HASH", cargo_lock_hash);
+        std::env::set_var("ETHREX_BUILD_TOOLCHAIN", toolchain);
+        std::env::set_var("ETHREX_BUILD_FEATURES", features.join(","));
+    }
+}
+
+/// `<name>, <rustc --version>` for the given rustup toolchain, or just `name`
+/// when the toolchain isn't installed.
+#[cfg(all(not(clippy), any(feature = "risc0", feature = "sp1", feature = "zisk")))]
+fn toolchain_version(name: &str, rustup_toolchain: &str) -> String {
+    std::process::Command::new("rustc")
+        .env("RUSTUP_TOOLCHAIN", rustup_toolchain)
+        .arg("--version")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| format!("{name}, {}", String::from_utf8_lossy(&output.stdout).trim()))
+        .unwrap_or_else(|| name.to_string())
+}
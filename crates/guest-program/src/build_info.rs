@@ -0,0 +1,398 @@
+//! Build metadata embedded in guest program ELFs.
+//!
+//! Each guest binary calls [`embed_build_info!`](crate::embed_build_info) from
+//! its `main`, which places a fixed-size record in the [`BUILD_INFO_SECTION`]
+//! section of the ELF.  The values come from the `ETHREX_BUILD_*` environment
+//! variables this crate's `build.rs` exports while compiling the guests, so the
+//! host can tell which commit, lockfile and toolchain an ELF was built from
+//! instead of trusting whatever `include_bytes!` picked up.
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::traits::GuestProgramError;
+
+/// Name of the ELF section holding the encoded [`ElfBuildInfo`].
+pub const BUILD_INFO_SECTION: &str = ".ethrex_build_info";
+
+/// Size of the embedded record.  Unused bytes are zero.
+pub const BUILD_INFO_LEN: usize = 512;
+
+/// Metadata describing how a guest ELF was built.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ElfBuildInfo {
+    /// Git commit of the source tree, suffixed with `-dirty` when it had
+    /// uncommitted changes.
+    pub git_commit: String,
+    /// Hex SHA-256 of the guest's `Cargo.lock`.
+    pub cargo_lock_hash: String,
+    /// zkVM toolchain the guest was compiled with (e.g. `sp1 v5.0.8`).
+    pub toolchain: String,
+    /// Cargo features enabled on the guest crate.
+    #[serde(default)]
+    pub features: Vec<String>,
+}
+
+impl ElfBuildInfo {
+    /// Decode the contents of a [`BUILD_INFO_SECTION`] section.
+    ///
+    /// The record is a list of `key=value` lines padded with zeros.  Unknown
+    /// keys are ignored so newer guests stay readable by older hosts.
+    pub fn decode(section: &[u8]) -> Result<Self, GuestProgramError> {
+        let end = section
+            .iter()
+            .position(|byte| *byte == 0)
+            .unwrap_or(section.len());
+        let text = std::str::from_utf8(section.get(..end).unwrap_or_default()).map_err(|e| {
+            GuestProgramError::InvalidElf(format!("build info is not valid UTF-8: {e}"))
+        })?;
+
+        let mut info = Self::default();
+        for line in text.lines().filter(|line| !line.is_empty()) {
+            let (key, value) = line.split_once('=').ok_or_else(|| {
+                GuestProgramError::InvalidElf(format!("malformed build info line: {line}"))
+            })?;
+            match key {
+                "git_commit" => info.git_commit = value.to_string(),
+                "cargo_lock_hash" => info.cargo_lock_hash = value.to_string(),
+                "toolchain" => info.toolchain = value.to_string(),
+                "features" => {
+                    info.features = value
+                        .split(',')
+                        .filter(|feature| !feature.is_empty())
+                        .map(str::to_string)
+                        .collect()
+                }
+                _ => {}
+            }
+        }
+        Ok(info)
+    }
+}
+
+impl std::fmt::Display for ElfBuildInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "commit {}, Cargo.lock {}, toolchain {}, features [{}]",
+            self.git_commit,
+            self.cargo_lock_hash,
+            self.toolchain,
+            self.features.join(",")
+        )
+    }
+}
+
+/// SHA-256 of an ELF binary.
+///
+/// This is the content hash the prover advertises during the capability
+/// handshake and keys verification keys by, so every component refers to an
+/// ELF the same way.
+pub fn elf_hash(elf: &[u8]) -> [u8; 32] {
+    Sha256::digest(elf).into()
+}
+
+/// Read the embedded build metadata of an ELF.
+///
+/// Returns `Ok(None)` when the ELF has no [`BUILD_INFO_SECTION`] section (e.g.
+/// it predates build metadata or its section headers were stripped).
+pub fn read_build_info(elf: &[u8]) -> Result<Option<ElfBuildInfo>, GuestProgramError> {
+    find_section(elf, BUILD_INFO_SECTION)?
+        .map(ElfBuildInfo::decode)
+        .transpose()
+}
+
+/// `Option::unwrap_or("")` usable in const context, for `option_env!` values.
+pub const fn env_or_empty(value: Option<&'static str>) -> &'static str {
+    match value {
+        Some(value) => value,
+        None => "",
+    }
+}
+
+/// Encode build metadata into the fixed-size record stored in the ELF.
+///
+/// Fails to compile (or panics at runtime) if the fields don't fit in
+/// [`BUILD_INFO_LEN`] bytes.
+pub const fn encode_build_info(
+    git_commit: &str,
+    cargo_lock_hash: &str,
+    toolchain: &str,
+    features: &str,
+) -> [u8; BUILD_INFO_LEN] {
+    let mut buf = [0u8; BUILD_INFO_LEN];
+    let mut pos = 0;
+    pos = push_line(&mut buf, pos, b"git_commit=", git_commit.as_bytes());
+    pos = push_line(
+        &mut buf,
+        pos,
+        b"cargo_lock_hash=",
+        cargo_lock_hash.as_bytes(),
+    );
+    pos = push_line(&mut buf, pos, b"toolchain=", toolchain.as_bytes());
+    push_line(&mut buf, pos, b"features=", features.as_bytes());
+    buf
+}
+
+const fn push_line(buf: &mut [u8; BUILD_INFO_LEN], pos: usize, key: &[u8], value: &[u8]) -> usize {
+    let pos = push_bytes(buf, pos, key);
+    let pos = push_bytes(buf, pos, value);
+    push_bytes(buf, pos, b"\n")
+}
+
+const fn push_bytes(buf: &mut [u8; BUILD_INFO_LEN], mut pos: usize, bytes: &[u8]) -> usize {
+    let mut i = 0;
+    while i < bytes.len() {
+        assert!(
+            pos < BUILD_INFO_LEN,
+            "build info doesn't fit in BUILD_INFO_LEN"
+        );
+        buf[pos] = bytes[i];
+        pos += 1;
+        i += 1;
+    }
+    pos
+}
+
+/// Embed the build metadata of the guest being compiled.
+///
+/// Call it once from the guest's `main`.  The record is referenced through
+/// [`core::hint::black_box`] so the linker can't garbage-collect the section.
+#[macro_export]
+macro_rules! embed_build_info {
+    () => {{
+        // The section name must match `build_info::BUILD_INFO_SECTION`.
+        #[used]
+        #[unsafe(link_section = ".ethrex_build_info")]
+        static ETHREX_BUILD_INFO: [u8; $crate::build_info::BUILD_INFO_LEN] =
+            $crate::build_info::encode_build_info(
+                $crate::build_info::env_or_empty(option_env!("ETHREX_BUILD_GIT_COMMIT")),
+                $crate::build_info::env_or_empty(option_env!("ETHREX_BUILD_CARGO_LOCK_HASH")),
+                $crate::build_info::env_or_empty(option_env!("ETHREX_BUILD_TOOLCHAIN")),
+                $crate::build_info::env_or_empty(option_env!("ETHREX_BUILD_FEATURES")),
+            );
+        core::hint::black_box(&ETHREX_BUILD_INFO);
+    }};
+}
+
+// ── ELF section lookup ───────────────────────────────────────────────
+
+const ELFCLASS32: u8 = 1;
+const ELFCLASS64: u8 = 2;
+const ELFDATA2LSB: u8 = 1;
+
+/// Offsets of the ELF fields needed to walk the section headers.
+struct ElfLayout {
+    e_shoff: (usize, usize),
+    e_shentsize: usize,
+    e_shnum: usize,
+    e_shstrndx: usize,
+    sh_offset: (usize, usize),
+    sh_size: (usize, usize),
+}
+
+const ELF32_LAYOUT: ElfLayout = ElfLayout {
+    e_shoff: (0x20, 4),
+    e_shentsize: 0x2E,
+    e_shnum: 0x30,
+    e_shstrndx: 0x32,
+    sh_offset: (0x10, 4),
+    sh_size: (0x14, 4),
+};
+
+const ELF64_LAYOUT: ElfLayout = ElfLayout {
+    e_shoff: (0x28, 8),
+    e_shentsize: 0x3A,
+    e_shnum: 0x3C,
+    e_shstrndx: 0x3E,
+    sh_offset: (0x18, 8),
+    sh_size: (0x20, 8),
+};
+
+/// Little-endian unsigned integer of `size` bytes at `offset`.
+fn read_uint(elf: &[u8], offset: usize, size: usize) -> Result<usize, GuestProgramError> {
+    let bytes = offset
+        .checked_add(size)
+        .and_then(|end| elf.get(offset..end))
+        .ok_or_else(|| {
+            GuestProgramError::InvalidElf(format!("truncated ELF: can't read offset {offset}"))
+        })?;
+    let mut buf = [0u8; 8];
+    buf[..size].copy_from_slice(bytes);
+    usize::try_from(u64::from_le_bytes(buf))
+        .map_err(|_| GuestProgramError::InvalidElf(format!("ELF offset overflow at {offset}")))
+}
+
+/// Contents of the section named `name`, if present.
+fn find_section<'a>(elf: &'a [u8], name: &str) -> Result<Option<&'a [u8]>, GuestProgramError> {
+    if elf.get(0..4) != Some(&[0x7f, b'E', b'L', b'F'][..]) {
+        return Err(GuestProgramError::InvalidElf(
+            "invalid ELF magic number".to_string(),
+        ));
+    }
+    let layout = match elf.get(4) {
+        Some(&ELFCLASS32) => &ELF32_LAYOUT,
+        Some(&ELFCLASS64) => &ELF64_LAYOUT,
+        class => {
+            return Err(GuestProgramError::InvalidElf(format!(
+                "unknown ELF class {class:?}"
+            )));
+        }
+    };
+    if elf.get(5) != Some(&ELFDATA2LSB) {
+        return Err(GuestProgramError::InvalidElf(
+            "only little-endian ELFs are supported".to_string(),
+        ));
+    }
+
+    let shoff = read_uint(elf, layout.e_shoff.0, layout.e_shoff.1)?;
+    let shentsize = read_uint(elf, layout.e_shentsize, 2)?;
+    let shnum = read_uint(elf, layout.e_shnum, 2)?;
+    let shstrndx = read_uint(elf, layout.e_shstrndx, 2)?;
+    if shoff == 0 || shnum == 0 {
+        return Ok(None);
+    }
+
+    let header = |index: usize| -> Result<usize, GuestProgramError> {
+        index
+            .checked_mul(shentsize)
+            .and_then(|offset| offset.checked_add(shoff))
+            .ok_or_else(|| GuestProgramError::InvalidElf("section header overflow".to_string()))
+    };
+    let section = |index: usize| -> Result<&'a [u8], GuestProgramError> {
+        let base = header(index)?;
+        let offset = read_uint(
+            elf,
+            base.saturating_add(layout.sh_offset.0),
+            layout.sh_offset.1,
+        )?;
+        let size = read_uint(elf, base.saturating_add(layout.sh_size.0), layout.sh_size.1)?;
+        offset
+            .checked_add(size)
+            .and_then(|end| elf.get(offset..end))
+            .ok_or_else(|| {
+                GuestProgramError::InvalidElf(format!("section {index} is out of bounds"))
+            })
+    };
+
+    let names = section(shstrndx)?;
+    for index in 0..shnum {
+        let name_offset = read_uint(elf, header(index)?, 4)?;
+        let section_name = names
+            .get(name_offset..)
+            .and_then(|rest| rest.split(|byte| *byte == 0).next())
+            .unwrap_or_default();
+        if section_name == name.as_bytes() {
+            return section(index).map(Some);
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// riscv32 ELF with a `.ethrex_build_info` section, generated by `tests/fixtures/gen_build_info_elf.py`.
+    const FIXTURE_ELF32: &[u8] = include_bytes!("../tests/fixtures/build-info-riscv32.elf");
+    /// riscv64 ELF with a `.ethrex_build_info` section.
+    const FIXTURE_ELF64: &[u8] = include_bytes!("../tests/fixtures/build-info-riscv64.elf");
+    /// riscv32 ELF without the section.
+    const FIXTURE_NO_INFO: &[u8] = include_bytes!("../tests/fixtures/no-build-info-riscv32.elf");
+
+    fn fixture_info() -> ElfBuildInfo {
+        ElfBuildInfo {
+            git_commit: "0123456789abcdef0123456789abcdef01234567".to_string(),
+            cargo_lock_hash: "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
+                .to_string(),
+            toolchain: "sp1 v5.0.8".to_string(),
+            features: vec!["l2".to_string()],
+        }
+    }
+
+    #[test]
+    fn reads_build_info_from_elf32() {
+        assert_eq!(
+            read_build_info(FIXTURE_ELF32).unwrap(),
+            Some(fixture_info())
+        );
+    }
+
+    #[test]
+    fn reads_build_info_from_elf64() {
+        assert_eq!(
+            read_build_info(FIXTURE_ELF64).unwrap(),
+            Some(fixture_info())
+        );
+    }
+
+    #[test]
+    fn missing_section_is_none() {
+        assert_eq!(read_build_info(FIXTURE_NO_INFO).unwrap(), None);
+    }
+
+    #[test]
+    fn fixture_section_matches_encoder() {
+        let info = fixture_info();
+        let encoded = encode_build_info(
+            &info.git_commit,
+            &info.cargo_lock_hash,
+            &info.toolchain,
+            &info.features.join(","),
+        );
+        let section = find_section(FIXTURE_ELF32, BUILD_INFO_SECTION)
+            .unwrap()
+            .unwrap();
+        assert_eq!(section, encoded);
+    }
+
+    #[test]
+    fn encode_decode_roundtrip() {
+        let encoded = encode_build_info("abc-dirty", "", "risc0 v3.0.3", "l2,c-kzg");
+        let info = ElfBuildInfo::decode(&encoded).unwrap();
+        assert_eq!(info.git_commit, "abc-dirty");
+        assert_eq!(info.cargo_lock_hash, "");
+        assert_eq!(info.toolchain, "risc0 v3.0.3");
+        assert_eq!(info.features, vec!["l2", "c-kzg"]);
+    }
+
+    #[test]
+    fn decode_ignores_unknown_keys() {
+        let info = ElfBuildInfo::decode(b"toolchain=zisk\nrustc=1.88\n\0\0").unwrap();
+        assert_eq!(info.toolchain, "zisk");
+        assert!(info.features.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't fit")]
+    fn encode_rejects_oversized_fields() {
+        encode_build_info(&"f".repeat(BUILD_INFO_LEN), "", "", "");
+    }
+
+    #[test]
+    fn rejects_non_elf() {
+        assert!(read_build_info(b"not an elf").is_err());
+    }
+
+    #[test]
+    fn truncated_elf_never_panics() {
+        for len in 0..FIXTURE_ELF32.len() {
+            let _ = read_build_info(&FIXTURE_ELF32[..len]);
+        }
+        for len in 0..FIXTURE_ELF64.len() {
+            let _ = read_build_info(&FIXTURE_ELF64[..len]);
+        }
+    }
+
+    #[test]
+    fn elf_hash_is_sha256() {
+        assert_eq!(
+            hex_encode(&elf_hash(b"")),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+
+    fn hex_encode(bytes: &[u8]) -> String {
+        bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+    }
+}
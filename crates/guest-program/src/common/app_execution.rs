@@ -24,6 +24,7 @@ use ethrex_common::{Address, U256};
 
 use crate::l2::ProgramOutput;
 use crate::l2::messages::{compute_message_digests, get_batch_messages};
+use crate::report_cycles;
 
 use super::app_state::{AppState, AppStateError};
 use super::app_types::AppProgramInput;
@@ -87,6 +88,16 @@ pub trait AppCircuit {
     /// receipt root consistency.
     fn generate_logs(&self, from: Address, op: &AppOperation, result: &OperationResult)
     -> Vec<Log>;
+
+    /// Check the state the batch starts from, before executing it.
+    ///
+    /// Circuits that only accept state in a given format (e.g. the one a
+    /// [state migration](super::migration) produces) refuse the rest here.
+    /// Values must be read with [`incremental_mpt::verified_storage`]. The
+    /// default accepts any state.
+    fn check_state(&self, _state: &AppState) -> Result<(), AppCircuitError> {
+        Ok(())
+    }
 }
 
 /// An app-specific operation parsed from a transaction.
@@ -128,6 +139,8 @@ pub enum AppCircuitError {
     ContractCreationNotAllowed,
     #[error("Empty batch")]
     EmptyBatch,
+    #[error("Unsupported state: {0}")]
+    UnsupportedState(String),
 }
 
 // ── Main execution function ───────────────────────────────────────
@@ -157,6 +170,7 @@ pub fn execute_app_circuit<C: AppCircuit>(
 
     // 2. Verify all proofs against the previous state root.
     incremental_mpt::verify_state_proofs(&state)?;
+    circuit.check_state(&state)?;
 
     // 3. Execute each block.
     let mut all_receipts: Vec<Vec<Receipt>> = Vec::new();
@@ -340,7 +354,12 @@ pub fn execute_app_circuit<C: AppCircuit>(
     }
 
     // 4. Compute new state root (incremental MPT update).
-    let final_state_hash = incremental_mpt::compute_new_state_root(&state)?;
+    #[cfg(not(feature = "batched-mpt"))]
+    let compute_new_state_root = incremental_mpt::compute_new_state_root;
+    #[cfg(feature = "batched-mpt")]
+    let compute_new_state_root = incremental_mpt::compute_new_state_root_batched;
+    let final_state_hash =
+        report_cycles("compute_new_state_root", || compute_new_state_root(&state))?;
 
     // 5. Compute message digests (deposits, withdrawals).
     let batch_messages = get_batch_messages(&input.blocks, &all_receipts, input.chain_id);
@@ -386,6 +405,9 @@ pub fn execute_app_circuit<C: AppCircuit>(
         chain_id: U256::from(input.chain_id),
         non_privileged_count: U256::from(non_privileged_count),
         balance_diffs,
+        deposit_inclusion_timestamp: None,
+        ordering_commitment: None,
+        block_summaries: None,
     })
 }
 
@@ -1,5 +1,6 @@
 use ethrex_common::InvalidBlockError;
 use ethrex_common::types::block_execution_witness::GuestProgramStateError;
+use ethrex_rlp::error::RLPDecodeError;
 use ethrex_vm::EvmError;
 
 /// Errors that can occur during stateless block execution.
@@ -9,12 +10,18 @@ use ethrex_vm::EvmError;
 pub enum ExecutionError {
     #[error("Block validation error: {0}")]
     BlockValidation(InvalidBlockError),
-    #[error("Gas validation error: {0}")]
-    GasValidation(InvalidBlockError),
-    #[error("Requests root validation error: {0}")]
-    RequestsRootValidation(InvalidBlockError),
-    #[error("Receipts validation error: {0}")]
-    ReceiptsRootValidation(InvalidBlockError),
+    #[error("Post-execution validation error: {0}")]
+    PostExecutionValidation(InvalidBlockError),
+    #[error("Block access list validation error: {0}")]
+    BlockAccessListValidation(InvalidBlockError),
+    #[error("Expected one block access list per block ({expected}), got {actual}")]
+    BlockAccessListCount { expected: usize, actual: usize },
+    #[error("Failed to decode block access list: {0}")]
+    BlockAccessListDecode(RLPDecodeError),
+    #[error("Execution witness is missing {0}, which is in the block access list")]
+    MissingWitnessKey(String),
+    #[error("Execution witness has {0}, which is not in the block access list")]
+    UnusedWitnessKey(String),
     #[error("EVM error: {0}")]
     Evm(#[from] EvmError),
     #[error("Batch has no blocks")]
@@ -25,6 +32,8 @@ pub enum ExecutionError {
     InvalidInitialStateTrie,
     #[error("Invalid final state trie")]
     InvalidFinalStateTrie,
+    #[error("Invalid state trie after block {0}")]
+    InvalidBlockStateTrie(u64),
     #[error("Invalid hash of block {0} (it's not the parent hash of its successor)")]
     InvalidBlockHash(u64),
     #[error("Internal error: {0}")]
@@ -1,8 +1,6 @@
 use ethrex_common::types::block_execution_witness::{ExecutionWitness, GuestProgramState};
-use ethrex_common::types::{Block, Receipt};
-use ethrex_common::{
-    H256, U256, validate_block, validate_gas_used, validate_receipts_root, validate_requests_hash,
-};
+use ethrex_common::types::{AccountUpdate, Block, Receipt};
+use ethrex_common::{H256, U256, validate_block, validate_post_execution_commitments};
 use ethrex_vm::{Evm, GuestProgramStateWrapper, VmDatabase};
 
 use crate::common::ExecutionError;
@@ -24,6 +22,16 @@ pub struct BatchExecutionResult {
     pub chain_id: u64,
 }
 
+/// A block as executed by [`execute_blocks`], before its results are validated.
+pub struct BlockExecution<'a> {
+    pub block: &'a Block,
+    pub gas_used: u64,
+    pub receipts: &'a [Receipt],
+    pub account_updates: &'a [AccountUpdate],
+    /// State with the block's account updates already applied.
+    pub state: &'a GuestProgramStateWrapper,
+}
+
 /// Execute a batch of blocks using the provided VM factory.
 ///
 /// This is the core execution logic shared by both L1 and L2 programs.
@@ -34,15 +42,22 @@ pub struct BatchExecutionResult {
 /// * `blocks` - The blocks to execute
 /// * `execution_witness` - Database containing all data necessary to execute
 /// * `elasticity_multiplier` - Value used to calculate base fee
+/// * `validate_block_state_roots` - Whether to check the state root of every
+///   block header, not only the last one. Costs a state trie hash per block.
 /// * `vm_factory` - Closure that creates an EVM instance for a given block index
-pub fn execute_blocks<F>(
+/// * `on_block` - Closure called after each block is executed, before the
+///   block's results are validated. Used to inspect executions that fail.
+pub fn execute_blocks<F, O>(
     blocks: &[Block],
     execution_witness: ExecutionWitness,
     elasticity_multiplier: u64,
+    validate_block_state_roots: bool,
     vm_factory: F,
+    mut on_block: O,
 ) -> Result<BatchExecutionResult, ExecutionError>
 where
     F: Fn(&GuestProgramStateWrapper, usize) -> Result<Evm, ExecutionError>,
+    O: FnMut(BlockExecution<'_>) -> Result<(), ExecutionError>,
 {
     let chain_id = execution_witness.chain_config.chain_id;
 
@@ -113,8 +128,8 @@ where
         // Create VM using the provided factory
         let mut vm = report_cycles("setup_evm", || vm_factory(&wrapped_db, i))?;
 
-        // Execute block
-        let (result, _bal) = report_cycles("execute_block", || {
+        // Execute block, recording its access list (Amsterdam+)
+        let (result, block_access_list) = report_cycles("execute_block", || {
             vm.execute_block(block).map_err(ExecutionError::Evm)
         })?;
 
@@ -133,6 +148,25 @@ where
                 .map_err(ExecutionError::GuestProgramState)
         })?;
 
+        on_block(BlockExecution {
+            block,
+            gas_used: block_gas_used,
+            receipts: &receipts,
+            account_updates: &account_updates,
+            state: &wrapped_db,
+        })?;
+
+        if validate_block_state_roots {
+            let state_root = report_cycles("block_state_root", || {
+                wrapped_db
+                    .state_trie_root()
+                    .map_err(ExecutionError::GuestProgramState)
+            })?;
+            if state_root != block.header.state_root {
+                return Err(ExecutionError::InvalidBlockStateTrie(block.header.number));
+            }
+        }
+
         // Count non-privileged transactions
         non_privileged_count += block
             .body
@@ -141,19 +175,19 @@ where
             .filter(|tx| !tx.is_privileged())
             .count();
 
-        // Validate gas and receipts
-        report_cycles("validate_gas_and_receipts", || {
-            validate_gas_used(block_gas_used, &block.header).map_err(ExecutionError::GasValidation)
-        })?;
-
-        report_cycles("validate_receipts_root", || {
-            validate_receipts_root(&block.header, &receipts)
-                .map_err(ExecutionError::ReceiptsRootValidation)
-        })?;
-
-        report_cycles("validate_requests_hash", || {
-            validate_requests_hash(&block.header, &chain_config, &result.requests)
-                .map_err(ExecutionError::RequestsRootValidation)
+        // Validate the header commitments to the execution results, the same
+        // checks the node runs when importing the block
+        report_cycles("validate_post_execution_commitments", || {
+            validate_post_execution_commitments(
+                &block.header,
+                &chain_config,
+                block_gas_used,
+                &receipts,
+                &result.requests,
+                block_access_list.as_ref(),
+                block.body.transactions.len(),
+            )
+            .map_err(ExecutionError::PostExecutionValidation)
         })?;
 
         acc_receipts.push(receipts);
@@ -1,10 +1,8 @@
 //! Centralized constants for system contract addresses, event topics,
 //! and gas costs used by the common transaction handlers.
 
-use std::sync::LazyLock;
-
+use ethrex_common::types::events::{L1_MESSAGE_EVENT, WITHDRAWAL_INITIATED_EVENT};
 use ethrex_common::{Address, H160, H256};
-use ethrex_crypto::keccak::keccak_hash;
 
 // ── System contract addresses ─────────────────────────────────────
 
@@ -48,15 +46,13 @@ pub const ETH_TOKEN_ADDRESS: Address = H160([
 /// Storage slot 0 of L2ToL1Messenger holds `lastMessageId`.
 pub const MESSENGER_LAST_MESSAGE_ID_SLOT: H256 = H256([0u8; 32]);
 
-// ── Event topics (LazyLock) ───────────────────────────────────────
+// ── Event topics ──────────────────────────────────────────────────
 
 /// keccak256("WithdrawalInitiated(address,address,uint256)")
-pub static WITHDRAWAL_INITIATED_TOPIC: LazyLock<H256> =
-    LazyLock::new(|| H256::from(keccak_hash(b"WithdrawalInitiated(address,address,uint256)")));
+pub const WITHDRAWAL_INITIATED_TOPIC: H256 = WITHDRAWAL_INITIATED_EVENT.topic;
 
 /// keccak256("L1Message(address,bytes32,uint256)")
-pub static L1MESSAGE_TOPIC: LazyLock<H256> =
-    LazyLock::new(|| H256::from(keccak_hash(b"L1Message(address,bytes32,uint256)")));
+pub const L1MESSAGE_TOPIC: H256 = L1_MESSAGE_EVENT.topic;
 
 // Note: Gas constants (WITHDRAWAL_GAS, ETH_TRANSFER_GAS, SYSTEM_CALL_GAS)
 // were removed. The guest program now uses block header gas_used instead of
@@ -87,7 +87,7 @@ pub fn generate_withdrawal_logs(sender: Address, tx: &Transaction, message_id: U
     let log1 = Log {
         address: COMMON_BRIDGE_L2_ADDRESS,
         topics: vec![
-            *WITHDRAWAL_INITIATED_TOPIC,
+            WITHDRAWAL_INITIATED_TOPIC,
             addr_to_h256(sender),
             addr_to_h256(receiver_on_l1),
             u256_to_h256(value),
@@ -101,7 +101,7 @@ pub fn generate_withdrawal_logs(sender: Address, tx: &Transaction, message_id: U
     let log2 = Log {
         address: L2_TO_L1_MESSENGER_ADDRESS,
         topics: vec![
-            *L1MESSAGE_TOPIC,
+            L1MESSAGE_TOPIC,
             addr_to_h256(COMMON_BRIDGE_L2_ADDRESS),
             data_hash,
             u256_to_h256(message_id),
@@ -342,7 +342,7 @@ mod tests {
         let expected_selector =
             H256::from(keccak_hash(b"WithdrawalInitiated(address,address,uint256)"));
         assert_eq!(log1.topics[0], expected_selector);
-        assert_eq!(log1.topics[0], *WITHDRAWAL_INITIATED_TOPIC);
+        assert_eq!(log1.topics[0], WITHDRAWAL_INITIATED_TOPIC);
 
         let mut expected_sender = [0u8; 32];
         expected_sender[12..32].copy_from_slice(&[0xAA; 20]);
@@ -375,7 +375,7 @@ mod tests {
 
         let expected_selector = H256::from(keccak_hash(b"L1Message(address,bytes32,uint256)"));
         assert_eq!(log2.topics[0], expected_selector);
-        assert_eq!(log2.topics[0], *L1MESSAGE_TOPIC);
+        assert_eq!(log2.topics[0], L1MESSAGE_TOPIC);
 
         let mut expected_from = [0u8; 32];
         expected_from[12..32].copy_from_slice(COMMON_BRIDGE_L2_ADDRESS.as_bytes());
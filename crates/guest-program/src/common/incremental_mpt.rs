@@ -11,11 +11,15 @@
 //! - Number of keccak256 calls ≈ (# changed slots) × (trie depth ≈ 15)
 
 use ethrex_common::types::AccountState;
-use ethrex_common::{Address, H256};
+use ethrex_common::{Address, H256, U256};
 use ethrex_crypto::keccak::keccak_hash;
 use ethrex_rlp::decode::RLPDecode;
 use ethrex_rlp::encode::RLPEncode;
-use ethrex_trie::{EMPTY_TRIE_HASH, InMemoryTrieDB, Nibbles, Node, Trie, TrieDB};
+use ethrex_trie::node::{BranchNode, ExtensionNode, LeafNode};
+use ethrex_trie::{
+    EMPTY_TRIE_HASH, InMemoryTrieDB, Nibbles, Node, NodeHash, NodeRef, PathRLP, Trie, TrieDB,
+    ValueRLP,
+};
 
 use std::collections::BTreeMap;
 use std::sync::{Arc, Mutex};
@@ -48,17 +52,7 @@ pub fn verify_state_proofs(state: &AppState) -> Result<(), IncrementalMptError>
     }
 
     // Build a partial state trie from account proofs and verify root.
-    let state_trie = build_trie_from_proofs(
-        prev_root,
-        state
-            .account_proofs()
-            .iter()
-            .map(|ap| {
-                let path = keccak_hash(ap.address.as_bytes()).to_vec();
-                (path, ap.proof.clone())
-            })
-            .collect(),
-    )?;
+    let state_trie = build_state_trie(state)?;
 
     let computed_root = state_trie.hash_no_commit();
     if computed_root != prev_root {
@@ -126,6 +120,107 @@ pub fn verify_state_proofs(state: &AppState) -> Result<(), IncrementalMptError>
     Ok(())
 }
 
+/// Read a storage slot from its proof against the previous state root.
+///
+/// [`verify_state_proofs`] only checks that the proofs hash to the roots, not
+/// the values the prover put in the [`AppState`]. Checks that depend on a
+/// value before it is written must read it through here: every node on the
+/// path is checked against the hash its parent references. Slots that
+/// aren't in the trie read as zero.
+pub fn verified_storage(
+    state: &AppState,
+    address: Address,
+    slot: H256,
+) -> Result<U256, IncrementalMptError> {
+    let account_proof = state
+        .account_proofs()
+        .iter()
+        .find(|ap| ap.address == address)
+        .ok_or(IncrementalMptError::StorageProofMismatch { address, slot })?;
+    let Some(account_rlp) = verified_proof_value(
+        state.prev_state_root(),
+        &keccak_hash(address.as_bytes()),
+        &account_proof.proof,
+    )
+    .map_err(|_| {
+        IncrementalMptError::AccountProofMismatch(address, H256::zero(), state.prev_state_root())
+    })?
+    else {
+        return Ok(U256::zero());
+    };
+    let storage_root = AccountState::decode(&account_rlp)
+        .map_err(|e| IncrementalMptError::RlpDecode(e.to_string()))?
+        .storage_root;
+
+    let storage_proof = state
+        .storage_proofs()
+        .iter()
+        .find(|sp| sp.address == address && sp.slot == slot)
+        .ok_or(IncrementalMptError::StorageProofMismatch { address, slot })?;
+    let value = verified_proof_value(
+        storage_root,
+        &keccak_hash(slot.as_bytes()),
+        &storage_proof.storage_proof,
+    )
+    .map_err(|_| IncrementalMptError::StorageProofMismatch { address, slot })?;
+    match value {
+        Some(value_rlp) => {
+            U256::decode(&value_rlp).map_err(|e| IncrementalMptError::RlpDecode(e.to_string()))
+        }
+        None => Ok(U256::zero()),
+    }
+}
+
+/// Walk `proof` from `root` along `path`, checking each node against the
+/// hash its parent references, and return the value at `path`, if any.
+fn verified_proof_value(
+    root: H256,
+    path: &[u8],
+    proof: &[Vec<u8>],
+) -> Result<Option<ValueRLP>, IncrementalMptError> {
+    if root == *EMPTY_TRIE_HASH {
+        return Ok(None);
+    }
+    let nodes: BTreeMap<H256, &Vec<u8>> = proof
+        .iter()
+        .map(|node| (H256(keccak_hash(node)), node))
+        .collect();
+    let mut path = Nibbles::from_bytes(path);
+    let mut node_hash = NodeHash::Hashed(root);
+    loop {
+        let node = match node_hash {
+            NodeHash::Hashed(hash) => {
+                let node_rlp = nodes.get(&hash).ok_or_else(|| {
+                    IncrementalMptError::Trie(format!("proof is missing node {hash:?}"))
+                })?;
+                Node::decode(node_rlp)
+            }
+            NodeHash::Inline(_) => Node::decode(node_hash.as_ref()),
+        }
+        .map_err(|e| IncrementalMptError::RlpDecode(e.to_string()))?;
+
+        node_hash = match node {
+            Node::Branch(branch) => match path.next_choice() {
+                Some(choice) => {
+                    let child = &branch.choices[choice];
+                    if !child.is_valid() {
+                        return Ok(None);
+                    }
+                    child.compute_hash()
+                }
+                None => return Ok((!branch.value.is_empty()).then_some(branch.value)),
+            },
+            Node::Extension(extension) => {
+                if !path.skip_prefix(&extension.prefix) {
+                    return Ok(None);
+                }
+                extension.child.compute_hash()
+            }
+            Node::Leaf(leaf) => return Ok((leaf.partial == path).then_some(leaf.value)),
+        };
+    }
+}
+
 /// Compute the new state root after applying all changes from the AppState.
 ///
 /// This builds partial tries from the proofs, applies the dirty changes,
@@ -142,17 +237,7 @@ pub fn compute_new_state_root(state: &AppState) -> Result<H256, IncrementalMptEr
     }
 
     // 1. Build state trie from account proofs.
-    let mut state_trie = build_trie_from_proofs(
-        prev_root,
-        state
-            .account_proofs()
-            .iter()
-            .map(|ap| {
-                let path = keccak_hash(ap.address.as_bytes()).to_vec();
-                (path, ap.proof.clone())
-            })
-            .collect(),
-    )?;
+    let mut state_trie = build_state_trie(state)?;
 
     // 2. For each dirty account, update its storage trie and then the state trie.
     for (address, account_state) in state.dirty_accounts() {
@@ -160,20 +245,7 @@ pub fn compute_new_state_root(state: &AppState) -> Result<H256, IncrementalMptEr
 
         // If this account has dirty storage, update the storage trie.
         if let Some(dirty_slots) = state.dirty_storage().get(address) {
-            let storage_root = account_state.storage_root;
-
-            // Build storage trie from proofs for this account.
-            let storage_proofs: Vec<_> = state
-                .storage_proofs()
-                .iter()
-                .filter(|sp| sp.address == *address)
-                .map(|sp| {
-                    let slot_path = keccak_hash(sp.slot.as_bytes()).to_vec();
-                    (slot_path, sp.storage_proof.clone())
-                })
-                .collect();
-
-            let mut storage_trie = build_trie_from_proofs(storage_root, storage_proofs)?;
+            let mut storage_trie = build_storage_trie(state, *address, account_state.storage_root)?;
 
             // Apply dirty storage slot updates.
             for (slot, new_value) in dirty_slots {
@@ -206,6 +278,296 @@ pub fn compute_new_state_root(state: &AppState) -> Result<H256, IncrementalMptEr
     Ok(state_trie.hash_no_commit())
 }
 
+/// Same as [`compute_new_state_root`], but applies the updates of each trie
+/// in a single batch with [`apply_updates_batched`] instead of key by key.
+pub fn compute_new_state_root_batched(state: &AppState) -> Result<H256, IncrementalMptError> {
+    let prev_root = state.prev_state_root();
+
+    // Short-circuit: no changes means the state root is unchanged.
+    if state.dirty_accounts().peekable().peek().is_none() && state.account_proofs().is_empty() {
+        return Ok(prev_root);
+    }
+
+    let state_trie = build_state_trie(state)?;
+
+    let mut account_updates = Vec::new();
+    for (address, account_state) in state.dirty_accounts() {
+        let mut updated_account = *account_state;
+
+        if let Some(dirty_slots) = state.dirty_storage().get(address) {
+            let storage_trie = build_storage_trie(state, *address, account_state.storage_root)?;
+            let slot_updates = dirty_slots
+                .iter()
+                .map(|(slot, new_value)| {
+                    let slot_path = keccak_hash(slot.as_bytes()).to_vec();
+                    (
+                        slot_path,
+                        (!new_value.is_zero()).then(|| new_value.encode_to_vec()),
+                    )
+                })
+                .collect();
+            updated_account.storage_root = apply_updates_batched(&storage_trie, slot_updates)?;
+        }
+
+        let account_path = keccak_hash(address.as_bytes()).to_vec();
+        account_updates.push((account_path, Some(updated_account.encode_to_vec())));
+    }
+
+    apply_updates_batched(&state_trie, account_updates)
+}
+
+/// Build the partial state trie covering every account with a proof.
+fn build_state_trie(state: &AppState) -> Result<Trie, IncrementalMptError> {
+    build_trie_from_proofs(
+        state.prev_state_root(),
+        state
+            .account_proofs()
+            .iter()
+            .map(|ap| {
+                let path = keccak_hash(ap.address.as_bytes()).to_vec();
+                (path, ap.proof.clone())
+            })
+            .collect(),
+    )
+}
+
+/// Build the partial storage trie of `address` covering every slot with a proof.
+fn build_storage_trie(
+    state: &AppState,
+    address: Address,
+    storage_root: H256,
+) -> Result<Trie, IncrementalMptError> {
+    let storage_proofs: Vec<_> = state
+        .storage_proofs()
+        .iter()
+        .filter(|sp| sp.address == address)
+        .map(|sp| {
+            let slot_path = keccak_hash(sp.slot.as_bytes()).to_vec();
+            (slot_path, sp.storage_proof.clone())
+        })
+        .collect();
+
+    build_trie_from_proofs(storage_root, storage_proofs)
+}
+
+/// A pending update of a trie key: `Some` sets the RLP-encoded value, `None` removes the key.
+pub type TrieUpdate = (PathRLP, Option<ValueRLP>);
+
+/// Apply `updates` to a (partial) trie in a single walk and return the new root hash.
+///
+/// Updating key by key re-traverses the trie from the root for every key.
+/// Here the updates are sorted by path, so the walk visits every affected node
+/// once and keys sharing a prefix share its traversal. Only the subtries that
+/// contain updated keys are rebuilt, bottom-up; the rest keep their hash.
+///
+/// The resulting root is the same as inserting and removing the keys one by
+/// one, with later updates to the same key taking precedence.
+pub fn apply_updates_batched(
+    trie: &Trie,
+    updates: Vec<TrieUpdate>,
+) -> Result<H256, IncrementalMptError> {
+    let mut updates: Vec<(Nibbles, Option<ValueRLP>)> = updates
+        .into_iter()
+        .map(|(path, value)| (Nibbles::from_bytes(&path), value))
+        .collect();
+    // The sort is stable, so the last update of each key is the latest one.
+    updates.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut batch: Vec<(Nibbles, Option<ValueRLP>)> = Vec::with_capacity(updates.len());
+    for update in updates {
+        match batch.last_mut() {
+            Some(last) if last.0 == update.0 => *last = update,
+            _ => batch.push(update),
+        }
+    }
+
+    let root = trie
+        .root_node()
+        .map_err(|e| IncrementalMptError::Trie(e.to_string()))?;
+    let new_root = update_subtrie(trie.db(), root, &Nibbles::default(), &batch)?;
+    Ok(new_root.map_or(*EMPTY_TRIE_HASH, |node| node.compute_hash().finalize()))
+}
+
+/// Apply sorted `updates` to the subtrie rooted at `node`, found at `path`.
+///
+/// All the keys in `updates` start with `path`. Returns the new root of the
+/// subtrie, or `None` if it became empty.
+fn update_subtrie(
+    db: &dyn TrieDB,
+    node: Option<Arc<Node>>,
+    path: &Nibbles,
+    updates: &[(Nibbles, Option<ValueRLP>)],
+) -> Result<Option<Node>, IncrementalMptError> {
+    let Some(node) = node else {
+        // Removing keys from an empty subtrie is a no-op.
+        let entries: Vec<_> = updates
+            .iter()
+            .filter_map(|(key, value)| Some((key.clone(), value.clone()?)))
+            .collect();
+        return Ok(build_subtrie(&entries, path.len()));
+    };
+
+    match &*node {
+        Node::Branch(branch) => update_branch(db, (**branch).clone(), path, updates),
+        Node::Extension(extension) => {
+            // Expand the extension into a branch with a single child, which
+            // `update_branch` collapses back if no update diverges from it.
+            let mut choices = BranchNode::EMPTY_CHOICES;
+            let child = if extension.prefix.len() > 1 {
+                let prefix = extension.prefix.slice(1, extension.prefix.len());
+                Node::from(ExtensionNode::new(prefix, extension.child.clone())).into()
+            } else {
+                extension.child.clone()
+            };
+            choices[extension.prefix.at(0)] = child;
+            update_branch(db, BranchNode::new(choices), path, updates)
+        }
+        Node::Leaf(leaf) => {
+            // Rebuild the subtrie from the leaf and the updated keys.
+            let leaf_key = path.concat(&leaf.partial);
+            let mut entries: Vec<_> = updates
+                .iter()
+                .filter_map(|(key, value)| Some((key.clone(), value.clone()?)))
+                .collect();
+            if !updates.iter().any(|(key, _)| *key == leaf_key) {
+                let position = entries.partition_point(|(key, _)| *key < leaf_key);
+                entries.insert(position, (leaf_key, leaf.value.clone()));
+            }
+            Ok(build_subtrie(&entries, path.len()))
+        }
+    }
+}
+
+/// Apply sorted `updates` to the children of `branch` and normalize the result.
+fn update_branch(
+    db: &dyn TrieDB,
+    mut branch: BranchNode,
+    path: &Nibbles,
+    updates: &[(Nibbles, Option<ValueRLP>)],
+) -> Result<Option<Node>, IncrementalMptError> {
+    for (choice, group) in group_by_nibble(updates, path.len()) {
+        if choice == 16 {
+            // The key ends at this branch, so the update applies to its value.
+            branch.value = group
+                .last()
+                .and_then(|(_, value)| value.clone())
+                .unwrap_or_default();
+            continue;
+        }
+        let child_path = path.append_new(choice as u8);
+        let child = load_child(db, &branch.choices[choice], &child_path)?;
+        branch.choices[choice] = update_subtrie(db, child, &child_path, group)?
+            .map(NodeRef::from)
+            .unwrap_or_default();
+    }
+
+    // Restructure the branch the same way node removal does.
+    let mut children = branch
+        .choices
+        .iter()
+        .enumerate()
+        .filter(|(_, child)| child.is_valid());
+    let only_child = match (children.next(), children.next()) {
+        (Some((choice, child_ref)), None) => Some((choice, child_ref.clone())),
+        _ => None,
+    };
+    let has_children = branch.choices.iter().any(NodeRef::is_valid);
+
+    Ok(match (has_children, only_child, branch.value.is_empty()) {
+        (false, _, true) => None,
+        (false, _, false) => Some(LeafNode::new(Nibbles::from_hex(vec![16]), branch.value).into()),
+        (true, Some((choice, child_ref)), true) => {
+            let child_path = path.append_new(choice as u8);
+            let child = load_child(db, &child_ref, &child_path)?
+                .ok_or_else(|| missing_node(&child_path))?;
+            Some(match &*child {
+                Node::Branch(_) => {
+                    ExtensionNode::new(Nibbles::from_hex(vec![choice as u8]), child_ref).into()
+                }
+                Node::Extension(extension) => {
+                    let mut extension = extension.clone();
+                    extension.prefix.prepend(choice as u8);
+                    extension.into()
+                }
+                Node::Leaf(leaf) => {
+                    let mut leaf = leaf.clone();
+                    leaf.partial.prepend(choice as u8);
+                    leaf.into()
+                }
+            })
+        }
+        _ => Some(branch.into()),
+    })
+}
+
+/// Build a subtrie from scratch out of sorted, distinct `entries` sharing their first `depth` nibbles.
+fn build_subtrie(entries: &[(Nibbles, ValueRLP)], depth: usize) -> Option<Node> {
+    match entries {
+        [] => None,
+        [(key, value)] => Some(LeafNode::new(key.slice(depth, key.len()), value.clone()).into()),
+        [(first, _), .., (last, _)] => {
+            // Sorted keys share the prefix that the first and last keys share.
+            let shared = (depth..first.len().min(last.len()))
+                .take_while(|&i| first.at(i) == last.at(i))
+                .count();
+            if shared > 0 {
+                let prefix = first.slice(depth, depth + shared);
+                let child = build_subtrie(entries, depth + shared)?;
+                return Some(ExtensionNode::new(prefix, child.into()).into());
+            }
+
+            let mut branch = BranchNode::new(BranchNode::EMPTY_CHOICES);
+            for (choice, group) in group_by_nibble(entries, depth) {
+                if choice == 16 {
+                    // Only one key can end at this branch.
+                    branch.value = group.first().map(|(_, value)| value.clone())?;
+                } else if let Some(child) = build_subtrie(group, depth + 1) {
+                    branch.choices[choice] = child.into();
+                }
+            }
+            Some(branch.into())
+        }
+    }
+}
+
+/// Split sorted `items` into runs of keys with the same nibble at `depth`.
+fn group_by_nibble<V>(
+    mut items: &[(Nibbles, V)],
+    depth: usize,
+) -> impl Iterator<Item = (usize, &[(Nibbles, V)])> {
+    std::iter::from_fn(move || {
+        let choice = items.first()?.0.at(depth);
+        let len = items
+            .iter()
+            .take_while(|(key, _)| key.at(depth) == choice)
+            .count();
+        let (group, rest) = items.split_at(len);
+        items = rest;
+        Some((choice, group))
+    })
+}
+
+/// Load a child node, `None` if the choice is empty.
+fn load_child(
+    db: &dyn TrieDB,
+    child: &NodeRef,
+    path: &Nibbles,
+) -> Result<Option<Arc<Node>>, IncrementalMptError> {
+    if !child.is_valid() {
+        return Ok(None);
+    }
+    child
+        .get_node(db, path.clone())
+        .map_err(|e| IncrementalMptError::Trie(e.to_string()))?
+        .ok_or_else(|| missing_node(path))
+        .map(Some)
+}
+
+fn missing_node(path: &Nibbles) -> IncrementalMptError {
+    IncrementalMptError::Trie(format!(
+        "node at path {path:?} is not covered by the proofs"
+    ))
+}
+
 /// Build a partial trie from Merkle proof nodes.
 ///
 /// The proof nodes are RLP-encoded trie nodes along the path from root
@@ -274,7 +636,7 @@ mod tests {
     use super::*;
     use ethrex_common::{H160, U256};
 
-    use crate::common::app_types::AccountProof;
+    use crate::common::app_types::{AccountProof, StorageProof};
 
     fn test_address(byte: u8) -> Address {
         H160([byte; 20])
@@ -512,4 +874,178 @@ mod tests {
         let expected_root = expected_trie.hash_no_commit();
         assert_eq!(new_root, expected_root, "incremental root should match");
     }
+
+    /// Deterministic xorshift generator, so that failing seeds can be replayed.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+
+        /// A 32-byte key, drawn from a small alphabet every other seed so that
+        /// keys share long prefixes and the trie gets extensions and inline nodes.
+        fn key(&mut self, clustered: bool) -> Vec<u8> {
+            (0..32)
+                .map(|_| {
+                    if clustered {
+                        [0x00, 0x01, 0x10][self.below(3) as usize]
+                    } else {
+                        self.next() as u8
+                    }
+                })
+                .collect()
+        }
+
+        fn value(&mut self) -> Vec<u8> {
+            (0..=self.below(40)).map(|_| self.next() as u8).collect()
+        }
+    }
+
+    /// Compare the batched updates against key-by-key updates over random
+    /// tries and random sets of inserts, overwrites and removals.
+    #[test]
+    fn batched_updates_match_per_key_updates() {
+        for seed in 1..=500u64 {
+            let mut rng = Rng(seed);
+            let clustered = seed % 2 == 0;
+
+            let mut trie = Trie::empty_in_memory();
+            let keys: Vec<Vec<u8>> = (0..rng.below(48)).map(|_| rng.key(clustered)).collect();
+            for key in &keys {
+                trie.insert(key.clone(), rng.value()).unwrap();
+            }
+
+            let updates: Vec<TrieUpdate> = (0..=rng.below(32))
+                .map(|_| {
+                    let key = if !keys.is_empty() && rng.below(2) == 0 {
+                        keys[rng.below(keys.len() as u64) as usize].clone()
+                    } else {
+                        rng.key(clustered)
+                    };
+                    let value = (rng.below(3) != 0).then(|| rng.value());
+                    (key, value)
+                })
+                .collect();
+
+            let batched_root = apply_updates_batched(&trie, updates.clone()).unwrap();
+
+            for (key, value) in updates {
+                match value {
+                    Some(value) => trie.insert(key, value).unwrap(),
+                    None => {
+                        trie.remove(&key).unwrap();
+                    }
+                }
+            }
+            assert_eq!(batched_root, trie.hash_no_commit(), "seed {seed}");
+        }
+    }
+
+    /// Test that removing every key yields the empty root.
+    #[test]
+    fn batched_removal_of_all_keys_empties_trie() {
+        let mut trie = Trie::empty_in_memory();
+        let mut rng = Rng(7);
+        let keys: Vec<Vec<u8>> = (0..16).map(|_| rng.key(false)).collect();
+        for key in &keys {
+            trie.insert(key.clone(), rng.value()).unwrap();
+        }
+
+        let updates = keys.into_iter().map(|key| (key, None)).collect();
+        let root = apply_updates_batched(&trie, updates).unwrap();
+        assert_eq!(root, *EMPTY_TRIE_HASH);
+    }
+
+    /// Test the batched state root against the per-key one on partial tries
+    /// built from proofs, with account and storage updates.
+    #[test]
+    fn batched_state_root_matches_per_key_state_root() {
+        let mut rng = Rng(42);
+        let mut state_trie = Trie::empty_in_memory();
+        let mut account_proofs = Vec::new();
+        let mut storage_proofs = Vec::new();
+
+        let accounts: Vec<Address> = (0u8..12).map(test_address).collect();
+        let mut storage_tries = Vec::new();
+        for (i, address) in accounts.iter().enumerate() {
+            // Every account but the last has storage, the second to last a single slot.
+            let slot_count = match i {
+                11 => 0,
+                10 => 1,
+                _ => 8,
+            };
+            let mut storage_trie = Trie::empty_in_memory();
+            let slots: Vec<(H256, U256)> = (0..slot_count)
+                .map(|_| (H256::from_slice(&rng.key(false)), U256::from(rng.next())))
+                .collect();
+            for (slot, value) in &slots {
+                let slot_path = keccak_hash(slot.as_bytes()).to_vec();
+                storage_trie
+                    .insert(slot_path, value.encode_to_vec())
+                    .unwrap();
+            }
+
+            let mut account = test_account(i as u64, 1000);
+            account.storage_root = storage_trie.hash_no_commit();
+            let path = keccak_hash(address.as_bytes()).to_vec();
+            state_trie.insert(path, account.encode_to_vec()).unwrap();
+            storage_tries.push((account, slots, storage_trie));
+        }
+
+        for (address, (account, slots, storage_trie)) in accounts.iter().zip(&storage_tries) {
+            let path = keccak_hash(address.as_bytes()).to_vec();
+            let account_proof = state_trie.get_proof(&path).unwrap();
+            for (slot, value) in slots {
+                let slot_path = keccak_hash(slot.as_bytes()).to_vec();
+                storage_proofs.push(StorageProof {
+                    address: *address,
+                    slot: *slot,
+                    value: *value,
+                    account_proof: account_proof.clone(),
+                    storage_proof: storage_trie.get_proof(&slot_path).unwrap(),
+                });
+            }
+            account_proofs.push(AccountProof {
+                address: *address,
+                nonce: account.nonce,
+                balance: account.balance,
+                storage_root: account.storage_root,
+                code_hash: account.code_hash,
+                proof: account_proof,
+            });
+        }
+
+        let root = state_trie.hash_no_commit();
+        let mut state = AppState::from_proofs(root, account_proofs, storage_proofs);
+        verify_state_proofs(&state).unwrap();
+
+        for (i, (address, (_, slots, _))) in accounts.iter().zip(&storage_tries).enumerate() {
+            if i % 3 == 0 {
+                state.set_balance(*address, U256::from(rng.next())).unwrap();
+            }
+            for (slot, _) in slots.iter().step_by(2) {
+                state
+                    .set_storage(*address, *slot, U256::from(rng.next()))
+                    .unwrap();
+            }
+        }
+        // Clear the only slot of its account, emptying its storage trie.
+        let (_, single_slot, _) = &storage_tries[10];
+        state
+            .set_storage(accounts[10], single_slot[0].0, U256::zero())
+            .unwrap();
+
+        let per_key_root = compute_new_state_root(&state).unwrap();
+        let batched_root = compute_new_state_root_batched(&state).unwrap();
+        assert_ne!(batched_root, root);
+        assert_eq!(batched_root, per_key_root);
+    }
 }
@@ -35,6 +35,8 @@ pub enum InputConversionError {
     RlpDecode(String),
     #[error("Trie error: {0}")]
     TrieError(String),
+    #[error("Invalid fee config for block {0} of the batch: {1}")]
+    InvalidFeeConfig(usize, String),
 }
 
 /// Convert a `ProgramInput` into an `AppProgramInput` by extracting Merkle
@@ -52,6 +54,13 @@ pub fn convert_to_app_input(
 ) -> Result<AppProgramInput, InputConversionError> {
     let witness = &input.execution_witness;
 
+    // 0. Reject fee configs the guest program would fail to charge.
+    for (index, fee_config) in input.fee_configs.iter().enumerate() {
+        fee_config
+            .validate(&witness.chain_config)
+            .map_err(|e| InputConversionError::InvalidFeeConfig(index, e.to_string()))?;
+    }
+
     // 1. Rebuild state trie from the ExecutionWitness.
     let state_trie = rebuild_state_trie(witness)?;
     let prev_state_root = state_trie.hash_no_commit();
@@ -183,7 +192,7 @@ mod tests {
     use std::sync::Arc;
 
     use ethrex_common::types::block_execution_witness::ExecutionWitness;
-    use ethrex_common::types::{AccountState, ChainConfig};
+    use ethrex_common::types::{AccountState, ChainConfig, fee_config::FeeConfig};
     use ethrex_common::{H160, H256, U256};
     use ethrex_rlp::encode::RLPEncode as _;
     use ethrex_trie::{EMPTY_TRIE_HASH, Node, Trie};
@@ -246,6 +255,9 @@ mod tests {
             blob_commitment: [0u8; 48],
             blob_proof: [0u8; 48],
             native_token_scale_factor: None,
+            ordering_commitment: None,
+            publish_block_summaries: false,
+            commit_deposit_inclusion: false,
         }
     }
 
@@ -415,4 +427,25 @@ mod tests {
             "expected StorageTrieNotFound, got: {err}"
         );
     }
+
+    #[test]
+    fn test_invalid_fee_config_returns_error() {
+        let addr = test_address(0x01);
+        let account = test_account(0, 0);
+        let (witness, _) = make_witness_with_accounts(vec![(addr, account)]);
+
+        let mut input = make_program_input(witness);
+        input.fee_configs = vec![
+            FeeConfig::default(),
+            FeeConfig {
+                base_fee_vault: Some(Address::zero()),
+                ..Default::default()
+            },
+        ];
+        let err = convert_to_app_input(input, &[addr], &[]).unwrap_err();
+        assert!(
+            matches!(err, InputConversionError::InvalidFeeConfig(1, _)),
+            "expected InvalidFeeConfig for the second block, got: {err}"
+        );
+    }
 }
@@ -0,0 +1,235 @@
+//! State migration between versions of an app guest program.
+//!
+//! When an app changes its state format (e.g. zk-dex v0.1 → v0.2 with a new
+//! note format), the successor program can't consume the state root the
+//! predecessor left on-chain. A migration guest proves, with
+//! [`migrate_state`], the transition from the predecessor's final state to
+//! the successor's initial state. The prover only proves batches of the
+//! successor once that proof exists for the chain.
+//!
+//! ```text
+//! migrate_state(migration, input)
+//!   ├── Check the input comes from the declared predecessor
+//!   ├── Verify the proofs against the predecessor's final state root
+//!   ├── migration.migrate()  [app-specific]
+//!   ├── Compute the successor's initial state root (incremental MPT)
+//!   └── Return MigrationOutput
+//! ```
+
+use ethrex_common::H256;
+use ethrex_common::rkyv_utils::H256Wrapper;
+use ethrex_crypto::keccak::keccak_hash;
+use rkyv::{Archive, Deserialize as RDeserialize, Serialize as RSerialize};
+use serde::{Deserialize, Serialize};
+
+use super::app_state::{AppState, AppStateError};
+use super::app_types::{AccountProof, StorageProof};
+use super::incremental_mpt::{self, IncrementalMptError};
+
+/// Trait that each app-specific state migration must implement.
+pub trait StateMigration {
+    /// Program id and state version of the predecessor the migration reads.
+    fn from(&self) -> (&str, u8);
+
+    /// State version the migration produces.
+    fn to_version(&self) -> u8;
+
+    /// Rewrite the predecessor's state into the successor's format.
+    ///
+    /// Values the migration depends on must be read with
+    /// [`incremental_mpt::verified_storage`], the values in `state` aren't
+    /// checked against the proofs.
+    fn migrate(&self, state: &mut AppState) -> Result<(), MigrationError>;
+}
+
+/// Input of a migration guest: the final output of the predecessor and the
+/// proofs of the state the migration rewrites.
+#[derive(Serialize, Deserialize, RSerialize, RDeserialize, Archive, Clone, Debug)]
+pub struct MigrationInput {
+    /// Program id of the predecessor.
+    pub from_program_id: String,
+
+    /// State version of the predecessor.
+    pub from_version: u8,
+
+    /// Last batch proven by the predecessor.
+    pub boundary_batch: u64,
+
+    /// State root after the boundary batch.
+    #[rkyv(with = H256Wrapper)]
+    pub final_state_root: H256,
+
+    /// Account proofs for accounts whose storage the migration rewrites.
+    pub account_proofs: Vec<AccountProof>,
+
+    /// Merkle proofs for the storage slots the migration reads or rewrites.
+    pub storage_proofs: Vec<StorageProof>,
+}
+
+/// Public output of a migration guest.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MigrationOutput {
+    /// Keccak hash of the predecessor's program id.
+    pub from_program_id: H256,
+    pub from_version: u8,
+    pub to_version: u8,
+    /// Last batch proven by the predecessor.
+    pub boundary_batch: u64,
+    /// Final state root of the predecessor.
+    pub old_state_root: H256,
+    /// Initial state root of the successor.
+    pub new_state_root: H256,
+}
+
+/// Errors during a state migration.
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationError {
+    #[error("Migration reads {expected_id} v{expected_version}, got state of {id} v{version}")]
+    WrongPredecessor {
+        expected_id: String,
+        expected_version: u8,
+        id: String,
+        version: u8,
+    },
+    #[error("State error: {0}")]
+    State(#[from] AppStateError),
+    #[error("MPT error: {0}")]
+    Mpt(#[from] IncrementalMptError),
+    #[error("Invalid state: {0}")]
+    InvalidState(String),
+    #[error("Invalid migration output: {0}")]
+    InvalidOutput(String),
+}
+
+impl MigrationOutput {
+    /// Size of [`MigrationOutput::encode`]: six 32-byte words.
+    pub const ENCODED_LEN: usize = 6 * 32;
+
+    /// ABI-style encoding, each field left-padded to a 32-byte word.
+    pub fn encode(&self) -> Vec<u8> {
+        let word = |value: u64| H256::from_low_u64_be(value);
+        [
+            self.from_program_id,
+            word(self.from_version.into()),
+            word(self.to_version.into()),
+            word(self.boundary_batch),
+            self.old_state_root,
+            self.new_state_root,
+        ]
+        .iter()
+        .flat_map(|word| word.0)
+        .collect()
+    }
+
+    /// Decode the public values of a migration proof.
+    pub fn decode(bytes: &[u8]) -> Result<Self, MigrationError> {
+        if bytes.len() != Self::ENCODED_LEN {
+            return Err(MigrationError::InvalidOutput(format!(
+                "expected {} bytes, got {}",
+                Self::ENCODED_LEN,
+                bytes.len()
+            )));
+        }
+        let words: Vec<H256> = bytes.chunks_exact(32).map(H256::from_slice).collect();
+        let small = |index: usize, max: u64| {
+            let word = words[index];
+            if word.0[..24].iter().any(|byte| *byte != 0) || word.to_low_u64_be() > max {
+                return Err(MigrationError::InvalidOutput(format!(
+                    "word {index} out of range"
+                )));
+            }
+            Ok(word.to_low_u64_be())
+        };
+        Ok(Self {
+            from_program_id: words[0],
+            from_version: small(1, u8::MAX.into())? as u8,
+            to_version: small(2, u8::MAX.into())? as u8,
+            boundary_batch: small(3, u64::MAX)?,
+            old_state_root: words[4],
+            new_state_root: words[5],
+        })
+    }
+}
+
+/// Keccak hash of a program id, as committed in [`MigrationOutput`].
+pub fn program_id_hash(program_id: &str) -> H256 {
+    H256(keccak_hash(program_id.as_bytes()))
+}
+
+/// Migrate the predecessor's final state.
+///
+/// This is the entry point of migration guests. It refuses input that
+/// doesn't come from the predecessor `migration` reads, verifies the proofs
+/// against the predecessor's final state root and computes the successor's
+/// initial state root from the state the migration rewrote.
+pub fn migrate_state<M: StateMigration>(
+    migration: &M,
+    input: MigrationInput,
+) -> Result<MigrationOutput, MigrationError> {
+    let (expected_id, expected_version) = migration.from();
+    if input.from_program_id != expected_id || input.from_version != expected_version {
+        return Err(MigrationError::WrongPredecessor {
+            expected_id: expected_id.to_string(),
+            expected_version,
+            id: input.from_program_id,
+            version: input.from_version,
+        });
+    }
+
+    let mut state = AppState::from_proofs(
+        input.final_state_root,
+        input.account_proofs,
+        input.storage_proofs,
+    );
+    incremental_mpt::verify_state_proofs(&state)?;
+
+    migration.migrate(&mut state)?;
+
+    let new_state_root = incremental_mpt::compute_new_state_root(&state)?;
+    Ok(MigrationOutput {
+        from_program_id: program_id_hash(expected_id),
+        from_version: expected_version,
+        to_version: migration.to_version(),
+        boundary_batch: input.boundary_batch,
+        old_state_root: input.final_state_root,
+        new_state_root,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn output() -> MigrationOutput {
+        MigrationOutput {
+            from_program_id: program_id_hash("zk-dex"),
+            from_version: 1,
+            to_version: 2,
+            boundary_batch: 120,
+            old_state_root: H256([0xAA; 32]),
+            new_state_root: H256([0xBB; 32]),
+        }
+    }
+
+    #[test]
+    fn output_roundtrip() {
+        let encoded = output().encode();
+        assert_eq!(encoded.len(), MigrationOutput::ENCODED_LEN);
+        assert_eq!(MigrationOutput::decode(&encoded).unwrap(), output());
+    }
+
+    #[test]
+    fn decode_rejects_wrong_length() {
+        let encoded = output().encode();
+        assert!(MigrationOutput::decode(&encoded[..160]).is_err());
+        assert!(MigrationOutput::decode(&[encoded.clone(), vec![0]].concat()).is_err());
+    }
+
+    #[test]
+    fn decode_rejects_out_of_range_version() {
+        let mut encoded = output().encode();
+        // to_version is the third word, 0x0100 doesn't fit in a u8.
+        encoded[94] = 1;
+        assert!(MigrationOutput::decode(&encoded).is_err());
+    }
+}
@@ -8,6 +8,7 @@ pub mod handlers;
 pub mod incremental_mpt;
 #[cfg(feature = "l2")]
 pub mod input_converter;
+pub mod migration;
 
 pub use error::ExecutionError;
-pub use execution::{BatchExecutionResult, execute_blocks};
+pub use execution::{BatchExecutionResult, BlockExecution, execute_blocks};
@@ -0,0 +1,127 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use ethrex_common::constants::SYSTEM_ADDRESS;
+use ethrex_common::types::block_access_list::BlockAccessList;
+use ethrex_common::types::{Block, ChainConfig};
+use ethrex_common::{Address, H256, validate_block_access_list_hash};
+
+use crate::common::ExecutionError;
+
+/// Validates the block access lists provided along with the blocks (Amsterdam+).
+///
+/// There must be one per block, and each must hash to its block's `bal_hash`.
+/// Returns whether they cover every block of the batch, i.e. whether they can be
+/// used to validate the execution witness.
+pub fn validate_block_access_lists(
+    blocks: &[Block],
+    chain_config: &ChainConfig,
+    block_access_lists: &[BlockAccessList],
+) -> Result<bool, ExecutionError> {
+    if block_access_lists.len() != blocks.len() {
+        return Err(ExecutionError::BlockAccessListCount {
+            expected: blocks.len(),
+            actual: block_access_lists.len(),
+        });
+    }
+
+    let mut covers_batch = true;
+    for (block, block_access_list) in blocks.iter().zip(block_access_lists) {
+        // A pre-Amsterdam block has no bal_hash to check its access list against
+        if !chain_config.is_amsterdam_activated(block.header.timestamp) {
+            covers_batch = false;
+            continue;
+        }
+        validate_block_access_list_hash(
+            &block.header,
+            chain_config,
+            block_access_list,
+            block.body.transactions.len(),
+        )
+        .map_err(ExecutionError::BlockAccessListValidation)?;
+    }
+    Ok(covers_batch)
+}
+
+/// Validates that the execution witness keys are exactly the accounts and storage
+/// slots accessed by the batch, as listed in its block access lists.
+///
+/// `keys` is laid out as in [`ExecutionWitness::keys`]: each account address
+/// followed by the storage slots accessed in it. The system address is allowed
+/// in the witness without being in the block access lists, since it's only
+/// listed when its state changes.
+///
+/// [`ExecutionWitness::keys`]: ethrex_common::types::block_execution_witness::ExecutionWitness::keys
+pub fn validate_witness_keys(
+    keys: &[Vec<u8>],
+    block_access_lists: &[BlockAccessList],
+) -> Result<(), ExecutionError> {
+    let mut accessed: BTreeMap<Address, BTreeSet<H256>> = BTreeMap::new();
+    for account in block_access_lists
+        .iter()
+        .flat_map(BlockAccessList::accounts)
+    {
+        let slots = accessed.entry(account.address).or_default();
+        let written = account.storage_changes.iter().map(|change| change.slot);
+        let read = account.storage_reads.iter().copied();
+        slots.extend(written.chain(read).map(|slot| H256(slot.to_big_endian())));
+    }
+
+    let mut witnessed: BTreeMap<Address, BTreeSet<H256>> = BTreeMap::new();
+    let mut current_account = None;
+    for key in keys {
+        match key.len() {
+            20 => {
+                let address = Address::from_slice(key);
+                witnessed.entry(address).or_default();
+                current_account = Some(address);
+            }
+            32 => {
+                let address = current_account.ok_or_else(|| {
+                    ExecutionError::Internal(
+                        "Execution witness has a storage slot before any account".to_string(),
+                    )
+                })?;
+                witnessed
+                    .entry(address)
+                    .or_default()
+                    .insert(H256::from_slice(key));
+            }
+            len => {
+                return Err(ExecutionError::Internal(format!(
+                    "Execution witness has a key of invalid length {len}"
+                )));
+            }
+        }
+    }
+
+    for (address, slots) in &accessed {
+        let Some(witnessed_slots) = witnessed.get(address) else {
+            return Err(ExecutionError::MissingWitnessKey(format!(
+                "account {address:#x}"
+            )));
+        };
+        if let Some(slot) = slots.difference(witnessed_slots).next() {
+            return Err(ExecutionError::MissingWitnessKey(format!(
+                "slot {slot:#x} of account {address:#x}"
+            )));
+        }
+    }
+
+    for (address, witnessed_slots) in &witnessed {
+        let Some(slots) = accessed.get(address) else {
+            if *address == SYSTEM_ADDRESS && witnessed_slots.is_empty() {
+                continue;
+            }
+            return Err(ExecutionError::UnusedWitnessKey(format!(
+                "account {address:#x}"
+            )));
+        };
+        if let Some(slot) = witnessed_slots.difference(slots).next() {
+            return Err(ExecutionError::UnusedWitnessKey(format!(
+                "slot {slot:#x} of account {address:#x}"
+            )));
+        }
+    }
+
+    Ok(())
+}
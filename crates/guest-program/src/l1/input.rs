@@ -1,4 +1,7 @@
+use ethrex_common::rkyv_utils::VecVecWrapper;
+use ethrex_common::types::block_access_list::BlockAccessList;
 use ethrex_common::types::{Block, block_execution_witness::ExecutionWitness};
+use ethrex_rlp::encode::RLPEncode;
 use rkyv::{Archive, Deserialize as RDeserialize, Serialize as RSerialize};
 use serde::{Deserialize, Serialize};
 
@@ -9,6 +12,11 @@ pub struct ProgramInput {
     pub blocks: Vec<Block>,
     /// Database containing all the data necessary to execute.
     pub execution_witness: ExecutionWitness,
+    /// RLP-encoded block access lists of the blocks (Amsterdam+), one per block.
+    /// When provided, they're used to validate the execution witness before executing.
+    #[serde(default)]
+    #[rkyv(with = VecVecWrapper)]
+    pub block_access_lists: Vec<Vec<u8>>,
 }
 
 impl ProgramInput {
@@ -17,6 +25,16 @@ impl ProgramInput {
         Self {
             blocks,
             execution_witness,
+            block_access_lists: Vec::new(),
         }
     }
+
+    /// Sets the block access lists of the blocks, one per block.
+    pub fn with_block_access_lists(mut self, block_access_lists: &[BlockAccessList]) -> Self {
+        self.block_access_lists = block_access_lists
+            .iter()
+            .map(RLPEncode::encode_to_vec)
+            .collect();
+        self
+    }
 }
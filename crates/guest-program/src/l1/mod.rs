@@ -1,7 +1,9 @@
+mod block_access_list;
 mod input;
 mod output;
 mod program;
 
+pub use block_access_list::{validate_block_access_lists, validate_witness_keys};
 pub use input::ProgramInput;
 pub use output::ProgramOutput;
 pub use program::execution_program;
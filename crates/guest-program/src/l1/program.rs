@@ -1,9 +1,13 @@
 use ethrex_common::types::ELASTICITY_MULTIPLIER;
+use ethrex_common::types::block_access_list::BlockAccessList;
+use ethrex_rlp::decode::RLPDecode;
 use ethrex_vm::Evm;
 
 use crate::common::{BatchExecutionResult, ExecutionError, execute_blocks};
+use crate::l1::block_access_list::{validate_block_access_lists, validate_witness_keys};
 use crate::l1::input::ProgramInput;
 use crate::l1::output::ProgramOutput;
+use crate::report_cycles;
 
 /// Execute the L1 stateless validation program.
 ///
@@ -13,8 +17,33 @@ pub fn execution_program(input: ProgramInput) -> Result<ProgramOutput, Execution
     let ProgramInput {
         blocks,
         execution_witness,
+        block_access_lists,
     } = input;
 
+    // Validate the witness against the provided block access lists before
+    // executing, to detect missing or unused witness data early
+    if !block_access_lists.is_empty() {
+        let block_access_lists = report_cycles("decode_block_access_lists", || {
+            block_access_lists
+                .iter()
+                .map(|bytes| BlockAccessList::decode(bytes))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(ExecutionError::BlockAccessListDecode)
+        })?;
+        let covers_batch = report_cycles("validate_block_access_lists", || {
+            validate_block_access_lists(
+                &blocks,
+                &execution_witness.chain_config,
+                &block_access_lists,
+            )
+        })?;
+        if covers_batch {
+            report_cycles("validate_witness_keys", || {
+                validate_witness_keys(&execution_witness.keys, &block_access_lists)
+            })?;
+        }
+    }
+
     let BatchExecutionResult {
         receipts: _,
         initial_state_hash,
@@ -26,10 +55,12 @@ pub fn execution_program(input: ProgramInput) -> Result<ProgramOutput, Execution
         &blocks,
         execution_witness,
         ELASTICITY_MULTIPLIER,
+        false,
         |db, _| {
             // L1 VM factory - simple creation without fee configs
             Ok(Evm::new_for_l1(db.clone()))
         },
+        |_| Ok(()),
     )?;
 
     Ok(ProgramOutput {
@@ -11,6 +11,12 @@ use crate::l2::L2ExecutionError;
 
 /// Verify the KZG blob proof and return the versioned hash.
 ///
+/// `proof` is the single proof of the whole blob (a version 0 bundle's).
+/// Bundles published with EIP-7594 cell proofs commit to the blob with the
+/// same commitment, so the committer converts them with
+/// `BlobsBundle::into_version(0)` and the versioned hash is the same on both
+/// sides of the Osaka fork.
+///
 /// Returns `H256::zero()` for validium mode (when commitment and proof are all zeros).
 pub fn verify_blob(
     blocks: &[Block],
@@ -45,3 +51,40 @@ pub fn verify_blob(
 
     Ok(kzg_commitment_to_versioned_hash(&commitment))
 }
+
+#[cfg(all(test, feature = "c-kzg"))]
+mod tests {
+    use super::*;
+    use ethrex_common::types::BlobsBundle;
+
+    fn batch_bundle(blocks: &[Block], version: u8) -> BlobsBundle {
+        let mut blob_data = u64::try_from(blocks.len()).unwrap().to_be_bytes().to_vec();
+        for block in blocks {
+            blob_data.extend(block.encode_to_vec());
+        }
+        let blob = blob_from_bytes(Bytes::from(blob_data)).unwrap();
+        BlobsBundle::create_from_blobs(&vec![blob], Some(version)).unwrap()
+    }
+
+    #[test]
+    fn accepts_proofs_of_both_bundle_versions() {
+        let blocks = [Block::default()];
+        let v0 = batch_bundle(&blocks, 0);
+        let v1 = batch_bundle(&blocks, 1);
+        let expected = v1.generate_versioned_hashes()[0];
+
+        let v0_hash = verify_blob(&blocks, &[], v0.commitments[0], v0.proofs[0]).unwrap();
+        let v1 = v1.into_version(0).unwrap();
+        let v1_hash = verify_blob(&blocks, &[], v1.commitments[0], v1.proofs[0]).unwrap();
+
+        assert_eq!(v0_hash, expected);
+        assert_eq!(v1_hash, expected);
+    }
+
+    #[test]
+    fn rejects_a_cell_proof_as_blob_proof() {
+        let blocks = [Block::default()];
+        let v1 = batch_bundle(&blocks, 1);
+        assert!(verify_blob(&blocks, &[], v1.commitments[0], v1.proofs[0]).is_err());
+    }
+}
@@ -1,6 +1,6 @@
-use ethrex_common::InvalidBlockError;
 use ethrex_common::types::BlobsBundleError;
 use ethrex_common::types::block_execution_witness::GuestProgramStateError;
+use ethrex_common::{H256, InvalidBlockError};
 use ethrex_l2_common::privileged_transactions::PrivilegedTransactionError;
 use ethrex_vm::EvmError;
 
@@ -9,12 +9,8 @@ use ethrex_vm::EvmError;
 pub enum L2ExecutionError {
     #[error("Block validation error: {0}")]
     BlockValidation(InvalidBlockError),
-    #[error("Gas validation error: {0}")]
-    GasValidation(InvalidBlockError),
-    #[error("Requests root validation error: {0}")]
-    RequestsRootValidation(InvalidBlockError),
-    #[error("Receipts validation error: {0}")]
-    ReceiptsRootValidation(InvalidBlockError),
+    #[error("Post-execution validation error: {0}")]
+    PostExecutionValidation(InvalidBlockError),
     #[error("EVM error: {0}")]
     Evm(#[from] EvmError),
     #[error("Privileged transaction calculation error: {0}")]
@@ -35,10 +31,20 @@ pub enum L2ExecutionError {
     InvalidInitialStateTrie,
     #[error("Invalid final state trie")]
     InvalidFinalStateTrie,
+    #[error("Invalid state trie after block {0}")]
+    InvalidBlockStateTrie(u64),
     #[error("Invalid hash of block {0} (it's not the parent hash of its successor)")]
     InvalidBlockHash(u64),
     #[error("Failed to calculate privileged transaction hash")]
     InvalidPrivilegedTransaction,
+    #[error("Promised transaction {0:#x} is not included in the batch")]
+    MissingPromisedTransaction(H256),
+    #[error("Promised transaction {tx_hash:#x} is at position {actual} instead of {promised}")]
+    MisplacedPromisedTransaction {
+        tx_hash: H256,
+        promised: u64,
+        actual: u64,
+    },
     #[error("Internal error: {0}")]
     Internal(String),
     #[error("Failed to convert integer")]
@@ -50,18 +56,15 @@ impl From<crate::common::ExecutionError> for L2ExecutionError {
         use crate::common::ExecutionError;
         match err {
             ExecutionError::BlockValidation(e) => L2ExecutionError::BlockValidation(e),
-            ExecutionError::GasValidation(e) => L2ExecutionError::GasValidation(e),
-            ExecutionError::RequestsRootValidation(e) => {
-                L2ExecutionError::RequestsRootValidation(e)
-            }
-            ExecutionError::ReceiptsRootValidation(e) => {
-                L2ExecutionError::ReceiptsRootValidation(e)
+            ExecutionError::PostExecutionValidation(e) => {
+                L2ExecutionError::PostExecutionValidation(e)
             }
             ExecutionError::Evm(e) => L2ExecutionError::Evm(e),
             ExecutionError::EmptyBatch => L2ExecutionError::EmptyBatch,
             ExecutionError::GuestProgramState(e) => L2ExecutionError::GuestProgramState(e),
             ExecutionError::InvalidInitialStateTrie => L2ExecutionError::InvalidInitialStateTrie,
             ExecutionError::InvalidFinalStateTrie => L2ExecutionError::InvalidFinalStateTrie,
+            ExecutionError::InvalidBlockStateTrie(n) => L2ExecutionError::InvalidBlockStateTrie(n),
             ExecutionError::InvalidBlockHash(n) => L2ExecutionError::InvalidBlockHash(n),
             ExecutionError::Internal(s) => L2ExecutionError::Internal(s),
         }
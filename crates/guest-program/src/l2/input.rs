@@ -2,6 +2,7 @@ use ethrex_common::U256;
 use ethrex_common::types::{
     Block, blobs_bundle, block_execution_witness::ExecutionWitness, fee_config::FeeConfig,
 };
+use ethrex_l2_common::preconfirmations::OrderingCommitment;
 use rkyv::{Archive, Deserialize as RDeserialize, Serialize as RSerialize};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
@@ -29,6 +30,20 @@ pub struct ProgramInput {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[rkyv(with = ethrex_common::rkyv_utils::OptionU256Wrapper)]
     pub native_token_scale_factor: Option<U256>,
+    /// Ordering promised by the preconfirmation gateway. When present, the
+    /// promised transactions must be executed at exactly their positions.
+    #[serde(default)]
+    pub ordering_commitment: Option<OrderingCommitment>,
+    /// Whether to publish per-block summaries in the output. Off by default:
+    /// the OnChainProposer doesn't rebuild the section, and summarizing costs
+    /// a state trie hash per block.
+    #[serde(default)]
+    pub publish_block_summaries: bool,
+    /// Whether to commit the last block timestamp the OnChainProposer checks
+    /// privileged transaction deadlines against. Required by the EVM-L2
+    /// OnChainProposer; the based one doesn't rebuild the section.
+    #[serde(default)]
+    pub commit_deposit_inclusion: bool,
 }
 
 impl Default for ProgramInput {
@@ -41,6 +56,9 @@ impl Default for ProgramInput {
             blob_commitment: [0; 48],
             blob_proof: [0u8; 48],
             native_token_scale_factor: None,
+            ordering_commitment: None,
+            publish_block_summaries: false,
+            commit_deposit_inclusion: false,
         }
     }
 }
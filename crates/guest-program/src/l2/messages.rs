@@ -103,3 +103,70 @@ pub fn compute_message_digests(
         l2_in_message_rolling_hashes,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethrex_common::types::events::L1_MESSAGE_EVENT;
+    use ethrex_common::types::{Log, TxType};
+    use ethrex_l2_common::messages::MESSENGER_ADDRESS;
+    use serde::Deserialize;
+
+    /// Test vector shared with `ethrex_l2_common::withdrawals`, which rebuilds
+    /// this tree to prove withdrawals.
+    const WITHDRAWAL_TREE: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../fixtures/l2/withdrawal_tree.json"
+    ));
+
+    #[derive(Deserialize)]
+    struct WithdrawalTree {
+        /// Messages emitted by each receipt of each block.
+        blocks: Vec<Vec<Vec<L1Message>>>,
+        message_hashes: Vec<H256>,
+        root: H256,
+    }
+
+    fn messenger_log(msg: &L1Message) -> Log {
+        Log {
+            address: MESSENGER_ADDRESS,
+            topics: vec![
+                L1_MESSAGE_EVENT.topic,
+                H256::from(msg.from),
+                msg.data_hash,
+                H256(msg.message_id.to_big_endian()),
+            ],
+            data: Default::default(),
+        }
+    }
+
+    #[test]
+    fn l1_out_messages_root_matches_withdrawal_vector() {
+        let tree: WithdrawalTree = serde_json::from_str(WITHDRAWAL_TREE).unwrap();
+        let blocks = vec![Block::default(); tree.blocks.len()];
+        let receipts: Vec<Vec<Receipt>> = tree
+            .blocks
+            .iter()
+            .map(|block_messages| {
+                block_messages
+                    .iter()
+                    .map(|messages| {
+                        let logs = messages.iter().map(messenger_log).collect();
+                        Receipt::new(TxType::EIP1559, true, 0, logs)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let batch_messages = get_batch_messages(&blocks, &receipts, 1);
+        let hashes: Vec<H256> = batch_messages
+            .l1_out_messages
+            .iter()
+            .map(get_l1_message_hash)
+            .collect();
+        assert_eq!(hashes, tree.message_hashes);
+
+        let digests = compute_message_digests(&batch_messages).unwrap();
+        assert_eq!(digests.l1_out_messages_merkle_root, tree.root);
+    }
+}
@@ -2,10 +2,14 @@ pub(crate) mod blobs;
 mod error;
 mod input;
 pub(crate) mod messages;
+mod ordering;
 pub(crate) mod output;
 mod program;
 
 pub use error::L2ExecutionError;
 pub use input::ProgramInput;
-pub use output::ProgramOutput;
+pub use output::{
+    BlockSummary, MAX_BLOCK_SUMMARIES, ProgramOutput, block_summaries_hash, block_summaries_tag,
+    decode_block_summaries_hash, deposit_inclusion_tag, ordering_commitment_tag,
+};
 pub use program::execution_program;
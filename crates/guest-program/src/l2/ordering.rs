@@ -0,0 +1,123 @@
+use ethrex_common::H256;
+use ethrex_common::types::{Block, Transaction};
+use ethrex_l2_common::preconfirmations::OrderingCommitment;
+
+use crate::l2::error::L2ExecutionError;
+
+/// Verify that every transaction promised by the preconfirmation gateway is
+/// executed at exactly its promised position of the batch. Positions that
+/// were not promised may hold any transaction.
+///
+/// Returns the root of the ordering commitment.
+pub fn verify_ordering(
+    blocks: &[Block],
+    commitment: &OrderingCommitment,
+) -> Result<H256, L2ExecutionError> {
+    let batch_hashes: Vec<H256> = blocks
+        .iter()
+        .flat_map(|block| &block.body.transactions)
+        .map(Transaction::hash)
+        .collect();
+
+    for promise in &commitment.promised {
+        let position = batch_hashes
+            .iter()
+            .position(|hash| *hash == promise.tx_hash)
+            .ok_or(L2ExecutionError::MissingPromisedTransaction(
+                promise.tx_hash,
+            ))?;
+        let position = u64::try_from(position)?;
+        if position != promise.position {
+            return Err(L2ExecutionError::MisplacedPromisedTransaction {
+                tx_hash: promise.tx_hash,
+                promised: promise.position,
+                actual: position,
+            });
+        }
+    }
+
+    Ok(commitment.root())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethrex_common::types::{BlockBody, LegacyTransaction};
+    use ethrex_l2_common::preconfirmations::PromisedTransaction;
+
+    fn transaction(nonce: u64) -> Transaction {
+        Transaction::LegacyTransaction(LegacyTransaction {
+            nonce,
+            gas: 21000,
+            ..Default::default()
+        })
+    }
+
+    /// Two blocks holding the transactions with nonces 0..2 and 2..5.
+    fn batch() -> Vec<Block> {
+        [0..2, 2..5]
+            .into_iter()
+            .map(|nonces| Block {
+                body: BlockBody {
+                    transactions: nonces.map(transaction).collect(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn commitment(promised: &[(u64, u64)]) -> OrderingCommitment {
+        OrderingCommitment {
+            promised: promised
+                .iter()
+                .map(|(position, nonce)| PromisedTransaction {
+                    position: *position,
+                    tx_hash: transaction(*nonce).hash(),
+                })
+                .collect(),
+            signer_key_id: 1,
+        }
+    }
+
+    #[test]
+    fn promised_positions_are_respected() {
+        // Positions count across blocks, the unpromised ones are free
+        let commitment = commitment(&[(0, 0), (3, 3)]);
+        let root = verify_ordering(&batch(), &commitment).unwrap();
+        assert_eq!(root, commitment.root());
+    }
+
+    #[test]
+    fn misplaced_promised_transaction_is_rejected() {
+        let commitment = commitment(&[(0, 0), (2, 3)]);
+        let result = verify_ordering(&batch(), &commitment);
+        assert!(matches!(
+            result,
+            Err(L2ExecutionError::MisplacedPromisedTransaction {
+                promised: 2,
+                actual: 3,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn missing_promised_transaction_is_rejected() {
+        let commitment = commitment(&[(5, 5)]);
+        let result = verify_ordering(&batch(), &commitment);
+        let missing = transaction(5).hash();
+        assert!(matches!(
+            result,
+            Err(L2ExecutionError::MissingPromisedTransaction(hash)) if hash == missing
+        ));
+    }
+
+    #[test]
+    fn root_depends_on_positions() {
+        assert_ne!(
+            commitment(&[(0, 0), (3, 3)]).root(),
+            commitment(&[(3, 0), (0, 3)]).root()
+        );
+    }
+}
@@ -1,7 +1,95 @@
-use ethrex_common::types::balance_diff::BalanceDiff;
+use std::num::TryFromIntError;
+
+use ethrex_common::types::{Block, balance_diff::BalanceDiff};
+use ethrex_common::utils::keccak;
 use ethrex_common::{H256, U256};
 use serde::{Deserialize, Serialize};
 
+/// Maximum number of blocks summarized in the output. Larger batches leave
+/// the block summaries out.
+pub const MAX_BLOCK_SUMMARIES: usize = 256;
+
+/// Execution summary of a block of the batch. The output commits to the
+/// summaries of all its blocks, see [`block_summaries_hash`], so that monitors
+/// can check each block of a proven batch against their own execution.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockSummary {
+    pub gas_used: u64,
+    pub tx_count: u64,
+    pub receipts_root: H256,
+    pub state_root: H256,
+}
+
+impl BlockSummary {
+    /// Summary of `block` taken from its header, which the guest checks
+    /// against execution for summarized batches.
+    pub fn from_block(block: &Block) -> Result<Self, TryFromIntError> {
+        Ok(Self {
+            gas_used: block.header.gas_used,
+            tx_count: u64::try_from(block.body.transactions.len())?,
+            receipts_root: block.header.receipts_root,
+            state_root: block.header.state_root,
+        })
+    }
+
+    fn encode(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&U256::from(self.gas_used).to_big_endian());
+        buf.extend_from_slice(&U256::from(self.tx_count).to_big_endian());
+        buf.extend_from_slice(&self.receipts_root.to_fixed_bytes());
+        buf.extend_from_slice(&self.state_root.to_fixed_bytes());
+    }
+}
+
+/// Hash committed to by the block summaries section: keccak of the summary
+/// count followed by each summary, every value as a 32-byte word. The
+/// sequencer computes it from the batch's blocks and commits it to the
+/// OnChainProposer, which matches it against the proof.
+pub fn block_summaries_hash(summaries: &[BlockSummary]) -> H256 {
+    let mut encoded = U256::from(summaries.len()).to_big_endian().to_vec();
+    for summary in summaries {
+        summary.encode(&mut encoded);
+    }
+    keccak(encoded)
+}
+
+/// Read the block summaries hash of an encoded [`ProgramOutput`], such as the
+/// public values of a batch proof. The section is the last one of the output,
+/// so the rest of the layout doesn't need to be parsed.
+///
+/// Returns `None` for outputs without block summaries.
+pub fn decode_block_summaries_hash(encoded: &[u8]) -> Option<H256> {
+    let (rest, tag) = encoded.split_last_chunk::<32>()?;
+    if H256(*tag) != block_summaries_tag() {
+        return None;
+    }
+    let (_, hash) = rest.split_last_chunk::<32>()?;
+    Some(H256(*hash))
+}
+
+// The optional sections after the L2 in message rolling hashes each end with
+// their own tag word, so that outputs carrying different sections can't share
+// a layout and each section can be found from the end of the output.
+
+/// Last word of the deposit inclusion section. The OnChainProposer appends
+/// the same tag when it rebuilds the public inputs of an EVM-L2 batch.
+pub fn deposit_inclusion_tag() -> H256 {
+    keccak(b"ethrex.l2.deposit_inclusion.v1")
+}
+
+/// Last word of the ordering commitment section. The OnChainProposer appends
+/// the same tag when it rebuilds the public inputs of a batch committed with
+/// an ordering root.
+pub fn ordering_commitment_tag() -> H256 {
+    keccak(b"ethrex.l2.ordering_commitment.v1")
+}
+
+/// Last word of the block summaries section. The OnChainProposer appends the
+/// same tag when it rebuilds the public inputs of a batch committed with a
+/// block summaries hash.
+pub fn block_summaries_tag() -> H256 {
+    keccak(b"ethrex.l2.block_summaries.v1")
+}
+
 /// Output of the L2 stateless validation program.
 #[derive(Serialize, Deserialize)]
 pub struct ProgramOutput {
@@ -25,6 +113,27 @@ pub struct ProgramOutput {
     pub non_privileged_count: U256,
     /// Balance diffs for each chain ID.
     pub balance_diffs: Vec<BalanceDiff>,
+    /// Timestamp of the last block of the batch. The OnChainProposer rejects
+    /// the batch if a privileged transaction it didn't process had passed its
+    /// inclusion deadline by then. Encoded as the timestamp and
+    /// [`deposit_inclusion_tag`]. Only set when the input asks for it;
+    /// otherwise it's omitted from the encoding.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deposit_inclusion_timestamp: Option<u64>,
+    /// Preconfirmed ordering the batch respected, as `(root, signer_key_id)`,
+    /// matched on L1 against the ordering committed with the batch. Encoded as
+    /// the root, the key id and [`ordering_commitment_tag`]. Batches without
+    /// an ordering commitment leave it unset and omit it from the encoding.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ordering_commitment: Option<(H256, u64)>,
+    /// Execution summary of each block of the batch, in order. Encoded as
+    /// [`block_summaries_hash`] and [`block_summaries_tag`], always as the last
+    /// section so that [`decode_block_summaries_hash`] can find it from the
+    /// end. Only set when the input asks to publish block summaries and the
+    /// batch has at most [`MAX_BLOCK_SUMMARIES`] blocks; otherwise it's
+    /// omitted from the encoding.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_summaries: Option<Vec<BlockSummary>>,
 }
 
 impl ProgramOutput {
@@ -64,6 +173,22 @@ impl ProgramOutput {
             encoded.extend_from_slice(&hash.to_fixed_bytes());
         }
 
+        if let Some(timestamp) = self.deposit_inclusion_timestamp {
+            encoded.extend_from_slice(&U256::from(timestamp).to_big_endian());
+            encoded.extend_from_slice(&deposit_inclusion_tag().to_fixed_bytes());
+        }
+
+        if let Some((root, signer_key_id)) = self.ordering_commitment {
+            encoded.extend_from_slice(&root.to_fixed_bytes());
+            encoded.extend_from_slice(&U256::from(signer_key_id).to_big_endian());
+            encoded.extend_from_slice(&ordering_commitment_tag().to_fixed_bytes());
+        }
+
+        if let Some(block_summaries) = &self.block_summaries {
+            encoded.extend_from_slice(&block_summaries_hash(block_summaries).to_fixed_bytes());
+            encoded.extend_from_slice(&block_summaries_tag().to_fixed_bytes());
+        }
+
         encoded
     }
 }
@@ -87,6 +212,9 @@ mod tests {
             chain_id: U256::from(7u64),
             non_privileged_count: U256::from(8u64),
             balance_diffs: vec![],
+            deposit_inclusion_timestamp: None,
+            ordering_commitment: None,
+            block_summaries: None,
         };
         let encoded = output.encode();
         // 8 fixed fields × 32 bytes = 256 bytes (no variable parts).
@@ -123,6 +251,9 @@ mod tests {
                 value_per_token: vec![],
                 message_hashes: vec![],
             }],
+            deposit_inclusion_timestamp: None,
+            ordering_commitment: None,
+            block_summaries: None,
         };
         let encoded = output.encode();
         // 256 (fixed) + 32 (chain_id) + 32 (value) = 320
@@ -150,6 +281,9 @@ mod tests {
             chain_id: U256::zero(),
             non_privileged_count: U256::zero(),
             balance_diffs: vec![],
+            deposit_inclusion_timestamp: None,
+            ordering_commitment: None,
+            block_summaries: None,
         };
         let encoded = output.encode();
         // 256 (fixed) + 2 × (8 + 32) = 256 + 80 = 336
@@ -163,4 +297,146 @@ mod tests {
         assert_eq!(u64::from_be_bytes(chain_id_bytes2.try_into().unwrap()), 99);
         assert_eq!(&encoded[304..336], &[0xBB; 32]);
     }
+
+    /// Verify the ordering commitment is appended as its root, signer key id
+    /// and tag after the L2 in message rolling hashes.
+    #[test]
+    fn l2_encode_with_ordering_commitment() {
+        let output = ProgramOutput {
+            initial_state_hash: H256::zero(),
+            final_state_hash: H256::zero(),
+            l1_out_messages_merkle_root: H256::zero(),
+            l1_in_messages_rolling_hash: H256::zero(),
+            l2_in_message_rolling_hashes: vec![],
+            blob_versioned_hash: H256::zero(),
+            last_block_hash: H256::zero(),
+            chain_id: U256::zero(),
+            non_privileged_count: U256::zero(),
+            balance_diffs: vec![],
+            deposit_inclusion_timestamp: None,
+            ordering_commitment: Some((H256::from([0xCC; 32]), 3)),
+            block_summaries: None,
+        };
+        let encoded = output.encode();
+        // 256 (fixed) + 3 × 32 = 352
+        assert_eq!(encoded.len(), 352);
+        assert_eq!(&encoded[256..288], &[0xCC; 32]);
+        assert_eq!(U256::from_big_endian(&encoded[288..320]), U256::from(3u64));
+        assert_eq!(&encoded[320..352], ordering_commitment_tag().as_bytes());
+        assert_eq!(decode_block_summaries_hash(&encoded), None);
+    }
+
+    /// Verify the deposit inclusion timestamp and its tag come right after the
+    /// L2 in message rolling hashes, before the ordering commitment.
+    #[test]
+    fn l2_encode_with_deposit_inclusion_timestamp() {
+        let mut output = output_with_summaries(None);
+        output.deposit_inclusion_timestamp = Some(1_700_000_000);
+        output.ordering_commitment = Some((H256::from([0xCC; 32]), 3));
+        let encoded = output.encode();
+        // 296 (legacy layout) + 2 × 32 (deposit inclusion) + 3 × 32 (ordering)
+        assert_eq!(encoded.len(), 456);
+        assert_eq!(
+            U256::from_big_endian(&encoded[296..328]),
+            U256::from(1_700_000_000u64)
+        );
+        assert_eq!(&encoded[328..360], deposit_inclusion_tag().as_bytes());
+        assert_eq!(&encoded[360..392], &[0xCC; 32]);
+        assert_eq!(&encoded[424..456], ordering_commitment_tag().as_bytes());
+    }
+
+    fn output_with_summaries(block_summaries: Option<Vec<BlockSummary>>) -> ProgramOutput {
+        ProgramOutput {
+            initial_state_hash: H256::zero(),
+            final_state_hash: H256::zero(),
+            l1_out_messages_merkle_root: H256::zero(),
+            l1_in_messages_rolling_hash: H256::zero(),
+            l2_in_message_rolling_hashes: vec![(42u64, H256::from([0xAA; 32]))],
+            blob_versioned_hash: H256::zero(),
+            last_block_hash: H256::zero(),
+            chain_id: U256::zero(),
+            non_privileged_count: U256::zero(),
+            balance_diffs: vec![],
+            deposit_inclusion_timestamp: None,
+            ordering_commitment: None,
+            block_summaries,
+        }
+    }
+
+    fn summary(i: u8) -> BlockSummary {
+        BlockSummary {
+            gas_used: 21000 * u64::from(i),
+            tx_count: u64::from(i),
+            receipts_root: H256::from([i; 32]),
+            state_root: H256::from([0x80 | i; 32]),
+        }
+    }
+
+    /// Verify outputs without block summaries keep their layout and decode
+    /// as having none.
+    #[test]
+    fn l2_decode_legacy_output_has_no_block_summaries() {
+        let encoded = output_with_summaries(None).encode();
+        // 256 (fixed) + (8 + 32) = 296
+        assert_eq!(encoded.len(), 296);
+        assert_eq!(decode_block_summaries_hash(&encoded), None);
+        assert_eq!(decode_block_summaries_hash(&[]), None);
+    }
+
+    /// Verify the block summaries are appended after the optional sections as
+    /// their hash and the tag, and the hash decodes back from the end.
+    #[test]
+    fn l2_block_summaries_hash_round_trip() {
+        for count in [0u8, 1, 5] {
+            let summaries: Vec<BlockSummary> = (1..=count).map(summary).collect();
+            let encoded = output_with_summaries(Some(summaries.clone())).encode();
+            // 296 (legacy layout) + 32 (hash) + 32 (tag)
+            assert_eq!(encoded.len(), 296 + 64);
+            assert_eq!(&encoded[328..360], block_summaries_tag().as_bytes());
+            assert_eq!(
+                decode_block_summaries_hash(&encoded),
+                Some(block_summaries_hash(&summaries))
+            );
+        }
+    }
+
+    /// Verify the hash covers the count and each field of every summary, in
+    /// order, as 32-byte words.
+    #[test]
+    fn l2_block_summaries_hash_layout() {
+        let summaries = [summary(1), summary(2)];
+        let mut preimage = U256::from(2u64).to_big_endian().to_vec();
+        for summary in &summaries {
+            preimage.extend_from_slice(&U256::from(summary.gas_used).to_big_endian());
+            preimage.extend_from_slice(&U256::from(summary.tx_count).to_big_endian());
+            preimage.extend_from_slice(summary.receipts_root.as_bytes());
+            preimage.extend_from_slice(summary.state_root.as_bytes());
+        }
+        assert_eq!(preimage.len(), 32 + 2 * 128);
+        assert_eq!(block_summaries_hash(&summaries), keccak(preimage));
+        assert_ne!(
+            block_summaries_hash(&summaries),
+            block_summaries_hash(&[summary(2), summary(1)])
+        );
+        assert_ne!(
+            block_summaries_hash(&[]),
+            block_summaries_hash(&[summary(1)])
+        );
+    }
+
+    /// Verify the block summaries follow a tagged ordering commitment and
+    /// still decode from the end.
+    #[test]
+    fn l2_block_summaries_follow_ordering_commitment() {
+        let mut output = output_with_summaries(Some(vec![summary(1)]));
+        output.ordering_commitment = Some((H256::from([0xCC; 32]), 3));
+        let encoded = output.encode();
+        // 296 (legacy layout) + 3 × 32 (ordering) + 32 (hash) + 32 (tag)
+        assert_eq!(encoded.len(), 296 + 96 + 64);
+        assert_eq!(&encoded[360..392], ordering_commitment_tag().as_bytes());
+        assert_eq!(
+            decode_block_summaries_hash(&encoded),
+            Some(block_summaries_hash(&[summary(1)]))
+        );
+    }
 }
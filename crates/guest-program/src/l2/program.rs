@@ -6,7 +6,8 @@ use crate::l2::blobs::verify_blob;
 use crate::l2::error::L2ExecutionError;
 use crate::l2::input::ProgramInput;
 use crate::l2::messages::{compute_message_digests, get_batch_messages};
-use crate::l2::output::ProgramOutput;
+use crate::l2::ordering::verify_ordering;
+use crate::l2::output::{BlockSummary, MAX_BLOCK_SUMMARIES, ProgramOutput};
 
 /// Execute the L2 stateless validation program.
 ///
@@ -21,8 +22,14 @@ pub fn execution_program(input: ProgramInput) -> Result<ProgramOutput, L2Executi
         blob_commitment,
         blob_proof,
         native_token_scale_factor,
+        ordering_commitment,
+        publish_block_summaries,
+        commit_deposit_inclusion,
     } = input;
 
+    // Summarized blocks must have their state roots checked against execution
+    let summarize_blocks = publish_block_summaries && blocks.len() <= MAX_BLOCK_SUMMARIES;
+
     // Execute blocks using the common execution logic
     let BatchExecutionResult {
         receipts,
@@ -35,6 +42,7 @@ pub fn execution_program(input: ProgramInput) -> Result<ProgramOutput, L2Executi
         &blocks,
         execution_witness,
         elasticity_multiplier,
+        summarize_blocks,
         |db: &GuestProgramStateWrapper, i: usize| -> Result<Evm, crate::common::ExecutionError> {
             // L2 VM factory - requires fee config for each block
             let fee_config = fee_configs.get(i).cloned().ok_or_else(|| {
@@ -44,6 +52,7 @@ pub fn execution_program(input: ProgramInput) -> Result<ProgramOutput, L2Executi
             })?;
             Evm::new_for_l2(db.clone(), fee_config).map_err(crate::common::ExecutionError::Evm)
         },
+        |_| Ok(()),
     )?;
 
     // Extract and process messages
@@ -52,6 +61,34 @@ pub fn execution_program(input: ProgramInput) -> Result<ProgramOutput, L2Executi
     let balance_diffs =
         get_balance_diffs(&batch_messages.l2_out_messages, native_token_scale_factor);
 
+    // Verify the preconfirmed ordering, if the batch has one
+    let ordering_commitment = match ordering_commitment {
+        Some(commitment) => Some((
+            verify_ordering(&blocks, &commitment)?,
+            commitment.signer_key_id,
+        )),
+        None => None,
+    };
+
+    // Gas used, receipts root and state root were checked against each header
+    let block_summaries = summarize_blocks
+        .then(|| {
+            blocks
+                .iter()
+                .map(BlockSummary::from_block)
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?;
+
+    // Privileged transactions past their deadline at this time must have been
+    // processed, which the OnChainProposer checks against the bridge queue
+    let deposit_inclusion_timestamp = if commit_deposit_inclusion {
+        let last_block = blocks.last().ok_or(L2ExecutionError::EmptyBatch)?;
+        Some(last_block.header.timestamp)
+    } else {
+        None
+    };
+
     // Verify blob proof
     let blob_versioned_hash = verify_blob(&blocks, &fee_configs, blob_commitment, blob_proof)?;
 
@@ -66,5 +103,8 @@ pub fn execution_program(input: ProgramInput) -> Result<ProgramOutput, L2Executi
         chain_id: chain_id.into(),
         non_privileged_count,
         balance_diffs,
+        deposit_inclusion_timestamp,
+        ordering_commitment,
+        block_summaries,
     })
 }
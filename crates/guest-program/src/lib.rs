@@ -1,3 +1,4 @@
+pub mod build_info;
 pub mod common;
 pub mod l1;
 pub mod l2;
@@ -70,6 +71,24 @@ pub static ZKVM_SP1_ZK_DEX_ELF: &[u8] =
 #[cfg(any(clippy, not(feature = "sp1")))]
 pub const ZKVM_SP1_ZK_DEX_ELF: &[u8] = &[];
 
+#[cfg(all(not(clippy), feature = "sp1"))]
+pub static ZKVM_SP1_ZK_DEX_V2_ELF: &[u8] =
+    include_bytes!("../bin/sp1-zk-dex-v2/out/riscv32im-succinct-zkvm-elf");
+#[cfg(any(clippy, not(feature = "sp1")))]
+pub const ZKVM_SP1_ZK_DEX_V2_ELF: &[u8] = &[];
+
+#[cfg(all(not(clippy), feature = "sp1"))]
+pub static ZKVM_SP1_ZK_DEX_MIGRATION_ELF: &[u8] =
+    include_bytes!("../bin/sp1-zk-dex-migration/out/riscv32im-succinct-zkvm-elf");
+#[cfg(any(clippy, not(feature = "sp1")))]
+pub const ZKVM_SP1_ZK_DEX_MIGRATION_ELF: &[u8] = &[];
+
+#[cfg(all(not(clippy), feature = "sp1"))]
+pub static ZKVM_SP1_ZK_DEX_MIGRATION_VK: &str =
+    include_str!("../bin/sp1-zk-dex-migration/out/riscv32im-succinct-zkvm-vk-bn254");
+#[cfg(any(clippy, not(feature = "sp1")))]
+pub const ZKVM_SP1_ZK_DEX_MIGRATION_VK: &str = "";
+
 // Tokamon guest program ELFs (per-backend).
 
 #[cfg(all(not(clippy), feature = "sp1"))]
@@ -8,4 +8,5 @@ pub use bridge::BridgeGuestProgram;
 pub use dynamic::DynamicGuestProgram;
 pub use evm_l2::EvmL2GuestProgram;
 pub use tokamon::TokammonGuestProgram;
-pub use zk_dex::ZkDexGuestProgram;
+pub use zk_dex::migration::ZkDexMigrationGuestProgram;
+pub use zk_dex::{ZkDexGuestProgram, ZkDexV2GuestProgram};
@@ -19,6 +19,7 @@ use crate::common::app_execution::{AppCircuit, AppCircuitError, AppOperation, Op
 use crate::common::app_state::AppState;
 
 use super::events;
+use super::migration::note_format;
 use super::notes::{
     EMPTY_NOTE_HASH, NOTE_INVALID, NOTE_SPENT, NOTE_TRADING, NOTE_VALID, execute_convert_note,
     execute_liquidate, execute_mint, execute_spend,
@@ -130,6 +131,9 @@ fn transfer_event_topic() -> H256 {
 /// ZK-DEX circuit that implements [`AppCircuit`].
 pub struct DexCircuit {
     pub contract_address: Address,
+    /// Note format the DEX state must be in, see [`migration`](super::migration).
+    /// `None` accepts any state, as v0.1 did before note formats existed.
+    pub note_format: Option<u8>,
 }
 
 impl AppCircuit for DexCircuit {
@@ -231,6 +235,19 @@ impl AppCircuit for DexCircuit {
             _ => vec![],
         }
     }
+
+    fn check_state(&self, state: &AppState) -> Result<(), AppCircuitError> {
+        let Some(required) = self.note_format else {
+            return Ok(());
+        };
+        let format = note_format(state, self.contract_address)?;
+        if format != U256::from(required) {
+            return Err(AppCircuitError::UnsupportedState(format!(
+                "DEX state is in note format {format}, the circuit reads note format {required}"
+            )));
+        }
+        Ok(())
+    }
 }
 
 // ── Transfer (existing) ─────────────────────────────────────────
@@ -789,6 +806,7 @@ mod tests {
     fn make_circuit() -> DexCircuit {
         DexCircuit {
             contract_address: dex_address(),
+            note_format: None,
         }
     }
 
@@ -0,0 +1,326 @@
+//! Migration of the DEX state from note format 1 (v0.1) to note format 2 (v0.2).
+//!
+//! v0.2 records the note format of the DEX state at
+//! [`note_format_slot`](super::storage::note_format_slot) and refuses state in
+//! another format, so it can't take over the state root v0.1 committed. The
+//! migration guest (`bin/sp1-zk-dex-migration`) proves the rewrite of that
+//! state with [`NoteFormatMigration`], and its output is v0.2's initial
+//! state root.
+
+use ethrex_common::{Address, U256};
+
+use crate::common::app_state::AppState;
+use crate::common::incremental_mpt::{IncrementalMptError, verified_storage};
+use crate::common::migration::{MigrationError, StateMigration};
+use crate::traits::{GuestProgram, ResourceLimits, backends};
+
+use super::storage::note_format_slot;
+
+/// Note format of v0.1, implied by an unset note format slot.
+pub const NOTE_FORMAT_V1: u8 = 1;
+
+/// Note format of v0.2.
+pub const NOTE_FORMAT_V2: u8 = 2;
+
+/// Note format of the DEX state, read from its proof.
+pub fn note_format(state: &AppState, contract: Address) -> Result<U256, IncrementalMptError> {
+    let format = verified_storage(state, contract, note_format_slot())?;
+    if format.is_zero() {
+        Ok(U256::from(NOTE_FORMAT_V1))
+    } else {
+        Ok(format)
+    }
+}
+
+/// Rewrites the final state of zk-dex v0.1 into note format 2.
+pub struct NoteFormatMigration {
+    pub contract_address: Address,
+}
+
+impl StateMigration for NoteFormatMigration {
+    fn from(&self) -> (&str, u8) {
+        ("zk-dex", NOTE_FORMAT_V1)
+    }
+
+    fn to_version(&self) -> u8 {
+        NOTE_FORMAT_V2
+    }
+
+    fn migrate(&self, state: &mut AppState) -> Result<(), MigrationError> {
+        let format = note_format(state, self.contract_address)?;
+        if format != U256::from(NOTE_FORMAT_V1) {
+            return Err(MigrationError::InvalidState(format!(
+                "DEX state is in note format {format}, expected {NOTE_FORMAT_V1}"
+            )));
+        }
+        state.set_storage(
+            self.contract_address,
+            note_format_slot(),
+            U256::from(NOTE_FORMAT_V2),
+        )?;
+        Ok(())
+    }
+}
+
+/// Guest program proving [`NoteFormatMigration`].
+///
+/// It has its own ELF and verification key, so a migration proof can't be
+/// mistaken for a batch proof of either zk-dex version. It is reached
+/// through [`GuestProgram::migration_program`] of
+/// [`ZkDexV2GuestProgram`](super::ZkDexV2GuestProgram), not registered on its
+/// own.
+pub struct ZkDexMigrationGuestProgram;
+
+impl GuestProgram for ZkDexMigrationGuestProgram {
+    fn program_id(&self) -> &str {
+        "zk-dex-migration"
+    }
+
+    fn elf(&self, backend: &str) -> Option<&[u8]> {
+        match backend {
+            backends::SP1 => {
+                super::ZkDexGuestProgram::non_empty(crate::ZKVM_SP1_ZK_DEX_MIGRATION_ELF)
+            }
+            _ => None,
+        }
+    }
+
+    fn vk_bytes(&self, backend: &str) -> Option<Vec<u8>> {
+        match backend {
+            // The `vk.bytes32()` hash written by build.rs.
+            backends::SP1 => {
+                let vk = crate::ZKVM_SP1_ZK_DEX_MIGRATION_VK.trim();
+                if vk.is_empty() {
+                    None
+                } else {
+                    Some(vk.as_bytes().to_vec())
+                }
+            }
+            _ => None,
+        }
+    }
+
+    fn program_type_id(&self) -> u8 {
+        2 // ZK-DEX
+    }
+
+    fn resource_limits(&self) -> ResourceLimits {
+        ResourceLimits {
+            max_input_bytes: Some(16 * 1024 * 1024), // 16 MB
+            max_proving_duration: Some(std::time::Duration::from_secs(1800)), // 30 minutes
+        }
+    }
+
+    fn version(&self) -> &str {
+        "0.2.0"
+    }
+
+    fn state_version(&self) -> u8 {
+        NOTE_FORMAT_V2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ethrex_common::types::AccountState;
+    use ethrex_common::{H160, H256};
+    use ethrex_crypto::keccak::keccak_hash;
+    use ethrex_rlp::encode::RLPEncode;
+    use ethrex_trie::Trie;
+
+    use super::*;
+    use crate::common::app_execution::{AppCircuit, AppCircuitError};
+    use crate::common::app_types::{AccountProof, StorageProof};
+    use crate::common::migration::{MigrationInput, migrate_state, program_id_hash};
+    use crate::programs::zk_dex::circuit::DexCircuit;
+    use crate::programs::zk_dex::notes::NOTE_VALID;
+    use crate::programs::zk_dex::storage::note_state_slot;
+
+    fn dex_address() -> Address {
+        H160([0xDE; 20])
+    }
+
+    fn note_hash() -> H256 {
+        H256([0x11; 32])
+    }
+
+    fn storage_trie(format: Option<u8>) -> Trie {
+        let mut trie = Trie::empty_in_memory();
+        let mut insert = |slot: H256, value: U256| {
+            trie.insert(keccak_hash(slot.as_bytes()).to_vec(), value.encode_to_vec())
+                .unwrap();
+        };
+        insert(note_state_slot(note_hash()), NOTE_VALID);
+        if let Some(format) = format {
+            insert(note_format_slot(), U256::from(format));
+        }
+        trie
+    }
+
+    fn dex_account(storage_root: H256) -> AccountState {
+        AccountState {
+            nonce: 1,
+            storage_root,
+            ..Default::default()
+        }
+    }
+
+    fn state_trie(dex_storage_root: H256) -> Trie {
+        let mut trie = Trie::empty_in_memory();
+        trie.insert(
+            keccak_hash(dex_address().as_bytes()).to_vec(),
+            dex_account(dex_storage_root).encode_to_vec(),
+        )
+        .unwrap();
+        trie.insert(
+            keccak_hash(H160([0x01; 20]).as_bytes()).to_vec(),
+            AccountState::default().encode_to_vec(),
+        )
+        .unwrap();
+        trie
+    }
+
+    /// Root and proofs of a DEX state whose note format slot holds `format`
+    /// (unset when `None`). The note format slot is claimed to hold
+    /// `claimed_format`, which may differ from the trie.
+    fn dex_state(
+        format: Option<u8>,
+        claimed_format: U256,
+    ) -> (H256, Vec<AccountProof>, Vec<StorageProof>) {
+        let storage = storage_trie(format);
+        let storage_root = storage.hash_no_commit();
+        let state = state_trie(storage_root);
+        let root = state.hash_no_commit();
+
+        let account_path = keccak_hash(dex_address().as_bytes()).to_vec();
+        let account_proof = state.get_proof(&account_path).unwrap();
+        let account = dex_account(storage_root);
+        let account_proofs = vec![AccountProof {
+            address: dex_address(),
+            nonce: account.nonce,
+            balance: account.balance,
+            storage_root,
+            code_hash: account.code_hash,
+            proof: account_proof.clone(),
+        }];
+        let slot = note_format_slot();
+        let storage_proofs = vec![StorageProof {
+            address: dex_address(),
+            slot,
+            value: claimed_format,
+            account_proof,
+            storage_proof: storage
+                .get_proof(&keccak_hash(slot.as_bytes()).to_vec())
+                .unwrap(),
+        }];
+        (root, account_proofs, storage_proofs)
+    }
+
+    fn input(from_program_id: &str, format: Option<u8>, claimed_format: U256) -> MigrationInput {
+        let (root, account_proofs, storage_proofs) = dex_state(format, claimed_format);
+        MigrationInput {
+            from_program_id: from_program_id.to_string(),
+            from_version: NOTE_FORMAT_V1,
+            boundary_batch: 120,
+            final_state_root: root,
+            account_proofs,
+            storage_proofs,
+        }
+    }
+
+    fn migration() -> NoteFormatMigration {
+        NoteFormatMigration {
+            contract_address: dex_address(),
+        }
+    }
+
+    #[test]
+    fn migrates_note_format_1_state() {
+        let input = input("zk-dex", None, U256::zero());
+        let old_root = input.final_state_root;
+
+        let output = migrate_state(&migration(), input).unwrap();
+
+        let expected_root =
+            state_trie(storage_trie(Some(NOTE_FORMAT_V2)).hash_no_commit()).hash_no_commit();
+        assert_eq!(output.from_program_id, program_id_hash("zk-dex"));
+        assert_eq!(output.from_version, NOTE_FORMAT_V1);
+        assert_eq!(output.to_version, NOTE_FORMAT_V2);
+        assert_eq!(output.boundary_batch, 120);
+        assert_eq!(output.old_state_root, old_root);
+        assert_eq!(output.new_state_root, expected_root);
+    }
+
+    #[test]
+    fn refuses_migrated_state() {
+        let input = input("zk-dex", Some(NOTE_FORMAT_V2), U256::from(NOTE_FORMAT_V2));
+        let err = migrate_state(&migration(), input).unwrap_err();
+        assert!(matches!(err, MigrationError::InvalidState(_)), "{err}");
+    }
+
+    #[test]
+    fn refuses_forged_note_format() {
+        // The proof says the state was already migrated, the input claims it wasn't.
+        let input = input("zk-dex", Some(NOTE_FORMAT_V2), U256::zero());
+        let err = migrate_state(&migration(), input).unwrap_err();
+        assert!(matches!(err, MigrationError::InvalidState(_)), "{err}");
+    }
+
+    #[test]
+    fn refuses_wrong_predecessor() {
+        let input = input("tokamon", None, U256::zero());
+        let err = migrate_state(&migration(), input).unwrap_err();
+        assert!(
+            matches!(err, MigrationError::WrongPredecessor { .. }),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn refuses_proofs_of_another_root() {
+        let mut input = input("zk-dex", None, U256::zero());
+        input.final_state_root = H256([0xAB; 32]);
+        let err = migrate_state(&migration(), input).unwrap_err();
+        assert!(matches!(err, MigrationError::Mpt(_)), "{err}");
+    }
+
+    #[test]
+    fn v2_circuit_refuses_note_format_1_state() {
+        let circuit = DexCircuit {
+            contract_address: dex_address(),
+            note_format: Some(NOTE_FORMAT_V2),
+        };
+
+        let (root, account_proofs, storage_proofs) = dex_state(None, U256::zero());
+        let state = AppState::from_proofs(root, account_proofs, storage_proofs);
+        let err = circuit.check_state(&state).unwrap_err();
+        assert!(matches!(err, AppCircuitError::UnsupportedState(_)), "{err}");
+
+        let (root, account_proofs, storage_proofs) =
+            dex_state(Some(NOTE_FORMAT_V2), U256::from(NOTE_FORMAT_V2));
+        let state = AppState::from_proofs(root, account_proofs, storage_proofs);
+        circuit.check_state(&state).unwrap();
+    }
+
+    #[test]
+    fn v1_circuit_accepts_any_state() {
+        let circuit = DexCircuit {
+            contract_address: dex_address(),
+            note_format: None,
+        };
+        let (root, account_proofs, storage_proofs) = dex_state(None, U256::zero());
+        let state = AppState::from_proofs(root, account_proofs, storage_proofs);
+        circuit.check_state(&state).unwrap();
+    }
+
+    #[test]
+    fn migration_program_has_its_own_id() {
+        let program = ZkDexMigrationGuestProgram;
+        assert_eq!(program.program_id(), "zk-dex-migration");
+        assert_eq!(program.state_version(), NOTE_FORMAT_V2);
+        assert!(program.elf(backends::RISC0).is_none());
+        if crate::ZKVM_SP1_ZK_DEX_MIGRATION_ELF.is_empty() {
+            assert!(program.elf(backends::SP1).is_none());
+        }
+    }
+}
@@ -1,5 +1,6 @@
 pub mod circuit;
 pub mod events;
+pub mod migration;
 pub mod notes;
 pub mod orders;
 pub mod storage;
@@ -53,36 +54,7 @@ impl GuestProgram for ZkDexGuestProgram {
     }
 
     fn serialize_input(&self, raw_input: &[u8]) -> Result<Vec<u8>, GuestProgramError> {
-        #[cfg(feature = "l2")]
-        {
-            use crate::common::input_converter::convert_to_app_input;
-            use crate::l2::ProgramInput;
-            use rkyv::rancor::Error as RkyvError;
-
-            let program_input: ProgramInput =
-                rkyv::from_bytes::<ProgramInput, RkyvError>(raw_input)
-                    .map_err(|e| GuestProgramError::Serialization(e.to_string()))?;
-
-            let (accounts, storage_slots) = analyze_zk_dex_transactions(
-                &program_input.blocks,
-                DEX_CONTRACT_ADDRESS,
-                &program_input.fee_configs,
-                &program_input.execution_witness,
-            )
-            .map_err(|e| GuestProgramError::Internal(e.to_string()))?;
-
-            let app_input = convert_to_app_input(program_input, &accounts, &storage_slots)
-                .map_err(|e| GuestProgramError::Internal(e.to_string()))?;
-
-            let bytes = rkyv::to_bytes::<RkyvError>(&app_input)
-                .map_err(|e| GuestProgramError::Serialization(e.to_string()))?;
-            Ok(bytes.to_vec())
-        }
-
-        #[cfg(not(feature = "l2"))]
-        {
-            Ok(raw_input.to_vec())
-        }
+        serialize_zk_dex_input(raw_input, false)
     }
 
     fn encode_output(&self, raw_output: &[u8]) -> Result<Vec<u8>, GuestProgramError> {
@@ -101,6 +73,102 @@ impl GuestProgram for ZkDexGuestProgram {
     }
 }
 
+/// ZK-DEX v0.2, with note format 2.
+///
+/// It refuses DEX state in another note format, so it takes over from v0.1
+/// through the state [`migration`] proven by its
+/// [`migration_program`](GuestProgram::migration_program). The prover refuses
+/// its batches until that proof exists for the chain.
+pub struct ZkDexV2GuestProgram;
+
+impl GuestProgram for ZkDexV2GuestProgram {
+    fn program_id(&self) -> &str {
+        "zk-dex-v2"
+    }
+
+    fn elf(&self, backend: &str) -> Option<&[u8]> {
+        match backend {
+            backends::SP1 => ZkDexGuestProgram::non_empty(crate::ZKVM_SP1_ZK_DEX_V2_ELF),
+            _ => None,
+        }
+    }
+
+    fn vk_bytes(&self, _backend: &str) -> Option<Vec<u8>> {
+        None
+    }
+
+    fn program_type_id(&self) -> u8 {
+        2 // ZK-DEX
+    }
+
+    fn serialize_input(&self, raw_input: &[u8]) -> Result<Vec<u8>, GuestProgramError> {
+        serialize_zk_dex_input(raw_input, true)
+    }
+
+    fn resource_limits(&self) -> ResourceLimits {
+        ZkDexGuestProgram.resource_limits()
+    }
+
+    fn version(&self) -> &str {
+        "0.2.0"
+    }
+
+    fn state_version(&self) -> u8 {
+        migration::NOTE_FORMAT_V2
+    }
+
+    fn migration_from(&self) -> Option<(&str, u8)> {
+        Some(("zk-dex", migration::NOTE_FORMAT_V1))
+    }
+
+    fn migration_program(&self) -> Option<&dyn GuestProgram> {
+        Some(&migration::ZkDexMigrationGuestProgram)
+    }
+}
+
+/// Convert a `ProgramInput` into the `AppProgramInput` of a zk-dex version.
+///
+/// Versions with a note format also get the proof of the note format slot,
+/// which their circuit checks before executing the batch.
+#[cfg_attr(not(feature = "l2"), expect(unused_variables))]
+fn serialize_zk_dex_input(
+    raw_input: &[u8],
+    note_format: bool,
+) -> Result<Vec<u8>, GuestProgramError> {
+    #[cfg(feature = "l2")]
+    {
+        use crate::common::input_converter::convert_to_app_input;
+        use crate::l2::ProgramInput;
+        use rkyv::rancor::Error as RkyvError;
+
+        let program_input: ProgramInput = rkyv::from_bytes::<ProgramInput, RkyvError>(raw_input)
+            .map_err(|e| GuestProgramError::Serialization(e.to_string()))?;
+
+        let (accounts, mut storage_slots) = analyze_zk_dex_transactions(
+            &program_input.blocks,
+            DEX_CONTRACT_ADDRESS,
+            &program_input.fee_configs,
+            &program_input.execution_witness,
+        )
+        .map_err(|e| GuestProgramError::Internal(e.to_string()))?;
+        if note_format {
+            storage_slots.push((DEX_CONTRACT_ADDRESS, storage::note_format_slot()));
+        }
+
+        let app_input = convert_to_app_input(program_input, &accounts, &storage_slots)
+            .map_err(|e| GuestProgramError::Internal(e.to_string()))?;
+
+        let bytes = rkyv::to_bytes::<RkyvError>(&app_input)
+            .map_err(|e| GuestProgramError::Serialization(e.to_string()))?;
+        Ok(bytes.to_vec())
+    }
+
+    #[cfg(not(feature = "l2"))]
+    {
+        Ok(raw_input.to_vec())
+    }
+}
+
 /// Analyze zk-dex batch transactions to determine which accounts and storage
 /// slots are needed for proof generation.
 ///
@@ -538,4 +606,36 @@ mod tests {
             "serialize_input should reject arbitrary bytes"
         );
     }
+
+    #[test]
+    fn v2_takes_over_from_v1_through_migration() {
+        let v1 = ZkDexGuestProgram;
+        let v2 = ZkDexV2GuestProgram;
+        assert_eq!(v2.program_id(), "zk-dex-v2");
+        assert_eq!(v2.program_type_id(), v1.program_type_id());
+        assert_eq!(v1.state_version(), migration::NOTE_FORMAT_V1);
+        assert_eq!(v2.state_version(), migration::NOTE_FORMAT_V2);
+        assert_eq!(
+            v2.migration_from(),
+            Some((v1.program_id(), v1.state_version()))
+        );
+
+        let migration = v2.migration_program().expect("v2 has a migration program");
+        assert_eq!(migration.program_id(), "zk-dex-migration");
+        assert_eq!(migration.state_version(), v2.state_version());
+        assert!(v1.migration_from().is_none());
+        assert!(v1.migration_program().is_none());
+    }
+
+    #[test]
+    fn v2_sp1_elf_lookup() {
+        let gp = ZkDexV2GuestProgram;
+        let result = gp.elf(crate::traits::backends::SP1);
+        if crate::ZKVM_SP1_ZK_DEX_V2_ELF.is_empty() {
+            assert!(result.is_none());
+        } else {
+            assert!(result.is_some());
+        }
+        assert!(gp.elf("risc0").is_none());
+    }
 }
@@ -25,6 +25,9 @@
 //!   slot 13: orders  Order[]
 //! ```
 //!
+//! From note format 2 (v0.2), the note format is also stored at
+//! [`note_format_slot`], outside the sequential layout.
+//!
 //! Verified with `forge inspect ZkDex storage-layout`.
 
 use ethrex_common::{Address, H256, U256};
@@ -54,6 +57,15 @@ pub fn note_state_slot(note_hash: H256) -> H256 {
     mapping_slot(note_hash, NOTES_SLOT)
 }
 
+/// Compute the storage slot holding the note format of the DEX state.
+///
+/// `keccak256("zk-dex.note-format")`, an unstructured slot so it can't
+/// collide with the sequential layout. Unset (zero) on state written by
+/// note format 1, the format-2 upgrade of the contract sets it to 2.
+pub fn note_format_slot() -> H256 {
+    H256::from(keccak_hash(b"zk-dex.note-format"))
+}
+
 /// Compute the storage slot for `orders.length`.
 ///
 /// For dynamic arrays, the length is stored at the base slot itself.
@@ -127,18 +127,57 @@ pub trait GuestProgram: Send + Sync {
         "0.0.0"
     }
 
+    /// Version of the state format the program reads and writes.
+    ///
+    /// Bumped when a new version of the program can't consume the state the
+    /// previous one left, see [`migration_from`](Self::migration_from).
+    /// The default is `1`.
+    fn state_version(&self) -> u8 {
+        1
+    }
+
+    /// Program id and state version of the predecessor this program takes
+    /// over from through a state migration.
+    ///
+    /// When set, the prover refuses batches of this program until the
+    /// migration of the predecessor's final state has been proven with
+    /// [`migration_program`](Self::migration_program). The default is `None`
+    /// (no migration).
+    fn migration_from(&self) -> Option<(&str, u8)> {
+        None
+    }
+
+    /// Guest program proving the migration from
+    /// [`migration_from`](Self::migration_from).
+    ///
+    /// It has its own ELF and verification key, and commits a
+    /// [`MigrationOutput`](crate::common::migration::MigrationOutput).
+    fn migration_program(&self) -> Option<&dyn GuestProgram> {
+        None
+    }
+
     /// SHA-256 hash of the ELF binary for a given backend.
     ///
     /// Returns `None` when no ELF is available for the backend.
     /// This is useful for VK cache invalidation: when the hash changes,
     /// the verification key must be regenerated.
     fn elf_hash(&self, backend: &str) -> Option<[u8; 32]> {
-        use sha2::{Digest, Sha256};
-        self.elf(backend).map(|elf| {
-            let mut hasher = Sha256::new();
-            hasher.update(elf);
-            hasher.finalize().into()
-        })
+        self.elf(backend).map(crate::build_info::elf_hash)
+    }
+
+    /// Build metadata embedded in the ELF for a given backend.
+    ///
+    /// Returns `Ok(None)` when no ELF is available for the backend or the ELF
+    /// carries no [`BUILD_INFO_SECTION`](crate::build_info::BUILD_INFO_SECTION)
+    /// section, and an error when the ELF is malformed.
+    fn build_info(
+        &self,
+        backend: &str,
+    ) -> Result<Option<crate::build_info::ElfBuildInfo>, GuestProgramError> {
+        match self.elf(backend) {
+            Some(elf) if !elf.is_empty() => crate::build_info::read_build_info(elf),
+            _ => Ok(None),
+        }
     }
 
     /// Validate that an ELF binary has the correct format for the given backend.
@@ -437,6 +476,37 @@ mod tests {
         assert_ne!(h1, [0u8; 32]);
     }
 
+    #[test]
+    fn build_info_via_trait_default() {
+        struct FixtureElf;
+        impl GuestProgram for FixtureElf {
+            fn program_id(&self) -> &str {
+                "fixture"
+            }
+            fn elf(&self, backend: &str) -> Option<&[u8]> {
+                match backend {
+                    backends::SP1 => {
+                        Some(include_bytes!("../tests/fixtures/build-info-riscv32.elf"))
+                    }
+                    backends::RISC0 => Some(&[]),
+                    _ => None,
+                }
+            }
+            fn vk_bytes(&self, _: &str) -> Option<Vec<u8>> {
+                None
+            }
+            fn program_type_id(&self) -> u8 {
+                99
+            }
+        }
+        let info = FixtureElf.build_info(backends::SP1).unwrap().unwrap();
+        assert_eq!(info.toolchain, "sp1 v5.0.8");
+        assert_eq!(info.features, vec!["l2"]);
+        // Placeholder (empty) and missing ELFs have no build info.
+        assert!(FixtureElf.build_info(backends::RISC0).unwrap().is_none());
+        assert!(FixtureElf.build_info(backends::ZISK).unwrap().is_none());
+    }
+
     #[test]
     fn evm_l2_has_limits() {
         let limits = EvmL2GuestProgram.resource_limits();
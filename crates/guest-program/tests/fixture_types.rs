@@ -152,6 +152,9 @@ pub fn fixture_to_program_output(f: &TestFixture) -> ProgramOutput {
             .iter()
             .map(|(cid, h)| (*cid, hex_to_h256(h)))
             .collect(),
+        deposit_inclusion_timestamp: None,
+        ordering_commitment: None,
+        block_summaries: None,
     }
 }
 
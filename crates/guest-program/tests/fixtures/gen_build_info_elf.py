@@ -0,0 +1,54 @@
+#!/usr/bin/env python3
+"""Generate the minimal RISC-V ELF fixtures used by the build_info tests.
+
+The ELFs only carry what `build_info::read_build_info` looks at: the ELF
+header, a section name table and (optionally) a `.ethrex_build_info`
+section.  Run from this directory: `python3 gen_build_info_elf.py`.
+"""
+import struct
+
+BUILD_INFO_LEN = 512
+EM_RISCV = 243
+SHT_PROGBITS = 1
+SHT_STRTAB = 3
+
+INFO = (
+    b"git_commit=0123456789abcdef0123456789abcdef01234567\n"
+    b"cargo_lock_hash=9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08\n"
+    b"toolchain=sp1 v5.0.8\n"
+    b"features=l2\n"
+).ljust(BUILD_INFO_LEN, b"\0")
+
+
+def elf(is64, with_info):
+    names = b"\0.shstrtab\0" + (b".ethrex_build_info\0" if with_info else b"")
+    ehsize, shentsize = (64, 64) if is64 else (52, 40)
+    data = names + (INFO if with_info else b"")
+    shoff = ehsize + len(data)
+    shnum = 3 if with_info else 2
+
+    word = "Q" if is64 else "I"
+    header = b"\x7fELF" + bytes([2 if is64 else 1, 1, 1]) + bytes(9)
+    header += struct.pack("<HHI", 2, EM_RISCV, 1)
+    header += struct.pack("<" + word * 3, 0, 0, shoff)
+    header += struct.pack("<IHHHHHH", 0, ehsize, 0, 0, shentsize, shnum, 1)
+
+    def section(name, kind, offset, size):
+        if is64:
+            return struct.pack("<IIQQQQIIQQ", name, kind, 0, 0, offset, size, 0, 0, 1, 0)
+        return struct.pack("<IIIIIIIIII", name, kind, 0, 0, offset, size, 0, 0, 1, 0)
+
+    sections = bytes(shentsize)
+    sections += section(1, SHT_STRTAB, ehsize, len(names))
+    if with_info:
+        sections += section(11, SHT_PROGBITS, ehsize + len(names), BUILD_INFO_LEN)
+    return header + data + sections
+
+
+for path, is64, with_info in [
+    ("build-info-riscv32.elf", False, True),
+    ("build-info-riscv64.elf", True, True),
+    ("no-build-info-riscv32.elf", False, False),
+]:
+    with open(path, "wb") as f:
+        f.write(elf(is64, with_info))
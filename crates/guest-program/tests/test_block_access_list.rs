@@ -0,0 +1,172 @@
+/// Test validation of the block access lists provided to the L1 program (Amsterdam+).
+/// A provided access list must hash to its block's bal_hash, and the execution
+/// witness must hold exactly the accounts and storage slots it lists.
+use ethrex_common::constants::SYSTEM_ADDRESS;
+use ethrex_common::types::block_access_list::{
+    AccountChanges, BalanceChange, BlockAccessList, SlotChange, StorageChange,
+};
+use ethrex_common::types::{Block, BlockBody, BlockHeader, ChainConfig};
+use ethrex_common::{Address, H256, U256};
+use ethrex_guest_program::common::ExecutionError;
+use ethrex_guest_program::l1::{validate_block_access_lists, validate_witness_keys};
+
+const AMSTERDAM_TIME: u64 = 1_000;
+
+fn chain_config() -> ChainConfig {
+    ChainConfig {
+        amsterdam_time: Some(AMSTERDAM_TIME),
+        ..Default::default()
+    }
+}
+
+fn contract() -> Address {
+    Address::from_low_u64_be(0xc0de)
+}
+
+fn sender() -> Address {
+    Address::from_low_u64_be(0x5e4d)
+}
+
+/// Access list of a block where `sender` pays for a call that writes slot 1 and
+/// reads slot 2 of `contract`.
+fn block_access_list() -> BlockAccessList {
+    BlockAccessList::from_accounts(vec![
+        AccountChanges::new(contract())
+            .with_storage_changes(vec![SlotChange::with_changes(
+                U256::one(),
+                vec![StorageChange::new(1, U256::from(42))],
+            )])
+            .with_storage_reads(vec![U256::from(2)]),
+        AccountChanges::new(sender())
+            .with_balance_changes(vec![BalanceChange::new(1, U256::from(100))]),
+    ])
+}
+
+fn block(timestamp: u64, bal_hash: H256) -> Block {
+    let header = BlockHeader {
+        timestamp,
+        block_access_list_hash: Some(bal_hash),
+        ..Default::default()
+    };
+    Block::new(header, BlockBody::default())
+}
+
+fn slot(slot: u64) -> Vec<u8> {
+    H256::from_low_u64_be(slot).as_bytes().to_vec()
+}
+
+/// Witness keys holding exactly the accounts and slots of `block_access_list()`.
+fn witness_keys() -> Vec<Vec<u8>> {
+    vec![
+        contract().as_bytes().to_vec(),
+        slot(1),
+        slot(2),
+        sender().as_bytes().to_vec(),
+    ]
+}
+
+#[test]
+fn correct_block_access_list_is_accepted() {
+    let bal = block_access_list();
+    let blocks = [block(AMSTERDAM_TIME, bal.compute_hash())];
+
+    let covers_batch = validate_block_access_lists(&blocks, &chain_config(), &[bal.clone()])
+        .expect("access list matches the header");
+    assert!(covers_batch);
+    validate_witness_keys(&witness_keys(), &[bal]).expect("witness matches the access list");
+}
+
+#[test]
+fn tampered_bal_hash_is_rejected() {
+    let bal = block_access_list();
+    let blocks = [block(AMSTERDAM_TIME, H256::repeat_byte(0xba))];
+
+    let result = validate_block_access_lists(&blocks, &chain_config(), &[bal]);
+    assert!(matches!(
+        result,
+        Err(ExecutionError::BlockAccessListValidation(_))
+    ));
+}
+
+#[test]
+fn padded_block_access_list_is_rejected() {
+    let bal = block_access_list();
+    let blocks = [block(AMSTERDAM_TIME, bal.compute_hash())];
+
+    let mut padded = bal;
+    padded.add_account_changes(AccountChanges::new(Address::from_low_u64_be(0xbad)));
+
+    let result = validate_block_access_lists(&blocks, &chain_config(), &[padded]);
+    assert!(matches!(
+        result,
+        Err(ExecutionError::BlockAccessListValidation(_))
+    ));
+}
+
+#[test]
+fn one_access_list_per_block_is_required() {
+    let bal = block_access_list();
+    let blocks = [
+        block(AMSTERDAM_TIME, bal.compute_hash()),
+        block(AMSTERDAM_TIME + 12, bal.compute_hash()),
+    ];
+
+    let result = validate_block_access_lists(&blocks, &chain_config(), &[bal]);
+    assert!(matches!(
+        result,
+        Err(ExecutionError::BlockAccessListCount {
+            expected: 2,
+            actual: 1
+        })
+    ));
+}
+
+#[test]
+fn pre_amsterdam_block_does_not_cover_batch() {
+    let bal = block_access_list();
+    let blocks = [
+        block(AMSTERDAM_TIME - 12, H256::zero()),
+        block(AMSTERDAM_TIME, bal.compute_hash()),
+    ];
+
+    let covers_batch =
+        validate_block_access_lists(&blocks, &chain_config(), &[BlockAccessList::new(), bal])
+            .expect("Amsterdam block access list matches the header");
+    assert!(!covers_batch);
+}
+
+#[test]
+fn witness_gap_is_rejected() {
+    let mut keys = witness_keys();
+    // Drop slot 2 of the contract
+    keys.remove(2);
+
+    let result = validate_witness_keys(&keys, &[block_access_list()]);
+    assert!(matches!(result, Err(ExecutionError::MissingWitnessKey(_))));
+
+    // Drop the sender
+    let result = validate_witness_keys(&witness_keys()[..3], &[block_access_list()]);
+    assert!(matches!(result, Err(ExecutionError::MissingWitnessKey(_))));
+}
+
+#[test]
+fn witness_bloat_is_rejected() {
+    let mut keys = witness_keys();
+    keys.push(slot(3));
+    let result = validate_witness_keys(&keys, &[block_access_list()]);
+    assert!(matches!(result, Err(ExecutionError::UnusedWitnessKey(_))));
+
+    let mut keys = witness_keys();
+    keys.push(Address::from_low_u64_be(0xbad).as_bytes().to_vec());
+    let result = validate_witness_keys(&keys, &[block_access_list()]);
+    assert!(matches!(result, Err(ExecutionError::UnusedWitnessKey(_))));
+}
+
+#[test]
+fn system_address_is_allowed_in_witness() {
+    let mut keys = witness_keys();
+    keys.push(SYSTEM_ADDRESS.as_bytes().to_vec());
+
+    validate_witness_keys(&keys, &[block_access_list()])
+        .expect("system address is only listed when its state changes");
+}
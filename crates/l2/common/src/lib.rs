@@ -1,10 +1,13 @@
 pub mod calldata;
 pub mod merkle_tree;
 pub mod messages;
+pub mod preconfirmations;
 pub mod privileged_transactions;
+pub mod proof_submission;
 pub mod prover;
 pub mod sequencer_state;
 pub mod utils;
+pub mod withdrawals;
 
 /// Maps a guest program ID string to its on-chain `programTypeId`.
 ///
@@ -17,7 +20,8 @@ pub mod utils;
 pub fn resolve_program_type_id(program_id: &str) -> u8 {
     match program_id {
         "evm-l2" => 1,
-        "zk-dex" => 2,
+        // v0.2 replaces v0.1 on the same verification key slot.
+        "zk-dex" | "zk-dex-v2" => 2,
         "tokamon" => 3,
         "bridge" => 4,
         _ => 0,
@@ -59,6 +63,7 @@ mod tests {
     fn test_resolve_known_programs() {
         assert_eq!(resolve_program_type_id("evm-l2"), 1);
         assert_eq!(resolve_program_type_id("zk-dex"), 2);
+        assert_eq!(resolve_program_type_id("zk-dex-v2"), 2);
         assert_eq!(resolve_program_type_id("tokamon"), 3);
         assert_eq!(resolve_program_type_id("unknown"), 0);
     }
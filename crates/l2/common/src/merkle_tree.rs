@@ -67,3 +67,12 @@ pub fn compute_merkle_proof(hashes: &[H256], index: usize) -> Option<Vec<H256>>
             .collect(),
     )
 }
+
+/// Checks a proof built by [`compute_merkle_proof`] the way OpenZeppelin's
+/// `MerkleProof.verify` does on L1.
+pub fn verify_merkle_proof(leaf: H256, proof: &[H256], root: H256) -> bool {
+    let computed_root = proof.iter().fold(leaf.to_fixed_bytes(), |node, sibling| {
+        TreeData::hash_new_parent(&node, &sibling.to_fixed_bytes())
+    });
+    computed_root == root.to_fixed_bytes()
+}
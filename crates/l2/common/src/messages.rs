@@ -1,9 +1,9 @@
 use std::collections::BTreeMap;
-use std::sync::LazyLock;
 
 use bytes::Bytes;
 use ethereum_types::{Address, H256};
 use ethrex_common::types::balance_diff::{AssetDiff, BalanceDiff};
+use ethrex_common::types::events::{L1MessageEvent, L2_MESSAGE_EVENT, L2MessageEvent};
 use ethrex_common::utils::keccak;
 use ethrex_common::{H160, U256, types::Receipt};
 
@@ -14,14 +14,6 @@ pub const MESSENGER_ADDRESS: Address = H160([
     0x00, 0x00, 0xff, 0xfe,
 ]);
 
-pub static L1MESSAGE_EVENT_SELECTOR: LazyLock<H256> =
-    LazyLock::new(|| keccak("L1Message(address,bytes32,uint256)".as_bytes()));
-
-// keccak256("L2Message(uint256,address,address,uint256,uint256,uint256,bytes)")
-pub static L2MESSAGE_EVENT_SELECTOR: LazyLock<H256> = LazyLock::new(|| {
-    keccak("L2Message(uint256,address,address,uint256,uint256,uint256,bytes)".as_bytes())
-});
-
 // crosschainMintERC20(address,address,address,address,uint256)
 pub static CROSSCHAIN_MINT_ERC20_SELECTOR: [u8; 4] = [0xf0, 0x26, 0x31, 0x95];
 
@@ -68,25 +60,21 @@ pub fn get_l2_message_hash(msg: &L2Message) -> H256 {
 }
 
 pub fn get_block_l1_messages(receipts: &[Receipt]) -> Vec<L1Message> {
-    receipts
+    receipts.iter().flat_map(get_receipt_l1_messages).collect()
+}
+
+/// Returns the L1 messages emitted by a single transaction, in log order.
+pub fn get_receipt_l1_messages(receipt: &Receipt) -> impl Iterator<Item = L1Message> + '_ {
+    receipt
+        .logs
         .iter()
-        .flat_map(|receipt| {
-            receipt
-                .logs
-                .iter()
-                .filter(|log| {
-                    log.address == MESSENGER_ADDRESS
-                        && log.topics.contains(&L1MESSAGE_EVENT_SELECTOR)
-                })
-                .flat_map(|log| -> Option<L1Message> {
-                    Some(L1Message {
-                        from: Address::from_slice(&log.topics.get(1)?.0[12..32]),
-                        data_hash: *log.topics.get(2)?,
-                        message_id: U256::from_big_endian(&log.topics.get(3)?.to_fixed_bytes()),
-                    })
-                })
+        .filter(|log| log.address == MESSENGER_ADDRESS)
+        .filter_map(L1MessageEvent::from_log)
+        .map(|event| L1Message {
+            from: event.from,
+            data_hash: event.data_hash,
+            message_id: event.message_id,
         })
-        .collect()
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -124,27 +112,16 @@ impl L2Message {
         .concat()
     }
     pub fn from_log(log: &ethrex_common::types::Log, source_chain_id: u64) -> Option<L2Message> {
-        let dest_chain_id = U256::from_big_endian(&log.topics.get(1)?.0);
-        let from = H256::from_slice(log.data.get(0..32)?);
-        let from = Address::from_slice(&from.as_fixed_bytes()[12..]);
-        let to = H256::from_slice(log.data.get(32..64)?);
-        let to = Address::from_slice(&to.as_fixed_bytes()[12..]);
-        let value = U256::from_big_endian(log.data.get(64..96)?);
-        let gas_limit = U256::from_big_endian(log.data.get(96..128)?);
-        let tx_id = U256::from_big_endian(log.data.get(128..160)?);
-        // 160 to 192 is the offset for calldata
-        let calldata_len = U256::from_big_endian(log.data.get(192..224)?);
-        let calldata = log.data.get(224..224 + calldata_len.as_usize())?;
-
+        let event = L2MessageEvent::from_log(log)?;
         Some(L2Message {
-            dest_chain_id,
+            dest_chain_id: event.dest_chain_id,
             source_chain_id,
-            from,
-            to,
-            value,
-            gas_limit,
-            tx_id,
-            data: Bytes::copy_from_slice(calldata),
+            from: event.from,
+            to: event.to,
+            value: event.value,
+            gas_limit: event.gas_limit,
+            tx_id: event.tx_id,
+            data: event.data,
         })
     }
 }
@@ -156,11 +133,7 @@ pub fn get_block_l2_out_messages(receipts: &[Receipt], source_chain_id: u64) ->
             receipt
                 .logs
                 .iter()
-                .filter(|log| {
-                    log.address == MESSENGER_ADDRESS
-                        && log.topics.first() == Some(&*L2MESSAGE_EVENT_SELECTOR)
-                        && log.topics.len() >= 2 // need chainId
-                })
+                .filter(|log| log.address == MESSENGER_ADDRESS && L2_MESSAGE_EVENT.matches(log))
                 .filter_map(|log| L2Message::from_log(log, source_chain_id))
         })
         .collect()
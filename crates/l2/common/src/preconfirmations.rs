@@ -0,0 +1,107 @@
+use std::path::Path;
+
+use ethereum_types::{H256, U256};
+use ethrex_common::utils::keccak;
+use rkyv::{Archive, Deserialize as RDeserialize, Serialize as RSerialize};
+use serde::{Deserialize, Serialize};
+
+/// A transaction the gateway promised to include at a given position of the
+/// batch. Positions count every transaction of the batch, block after block.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, RSerialize, RDeserialize, Archive,
+)]
+pub struct PromisedTransaction {
+    pub position: u64,
+    #[rkyv(with = ethrex_common::rkyv_utils::H256Wrapper)]
+    pub tx_hash: H256,
+}
+
+/// Ordering the gateway committed to when preconfirming transactions of a
+/// batch. Positions not in the list may be filled freely.
+#[derive(
+    Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, RSerialize, RDeserialize, Archive,
+)]
+pub struct OrderingCommitment {
+    /// Promised transactions, as signed by the gateway.
+    pub promised: Vec<PromisedTransaction>,
+    /// Id of the gateway key that signed the ordering, as registered on L1.
+    pub signer_key_id: u64,
+}
+
+impl OrderingCommitment {
+    /// Root of the promised list, matched on L1 against the preconfirmation
+    /// registry: the keccak of each `(position, tx_hash)` pair encoded as two
+    /// 32-byte words, in list order.
+    pub fn root(&self) -> H256 {
+        let encoded: Vec<u8> = self
+            .promised
+            .iter()
+            .flat_map(|promise| {
+                [
+                    U256::from(promise.position).to_big_endian(),
+                    promise.tx_hash.to_fixed_bytes(),
+                ]
+                .concat()
+            })
+            .collect();
+        keccak(encoded)
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum OrderingCommitmentError {
+    #[error("Failed to read ordering commitment file: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to parse ordering commitment file: {0}")]
+    Parse(#[from] serde_json::Error),
+}
+
+/// Reads the ordering commitment of `batch_number` that the preconfirmation
+/// gateway wrote to `dir`, as JSON in `batch_<batch_number>.json`.
+///
+/// Returns `None` if the gateway didn't commit to an ordering for the batch.
+pub fn read_ordering_commitment(
+    dir: &Path,
+    batch_number: u64,
+) -> Result<Option<OrderingCommitment>, OrderingCommitmentError> {
+    let path = dir.join(format!("batch_{batch_number}.json"));
+    match std::fs::read(path) {
+        Ok(contents) => Ok(Some(serde_json::from_slice(&contents)?)),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_ordering_commitment_of_batch() {
+        let dir = std::env::temp_dir().join(format!("ethrex-ordering-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let commitment = OrderingCommitment {
+            promised: vec![PromisedTransaction {
+                position: 2,
+                tx_hash: H256::repeat_byte(0xab),
+            }],
+            signer_key_id: 7,
+        };
+        std::fs::write(
+            dir.join("batch_5.json"),
+            serde_json::to_vec(&commitment).unwrap(),
+        )
+        .unwrap();
+        std::fs::write(dir.join("batch_6.json"), b"not json").unwrap();
+
+        assert_eq!(read_ordering_commitment(&dir, 5).unwrap(), Some(commitment));
+        assert_eq!(read_ordering_commitment(&dir, 4).unwrap(), None);
+        assert!(matches!(
+            read_ordering_commitment(&dir, 6),
+            Err(OrderingCommitmentError::Parse(_))
+        ));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,54 @@
+//! Persistent state of the L1 submission of a batch's proofs.
+
+use ethereum_types::H256;
+use serde::{Deserialize, Serialize};
+
+/// Submission state of a batch whose proofs are complete and waiting to be
+/// verified on L1. Times are unix timestamps in seconds.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofSubmission {
+    /// When the batch's proofs were first found complete. The submission
+    /// deadline counts from here.
+    pub queued_at: u64,
+    /// Consecutive failed submission attempts.
+    pub failed_attempts: u64,
+    /// No new transaction is sent before this time.
+    pub next_attempt_at: u64,
+    /// Since when submission is deferred because of high L1 fees.
+    pub deferred_since: Option<u64>,
+    /// Time spent deferred in finished deferral periods.
+    pub deferred_secs: u64,
+    /// Verification transaction waiting to be included.
+    pub pending_tx: Option<PendingVerifyTx>,
+}
+
+impl ProofSubmission {
+    pub fn new(queued_at: u64) -> Self {
+        Self {
+            queued_at,
+            ..Default::default()
+        }
+    }
+
+    /// Total time spent deferred, including the ongoing deferral.
+    pub fn deferred_time(&self, now: u64) -> u64 {
+        let ongoing = self
+            .deferred_since
+            .map(|since| now.saturating_sub(since))
+            .unwrap_or_default();
+        self.deferred_secs.saturating_add(ongoing)
+    }
+}
+
+/// A verification transaction sent to L1 and its replacements.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PendingVerifyTx {
+    pub nonce: u64,
+    pub max_fee_per_gas: u64,
+    pub max_priority_fee_per_gas: u64,
+    /// Hashes of every transaction sent with this nonce, the latest last. Any
+    /// of them may be the one that gets included.
+    pub tx_hashes: Vec<H256>,
+    /// When the latest transaction was sent.
+    pub sent_at: u64,
+}
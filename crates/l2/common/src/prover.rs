@@ -1,14 +1,19 @@
 use bytes::Bytes;
-use ethrex_common::U256;
 use ethrex_common::types::{
     Block, blobs_bundle, block_execution_witness::ExecutionWitness, fee_config::FeeConfig,
 };
+use ethrex_common::utils::keccak;
+use ethrex_common::{H256, U256};
 use rkyv::{Archive, Deserialize as RDeserialize, Serialize as RSerialize};
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use std::fmt::{Debug, Display};
+use std::str::FromStr;
 
 use crate::calldata::Value;
+use crate::preconfirmations::OrderingCommitment;
+
+mod legacy;
 
 #[serde_as]
 #[derive(Serialize, Deserialize, RDeserialize, RSerialize, Archive)]
@@ -26,6 +31,91 @@ pub struct ProverInputData {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[rkyv(with = ethrex_common::rkyv_utils::OptionU256Wrapper)]
     pub native_token_scale_factor: Option<U256>,
+    /// Ordering promised by the preconfirmation gateway, if any.
+    #[serde(default)]
+    pub ordering_commitment: Option<OrderingCommitment>,
+    /// Whether the prover publishes per-block summaries in its output.
+    #[serde(default)]
+    pub publish_block_summaries: bool,
+    /// Whether the prover commits the last block timestamp checked against
+    /// privileged transaction deadlines.
+    #[serde(default)]
+    pub commit_deposit_inclusion: bool,
+}
+
+/// Prefix of an encoded [`ProverInputData`], followed by the layout version
+/// and the rkyv archive. Inputs encoded without it have the layout of
+/// [`legacy::ProverInputDataV0`].
+const PROVER_INPUT_MAGIC: &[u8] = b"ethrexP";
+
+/// Layout version of [`ProverInputData`]. Bump it, and keep the previous
+/// layout in [`legacy`], whenever a change to the input or the types it holds
+/// breaks the archive.
+const PROVER_INPUT_VERSION: u8 = 1;
+
+#[derive(Debug, thiserror::Error)]
+#[error("Unsupported prover input layout version: {0:?}")]
+struct UnsupportedProverInputVersion(Option<u8>);
+
+impl ProverInputData {
+    /// Encodes the input with rkyv, prefixed with its layout version. This is
+    /// the encoding used by the rollup store.
+    pub fn encode(&self) -> Result<Vec<u8>, rkyv::rancor::Error> {
+        let archive = rkyv::to_bytes::<rkyv::rancor::Error>(self)?;
+        let mut encoded = Vec::with_capacity(PROVER_INPUT_MAGIC.len() + 1 + archive.len());
+        encoded.extend_from_slice(PROVER_INPUT_MAGIC);
+        encoded.push(PROVER_INPUT_VERSION);
+        encoded.extend_from_slice(&archive);
+        Ok(encoded)
+    }
+
+    /// Decodes an input encoded by [`ProverInputData::encode`], or stored as a
+    /// bare archive by versions that didn't prefix it.
+    pub fn decode(bytes: &[u8]) -> Result<Self, rkyv::rancor::Error> {
+        match bytes.strip_prefix(PROVER_INPUT_MAGIC) {
+            Some([PROVER_INPUT_VERSION, archive @ ..]) => {
+                rkyv::from_bytes::<Self, rkyv::rancor::Error>(archive)
+            }
+            Some(rest) => Err(rkyv::rancor::Source::new(UnsupportedProverInputVersion(
+                rest.first().copied(),
+            ))),
+            None => rkyv::from_bytes::<legacy::ProverInputDataV0, rkyv::rancor::Error>(bytes)
+                .map(Into::into),
+        }
+    }
+}
+
+/// Hash of an encoded [`ProverInputData`].
+///
+/// Advertised by the proof coordinator so provers can tell whether an input
+/// they cached earlier is still the one to prove.
+pub fn prover_input_hash(encoded_input: &[u8]) -> H256 {
+    keccak(encoded_input)
+}
+
+/// A batch input the prover already holds, identified by its hash.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CachedInput {
+    pub batch_number: u64,
+    pub input_hash: H256,
+}
+
+/// How costly a proof was to produce, reported by the prover with each
+/// submitted proof so the sequencer can size batches to its capacity.
+///
+/// The proof coordinator only takes the proving time and cycles from it, it
+/// reads the gas used and the backlog from its own store.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProvingFeedback {
+    /// Gas used by the blocks of the proven batch.
+    pub gas_used: u64,
+    /// Cycles used by the guest program, when the backend reports them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cycles: Option<u64>,
+    /// Wall time spent proving the batch, in milliseconds.
+    pub proving_time_ms: u64,
+    /// Batches the prover holds ready to prove after this one.
+    pub queue_depth: u64,
 }
 
 /// Enum used to identify the different proving systems.
@@ -159,7 +249,7 @@ pub struct ProofCalldata {
 }
 
 /// Indicates the prover which proof *format* to generate
-#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
 pub enum ProofFormat {
     #[default]
     /// A compressed proof wrapped over groth16. EVM friendly.
@@ -168,6 +258,153 @@ pub enum ProofFormat {
     Compressed,
 }
 
+impl ProofFormat {
+    /// Used to iterate through all the possible proof formats
+    pub fn all() -> impl Iterator<Item = ProofFormat> {
+        [ProofFormat::Groth16, ProofFormat::Compressed].into_iter()
+    }
+}
+
+impl FromStr for ProofFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "groth16" => Ok(ProofFormat::Groth16),
+            "compressed" => Ok(ProofFormat::Compressed),
+            _ => Err(format!(
+                "unknown proof format {s}, expected groth16 or compressed"
+            )),
+        }
+    }
+}
+
+/// Version of the ProverServer <--> ProverClient protocol implemented by this crate.
+///
+/// Bump this whenever a message changes shape in a way older peers can't
+/// tolerate through `#[serde(default)]`. Peers that predate capability
+/// negotiation don't send a version at all and are treated as legacy.
+pub const PROVER_PROTOCOL_VERSION: u32 = 1;
+
+/// Protocol versions this crate is able to decode.
+pub const SUPPORTED_PROTOCOL_VERSIONS: [u32; 1] = [1];
+
+/// Returns whether `version` is one of the [`SUPPORTED_PROTOCOL_VERSIONS`].
+pub fn is_supported_protocol_version(version: u32) -> bool {
+    SUPPORTED_PROTOCOL_VERSIONS.contains(&version)
+}
+
+/// A guest program the prover is able to prove, as advertised during the handshake.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProgramCapability {
+    pub program_id: String,
+    /// Semantic version reported by the guest program.
+    pub version: String,
+    /// SHA-256 of the ELF loaded for the prover's backend, if any.
+    /// `None` for backends that execute natively (e.g. exec).
+    #[serde(default)]
+    pub elf_hash: Option<[u8; 32]>,
+    /// Git commit the ELF was built from, read from its embedded build info.
+    #[serde(default)]
+    pub build_commit: Option<String>,
+}
+
+/// Capabilities advertised by a prover speaking version 1 of the protocol.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ProverCapabilitiesV1 {
+    pub backends: Vec<ProverType>,
+    pub programs: Vec<ProgramCapability>,
+    pub proof_formats: Vec<ProofFormat>,
+}
+
+/// Versioned capabilities message sent by the prover when it connects.
+///
+/// The enum is internally tagged with `protocol_version`, so a peer receiving
+/// a version it does not know gets a decode error that [`ProofData::decode`]
+/// turns into [`ProtocolError::UnsupportedVersion`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "protocol_version")]
+pub enum ProverCapabilities {
+    #[serde(rename = "1")]
+    V1(ProverCapabilitiesV1),
+}
+
+impl ProverCapabilities {
+    /// Builder function for creating capabilities for the current protocol version.
+    pub fn new(
+        backends: Vec<ProverType>,
+        programs: Vec<ProgramCapability>,
+        proof_formats: Vec<ProofFormat>,
+    ) -> Self {
+        ProverCapabilities::V1(ProverCapabilitiesV1 {
+            backends,
+            programs,
+            proof_formats,
+        })
+    }
+
+    pub fn protocol_version(&self) -> u32 {
+        match self {
+            ProverCapabilities::V1(_) => 1,
+        }
+    }
+
+    pub fn backends(&self) -> &[ProverType] {
+        match self {
+            ProverCapabilities::V1(caps) => &caps.backends,
+        }
+    }
+
+    pub fn programs(&self) -> &[ProgramCapability] {
+        match self {
+            ProverCapabilities::V1(caps) => &caps.programs,
+        }
+    }
+
+    pub fn proof_formats(&self) -> &[ProofFormat] {
+        match self {
+            ProverCapabilities::V1(caps) => &caps.proof_formats,
+        }
+    }
+
+    pub fn program(&self, program_id: &str) -> Option<&ProgramCapability> {
+        self.programs().iter().find(|p| p.program_id == program_id)
+    }
+
+    /// Returns whether a prover with these capabilities can handle an
+    /// assignment for `program_id` with the given backend and proof format.
+    pub fn can_prove(
+        &self,
+        prover_type: ProverType,
+        program_id: &str,
+        format: ProofFormat,
+    ) -> bool {
+        self.backends().contains(&prover_type)
+            && self.proof_formats().contains(&format)
+            && self.program(program_id).is_some()
+    }
+}
+
+/// Structured error exchanged when a peer sends a message that can't be handled.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum ProtocolError {
+    #[error("Unsupported protocol version {received} (supported: {supported:?})")]
+    UnsupportedVersion { received: u32, supported: Vec<u32> },
+    #[error("Unknown message type: {0}")]
+    UnknownMessage(String),
+    #[error("Malformed message: {0}")]
+    Malformed(String),
+}
+
+impl ProtocolError {
+    fn unsupported_version(received: u32) -> Self {
+        ProtocolError::UnsupportedVersion {
+            received,
+            supported: SUPPORTED_PROTOCOL_VERSIONS.to_vec(),
+        }
+    }
+}
+
 /// Enum for the ProverServer <--> ProverClient Communication Protocol.
 #[allow(clippy::large_enum_variant)]
 #[derive(Serialize, Deserialize)]
@@ -192,11 +429,19 @@ pub enum ProofData {
     /// so it can skip batches that already have a proof for that type.
     /// The optional supported_programs field lists the guest programs the
     /// prover can handle (empty = all / legacy prover).
+    /// The optional capabilities are sent by provers that support capability
+    /// negotiation so the Server only assigns work the prover can handle.
+    /// The optional cached_inputs list the batch inputs the prover already
+    /// holds, so the Server can leave the input out of the BatchResponse.
     BatchRequest {
         commit_hash: String,
         prover_type: ProverType,
         #[serde(default)]
         supported_programs: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        capabilities: Option<ProverCapabilities>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        cached_inputs: Vec<CachedInput>,
     },
 
     /// 4.
@@ -216,29 +461,99 @@ pub enum ProofData {
     /// If the BatchResponse is ProofData::BatchResponse{None, None, None},
     /// the Client knows the BatchRequest couldn't be performed.
     /// The optional program_id tells the prover which guest program to use.
+    /// The protocol_version is absent when sent by a legacy Server.
+    /// The optional input_hash is the hash of the batch input. When it matches
+    /// one of the request's cached_inputs, the input itself is left out.
     BatchResponse {
         batch_number: Option<u64>,
         input: Option<ProverInputData>,
         format: Option<ProofFormat>,
         #[serde(default)]
         program_id: Option<String>,
+        #[serde(default)]
+        protocol_version: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        input_hash: Option<H256>,
     },
 
     /// 6.
     /// The Client submits the zk Proof generated by the prover for the specified batch.
     /// The program_id identifies which guest program produced the proof.
+    /// The optional feedback reports how costly the proof was, so the Server
+    /// can adjust the size of upcoming batches.
     ProofSubmit {
         batch_number: u64,
         batch_proof: BatchProof,
         #[serde(default = "default_program_id")]
         program_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        feedback: Option<ProvingFeedback>,
     },
 
     /// 7.
     /// The Server acknowledges the receipt of the proof and updates its state,
     ProofSubmitACK { batch_number: u64 },
+
+    /// 8.
+    /// The Client advertises its capabilities (backends, guest programs and
+    /// proof formats) right after connecting to a Server. Legacy Servers fail
+    /// to parse this message and close the connection without answering,
+    /// which the Client treats as "no negotiation available".
+    Capabilities { capabilities: ProverCapabilities },
+
+    /// 9.
+    /// The Server answers the capabilities with the protocol version it speaks
+    /// and the programs it may assign to this prover (empty = nothing to do).
+    CapabilitiesACK {
+        protocol_version: u32,
+        assignable_programs: Vec<String>,
+    },
+
+    /// 10.
+    /// Either side responds with a ProtocolError when it can't handle the
+    /// received message (unknown type, unsupported version, malformed data).
+    ProtocolError { error: ProtocolError },
+
+    /// 11.
+    /// The Client asks for the input of a specific batch ahead of time, so it
+    /// is ready once that batch is assigned. The optional input_hash is the
+    /// hash of the input the Client already holds for that batch, if any.
+    InputRequest {
+        commit_hash: String,
+        batch_number: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        input_hash: Option<H256>,
+    },
+
+    /// 12.
+    /// The Server responds with the batch input and its hash. Both are None
+    /// when there's no input for that batch yet, and only the input is None
+    /// when the hash matches the one sent in the InputRequest.
+    InputResponse {
+        batch_number: u64,
+        input: Option<ProverInputData>,
+        input_hash: Option<H256>,
+    },
 }
 
+/// Names of every [`ProofData`] variant, used to tell unknown message types
+/// apart from malformed known ones.
+const PROOF_DATA_VARIANTS: [&str; 13] = [
+    "ProverSetup",
+    "ProverSetupACK",
+    "BatchRequest",
+    "VersionMismatch",
+    "ProverTypeNotNeeded",
+    "BatchResponse",
+    "ProofSubmit",
+    "ProofSubmitACK",
+    "Capabilities",
+    "CapabilitiesACK",
+    "ProtocolError",
+    "InputRequest",
+    "InputResponse",
+];
+
 /// Default program id for backward compatibility with pre-modularization provers.
 fn default_program_id() -> String {
     "evm-l2".to_string()
@@ -264,6 +579,8 @@ impl ProofData {
             commit_hash,
             prover_type,
             supported_programs: Vec::new(),
+            capabilities: None,
+            cached_inputs: Vec::new(),
         }
     }
 
@@ -277,9 +594,41 @@ impl ProofData {
             commit_hash,
             prover_type,
             supported_programs,
+            capabilities: None,
+            cached_inputs: Vec::new(),
         }
     }
 
+    /// Builder function for creating a BatchRequest carrying the prover's
+    /// negotiated capabilities.
+    pub fn batch_request_with_capabilities(
+        commit_hash: String,
+        prover_type: ProverType,
+        capabilities: ProverCapabilities,
+    ) -> Self {
+        let supported_programs = capabilities
+            .programs()
+            .iter()
+            .map(|p| p.program_id.clone())
+            .collect();
+        ProofData::BatchRequest {
+            commit_hash,
+            prover_type,
+            supported_programs,
+            capabilities: Some(capabilities),
+            cached_inputs: Vec::new(),
+        }
+    }
+
+    /// Attaches the inputs the prover already holds to a BatchRequest.
+    /// Other messages are returned unchanged.
+    pub fn with_cached_inputs(mut self, inputs: Vec<CachedInput>) -> Self {
+        if let ProofData::BatchRequest { cached_inputs, .. } = &mut self {
+            *cached_inputs = inputs;
+        }
+        self
+    }
+
     /// Builder function for creating a VersionMismatch
     pub fn version_mismatch() -> Self {
         ProofData::VersionMismatch
@@ -292,6 +641,8 @@ impl ProofData {
             input: Some(input),
             format: Some(format),
             program_id: None,
+            protocol_version: Some(PROVER_PROTOCOL_VERSION),
+            input_hash: None,
         }
     }
 
@@ -307,6 +658,27 @@ impl ProofData {
             input: Some(input),
             format: Some(format),
             program_id: Some(program_id),
+            protocol_version: Some(PROVER_PROTOCOL_VERSION),
+            input_hash: None,
+        }
+    }
+
+    /// Builder function for creating a BatchResponse advertising the input
+    /// hash. `input` is None when the prover already holds that input.
+    pub fn batch_response_with_input_hash(
+        batch_number: u64,
+        input: Option<ProverInputData>,
+        input_hash: H256,
+        format: ProofFormat,
+        program_id: String,
+    ) -> Self {
+        ProofData::BatchResponse {
+            batch_number: Some(batch_number),
+            input,
+            format: Some(format),
+            program_id: Some(program_id),
+            protocol_version: Some(PROVER_PROTOCOL_VERSION),
+            input_hash: Some(input_hash),
         }
     }
 
@@ -316,6 +688,8 @@ impl ProofData {
             input: None,
             format: None,
             program_id: None,
+            protocol_version: Some(PROVER_PROTOCOL_VERSION),
+            input_hash: None,
         }
     }
 
@@ -325,6 +699,7 @@ impl ProofData {
             batch_number,
             batch_proof,
             program_id: default_program_id(),
+            feedback: None,
         }
     }
 
@@ -338,6 +713,23 @@ impl ProofData {
             batch_number,
             batch_proof,
             program_id,
+            feedback: None,
+        }
+    }
+
+    /// Builder function for creating a ProofSubmit with a specific program id
+    /// and the feedback on how costly the proof was.
+    pub fn proof_submit_with_feedback(
+        batch_number: u64,
+        batch_proof: BatchProof,
+        program_id: String,
+        feedback: ProvingFeedback,
+    ) -> Self {
+        ProofData::ProofSubmit {
+            batch_number,
+            batch_proof,
+            program_id,
+            feedback: Some(feedback),
         }
     }
 
@@ -345,6 +737,108 @@ impl ProofData {
     pub fn proof_submit_ack(batch_number: u64) -> Self {
         ProofData::ProofSubmitACK { batch_number }
     }
+
+    /// Builder function for creating a Capabilities message
+    pub fn capabilities(capabilities: ProverCapabilities) -> Self {
+        ProofData::Capabilities { capabilities }
+    }
+
+    /// Builder function for creating a CapabilitiesACK
+    pub fn capabilities_ack(assignable_programs: Vec<String>) -> Self {
+        ProofData::CapabilitiesACK {
+            protocol_version: PROVER_PROTOCOL_VERSION,
+            assignable_programs,
+        }
+    }
+
+    /// Builder function for creating a ProtocolError
+    pub fn protocol_error(error: ProtocolError) -> Self {
+        ProofData::ProtocolError { error }
+    }
+
+    /// Builder function for creating an InputRequest
+    pub fn input_request(commit_hash: String, batch_number: u64, input_hash: Option<H256>) -> Self {
+        ProofData::InputRequest {
+            commit_hash,
+            batch_number,
+            input_hash,
+        }
+    }
+
+    /// Builder function for creating an InputResponse
+    pub fn input_response(
+        batch_number: u64,
+        input: Option<ProverInputData>,
+        input_hash: Option<H256>,
+    ) -> Self {
+        ProofData::InputResponse {
+            batch_number,
+            input,
+            input_hash,
+        }
+    }
+
+    /// Protocol version carried by the message, if any.
+    pub fn protocol_version(&self) -> Option<u32> {
+        match self {
+            ProofData::BatchResponse {
+                protocol_version, ..
+            } => *protocol_version,
+            ProofData::CapabilitiesACK {
+                protocol_version, ..
+            } => Some(*protocol_version),
+            ProofData::Capabilities { capabilities } => Some(capabilities.protocol_version()),
+            ProofData::BatchRequest { capabilities, .. } => capabilities
+                .as_ref()
+                .map(ProverCapabilities::protocol_version),
+            _ => None,
+        }
+    }
+
+    /// Decodes a message received from a peer.
+    ///
+    /// Unlike a bare `serde_json::from_slice`, messages from peers on a newer
+    /// protocol version are reported as [`ProtocolError::UnsupportedVersion`]
+    /// and unknown message types as [`ProtocolError::UnknownMessage`], so the
+    /// caller can react instead of failing with an opaque parse error.
+    pub fn decode(bytes: &[u8]) -> Result<Self, ProtocolError> {
+        match serde_json::from_slice::<ProofData>(bytes) {
+            Ok(data) => match data.protocol_version() {
+                Some(version) if !is_supported_protocol_version(version) => {
+                    Err(ProtocolError::unsupported_version(version))
+                }
+                _ => Ok(data),
+            },
+            Err(err) => Err(classify_decode_error(bytes, err)),
+        }
+    }
+}
+
+/// Figures out why a message failed to decode.
+fn classify_decode_error(bytes: &[u8], err: serde_json::Error) -> ProtocolError {
+    let Ok(serde_json::Value::Object(message)) = serde_json::from_slice(bytes) else {
+        return ProtocolError::Malformed(err.to_string());
+    };
+    let (variant, body) = match message.iter().next() {
+        Some((variant, body)) if message.len() == 1 => (variant, body),
+        _ => return ProtocolError::Malformed(err.to_string()),
+    };
+    if !PROOF_DATA_VARIANTS.contains(&variant.as_str()) {
+        return ProtocolError::UnknownMessage(variant.clone());
+    }
+
+    // Versions are plain numbers in BatchResponse/CapabilitiesACK and string
+    // tags inside ProverCapabilities.
+    let version = |value: &serde_json::Value| match value.get("protocol_version")? {
+        serde_json::Value::Number(n) => n.as_u64(),
+        serde_json::Value::String(s) => s.parse().ok(),
+        _ => None,
+    };
+    let received = version(body).or_else(|| body.get("capabilities").and_then(version));
+    match received.and_then(|v| u32::try_from(v).ok()) {
+        Some(v) if !is_supported_protocol_version(v) => ProtocolError::unsupported_version(v),
+        _ => ProtocolError::Malformed(err.to_string()),
+    }
 }
 
 #[cfg(test)]
@@ -373,6 +867,7 @@ mod tests {
                 commit_hash,
                 prover_type,
                 supported_programs,
+                ..
             } => {
                 assert_eq!(commit_hash, "abc123");
                 assert_eq!(prover_type, ProverType::Exec);
@@ -396,6 +891,7 @@ mod tests {
                 commit_hash,
                 prover_type,
                 supported_programs,
+                ..
             } => {
                 assert_eq!(commit_hash, "hash1");
                 assert_eq!(prover_type, ProverType::SP1);
@@ -451,13 +947,45 @@ mod tests {
         }"#;
         let data: ProofData = serde_json::from_str(json).expect("should deserialize");
         match data {
-            ProofData::ProofSubmit { program_id, .. } => {
+            ProofData::ProofSubmit {
+                program_id,
+                feedback,
+                ..
+            } => {
                 assert_eq!(program_id, "evm-l2", "default program_id should be evm-l2");
+                assert!(feedback.is_none(), "default feedback should be None");
             }
             _ => panic!("expected ProofSubmit"),
         }
     }
 
+    #[test]
+    fn proof_submit_with_feedback_roundtrips() {
+        let proof = BatchProof::ProofCalldata(ProofCalldata {
+            prover_type: ProverType::Exec,
+            calldata: vec![],
+            public_values: vec![],
+        });
+        let feedback = ProvingFeedback {
+            gas_used: 15_000_000,
+            cycles: None,
+            proving_time_ms: 42_000,
+            queue_depth: 2,
+        };
+        let original =
+            ProofData::proof_submit_with_feedback(5, proof, "evm-l2".to_string(), feedback);
+        let json = serde_json::to_string(&original).expect("serialize");
+        assert!(!json.contains("cycles"), "unknown cycles are left out");
+        let deserialized: ProofData = serde_json::from_str(&json).expect("deserialize");
+        match deserialized {
+            ProofData::ProofSubmit {
+                feedback: deserialized,
+                ..
+            } => assert_eq!(deserialized, Some(feedback)),
+            _ => panic!("expected ProofSubmit"),
+        }
+    }
+
     #[test]
     fn proof_submit_with_program_id_roundtrips() {
         let proof = BatchProof::ProofCalldata(ProofCalldata {
@@ -527,6 +1055,23 @@ mod tests {
             ProofData::ProverTypeNotNeeded {
                 prover_type: ProverType::TDX,
             },
+            ProofData::batch_request_with_capabilities(
+                "h".into(),
+                ProverType::SP1,
+                sample_capabilities(),
+            ),
+            ProofData::capabilities(sample_capabilities()),
+            ProofData::capabilities_ack(vec!["evm-l2".into()]),
+            ProofData::protocol_error(ProtocolError::UnknownMessage("Foo".into())),
+            ProofData::batch_request("h".into(), ProverType::SP1).with_cached_inputs(vec![
+                CachedInput {
+                    batch_number: 4,
+                    input_hash: H256::repeat_byte(0x11),
+                },
+            ]),
+            ProofData::input_request("h".into(), 4, Some(H256::repeat_byte(0x11))),
+            ProofData::input_response(4, None, Some(H256::repeat_byte(0x11))),
+            ProofData::input_response(5, None, None),
         ];
         for variant in &variants {
             let json = serde_json::to_string(variant).expect("serialize");
@@ -558,6 +1103,7 @@ mod tests {
                 input,
                 format,
                 program_id,
+                ..
             } => {
                 assert!(batch_number.is_none());
                 assert!(input.is_none());
@@ -567,4 +1113,301 @@ mod tests {
             _ => panic!("expected BatchResponse"),
         }
     }
+
+    // ── Capability negotiation tests ───────────────────────────────────
+
+    fn sample_capabilities() -> ProverCapabilities {
+        ProverCapabilities::new(
+            vec![ProverType::SP1],
+            vec![ProgramCapability {
+                program_id: "evm-l2".to_string(),
+                version: "1.0.0".to_string(),
+                elf_hash: Some([0xab; 32]),
+                build_commit: Some("0123abcd".to_string()),
+            }],
+            vec![ProofFormat::Groth16],
+        )
+    }
+
+    /// Shape of the protocol messages before capability negotiation existed,
+    /// used to check that old provers and coordinators still understand the
+    /// messages produced by new ones.
+    #[derive(Deserialize)]
+    #[allow(dead_code)]
+    enum LegacyProofData {
+        BatchRequest {
+            commit_hash: String,
+            prover_type: ProverType,
+            #[serde(default)]
+            supported_programs: Vec<String>,
+        },
+        BatchResponse {
+            batch_number: Option<u64>,
+            input: Option<ProverInputData>,
+            format: Option<ProofFormat>,
+            #[serde(default)]
+            program_id: Option<String>,
+        },
+    }
+
+    #[test]
+    fn capabilities_roundtrip_with_version_tag() {
+        let original = ProofData::capabilities(sample_capabilities());
+        let json = serde_json::to_string(&original).expect("serialize");
+        assert!(json.contains(r#""protocol_version":"1""#), "{json}");
+        match ProofData::decode(json.as_bytes()).expect("decode") {
+            ProofData::Capabilities { capabilities } => {
+                assert_eq!(capabilities, sample_capabilities());
+                assert_eq!(capabilities.protocol_version(), PROVER_PROTOCOL_VERSION);
+            }
+            _ => panic!("expected Capabilities"),
+        }
+    }
+
+    #[test]
+    fn can_prove_checks_backend_program_and_format() {
+        let caps = sample_capabilities();
+        assert!(caps.can_prove(ProverType::SP1, "evm-l2", ProofFormat::Groth16));
+        assert!(!caps.can_prove(ProverType::RISC0, "evm-l2", ProofFormat::Groth16));
+        assert!(!caps.can_prove(ProverType::SP1, "zk-dex", ProofFormat::Groth16));
+        assert!(!caps.can_prove(ProverType::SP1, "evm-l2", ProofFormat::Compressed));
+    }
+
+    #[test]
+    fn old_prover_new_coordinator() {
+        // An old prover sends a BatchRequest without capabilities...
+        let json = r#"{"BatchRequest":{"commit_hash":"abc","prover_type":"SP1","supported_programs":["evm-l2"]}}"#;
+        match ProofData::decode(json.as_bytes()).expect("decode") {
+            ProofData::BatchRequest { capabilities, .. } => assert!(capabilities.is_none()),
+            _ => panic!("expected BatchRequest"),
+        }
+
+        // ...and still understands the versioned BatchResponse it gets back.
+        let response = ProofData::empty_batch_response();
+        let json = serde_json::to_string(&response).expect("serialize");
+        let legacy: LegacyProofData = serde_json::from_str(&json).expect("legacy decode");
+        assert!(matches!(
+            legacy,
+            LegacyProofData::BatchResponse {
+                batch_number: None,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn new_prover_old_coordinator() {
+        // An old coordinator can't parse the Capabilities handshake, which
+        // makes it drop the connection and the prover fall back to legacy mode.
+        let json = serde_json::to_string(&ProofData::capabilities(sample_capabilities()))
+            .expect("serialize");
+        assert!(serde_json::from_str::<LegacyProofData>(&json).is_err());
+
+        // It does accept BatchRequests carrying capabilities, ignoring them.
+        let request = ProofData::batch_request_with_capabilities(
+            "abc".into(),
+            ProverType::SP1,
+            sample_capabilities(),
+        );
+        let json = serde_json::to_string(&request).expect("serialize");
+        match serde_json::from_str::<LegacyProofData>(&json).expect("legacy decode") {
+            LegacyProofData::BatchRequest {
+                supported_programs, ..
+            } => assert_eq!(supported_programs, vec!["evm-l2"]),
+            _ => panic!("expected BatchRequest"),
+        }
+
+        // Its unversioned BatchResponse is accepted by the new prover.
+        let json = r#"{"BatchResponse":{"batch_number":3,"input":null,"format":null}}"#;
+        let data = ProofData::decode(json.as_bytes()).expect("decode");
+        assert_eq!(data.protocol_version(), None);
+    }
+
+    #[test]
+    fn cached_inputs_are_optional_on_both_sides() {
+        // Old coordinators ignore the cached inputs and always send the input...
+        let cached = CachedInput {
+            batch_number: 4,
+            input_hash: H256::repeat_byte(0x11),
+        };
+        let request = ProofData::batch_request("abc".into(), ProverType::SP1)
+            .with_cached_inputs(vec![cached]);
+        let json = serde_json::to_string(&request).expect("serialize");
+        assert!(serde_json::from_str::<LegacyProofData>(&json).is_ok());
+        match ProofData::decode(json.as_bytes()).expect("decode") {
+            ProofData::BatchRequest { cached_inputs, .. } => {
+                assert_eq!(cached_inputs, vec![cached])
+            }
+            _ => panic!("expected BatchRequest"),
+        }
+
+        // ...and their BatchResponses carry no input hash.
+        let json = r#"{"BatchResponse":{"batch_number":4,"input":null,"format":null}}"#;
+        match ProofData::decode(json.as_bytes()).expect("decode") {
+            ProofData::BatchResponse { input_hash, .. } => assert!(input_hash.is_none()),
+            _ => panic!("expected BatchResponse"),
+        }
+
+        // Requests without cached inputs don't mention them at all.
+        let json = serde_json::to_string(&ProofData::batch_request("abc".into(), ProverType::SP1))
+            .expect("serialize");
+        assert!(!json.contains("cached_inputs"), "{json}");
+    }
+
+    #[test]
+    fn batch_response_with_input_hash_may_omit_input() {
+        let response = ProofData::batch_response_with_input_hash(
+            4,
+            None,
+            H256::repeat_byte(0x11),
+            ProofFormat::Groth16,
+            "evm-l2".into(),
+        );
+        let json = serde_json::to_string(&response).expect("serialize");
+        match ProofData::decode(json.as_bytes()).expect("decode") {
+            ProofData::BatchResponse {
+                batch_number,
+                input,
+                input_hash,
+                ..
+            } => {
+                assert_eq!(batch_number, Some(4));
+                assert!(input.is_none());
+                assert_eq!(input_hash, Some(H256::repeat_byte(0x11)));
+            }
+            _ => panic!("expected BatchResponse"),
+        }
+    }
+
+    #[test]
+    fn batch_response_with_unknown_version_is_rejected() {
+        let json = r#"{"BatchResponse":{"batch_number":3,"input":null,"format":null,"protocol_version":99}}"#;
+        let err = ProofData::decode(json.as_bytes())
+            .err()
+            .expect("should fail");
+        assert_eq!(
+            err,
+            ProtocolError::UnsupportedVersion {
+                received: 99,
+                supported: SUPPORTED_PROTOCOL_VERSIONS.to_vec(),
+            }
+        );
+    }
+
+    #[test]
+    fn capabilities_with_unknown_version_is_rejected() {
+        let json = r#"{"Capabilities":{"capabilities":{"protocol_version":"2","backends":[],"programs":[],"proof_formats":[],"gpus":4}}}"#;
+        let err = ProofData::decode(json.as_bytes())
+            .err()
+            .expect("should fail");
+        assert!(matches!(
+            err,
+            ProtocolError::UnsupportedVersion { received: 2, .. }
+        ));
+    }
+
+    #[test]
+    fn unknown_message_type_is_rejected() {
+        let json = r#"{"BatchLease":{"batch_number":3}}"#;
+        let err = ProofData::decode(json.as_bytes())
+            .err()
+            .expect("should fail");
+        assert_eq!(err, ProtocolError::UnknownMessage("BatchLease".to_string()));
+    }
+
+    #[test]
+    fn garbage_is_malformed() {
+        for input in [
+            &b""[..],
+            b"not json",
+            br#"{"BatchRequest":{"prover_type":"Exec"}}"#,
+        ] {
+            let err = ProofData::decode(input).err().expect("should fail");
+            assert!(matches!(err, ProtocolError::Malformed(_)), "{err}");
+        }
+    }
+
+    #[test]
+    fn proof_format_parses_case_insensitively() {
+        assert_eq!("groth16".parse(), Ok(ProofFormat::Groth16));
+        assert_eq!("Compressed".parse(), Ok(ProofFormat::Compressed));
+        assert!("plonk".parse::<ProofFormat>().is_err());
+    }
+
+    // ── Prover input layout versions ───────────────────────────────────
+
+    #[test]
+    fn prover_input_round_trips() {
+        let input = ProverInputData {
+            blocks: vec![],
+            execution_witness: Default::default(),
+            elasticity_multiplier: 2,
+            blob_commitment: [1; 48],
+            blob_proof: [2; 48],
+            fee_configs: vec![],
+            native_token_scale_factor: None,
+            ordering_commitment: None,
+            publish_block_summaries: true,
+            commit_deposit_inclusion: true,
+        };
+        let encoded = input.encode().unwrap();
+        assert!(encoded.starts_with(PROVER_INPUT_MAGIC));
+
+        let decoded = ProverInputData::decode(&encoded).unwrap();
+        assert_eq!(decoded.elasticity_multiplier, 2);
+        assert!(decoded.publish_block_summaries);
+        assert!(decoded.commit_deposit_inclusion);
+    }
+
+    #[test]
+    fn legacy_prover_input_decodes() {
+        // An input stored as a bare archive, before the layout was versioned
+        let legacy = legacy::ProverInputDataV0 {
+            blocks: vec![],
+            execution_witness: legacy::ExecutionWitnessV0 {
+                codes: vec![vec![0x60, 0x00]],
+                block_headers_bytes: vec![],
+                first_block_number: 7,
+                chain_config: legacy::ChainConfigV0 {
+                    chain_id: 65536999,
+                    prague_time: Some(0),
+                    ..Default::default()
+                },
+                state_trie_root: None,
+                storage_trie_roots: Default::default(),
+                keys: vec![],
+            },
+            elasticity_multiplier: 2,
+            blob_commitment: [1; 48],
+            blob_proof: [2; 48],
+            fee_configs: vec![],
+            native_token_scale_factor: Some(U256::from(1000)),
+        };
+        let encoded = rkyv::to_bytes::<rkyv::rancor::Error>(&legacy).unwrap();
+
+        let input = ProverInputData::decode(&encoded).unwrap();
+        let witness = &input.execution_witness;
+        assert_eq!(witness.codes, vec![vec![0x60, 0x00]]);
+        assert_eq!(witness.first_block_number, 7);
+        assert_eq!(witness.chain_config.chain_id, 65536999);
+        assert_eq!(witness.chain_config.prague_time, Some(0));
+        assert_eq!(witness.chain_config.eof_time, None);
+        assert_eq!(
+            witness.chain_config.system_contracts,
+            ethrex_common::types::SystemContractsConfig::default()
+        );
+        assert_eq!(input.elasticity_multiplier, 2);
+        assert_eq!(input.blob_commitment, [1; 48]);
+        assert_eq!(input.native_token_scale_factor, Some(U256::from(1000)));
+        assert!(input.ordering_commitment.is_none());
+        assert!(!input.publish_block_summaries);
+        assert!(!input.commit_deposit_inclusion);
+    }
+
+    #[test]
+    fn unknown_prover_input_version_is_rejected() {
+        let mut encoded = PROVER_INPUT_MAGIC.to_vec();
+        encoded.push(PROVER_INPUT_VERSION + 1);
+        assert!(ProverInputData::decode(&encoded).is_err());
+    }
 }
@@ -0,0 +1,169 @@
+//! Layouts of [`ProverInputData`] stored before its encoding was versioned.
+//!
+//! Rollup stores keep the inputs of every batch that wasn't verified yet, so
+//! inputs written by older sequencers must still decode after an upgrade.
+//! These types mirror the archived layout of the old structs field by field
+//! and must never change.
+
+use std::collections::BTreeMap;
+
+use ethrex_common::rkyv_utils::{H160Wrapper, OptionH160Wrapper, OptionU256Wrapper, VecVecWrapper};
+use ethrex_common::types::{
+    BlobSchedule, Block, ChainConfig, blobs_bundle, block_execution_witness::ExecutionWitness,
+    fee_config::FeeConfig,
+};
+use ethrex_common::{Address, U256};
+use ethrex_trie::Node;
+use rkyv::with::{Identity, MapKV};
+use rkyv::{Archive, Deserialize as RDeserialize, Serialize as RSerialize};
+
+use super::ProverInputData;
+
+/// [`ProverInputData`] before the ordering commitment, block summaries and
+/// deposit inclusion flags were added.
+#[derive(RDeserialize, RSerialize, Archive)]
+pub(super) struct ProverInputDataV0 {
+    pub blocks: Vec<Block>,
+    pub execution_witness: ExecutionWitnessV0,
+    pub elasticity_multiplier: u64,
+    pub blob_commitment: blobs_bundle::Commitment,
+    pub blob_proof: blobs_bundle::Proof,
+    pub fee_configs: Vec<FeeConfig>,
+    #[rkyv(with = OptionU256Wrapper)]
+    pub native_token_scale_factor: Option<U256>,
+}
+
+impl From<ProverInputDataV0> for ProverInputData {
+    fn from(input: ProverInputDataV0) -> Self {
+        ProverInputData {
+            blocks: input.blocks,
+            execution_witness: input.execution_witness.into(),
+            elasticity_multiplier: input.elasticity_multiplier,
+            blob_commitment: input.blob_commitment,
+            blob_proof: input.blob_proof,
+            fee_configs: input.fee_configs,
+            native_token_scale_factor: input.native_token_scale_factor,
+            ordering_commitment: None,
+            publish_block_summaries: false,
+            commit_deposit_inclusion: false,
+        }
+    }
+}
+
+/// [`ExecutionWitness`] holding a [`ChainConfigV0`].
+#[derive(RDeserialize, RSerialize, Archive)]
+pub(super) struct ExecutionWitnessV0 {
+    #[rkyv(with = VecVecWrapper)]
+    pub codes: Vec<Vec<u8>>,
+    #[rkyv(with = VecVecWrapper)]
+    pub block_headers_bytes: Vec<Vec<u8>>,
+    pub first_block_number: u64,
+    pub chain_config: ChainConfigV0,
+    pub state_trie_root: Option<Node>,
+    #[rkyv(with = MapKV<H160Wrapper, Identity>)]
+    pub storage_trie_roots: BTreeMap<Address, Node>,
+    #[rkyv(with = VecVecWrapper)]
+    pub keys: Vec<Vec<u8>>,
+}
+
+impl From<ExecutionWitnessV0> for ExecutionWitness {
+    fn from(witness: ExecutionWitnessV0) -> Self {
+        ExecutionWitness {
+            codes: witness.codes,
+            block_headers_bytes: witness.block_headers_bytes,
+            first_block_number: witness.first_block_number,
+            chain_config: witness.chain_config.into(),
+            state_trie_root: witness.state_trie_root,
+            storage_trie_roots: witness.storage_trie_roots,
+            keys: witness.keys,
+        }
+    }
+}
+
+/// [`ChainConfig`] before `eof_time` and `system_contracts` were added.
+#[derive(Default, RDeserialize, RSerialize, Archive)]
+pub(super) struct ChainConfigV0 {
+    pub chain_id: u64,
+    pub homestead_block: Option<u64>,
+    pub dao_fork_block: Option<u64>,
+    pub dao_fork_support: bool,
+    pub eip150_block: Option<u64>,
+    pub eip155_block: Option<u64>,
+    pub eip158_block: Option<u64>,
+    pub byzantium_block: Option<u64>,
+    pub constantinople_block: Option<u64>,
+    pub petersburg_block: Option<u64>,
+    pub istanbul_block: Option<u64>,
+    pub muir_glacier_block: Option<u64>,
+    pub berlin_block: Option<u64>,
+    pub london_block: Option<u64>,
+    pub arrow_glacier_block: Option<u64>,
+    pub gray_glacier_block: Option<u64>,
+    pub merge_netsplit_block: Option<u64>,
+    pub shanghai_time: Option<u64>,
+    pub cancun_time: Option<u64>,
+    pub prague_time: Option<u64>,
+    pub verkle_time: Option<u64>,
+    pub osaka_time: Option<u64>,
+    pub bpo1_time: Option<u64>,
+    pub bpo2_time: Option<u64>,
+    pub bpo3_time: Option<u64>,
+    pub bpo4_time: Option<u64>,
+    pub bpo5_time: Option<u64>,
+    pub amsterdam_time: Option<u64>,
+    pub terminal_total_difficulty: Option<u128>,
+    pub terminal_total_difficulty_passed: bool,
+    pub blob_schedule: BlobSchedule,
+    #[rkyv(with = H160Wrapper)]
+    pub deposit_contract_address: Address,
+    pub enable_verkle_at_genesis: bool,
+    #[rkyv(with = OptionH160Wrapper)]
+    pub native_token_l1_address: Option<Address>,
+    pub native_token_l1_decimals: Option<u8>,
+}
+
+impl From<ChainConfigV0> for ChainConfig {
+    /// Old configs had no EOF schedule and always used the mainnet system
+    /// contracts.
+    fn from(config: ChainConfigV0) -> Self {
+        ChainConfig {
+            chain_id: config.chain_id,
+            homestead_block: config.homestead_block,
+            dao_fork_block: config.dao_fork_block,
+            dao_fork_support: config.dao_fork_support,
+            eip150_block: config.eip150_block,
+            eip155_block: config.eip155_block,
+            eip158_block: config.eip158_block,
+            byzantium_block: config.byzantium_block,
+            constantinople_block: config.constantinople_block,
+            petersburg_block: config.petersburg_block,
+            istanbul_block: config.istanbul_block,
+            muir_glacier_block: config.muir_glacier_block,
+            berlin_block: config.berlin_block,
+            london_block: config.london_block,
+            arrow_glacier_block: config.arrow_glacier_block,
+            gray_glacier_block: config.gray_glacier_block,
+            merge_netsplit_block: config.merge_netsplit_block,
+            shanghai_time: config.shanghai_time,
+            cancun_time: config.cancun_time,
+            prague_time: config.prague_time,
+            verkle_time: config.verkle_time,
+            osaka_time: config.osaka_time,
+            bpo1_time: config.bpo1_time,
+            bpo2_time: config.bpo2_time,
+            bpo3_time: config.bpo3_time,
+            bpo4_time: config.bpo4_time,
+            bpo5_time: config.bpo5_time,
+            amsterdam_time: config.amsterdam_time,
+            eof_time: None,
+            terminal_total_difficulty: config.terminal_total_difficulty,
+            terminal_total_difficulty_passed: config.terminal_total_difficulty_passed,
+            blob_schedule: config.blob_schedule,
+            deposit_contract_address: config.deposit_contract_address,
+            enable_verkle_at_genesis: config.enable_verkle_at_genesis,
+            native_token_l1_address: config.native_token_l1_address,
+            native_token_l1_decimals: config.native_token_l1_decimals,
+            system_contracts: Default::default(),
+        }
+    }
+}
@@ -0,0 +1,242 @@
+//! Merkle proofs of L2→L1 messages (withdrawals) against the message root a
+//! batch commits on L1.
+
+use ethereum_types::{H256, U256};
+use ethrex_common::types::{Block, Receipt};
+
+use crate::merkle_tree::{compute_merkle_proof, compute_merkle_root, verify_merkle_proof};
+use crate::messages::{L1Message, L1MessageProof, get_l1_message_hash, get_receipt_l1_messages};
+
+/// An L1 message of a batch, with the transaction that emitted it.
+#[derive(Debug, Clone)]
+pub struct BatchL1Message {
+    /// Hash of the emitting transaction, `None` if the block had no transaction
+    /// for the receipt.
+    pub tx_hash: Option<H256>,
+    pub message: L1Message,
+    pub message_hash: H256,
+}
+
+/// Rebuilds the L1 message tree of a batch and proves its messages.
+///
+/// The tree holds the messages in the order the guest program commits them:
+/// block by block, receipt by receipt, log by log.
+pub struct WithdrawalProver {
+    batch_number: u64,
+    messages: Vec<BatchL1Message>,
+    message_hashes: Vec<H256>,
+}
+
+impl WithdrawalProver {
+    /// Collects the L1 messages of a batch from its blocks and their receipts.
+    pub fn from_blocks(batch_number: u64, blocks: &[Block], receipts: &[Vec<Receipt>]) -> Self {
+        let mut messages = Vec::new();
+        for (block, receipts) in blocks.iter().zip(receipts) {
+            for (index, receipt) in receipts.iter().enumerate() {
+                let tx_hash = block.body.transactions.get(index).map(|tx| tx.hash());
+                messages.extend(
+                    get_receipt_l1_messages(receipt).map(|message| BatchL1Message {
+                        tx_hash,
+                        message_hash: get_l1_message_hash(&message),
+                        message,
+                    }),
+                );
+            }
+        }
+        let message_hashes = messages.iter().map(|msg| msg.message_hash).collect();
+        Self {
+            batch_number,
+            messages,
+            message_hashes,
+        }
+    }
+
+    pub fn batch_number(&self) -> u64 {
+        self.batch_number
+    }
+
+    pub fn messages(&self) -> &[BatchL1Message] {
+        &self.messages
+    }
+
+    /// Message hashes in tree order, as stored by the committer.
+    pub fn message_hashes(&self) -> &[H256] {
+        &self.message_hashes
+    }
+
+    /// The L1 out messages merkle root the batch commits.
+    pub fn root(&self) -> H256 {
+        compute_merkle_root(&self.message_hashes)
+    }
+
+    /// Proves the message with the given id, if the batch contains it.
+    pub fn proof_by_message_id(&self, message_id: U256) -> Option<L1MessageProof> {
+        let index = self
+            .messages
+            .iter()
+            .position(|msg| msg.message.message_id == message_id)?;
+        self.proof_at(index)
+    }
+
+    /// Proves every message emitted by the given transaction.
+    pub fn proofs_by_tx_hash(&self, tx_hash: H256) -> Vec<L1MessageProof> {
+        self.messages
+            .iter()
+            .enumerate()
+            .filter(|(_, msg)| msg.tx_hash == Some(tx_hash))
+            .filter_map(|(index, _)| self.proof_at(index))
+            .collect()
+    }
+
+    fn proof_at(&self, index: usize) -> Option<L1MessageProof> {
+        let msg = self.messages.get(index)?;
+        Some(L1MessageProof {
+            batch_number: self.batch_number,
+            message_id: msg.message.message_id,
+            message_hash: msg.message_hash,
+            merkle_proof: compute_merkle_proof(&self.message_hashes, index)?,
+        })
+    }
+}
+
+/// Checks a withdrawal proof against the message root committed for its batch.
+pub fn verify_withdrawal_proof(proof: &L1MessageProof, root: H256) -> bool {
+    verify_merkle_proof(proof.message_hash, &proof.merkle_proof, root)
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+    use crate::messages::MESSENGER_ADDRESS;
+    use ethrex_common::types::events::L1_MESSAGE_EVENT;
+    use ethrex_common::types::{EIP1559Transaction, Log, Transaction, TxType};
+    use serde::Deserialize;
+
+    /// Test vector shared with the guest program's message tests.
+    const WITHDRAWAL_TREE: &str = include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../../../fixtures/l2/withdrawal_tree.json"
+    ));
+
+    #[derive(Deserialize)]
+    struct WithdrawalTree {
+        /// Messages emitted by each receipt of each block.
+        blocks: Vec<Vec<Vec<L1Message>>>,
+        message_hashes: Vec<H256>,
+        root: H256,
+    }
+
+    fn messenger_log(msg: &L1Message) -> Log {
+        Log {
+            address: MESSENGER_ADDRESS,
+            topics: vec![
+                L1_MESSAGE_EVENT.topic,
+                H256::from(msg.from),
+                msg.data_hash,
+                H256(msg.message_id.to_big_endian()),
+            ],
+            data: Default::default(),
+        }
+    }
+
+    /// Blocks with one transaction per receipt, each emitting the vector's messages.
+    fn batch(tree: &WithdrawalTree) -> (Vec<Block>, Vec<Vec<Receipt>>) {
+        let mut nonce = 0;
+        tree.blocks
+            .iter()
+            .map(|block_messages| {
+                let mut block = Block::default();
+                let receipts: Vec<Receipt> = block_messages
+                    .iter()
+                    .map(|messages| {
+                        block
+                            .body
+                            .transactions
+                            .push(Transaction::EIP1559Transaction(EIP1559Transaction {
+                                nonce,
+                                ..Default::default()
+                            }));
+                        nonce += 1;
+                        let logs = messages.iter().map(messenger_log).collect();
+                        Receipt::new(TxType::EIP1559, true, 0, logs)
+                    })
+                    .collect();
+                (block, receipts)
+            })
+            .unzip()
+    }
+
+    fn load_vector() -> WithdrawalTree {
+        serde_json::from_str(WITHDRAWAL_TREE).expect("invalid withdrawal tree vector")
+    }
+
+    #[test]
+    fn rebuilds_committed_tree() {
+        let tree = load_vector();
+        let (blocks, receipts) = batch(&tree);
+        let prover = WithdrawalProver::from_blocks(7, &blocks, &receipts);
+
+        assert_eq!(prover.message_hashes(), tree.message_hashes.as_slice());
+        assert_eq!(prover.root(), tree.root);
+    }
+
+    #[test]
+    fn proofs_verify_against_root() {
+        let tree = load_vector();
+        let (blocks, receipts) = batch(&tree);
+        let prover = WithdrawalProver::from_blocks(7, &blocks, &receipts);
+
+        for msg in prover.messages() {
+            let proof = prover
+                .proof_by_message_id(msg.message.message_id)
+                .expect("message should be provable");
+            assert_eq!(proof.batch_number, 7);
+            assert_eq!(proof.message_hash, msg.message_hash);
+            assert!(verify_withdrawal_proof(&proof, tree.root));
+            assert!(!verify_withdrawal_proof(&proof, H256::zero()));
+        }
+        assert!(prover.proof_by_message_id(U256::from(100)).is_none());
+    }
+
+    #[test]
+    fn proves_every_message_of_a_transaction() {
+        let tree = load_vector();
+        let (blocks, receipts) = batch(&tree);
+        let prover = WithdrawalProver::from_blocks(7, &blocks, &receipts);
+
+        // The third transaction of the first block emits two messages
+        let tx_hash = blocks
+            .first()
+            .and_then(|block| block.body.transactions.get(2))
+            .expect("vector should have a third transaction")
+            .hash();
+        let ids: Vec<U256> = prover
+            .proofs_by_tx_hash(tx_hash)
+            .iter()
+            .map(|proof| proof.message_id)
+            .collect();
+        assert_eq!(ids, vec![U256::from(2), U256::from(3)]);
+
+        // The second transaction emits none
+        let tx_hash = blocks
+            .first()
+            .and_then(|block| block.body.transactions.get(1))
+            .expect("vector should have a second transaction")
+            .hash();
+        assert!(prover.proofs_by_tx_hash(tx_hash).is_empty());
+    }
+
+    #[test]
+    fn tampered_proof_is_rejected() {
+        let tree = load_vector();
+        let (blocks, receipts) = batch(&tree);
+        let prover = WithdrawalProver::from_blocks(7, &blocks, &receipts);
+
+        let mut proof = prover
+            .proof_by_message_id(U256::one())
+            .expect("message should be provable");
+        proof.message_hash = H256::repeat_byte(0xab);
+        assert!(!verify_withdrawal_proof(&proof, tree.root));
+    }
+}
@@ -439,6 +439,19 @@ contract CommonBridge is
         pendingMessagesIndexPerChain[chainId] += number;
     }
 
+    /// @inheritdoc ICommonBridge
+    function hasPrivilegedTransactionsExpiredAt(
+        uint256 offset,
+        uint256 timestamp
+    ) public view returns (bool) {
+        return
+            offset < pendingTxHashesLength() &&
+            timestamp >
+            privilegedTxDeadline[
+                pendingTxHashes[pendingPrivilegedTxIndex + offset]
+            ];
+    }
+
     /// @inheritdoc ICommonBridge
     function hasExpiredPrivilegedTransactions() public view returns (bool) {
         if (pendingTxHashesLength() != 0) {
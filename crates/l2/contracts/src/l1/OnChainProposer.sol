@@ -32,6 +32,8 @@ contract OnChainProposer is
     /// @dev withdrawalsLogsMerkleRoot is the Merkle root of the Merkle tree containing
     /// all the withdrawals that were processed in the batch being committed
     /// @dev commitHash: keccak of the git commit hash that produced the proof/verification key used for this batch
+    /// @dev lastBlockTimestamp: timestamp of the last block of the batch. Privileged transactions
+    /// whose inclusion deadline passed before it must be processed by the batch.
     struct BatchCommitmentInfo {
         bytes32 newStateRoot;
         bytes32 blobKZGVersionedHash;
@@ -47,6 +49,17 @@ contract OnChainProposer is
         /// For EVM-L2 (programTypeId == 1) this is bytes32(0) since public inputs are
         /// reconstructed from commitment data.
         bytes32 publicValuesHash;
+        /// @dev Root of the ordering promised by the preconfirmation gateway and the id
+        /// of the key that signed it. bytes32(0) if the batch has no ordering commitment.
+        bytes32 orderingRoot;
+        uint256 orderingSignerKeyId;
+        uint256 lastBlockTimestamp;
+        /// @dev Hash of the execution summaries of the batch's blocks, matched against the
+        /// proof. bytes32(0) if the batch doesn't publish them.
+        bytes32 blockSummariesHash;
+        /// @dev Whether the proof commits the last block timestamp in a deposit inclusion
+        /// section. False for batches committed before the section existed.
+        bool depositInclusion;
     }
 
     uint8 internal constant SP1_VERIFIER_ID = 1;
@@ -55,6 +68,21 @@ contract OnChainProposer is
     /// @notice Program type ID for the default EVM-L2 guest program.
     uint8 internal constant DEFAULT_PROGRAM_TYPE_ID = 1;
 
+    /// @notice Tag ending the ordering commitment section of the public inputs.
+    /// @dev Matches ordering_commitment_tag() of the L2 guest program output.
+    bytes32 internal constant ORDERING_COMMITMENT_TAG =
+        keccak256("ethrex.l2.ordering_commitment.v1");
+
+    /// @notice Tag ending the deposit inclusion section of the public inputs.
+    /// @dev Matches deposit_inclusion_tag() of the L2 guest program output.
+    bytes32 internal constant DEPOSIT_INCLUSION_TAG =
+        keccak256("ethrex.l2.deposit_inclusion.v1");
+
+    /// @notice Tag ending the block summaries section of the public inputs.
+    /// @dev Matches block_summaries_tag() of the L2 guest program output.
+    bytes32 internal constant BLOCK_SUMMARIES_TAG =
+        keccak256("ethrex.l2.block_summaries.v1");
+
     /// @notice Aligned Layer proving system ID for SP1 in isProofVerified calls.
     /// @dev Currently only SP1 is supported by Aligned in aggregation mode.
     uint16 internal constant ALIGNED_SP1_PROVING_SYSTEM_ID = 1;
@@ -318,10 +346,14 @@ contract OnChainProposer is
         bytes32 withdrawalsLogsMerkleRoot,
         bytes32 processedPrivilegedTransactionsRollingHash,
         bytes32 lastBlockHash,
+        uint256 lastBlockTimestamp,
         uint256 nonPrivilegedTransactions,
         bytes32 commitHash,
         uint8 programTypeId,
         bytes32 publicValuesHash,
+        bytes32 orderingRoot,
+        uint256 orderingSignerKeyId,
+        bytes32 blockSummariesHash,
         ICommonBridge.BalanceDiff[] calldata balanceDiffs,
         ICommonBridge.L2MessageRollingHash[] calldata l2MessageRollingHashes
     ) external override onlyOwner whenNotPaused {
@@ -351,6 +383,19 @@ contract OnChainProposer is
             );
         }
 
+        // Privileged transactions left pending by this batch must not have
+        // expired before its last block. Checking at commit time rather than
+        // at verification keeps a committed batch always verifiable.
+        if (
+            ICommonBridge(BRIDGE).hasPrivilegedTransactionsExpiredAt(
+                _privilegedTransactionsProcessedSinceLastVerified() +
+                    uint16(bytes2(processedPrivilegedTransactionsRollingHash)),
+                lastBlockTimestamp
+            )
+        ) {
+            revert("00w"); // batch skipped privileged transactions whose inclusion deadline passed before its last block
+        }
+
         for (uint256 i = 0; i < l2MessageRollingHashes.length; i++) {
             bytes32 receivedRollingHash = l2MessageRollingHashes[i].rollingHash;
             bytes32 expectedRollingHash = ICommonBridge(BRIDGE)
@@ -435,7 +480,12 @@ contract OnChainProposer is
             commitHash,
             l2MessageRollingHashes,
             effectiveProgramTypeId,
-            publicValuesHash
+            publicValuesHash,
+            orderingRoot,
+            orderingSignerKeyId,
+            lastBlockTimestamp,
+            blockSummariesHash,
+            effectiveProgramTypeId == DEFAULT_PROGRAM_TYPE_ID
         );
         emit BatchCommitted(newStateRoot);
 
@@ -764,6 +814,16 @@ contract OnChainProposer is
         );
     }
 
+    /// @notice Counts the privileged transactions processed by committed batches that
+    /// weren't verified yet, which are still at the front of the bridge's pending queue.
+    function _privilegedTransactionsProcessedSinceLastVerified() internal view returns (uint256 count) {
+        for (uint256 i = lastVerifiedBatch + 1; i <= lastCommittedBatch; i++) {
+            count += uint16(
+                bytes2(batchCommitments[i].processedPrivilegedTransactionsRollingHash)
+            );
+        }
+    }
+
     /// @notice Constructs public inputs from committed batch data for proof verification.
     /// @dev Public inputs structure:
     /// Fixed-size fields (256 bytes):
@@ -789,6 +849,17 @@ contract OnChainProposer is
     /// - For each L2 in message rolling hash:
     ///   - bytes: Chain ID (32 bytes)
     ///   - bytes: Rolling hash (32 bytes)
+    /// Optional sections, each ending with its tag:
+    /// - If the batch was committed with a deposit inclusion section (EVM-L2 batches):
+    ///   - bytes: Last block timestamp (32 bytes)
+    ///   - bytes: DEPOSIT_INCLUSION_TAG (32 bytes)
+    /// - If the batch has an ordering commitment:
+    ///   - bytes: Ordering root (32 bytes)
+    ///   - bytes: Signer key id (32 bytes)
+    ///   - bytes: ORDERING_COMMITMENT_TAG (32 bytes)
+    /// - If the batch publishes block summaries, always last:
+    ///   - bytes: Block summaries hash (32 bytes)
+    ///   - bytes: BLOCK_SUMMARIES_TAG (32 bytes)
     /// @param batchNumber The batch number for which to construct public inputs.
     /// @return publicInputs The constructed public inputs as a byte array.
     function _getPublicInputsFromCommitment(
@@ -852,6 +923,33 @@ contract OnChainProposer is
             );
         }
 
+        // Only the EVM-L2 program proves the timestamp checked against the
+        // privileged transaction deadlines
+        if (currentBatch.depositInclusion) {
+            publicInputs = abi.encodePacked(
+                publicInputs,
+                bytes32(currentBatch.lastBlockTimestamp),
+                DEPOSIT_INCLUSION_TAG
+            );
+        }
+
+        if (currentBatch.orderingRoot != bytes32(0)) {
+            publicInputs = abi.encodePacked(
+                publicInputs,
+                currentBatch.orderingRoot,
+                bytes32(currentBatch.orderingSignerKeyId),
+                ORDERING_COMMITMENT_TAG
+            );
+        }
+
+        if (currentBatch.blockSummariesHash != bytes32(0)) {
+            publicInputs = abi.encodePacked(
+                publicInputs,
+                currentBatch.blockSummariesHash,
+                BLOCK_SUMMARIES_TAG
+            );
+        }
+
         return publicInputs;
     }
 
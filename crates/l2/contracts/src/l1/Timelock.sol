@@ -89,10 +89,14 @@ contract Timelock is TimelockControllerUpgradeable, UUPSUpgradeable, ITimelock {
         bytes32 withdrawalsLogsMerkleRoot,
         bytes32 processedPrivilegedTransactionsRollingHash,
         bytes32 lastBlockHash,
+        uint256 lastBlockTimestamp,
         uint256 nonPrivilegedTransactions,
         bytes32 commitHash,
         uint8 programTypeId,
         bytes32 publicValuesHash,
+        bytes32 orderingRoot,
+        uint256 orderingSignerKeyId,
+        bytes32 blockSummariesHash,
         ICommonBridge.BalanceDiff[] calldata balanceDiffs,
         ICommonBridge.L2MessageRollingHash[] calldata l2MessageRollingHashes
     ) external onlyRole(SEQUENCER) {
@@ -102,10 +106,14 @@ contract Timelock is TimelockControllerUpgradeable, UUPSUpgradeable, ITimelock {
             withdrawalsLogsMerkleRoot,
             processedPrivilegedTransactionsRollingHash,
             lastBlockHash,
+            lastBlockTimestamp,
             nonPrivilegedTransactions,
             commitHash,
             programTypeId,
             publicValuesHash,
+            orderingRoot,
+            orderingSignerKeyId,
+            blockSummariesHash,
             balanceDiffs,
             l2MessageRollingHashes
         );
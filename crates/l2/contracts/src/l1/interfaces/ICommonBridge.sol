@@ -242,6 +242,17 @@ interface ICommonBridge {
     /// @notice Checks if the sequencer has exceeded it's processing deadlines
     function hasExpiredPrivilegedTransactions() external view returns (bool);
 
+    /// @notice Checks if the pending privileged transaction at position
+    /// `offset` of the queue had already exceeded its inclusion deadline at
+    /// `timestamp`. Returns false if fewer than `offset + 1` are pending.
+    /// @param offset the number of pending transactions to skip, already
+    /// processed by committed batches.
+    /// @param timestamp the time to compare the deadline against.
+    function hasPrivilegedTransactionsExpiredAt(
+        uint256 offset,
+        uint256 timestamp
+    ) external view returns (bool);
+
     /// @notice Allows the owner to pause the contract
     function pause() external;
 
@@ -91,11 +91,17 @@ interface IOnChainProposer {
     /// @param processedPrivilegedTransactionsRollingHash the rolling hash of the processed
     /// privileged transactions of the batch to be committed.
     /// @param lastBlockHash the hash of the last block of the batch to be committed.
+    /// @param lastBlockTimestamp the timestamp of the last block of the batch to be committed.
     /// @param nonPrivilegedTransactions the number of non-privileged transactions in the batch to be committed.
     /// @param commitHash git commit hash that produced the verifier keys for this batch.
     /// @param programTypeId the guest program type (1=EVM-L2, etc.). 0 defaults to EVM-L2.
     /// @param publicValuesHash keccak256 hash of proof public values for custom programs (programTypeId > 1).
     ///        Must be bytes32(0) for EVM-L2 (programTypeId == 1).
+    /// @param orderingRoot root of the ordering promised by the preconfirmation gateway,
+    ///        bytes32(0) if the batch has no ordering commitment.
+    /// @param orderingSignerKeyId id of the gateway key that signed the ordering.
+    /// @param blockSummariesHash hash of the execution summaries of the batch's blocks,
+    ///        bytes32(0) if the batch doesn't publish them.
     /// @param balanceDiffs the balance diffs of the batch to be committed.
     /// @param l2MessageRollingHashes the L2 message rolling hashes of the batch to be committed.
     function commitBatch(
@@ -104,10 +110,14 @@ interface IOnChainProposer {
         bytes32 withdrawalsLogsMerkleRoot,
         bytes32 processedPrivilegedTransactionsRollingHash,
         bytes32 lastBlockHash,
+        uint256 lastBlockTimestamp,
         uint256 nonPrivilegedTransactions,
         bytes32 commitHash,
         uint8 programTypeId,
         bytes32 publicValuesHash,
+        bytes32 orderingRoot,
+        uint256 orderingSignerKeyId,
+        bytes32 blockSummariesHash,
         ICommonBridge.BalanceDiff[] calldata balanceDiffs,
         ICommonBridge.L2MessageRollingHash[] calldata l2MessageRollingHashes
     ) external;
@@ -48,10 +48,14 @@ interface ITimelock {
         bytes32 withdrawalsLogsMerkleRoot,
         bytes32 processedPrivilegedTransactionsRollingHash,
         bytes32 lastBlockHash,
+        uint256 lastBlockTimestamp,
         uint256 nonPrivilegedTransactions,
         bytes32 commitHash,
         uint8 programTypeId,
         bytes32 publicValuesHash,
+        bytes32 orderingRoot,
+        uint256 orderingSignerKeyId,
+        bytes32 blockSummariesHash,
         ICommonBridge.BalanceDiff[] calldata balanceDiffs,
         ICommonBridge.L2MessageRollingHash[] calldata l2MessageRollingHashes
     ) external;
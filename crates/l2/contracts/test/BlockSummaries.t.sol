@@ -0,0 +1,135 @@
+// SPDX-License-Identifier: MIT
+pragma solidity =0.8.31;
+
+import "forge-std/Test.sol";
+import "../src/l1/CommonBridge.sol";
+import "../src/l1/OnChainProposer.sol";
+import "../src/l1/interfaces/ICommonBridge.sol";
+
+/// @notice Exposes the public inputs OnChainProposer rebuilds for a batch.
+contract OnChainProposerHarness is OnChainProposer {
+    function publicInputs(uint256 batchNumber) external view returns (bytes memory) {
+        return _getPublicInputsFromCommitment(batchNumber);
+    }
+}
+
+/// @title BlockSummaries Tests
+/// @notice Tests that the block summaries hash committed with a batch ends the
+///         public inputs its proof is verified against.
+contract BlockSummariesTest is Test {
+    OnChainProposerHarness public proposer;
+
+    bytes32 constant COMMIT_HASH = bytes32(uint256(1));
+    bytes32 constant GENESIS_STATE_ROOT = bytes32(uint256(0xdead));
+    bytes32 constant NEW_STATE_ROOT = bytes32(uint256(0xbeef));
+    bytes32 constant LAST_BLOCK_HASH = bytes32(uint256(0xb10c));
+    bytes32 constant SUMMARIES_HASH = bytes32(uint256(0x5a));
+    uint256 constant LAST_BLOCK_TIMESTAMP = 1000;
+
+    function setUp() public {
+        proposer = new OnChainProposerHarness();
+        CommonBridge bridge = new CommonBridge();
+
+        bridge.initialize(
+            address(this),      // owner
+            address(proposer),  // onChainProposer
+            100,                // inclusionMaxWait
+            address(0),         // sharedBridgeRouter
+            12345,              // chainId
+            address(0),         // nativeTokenL1
+            1                   // nativeTokenScaleFactor
+        );
+
+        proposer.initialize(
+            true,               // validium
+            address(this),      // timelock_owner
+            false,              // requireRisc0Proof
+            false,              // requireSp1Proof
+            false,              // requireTdxProof
+            false,              // aligned
+            address(0),         // r0verifier
+            address(0),         // sp1verifier
+            address(0),         // tdxverifier
+            address(0),         // alignedProofAggregator
+            bytes32(0),         // sp1Vk
+            bytes32(0),         // risc0Vk
+            COMMIT_HASH,        // commitHash
+            GENESIS_STATE_ROOT, // genesisStateRoot
+            12345,              // chainId
+            address(bridge),    // bridge
+            address(0)          // guestProgramRegistry
+        );
+    }
+
+    function _commit(bytes32 orderingRoot, bytes32 blockSummariesHash) internal {
+        proposer.commitBatch(
+            1,
+            NEW_STATE_ROOT,
+            bytes32(0),
+            bytes32(0),
+            LAST_BLOCK_HASH,
+            LAST_BLOCK_TIMESTAMP,
+            1,
+            COMMIT_HASH,
+            0,
+            bytes32(0),
+            orderingRoot,
+            orderingRoot == bytes32(0) ? 0 : 3,
+            blockSummariesHash,
+            new ICommonBridge.BalanceDiff[](0),
+            new ICommonBridge.L2MessageRollingHash[](0)
+        );
+    }
+
+    function _lastWords(bytes memory data, uint256 words) internal pure returns (bytes32[] memory) {
+        bytes32[] memory result = new bytes32[](words);
+        for (uint256 i = 0; i < words; i++) {
+            uint256 offset = data.length - (words - i) * 32;
+            bytes32 word;
+            assembly {
+                word := mload(add(add(data, 32), offset))
+            }
+            result[i] = word;
+        }
+        return result;
+    }
+
+    /// @notice Without a summaries hash the deposit inclusion section is last.
+    function test_publicInputs_without_summaries() public {
+        _commit(bytes32(0), bytes32(0));
+
+        bytes memory inputs = proposer.publicInputs(1);
+        // 256 (fixed) + 2 × 32 (deposit inclusion)
+        assertEq(inputs.length, 320);
+        bytes32[] memory tail = _lastWords(inputs, 2);
+        assertEq(tail[0], bytes32(LAST_BLOCK_TIMESTAMP));
+        assertEq(tail[1], keccak256("ethrex.l2.deposit_inclusion.v1"));
+    }
+
+    /// @notice The summaries hash and its tag are appended after the other sections.
+    function test_publicInputs_end_with_summaries() public {
+        _commit(bytes32(0), SUMMARIES_HASH);
+
+        bytes memory inputs = proposer.publicInputs(1);
+        // 256 (fixed) + 2 × 32 (deposit inclusion) + 2 × 32 (summaries)
+        assertEq(inputs.length, 384);
+        bytes32[] memory tail = _lastWords(inputs, 2);
+        assertEq(tail[0], SUMMARIES_HASH);
+        assertEq(tail[1], keccak256("ethrex.l2.block_summaries.v1"));
+    }
+
+    /// @notice The summaries section follows the ordering commitment.
+    function test_publicInputs_summaries_follow_ordering() public {
+        _commit(bytes32(uint256(0xcc)), SUMMARIES_HASH);
+
+        bytes memory inputs = proposer.publicInputs(1);
+        // 256 (fixed) + 2 × 32 (deposit inclusion) + 3 × 32 (ordering) + 2 × 32 (summaries)
+        assertEq(inputs.length, 480);
+        bytes32[] memory tail = _lastWords(inputs, 5);
+        assertEq(tail[0], bytes32(uint256(0xcc)));
+        assertEq(tail[1], bytes32(uint256(3)));
+        assertEq(tail[2], keccak256("ethrex.l2.ordering_commitment.v1"));
+        assertEq(tail[3], SUMMARIES_HASH);
+        assertEq(tail[4], keccak256("ethrex.l2.block_summaries.v1"));
+    }
+}
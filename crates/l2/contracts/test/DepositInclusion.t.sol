@@ -0,0 +1,156 @@
+// SPDX-License-Identifier: MIT
+pragma solidity =0.8.31;
+
+import "forge-std/Test.sol";
+import "../src/l1/CommonBridge.sol";
+import "../src/l1/OnChainProposer.sol";
+import "../src/l1/interfaces/ICommonBridge.sol";
+
+/// @title DepositInclusion Tests
+/// @notice Tests that commitBatch rejects batches whose last L2 block is past
+///         the inclusion deadline of a deposit they didn't process.
+contract DepositInclusionTest is Test {
+    OnChainProposer public proposer;
+    CommonBridge public bridge;
+
+    uint256 constant INCLUSION_MAX_WAIT = 100;
+    uint256 constant DEPOSIT_TIME = 1000;
+    uint256 constant DEADLINE = DEPOSIT_TIME + INCLUSION_MAX_WAIT;
+
+    bytes32 constant COMMIT_HASH = bytes32(uint256(1));
+    bytes32 constant GENESIS_STATE_ROOT = bytes32(uint256(0xdead));
+    bytes32 constant LAST_BLOCK_HASH = bytes32(uint256(0xb10c));
+
+    function setUp() public {
+        proposer = new OnChainProposer();
+        bridge = new CommonBridge();
+
+        bridge.initialize(
+            address(this),      // owner
+            address(proposer),  // onChainProposer
+            INCLUSION_MAX_WAIT, // inclusionMaxWait
+            address(0),         // sharedBridgeRouter
+            12345,              // chainId
+            address(0),         // nativeTokenL1
+            1                   // nativeTokenScaleFactor
+        );
+
+        // Validium, so commits with privileged transactions don't need a blob
+        proposer.initialize(
+            true,               // validium
+            address(this),      // timelock_owner
+            false,              // requireRisc0Proof
+            false,              // requireSp1Proof
+            false,              // requireTdxProof
+            false,              // aligned
+            address(0),         // r0verifier
+            address(0),         // sp1verifier
+            address(0),         // tdxverifier
+            address(0),         // alignedProofAggregator
+            bytes32(0),         // sp1Vk
+            bytes32(0),         // risc0Vk
+            COMMIT_HASH,        // commitHash
+            GENESIS_STATE_ROOT, // genesisStateRoot
+            12345,              // chainId
+            address(bridge),    // bridge
+            address(0)          // guestProgramRegistry
+        );
+
+        vm.warp(DEPOSIT_TIME);
+        bridge.deposit{value: 1 ether}(makeAddr("recipient"));
+    }
+
+    function _commit(
+        uint256 batchNumber,
+        bytes32 processedPrivilegedTransactionsRollingHash,
+        uint256 lastBlockTimestamp
+    ) internal {
+        proposer.commitBatch(
+            batchNumber,
+            GENESIS_STATE_ROOT,
+            bytes32(0),
+            processedPrivilegedTransactionsRollingHash,
+            LAST_BLOCK_HASH,
+            lastBlockTimestamp,
+            0,
+            COMMIT_HASH,
+            0,
+            bytes32(0),
+            bytes32(0),
+            0,
+            bytes32(0),
+            new ICommonBridge.BalanceDiff[](0),
+            new ICommonBridge.L2MessageRollingHash[](0)
+        );
+    }
+
+    /// @notice The deadline is inclusive, like the L1 clock check.
+    function test_expiredAt_bounds() public view {
+        assertFalse(bridge.hasPrivilegedTransactionsExpiredAt(0, DEADLINE));
+        assertTrue(bridge.hasPrivilegedTransactionsExpiredAt(0, DEADLINE + 1));
+    }
+
+    /// @notice Nothing expires while no privileged transaction is pending.
+    function test_expiredAt_without_pending() public {
+        CommonBridge emptyBridge = new CommonBridge();
+        emptyBridge.initialize(
+            address(this),
+            address(proposer),
+            INCLUSION_MAX_WAIT,
+            address(0),
+            12345,
+            address(0),
+            1
+        );
+        assertFalse(emptyBridge.hasPrivilegedTransactionsExpiredAt(0, type(uint256).max));
+    }
+
+    /// @notice Transactions skipped by the offset are not checked.
+    function test_expiredAt_offset_past_pending() public view {
+        assertFalse(bridge.hasPrivilegedTransactionsExpiredAt(1, type(uint256).max));
+    }
+
+    /// @notice A batch that skips the deposit and ends past its deadline can't be committed.
+    function test_commitBatch_reverts_when_deposit_skipped_past_deadline() public {
+        vm.expectRevert(bytes("00w"));
+        _commit(1, bytes32(0), DEADLINE + 1);
+        assertEq(proposer.lastCommittedBatch(), 0);
+    }
+
+    /// @notice A batch that skips the deposit but ends by its deadline is committed and verified.
+    function test_commitBatch_accepts_skipped_deposit_before_deadline() public {
+        _commit(1, bytes32(0), DEADLINE);
+
+        proposer.verifyBatch(1, "", "", "", "");
+        assertEq(proposer.lastVerifiedBatch(), 1);
+    }
+
+    /// @notice A batch that processes the deposit may end past its deadline.
+    function test_commitBatch_accepts_processed_deposit_past_deadline() public {
+        _commit(1, bridge.getPendingTransactionsVersionedHash(1), DEADLINE + 1);
+
+        proposer.verifyBatch(1, "", "", "", "");
+        assertEq(proposer.lastVerifiedBatch(), 1);
+        assertFalse(bridge.hasPrivilegedTransactionsExpiredAt(0, type(uint256).max));
+    }
+
+    /// @notice Deposits processed by committed but unverified batches are skipped,
+    ///         so the next batch is checked against the first one still pending.
+    function test_commitBatch_skips_deposits_of_unverified_batches() public {
+        uint256 secondDeadline = DEADLINE + INCLUSION_MAX_WAIT / 2;
+        vm.warp(secondDeadline - INCLUSION_MAX_WAIT);
+        bridge.deposit{value: 1 ether}(makeAddr("recipient"));
+
+        _commit(1, bridge.getPendingTransactionsVersionedHash(1), DEADLINE + 1);
+
+        // The first deposit's deadline passed, but batch 1 already processes it
+        _commit(2, bytes32(0), secondDeadline);
+
+        vm.expectRevert(bytes("00w"));
+        _commit(3, bytes32(0), secondDeadline + 1);
+
+        proposer.verifyBatch(1, "", "", "", "");
+        proposer.verifyBatch(2, "", "", "", "");
+        assertEq(proposer.lastVerifiedBatch(), 2);
+    }
+}
@@ -25,6 +25,19 @@ pub async fn get_l1_message_proof(
     client.send_request_parsed(request).await
 }
 
+pub async fn get_withdrawal_proof(
+    client: &EthClient,
+    batch_number: u64,
+    message_id: U256,
+) -> Result<Option<L1MessageProof>, EthClientError> {
+    let params = Some(vec![
+        json!(format!("{batch_number:#x}")),
+        json!(format!("{message_id:#x}")),
+    ]);
+    let request = RpcRequest::new("ethrex_getWithdrawalProof", params);
+    client.send_request_parsed(request).await
+}
+
 pub async fn get_batch_by_block(
     client: &EthClient,
     block: BlockIdentifier,
@@ -1,6 +1,6 @@
 use std::collections::HashMap;
 
-use ethrex_common::H256;
+use ethrex_common::{H256, U256};
 use serde_json::Value;
 use tracing::info;
 
@@ -12,6 +12,7 @@ use crate::{
 use ethrex_l2_common::{
     merkle_tree::compute_merkle_proof,
     messages::{L1MessageProof, get_block_l1_messages, get_l1_message_hash},
+    withdrawals::WithdrawalProver,
 };
 
 pub struct GetL1MessageProof {
@@ -104,3 +105,83 @@ impl RpcHandler for GetL1MessageProof {
             .map_err(|error| ethrex_rpc::RpcErr::Internal(error.to_string()).into())
     }
 }
+
+/// Proves a withdrawal by rebuilding the message tree of its batch from the
+/// stored blocks and receipts.
+pub struct GetWithdrawalProof {
+    pub batch_number: u64,
+    pub message_id: U256,
+}
+
+impl RpcHandler for GetWithdrawalProof {
+    fn parse(params: &Option<Vec<Value>>) -> Result<GetWithdrawalProof, RpcErr> {
+        let params = params.as_ref().ok_or(ethrex_rpc::RpcErr::BadParams(
+            "No params provided".to_owned(),
+        ))?;
+        if params.len() != 2 {
+            return Err(ethrex_rpc::RpcErr::BadParams(format!(
+                "Expected two params and {} were provided",
+                params.len()
+            ))
+            .into());
+        };
+        let hex_str = serde_json::from_value::<String>(params[0].clone())
+            .map_err(|e| ethrex_rpc::RpcErr::BadParams(e.to_string()))?;
+        let hex_str = hex_str
+            .strip_prefix("0x")
+            .ok_or(ethrex_rpc::RpcErr::BadHexFormat(0))?;
+        let batch_number =
+            u64::from_str_radix(hex_str, 16).map_err(|_| ethrex_rpc::RpcErr::BadHexFormat(0))?;
+        Ok(GetWithdrawalProof {
+            batch_number,
+            message_id: serde_json::from_value(params[1].clone())?,
+        })
+    }
+
+    async fn handle(&self, context: RpcApiContext) -> Result<Value, RpcErr> {
+        let storage = &context.l1_ctx.storage;
+        info!(
+            "Requested withdrawal proof for message {} of batch {}",
+            self.message_id, self.batch_number,
+        );
+
+        let Some(block_numbers) = context
+            .rollup_store
+            .get_block_numbers_by_batch(self.batch_number)
+            .await?
+        else {
+            return Ok(Value::Null);
+        };
+
+        let mut blocks = Vec::with_capacity(block_numbers.len());
+        let mut receipts = Vec::with_capacity(block_numbers.len());
+        for block_number in block_numbers {
+            let Some(block) = storage.get_block_by_number(block_number).await? else {
+                return Ok(Value::Null);
+            };
+            receipts.push(storage.get_receipts_for_block(&block.hash()).await?);
+            blocks.push(block);
+        }
+        let prover = WithdrawalProver::from_blocks(self.batch_number, &blocks, &receipts);
+
+        // The rebuilt tree must be the one the committer stored for the batch
+        if let Some(stored_hashes) = context
+            .rollup_store
+            .get_l1_out_message_hashes_by_batch(self.batch_number)
+            .await?
+            && stored_hashes != prover.message_hashes()
+        {
+            return Err(ethrex_rpc::RpcErr::Internal(format!(
+                "Rebuilt message tree of batch {} doesn't match the committed one",
+                self.batch_number
+            ))
+            .into());
+        }
+
+        let Some(proof) = prover.proof_by_message_id(self.message_id) else {
+            return Ok(Value::Null);
+        };
+        serde_json::to_value(proof)
+            .map_err(|error| ethrex_rpc::RpcErr::Internal(error.to_string()).into())
+    }
+}
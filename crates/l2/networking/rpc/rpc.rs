@@ -6,7 +6,7 @@ use crate::l2::fees::{
     GetBaseFeeVaultAddress, GetL1BlobBaseFeeRequest, GetL1FeeVaultAddress, GetOperatorFee,
     GetOperatorFeeVaultAddress,
 };
-use crate::l2::messages::GetL1MessageProof;
+use crate::l2::messages::{GetL1MessageProof, GetWithdrawalProof};
 use crate::l2::metadata::MetadataRequest;
 use crate::utils::{RpcErr, RpcNamespace, resolve_namespace};
 use axum::extract::State;
@@ -231,6 +231,7 @@ pub async fn map_l2_requests(req: &RpcRequest, context: RpcApiContext) -> Result
     match req.method.as_str() {
         "ethrex_sendTransaction" => SponsoredTx::call(req, context).await,
         "ethrex_getL1MessageProof" => GetL1MessageProof::call(req, context).await,
+        "ethrex_getWithdrawalProof" => GetWithdrawalProof::call(req, context).await,
         "ethrex_batchNumber" => BatchNumberRequest::call(req, context).await,
         "ethrex_getBatchByBlock" => GetBatchByBatchBlockNumberRequest::call(req, context).await,
         "ethrex_getBatchByNumber" => GetBatchByBatchNumberRequest::call(req, context).await,
@@ -63,10 +63,11 @@ pub fn resolve_namespace(method: &str) -> Result<RpcNamespace, RpcErr> {
     }
 }
 
-/// Failure to read from DB will always constitute an internal error
+/// Failure to read from DB will always constitute an internal error, except
+/// for reads of pruned history
 impl From<StoreError> for RpcErr {
     fn from(value: StoreError) -> Self {
-        RpcErr::Internal(value.to_string())
+        Self::L1RpcErr(value.into())
     }
 }
 
@@ -0,0 +1,351 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// Name of the file, inside the accounting directory, holding one JSON
+/// encoded [`ProofRecord`] per line.
+const RECORDS_FILE: &str = "proofs.jsonl";
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+#[derive(Debug, thiserror::Error)]
+pub enum AccountingError {
+    #[error("Accounting store IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to encode proof record: {0}")]
+    Encoding(#[from] serde_json::Error),
+}
+
+/// Price of proving a guest program on this machine.
+///
+/// Configured as `{ per_second = 0.002 }` or `{ per_cycle = 1e-9 }`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CostRate {
+    /// Cost per second of proving wall time.
+    PerSecond(f64),
+    /// Cost per executed guest cycle, only priced when the backend reports
+    /// the cycle count.
+    PerCycle(f64),
+}
+
+impl CostRate {
+    /// Estimated cost of a proof, `None` if it can't be priced with this rate.
+    pub fn cost(&self, wall_time: Duration, cycles: Option<u64>) -> Option<f64> {
+        match self {
+            Self::PerSecond(rate) => Some(rate * wall_time.as_secs_f64()),
+            Self::PerCycle(rate) => cycles.map(|cycles| rate * u64_to_f64(cycles)),
+        }
+    }
+}
+
+/// Host-side accounting entry of a single proof.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProofRecord {
+    pub program_id: String,
+    pub backend: String,
+    pub batch_number: u64,
+    /// Size of the serialized guest input, if it was serialized.
+    pub input_size: Option<u64>,
+    pub wall_time_ms: u64,
+    /// Peak resident memory of the prover process while proving.
+    pub peak_memory_bytes: Option<u64>,
+    pub cycles: Option<u64>,
+    pub rate: Option<CostRate>,
+    /// Unix timestamp, in seconds, at which the proof finished.
+    pub timestamp: u64,
+}
+
+impl ProofRecord {
+    pub fn wall_time(&self) -> Duration {
+        Duration::from_millis(self.wall_time_ms)
+    }
+
+    pub fn estimated_cost(&self) -> Option<f64> {
+        self.rate
+            .and_then(|rate| rate.cost(self.wall_time(), self.cycles))
+    }
+
+    /// Days since the Unix epoch of the record's timestamp.
+    pub fn day(&self) -> u64 {
+        self.timestamp / SECONDS_PER_DAY
+    }
+}
+
+/// Aggregated statistics of a guest program for a single UTC day.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DailyStats {
+    pub program_id: String,
+    /// `YYYY-MM-DD` date of the day, in UTC.
+    pub date: String,
+    pub proofs: u64,
+    pub total_time_ms: u64,
+    pub estimated_cost: f64,
+    /// Proofs that couldn't be priced, either because no rate was configured
+    /// or because the rate is per cycle and the cycle count is unknown.
+    pub unpriced_proofs: u64,
+}
+
+impl DailyStats {
+    fn add(&mut self, record: &ProofRecord) {
+        self.proofs = self.proofs.saturating_add(1);
+        self.total_time_ms = self.total_time_ms.saturating_add(record.wall_time_ms);
+        match record.estimated_cost() {
+            Some(cost) => self.estimated_cost += cost,
+            None => self.unpriced_proofs = self.unpriced_proofs.saturating_add(1),
+        }
+    }
+}
+
+/// Append-only store of the proofs generated by this prover, kept as JSON
+/// lines in `<dir>/proofs.jsonl` so statistics survive restarts.
+///
+/// Proof durations are measured with a monotonic clock by the caller, so
+/// wall clock adjustments only affect the day a proof is attributed to. Those
+/// timestamps never go back: when the system clock moves backwards, proofs
+/// are stamped with the latest timestamp recorded until it catches up.
+pub struct AccountingStore {
+    path: PathBuf,
+    daily: BTreeMap<(String, u64), DailyStats>,
+    last_timestamp: u64,
+}
+
+impl AccountingStore {
+    /// Opens the store at `dir`, aggregating the records left by previous
+    /// runs. Lines that can't be decoded (e.g. a write interrupted by a
+    /// crash) are skipped.
+    pub fn open(dir: &Path) -> Result<Self, AccountingError> {
+        fs::create_dir_all(dir)?;
+        let mut store = Self {
+            path: dir.join(RECORDS_FILE),
+            daily: BTreeMap::new(),
+            last_timestamp: 0,
+        };
+        if !store.path.exists() {
+            return Ok(store);
+        }
+        for (index, line) in BufReader::new(File::open(&store.path)?).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<ProofRecord>(&line) {
+                Ok(record) => store.aggregate(&record),
+                Err(e) => warn!(
+                    "Skipping malformed proof record at {}:{}: {e}",
+                    store.path.display(),
+                    index.saturating_add(1)
+                ),
+            }
+        }
+        Ok(store)
+    }
+
+    /// Timestamp for a proof finishing now, clamped so it's never earlier
+    /// than the ones already recorded.
+    pub fn timestamp(&self) -> u64 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+        now.max(self.last_timestamp)
+    }
+
+    /// Persists `record` and adds it to the statistics.
+    pub fn record(&mut self, mut record: ProofRecord) -> Result<(), AccountingError> {
+        record.timestamp = record.timestamp.max(self.last_timestamp);
+        let mut line = serde_json::to_vec(&record)?;
+        line.push(b'\n');
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(&line)?;
+        file.sync_data()?;
+        self.aggregate(&record);
+        Ok(())
+    }
+
+    /// Statistics per program per day, sorted by program and date.
+    pub fn daily_stats(&self) -> Vec<DailyStats> {
+        self.daily.values().cloned().collect()
+    }
+
+    fn aggregate(&mut self, record: &ProofRecord) {
+        self.last_timestamp = self.last_timestamp.max(record.timestamp);
+        let day = record.day();
+        self.daily
+            .entry((record.program_id.clone(), day))
+            .or_insert_with(|| DailyStats {
+                program_id: record.program_id.clone(),
+                date: format_date(day),
+                ..Default::default()
+            })
+            .add(record);
+    }
+}
+
+/// Resets the peak resident memory tracked by the kernel for this process,
+/// so [`peak_memory_bytes`] reports the peak of what runs next. Only
+/// supported on Linux.
+pub fn reset_peak_memory() {
+    #[cfg(target_os = "linux")]
+    if let Err(e) = fs::write("/proc/self/clear_refs", "5") {
+        tracing::debug!("Can't reset peak memory usage: {e}");
+    }
+}
+
+/// Peak resident memory of this process, if the platform reports it.
+pub fn peak_memory_bytes() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    let kib = status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    kib.checked_mul(1024)
+}
+
+/// Formats days since the Unix epoch as a `YYYY-MM-DD` UTC date.
+fn format_date(days: u64) -> String {
+    // Civil-from-days algorithm by Howard Hinnant, restricted to dates after
+    // the epoch.
+    let days = days.saturating_add(719_468);
+    let era = days / 146_097;
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + u64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+fn u64_to_f64(value: u64) -> f64 {
+    let high = u32::try_from(value >> 32).unwrap_or(u32::MAX);
+    let low = u32::try_from(value & u64::from(u32::MAX)).unwrap_or(u32::MAX);
+    f64::from(high) * 4_294_967_296.0 + f64::from(low)
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::panic,
+    clippy::expect_used,
+    clippy::indexing_slicing,
+    clippy::unwrap_used
+)]
+mod tests {
+    use super::*;
+
+    fn record(program_id: &str, timestamp: u64, wall_time_ms: u64) -> ProofRecord {
+        ProofRecord {
+            program_id: program_id.to_string(),
+            backend: "exec".to_string(),
+            batch_number: 1,
+            input_size: Some(1024),
+            wall_time_ms,
+            peak_memory_bytes: None,
+            cycles: None,
+            rate: Some(CostRate::PerSecond(0.5)),
+            timestamp,
+        }
+    }
+
+    #[test]
+    fn formats_dates() {
+        assert_eq!(format_date(0), "1970-01-01");
+        assert_eq!(format_date(59), "1970-03-01");
+        assert_eq!(format_date(11_016), "2000-02-29");
+        assert_eq!(format_date(20_742), "2026-10-16");
+    }
+
+    #[test]
+    fn prices_proofs() {
+        let wall_time = Duration::from_secs(4);
+        assert_eq!(CostRate::PerSecond(0.5).cost(wall_time, None), Some(2.0));
+        assert_eq!(CostRate::PerCycle(0.5).cost(wall_time, None), None);
+        assert_eq!(CostRate::PerCycle(0.5).cost(wall_time, Some(10)), Some(5.0));
+        assert_eq!(u64_to_f64(u64::from(u32::MAX) + 2), 4_294_967_297.0);
+    }
+
+    #[test]
+    fn aggregates_per_program_per_day() {
+        let dir = tempfile::tempdir().expect("tmpdir");
+        let mut store = AccountingStore::open(dir.path()).unwrap();
+        store.record(record("zk-dex", 100, 2_000)).unwrap();
+        store.record(record("zk-dex", 200, 4_000)).unwrap();
+        store.record(record("tokamon", 300, 1_000)).unwrap();
+        store
+            .record(ProofRecord {
+                rate: None,
+                ..record("zk-dex", SECONDS_PER_DAY, 1_000)
+            })
+            .unwrap();
+
+        let stats = store.daily_stats();
+        assert_eq!(stats.len(), 3);
+        assert_eq!(stats[0].program_id, "tokamon");
+        assert_eq!(stats[1].program_id, "zk-dex");
+        assert_eq!(stats[1].date, "1970-01-01");
+        assert_eq!(stats[1].proofs, 2);
+        assert_eq!(stats[1].total_time_ms, 6_000);
+        assert_eq!(stats[1].estimated_cost, 3.0);
+        assert_eq!(stats[2].date, "1970-01-02");
+        assert_eq!(stats[2].unpriced_proofs, 1);
+    }
+
+    #[test]
+    fn records_survive_reopening() {
+        let dir = tempfile::tempdir().expect("tmpdir");
+        let mut store = AccountingStore::open(dir.path()).unwrap();
+        store.record(record("zk-dex", 100, 2_000)).unwrap();
+        drop(store);
+
+        // A record cut short by a crash is skipped.
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(dir.path().join(RECORDS_FILE))
+            .unwrap();
+        file.write_all(b"{\"program_id\":\"zk-").unwrap();
+
+        let store = AccountingStore::open(dir.path()).unwrap();
+        assert_eq!(store.daily_stats(), {
+            let mut stats = DailyStats {
+                program_id: "zk-dex".to_string(),
+                date: "1970-01-01".to_string(),
+                ..Default::default()
+            };
+            stats.add(&record("zk-dex", 100, 2_000));
+            vec![stats]
+        });
+    }
+
+    #[test]
+    fn timestamps_never_go_back() {
+        let dir = tempfile::tempdir().expect("tmpdir");
+        let mut store = AccountingStore::open(dir.path()).unwrap();
+        let later = store.timestamp() + SECONDS_PER_DAY;
+        store.record(record("zk-dex", later, 1_000)).unwrap();
+
+        // The system clock is now behind the last record.
+        assert_eq!(store.timestamp(), later);
+        store.record(record("zk-dex", 100, 1_000)).unwrap();
+        let stats = store.daily_stats();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].proofs, 2);
+    }
+}
@@ -0,0 +1,362 @@
+//! Hardware requirements of the proving backends, checked against the machine
+//! the prover runs on before it starts proving.
+
+use std::fmt;
+use std::process::Command;
+
+use ethrex_l2_common::prover::ProofFormat;
+use serde::Serialize;
+
+use crate::backend::BackendError;
+
+const GIB: u64 = 1024 * 1024 * 1024;
+
+/// Source of information about the machine the prover runs on.
+///
+/// [`SystemProbe`] queries the actual machine; tests use mocked probes.
+pub trait HardwareProbe {
+    /// Number of CUDA devices, 0 if there are none or they can't be queried.
+    fn cuda_device_count(&self) -> u64;
+
+    /// Memory available for new processes in bytes, if it can be determined.
+    fn available_memory(&self) -> Option<u64>;
+}
+
+/// Probes the machine the prover runs on.
+pub struct SystemProbe;
+
+impl HardwareProbe for SystemProbe {
+    fn cuda_device_count(&self) -> u64 {
+        // nvidia-smi ships with the driver, so the query works without linking CUDA.
+        Command::new("nvidia-smi")
+            .args(["--query-gpu=name", "--format=csv,noheader"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| {
+                String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .fold(0, |count, _| count + 1)
+            })
+            .unwrap_or(0)
+    }
+
+    fn available_memory(&self) -> Option<u64> {
+        let meminfo = std::fs::read_to_string("/proc/meminfo").ok()?;
+        parse_mem_available(&meminfo)
+    }
+}
+
+/// Parses the `MemAvailable` entry of `/proc/meminfo` into bytes.
+fn parse_mem_available(meminfo: &str) -> Option<u64> {
+    let kib = meminfo
+        .lines()
+        .find_map(|line| line.strip_prefix("MemAvailable:"))?
+        .trim()
+        .strip_suffix("kB")?
+        .trim()
+        .parse::<u64>()
+        .ok()?;
+    Some(kib.saturating_mul(1024))
+}
+
+/// Hardware a backend needs, as built.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackendRequirements {
+    /// The backend was built to prove on CUDA devices.
+    pub requires_gpu: bool,
+    /// The backend can be built with GPU support (the `gpu` feature).
+    pub gpu_capable: bool,
+    /// Rough amount of host memory needed to prove a batch, in bytes.
+    pub memory_per_proof: u64,
+}
+
+impl BackendRequirements {
+    /// Requirements of a backend that proves on CPU, or on GPU when built with the `gpu` feature.
+    pub fn gpu_capable(memory_per_proof_gib: u64) -> Self {
+        Self {
+            requires_gpu: cfg!(feature = "gpu"),
+            gpu_capable: true,
+            memory_per_proof: memory_per_proof_gib.saturating_mul(GIB),
+        }
+    }
+
+    /// Requirements of a backend that only proves on CPU.
+    pub fn cpu_only(memory_per_proof_gib: u64) -> Self {
+        Self {
+            requires_gpu: false,
+            gpu_capable: false,
+            memory_per_proof: memory_per_proof_gib.saturating_mul(GIB),
+        }
+    }
+}
+
+/// What a backend can do on the machine the prover runs on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BackendCapabilities {
+    pub backend: &'static str,
+    pub requires_gpu: bool,
+    pub gpu_capable: bool,
+    pub gpu_detected: bool,
+    pub cuda_devices: u64,
+    /// Memory available on the machine in bytes, if it could be determined.
+    pub available_memory: Option<u64>,
+    pub estimated_memory_per_proof: u64,
+    pub proof_formats: Vec<ProofFormat>,
+    /// Proofs that can run at the same time given the available memory and
+    /// devices. `None` when neither limits the backend or they are unknown.
+    pub max_recommended_concurrency: Option<u64>,
+}
+
+impl BackendCapabilities {
+    pub fn probe(
+        backend: &'static str,
+        requirements: BackendRequirements,
+        proof_formats: Vec<ProofFormat>,
+        probe: &dyn HardwareProbe,
+    ) -> Self {
+        let cuda_devices = probe.cuda_device_count();
+        let available_memory = probe.available_memory();
+
+        let memory_limit = available_memory
+            .zip(Some(requirements.memory_per_proof).filter(|memory| *memory > 0))
+            .map(|(available, per_proof)| available / per_proof);
+        // One proof per device
+        let device_limit = requirements.requires_gpu.then_some(cuda_devices);
+        let max_recommended_concurrency = match (memory_limit, device_limit) {
+            (Some(memory_limit), Some(device_limit)) => Some(memory_limit.min(device_limit)),
+            (limit, None) | (None, limit) => limit,
+        };
+
+        Self {
+            backend,
+            requires_gpu: requirements.requires_gpu,
+            gpu_capable: requirements.gpu_capable,
+            gpu_detected: cuda_devices > 0,
+            cuda_devices,
+            available_memory,
+            estimated_memory_per_proof: requirements.memory_per_proof,
+            proof_formats,
+            max_recommended_concurrency,
+        }
+    }
+
+    /// True if the machine has a GPU the backend could use but wasn't built for.
+    pub fn gpu_unused(&self) -> bool {
+        self.gpu_detected && self.gpu_capable && !self.requires_gpu
+    }
+
+    /// Checks that the backend can produce `format`, if the operator pinned one.
+    ///
+    /// Proving would fail on every batch otherwise, so the prover refuses to start.
+    pub fn check_format(&self, format: Option<ProofFormat>) -> Result<(), BackendError> {
+        match format {
+            Some(format) if !self.proof_formats.contains(&format) => {
+                Err(BackendError::unsupported_environment(format!(
+                    "the {} backend can't produce {format:?} proofs, it supports {:?}",
+                    self.backend, self.proof_formats
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks that the backend can prove `concurrency` batches at a time on this machine.
+    ///
+    /// A maximum of 0 means not even one proof fits, which [`hardware_warnings`](Self::hardware_warnings)
+    /// reports instead.
+    pub fn check_concurrency(&self, concurrency: u64) -> Result<(), BackendError> {
+        if let Some(max_concurrency) = self.max_recommended_concurrency
+            && max_concurrency > 0
+            && concurrency > max_concurrency
+        {
+            return Err(BackendError::unsupported_environment(format!(
+                "the {} backend can run at most {max_concurrency} proof(s) at a time on this machine \
+                 but {concurrency} are needed ({self})",
+                self.backend
+            )));
+        }
+        Ok(())
+    }
+
+    /// Reasons the probed hardware can't run a single proof.
+    ///
+    /// The probes can be wrong (nvidia-smi missing from a container, rough memory
+    /// estimates), so these are warnings unless the operator enforces them.
+    pub fn hardware_warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+        if self.requires_gpu && !self.gpu_detected {
+            warnings.push(format!(
+                "the {} backend was built with the `gpu` feature but no CUDA device was found; \
+                 check the NVIDIA driver or rebuild without `gpu` to prove on CPU",
+                self.backend
+            ));
+        }
+        if let Some(available_memory) = self.available_memory
+            && available_memory < self.estimated_memory_per_proof
+        {
+            warnings.push(format!(
+                "the {} backend needs ~{} GiB of memory per proof but only {} GiB are available",
+                self.backend,
+                self.estimated_memory_per_proof / GIB,
+                available_memory / GIB
+            ));
+        }
+        warnings
+    }
+}
+
+impl fmt::Display for BackendCapabilities {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let gib = |bytes: u64| bytes / GIB;
+        write!(
+            f,
+            "backend: {}, gpu: {}, cuda devices: {}, memory per proof: ~{} GiB, available memory: ",
+            self.backend,
+            if self.requires_gpu {
+                "required"
+            } else {
+                "not used"
+            },
+            self.cuda_devices,
+            gib(self.estimated_memory_per_proof),
+        )?;
+        match self.available_memory {
+            Some(memory) => write!(f, "{} GiB", gib(memory))?,
+            None => write!(f, "unknown")?,
+        }
+        write!(
+            f,
+            ", proof formats: {:?}, max concurrency: ",
+            self.proof_formats
+        )?;
+        match self.max_recommended_concurrency {
+            Some(max_concurrency) => write!(f, "{max_concurrency}"),
+            None => write!(f, "unknown"),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::panic, clippy::expect_used, clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    struct MockProbe {
+        cuda_devices: u64,
+        available_memory_gib: Option<u64>,
+    }
+
+    impl HardwareProbe for MockProbe {
+        fn cuda_device_count(&self) -> u64 {
+            self.cuda_devices
+        }
+
+        fn available_memory(&self) -> Option<u64> {
+            self.available_memory_gib.map(|memory| memory * GIB)
+        }
+    }
+
+    fn capabilities(
+        requires_gpu: bool,
+        cuda_devices: u64,
+        available_memory_gib: Option<u64>,
+    ) -> BackendCapabilities {
+        let requirements = BackendRequirements {
+            requires_gpu,
+            gpu_capable: true,
+            memory_per_proof: 16 * GIB,
+        };
+        let probe = MockProbe {
+            cuda_devices,
+            available_memory_gib,
+        };
+        BackendCapabilities::probe("sp1", requirements, ProofFormat::all().collect(), &probe)
+    }
+
+    #[test]
+    fn warns_on_gpu_build_without_cuda_device() {
+        let caps = capabilities(true, 0, Some(64));
+        assert!(!caps.gpu_detected);
+        let warnings = caps.hardware_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(
+            warnings[0].contains("no CUDA device"),
+            "unexpected message: {}",
+            warnings[0]
+        );
+        // The missing device isn't a concurrency limit on its own
+        assert!(caps.check_concurrency(1).is_ok());
+    }
+
+    #[test]
+    fn refuses_unsupported_proof_format() {
+        let mut caps = capabilities(false, 0, Some(64));
+        caps.proof_formats = vec![ProofFormat::Compressed];
+        assert!(caps.check_format(None).is_ok());
+        assert!(caps.check_format(Some(ProofFormat::Compressed)).is_ok());
+        match caps.check_format(Some(ProofFormat::Groth16)) {
+            Err(BackendError::UnsupportedEnvironment(msg)) => {
+                assert!(msg.contains("Groth16"), "unexpected message: {msg}")
+            }
+            other => panic!("expected UnsupportedEnvironment, got: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn refuses_concurrency_above_available_memory() {
+        let caps = capabilities(false, 0, Some(24));
+        assert_eq!(caps.max_recommended_concurrency, Some(1));
+        assert!(caps.check_concurrency(1).is_ok());
+        assert!(caps.hardware_warnings().is_empty());
+        assert!(matches!(
+            caps.check_concurrency(2),
+            Err(BackendError::UnsupportedEnvironment(_))
+        ));
+    }
+
+    #[test]
+    fn warns_when_a_single_proof_does_not_fit_in_memory() {
+        let caps = capabilities(false, 0, Some(8));
+        assert_eq!(caps.max_recommended_concurrency, Some(0));
+        assert!(caps.check_concurrency(1).is_ok());
+        let warnings = caps.hardware_warnings();
+        assert_eq!(warnings.len(), 1);
+        assert!(
+            warnings[0].contains("memory"),
+            "unexpected message: {}",
+            warnings[0]
+        );
+    }
+
+    #[test]
+    fn gpu_concurrency_is_limited_by_devices() {
+        let caps = capabilities(true, 2, Some(128));
+        assert_eq!(caps.max_recommended_concurrency, Some(2));
+        assert!(caps.check_concurrency(2).is_ok());
+        assert!(caps.check_concurrency(3).is_err());
+    }
+
+    #[test]
+    fn unknown_memory_does_not_limit_concurrency() {
+        let caps = capabilities(false, 0, None);
+        assert_eq!(caps.max_recommended_concurrency, None);
+        assert!(caps.check_concurrency(4).is_ok());
+        assert!(caps.hardware_warnings().is_empty());
+    }
+
+    #[test]
+    fn detects_unused_gpu() {
+        assert!(capabilities(false, 1, Some(64)).gpu_unused());
+        assert!(!capabilities(true, 1, Some(64)).gpu_unused());
+        assert!(!capabilities(false, 0, Some(64)).gpu_unused());
+    }
+
+    #[test]
+    fn parses_mem_available() {
+        let meminfo = "MemTotal:       65536000 kB\nMemFree:         1024000 kB\nMemAvailable:   32768000 kB\n";
+        assert_eq!(parse_mem_available(meminfo), Some(32768000 * 1024));
+        assert_eq!(parse_mem_available("MemTotal: 1 kB\n"), None);
+    }
+}
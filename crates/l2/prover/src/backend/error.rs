@@ -21,6 +21,9 @@ pub enum BackendError {
 
     #[error("Resource limit exceeded: {0}")]
     ResourceLimitExceeded(String),
+
+    #[error("Unsupported environment: {0}")]
+    UnsupportedEnvironment(String),
 }
 
 impl BackendError {
@@ -55,4 +58,8 @@ impl BackendError {
     pub fn resource_limit(msg: impl Into<String>) -> Self {
         Self::ResourceLimitExceeded(msg.into())
     }
+
+    pub fn unsupported_environment(msg: impl Into<String>) -> Self {
+        Self::UnsupportedEnvironment(msg.into())
+    }
 }
@@ -2,13 +2,16 @@ use std::time::{Duration, Instant};
 
 use tracing::{info, warn};
 
+use ethrex_common::types::fee_config::FeeConfig;
 use ethrex_guest_program::{input::ProgramInput, output::ProgramOutput, traits::backends};
 use ethrex_l2_common::{
     calldata::Value,
-    prover::{BatchProof, ProofCalldata, ProofFormat, ProverType},
+    prover::{BatchProof, ProofCalldata, ProofFormat, ProverInputData, ProverType},
 };
+use ethrex_storage::Store;
 
-use crate::backend::{BackendError, ProverBackend};
+use crate::backend::{BackendError, BackendRequirements, ProverBackend};
+use crate::differential::Divergence;
 
 /// Exec backend - executes the program without generating actual proofs.
 ///
@@ -27,6 +30,17 @@ impl ExecBackend {
         ethrex_guest_program::execution::execution_program(input).map_err(BackendError::execution)
     }
 
+    /// Differential mode - runs the guest program's stateless execution and the
+    /// native execution on `store` over the same batch, reporting where they diverge.
+    pub fn differential(
+        &self,
+        input: &ProverInputData,
+        store: &Store,
+        native_fee_configs: &[FeeConfig],
+    ) -> Result<Option<Divergence>, BackendError> {
+        crate::differential::check_batch(input, store, native_fee_configs)
+    }
+
     fn to_calldata() -> ProofCalldata {
         ProofCalldata {
             prover_type: ProverType::Exec,
@@ -48,6 +62,11 @@ impl ProverBackend for ExecBackend {
         backends::EXEC
     }
 
+    fn requirements(&self) -> BackendRequirements {
+        // Executes the guest program natively, without proving
+        BackendRequirements::cpu_only(1)
+    }
+
     fn serialize_input(
         &self,
         _input: &ProgramInput,
@@ -179,6 +198,9 @@ mod tests {
             blob_commitment: [0u8; 48],
             blob_proof: [0u8; 48],
             native_token_scale_factor: None,
+            ordering_commitment: None,
+            publish_block_summaries: false,
+            commit_deposit_inclusion: false,
         };
 
         // serialize_raw should produce valid rkyv bytes.
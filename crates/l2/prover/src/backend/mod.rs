@@ -8,6 +8,7 @@ use ethrex_l2_common::prover::{BatchProof, ProofFormat, ProverType};
 use rkyv::rancor::Error as RkyvError;
 use serde::{Deserialize, Serialize};
 
+pub mod capabilities;
 pub mod error;
 pub mod exec;
 
@@ -23,6 +24,7 @@ pub mod zisk;
 #[cfg(feature = "openvm")]
 pub mod openvm;
 
+pub use capabilities::{BackendCapabilities, BackendRequirements, HardwareProbe, SystemProbe};
 pub use error::BackendError;
 
 // Re-export backend structs
@@ -128,6 +130,31 @@ pub trait ProverBackend {
     /// for the correct ELF binary.
     fn backend_name(&self) -> &'static str;
 
+    /// Proof formats this backend can produce.
+    ///
+    /// Advertised to proof coordinators during capability negotiation.
+    fn supported_formats(&self) -> Vec<ProofFormat> {
+        ProofFormat::all().collect()
+    }
+
+    /// Hardware this backend needs, as built.
+    fn requirements(&self) -> BackendRequirements;
+
+    /// What this backend can do on the machine the prover runs on.
+    fn capabilities(&self) -> BackendCapabilities {
+        self.capabilities_with(&SystemProbe)
+    }
+
+    /// Like [`capabilities`](Self::capabilities), using `probe` to inspect the hardware.
+    fn capabilities_with(&self, probe: &dyn HardwareProbe) -> BackendCapabilities {
+        BackendCapabilities::probe(
+            self.backend_name(),
+            self.requirements(),
+            self.supported_formats(),
+            probe,
+        )
+    }
+
     /// Serialize the program input into the backend-specific format.
     fn serialize_input(&self, input: &ProgramInput) -> Result<Self::SerializedInput, BackendError>;
 
@@ -1,6 +1,6 @@
 use std::time::{Duration, Instant};
 
-use crate::backend::{BackendError, ProverBackend};
+use crate::backend::{BackendError, BackendRequirements, ProverBackend};
 use ethrex_guest_program::{ZKVM_OPENVM_PROGRAM_ELF, input::ProgramInput, traits::backends};
 use ethrex_l2_common::prover::{BatchProof, ProofFormat, ProverType};
 use openvm_continuations::verifier::internal::types::VmStarkProof;
@@ -67,6 +67,10 @@ impl ProverBackend for OpenVmBackend {
         backends::OPENVM
     }
 
+    fn requirements(&self) -> BackendRequirements {
+        BackendRequirements::gpu_capable(32)
+    }
+
     fn serialize_input(&self, input: &ProgramInput) -> Result<Self::SerializedInput, BackendError> {
         let mut stdin = StdIn::default();
         let bytes = self.serialize_raw(input)?;
@@ -1,6 +1,6 @@
 use std::time::{Duration, Instant};
 
-use crate::backend::{BackendError, ProverBackend};
+use crate::backend::{BackendError, BackendRequirements, ProverBackend};
 use ethrex_guest_program::{
     input::ProgramInput,
     methods::{ETHREX_GUEST_RISC0_ELF, ETHREX_GUEST_RISC0_ID},
@@ -11,7 +11,8 @@ use ethrex_l2_common::{
     prover::{BatchProof, ProofBytes, ProofCalldata, ProofFormat, ProverType},
 };
 use risc0_zkvm::{
-    ExecutorEnv, InnerReceipt, ProverOpts, Receipt, default_executor, default_prover,
+    ExecutorEnv, InnerReceipt, ProverOpts, Receipt, ReceiptClaim, default_executor, default_prover,
+    sha::{self, Digestible},
 };
 
 /// RISC0 prover backend.
@@ -30,7 +31,37 @@ impl Risc0Backend {
         }
     }
 
+    /// Wraps a receipt into a Groth16 one, compressing it if it was proven in another format.
+    fn to_groth16(receipt: Receipt) -> Result<Receipt, BackendError> {
+        if matches!(receipt.inner, InnerReceipt::Groth16(_)) {
+            return Ok(receipt);
+        }
+        default_prover()
+            .compress(&ProverOpts::groth16(), &receipt)
+            .map_err(BackendError::batch_proof)
+    }
+
+    /// The verifier contract only receives the seal and recomputes the claim from the image id
+    /// and `sha256(publicInputs)`, so a journal not matching the one the seal commits to would
+    /// only be caught on-chain. Check it locally before building the calldata.
+    fn check_journal(receipt: &Receipt) -> Result<(), BackendError> {
+        let claim = receipt.claim().map_err(BackendError::batch_proof)?;
+        let pre = claim
+            .as_value()
+            .map_err(BackendError::batch_proof)?
+            .pre
+            .digest::<sha::Impl>();
+        let expected = ReceiptClaim::ok(pre, receipt.journal.bytes.clone());
+        if expected.digest::<sha::Impl>() != claim.digest::<sha::Impl>() {
+            return Err(BackendError::batch_proof(
+                "journal does not match the one committed by the seal",
+            ));
+        }
+        Ok(())
+    }
+
     fn to_calldata(receipt: &Receipt) -> Result<ProofCalldata, BackendError> {
+        Self::check_journal(receipt)?;
         let seal = Self::encode_seal(receipt)?;
 
         let calldata = vec![Value::Bytes(seal.into())];
@@ -38,7 +69,7 @@ impl Risc0Backend {
         Ok(ProofCalldata {
             prover_type: ProverType::RISC0,
             calldata,
-            public_values: vec![],
+            public_values: receipt.journal.bytes.clone(),
         })
     }
 
@@ -97,6 +128,10 @@ impl ProverBackend for Risc0Backend {
         backends::RISC0
     }
 
+    fn requirements(&self) -> BackendRequirements {
+        BackendRequirements::gpu_capable(16)
+    }
+
     fn serialize_input(&self, input: &ProgramInput) -> Result<Self::SerializedInput, BackendError> {
         let bytes = self.serialize_raw(input)?;
         ExecutorEnv::builder()
@@ -138,7 +173,9 @@ impl ProverBackend for Risc0Backend {
                 proof: bincode::serialize(&proof.inner).map_err(BackendError::batch_proof)?,
                 public_values: proof.journal.bytes,
             }),
-            ProofFormat::Groth16 => BatchProof::ProofCalldata(Self::to_calldata(&proof)?),
+            ProofFormat::Groth16 => {
+                BatchProof::ProofCalldata(Self::to_calldata(&Self::to_groth16(proof)?)?)
+            }
         };
 
         Ok(batch_proof)
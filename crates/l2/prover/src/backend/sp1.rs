@@ -1,9 +1,10 @@
-use ethrex_guest_program::{ZKVM_SP1_PROGRAM_ELF, input::ProgramInput, traits::backends};
+use ethrex_guest_program::{
+    ZKVM_SP1_PROGRAM_ELF, build_info::elf_hash, input::ProgramInput, traits::backends,
+};
 use ethrex_l2_common::{
     calldata::Value,
     prover::{BatchProof, ProofBytes, ProofCalldata, ProofFormat, ProverType},
 };
-use sha2::{Digest, Sha256};
 use sp1_prover::components::CpuProverComponents;
 #[cfg(not(feature = "gpu"))]
 use sp1_sdk::CpuProver;
@@ -21,7 +22,7 @@ use std::{
 };
 use url::Url;
 
-use crate::backend::{BackendError, ProverBackend};
+use crate::backend::{BackendError, BackendRequirements, ProverBackend};
 
 /// Setup data for the SP1 prover (client, proving key, verifying key).
 pub struct ProverSetup {
@@ -104,9 +105,9 @@ impl Sp1Backend {
     }
 
     /// Returns cached (pk, vk) for the given ELF, running `client.setup(elf)` only on
-    /// the first call per unique ELF (identified by SHA-256 hash).
+    /// the first call per unique ELF (identified by its [`elf_hash`]).
     fn get_or_setup_keys(&self, elf: &[u8]) -> (SP1ProvingKey, SP1VerifyingKey) {
-        let hash: [u8; 32] = Sha256::digest(elf).into();
+        let hash = elf_hash(elf);
         let cache = ELF_KEY_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
         #[expect(clippy::expect_used)]
         let mut guard = cache.lock().expect("ELF_KEY_CACHE lock poisoned");
@@ -175,6 +176,10 @@ impl ProverBackend for Sp1Backend {
         backends::SP1
     }
 
+    fn requirements(&self) -> BackendRequirements {
+        BackendRequirements::gpu_capable(16)
+    }
+
     fn serialize_input(&self, input: &ProgramInput) -> Result<Self::SerializedInput, BackendError> {
         let mut stdin = SP1Stdin::new();
         let bytes = self.serialize_raw(input)?;
@@ -7,7 +7,7 @@ use std::{
 use ethrex_guest_program::{ZKVM_ZISK_PROGRAM_ELF, input::ProgramInput, traits::backends};
 use ethrex_l2_common::prover::{BatchProof, ProofFormat, ProverType};
 
-use crate::backend::{BackendError, ProverBackend};
+use crate::backend::{BackendError, BackendRequirements, ProverBackend};
 
 const INPUT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/zisk_input.bin");
 const OUTPUT_DIR_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/zisk_output");
@@ -121,6 +121,11 @@ impl ProverBackend for ZiskBackend {
         backends::ZISK
     }
 
+    fn requirements(&self) -> BackendRequirements {
+        // Proving runs in the `cargo-zisk` binary, which is built separately
+        BackendRequirements::cpu_only(32)
+    }
+
     fn serialize_input(&self, input: &ProgramInput) -> Result<Self::SerializedInput, BackendError> {
         let input_bytes = self.serialize_raw(input)?;
         std::fs::write(INPUT_PATH, &input_bytes).map_err(BackendError::serialization)?;
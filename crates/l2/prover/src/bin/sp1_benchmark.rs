@@ -248,6 +248,9 @@ fn generate_zk_dex_input(transfer_count: u32) -> anyhow::Result<Vec<u8>> {
         blob_commitment: [0u8; 48],
         blob_proof: [0u8; 48],
         native_token_scale_factor: None,
+        ordering_commitment: None,
+        publish_block_summaries: false,
+        commit_deposit_inclusion: false,
     };
 
     // Serialize ProgramInput via rkyv.
@@ -1,3 +1,6 @@
+use std::net::SocketAddr;
+
+use ethrex_l2_common::prover::ProofFormat;
 use serde::Deserialize;
 use url::Url;
 
@@ -14,4 +17,54 @@ pub struct ProverConfig {
     /// Optional path to a TOML file that configures which guest programs to load.
     #[serde(default)]
     pub programs_config_path: Option<String>,
+    /// Directory where batch inputs are cached. Prefetching is disabled when unset.
+    #[serde(default)]
+    pub input_cache_dir: Option<String>,
+    /// Number of batches after the one being proven whose inputs are prefetched.
+    #[serde(default = "default_prefetch_depth")]
+    pub prefetch_depth: u64,
+    /// Size budget of the input cache of each proof coordinator, in MiB.
+    #[serde(default = "default_input_cache_size_mb")]
+    pub input_cache_size_mb: u64,
+    /// Directory where proof accounting records are stored. Accounting is
+    /// disabled when unset.
+    #[serde(default)]
+    pub accounting_dir: Option<String>,
+    /// Address to serve the prover status on. No status endpoint when unset.
+    #[serde(default)]
+    pub status_addr: Option<SocketAddr>,
+    /// `GET /healthz` fails when no proof coordinator answered for this long,
+    /// unless a batch is being proven.
+    #[serde(default = "default_status_health_window_secs")]
+    pub status_health_window_secs: u64,
+    /// Refuse to start when the hardware probes find that the backend can't
+    /// prove on this machine, instead of only warning.
+    #[serde(default)]
+    pub enforce_capabilities: bool,
+    /// Only proof format advertised to proof coordinators. All the formats the
+    /// backend supports when unset.
+    #[serde(default)]
+    pub proof_format: Option<ProofFormat>,
+}
+
+impl ProverConfig {
+    /// Whether proofs are generated on a remote server instead of this machine.
+    pub fn remote_proving(&self) -> bool {
+        #[cfg(all(feature = "sp1", feature = "gpu"))]
+        return self.backend == BackendType::SP1 && self.sp1_server.is_some();
+        #[cfg(not(all(feature = "sp1", feature = "gpu")))]
+        false
+    }
+}
+
+fn default_prefetch_depth() -> u64 {
+    2
+}
+
+fn default_input_cache_size_mb() -> u64 {
+    4096
+}
+
+fn default_status_health_window_secs() -> u64 {
+    300
 }
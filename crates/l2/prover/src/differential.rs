@@ -0,0 +1,483 @@
+//! Differential execution of a batch.
+//!
+//! Runs the guest program's stateless execution and the native, store-backed
+//! execution over the same batch and reports the first point where they
+//! disagree. Useful to debug batches that prove fine but whose state root
+//! doesn't match the sequencer's.
+
+use std::{collections::BTreeSet, fmt};
+
+use ethrex_blockchain::vm::StoreVmDatabase;
+use ethrex_common::{
+    Address, H256,
+    types::{
+        AccountUpdate, Block, Receipt, block_execution_witness::ExecutionWitness,
+        compute_receipts_root, fee_config::FeeConfig,
+    },
+};
+use ethrex_guest_program::common::{ExecutionError, execute_blocks};
+use ethrex_l2_common::prover::ProverInputData;
+use ethrex_storage::Store;
+use ethrex_vm::Evm;
+
+use crate::backend::BackendError;
+
+/// Outcome of executing a single block on one side of the comparison.
+#[derive(Debug, Clone)]
+pub struct BlockOutcome {
+    pub number: u64,
+    pub hash: H256,
+    pub gas_used: u64,
+    pub receipts_root: H256,
+    pub state_root: H256,
+    pub receipts: Vec<Receipt>,
+    pub account_updates: Vec<AccountUpdate>,
+}
+
+impl BlockOutcome {
+    fn new(
+        block: &Block,
+        gas_used: u64,
+        receipts: Vec<Receipt>,
+        state_root: H256,
+        mut account_updates: Vec<AccountUpdate>,
+    ) -> Self {
+        account_updates.sort_by_key(|update| update.address);
+        Self {
+            number: block.header.number,
+            hash: block.hash(),
+            gas_used,
+            receipts_root: compute_receipts_root(&receipts),
+            state_root,
+            receipts,
+            account_updates,
+        }
+    }
+}
+
+/// A value that differs between the guest and the native execution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mismatch {
+    GasUsed { guest: u64, native: u64 },
+    ReceiptsRoot { guest: H256, native: H256 },
+    StateRoot { guest: H256, native: H256 },
+    AccountUpdates,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mismatch::GasUsed { guest, native } => {
+                write!(f, "gas used: guest {guest}, native {native}")
+            }
+            Mismatch::ReceiptsRoot { guest, native } => {
+                write!(f, "receipts root: guest {guest:#x}, native {native:#x}")
+            }
+            Mismatch::StateRoot { guest, native } => {
+                write!(f, "state root: guest {guest:#x}, native {native:#x}")
+            }
+            Mismatch::AccountUpdates => write!(f, "account updates differ"),
+        }
+    }
+}
+
+/// Report of the first block where both executions disagree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    pub block_number: u64,
+    pub block_hash: H256,
+    /// Index of the first transaction whose receipt differs, if any.
+    pub tx_index: Option<usize>,
+    /// First account, in address order, whose update differs, if any.
+    pub account: Option<Address>,
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Execution diverged at block {} ({:#x})",
+            self.block_number, self.block_hash
+        )?;
+        if let Some(tx_index) = self.tx_index {
+            writeln!(f, "  first divergent transaction: {tx_index}")?;
+        }
+        if let Some(account) = self.account {
+            writeln!(f, "  first divergent account: {account:#x}")?;
+        }
+        for mismatch in &self.mismatches {
+            writeln!(f, "  {mismatch}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs the batch both ways and returns the first divergence, if any.
+///
+/// `native_fee_configs` are the per-block fee configs the sequencer used, as
+/// opposed to the ones shipped in `input`. If the guest program fails without
+/// diverging from the native execution first, its error is returned.
+pub fn check_batch(
+    input: &ProverInputData,
+    store: &Store,
+    native_fee_configs: &[FeeConfig],
+) -> Result<Option<Divergence>, BackendError> {
+    let guest = execute_guest(
+        &input.blocks,
+        input.execution_witness.clone(),
+        input.elasticity_multiplier,
+        &input.fee_configs,
+    )?;
+    let native = execute_native(store, &input.blocks, native_fee_configs)?;
+    match (first_divergence(&guest.outcomes, &native), guest.error) {
+        (Some(divergence), _) => Ok(Some(divergence)),
+        (None, Some(error)) => Err(BackendError::execution(error)),
+        (None, None) => Ok(None),
+    }
+}
+
+/// Blocks executed by the guest program and the error that stopped it, if any.
+pub struct GuestExecution {
+    pub outcomes: Vec<BlockOutcome>,
+    pub error: Option<ExecutionError>,
+}
+
+/// Stateless execution over the execution witness with the guest program's
+/// `execute_blocks`.
+///
+/// A diverging batch usually fails the guest's validations, so the outcomes of
+/// the blocks executed until then are kept along with the error.
+pub fn execute_guest(
+    blocks: &[Block],
+    execution_witness: ExecutionWitness,
+    elasticity_multiplier: u64,
+    fee_configs: &[FeeConfig],
+) -> Result<GuestExecution, BackendError> {
+    check_fee_configs(blocks, fee_configs)?;
+
+    let mut outcomes = Vec::with_capacity(blocks.len());
+    let result = execute_blocks(
+        blocks,
+        execution_witness,
+        elasticity_multiplier,
+        false,
+        |db, i| {
+            let fee_config = fee_configs.get(i).copied().ok_or_else(|| {
+                ExecutionError::Internal("FeeConfig not provided for L2 execution".to_string())
+            })?;
+            Evm::new_for_l2(db.clone(), fee_config).map_err(ExecutionError::Evm)
+        },
+        |execution| {
+            let state_root = execution
+                .state
+                .state_trie_root()
+                .map_err(ExecutionError::GuestProgramState)?;
+            outcomes.push(BlockOutcome::new(
+                execution.block,
+                execution.gas_used,
+                execution.receipts.to_vec(),
+                state_root,
+                execution.account_updates.to_vec(),
+            ));
+            Ok(())
+        },
+    );
+    Ok(GuestExecution {
+        outcomes,
+        error: result.err(),
+    })
+}
+
+/// Native execution on top of the state stored for each block's parent.
+pub fn execute_native(
+    store: &Store,
+    blocks: &[Block],
+    fee_configs: &[FeeConfig],
+) -> Result<Vec<BlockOutcome>, BackendError> {
+    check_fee_configs(blocks, fee_configs)?;
+
+    let mut outcomes = Vec::with_capacity(blocks.len());
+    for (block, fee_config) in blocks.iter().zip(fee_configs) {
+        let parent_header = store
+            .get_block_header_by_hash(block.header.parent_hash)
+            .map_err(BackendError::execution)?
+            .ok_or_else(|| {
+                BackendError::execution(format!(
+                    "Parent of block {} not found in store",
+                    block.header.number
+                ))
+            })?;
+        let parent_hash = parent_header.hash();
+        let db =
+            StoreVmDatabase::new(store.clone(), parent_header).map_err(BackendError::execution)?;
+        let mut vm = Evm::new_for_l2(db, *fee_config).map_err(BackendError::execution)?;
+        let (result, _bal) = vm.execute_block(block).map_err(BackendError::execution)?;
+        let account_updates = vm
+            .get_state_transitions()
+            .map_err(BackendError::execution)?;
+        let state_root = store
+            .apply_account_updates_batch(parent_hash, &account_updates)
+            .map_err(BackendError::execution)?
+            .ok_or_else(|| {
+                BackendError::execution(format!(
+                    "State of block {parent_hash:#x} not found in store"
+                ))
+            })?
+            .state_trie_hash;
+
+        outcomes.push(BlockOutcome::new(
+            block,
+            result.block_gas_used,
+            result.receipts,
+            state_root,
+            account_updates,
+        ));
+    }
+    Ok(outcomes)
+}
+
+/// Compares both executions block by block and reports the first mismatch.
+pub fn first_divergence(guest: &[BlockOutcome], native: &[BlockOutcome]) -> Option<Divergence> {
+    guest
+        .iter()
+        .zip(native)
+        .find_map(|(guest, native)| compare_block(guest, native))
+}
+
+fn compare_block(guest: &BlockOutcome, native: &BlockOutcome) -> Option<Divergence> {
+    let mut mismatches = Vec::new();
+    if guest.gas_used != native.gas_used {
+        mismatches.push(Mismatch::GasUsed {
+            guest: guest.gas_used,
+            native: native.gas_used,
+        });
+    }
+    if guest.receipts_root != native.receipts_root {
+        mismatches.push(Mismatch::ReceiptsRoot {
+            guest: guest.receipts_root,
+            native: native.receipts_root,
+        });
+    }
+    if guest.state_root != native.state_root {
+        mismatches.push(Mismatch::StateRoot {
+            guest: guest.state_root,
+            native: native.state_root,
+        });
+    }
+    let account = first_divergent_account(&guest.account_updates, &native.account_updates);
+    if account.is_some() {
+        mismatches.push(Mismatch::AccountUpdates);
+    }
+    if mismatches.is_empty() {
+        return None;
+    }
+
+    // Receipts carry the cumulative gas used, so the first differing receipt
+    // points at the transaction where execution started to diverge.
+    let tx_index = guest
+        .receipts
+        .iter()
+        .zip(&native.receipts)
+        .position(|(guest, native)| guest != native)
+        .or_else(|| {
+            (guest.receipts.len() != native.receipts.len())
+                .then(|| guest.receipts.len().min(native.receipts.len()))
+        });
+
+    Some(Divergence {
+        block_number: guest.number,
+        block_hash: guest.hash,
+        tx_index,
+        account,
+        mismatches,
+    })
+}
+
+fn first_divergent_account(guest: &[AccountUpdate], native: &[AccountUpdate]) -> Option<Address> {
+    let addresses: BTreeSet<Address> = guest
+        .iter()
+        .chain(native)
+        .map(|update| update.address)
+        .collect();
+    let find = |updates: &[AccountUpdate], address: Address| {
+        updates
+            .binary_search_by_key(&address, |update| update.address)
+            .ok()
+            .and_then(|index| updates.get(index))
+    };
+    addresses
+        .into_iter()
+        .find(|address| find(guest, *address) != find(native, *address))
+}
+
+fn check_fee_configs(blocks: &[Block], fee_configs: &[FeeConfig]) -> Result<(), BackendError> {
+    if blocks.len() != fee_configs.len() {
+        return Err(BackendError::execution(format!(
+            "Expected {} fee configs, got {}",
+            blocks.len(),
+            fee_configs.len()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use std::{
+        fs::File,
+        io::BufReader,
+        path::PathBuf,
+        sync::{Arc, RwLock},
+    };
+
+    use bytes::Bytes;
+    use ethrex_blockchain::{
+        Blockchain, BlockchainOptions, BlockchainType, L2Config,
+        payload::{BuildPayloadArgs, create_payload},
+    };
+    use ethrex_common::{
+        U256,
+        types::{
+            DEFAULT_BUILDER_GAS_CEIL, EIP1559Transaction, ELASTICITY_MULTIPLIER, Genesis,
+            GenesisAccount, Transaction, TxKind,
+        },
+    };
+    use ethrex_l2_rpc::signer::{LocalSigner, Signable, Signer};
+    use ethrex_storage::EngineType;
+    use secp256k1::SecretKey;
+
+    use super::*;
+
+    /// Builds and stores a block with a single transfer executed under
+    /// `fee_config`, returning its witness for stateless execution.
+    async fn setup(fee_config: FeeConfig) -> (Store, Block, ExecutionWitness) {
+        let private_key = SecretKey::from_slice(&[0x11; 32]).unwrap();
+        let sender = LocalSigner::new(private_key).address;
+        let signer: Signer = LocalSigner::new(private_key).into();
+
+        let genesis_path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("../../../fixtures/genesis/execution-api.json");
+        let mut genesis: Genesis =
+            serde_json::from_reader(BufReader::new(File::open(genesis_path).unwrap())).unwrap();
+        genesis.alloc.insert(
+            sender,
+            GenesisAccount {
+                code: Bytes::new(),
+                storage: Default::default(),
+                balance: U256::from(10).pow(U256::from(20)),
+                nonce: 0,
+            },
+        );
+        let chain_id = genesis.config.chain_id;
+
+        let mut store = Store::new("store.db", EngineType::InMemory).unwrap();
+        store.add_initial_state(genesis).await.unwrap();
+        let blockchain = Blockchain::new(
+            store.clone(),
+            BlockchainOptions {
+                r#type: BlockchainType::L2(L2Config {
+                    fee_config: Arc::new(RwLock::new(fee_config)),
+                }),
+                ..Default::default()
+            },
+        );
+
+        let tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+            chain_id,
+            max_priority_fee_per_gas: 1_000_000_000,
+            max_fee_per_gas: 10_000_000_000,
+            gas_limit: 21_000,
+            to: TxKind::Call(Address::from_low_u64_be(0xdead)),
+            value: U256::one(),
+            ..Default::default()
+        })
+        .sign(&signer)
+        .await
+        .unwrap();
+        blockchain.add_transaction_to_pool(tx).await.unwrap();
+
+        let parent = store.get_block_header(0).unwrap().unwrap();
+        let args = BuildPayloadArgs {
+            parent: parent.hash(),
+            timestamp: parent.timestamp + 12,
+            fee_recipient: Address::from_low_u64_be(0xc0ffee),
+            random: H256::zero(),
+            withdrawals: Some(Vec::new()),
+            beacon_root: Some(H256::zero()),
+            slot_number: None,
+            version: 1,
+            elasticity_multiplier: ELASTICITY_MULTIPLIER,
+            gas_ceil: DEFAULT_BUILDER_GAS_CEIL,
+        };
+        let block = create_payload(&args, &store, Bytes::new()).unwrap();
+        let block = blockchain.build_payload(block).unwrap().payload;
+        assert_eq!(block.body.transactions.len(), 1);
+        blockchain.add_block(block.clone()).unwrap();
+
+        let witness = blockchain
+            .generate_witness_for_blocks_with_fee_configs(
+                std::slice::from_ref(&block),
+                Some(&[fee_config]),
+            )
+            .await
+            .unwrap();
+        (store, block, witness)
+    }
+
+    fn prover_input(
+        block: Block,
+        execution_witness: ExecutionWitness,
+        fee_config: FeeConfig,
+    ) -> ProverInputData {
+        ProverInputData {
+            blocks: vec![block],
+            execution_witness,
+            elasticity_multiplier: ELASTICITY_MULTIPLIER,
+            fee_configs: vec![fee_config],
+            blob_commitment: [0u8; 48],
+            blob_proof: [0u8; 48],
+            native_token_scale_factor: None,
+            ordering_commitment: None,
+            publish_block_summaries: false,
+            commit_deposit_inclusion: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn matching_fee_configs_do_not_diverge() {
+        let fee_config = FeeConfig::default();
+        let (store, block, witness) = setup(fee_config).await;
+        let input = prover_input(block, witness, fee_config);
+
+        assert_eq!(check_batch(&input, &store, &[fee_config]).unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn perturbed_fee_config_reports_first_divergence() {
+        let fee_config = FeeConfig::default();
+        let (store, block, witness) = setup(fee_config).await;
+        // Send the base fee to the coinbase instead of burning it
+        let coinbase = block.header.coinbase;
+        let perturbed = FeeConfig {
+            base_fee_vault: Some(coinbase),
+            ..fee_config
+        };
+        let input = prover_input(block.clone(), witness, perturbed);
+
+        let divergence = check_batch(&input, &store, &[fee_config])
+            .unwrap()
+            .expect("executions should diverge");
+
+        assert_eq!(divergence.block_number, block.header.number);
+        assert_eq!(divergence.block_hash, block.hash());
+        // Receipts don't depend on where the base fee goes
+        assert_eq!(divergence.tx_index, None);
+        assert_eq!(divergence.account, Some(coinbase));
+        assert!(matches!(
+            divergence.mismatches.as_slice(),
+            [Mismatch::StateRoot { .. }, Mismatch::AccountUpdates]
+        ));
+    }
+}
@@ -0,0 +1,180 @@
+use ethrex_guest_program::build_info::{ElfBuildInfo, elf_hash, read_build_info};
+use ethrex_guest_program::traits::{GuestProgramError, validate_elf_header};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Expected identity of a guest program ELF, published next to a release so
+/// operators can check the ELF they run is the audited one.
+///
+/// ```toml
+/// program_id = "evm-l2"
+/// backend = "sp1"
+/// elf_hash = "0x…"
+///
+/// [build_info]
+/// git_commit = "…"
+/// cargo_lock_hash = "…"
+/// toolchain = "sp1 v5.0.8, rustc 1.88.0-dev"
+/// features = ["l2"]
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElfManifest {
+    pub program_id: String,
+    /// Backend the ELF targets, one of `ethrex_guest_program::traits::backends`.
+    pub backend: String,
+    /// Hex SHA-256 of the ELF, with or without `0x` prefix.
+    pub elf_hash: String,
+    /// Build metadata the ELF must embed.  When absent only the hash is checked.
+    #[serde(default)]
+    pub build_info: Option<ElfBuildInfo>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ElfManifestError {
+    #[error("failed to read {0}: {1}")]
+    Io(String, std::io::Error),
+    #[error("failed to parse manifest: {0}")]
+    Parse(#[from] toml::de::Error),
+    #[error("invalid elf_hash in manifest: {0}")]
+    InvalidHash(String),
+    #[error(transparent)]
+    InvalidElf(#[from] GuestProgramError),
+    #[error("ELF hash mismatch: expected 0x{expected}, got 0x{actual}")]
+    HashMismatch { expected: String, actual: String },
+    #[error("ELF has no embedded build info")]
+    MissingBuildInfo,
+    #[error("build info mismatch: expected {expected}, got {actual}")]
+    BuildInfoMismatch {
+        expected: Box<ElfBuildInfo>,
+        actual: Box<ElfBuildInfo>,
+    },
+}
+
+impl ElfManifest {
+    /// Load a manifest from a TOML file.
+    pub fn load(path: &Path) -> Result<Self, ElfManifestError> {
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| ElfManifestError::Io(path.display().to_string(), e))?;
+        Ok(toml::from_str(&content)?)
+    }
+
+    /// Check that `elf` is the binary this manifest describes.
+    ///
+    /// Validates the ELF header for the manifest's backend, then compares the
+    /// content hash and, when the manifest lists it, the embedded build info.
+    /// Returns the build info found in the ELF.
+    pub fn verify(&self, elf: &[u8]) -> Result<Option<ElfBuildInfo>, ElfManifestError> {
+        validate_elf_header(&self.backend, elf)?;
+
+        let expected = self.elf_hash.trim_start_matches("0x").to_lowercase();
+        if !hex::decode(&expected).is_ok_and(|hash| hash.len() == 32) {
+            return Err(ElfManifestError::InvalidHash(self.elf_hash.clone()));
+        }
+        let actual = hex::encode(elf_hash(elf));
+        if actual != expected {
+            return Err(ElfManifestError::HashMismatch { expected, actual });
+        }
+
+        let build_info = read_build_info(elf)?;
+        if let Some(expected) = &self.build_info {
+            let actual = build_info
+                .as_ref()
+                .ok_or(ElfManifestError::MissingBuildInfo)?;
+            if actual != expected {
+                return Err(ElfManifestError::BuildInfoMismatch {
+                    expected: Box::new(expected.clone()),
+                    actual: Box::new(actual.clone()),
+                });
+            }
+        }
+        Ok(build_info)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::panic, clippy::expect_used, clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const FIXTURE_ELF: &[u8] =
+        include_bytes!("../../../guest-program/tests/fixtures/build-info-riscv32.elf");
+    const FIXTURE_NO_INFO: &[u8] =
+        include_bytes!("../../../guest-program/tests/fixtures/no-build-info-riscv32.elf");
+
+    fn manifest(elf: &[u8], build_info: &str) -> ElfManifest {
+        toml::from_str(&format!(
+            "program_id = \"evm-l2\"\nbackend = \"sp1\"\nelf_hash = \"0x{}\"\n{build_info}",
+            hex::encode(elf_hash(elf))
+        ))
+        .unwrap()
+    }
+
+    const FIXTURE_BUILD_INFO: &str = r#"
+[build_info]
+git_commit = "0123456789abcdef0123456789abcdef01234567"
+cargo_lock_hash = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
+toolchain = "sp1 v5.0.8"
+features = ["l2"]
+"#;
+
+    #[test]
+    fn matching_elf_is_accepted() {
+        let info = manifest(FIXTURE_ELF, FIXTURE_BUILD_INFO)
+            .verify(FIXTURE_ELF)
+            .unwrap()
+            .unwrap();
+        assert_eq!(info.toolchain, "sp1 v5.0.8");
+    }
+
+    #[test]
+    fn hash_only_manifest_accepts_elf_without_build_info() {
+        assert!(
+            manifest(FIXTURE_NO_INFO, "")
+                .verify(FIXTURE_NO_INFO)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn other_elf_is_rejected() {
+        let result = manifest(FIXTURE_NO_INFO, "").verify(FIXTURE_ELF);
+        assert!(matches!(result, Err(ElfManifestError::HashMismatch { .. })));
+    }
+
+    #[test]
+    fn missing_build_info_is_rejected() {
+        let result = manifest(FIXTURE_NO_INFO, FIXTURE_BUILD_INFO).verify(FIXTURE_NO_INFO);
+        assert!(matches!(result, Err(ElfManifestError::MissingBuildInfo)));
+    }
+
+    #[test]
+    fn different_build_info_is_rejected() {
+        let other = FIXTURE_BUILD_INFO.replace("features = [\"l2\"]", "features = []");
+        let result = manifest(FIXTURE_ELF, &other).verify(FIXTURE_ELF);
+        assert!(matches!(
+            result,
+            Err(ElfManifestError::BuildInfoMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn wrong_backend_is_rejected() {
+        let mut manifest = manifest(FIXTURE_ELF, "");
+        manifest.backend = "zisk".to_string();
+        assert!(matches!(
+            manifest.verify(FIXTURE_ELF),
+            Err(ElfManifestError::InvalidElf(_))
+        ));
+    }
+
+    #[test]
+    fn malformed_hash_is_rejected() {
+        let mut manifest = manifest(FIXTURE_ELF, "");
+        manifest.elf_hash = "0x1234".to_string();
+        assert!(matches!(
+            manifest.verify(FIXTURE_ELF),
+            Err(ElfManifestError::InvalidHash(_))
+        ));
+    }
+}
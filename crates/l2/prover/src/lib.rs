@@ -1,11 +1,17 @@
+pub mod accounting;
 pub mod backend;
 pub mod config;
+pub mod differential;
+pub mod elf_manifest;
+pub mod migrations;
+pub mod prefetch;
 pub mod programs_config;
 pub mod prover;
 pub mod registry;
+pub mod status;
 
 use config::ProverConfig;
-use tracing::warn;
+use tracing::{error, warn};
 
 pub use crate::backend::{BackendError, BackendType, ExecBackend, ProverBackend};
 
@@ -22,6 +28,9 @@ pub use crate::backend::ZiskBackend;
 pub use crate::backend::OpenVmBackend;
 
 pub async fn init_client(config: ProverConfig) {
-    prover::start_prover(config).await;
+    if let Err(err) = prover::start_prover(config).await {
+        error!("Prover can't start: {err}");
+        return;
+    }
     warn!("Prover finished!");
 }
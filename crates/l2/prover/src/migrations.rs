@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use ethrex_common::H256;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// Name of the file, inside the migrations directory, holding one JSON
+/// encoded [`MigrationRecord`] per line.
+const RECORDS_FILE: &str = "migrations.jsonl";
+
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationStoreError {
+    #[error("Migration store IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to encode migration record: {0}")]
+    Encoding(#[from] serde_json::Error),
+    #[error("The migration of {0} is already recorded")]
+    AlreadyRecorded(String),
+}
+
+/// Migration boundary of a program taking over from a predecessor, e.g.
+///
+/// ```toml
+/// [migrations.zk-dex-v2]
+/// boundary_batch = 120
+/// input = "/var/lib/prover/zk-dex-v2-migration.bin"
+/// ```
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MigrationConfig {
+    /// Last batch proven by the predecessor. The successor proves the
+    /// batches after it.
+    pub boundary_batch: u64,
+    /// File holding the rkyv encoded `MigrationInput` of the migration guest,
+    /// proven at startup while the migration isn't recorded.
+    #[serde(default)]
+    pub input: Option<String>,
+}
+
+/// Proven migration of a successor program, checked against its
+/// [`MigrationConfig`] before being recorded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MigrationRecord {
+    /// Program taking over.
+    pub program_id: String,
+    pub from_program_id: String,
+    pub from_version: u8,
+    pub to_version: u8,
+    pub boundary_batch: u64,
+    /// Final state root of the predecessor.
+    pub old_state_root: H256,
+    /// Initial state root of the successor.
+    pub new_state_root: H256,
+    /// Verification key of the migration guest, if the backend has one.
+    pub vk: Option<String>,
+    /// Hex encoded, bincode serialized `BatchProof` of the migration.
+    pub proof: String,
+}
+
+/// Store of the migrations proven by this prover, kept as JSON lines in
+/// `<dir>/migrations.jsonl` so they're only proven once per chain.
+pub struct MigrationStore {
+    path: PathBuf,
+    records: HashMap<String, MigrationRecord>,
+}
+
+impl MigrationStore {
+    /// Opens the store at `dir`, loading the migrations recorded by previous
+    /// runs. Lines that can't be decoded (e.g. a write interrupted by a
+    /// crash) are skipped.
+    pub fn open(dir: &Path) -> Result<Self, MigrationStoreError> {
+        fs::create_dir_all(dir)?;
+        let mut store = Self {
+            path: dir.join(RECORDS_FILE),
+            records: HashMap::new(),
+        };
+        if !store.path.exists() {
+            return Ok(store);
+        }
+        for (index, line) in BufReader::new(File::open(&store.path)?).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<MigrationRecord>(&line) {
+                Ok(record) => {
+                    store
+                        .records
+                        .entry(record.program_id.clone())
+                        .or_insert(record);
+                }
+                Err(e) => warn!(
+                    "Skipping malformed migration record at {}:{}: {e}",
+                    store.path.display(),
+                    index.saturating_add(1)
+                ),
+            }
+        }
+        Ok(store)
+    }
+
+    /// Recorded migration of `program_id`.
+    pub fn get(&self, program_id: &str) -> Option<&MigrationRecord> {
+        self.records.get(program_id)
+    }
+
+    /// Persists `record`. A program migrates once, so a second record for
+    /// the same program is refused.
+    pub fn record(&mut self, record: MigrationRecord) -> Result<(), MigrationStoreError> {
+        if self.records.contains_key(&record.program_id) {
+            return Err(MigrationStoreError::AlreadyRecorded(record.program_id));
+        }
+        let mut line = serde_json::to_vec(&record)?;
+        line.push(b'\n');
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(&line)?;
+        file.sync_data()?;
+        self.records.insert(record.program_id.clone(), record);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(
+    clippy::panic,
+    clippy::expect_used,
+    clippy::indexing_slicing,
+    clippy::unwrap_used
+)]
+mod tests {
+    use super::*;
+
+    fn record(program_id: &str) -> MigrationRecord {
+        MigrationRecord {
+            program_id: program_id.to_string(),
+            from_program_id: "zk-dex".to_string(),
+            from_version: 1,
+            to_version: 2,
+            boundary_batch: 120,
+            old_state_root: H256([0xAA; 32]),
+            new_state_root: H256([0xBB; 32]),
+            vk: Some("0x01".to_string()),
+            proof: "00".to_string(),
+        }
+    }
+
+    #[test]
+    fn records_survive_reopen() {
+        let dir = tempfile::tempdir().expect("tmpdir");
+        let mut store = MigrationStore::open(dir.path()).unwrap();
+        assert!(store.get("zk-dex-v2").is_none());
+        store.record(record("zk-dex-v2")).unwrap();
+
+        let store = MigrationStore::open(dir.path()).unwrap();
+        assert_eq!(store.get("zk-dex-v2"), Some(&record("zk-dex-v2")));
+    }
+
+    #[test]
+    fn refuses_second_record() {
+        let dir = tempfile::tempdir().expect("tmpdir");
+        let mut store = MigrationStore::open(dir.path()).unwrap();
+        store.record(record("zk-dex-v2")).unwrap();
+        let err = store.record(record("zk-dex-v2")).unwrap_err();
+        assert!(matches!(err, MigrationStoreError::AlreadyRecorded(_)));
+    }
+
+    #[test]
+    fn skips_malformed_lines() {
+        let dir = tempfile::tempdir().expect("tmpdir");
+        let line = serde_json::to_string(&record("zk-dex-v2")).unwrap();
+        fs::write(
+            dir.path().join(RECORDS_FILE),
+            format!("{{\"program_id\":\n{line}\n"),
+        )
+        .unwrap();
+
+        let store = MigrationStore::open(dir.path()).unwrap();
+        assert_eq!(store.get("zk-dex-v2"), Some(&record("zk-dex-v2")));
+    }
+}
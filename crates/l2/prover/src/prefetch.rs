@@ -0,0 +1,889 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use ethrex_common::H256;
+use ethrex_l2_common::prover::{CachedInput, ProofData, ProverInputData, prover_input_hash};
+use tokio::sync::Mutex;
+use tracing::{debug, info, warn};
+use url::Url;
+
+use crate::prover::connect_to_prover_server_wr;
+
+#[derive(Debug, thiserror::Error)]
+pub enum InputCacheError {
+    #[error("Input cache IO error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Failed to encode batch input: {0}")]
+    Encoding(String),
+    #[error("Batch {batch_number} input hash mismatch: expected {expected:#x}, got {actual:#x}")]
+    HashMismatch {
+        batch_number: u64,
+        expected: H256,
+        actual: H256,
+    },
+}
+
+#[derive(Clone, Copy)]
+struct CacheEntry {
+    input_hash: H256,
+    size: u64,
+}
+
+/// Index of the on-disk cache of the batch inputs received from a single
+/// proof coordinator.
+///
+/// Entries are content-addressed: `batch_<number>_<input hash>.bin` holds the
+/// batch's [`ProverInputData`] as encoded by [`ProverInputData::encode`], and
+/// its hash is checked again when read. At most one entry is kept per batch, so a new input hash
+/// for a cached batch (e.g. after a reorg) replaces the old entry.
+///
+/// Apart from [`InputCache::open`], the index doesn't touch the files: the
+/// methods that drop entries return the files to delete, so the
+/// [`Prefetcher`] can do it after releasing its lock.
+pub struct InputCache {
+    dir: PathBuf,
+    budget_bytes: u64,
+    entries: BTreeMap<u64, CacheEntry>,
+}
+
+impl InputCache {
+    /// Opens the cache at `dir`, picking up the entries left by previous runs.
+    /// Blocks on file I/O.
+    pub fn open(dir: PathBuf, budget_bytes: u64) -> Result<Self, InputCacheError> {
+        fs::create_dir_all(&dir)?;
+        let mut cache = Self {
+            dir,
+            budget_bytes,
+            entries: BTreeMap::new(),
+        };
+        for entry in fs::read_dir(&cache.dir)? {
+            let path = entry?.path();
+            let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            if file_name.ends_with(".tmp") {
+                // Leftover of an interrupted write.
+                fs::remove_file(&path)?;
+                continue;
+            }
+            let Some((batch_number, input_hash)) = parse_file_name(file_name) else {
+                continue;
+            };
+            if cache.entries.contains_key(&batch_number) {
+                // Leftover of an interrupted replacement, keep a single entry.
+                fs::remove_file(&path)?;
+                continue;
+            }
+            let size = fs::metadata(&path)?.len();
+            cache
+                .entries
+                .insert(batch_number, CacheEntry { input_hash, size });
+        }
+        for path in cache.evict(None) {
+            fs::remove_file(&path)?;
+        }
+        Ok(cache)
+    }
+
+    /// The inputs held by the cache, to be advertised to the proof coordinator.
+    pub fn cached_inputs(&self) -> Vec<CachedInput> {
+        self.entries
+            .iter()
+            .map(|(batch_number, entry)| CachedInput {
+                batch_number: *batch_number,
+                input_hash: entry.input_hash,
+            })
+            .collect()
+    }
+
+    pub fn input_hash(&self, batch_number: u64) -> Option<H256> {
+        self.entries
+            .get(&batch_number)
+            .map(|entry| entry.input_hash)
+    }
+
+    pub fn size_bytes(&self) -> u64 {
+        self.entries.values().map(|entry| entry.size).sum()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.size_bytes() >= self.budget_bytes
+    }
+
+    /// The file holding the input of `batch_number`, if its hash is `input_hash`.
+    pub fn lookup(&self, batch_number: u64, input_hash: H256) -> Option<PathBuf> {
+        (self.input_hash(batch_number) == Some(input_hash))
+            .then(|| self.path(batch_number, input_hash))
+    }
+
+    /// Records an entry already written to [`InputCache::path`], replacing any
+    /// entry with a different hash. Returns the hash of the replaced entry, if
+    /// any, and the files to delete.
+    pub fn insert(
+        &mut self,
+        batch_number: u64,
+        input_hash: H256,
+        size: u64,
+    ) -> (Option<H256>, Vec<PathBuf>) {
+        if self.input_hash(batch_number) == Some(input_hash) {
+            return (None, Vec::new());
+        }
+        let replaced = self.remove(batch_number);
+        self.entries
+            .insert(batch_number, CacheEntry { input_hash, size });
+        let mut stale = self.evict(Some(batch_number));
+        let replaced = replaced.map(|(replaced, path)| {
+            stale.push(path);
+            replaced
+        });
+        (replaced, stale)
+    }
+
+    /// Drops the entry of `batch_number`, returning its hash and file.
+    pub fn remove(&mut self, batch_number: u64) -> Option<(H256, PathBuf)> {
+        let entry = self.entries.remove(&batch_number)?;
+        Some((entry.input_hash, self.path(batch_number, entry.input_hash)))
+    }
+
+    /// Drops the entry of `batch_number` if its hash is still `input_hash`,
+    /// returning its file.
+    pub fn remove_if(&mut self, batch_number: u64, input_hash: H256) -> Option<PathBuf> {
+        if self.input_hash(batch_number) != Some(input_hash) {
+            return None;
+        }
+        self.remove(batch_number).map(|(_, path)| path)
+    }
+
+    /// Drops the entries of batches before `batch_number`, which were already
+    /// proven. Returns their files.
+    pub fn prune_below(&mut self, batch_number: u64) -> Vec<PathBuf> {
+        let stale: Vec<u64> = self
+            .entries
+            .range(..batch_number)
+            .map(|(n, _)| *n)
+            .collect();
+        stale
+            .into_iter()
+            .filter_map(|batch_number| self.remove(batch_number))
+            .map(|(_, path)| path)
+            .collect()
+    }
+
+    /// Drops the oldest entries until the cache fits its budget, never
+    /// dropping the entry of `keep`. Returns their files.
+    fn evict(&mut self, keep: Option<u64>) -> Vec<PathBuf> {
+        let mut evicted = Vec::new();
+        while self.size_bytes() > self.budget_bytes {
+            let Some(oldest) = self.entries.keys().copied().find(|n| Some(*n) != keep) else {
+                break;
+            };
+            debug!("Evicting cached input of batch {oldest}");
+            if let Some((_, path)) = self.remove(oldest) {
+                evicted.push(path);
+            }
+        }
+        evicted
+    }
+
+    pub fn path(&self, batch_number: u64, input_hash: H256) -> PathBuf {
+        self.dir.join(format!(
+            "batch_{batch_number}_{}.bin",
+            hex::encode(input_hash)
+        ))
+    }
+}
+
+/// Reads the cached input at `path`, checking it against `input_hash`.
+/// Returns the input and its size in bytes.
+async fn read_entry(
+    path: &Path,
+    batch_number: u64,
+    input_hash: H256,
+) -> Result<(ProverInputData, u64), InputCacheError> {
+    let bytes = tokio::fs::read(path).await?;
+    let actual = prover_input_hash(&bytes);
+    if actual != input_hash {
+        return Err(InputCacheError::HashMismatch {
+            batch_number,
+            expected: input_hash,
+            actual,
+        });
+    }
+    let input =
+        ProverInputData::decode(&bytes).map_err(|e| InputCacheError::Encoding(e.to_string()))?;
+    Ok((input, u64::try_from(bytes.len()).unwrap_or(u64::MAX)))
+}
+
+/// Writes an entry to `path` through a temporary file, so a crash never leaves
+/// a truncated entry under a valid name.
+async fn write_entry(path: &Path, encoded_input: &[u8]) -> Result<(), InputCacheError> {
+    // Concurrent writes of the same entry each use their own temporary file.
+    static NEXT_TMP: AtomicU64 = AtomicU64::new(0);
+    let tmp_path = path.with_extension(format!("{}.tmp", NEXT_TMP.fetch_add(1, Ordering::Relaxed)));
+    tokio::fs::write(&tmp_path, encoded_input).await?;
+    tokio::fs::rename(&tmp_path, path).await?;
+    Ok(())
+}
+
+async fn remove_files(paths: Vec<PathBuf>) {
+    for path in paths {
+        if let Err(e) = tokio::fs::remove_file(&path).await {
+            warn!("Failed to remove cached input {}: {e}", path.display());
+        }
+    }
+}
+
+fn parse_file_name(file_name: &str) -> Option<(u64, H256)> {
+    let (batch_number, input_hash) = file_name
+        .strip_prefix("batch_")?
+        .strip_suffix(".bin")?
+        .split_once('_')?;
+    Some((batch_number.parse().ok()?, input_hash.parse().ok()?))
+}
+
+/// Counters reporting how useful prefetching is.
+#[derive(Default)]
+pub struct PrefetchMetrics {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    bytes_saved: AtomicU64,
+    invalidations: AtomicU64,
+}
+
+impl PrefetchMetrics {
+    /// An assigned batch was served from the cache instead of the network.
+    pub fn record_hit(&self, bytes: u64) {
+        self.hits.fetch_add(1, Ordering::Relaxed);
+        self.bytes_saved.fetch_add(bytes, Ordering::Relaxed);
+        self.export();
+    }
+
+    /// An assigned batch had to be downloaded.
+    pub fn record_miss(&self) {
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        self.export();
+    }
+
+    /// A cached input was replaced because the coordinator advertised a
+    /// different hash for its batch.
+    pub fn record_invalidation(&self) {
+        self.invalidations.fetch_add(1, Ordering::Relaxed);
+        self.export();
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    pub fn bytes_saved(&self) -> u64 {
+        self.bytes_saved.load(Ordering::Relaxed)
+    }
+
+    pub fn invalidations(&self) -> u64 {
+        self.invalidations.load(Ordering::Relaxed)
+    }
+
+    /// Percentage of assigned batches served from the cache.
+    pub fn hit_rate_percent(&self) -> u64 {
+        let hits = self.hits();
+        let total = hits.saturating_add(self.misses());
+        if total == 0 {
+            return 0;
+        }
+        hits.saturating_mul(100) / total
+    }
+
+    /// Mirrors the counters to the Prometheus gauges served at `GET /metrics`.
+    fn export(&self) {
+        #[cfg(feature = "metrics")]
+        {
+            use ethrex_metrics::prover::METRICS_PROVER;
+            let gauge = |value: u64| i64::try_from(value).unwrap_or(i64::MAX);
+            METRICS_PROVER.set_input_cache_hits(gauge(self.hits()));
+            METRICS_PROVER.set_input_cache_misses(gauge(self.misses()));
+            METRICS_PROVER.set_input_cache_bytes_saved(gauge(self.bytes_saved()));
+            METRICS_PROVER.set_input_cache_invalidations(gauge(self.invalidations()));
+        }
+    }
+
+    pub fn report(&self) {
+        info!(
+            prefetch_hits = self.hits(),
+            prefetch_misses = self.misses(),
+            prefetch_hit_rate_percent = self.hit_rate_percent(),
+            prefetch_bytes_saved = self.bytes_saved(),
+            prefetch_invalidations = self.invalidations(),
+            "Batch input prefetch stats"
+        );
+    }
+}
+
+/// Fetches the inputs of the batches following the one being proven, and
+/// keeps them in an [`InputCache`] per proof coordinator.
+///
+/// Only the cache indexes are behind `caches`; reading, writing and deleting
+/// the files happens after releasing the lock.
+pub struct Prefetcher {
+    cache_dir: PathBuf,
+    depth: u64,
+    budget_bytes: u64,
+    caches: Mutex<HashMap<Url, InputCache>>,
+    /// Serializes opening caches, so a cache is only scanned once.
+    opening: Mutex<()>,
+    in_flight: Mutex<HashSet<Url>>,
+    metrics: PrefetchMetrics,
+}
+
+impl Prefetcher {
+    /// `budget_bytes` applies to each proof coordinator's cache.
+    pub fn new(cache_dir: PathBuf, depth: u64, budget_bytes: u64) -> Self {
+        Self {
+            cache_dir,
+            depth,
+            budget_bytes,
+            caches: Mutex::new(HashMap::new()),
+            opening: Mutex::new(()),
+            in_flight: Mutex::new(HashSet::new()),
+            metrics: PrefetchMetrics::default(),
+        }
+    }
+
+    pub fn metrics(&self) -> &PrefetchMetrics {
+        &self.metrics
+    }
+
+    /// Runs `f` on the index of the cache of `endpoint`, opening it on first
+    /// use. `f` must not do file I/O.
+    async fn with_cache<T>(
+        &self,
+        endpoint: &Url,
+        f: impl FnOnce(&mut InputCache) -> T,
+    ) -> Option<T> {
+        if !self.caches.lock().await.contains_key(endpoint) {
+            let _opening = self.opening.lock().await;
+            if !self.caches.lock().await.contains_key(endpoint) {
+                let dir = self.cache_dir.join(endpoint_dir_name(endpoint));
+                let budget_bytes = self.budget_bytes;
+                let opened =
+                    tokio::task::spawn_blocking(move || InputCache::open(dir, budget_bytes)).await;
+                match opened {
+                    Ok(Ok(cache)) => {
+                        self.caches.lock().await.insert(endpoint.clone(), cache);
+                    }
+                    Ok(Err(e)) => {
+                        warn!(%endpoint, "Failed to open input cache: {e}");
+                        return None;
+                    }
+                    Err(e) => {
+                        warn!(%endpoint, "Failed to open input cache: {e}");
+                        return None;
+                    }
+                }
+            }
+        }
+        self.caches.lock().await.get_mut(endpoint).map(f)
+    }
+
+    /// The inputs cached for `endpoint`, to attach to a BatchRequest.
+    pub async fn cached_inputs(&self, endpoint: &Url) -> Vec<CachedInput> {
+        self.with_cache(endpoint, |cache| cache.cached_inputs())
+            .await
+            .unwrap_or_default()
+    }
+
+    /// Number of batches after `batch_number` whose inputs are cached for
+    /// `endpoint`, i.e. ready to be proven.
+    pub async fn queue_depth(&self, endpoint: &Url, batch_number: u64) -> u64 {
+        self.cached_inputs(endpoint)
+            .await
+            .iter()
+            .filter(|cached| cached.batch_number > batch_number)
+            .count()
+            .try_into()
+            .unwrap_or(u64::MAX)
+    }
+
+    /// Takes the cached input of an assigned batch, if it matches the hash
+    /// advertised by the coordinator.
+    pub async fn take(
+        &self,
+        endpoint: &Url,
+        batch_number: u64,
+        input_hash: H256,
+    ) -> Option<ProverInputData> {
+        self.prune_below(endpoint, batch_number).await;
+        match self.get(endpoint, batch_number, input_hash).await {
+            Ok(Some((input, size))) => {
+                debug!(%endpoint, "Using cached input for batch {batch_number}");
+                self.metrics.record_hit(size);
+                Some(input)
+            }
+            Ok(None) => None,
+            Err(e) => {
+                warn!(%endpoint, "Dropped cached input of batch {batch_number}: {e}");
+                None
+            }
+        }
+    }
+
+    /// Caches the input of an assigned batch that had to be downloaded, so a
+    /// restart doesn't download it again.
+    pub async fn store_assigned(
+        &self,
+        endpoint: &Url,
+        batch_number: u64,
+        input_hash: H256,
+        input: &ProverInputData,
+    ) {
+        self.metrics.record_miss();
+        self.store(endpoint, batch_number, input_hash, input).await;
+    }
+
+    /// Drops the input of a batch whose proof was accepted.
+    pub async fn proved(&self, endpoint: &Url, batch_number: u64) {
+        self.prune_below(endpoint, batch_number + 1).await;
+    }
+
+    /// Prefetches the inputs of the `depth` batches after `batch_number` in the
+    /// background. Does nothing if a prefetch for `endpoint` is still running.
+    pub fn spawn_prefetch(self: &Arc<Self>, endpoint: Url, commit_hash: String, batch_number: u64) {
+        let prefetcher = Arc::clone(self);
+        tokio::spawn(async move {
+            if !prefetcher.in_flight.lock().await.insert(endpoint.clone()) {
+                return;
+            }
+            prefetcher
+                .prefetch(&endpoint, commit_hash, batch_number)
+                .await;
+            prefetcher.in_flight.lock().await.remove(&endpoint);
+        });
+    }
+
+    async fn prefetch(&self, endpoint: &Url, commit_hash: String, batch_number: u64) {
+        for next in (batch_number + 1)..=(batch_number + self.depth) {
+            let Some((cached_hash, is_full)) = self
+                .with_cache(endpoint, |cache| (cache.input_hash(next), cache.is_full()))
+                .await
+            else {
+                return;
+            };
+            if cached_hash.is_none() && is_full {
+                debug!(%endpoint, "Input cache is full, not prefetching batch {next}");
+                return;
+            }
+
+            let request = ProofData::input_request(commit_hash.clone(), next, cached_hash);
+            let response = match connect_to_prover_server_wr(endpoint, &request).await {
+                Ok(response) => response,
+                Err(e) => {
+                    // Coordinators that don't support prefetching close the
+                    // connection or answer with a ProtocolError.
+                    debug!(%endpoint, "Failed to prefetch input of batch {next}: {e}");
+                    return;
+                }
+            };
+            match response {
+                ProofData::InputResponse {
+                    input_hash: Some(input_hash),
+                    input: Some(input),
+                    ..
+                } => {
+                    self.store(endpoint, next, input_hash, &input).await;
+                    debug!(%endpoint, "Prefetched input of batch {next}");
+                }
+                ProofData::InputResponse {
+                    input_hash: Some(input_hash),
+                    input: None,
+                    ..
+                } if cached_hash == Some(input_hash) => {}
+                ProofData::InputResponse {
+                    input_hash: None, ..
+                } => {
+                    // The batch doesn't exist yet, or no longer does.
+                    self.remove(endpoint, next).await;
+                    return;
+                }
+                ProofData::ProtocolError { error } => {
+                    debug!(%endpoint, "Proof coordinator doesn't support prefetching: {error}");
+                    return;
+                }
+                _ => {
+                    warn!(%endpoint, "Unexpected response to InputRequest for batch {next}");
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Encodes and caches `input` after checking it against the advertised hash.
+    async fn store(
+        &self,
+        endpoint: &Url,
+        batch_number: u64,
+        input_hash: H256,
+        input: &ProverInputData,
+    ) {
+        let encoded = match input.encode() {
+            Ok(encoded) => encoded,
+            Err(e) => {
+                warn!(%endpoint, "Failed to encode input of batch {batch_number}: {e}");
+                return;
+            }
+        };
+        let actual = prover_input_hash(&encoded);
+        if actual != input_hash {
+            warn!(
+                %endpoint,
+                "Not caching input of batch {batch_number}: advertised hash {input_hash:#x}, computed {actual:#x}"
+            );
+            return;
+        }
+        match self
+            .insert(endpoint, batch_number, input_hash, &encoded)
+            .await
+        {
+            Ok(Some(replaced)) => {
+                info!(
+                    %endpoint,
+                    "Cached input of batch {batch_number} was invalidated ({replaced:#x} -> {input_hash:#x})"
+                );
+                self.metrics.record_invalidation();
+            }
+            Ok(None) => {}
+            Err(e) => warn!(%endpoint, "Failed to cache input of batch {batch_number}: {e}"),
+        }
+    }
+
+    /// Reads the cached input of `batch_number` and its size in bytes, if its
+    /// hash is `input_hash`. Entries that fail to verify are dropped.
+    async fn get(
+        &self,
+        endpoint: &Url,
+        batch_number: u64,
+        input_hash: H256,
+    ) -> Result<Option<(ProverInputData, u64)>, InputCacheError> {
+        let Some(path) = self
+            .with_cache(endpoint, |cache| cache.lookup(batch_number, input_hash))
+            .await
+            .flatten()
+        else {
+            return Ok(None);
+        };
+        let result = read_entry(&path, batch_number, input_hash).await;
+        if result.is_err() {
+            let stale = self
+                .with_cache(endpoint, |cache| cache.remove_if(batch_number, input_hash))
+                .await
+                .flatten();
+            remove_files(stale.into_iter().collect()).await;
+        }
+        result.map(Some)
+    }
+
+    /// Writes the encoded input of `batch_number` and records it, replacing
+    /// any entry with a different hash. Returns the hash of the replaced
+    /// entry, if any.
+    async fn insert(
+        &self,
+        endpoint: &Url,
+        batch_number: u64,
+        input_hash: H256,
+        encoded_input: &[u8],
+    ) -> Result<Option<H256>, InputCacheError> {
+        let Some(path) = self
+            .with_cache(endpoint, |cache| {
+                (cache.input_hash(batch_number) != Some(input_hash))
+                    .then(|| cache.path(batch_number, input_hash))
+            })
+            .await
+            .flatten()
+        else {
+            return Ok(None);
+        };
+        write_entry(&path, encoded_input).await?;
+
+        let size = u64::try_from(encoded_input.len()).unwrap_or(u64::MAX);
+        let Some((replaced, stale)) = self
+            .with_cache(endpoint, |cache| {
+                cache.insert(batch_number, input_hash, size)
+            })
+            .await
+        else {
+            return Ok(None);
+        };
+        remove_files(stale).await;
+        Ok(replaced)
+    }
+
+    async fn remove(&self, endpoint: &Url, batch_number: u64) {
+        let stale = self
+            .with_cache(endpoint, |cache| cache.remove(batch_number))
+            .await
+            .flatten();
+        remove_files(stale.into_iter().map(|(_, path)| path).collect()).await;
+    }
+
+    async fn prune_below(&self, endpoint: &Url, batch_number: u64) {
+        let stale = self
+            .with_cache(endpoint, |cache| cache.prune_below(batch_number))
+            .await
+            .unwrap_or_default();
+        remove_files(stale).await;
+    }
+}
+
+/// Name of the directory holding the cache of `endpoint`.
+fn endpoint_dir_name(endpoint: &Url) -> String {
+    endpoint
+        .as_str()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used, clippy::unwrap_used, clippy::indexing_slicing)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn count_entries(path: &Path) -> usize {
+        fs::read_dir(path).map(|dir| dir.count()).unwrap_or(0)
+    }
+
+    fn sample_input(elasticity_multiplier: u64) -> ProverInputData {
+        ProverInputData {
+            blocks: vec![],
+            execution_witness: Default::default(),
+            elasticity_multiplier,
+            blob_commitment: [0; 48],
+            blob_proof: [0; 48],
+            fee_configs: vec![],
+            native_token_scale_factor: None,
+            ordering_commitment: None,
+            publish_block_summaries: false,
+            commit_deposit_inclusion: false,
+        }
+    }
+
+    fn encoded(input: &ProverInputData) -> (H256, Vec<u8>) {
+        let encoded = input.encode().expect("encode");
+        (prover_input_hash(&encoded), encoded)
+    }
+
+    fn endpoint() -> Url {
+        Url::parse("http://127.0.0.1:3900").expect("url")
+    }
+
+    fn cache_dir(dir: &Path) -> PathBuf {
+        dir.join(endpoint_dir_name(&endpoint()))
+    }
+
+    async fn cached_batches(prefetcher: &Prefetcher) -> Vec<u64> {
+        prefetcher
+            .cached_inputs(&endpoint())
+            .await
+            .iter()
+            .map(|c| c.batch_number)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn cached_input_survives_reopen() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let (hash, bytes) = encoded(&sample_input(2));
+
+        let prefetcher = Prefetcher::new(dir.path().to_path_buf(), 1, u64::MAX);
+        prefetcher
+            .insert(&endpoint(), 7, hash, &bytes)
+            .await
+            .expect("insert");
+        drop(prefetcher);
+
+        let prefetcher = Prefetcher::new(dir.path().to_path_buf(), 1, u64::MAX);
+        assert_eq!(
+            prefetcher.cached_inputs(&endpoint()).await,
+            vec![CachedInput {
+                batch_number: 7,
+                input_hash: hash
+            }]
+        );
+        let (input, size) = prefetcher
+            .get(&endpoint(), 7, hash)
+            .await
+            .expect("get")
+            .expect("cached");
+        assert_eq!(input.elasticity_multiplier, 2);
+        assert_eq!(size, u64::try_from(bytes.len()).unwrap());
+    }
+
+    #[tokio::test]
+    async fn get_requires_advertised_hash() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let (hash, bytes) = encoded(&sample_input(2));
+        let prefetcher = Prefetcher::new(dir.path().to_path_buf(), 1, u64::MAX);
+        let endpoint = endpoint();
+        prefetcher
+            .insert(&endpoint, 7, hash, &bytes)
+            .await
+            .expect("insert");
+
+        let other_hash = prefetcher.get(&endpoint, 7, H256::repeat_byte(1)).await;
+        assert!(other_hash.expect("get").is_none());
+        let other_batch = prefetcher.get(&endpoint, 8, hash).await;
+        assert!(other_batch.expect("get").is_none());
+        let cached = prefetcher.get(&endpoint, 7, hash).await;
+        assert!(cached.expect("get").is_some());
+    }
+
+    #[tokio::test]
+    async fn new_hash_for_cached_batch_replaces_entry() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let (old_hash, old_bytes) = encoded(&sample_input(2));
+        let (new_hash, new_bytes) = encoded(&sample_input(3));
+        let prefetcher = Prefetcher::new(dir.path().to_path_buf(), 1, u64::MAX);
+        let endpoint = endpoint();
+
+        for (input_hash, bytes, replaced) in [
+            (old_hash, &old_bytes, None),
+            (old_hash, &old_bytes, None),
+            (new_hash, &new_bytes, Some(old_hash)),
+        ] {
+            let result = prefetcher.insert(&endpoint, 7, input_hash, bytes).await;
+            assert_eq!(result.expect("insert"), replaced);
+        }
+        let cached_hash = prefetcher
+            .with_cache(&endpoint, |cache| cache.input_hash(7))
+            .await
+            .flatten();
+        assert_eq!(cached_hash, Some(new_hash));
+        assert_eq!(count_entries(&cache_dir(dir.path())), 1);
+    }
+
+    #[tokio::test]
+    async fn corrupted_entry_is_dropped() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let (hash, bytes) = encoded(&sample_input(2));
+        let prefetcher = Prefetcher::new(dir.path().to_path_buf(), 1, u64::MAX);
+        prefetcher
+            .insert(&endpoint(), 7, hash, &bytes)
+            .await
+            .expect("insert");
+        let path = prefetcher
+            .with_cache(&endpoint(), |cache| cache.path(7, hash))
+            .await
+            .expect("open");
+        fs::write(path, b"garbage").expect("corrupt");
+
+        assert!(matches!(
+            prefetcher.get(&endpoint(), 7, hash).await,
+            Err(InputCacheError::HashMismatch {
+                batch_number: 7,
+                ..
+            })
+        ));
+        assert!(cached_batches(&prefetcher).await.is_empty());
+        assert_eq!(count_entries(&cache_dir(dir.path())), 0);
+    }
+
+    #[tokio::test]
+    async fn oldest_entries_are_evicted_beyond_budget() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let (hash, bytes) = encoded(&sample_input(2));
+        let size = u64::try_from(bytes.len()).unwrap();
+        let prefetcher = Prefetcher::new(dir.path().to_path_buf(), 1, 2 * size);
+
+        for batch_number in [5, 6] {
+            prefetcher
+                .insert(&endpoint(), batch_number, hash, &bytes)
+                .await
+                .expect("insert");
+        }
+        let is_full = prefetcher
+            .with_cache(&endpoint(), |cache| cache.is_full())
+            .await;
+        assert_eq!(is_full, Some(true));
+        prefetcher
+            .insert(&endpoint(), 7, hash, &bytes)
+            .await
+            .expect("insert");
+
+        assert_eq!(cached_batches(&prefetcher).await, vec![6, 7]);
+        assert_eq!(count_entries(&cache_dir(dir.path())), 2);
+    }
+
+    #[tokio::test]
+    async fn inserted_entry_is_kept_even_if_over_budget() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let (hash, bytes) = encoded(&sample_input(2));
+        let prefetcher = Prefetcher::new(dir.path().to_path_buf(), 1, 1);
+
+        for batch_number in [5, 3] {
+            prefetcher
+                .insert(&endpoint(), batch_number, hash, &bytes)
+                .await
+                .expect("insert");
+        }
+        assert_eq!(cached_batches(&prefetcher).await, vec![3]);
+        assert_eq!(count_entries(&cache_dir(dir.path())), 1);
+    }
+
+    #[tokio::test]
+    async fn proven_batches_are_dropped() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let (hash, bytes) = encoded(&sample_input(2));
+        let prefetcher = Prefetcher::new(dir.path().to_path_buf(), 1, u64::MAX);
+        for batch_number in 5..=8 {
+            prefetcher
+                .insert(&endpoint(), batch_number, hash, &bytes)
+                .await
+                .expect("insert");
+        }
+
+        prefetcher.proved(&endpoint(), 6).await;
+        assert_eq!(cached_batches(&prefetcher).await, vec![7, 8]);
+        assert_eq!(count_entries(&cache_dir(dir.path())), 2);
+    }
+
+    #[tokio::test]
+    async fn leftovers_are_cleaned_on_open() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let (hash, bytes) = encoded(&sample_input(2));
+        let prefetcher = Prefetcher::new(dir.path().to_path_buf(), 1, u64::MAX);
+        prefetcher
+            .insert(&endpoint(), 7, hash, &bytes)
+            .await
+            .expect("insert");
+        let cache_dir = cache_dir(dir.path());
+        fs::write(cache_dir.join("batch_8_partial.3.tmp"), b"partial").expect("write");
+        fs::write(cache_dir.join("notes.txt"), b"unrelated").expect("write");
+
+        let prefetcher = Prefetcher::new(dir.path().to_path_buf(), 1, u64::MAX);
+        assert_eq!(cached_batches(&prefetcher).await, vec![7]);
+        assert!(!cache_dir.join("batch_8_partial.3.tmp").exists());
+        assert!(cache_dir.join("notes.txt").exists());
+    }
+
+    #[test]
+    fn hit_rate_and_bytes_saved() {
+        let metrics = PrefetchMetrics::default();
+        assert_eq!(metrics.hit_rate_percent(), 0);
+
+        metrics.record_hit(100);
+        metrics.record_hit(50);
+        metrics.record_miss();
+        metrics.record_miss();
+        metrics.record_invalidation();
+
+        assert_eq!(metrics.hit_rate_percent(), 50);
+        assert_eq!(metrics.bytes_saved(), 150);
+        assert_eq!(metrics.invalidations(), 1);
+    }
+}
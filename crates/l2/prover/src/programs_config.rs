@@ -1,6 +1,10 @@
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::path::Path;
 
+use crate::accounting::CostRate;
+use crate::migrations::MigrationConfig;
+
 /// Runtime configuration for the guest program registry.
 ///
 /// Controls which guest programs are loaded and which is the default.
@@ -18,6 +22,18 @@ pub struct ProgramsConfig {
     /// Programs found here are loaded at runtime without recompilation.
     #[serde(default)]
     pub programs_dir: Option<String>,
+    /// Cost rate used to account for proofs, e.g. `{ per_second = 0.002 }`.
+    #[serde(default)]
+    pub cost_rate: Option<CostRate>,
+    /// Per-program cost rates replacing `cost_rate`.
+    #[serde(default)]
+    pub rate_overrides: HashMap<String, CostRate>,
+    /// Directory where proven state migrations are recorded.
+    #[serde(default)]
+    pub migrations_dir: Option<String>,
+    /// Migration boundaries of programs taking over from a predecessor.
+    #[serde(default)]
+    pub migrations: HashMap<String, MigrationConfig>,
 }
 
 fn default_program() -> String {
@@ -39,6 +55,10 @@ impl Default for ProgramsConfig {
             default_program: default_program(),
             enabled_programs: default_enabled(),
             programs_dir: None,
+            cost_rate: None,
+            rate_overrides: HashMap::new(),
+            migrations_dir: None,
+            migrations: HashMap::new(),
         }
     }
 }
@@ -96,6 +116,58 @@ enabled_programs = ["zk-dex", "tokamon"]
         assert_eq!(cfg.enabled_programs, vec!["zk-dex", "tokamon"]);
     }
 
+    #[test]
+    fn load_cost_rates() {
+        let dir = tempfile::tempdir().expect("tmpdir");
+        let path = dir.path().join("programs.toml");
+        std::fs::write(
+            &path,
+            r#"
+cost_rate = { per_second = 0.002 }
+
+[rate_overrides]
+zk-dex = { per_cycle = 1e-9 }
+"#,
+        )
+        .expect("write");
+        let cfg = ProgramsConfig::load(path.to_str().expect("utf8")).expect("should parse");
+        assert_eq!(cfg.cost_rate, Some(CostRate::PerSecond(0.002)));
+        assert_eq!(
+            cfg.rate_overrides.get("zk-dex"),
+            Some(&CostRate::PerCycle(1e-9))
+        );
+    }
+
+    #[test]
+    fn load_migrations() {
+        let dir = tempfile::tempdir().expect("tmpdir");
+        let path = dir.path().join("programs.toml");
+        std::fs::write(
+            &path,
+            r#"
+enabled_programs = ["zk-dex", "zk-dex-v2"]
+migrations_dir = "/var/lib/prover/migrations"
+
+[migrations.zk-dex-v2]
+boundary_batch = 120
+input = "/var/lib/prover/zk-dex-v2-migration.bin"
+"#,
+        )
+        .expect("write");
+        let cfg = ProgramsConfig::load(path.to_str().expect("utf8")).expect("should parse");
+        assert_eq!(
+            cfg.migrations_dir.as_deref(),
+            Some("/var/lib/prover/migrations")
+        );
+        assert_eq!(
+            cfg.migrations.get("zk-dex-v2"),
+            Some(&MigrationConfig {
+                boundary_batch: 120,
+                input: Some("/var/lib/prover/zk-dex-v2-migration.bin".to_string()),
+            })
+        );
+    }
+
     #[test]
     fn filtered_registry() {
         use crate::registry::GuestProgramRegistry;
@@ -1,5 +1,8 @@
-use std::sync::Arc;
-use std::time::Duration;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
 
 use tokio::{
     io::{AsyncReadExt, AsyncWriteExt},
@@ -9,16 +12,30 @@ use tokio::{
 use tracing::{debug, error, info, warn};
 use url::Url;
 
+use ethrex_common::H256;
+use ethrex_guest_program::common::migration::MigrationOutput;
 use ethrex_guest_program::input::ProgramInput;
 use ethrex_guest_program::programs::dynamic::DynamicGuestProgram;
-use ethrex_guest_program::programs::{BridgeGuestProgram, EvmL2GuestProgram, TokammonGuestProgram, ZkDexGuestProgram};
+use ethrex_guest_program::programs::{
+    BridgeGuestProgram, EvmL2GuestProgram, TokammonGuestProgram, ZkDexGuestProgram,
+    ZkDexV2GuestProgram,
+};
 use ethrex_l2::sequencer::utils::get_git_commit_hash;
-use ethrex_l2_common::prover::{BatchProof, ProofData, ProofFormat, ProverType};
+use ethrex_l2_common::prover::{
+    BatchProof, ProgramCapability, ProofData, ProofFormat, ProverCapabilities, ProverInputData,
+    ProverType, ProvingFeedback,
+};
 
+use crate::accounting::{self, AccountingStore, ProofRecord};
 use crate::backend::{BackendError, BackendType, ExecBackend, ProverBackend};
 use crate::config::ProverConfig;
+use crate::migrations::MigrationStore;
+use crate::prefetch::Prefetcher;
 use crate::programs_config::ProgramsConfig;
 use crate::registry::GuestProgramRegistry;
+use crate::status::{
+    self, BatchOutcome, ProverErrorKind, ProverProgress, ProverStatus, ProvingPhase,
+};
 
 /// Create a guest program registry based on runtime config.
 ///
@@ -36,11 +53,16 @@ fn create_registry(config_path: Option<&str>) -> GuestProgramRegistry {
         .unwrap_or_default();
 
     let mut registry = GuestProgramRegistry::new(&config.default_program);
+    registry.set_default_rate(config.cost_rate);
+    for (program_id, rate) in &config.rate_overrides {
+        registry.set_rate_override(program_id, *rate);
+    }
 
     // Built-in programs (compiled into the binary)
     let builtin_programs: Vec<(String, Arc<dyn ethrex_guest_program::traits::GuestProgram>)> = vec![
         ("evm-l2".to_string(), Arc::new(EvmL2GuestProgram)),
         ("zk-dex".to_string(), Arc::new(ZkDexGuestProgram)),
+        ("zk-dex-v2".to_string(), Arc::new(ZkDexV2GuestProgram)),
         ("tokamon".to_string(), Arc::new(TokammonGuestProgram)),
         ("bridge".to_string(), Arc::new(BridgeGuestProgram)),
     ];
@@ -88,45 +110,175 @@ fn create_registry(config_path: Option<&str>) -> GuestProgramRegistry {
         }
     }
 
+    for (program_id, migration) in &config.migrations {
+        registry.set_migration(program_id, migration.clone());
+    }
+    if let Some(dir) = &config.migrations_dir {
+        match MigrationStore::open(Path::new(dir)) {
+            Ok(store) => registry.set_migration_store(store),
+            Err(e) => warn!("Can't open the migration store at {dir}: {e}"),
+        }
+    }
+
+    registry
+}
+
+/// Proves the state migration of the registered programs taking over from a
+/// predecessor, when it isn't recorded yet. Their batches are refused until
+/// it is.
+fn prove_migrations<B: ProverBackend>(backend: &B, registry: &mut GuestProgramRegistry) {
+    let pending: Vec<String> = registry
+        .pending_migrations()
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+    for program_id in pending {
+        match prove_migration(backend, registry, &program_id) {
+            Ok(()) => info!(%program_id, "Proved and recorded the state migration"),
+            Err(e) => warn!(
+                %program_id,
+                "Can't prove the state migration, batches of {program_id} are refused until it is: {e}"
+            ),
+        }
+    }
+}
+
+/// Proves the migration of `program_id` with its migration guest from the
+/// configured input and records it in the registry.
+fn prove_migration<B: ProverBackend>(
+    backend: &B,
+    registry: &mut GuestProgramRegistry,
+    program_id: &str,
+) -> Result<(), BackendError> {
+    let input_path = registry
+        .migration(program_id)
+        .and_then(|migration| migration.input.clone())
+        .ok_or_else(|| BackendError::proving("no migration input is configured"))?;
+    let program = registry
+        .get(program_id)
+        .cloned()
+        .ok_or_else(|| BackendError::proving("the program isn't registered"))?;
+    let migration = program
+        .migration_program()
+        .ok_or_else(|| BackendError::proving("the program has no migration guest"))?;
+    let backend_name = backend.backend_name();
+    let elf = migration.elf(backend_name).ok_or_else(|| {
+        BackendError::not_implemented(format!(
+            "{} has no {backend_name} ELF",
+            migration.program_id()
+        ))
+    })?;
+
+    let input = std::fs::read(&input_path)
+        .map_err(|e| BackendError::serialization(format!("can't read {input_path}: {e}")))?;
+    if let Some(max) = migration.resource_limits().max_input_bytes
+        && input.len() > max
+    {
+        return Err(BackendError::resource_limit(format!(
+            "migration input size {} bytes exceeds limit of {max} bytes for program '{}'",
+            input.len(),
+            migration.program_id()
+        )));
+    }
+
+    // Compressed, so the public values come with the proof.
+    let format = ProofFormat::Compressed;
+    let proof = backend.prove_with_elf(elf, &input, format)?;
+    backend.verify(&proof)?;
+    let batch_proof = backend.to_batch_proof(proof, format)?;
+    let output =
+        MigrationOutput::decode(&batch_proof.public_values()).map_err(BackendError::batch_proof)?;
+    let vk = migration
+        .vk_bytes(backend_name)
+        .map(|vk| String::from_utf8_lossy(&vk).into_owned());
+    let proof = bincode::serialize(&batch_proof).map_err(BackendError::serialization)?;
     registry
+        .record_migration(program_id, &output, vk, hex::encode(proof))
+        .map_err(BackendError::verification)
 }
 
-pub async fn start_prover(config: ProverConfig) {
+/// Batches a prover proves at a time.
+const PROVING_CONCURRENCY: u64 = 1;
+
+/// Logs what the backend can do on this machine and refuses to start if it
+/// can't produce the pinned proof format or run the proofs it would run at a
+/// time. Failed hardware probes only warn, unless the operator enforces them.
+fn check_capabilities<B: ProverBackend>(
+    backend: &B,
+    config: &ProverConfig,
+) -> Result<(), BackendError> {
+    let capabilities = backend.capabilities();
+    info!("Prover backend capabilities: {capabilities}");
+    capabilities.check_format(config.proof_format)?;
+
+    if config.remote_proving() {
+        // The remote server brings its own hardware, this machine's says nothing
+        info!("Proving remotely, skipping the hardware checks");
+        return Ok(());
+    }
+
+    if capabilities.gpu_unused() {
+        warn!(
+            "A CUDA device was found but the {} backend was built without the `gpu` feature, proving on CPU",
+            capabilities.backend
+        );
+    }
+    for warning in capabilities.hardware_warnings() {
+        if config.enforce_capabilities {
+            return Err(BackendError::unsupported_environment(warning));
+        }
+        warn!("{warning}; starting anyway, use --enforce-capabilities to refuse instead");
+    }
+    capabilities.check_concurrency(PROVING_CONCURRENCY)
+}
+
+pub async fn start_prover(config: ProverConfig) -> Result<(), BackendError> {
     let registry = create_registry(config.programs_config_path.as_deref());
     match config.backend {
         BackendType::Exec => {
-            let prover = Prover::new(ExecBackend::new(), &config, registry);
+            let backend = ExecBackend::new();
+            check_capabilities(&backend, &config)?;
+            let prover = Prover::new(backend, &config, registry);
             prover.start().await;
         }
         #[cfg(feature = "sp1")]
         BackendType::SP1 => {
             use crate::backend::sp1::{PROVER_SETUP, Sp1Backend, init_prover_setup};
+            let backend = Sp1Backend::new();
+            check_capabilities(&backend, &config)?;
             #[cfg(feature = "gpu")]
             PROVER_SETUP.get_or_init(|| init_prover_setup(config.sp1_server.clone()));
             #[cfg(not(feature = "gpu"))]
             PROVER_SETUP.get_or_init(|| init_prover_setup(None));
-            let prover = Prover::new(Sp1Backend::new(), &config, registry);
+            let prover = Prover::new(backend, &config, registry);
             prover.start().await;
         }
         #[cfg(feature = "risc0")]
         BackendType::RISC0 => {
             use crate::backend::Risc0Backend;
-            let prover = Prover::new(Risc0Backend::new(), &config, registry);
+            let backend = Risc0Backend::new();
+            check_capabilities(&backend, &config)?;
+            let prover = Prover::new(backend, &config, registry);
             prover.start().await;
         }
         #[cfg(feature = "zisk")]
         BackendType::ZisK => {
             use crate::backend::ZiskBackend;
-            let prover = Prover::new(ZiskBackend::new(), &config, registry);
+            let backend = ZiskBackend::new();
+            check_capabilities(&backend, &config)?;
+            let prover = Prover::new(backend, &config, registry);
             prover.start().await;
         }
         #[cfg(feature = "openvm")]
         BackendType::OpenVM => {
             use crate::backend::OpenVmBackend;
-            let prover = Prover::new(OpenVmBackend::new(), &config, registry);
+            let backend = OpenVmBackend::new();
+            check_capabilities(&backend, &config)?;
+            let prover = Prover::new(backend, &config, registry);
             prover.start().await;
         }
     }
+    Ok(())
 }
 
 struct ProverData {
@@ -148,24 +300,109 @@ enum InputRequest {
     ProverTypeNotNeeded(ProverType),
 }
 
+/// Outcome of the capability negotiation with a proof coordinator.
+#[derive(Clone)]
+enum Handshake {
+    /// The coordinator negotiated capabilities and may assign these programs.
+    Negotiated { assignable_programs: Vec<String> },
+    /// The coordinator predates capability negotiation.
+    Legacy,
+}
+
 struct Prover<B: ProverBackend> {
     backend: B,
     registry: GuestProgramRegistry,
+    capabilities: ProverCapabilities,
     proof_coordinator_endpoints: Vec<Url>,
     proving_time_ms: u64,
     timed: bool,
     commit_hash: String,
+    prefetcher: Option<Arc<Prefetcher>>,
+    accounting: Option<Arc<Mutex<AccountingStore>>>,
+    status_addr: Option<SocketAddr>,
+    status_health_window: Duration,
+    progress: Arc<ProverProgress>,
+}
+
+/// Build the capabilities advertised to proof coordinators from the backend,
+/// the registered guest programs and the pinned proof format, if any.
+///
+/// Programs awaiting their state migration aren't advertised.
+fn build_capabilities<B: ProverBackend>(
+    backend: &B,
+    registry: &GuestProgramRegistry,
+    proof_format: Option<ProofFormat>,
+) -> ProverCapabilities {
+    let programs = registry
+        .program_ids()
+        .into_iter()
+        .filter(|id| !registry.awaits_migration(id))
+        .filter_map(|id| registry.get(id))
+        .map(|program| {
+            let backend_name = backend.backend_name();
+            let elf_hash = program.elf_hash(backend_name);
+            let build_info = program
+                .build_info(backend_name)
+                .inspect_err(|e| {
+                    warn!("Can't read build info of {} ELF: {e}", program.program_id())
+                })
+                .ok()
+                .flatten();
+            match &build_info {
+                Some(info) => info!(
+                    program_id = program.program_id(),
+                    elf_hash = ?elf_hash.map(hex::encode),
+                    "Guest program built from {info}"
+                ),
+                None => info!(
+                    program_id = program.program_id(),
+                    elf_hash = ?elf_hash.map(hex::encode),
+                    "Guest program has no embedded build info"
+                ),
+            }
+            ProgramCapability {
+                program_id: program.program_id().to_string(),
+                version: program.version().to_string(),
+                elf_hash,
+                build_commit: build_info.map(|info| info.git_commit),
+            }
+        })
+        .collect();
+    let proof_formats = match proof_format {
+        Some(format) => vec![format],
+        None => backend.supported_formats(),
+    };
+    ProverCapabilities::new(vec![backend.prover_type()], programs, proof_formats)
 }
 
 impl<B: ProverBackend> Prover<B> {
-    pub fn new(backend: B, cfg: &ProverConfig, registry: GuestProgramRegistry) -> Self {
+    pub fn new(backend: B, cfg: &ProverConfig, mut registry: GuestProgramRegistry) -> Self {
+        prove_migrations(&backend, &mut registry);
+        let capabilities = build_capabilities(&backend, &registry, cfg.proof_format);
         Self {
             backend,
             registry,
+            capabilities,
             proof_coordinator_endpoints: cfg.proof_coordinators.clone(),
             proving_time_ms: cfg.proving_time_ms,
             timed: cfg.timed,
             commit_hash: get_git_commit_hash(),
+            prefetcher: cfg.input_cache_dir.as_ref().map(|dir| {
+                Arc::new(Prefetcher::new(
+                    PathBuf::from(dir),
+                    cfg.prefetch_depth,
+                    cfg.input_cache_size_mb.saturating_mul(1024 * 1024),
+                ))
+            }),
+            accounting: cfg.accounting_dir.as_ref().and_then(|dir| {
+                AccountingStore::open(Path::new(dir))
+                    .inspect_err(|e| warn!("Proof accounting disabled, can't open {dir}: {e}"))
+                    .ok()
+                    .map(|store| Arc::new(Mutex::new(store)))
+            }),
+            status_addr: cfg.status_addr,
+            status_health_window: Duration::from_secs(cfg.status_health_window_secs),
+            progress: Arc::new(ProverProgress::new()),
         }
     }
 
@@ -177,14 +414,56 @@ impl<B: ProverBackend> Prover<B> {
                 .map(|url| url.to_string())
                 .collect::<Vec<String>>()
         );
+        if let Some(addr) = self.status_addr {
+            tokio::spawn(status::serve(addr, self.status()));
+        }
+        let mut handshakes: HashMap<Url, Handshake> = HashMap::new();
         loop {
             sleep(Duration::from_millis(self.proving_time_ms)).await;
 
             for endpoint in &self.proof_coordinator_endpoints {
-                let prover_data = match self.request_new_input(endpoint).await {
+                let handshake = match handshakes.get(endpoint) {
+                    Some(handshake) => handshake.clone(),
+                    None => match self
+                        .negotiate(endpoint)
+                        .await
+                        .inspect(|_| self.progress.coordinator_contacted())
+                    {
+                        Ok(Handshake::Negotiated {
+                            assignable_programs,
+                        }) if assignable_programs.is_empty() => {
+                            // Not cached, the coordinator may be reconfigured.
+                            warn!(
+                                %endpoint,
+                                "Proof coordinator has no work this prover can handle"
+                            );
+                            continue;
+                        }
+                        Ok(handshake) => {
+                            handshakes.insert(endpoint.clone(), handshake.clone());
+                            handshake
+                        }
+                        Err(e) => {
+                            error!(%endpoint, "Failed to negotiate capabilities: {e}");
+                            self.progress.record_error(ProverErrorKind::Negotiation);
+                            continue;
+                        }
+                    },
+                };
+
+                self.progress.fetching(endpoint);
+                let input_request = self.request_new_input(endpoint, &handshake).await;
+                if input_request.is_ok() {
+                    self.progress.coordinator_contacted();
+                }
+                let prover_data = match input_request {
                     Ok(InputRequest::Batch(data)) => *data,
-                    Ok(InputRequest::RetryLater) => continue,
+                    Ok(InputRequest::RetryLater) => {
+                        self.progress.idle();
+                        continue;
+                    }
                     Ok(InputRequest::ProverTypeNotNeeded(prover_type)) => {
+                        self.progress.idle();
                         error!(
                             %endpoint,
                             "Proof coordinator does not need {prover_type} proofs. \
@@ -195,19 +474,70 @@ impl<B: ProverBackend> Prover<B> {
                     }
                     Err(e) => {
                         error!(%endpoint, "Failed to request new data: {e}");
+                        self.progress.idle();
+                        self.progress.record_error(ProverErrorKind::InputRequest);
+                        // Renegotiate next time, the coordinator may have been upgraded.
+                        handshakes.remove(endpoint);
                         continue;
                     }
                 };
 
+                self.progress
+                    .assigned(prover_data.batch_number, &prover_data.program_id);
+
+                if let Err(e) = self
+                    .registry
+                    .check_batch(&prover_data.program_id, prover_data.batch_number)
+                {
+                    error!(%endpoint, "Refusing batch {}: {e}", prover_data.batch_number);
+                    self.progress.record_error(ProverErrorKind::Proving);
+                    self.progress.finish(BatchOutcome::ProvingFailed);
+                    continue;
+                }
+
+                // Fetch the next batches' inputs while this one is proven.
+                if let Some(prefetcher) = &self.prefetcher {
+                    prefetcher.spawn_prefetch(
+                        endpoint.clone(),
+                        self.commit_hash.clone(),
+                        prover_data.batch_number,
+                    );
+                }
+
+                let gas_used = prover_data
+                    .input
+                    .blocks
+                    .iter()
+                    .map(|block| block.header.gas_used)
+                    .sum();
+                let start = Instant::now();
                 let batch_proof = self.prove_batch(
                     prover_data.input,
                     prover_data.format,
                     prover_data.batch_number,
                     &prover_data.program_id,
                 );
+                let proving_time = start.elapsed();
                 let Ok(batch_proof) = batch_proof.inspect_err(|e| error!("{e}")) else {
+                    self.progress.record_error(ProverErrorKind::Proving);
+                    self.progress.finish(BatchOutcome::ProvingFailed);
                     continue;
                 };
+                let queue_depth = match &self.prefetcher {
+                    Some(prefetcher) => {
+                        prefetcher
+                            .queue_depth(endpoint, prover_data.batch_number)
+                            .await
+                    }
+                    None => 0,
+                };
+                let feedback = ProvingFeedback {
+                    gas_used,
+                    // No backend reports its cycle count yet.
+                    cycles: None,
+                    proving_time_ms: u64::try_from(proving_time.as_millis()).unwrap_or(u64::MAX),
+                    queue_depth,
+                };
 
                 // ── Fixture dump: save prover public_values for offline testing ──
                 // Extracts field-by-field values from public_values bytes and saves
@@ -274,31 +604,121 @@ impl<B: ProverBackend> Prover<B> {
                 }
                 // ── END Fixture dump ──
 
-                let _ = self
+                self.progress.set_phase(ProvingPhase::Submitting);
+                let submitted = self
                     .submit_proof(
                         endpoint,
                         prover_data.batch_number,
                         batch_proof,
                         &prover_data.program_id,
+                        feedback,
                     )
                     .await
                     .inspect_err(|e|
                     // TODO: Retry?
                     warn!(%endpoint, "Failed to submit proof: {e}"));
+                match submitted {
+                    Ok(()) => {
+                        self.progress.coordinator_contacted();
+                        self.progress.finish(BatchOutcome::Submitted);
+                    }
+                    Err(_) => {
+                        self.progress.record_error(ProverErrorKind::Submission);
+                        self.progress.finish(BatchOutcome::SubmissionFailed);
+                    }
+                }
+
+                if submitted.is_ok()
+                    && let Some(prefetcher) = &self.prefetcher
+                {
+                    prefetcher.proved(endpoint, prover_data.batch_number).await;
+                    prefetcher.metrics().report();
+                }
             }
         }
     }
 
+    fn status(&self) -> ProverStatus {
+        let mut programs: Vec<String> = self
+            .registry
+            .program_ids()
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        programs.sort();
+        ProverStatus {
+            backend: self.backend.backend_name(),
+            capabilities: self.backend.capabilities(),
+            programs,
+            accounting: self.accounting.clone(),
+            prefetcher: self.prefetcher.clone(),
+            progress: self.progress.clone(),
+            health_window: self.status_health_window,
+        }
+    }
+
+    /// Phase of running the guest program, the exec backend doesn't prove.
+    fn proving_phase(&self) -> ProvingPhase {
+        if self.backend.prover_type() == ProverType::Exec {
+            ProvingPhase::Executing
+        } else {
+            ProvingPhase::Proving
+        }
+    }
+
+    /// Prove a batch and record the proof in the accounting store.
+    fn prove_batch(
+        &self,
+        input: ProgramInput,
+        format: ProofFormat,
+        batch_number: u64,
+        program_id: &str,
+    ) -> Result<BatchProof, BackendError> {
+        let Some(accounting) = &self.accounting else {
+            return self
+                .prove_batch_input(input, format, batch_number, program_id)
+                .map(|(batch_proof, _)| batch_proof);
+        };
+
+        accounting::reset_peak_memory();
+        // Monotonic, so clock adjustments don't skew the proving time.
+        let start = Instant::now();
+        let (batch_proof, input_size) =
+            self.prove_batch_input(input, format, batch_number, program_id)?;
+        let wall_time = start.elapsed();
+
+        let mut store = accounting.lock().unwrap_or_else(PoisonError::into_inner);
+        let record = ProofRecord {
+            program_id: program_id.to_string(),
+            backend: self.backend.backend_name().to_string(),
+            batch_number,
+            input_size,
+            wall_time_ms: u64::try_from(wall_time.as_millis()).unwrap_or(u64::MAX),
+            peak_memory_bytes: accounting::peak_memory_bytes(),
+            // No backend reports its cycle count yet.
+            cycles: None,
+            rate: self.registry.rate(program_id),
+            timestamp: store.timestamp(),
+        };
+        if let Err(e) = store.record(record) {
+            warn!("Failed to record proof of batch {batch_number}: {e}");
+        }
+        Ok(batch_proof)
+    }
+
     /// Prove a batch, trying the registry-based ELF path first and falling
     /// back to the legacy `prove()` path when no ELF is available (e.g. exec
     /// backend, or ELF not compiled for this backend).
-    fn prove_batch(
+    ///
+    /// Also returns the size of the serialized guest input, when the ELF
+    /// path serialized it.
+    fn prove_batch_input(
         &self,
         input: ProgramInput,
         format: ProofFormat,
         batch_number: u64,
         program_id: &str,
-    ) -> Result<BatchProof, BackendError> {
+    ) -> Result<(BatchProof, Option<u64>), BackendError> {
         // Try to resolve an ELF binary from the registry for this program + backend.
         let elf_and_program = self.registry.get(program_id).and_then(|program| {
             program
@@ -308,10 +728,12 @@ impl<B: ProverBackend> Prover<B> {
 
         if let Some((program, elf)) = elf_and_program {
             // Registry-based path: serialize input to raw bytes, then prove_with_elf.
+            self.progress.set_phase(ProvingPhase::Serializing);
             let input_bytes = self.backend.serialize_raw(&input)?;
             let serialized = program
                 .serialize_input(input_bytes.as_slice())
                 .map_err(|e| BackendError::serialization(e.to_string()))?;
+            let input_size = u64::try_from(serialized.len()).ok();
 
             // ── Fixture dump: save serialized input for offline re-proving ──
             if let Ok(fixture_dir) = std::env::var("ETHREX_DUMP_FIXTURES") {
@@ -340,6 +762,7 @@ impl<B: ProverBackend> Prover<B> {
                 )));
             }
 
+            self.progress.set_phase(self.proving_phase());
             if self.timed {
                 let (output, elapsed) =
                     self.backend
@@ -358,7 +781,7 @@ impl<B: ProverBackend> Prover<B> {
                     proving_time_ms = u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
                     "Proved batch {batch_number} in {elapsed:.2?} (program: {program_id}, elf)"
                 );
-                self.backend.to_batch_proof(output, format)
+                Ok((self.backend.to_batch_proof(output, format)?, input_size))
             } else {
                 let start = std::time::Instant::now();
                 let output = self.backend.prove_with_elf(elf, &serialized, format)?;
@@ -375,7 +798,7 @@ impl<B: ProverBackend> Prover<B> {
                     batch = batch_number,
                     "Proved batch {batch_number} (program: {program_id}, elf)"
                 );
-                self.backend.to_batch_proof(output, format)
+                Ok((self.backend.to_batch_proof(output, format)?, input_size))
             }
         } else {
             // Legacy path: no ELF available, use prove() with ProgramInput directly.
@@ -394,6 +817,7 @@ impl<B: ProverBackend> Prover<B> {
                     }
                 }
             }
+            self.progress.set_phase(self.proving_phase());
             if self.timed {
                 let (output, elapsed) = self.backend.prove_timed(input, format)?;
                 info!(
@@ -402,41 +826,99 @@ impl<B: ProverBackend> Prover<B> {
                     proving_time_ms = u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
                     "Proved batch {batch_number} in {elapsed:.2?} (program: {program_id}, legacy)"
                 );
-                self.backend.to_batch_proof(output, format)
+                Ok((self.backend.to_batch_proof(output, format)?, None))
             } else {
                 let output = self.backend.prove(input, format)?;
                 info!(
                     batch = batch_number,
                     "Proved batch {batch_number} (program: {program_id}, legacy)"
                 );
-                self.backend.to_batch_proof(output, format)
+                Ok((self.backend.to_batch_proof(output, format)?, None))
             }
         }
     }
 
-    async fn request_new_input(&self, endpoint: &Url) -> Result<InputRequest, String> {
-        let supported = self
-            .registry
-            .program_ids()
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
-        let request = ProofData::batch_request_with_programs(
-            self.commit_hash.clone(),
-            self.backend.prover_type(),
-            supported,
-        );
+    /// Advertise this prover's capabilities to a proof coordinator.
+    async fn negotiate(&self, endpoint: &Url) -> Result<Handshake, String> {
+        let request = ProofData::capabilities(self.capabilities.clone());
+        let buffer = send_to_prover_server(endpoint, &request)
+            .await
+            .map_err(|e| format!("Failed to get CapabilitiesACK: {e}"))?;
+
+        // Legacy coordinators can't parse the message and close the
+        // connection without answering.
+        if buffer.is_empty() {
+            info!(
+                %endpoint,
+                "Proof coordinator doesn't support capability negotiation, using legacy protocol"
+            );
+            return Ok(Handshake::Legacy);
+        }
+
+        match ProofData::decode(&buffer).map_err(|e| e.to_string())? {
+            ProofData::CapabilitiesACK {
+                protocol_version,
+                assignable_programs,
+            } => {
+                info!(
+                    %endpoint,
+                    "Negotiated protocol v{protocol_version}, assignable programs: {assignable_programs:?}"
+                );
+                Ok(Handshake::Negotiated {
+                    assignable_programs,
+                })
+            }
+            ProofData::ProtocolError { error } => {
+                Err(format!("Proof coordinator rejected capabilities: {error}"))
+            }
+            _ => Err("Expecting ProofData::CapabilitiesACK".to_owned()),
+        }
+    }
+
+    async fn request_new_input(
+        &self,
+        endpoint: &Url,
+        handshake: &Handshake,
+    ) -> Result<InputRequest, String> {
+        let request = match handshake {
+            Handshake::Negotiated { .. } => ProofData::batch_request_with_capabilities(
+                self.commit_hash.clone(),
+                self.backend.prover_type(),
+                self.capabilities.clone(),
+            ),
+            Handshake::Legacy => {
+                let supported = self
+                    .registry
+                    .program_ids()
+                    .iter()
+                    .map(|s| s.to_string())
+                    .collect();
+                ProofData::batch_request_with_programs(
+                    self.commit_hash.clone(),
+                    self.backend.prover_type(),
+                    supported,
+                )
+            }
+        };
+        let request = match &self.prefetcher {
+            Some(prefetcher) => {
+                request.with_cached_inputs(prefetcher.cached_inputs(endpoint).await)
+            }
+            None => request,
+        };
         let response = connect_to_prover_server_wr(endpoint, &request)
             .await
             .map_err(|e| format!("Failed to get Response: {e}"))?;
 
-        let (batch_number, input, format, program_id) = match response {
+        let (batch_number, input, format, program_id, input_hash) = match response {
             ProofData::BatchResponse {
                 batch_number,
                 input,
                 format,
                 program_id,
-            } => (batch_number, input, format, program_id),
+                input_hash,
+                ..
+            } => (batch_number, input, format, program_id, input_hash),
             ProofData::VersionMismatch => {
                 warn!(
                     "Version mismatch: the next batch to prove was built with a different code \
@@ -447,16 +929,26 @@ impl<B: ProverBackend> Prover<B> {
             ProofData::ProverTypeNotNeeded { prover_type } => {
                 return Ok(InputRequest::ProverTypeNotNeeded(prover_type));
             }
+            ProofData::ProtocolError { error } => {
+                return Err(format!("Proof coordinator rejected request: {error}"));
+            }
             _ => return Err("Expecting ProofData::Response".to_owned()),
         };
 
-        let (Some(batch_number), Some(input), Some(format)) = (batch_number, input, format) else {
+        let (Some(batch_number), Some(format)) = (batch_number, format) else {
             debug!(
                 %endpoint,
                 "No batches to prove right now, the prover may be ahead of the proposer"
             );
             return Ok(InputRequest::RetryLater);
         };
+        let Some(input) = self
+            .resolve_input(endpoint, batch_number, input, input_hash)
+            .await?
+        else {
+            debug!(%endpoint, "No input available for batch {batch_number} right now");
+            return Ok(InputRequest::RetryLater);
+        };
 
         // Default to "evm-l2" when the coordinator doesn't specify a program.
         let program_id = program_id.unwrap_or_else(|| "evm-l2".to_string());
@@ -471,12 +963,12 @@ impl<B: ProverBackend> Prover<B> {
             blob_proof: input.blob_proof,
             fee_configs: input.fee_configs,
             native_token_scale_factor: input.native_token_scale_factor,
+            ordering_commitment: input.ordering_commitment,
+            publish_block_summaries: input.publish_block_summaries,
+            commit_deposit_inclusion: input.commit_deposit_inclusion,
         };
         #[cfg(not(feature = "l2"))]
-        let input = ProgramInput {
-            blocks: input.blocks,
-            execution_witness: input.execution_witness,
-        };
+        let input = ProgramInput::new(input.blocks, input.execution_witness);
         Ok(InputRequest::Batch(Box::new(ProverData {
             batch_number,
             input,
@@ -485,15 +977,64 @@ impl<B: ProverBackend> Prover<B> {
         })))
     }
 
+    /// Returns the input of an assigned batch, taking it from the input cache
+    /// when the coordinator left it out because the prover already holds it.
+    async fn resolve_input(
+        &self,
+        endpoint: &Url,
+        batch_number: u64,
+        input: Option<ProverInputData>,
+        input_hash: Option<H256>,
+    ) -> Result<Option<ProverInputData>, String> {
+        let (Some(prefetcher), Some(input_hash)) = (&self.prefetcher, input_hash) else {
+            return Ok(input);
+        };
+        if let Some(input) = input {
+            prefetcher
+                .store_assigned(endpoint, batch_number, input_hash, &input)
+                .await;
+            return Ok(Some(input));
+        }
+        if let Some(input) = prefetcher.take(endpoint, batch_number, input_hash).await {
+            return Ok(Some(input));
+        }
+
+        // The cached input is gone (evicted or corrupted), download it again.
+        warn!(%endpoint, "Cached input of batch {batch_number} is unavailable, downloading it");
+        let request = ProofData::input_request(self.commit_hash.clone(), batch_number, None);
+        match connect_to_prover_server_wr(endpoint, &request)
+            .await
+            .map_err(|e| format!("Failed to get InputResponse: {e}"))?
+        {
+            ProofData::InputResponse {
+                input: Some(input),
+                input_hash: Some(input_hash),
+                ..
+            } => {
+                prefetcher
+                    .store_assigned(endpoint, batch_number, input_hash, &input)
+                    .await;
+                Ok(Some(input))
+            }
+            ProofData::InputResponse { .. } => Ok(None),
+            _ => Err("Expecting ProofData::InputResponse".to_owned()),
+        }
+    }
+
     async fn submit_proof(
         &self,
         endpoint: &Url,
         batch_number: u64,
         batch_proof: BatchProof,
         program_id: &str,
+        feedback: ProvingFeedback,
     ) -> Result<(), String> {
-        let submit =
-            ProofData::proof_submit_with_program(batch_number, batch_proof, program_id.to_string());
+        let submit = ProofData::proof_submit_with_feedback(
+            batch_number,
+            batch_proof,
+            program_id.to_string(),
+            feedback,
+        );
 
         let ProofData::ProofSubmitACK { batch_number } =
             connect_to_prover_server_wr(endpoint, &submit)
@@ -508,10 +1049,19 @@ impl<B: ProverBackend> Prover<B> {
     }
 }
 
-async fn connect_to_prover_server_wr(
+pub(crate) async fn connect_to_prover_server_wr(
     endpoint: &Url,
     write: &ProofData,
 ) -> Result<ProofData, Box<dyn std::error::Error>> {
+    let buffer = send_to_prover_server(endpoint, write).await?;
+    Ok(ProofData::decode(&buffer)?)
+}
+
+/// Send a message to the proof coordinator and return the raw response bytes.
+async fn send_to_prover_server(
+    endpoint: &Url,
+    write: &ProofData,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
     debug!("Connecting with {endpoint}");
     let mut stream = TcpStream::connect(&*endpoint.socket_addrs(|| None)?).await?;
     debug!("Connection established!");
@@ -521,7 +1071,5 @@ async fn connect_to_prover_server_wr(
 
     let mut buffer = Vec::new();
     stream.read_to_end(&mut buffer).await?;
-
-    let response: Result<ProofData, _> = serde_json::from_slice(&buffer);
-    Ok(response?)
+    Ok(buffer)
 }
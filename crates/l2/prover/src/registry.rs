@@ -1,17 +1,82 @@
 use std::collections::HashMap;
 use std::sync::Arc;
 
+use ethrex_guest_program::common::migration::{MigrationOutput, program_id_hash};
 use ethrex_guest_program::traits::GuestProgram;
 
+use crate::accounting::CostRate;
+use crate::migrations::{MigrationConfig, MigrationRecord, MigrationStore, MigrationStoreError};
+
+#[derive(Debug, thiserror::Error)]
+pub enum MigrationGateError {
+    #[error("{0} doesn't take over from another program")]
+    NoMigration(String),
+    #[error("No migration boundary is configured for {0}")]
+    NotConfigured(String),
+    #[error("No migration store is configured, can't record the migration of {0}")]
+    NoStore(String),
+    #[error("The migration of {0} hasn't been proven yet")]
+    NotMigrated(String),
+    #[error("Batch {batch} of {program_id} is at or before its migration boundary {boundary}")]
+    BeforeBoundary {
+        program_id: String,
+        batch: u64,
+        boundary: u64,
+    },
+    #[error(
+        "Batch {batch} of {program_id} is after its migration boundary {boundary}, {successor} proves it"
+    )]
+    AfterBoundary {
+        program_id: String,
+        successor: String,
+        batch: u64,
+        boundary: u64,
+    },
+    #[error("Migration proof of {program_id} doesn't read {expected_id} v{expected_version}")]
+    WrongPredecessor {
+        program_id: String,
+        expected_id: String,
+        expected_version: u8,
+    },
+    #[error("Migration proof of {program_id} produces state v{got}, expected v{expected}")]
+    WrongVersion {
+        program_id: String,
+        expected: u8,
+        got: u8,
+    },
+    #[error("Migration proof of {program_id} is at batch {got}, the boundary is {expected}")]
+    WrongBoundary {
+        program_id: String,
+        expected: u64,
+        got: u64,
+    },
+    #[error(transparent)]
+    Store(#[from] MigrationStoreError),
+}
+
 /// Registry mapping `program_id` → [`GuestProgram`] implementations.
 ///
-/// The registry is created once at prover startup and is immutable during
-/// the prover's lifetime.  Each registered [`GuestProgram`] provides ELF
+/// The registry is created once at prover startup and is immutable once the
+/// prover starts polling for batches.  Each registered [`GuestProgram`] provides ELF
 /// binaries and serialization logic for a specific guest program type
 /// (e.g. `"evm-l2"`, `"transfer"`).
+///
+/// The registry also holds the cost rates used to account for the proofs of
+/// each program: a default rate, optionally overridden per program.
+///
+/// Programs taking over from a predecessor through a state migration (see
+/// [`GuestProgram::migration_from`]) are gated on it: [`check_batch`]
+/// refuses their batches until the migration proof is recorded, and refuses
+/// the predecessor's batches after the migration boundary.
+///
+/// [`check_batch`]: GuestProgramRegistry::check_batch
 pub struct GuestProgramRegistry {
     programs: HashMap<String, Arc<dyn GuestProgram>>,
     default_program_id: String,
+    default_rate: Option<CostRate>,
+    rate_overrides: HashMap<String, CostRate>,
+    migrations: HashMap<String, MigrationConfig>,
+    migration_store: Option<MigrationStore>,
 }
 
 impl GuestProgramRegistry {
@@ -23,6 +88,10 @@ impl GuestProgramRegistry {
         Self {
             programs: HashMap::new(),
             default_program_id: default_program_id.to_string(),
+            default_rate: None,
+            rate_overrides: HashMap::new(),
+            migrations: HashMap::new(),
+            migration_store: None,
         }
     }
 
@@ -53,6 +122,178 @@ impl GuestProgramRegistry {
     pub fn program_ids(&self) -> Vec<&str> {
         self.programs.keys().map(|s| s.as_str()).collect()
     }
+
+    /// Set the cost rate of programs without a rate override.
+    pub fn set_default_rate(&mut self, rate: Option<CostRate>) {
+        self.default_rate = rate;
+    }
+
+    /// Price the proofs of `program_id` with `rate` instead of the default.
+    pub fn set_rate_override(&mut self, program_id: &str, rate: CostRate) {
+        self.rate_overrides.insert(program_id.to_string(), rate);
+    }
+
+    /// Cost rate of `program_id`, if one is configured.
+    pub fn rate(&self, program_id: &str) -> Option<CostRate> {
+        self.rate_overrides
+            .get(program_id)
+            .copied()
+            .or(self.default_rate)
+    }
+
+    /// Configure the migration boundary of `program_id`.
+    pub fn set_migration(&mut self, program_id: &str, config: MigrationConfig) {
+        self.migrations.insert(program_id.to_string(), config);
+    }
+
+    /// Persist and load proven migrations in `store`.
+    pub fn set_migration_store(&mut self, store: MigrationStore) {
+        self.migration_store = Some(store);
+    }
+
+    /// Configured migration boundary of `program_id`.
+    pub fn migration(&self, program_id: &str) -> Option<&MigrationConfig> {
+        self.migrations.get(program_id)
+    }
+
+    /// Recorded migration of `program_id`.
+    pub fn migration_record(&self, program_id: &str) -> Option<&MigrationRecord> {
+        self.migration_store
+            .as_ref()
+            .and_then(|store| store.get(program_id))
+    }
+
+    /// Whether `program_id` takes over from a predecessor and its migration
+    /// isn't recorded yet.
+    pub fn awaits_migration(&self, program_id: &str) -> bool {
+        self.get(program_id)
+            .is_some_and(|program| program.migration_from().is_some())
+            && self.migration_record(program_id).is_none()
+    }
+
+    /// Ids of the registered programs awaiting their migration, sorted.
+    pub fn pending_migrations(&self) -> Vec<&str> {
+        let mut ids: Vec<&str> = self
+            .program_ids()
+            .into_iter()
+            .filter(|id| self.awaits_migration(id))
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// Record the proven migration of `program_id` after checking its output
+    /// reads the predecessor's final state at the configured boundary and
+    /// produces the program's state version.
+    pub fn record_migration(
+        &mut self,
+        program_id: &str,
+        output: &MigrationOutput,
+        vk: Option<String>,
+        proof: String,
+    ) -> Result<(), MigrationGateError> {
+        let program = self
+            .get(program_id)
+            .ok_or_else(|| MigrationGateError::NoMigration(program_id.to_string()))?;
+        let (from_id, from_version) = program
+            .migration_from()
+            .ok_or_else(|| MigrationGateError::NoMigration(program_id.to_string()))?;
+        if output.from_program_id != program_id_hash(from_id) || output.from_version != from_version
+        {
+            return Err(MigrationGateError::WrongPredecessor {
+                program_id: program_id.to_string(),
+                expected_id: from_id.to_string(),
+                expected_version: from_version,
+            });
+        }
+        if output.to_version != program.state_version() {
+            return Err(MigrationGateError::WrongVersion {
+                program_id: program_id.to_string(),
+                expected: program.state_version(),
+                got: output.to_version,
+            });
+        }
+        let boundary = self
+            .migration(program_id)
+            .ok_or_else(|| MigrationGateError::NotConfigured(program_id.to_string()))?
+            .boundary_batch;
+        if output.boundary_batch != boundary {
+            return Err(MigrationGateError::WrongBoundary {
+                program_id: program_id.to_string(),
+                expected: boundary,
+                got: output.boundary_batch,
+            });
+        }
+
+        let record = MigrationRecord {
+            program_id: program_id.to_string(),
+            from_program_id: from_id.to_string(),
+            from_version,
+            to_version: output.to_version,
+            boundary_batch: output.boundary_batch,
+            old_state_root: output.old_state_root,
+            new_state_root: output.new_state_root,
+            vk,
+            proof,
+        };
+        let store = self
+            .migration_store
+            .as_mut()
+            .ok_or_else(|| MigrationGateError::NoStore(program_id.to_string()))?;
+        store.record(record)?;
+        Ok(())
+    }
+
+    /// Check `program_id` may prove `batch_number`.
+    ///
+    /// A program taking over from a predecessor proves the batches after
+    /// the boundary of its recorded migration. Its predecessor proves the
+    /// batches up to the configured boundary.
+    pub fn check_batch(
+        &self,
+        program_id: &str,
+        batch_number: u64,
+    ) -> Result<(), MigrationGateError> {
+        if let Some(program) = self.get(program_id)
+            && program.migration_from().is_some()
+        {
+            if self.migration(program_id).is_none() {
+                return Err(MigrationGateError::NotConfigured(program_id.to_string()));
+            }
+            let record = self
+                .migration_record(program_id)
+                .ok_or_else(|| MigrationGateError::NotMigrated(program_id.to_string()))?;
+            if batch_number <= record.boundary_batch {
+                return Err(MigrationGateError::BeforeBoundary {
+                    program_id: program_id.to_string(),
+                    batch: batch_number,
+                    boundary: record.boundary_batch,
+                });
+            }
+            return Ok(());
+        }
+
+        let state_version = self.get(program_id).map(|program| program.state_version());
+        for (successor_id, successor) in &self.programs {
+            let Some(config) = self.migrations.get(successor_id) else {
+                continue;
+            };
+            let takes_over = successor
+                .migration_from()
+                .is_some_and(|(from_id, from_version)| {
+                    from_id == program_id && Some(from_version) == state_version
+                });
+            if takes_over && batch_number > config.boundary_batch {
+                return Err(MigrationGateError::AfterBoundary {
+                    program_id: program_id.to_string(),
+                    successor: successor_id.clone(),
+                    batch: batch_number,
+                    boundary: config.boundary_batch,
+                });
+            }
+        }
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -137,6 +378,20 @@ mod tests {
         assert_eq!(reg.program_ids().len(), 1);
     }
 
+    #[test]
+    fn rate_overrides() {
+        let mut reg = GuestProgramRegistry::new("a");
+        reg.register(Arc::new(StubProgram { id: "a" }));
+        reg.register(Arc::new(StubProgram { id: "b" }));
+        assert_eq!(reg.rate("a"), None);
+
+        reg.set_default_rate(Some(CostRate::PerSecond(0.01)));
+        reg.set_rate_override("b", CostRate::PerCycle(1e-9));
+        assert_eq!(reg.rate("a"), Some(CostRate::PerSecond(0.01)));
+        assert_eq!(reg.rate("b"), Some(CostRate::PerCycle(1e-9)));
+        assert_eq!(reg.rate("unknown"), Some(CostRate::PerSecond(0.01)));
+    }
+
     // ── Integration tests with real guest program implementations ────
 
     use ethrex_guest_program::programs::{
@@ -212,6 +467,7 @@ mod tests {
         // Verify DexCircuit implements AppCircuit correctly.
         let circuit = DexCircuit {
             contract_address: ethrex_common::H160([0xDE; 20]),
+            note_format: None,
         };
 
         // Verify gas cost for token transfer operation.
@@ -294,4 +550,173 @@ mod tests {
         assert_eq!(restored.actions.len(), 1);
         assert_eq!(restored.actions[0].target_id, 99);
     }
+
+    // ── Migration gate ───────────────────────────────────────────────
+
+    use ethrex_common::H256;
+    use ethrex_guest_program::programs::ZkDexV2GuestProgram;
+
+    const BOUNDARY: u64 = 120;
+
+    fn migrating_registry(store_dir: Option<&std::path::Path>) -> GuestProgramRegistry {
+        let mut reg = GuestProgramRegistry::new("zk-dex");
+        reg.register(Arc::new(ZkDexGuestProgram));
+        reg.register(Arc::new(ZkDexV2GuestProgram));
+        reg.set_migration(
+            "zk-dex-v2",
+            MigrationConfig {
+                boundary_batch: BOUNDARY,
+                input: None,
+            },
+        );
+        if let Some(dir) = store_dir {
+            reg.set_migration_store(MigrationStore::open(dir).unwrap());
+        }
+        reg
+    }
+
+    fn migration_output(boundary_batch: u64) -> MigrationOutput {
+        MigrationOutput {
+            from_program_id: program_id_hash("zk-dex"),
+            from_version: 1,
+            to_version: 2,
+            boundary_batch,
+            old_state_root: H256([0xAA; 32]),
+            new_state_root: H256([0xBB; 32]),
+        }
+    }
+
+    fn record(
+        reg: &mut GuestProgramRegistry,
+        output: &MigrationOutput,
+    ) -> Result<(), MigrationGateError> {
+        reg.record_migration("zk-dex-v2", output, None, "00".to_string())
+    }
+
+    #[test]
+    fn refuses_successor_before_migration() {
+        let dir = tempfile::tempdir().expect("tmpdir");
+        let reg = migrating_registry(Some(dir.path()));
+
+        assert!(reg.awaits_migration("zk-dex-v2"));
+        assert_eq!(reg.pending_migrations(), vec!["zk-dex-v2"]);
+        let err = reg.check_batch("zk-dex-v2", BOUNDARY + 1).unwrap_err();
+        assert!(matches!(err, MigrationGateError::NotMigrated(_)), "{err}");
+        // The predecessor still proves up to the boundary.
+        reg.check_batch("zk-dex", BOUNDARY).unwrap();
+    }
+
+    #[test]
+    fn refuses_migration_at_wrong_boundary() {
+        let dir = tempfile::tempdir().expect("tmpdir");
+        let mut reg = migrating_registry(Some(dir.path()));
+
+        for boundary in [BOUNDARY - 1, BOUNDARY + 1] {
+            let err = record(&mut reg, &migration_output(boundary)).unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    MigrationGateError::WrongBoundary { expected: BOUNDARY, got, .. } if got == boundary
+                ),
+                "{err}"
+            );
+        }
+        assert!(reg.awaits_migration("zk-dex-v2"));
+        assert!(reg.check_batch("zk-dex-v2", BOUNDARY + 1).is_err());
+    }
+
+    #[test]
+    fn refuses_migration_of_wrong_predecessor() {
+        let dir = tempfile::tempdir().expect("tmpdir");
+        let mut reg = migrating_registry(Some(dir.path()));
+
+        let output = MigrationOutput {
+            from_program_id: program_id_hash("tokamon"),
+            ..migration_output(BOUNDARY)
+        };
+        let err = record(&mut reg, &output).unwrap_err();
+        assert!(
+            matches!(err, MigrationGateError::WrongPredecessor { .. }),
+            "{err}"
+        );
+
+        let output = MigrationOutput {
+            to_version: 3,
+            ..migration_output(BOUNDARY)
+        };
+        let err = record(&mut reg, &output).unwrap_err();
+        assert!(
+            matches!(err, MigrationGateError::WrongVersion { .. }),
+            "{err}"
+        );
+
+        let err = reg
+            .record_migration(
+                "zk-dex",
+                &migration_output(BOUNDARY),
+                None,
+                "00".to_string(),
+            )
+            .unwrap_err();
+        assert!(matches!(err, MigrationGateError::NoMigration(_)), "{err}");
+        assert!(reg.awaits_migration("zk-dex-v2"));
+    }
+
+    #[test]
+    fn accepts_migration_at_boundary_and_persists_it() {
+        let dir = tempfile::tempdir().expect("tmpdir");
+        let mut reg = migrating_registry(Some(dir.path()));
+        record(&mut reg, &migration_output(BOUNDARY)).unwrap();
+
+        // Reopened as after a restart, without proving again.
+        let reg = migrating_registry(Some(dir.path()));
+        assert!(!reg.awaits_migration("zk-dex-v2"));
+        assert!(reg.pending_migrations().is_empty());
+        let migration = reg.migration_record("zk-dex-v2").expect("recorded");
+        assert_eq!(migration.new_state_root, H256([0xBB; 32]));
+
+        reg.check_batch("zk-dex-v2", BOUNDARY + 1).unwrap();
+        let err = reg.check_batch("zk-dex-v2", BOUNDARY).unwrap_err();
+        assert!(
+            matches!(err, MigrationGateError::BeforeBoundary { .. }),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn refuses_second_migration() {
+        let dir = tempfile::tempdir().expect("tmpdir");
+        let mut reg = migrating_registry(Some(dir.path()));
+        record(&mut reg, &migration_output(BOUNDARY)).unwrap();
+        let err = record(&mut reg, &migration_output(BOUNDARY)).unwrap_err();
+        assert!(matches!(err, MigrationGateError::Store(_)), "{err}");
+    }
+
+    #[test]
+    fn refuses_predecessor_after_boundary() {
+        let reg = migrating_registry(None);
+        reg.check_batch("zk-dex", BOUNDARY).unwrap();
+        let err = reg.check_batch("zk-dex", BOUNDARY + 1).unwrap_err();
+        assert!(
+            matches!(err, MigrationGateError::AfterBoundary { .. }),
+            "{err}"
+        );
+        // Programs outside the migration aren't gated.
+        let reg = test_registry();
+        reg.check_batch("zk-dex", BOUNDARY + 1).unwrap();
+        reg.check_batch("tokamon", BOUNDARY + 1).unwrap();
+    }
+
+    #[test]
+    fn refuses_migration_without_store_or_boundary() {
+        let mut reg = migrating_registry(None);
+        let err = record(&mut reg, &migration_output(BOUNDARY)).unwrap_err();
+        assert!(matches!(err, MigrationGateError::NoStore(_)), "{err}");
+        assert!(reg.check_batch("zk-dex-v2", BOUNDARY + 1).is_err());
+
+        let mut reg = GuestProgramRegistry::new("zk-dex-v2");
+        reg.register(Arc::new(ZkDexV2GuestProgram));
+        let err = reg.check_batch("zk-dex-v2", BOUNDARY + 1).unwrap_err();
+        assert!(matches!(err, MigrationGateError::NotConfigured(_)), "{err}");
+    }
 }
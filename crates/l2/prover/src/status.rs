@@ -0,0 +1,559 @@
+use std::collections::VecDeque;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use axum::{Json, Router, extract::State, http::StatusCode, routing::get};
+use serde::Serialize;
+use tokio::net::TcpListener;
+use tracing::{error, info};
+use url::Url;
+
+use crate::accounting::{AccountingStore, DailyStats};
+use crate::backend::BackendCapabilities;
+use crate::prefetch::Prefetcher;
+
+/// Completed batches listed by `GET /status`.
+pub const RECENT_BATCHES: usize = 20;
+
+/// What the prover exposes at `GET /status` and `GET /healthz`.
+#[derive(Clone)]
+pub struct ProverStatus {
+    pub backend: &'static str,
+    pub capabilities: BackendCapabilities,
+    pub programs: Vec<String>,
+    pub accounting: Option<Arc<Mutex<AccountingStore>>>,
+    pub prefetcher: Option<Arc<Prefetcher>>,
+    pub progress: Arc<ProverProgress>,
+    /// While idle or talking to a proof coordinator, the prover is healthy if
+    /// a coordinator answered this recently.
+    pub health_window: Duration,
+}
+
+/// Step of the prover loop the current batch is in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProvingPhase {
+    /// Requesting a batch and its input from the proof coordinator.
+    Fetching,
+    /// Serializing the input for the guest program.
+    Serializing,
+    /// Running the guest program without proving, with the exec backend.
+    Executing,
+    Proving,
+    /// Sending the proof to the proof coordinator.
+    Submitting,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchOutcome {
+    Submitted,
+    ProvingFailed,
+    SubmissionFailed,
+}
+
+/// Errors of the prover loop, counted since the prover started.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProverErrorKind {
+    Negotiation,
+    InputRequest,
+    Proving,
+    Submission,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ErrorCounters {
+    pub negotiation: u64,
+    pub input_request: u64,
+    pub proving: u64,
+    pub submission: u64,
+}
+
+/// Progress of the prover loop, updated by the loop and read by the status
+/// endpoint.
+pub struct ProverProgress {
+    started: Instant,
+    state: Mutex<ProgressState>,
+}
+
+#[derive(Default)]
+struct ProgressState {
+    current: Option<CurrentBatch>,
+    recent: VecDeque<CompletedBatch>,
+    errors: ErrorCounters,
+    last_coordinator_contact: Option<Instant>,
+}
+
+struct CurrentBatch {
+    endpoint: Url,
+    /// `None` until the proof coordinator assigns a batch.
+    batch_number: Option<u64>,
+    program_id: Option<String>,
+    phase: ProvingPhase,
+    started: Instant,
+    phase_started: Instant,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CompletedBatch {
+    pub endpoint: String,
+    pub batch_number: u64,
+    pub program_id: String,
+    pub duration_ms: u64,
+    pub outcome: BatchOutcome,
+}
+
+impl Default for ProverProgress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProverProgress {
+    pub fn new() -> Self {
+        Self {
+            started: Instant::now(),
+            state: Mutex::new(ProgressState::default()),
+        }
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, ProgressState> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// A proof coordinator answered a request.
+    pub fn coordinator_contacted(&self) {
+        self.state().last_coordinator_contact = Some(Instant::now());
+    }
+
+    /// Starts asking `endpoint` for a batch to prove.
+    pub fn fetching(&self, endpoint: &Url) {
+        let now = Instant::now();
+        self.state().current = Some(CurrentBatch {
+            endpoint: endpoint.clone(),
+            batch_number: None,
+            program_id: None,
+            phase: ProvingPhase::Fetching,
+            started: now,
+            phase_started: now,
+        });
+    }
+
+    /// The proof coordinator assigned `batch_number` to this prover.
+    pub fn assigned(&self, batch_number: u64, program_id: &str) {
+        if let Some(current) = &mut self.state().current {
+            current.batch_number = Some(batch_number);
+            current.program_id = Some(program_id.to_string());
+        }
+    }
+
+    pub fn set_phase(&self, phase: ProvingPhase) {
+        if let Some(current) = &mut self.state().current
+            && current.phase != phase
+        {
+            current.phase = phase;
+            current.phase_started = Instant::now();
+        }
+    }
+
+    /// The current batch is done, it's listed among the recent batches.
+    pub fn finish(&self, outcome: BatchOutcome) {
+        let mut state = self.state();
+        let Some(current) = state.current.take() else {
+            return;
+        };
+        let (Some(batch_number), Some(program_id)) = (current.batch_number, current.program_id)
+        else {
+            return;
+        };
+        if state.recent.len() >= RECENT_BATCHES {
+            state.recent.pop_front();
+        }
+        state.recent.push_back(CompletedBatch {
+            endpoint: current.endpoint.to_string(),
+            batch_number,
+            program_id,
+            duration_ms: millis(current.started.elapsed()),
+            outcome,
+        });
+    }
+
+    /// No batch is being worked on, e.g. the coordinator had nothing to prove.
+    pub fn idle(&self) {
+        self.state().current = None;
+    }
+
+    pub fn record_error(&self, kind: ProverErrorKind) {
+        let mut state = self.state();
+        let counter = match kind {
+            ProverErrorKind::Negotiation => &mut state.errors.negotiation,
+            ProverErrorKind::InputRequest => &mut state.errors.input_request,
+            ProverErrorKind::Proving => &mut state.errors.proving,
+            ProverErrorKind::Submission => &mut state.errors.submission,
+        };
+        *counter = counter.saturating_add(1);
+    }
+
+    /// The proof coordinator and number of the batch being worked on.
+    pub fn current_batch(&self) -> Option<(Url, u64)> {
+        let state = self.state();
+        let current = state.current.as_ref()?;
+        Some((current.endpoint.clone(), current.batch_number?))
+    }
+
+    /// Whether the prover is working on a batch locally, or a proof
+    /// coordinator answered within `window`.
+    ///
+    /// Proving a batch can take longer than any sensible window without
+    /// talking to a coordinator, so staleness is only measured while idle,
+    /// fetching or submitting.
+    pub fn is_healthy(&self, window: Duration) -> bool {
+        let state = self.state();
+        let working = state.current.as_ref().is_some_and(|current| {
+            matches!(
+                current.phase,
+                ProvingPhase::Serializing | ProvingPhase::Executing | ProvingPhase::Proving
+            )
+        });
+        working
+            || state
+                .last_coordinator_contact
+                .is_some_and(|contact| contact.elapsed() <= window)
+    }
+
+    fn snapshot(&self) -> ProgressSnapshot {
+        let state = self.state();
+        ProgressSnapshot {
+            uptime_secs: self.started.elapsed().as_secs(),
+            current: state.current.as_ref().map(|current| CurrentBatchStatus {
+                endpoint: current.endpoint.to_string(),
+                batch_number: current.batch_number,
+                program_id: current.program_id.clone(),
+                phase: current.phase,
+                elapsed_ms: millis(current.started.elapsed()),
+                phase_elapsed_ms: millis(current.phase_started.elapsed()),
+            }),
+            recent_batches: state.recent.iter().rev().cloned().collect(),
+            errors: state.errors.clone(),
+            last_coordinator_contact_secs_ago: state
+                .last_coordinator_contact
+                .map(|contact| contact.elapsed().as_secs()),
+        }
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+struct ProgressSnapshot {
+    uptime_secs: u64,
+    current: Option<CurrentBatchStatus>,
+    recent_batches: Vec<CompletedBatch>,
+    errors: ErrorCounters,
+    last_coordinator_contact_secs_ago: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct CurrentBatchStatus {
+    endpoint: String,
+    batch_number: Option<u64>,
+    program_id: Option<String>,
+    phase: ProvingPhase,
+    elapsed_ms: u64,
+    phase_elapsed_ms: u64,
+}
+
+#[derive(Serialize)]
+struct PrefetchStatus {
+    /// Batches after the current one whose inputs are cached.
+    queue_depth: Option<u64>,
+    hits: u64,
+    misses: u64,
+    hit_rate_percent: u64,
+    bytes_saved: u64,
+    invalidations: u64,
+}
+
+#[derive(Serialize)]
+struct StatusResponse {
+    uptime_secs: u64,
+    backend: &'static str,
+    capabilities: BackendCapabilities,
+    programs: Vec<String>,
+    /// The batch being worked on, `None` while waiting for work.
+    current: Option<CurrentBatchStatus>,
+    /// Most recent first.
+    recent_batches: Vec<CompletedBatch>,
+    /// `None` when prefetching is disabled.
+    prefetch: Option<PrefetchStatus>,
+    errors: ErrorCounters,
+    last_coordinator_contact_secs_ago: Option<u64>,
+    /// Proof statistics per program per day, `None` when accounting is
+    /// disabled.
+    proofs: Option<Vec<DailyStats>>,
+}
+
+/// Routes of the status endpoint.
+pub fn router(status: ProverStatus) -> Router {
+    let router = Router::new()
+        .route("/status", get(get_status))
+        .route("/healthz", get(get_health));
+    #[cfg(feature = "metrics")]
+    let router = router.route("/metrics", get(get_metrics));
+    router.with_state(status)
+}
+
+/// Serves the prover status on `addr` until the process exits.
+pub async fn serve(addr: SocketAddr, status: ProverStatus) {
+    let listener = match TcpListener::bind(addr).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            error!("Failed to bind prover status endpoint to {addr}: {e}");
+            return;
+        }
+    };
+    info!("Prover status available at http://{addr}/status");
+    if let Err(e) = axum::serve(listener, router(status)).await {
+        error!("Prover status endpoint stopped: {e}");
+    }
+}
+
+async fn get_status(State(status): State<ProverStatus>) -> Json<StatusResponse> {
+    let proofs = status.accounting.as_ref().map(|store| {
+        store
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .daily_stats()
+    });
+    let progress = status.progress.snapshot();
+    let prefetch = match &status.prefetcher {
+        Some(prefetcher) => {
+            let queue_depth = match status.progress.current_batch() {
+                Some((endpoint, batch_number)) => {
+                    Some(prefetcher.queue_depth(&endpoint, batch_number).await)
+                }
+                None => None,
+            };
+            let metrics = prefetcher.metrics();
+            Some(PrefetchStatus {
+                queue_depth,
+                hits: metrics.hits(),
+                misses: metrics.misses(),
+                hit_rate_percent: metrics.hit_rate_percent(),
+                bytes_saved: metrics.bytes_saved(),
+                invalidations: metrics.invalidations(),
+            })
+        }
+        None => None,
+    };
+    Json(StatusResponse {
+        uptime_secs: progress.uptime_secs,
+        backend: status.backend,
+        capabilities: status.capabilities,
+        programs: status.programs,
+        current: progress.current,
+        recent_batches: progress.recent_batches,
+        prefetch,
+        errors: progress.errors,
+        last_coordinator_contact_secs_ago: progress.last_coordinator_contact_secs_ago,
+        proofs,
+    })
+}
+
+async fn get_health(State(status): State<ProverStatus>) -> StatusCode {
+    if status.progress.is_healthy(status.health_window) {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    }
+}
+
+#[cfg(feature = "metrics")]
+async fn get_metrics() -> Result<String, StatusCode> {
+    ethrex_metrics::prover::METRICS_PROVER
+        .gather_metrics()
+        .map_err(|e| {
+            error!("Failed to gather prover metrics: {e}");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used, clippy::indexing_slicing)]
+mod tests {
+    use ethrex_l2_common::prover::ProofFormat;
+    use serde_json::Value;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpStream;
+
+    use super::*;
+
+    fn endpoint() -> Url {
+        Url::parse("http://127.0.0.1:3900").unwrap()
+    }
+
+    fn capabilities() -> BackendCapabilities {
+        BackendCapabilities {
+            backend: "exec",
+            requires_gpu: false,
+            gpu_capable: false,
+            gpu_detected: false,
+            cuda_devices: 0,
+            available_memory: None,
+            estimated_memory_per_proof: 0,
+            proof_formats: vec![ProofFormat::Compressed],
+            max_recommended_concurrency: None,
+        }
+    }
+
+    fn status(progress: Arc<ProverProgress>) -> ProverStatus {
+        ProverStatus {
+            backend: "exec",
+            capabilities: capabilities(),
+            programs: vec!["evm-l2".to_string()],
+            accounting: None,
+            prefetcher: None,
+            progress,
+            health_window: Duration::from_secs(60),
+        }
+    }
+
+    fn prove(progress: &ProverProgress, batch_number: u64, outcome: BatchOutcome) {
+        progress.fetching(&endpoint());
+        progress.assigned(batch_number, "evm-l2");
+        progress.set_phase(ProvingPhase::Proving);
+        progress.finish(outcome);
+    }
+
+    /// Serves `status` on a free port and returns the status code and body of
+    /// `GET path`.
+    async fn get(status: ProverStatus, path: &str) -> (u16, String) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router(status)).await });
+
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!("GET {path} HTTP/1.1\r\nHost: {addr}\r\nConnection: close\r\n\r\n");
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        let (head, body) = response.split_once("\r\n\r\n").expect("HTTP response");
+        let code = head
+            .split_whitespace()
+            .nth(1)
+            .and_then(|code| code.parse().ok())
+            .expect("status line");
+        (code, body.to_string())
+    }
+
+    #[tokio::test]
+    async fn status_reports_current_and_recent_batches() {
+        let progress = Arc::new(ProverProgress::new());
+        prove(&progress, 7, BatchOutcome::Submitted);
+        prove(&progress, 8, BatchOutcome::SubmissionFailed);
+        progress.record_error(ProverErrorKind::Submission);
+        progress.record_error(ProverErrorKind::InputRequest);
+        progress.fetching(&endpoint());
+        progress.assigned(9, "evm-l2");
+        progress.set_phase(ProvingPhase::Serializing);
+
+        let (code, body) = get(status(progress), "/status").await;
+        assert_eq!(code, 200);
+        let status: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(status["backend"], "exec");
+        assert_eq!(status["capabilities"]["backend"], "exec");
+        assert_eq!(status["programs"], serde_json::json!(["evm-l2"]));
+
+        assert_eq!(status["current"]["batch_number"], 9);
+        assert_eq!(status["current"]["phase"], "serializing");
+        assert_eq!(status["current"]["endpoint"], "http://127.0.0.1:3900/");
+
+        // Most recent first
+        let recent = status["recent_batches"].as_array().unwrap();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[0]["batch_number"], 8);
+        assert_eq!(recent[0]["outcome"], "submission_failed");
+        assert_eq!(recent[1]["batch_number"], 7);
+        assert_eq!(recent[1]["outcome"], "submitted");
+
+        assert_eq!(status["errors"]["submission"], 1);
+        assert_eq!(status["errors"]["input_request"], 1);
+        assert_eq!(status["errors"]["proving"], 0);
+        assert!(status["prefetch"].is_null());
+        assert!(status["proofs"].is_null());
+        assert!(status["last_coordinator_contact_secs_ago"].is_null());
+    }
+
+    #[tokio::test]
+    async fn healthz_requires_recent_coordinator_contact() {
+        let progress = Arc::new(ProverProgress::new());
+        let (code, _) = get(status(progress.clone()), "/healthz").await;
+        assert_eq!(code, 503);
+
+        progress.coordinator_contacted();
+        let (code, _) = get(status(progress.clone()), "/healthz").await;
+        assert_eq!(code, 200);
+
+        let stale = Instant::now()
+            .checked_sub(Duration::from_secs(120))
+            .unwrap();
+        progress.state().last_coordinator_contact = Some(stale);
+        let (code, _) = get(status(progress), "/healthz").await;
+        assert_eq!(code, 503);
+    }
+
+    #[tokio::test]
+    async fn healthz_stays_up_during_a_long_proof() {
+        let progress = Arc::new(ProverProgress::new());
+        progress.coordinator_contacted();
+        progress.fetching(&endpoint());
+        progress.assigned(4, "evm-l2");
+        progress.set_phase(ProvingPhase::Proving);
+
+        // The last answer is older than the window, but the prover is busy
+        // proving the batch it got with it.
+        let stale = Instant::now()
+            .checked_sub(Duration::from_secs(3600))
+            .unwrap();
+        progress.state().last_coordinator_contact = Some(stale);
+        let (code, _) = get(status(progress.clone()), "/healthz").await;
+        assert_eq!(code, 200);
+
+        // Once the proof is sent, staleness counts again.
+        progress.set_phase(ProvingPhase::Submitting);
+        let (code, _) = get(status(progress), "/healthz").await;
+        assert_eq!(code, 503);
+    }
+
+    #[test]
+    fn recent_batches_are_capped() {
+        let progress = ProverProgress::new();
+        for batch_number in 0..30 {
+            prove(&progress, batch_number, BatchOutcome::Submitted);
+        }
+        let recent = progress.snapshot().recent_batches;
+        assert_eq!(recent.len(), RECENT_BATCHES);
+        assert_eq!(recent[0].batch_number, 29);
+        assert_eq!(recent[RECENT_BATCHES - 1].batch_number, 10);
+    }
+
+    #[test]
+    fn unassigned_fetches_are_not_recorded() {
+        let progress = ProverProgress::new();
+        progress.fetching(&endpoint());
+        assert!(progress.current_batch().is_none());
+        progress.finish(BatchOutcome::ProvingFailed);
+        let snapshot = progress.snapshot();
+        assert!(snapshot.current.is_none());
+        assert!(snapshot.recent_batches.is_empty());
+
+        progress.fetching(&endpoint());
+        progress.assigned(3, "evm-l2");
+        assert_eq!(progress.current_batch(), Some((endpoint(), 3)));
+        progress.idle();
+        assert!(progress.snapshot().recent_batches.is_empty());
+    }
+}
@@ -65,8 +65,26 @@ mod sp1_tests {
 #[cfg(feature = "risc0")]
 #[allow(clippy::expect_used, clippy::unwrap_used)]
 mod risc0_tests {
+    use ethrex_guest_program::methods::ETHREX_GUEST_RISC0_ELF;
     use ethrex_guest_program::programs::EvmL2GuestProgram;
     use ethrex_guest_program::traits::{GuestProgram, backends};
+    use ethrex_l2_common::prover::{ProofFormat, ProverType};
+    use ethrex_prover::{ProverBackend, Risc0Backend};
+    use std::path::{Path, PathBuf};
+
+    /// First evm-l2 `stdin.bin` fixture, collected via `ETHREX_DUMP_FIXTURES`.
+    fn evm_l2_stdin_fixture() -> Option<PathBuf> {
+        let dir =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("../../guest-program/tests/fixtures/evm-l2");
+        let mut fixtures: Vec<PathBuf> = std::fs::read_dir(dir)
+            .ok()?
+            .flatten()
+            .map(|batch| batch.path().join("stdin.bin"))
+            .filter(|path| path.exists())
+            .collect();
+        fixtures.sort();
+        fixtures.into_iter().next()
+    }
 
     #[test]
     #[ignore = "requires RISC0 toolchain and compiled ELF"]
@@ -95,4 +113,44 @@ mod risc0_tests {
         );
         assert!(!vk.unwrap().is_empty(), "VK bytes should not be empty");
     }
+
+    #[test]
+    #[ignore = "requires RISC0 toolchain, compiled ELF and stdin.bin fixtures. Very slow on CPU"]
+    fn risc0_groth16_batch_proof_round_trip() {
+        let Some(stdin_path) = evm_l2_stdin_fixture() else {
+            eprintln!("SKIP: no evm-l2 stdin.bin fixtures found.");
+            return;
+        };
+        let input = std::fs::read(stdin_path).expect("read stdin.bin");
+        let backend = Risc0Backend::new();
+
+        // Prove succinct so that `to_batch_proof` goes through the Groth16 compression
+        let receipt = backend
+            .prove_with_elf(ETHREX_GUEST_RISC0_ELF, &input, ProofFormat::Compressed)
+            .expect("proving should succeed");
+        backend
+            .verify(&receipt)
+            .expect("succinct receipt should verify");
+        let journal = receipt.journal.bytes.clone();
+
+        let mut tampered = receipt.clone();
+        if let Some(byte) = tampered.journal.bytes.first_mut() {
+            *byte ^= 1;
+        }
+        assert!(backend.verify(&tampered).is_err());
+        assert!(
+            backend
+                .to_batch_proof(tampered, ProofFormat::Groth16)
+                .is_err(),
+            "a seal for other public values should be rejected before submission"
+        );
+
+        let batch_proof = backend
+            .to_batch_proof(receipt, ProofFormat::Groth16)
+            .expect("Groth16 conversion should succeed");
+        assert_eq!(batch_proof.prover_type(), ProverType::RISC0);
+        assert_eq!(batch_proof.public_values(), journal);
+        assert_eq!(batch_proof.calldata().len(), 1);
+        assert!(batch_proof.compressed().is_none());
+    }
 }
@@ -0,0 +1,163 @@
+//! Tuning of the batch gas limit from the feedback provers send with each
+//! proof.
+//!
+//! [`BatchSizeController::observe`] estimates from each report how much gas
+//! the prover can prove in the target proving time and moves the batch gas
+//! limit towards it, within the configured bounds. Reports whose proving time
+//! is within the hysteresis band around the target don't change the limit,
+//! and a single report can at most halve or double it, so noisy proving times
+//! don't make batch sizes oscillate. The limit only grows while the prover
+//! has no backlog.
+//!
+//! The controller doesn't do any IO, every [`Decision`] carries the inputs it
+//! was made from so the caller can log it.
+
+use std::fmt;
+
+use ethrex_l2_common::prover::ProvingFeedback;
+
+/// Largest factor a single report can change the batch gas limit by.
+const MAX_STEP_FACTOR: u64 = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct BatchSizeControllerConfig {
+    pub min_batch_gas: u64,
+    pub max_batch_gas: u64,
+    /// Time between batches, in milliseconds.
+    pub proving_interval_ms: u64,
+    /// Proving time aimed for, in percent of the proving interval.
+    pub target_proving_time_percentage: u64,
+    /// Deviation from the target proving time tolerated before resizing batches, in percent.
+    pub hysteresis_percentage: u64,
+}
+
+impl BatchSizeControllerConfig {
+    /// Proving time aimed for, in milliseconds.
+    pub fn target_proving_time_ms(&self) -> u64 {
+        percent_of(
+            self.proving_interval_ms,
+            self.target_proving_time_percentage,
+        )
+    }
+}
+
+/// Why the batch gas limit changed, or didn't.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecisionReason {
+    /// Proving took longer than the target plus the hysteresis band.
+    SlowProving,
+    /// Proving took less than the target minus the hysteresis band.
+    FastProving,
+    /// Proving was fast, but the prover has batches queued.
+    Backlog,
+    /// Proving time is within the hysteresis band around the target.
+    WithinBand,
+    /// The batch used no gas or took no time, so it says nothing of the prover's capacity.
+    NoData,
+}
+
+impl fmt::Display for DecisionReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self {
+            DecisionReason::SlowProving => "proving slower than target",
+            DecisionReason::FastProving => "proving faster than target",
+            DecisionReason::Backlog => "prover has a backlog",
+            DecisionReason::WithinBand => "proving time within hysteresis band",
+            DecisionReason::NoData => "feedback carries no data",
+        };
+        f.write_str(reason)
+    }
+}
+
+/// Outcome of a proving feedback report, along with the inputs it was made from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Decision {
+    pub feedback: ProvingFeedback,
+    pub target_proving_time_ms: u64,
+    pub previous_batch_gas: u64,
+    pub batch_gas: u64,
+    pub reason: DecisionReason,
+}
+
+impl Decision {
+    pub fn changed(&self) -> bool {
+        self.batch_gas != self.previous_batch_gas
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct BatchSizeController {
+    config: BatchSizeControllerConfig,
+    batch_gas: u64,
+}
+
+impl BatchSizeController {
+    /// Starts from `initial_batch_gas`, clamped to the configured bounds.
+    pub fn new(config: BatchSizeControllerConfig, initial_batch_gas: u64) -> Self {
+        let batch_gas = initial_batch_gas.clamp(config.min_batch_gas, config.max_batch_gas);
+        Self { config, batch_gas }
+    }
+
+    pub fn config(&self) -> &BatchSizeControllerConfig {
+        &self.config
+    }
+
+    /// Current batch gas limit.
+    pub fn batch_gas(&self) -> u64 {
+        self.batch_gas
+    }
+
+    /// Adjusts the batch gas limit to a proof's feedback.
+    pub fn observe(&mut self, feedback: ProvingFeedback) -> Decision {
+        let target_proving_time_ms = self.config.target_proving_time_ms();
+        let previous_batch_gas = self.batch_gas;
+        let hysteresis = self.config.hysteresis_percentage;
+        let upper_bound_ms = percent_of(target_proving_time_ms, 100_u64.saturating_add(hysteresis));
+        let lower_bound_ms = percent_of(target_proving_time_ms, 100_u64.saturating_sub(hysteresis));
+
+        let reason = if feedback.gas_used == 0 || feedback.proving_time_ms == 0 {
+            DecisionReason::NoData
+        } else if feedback.proving_time_ms > upper_bound_ms {
+            DecisionReason::SlowProving
+        } else if feedback.proving_time_ms >= lower_bound_ms {
+            DecisionReason::WithinBand
+        } else if feedback.queue_depth > 0 {
+            DecisionReason::Backlog
+        } else {
+            DecisionReason::FastProving
+        };
+
+        // Gas the prover could prove in the target proving time at the rate of this proof.
+        let capacity = || {
+            let capacity = u128::from(feedback.gas_used) * u128::from(target_proving_time_ms)
+                / u128::from(feedback.proving_time_ms);
+            u64::try_from(capacity).unwrap_or(u64::MAX)
+        };
+        let batch_gas = match reason {
+            // A proof of a partially filled batch may be slow yet below the limit,
+            // so slow proofs never grow it and fast ones never shrink it.
+            DecisionReason::SlowProving => capacity()
+                .min(previous_batch_gas)
+                .max(previous_batch_gas / MAX_STEP_FACTOR),
+            DecisionReason::FastProving => capacity()
+                .max(previous_batch_gas)
+                .min(previous_batch_gas.saturating_mul(MAX_STEP_FACTOR)),
+            DecisionReason::Backlog | DecisionReason::WithinBand | DecisionReason::NoData => {
+                previous_batch_gas
+            }
+        };
+        self.batch_gas = batch_gas.clamp(self.config.min_batch_gas, self.config.max_batch_gas);
+
+        Decision {
+            feedback,
+            target_proving_time_ms,
+            previous_batch_gas,
+            batch_gas: self.batch_gas,
+            reason,
+        }
+    }
+}
+
+fn percent_of(value: u64, percentage: u64) -> u64 {
+    u64::try_from(u128::from(value) * u128::from(percentage) / 100).unwrap_or(u64::MAX)
+}
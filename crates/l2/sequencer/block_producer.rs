@@ -174,14 +174,21 @@ impl BlockProducer {
         debug!("Head block hash: {head_hash:#x}");
 
         // Proposer creates a new payload
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+        // There are no consensus slots on L2, the VM always sees slot number zero
+        let slot_number = self
+            .store
+            .get_chain_config()
+            .is_amsterdam_activated(timestamp)
+            .then_some(0);
         let args = BuildPayloadArgs {
             parent: head_hash,
-            timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+            timestamp,
             fee_recipient: self.coinbase_address,
             random: H256::zero(),
             withdrawals: Default::default(),
             beacon_root: Some(head_beacon_block_root),
-            slot_number: None,
+            slot_number,
             version,
             elasticity_multiplier: self.elasticity_multiplier,
             gas_ceil: self.block_gas_limit,
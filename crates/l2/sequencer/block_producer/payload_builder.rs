@@ -209,10 +209,10 @@ pub async fn fill_transactions(
             continue;
         }
 
-        // Set BAL index for this transaction (1-indexed per EIP-7928)
+        // Start recording BAL for this transaction (1-indexed per EIP-7928)
         #[allow(clippy::cast_possible_truncation, clippy::as_conversions)]
         let tx_index = (context.payload.body.transactions.len() + 1) as u16;
-        context.vm.set_bal_index(tx_index);
+        context.vm.record_tx(tx_index);
 
         // Record tx sender and recipient for BAL
         if let Some(recorder) = context.vm.db.bal_recorder_mut() {
@@ -231,6 +231,7 @@ pub async fn fill_transactions(
             Err(e) => {
                 debug!("Failed to execute transaction: {}, {e}", tx_hash);
                 metrics!(METRICS_TX.inc_tx_errors(e.to_metric()));
+                context.vm.remove_tx(tx_index);
                 // Ignore following txs from sender
                 txs.pop();
                 continue;
@@ -242,6 +243,7 @@ pub async fn fill_transactions(
         for msg in l2_messages {
             if !registered_chains.contains(&msg.dest_chain_id) {
                 txs.pop();
+                // Also retracts the transaction's BAL contributions
                 context.vm.undo_last_tx()?;
                 context.remaining_gas = previous_remaining_gas;
                 context.block_value = previous_block_value;
@@ -3,7 +3,7 @@ use ethrex_common::{Address, U256};
 use ethrex_l2_rpc::signer::Signer;
 use reqwest::Url;
 use secp256k1::SecretKey;
-use std::net::IpAddr;
+use std::{net::IpAddr, path::PathBuf};
 
 #[derive(Clone, Debug)]
 pub struct SequencerConfig {
@@ -37,9 +37,25 @@ pub struct CommitterConfig {
     pub first_wake_up_time_ms: u64,
     pub commit_time_ms: u64,
     pub batch_gas_limit: Option<u64>,
+    /// Lower bound of the batch gas limit when it's tuned from the provers' feedback.
+    /// Tuning is enabled when both bounds are set.
+    pub min_batch_gas: Option<u64>,
+    /// Upper bound of the batch gas limit when it's tuned from the provers' feedback.
+    pub max_batch_gas: Option<u64>,
+    /// Proving time batches are sized for, in percent of the commit time.
+    pub target_proving_time_percentage: u64,
+    /// Deviation from the target proving time tolerated before resizing batches, in percent.
+    pub batch_gas_hysteresis_percentage: u64,
     pub arbitrary_base_blob_gas_price: u64,
     pub validium: bool,
     pub signer: Signer,
+    /// Directory the preconfirmation gateway writes batch ordering commitments to,
+    /// as `batch_<number>.json`. Batches are built without one when unset.
+    pub ordering_commitments_dir: Option<PathBuf>,
+    /// Whether provers commit to a hash of each batch's block summaries, which
+    /// is sent to the L1 with the batch. Batches are then capped at
+    /// `MAX_BLOCK_SUMMARIES` blocks.
+    pub publish_block_summaries: bool,
 }
 
 #[derive(Clone, Debug)]
@@ -77,6 +93,16 @@ pub struct ProofCoordinatorConfig {
     pub qpl_tool_path: Option<String>,
     /// Which guest program to assign to batches (e.g. "evm-l2", "zk-dex", "tokamon").
     pub guest_program_id: String,
+    /// Proof submission is deferred while the L1 base fee is above this, in wei.
+    pub max_base_fee_per_gas: Option<u64>,
+    /// Proof submission is deferred while the L1 priority fee is above this, in wei.
+    pub max_priority_fee_per_gas: Option<u64>,
+    /// Seconds after a batch's proofs are complete from which fees no longer defer its submission.
+    pub submission_deadline_secs: u64,
+    /// Seconds without inclusion after which a verify transaction is replaced with higher fees.
+    pub stuck_tx_timeout_secs: u64,
+    /// Fee increase of replacement verify transactions, in percent.
+    pub fee_bump_percentage: u64,
 }
 
 #[derive(Clone, Debug)]
@@ -6,6 +6,7 @@ use aligned_sdk::gateway::provider::GatewayError;
 use ethereum_types::FromStrRadixErr;
 use ethrex_blockchain::error::{ChainError, InvalidBlockError, InvalidForkChoice};
 use ethrex_common::types::{BlobsBundleError, FakeExponentialError};
+use ethrex_l2_common::preconfirmations::OrderingCommitmentError;
 use ethrex_l2_common::privileged_transactions::PrivilegedTransactionError;
 use ethrex_l2_common::prover::ProverType;
 use ethrex_l2_rpc::signer::SignerError;
@@ -265,6 +266,8 @@ pub enum CommitterError {
     InvalidWithdrawalTransaction,
     #[error("Blob estimation failed: {0}")]
     BlobEstimationError(#[from] BlobEstimationError),
+    #[error("Committer failed to read the batch ordering commitment: {0}")]
+    OrderingCommitment(#[from] OrderingCommitmentError),
     #[error("Failed to convert integer")]
     TryIntoError(#[from] std::num::TryFromIntError),
     #[error("Failed to encode calldata: {0}")]
@@ -291,6 +294,10 @@ pub enum CommitterError {
     FailedToGenerateBatchWitness(#[source] ChainError),
     #[error("Missing blob for batch {0}")]
     MissingBlob(u64),
+    #[error("Missing prover input for batch {0}")]
+    MissingProverInput(u64),
+    #[error("Batch {0} has {1} blocks, more than its block summaries can cover")]
+    TooManyBlocksForSummaries(u64, usize),
     #[error("Failed to create checkpoint: {0}")]
     FailedToCreateCheckpoint(String),
     #[error("Failed to process blobs: {0}")]
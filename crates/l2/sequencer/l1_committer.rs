@@ -16,11 +16,12 @@ use ethrex_common::utils::keccak;
 use ethrex_common::{
     Address, H256, U256,
     types::{
-        BLOB_BASE_FEE_UPDATE_FRACTION, BlobsBundle, Block, BlockNumber, Fork, Genesis,
+        BLOB_BASE_FEE_UPDATE_FRACTION, BlobsBundle, Block, BlockHeader, BlockNumber, Fork, Genesis,
         MIN_BASE_FEE_PER_BLOB_GAS, TxKind, TxType, batch::Batch, blobs_bundle, fake_exponential,
         fee_config::FeeConfig,
     },
 };
+use ethrex_guest_program::l2::{BlockSummary, MAX_BLOCK_SUMMARIES, block_summaries_hash};
 use ethrex_l2_common::sequencer_state::{SequencerState, SequencerStatus};
 use ethrex_l2_common::{
     calldata::Value,
@@ -29,6 +30,7 @@ use ethrex_l2_common::{
         BRIDGE_ADDRESS, L2Message, get_balance_diffs, get_block_l1_messages,
         get_block_l2_out_messages, get_l1_message_hash,
     },
+    preconfirmations::read_ordering_commitment,
     privileged_transactions::{
         PRIVILEGED_TX_BUDGET, compute_privileged_transactions_hash, get_block_l1_in_messages,
         get_block_l2_in_messages,
@@ -58,19 +60,21 @@ use std::{
     collections::BTreeMap,
     fs::remove_dir_all,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, Mutex, PoisonError},
 };
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, trace, warn};
 
-use super::{errors::BlobEstimationError, utils::random_duration};
+use super::{
+    batch_size_controller::BatchSizeController, errors::BlobEstimationError, utils::random_duration,
+};
 use spawned_concurrency::tasks::{
     CallResponse, CastResponse, GenServer, GenServerHandle, send_after,
 };
 
 const COMMIT_FUNCTION_SIGNATURE_BASED: &str =
     "commitBatch(uint256,bytes32,bytes32,bytes32,bytes32,uint256,bytes32,uint8,bytes32,bytes[])";
-const COMMIT_FUNCTION_SIGNATURE: &str = "commitBatch(uint256,bytes32,bytes32,bytes32,bytes32,uint256,bytes32,uint8,bytes32,(uint256,uint256,(address,address,address,uint256)[],bytes32[])[],(uint256,bytes32)[])";
+const COMMIT_FUNCTION_SIGNATURE: &str = "commitBatch(uint256,bytes32,bytes32,bytes32,bytes32,uint256,uint256,bytes32,uint8,bytes32,bytes32,uint256,bytes32,(uint256,uint256,(address,address,address,uint256)[],bytes32[])[],(uint256,bytes32)[])";
 /// Default wake up time for the committer to check if it should send a commit tx
 const COMMITTER_DEFAULT_WAKE_TIME_MS: u64 = 60_000;
 
@@ -106,10 +110,16 @@ pub struct L1Committer {
     rollup_store: StoreRollup,
     commit_time_ms: u64,
     batch_gas_limit: Option<u64>,
+    /// Tunes the batch gas limit from the provers' feedback, overriding `batch_gas_limit`.
+    batch_size_controller: Option<Arc<Mutex<BatchSizeController>>>,
     arbitrary_base_blob_gas_price: u64,
     validium: bool,
     signer: Signer,
     based: bool,
+    /// Directory the preconfirmation gateway writes batch ordering commitments to.
+    ordering_commitments_dir: Option<PathBuf>,
+    /// Whether batches are proven and committed with their block summaries hash.
+    publish_block_summaries: bool,
     sequencer_state: SequencerState,
     /// Time to wait before checking if it should send a new batch
     committer_wake_up_ms: u64,
@@ -167,6 +177,7 @@ impl L1Committer {
         sequencer_state: SequencerState,
         genesis: Genesis,
         checkpoints_dir: PathBuf,
+        batch_size_controller: Option<Arc<Mutex<BatchSizeController>>>,
     ) -> Result<Self, CommitterError> {
         let eth_client = EthClient::new_with_config(
             eth_config.rpc_url.clone(),
@@ -211,10 +222,18 @@ impl L1Committer {
             rollup_store,
             commit_time_ms: committer_config.commit_time_ms,
             batch_gas_limit: committer_config.batch_gas_limit,
+            batch_size_controller,
             arbitrary_base_blob_gas_price: committer_config.arbitrary_base_blob_gas_price,
             validium: committer_config.validium,
             signer: committer_config.signer.clone(),
             based,
+            // The based OnChainProposer doesn't take ordering commitments
+            ordering_commitments_dir: committer_config
+                .ordering_commitments_dir
+                .clone()
+                .filter(|_| !based),
+            // Nor block summaries hashes
+            publish_block_summaries: committer_config.publish_block_summaries && !based,
             sequencer_state,
             committer_wake_up_ms: committer_config
                 .commit_time_ms
@@ -316,6 +335,7 @@ impl L1Committer {
         Ok(true)
     }
 
+    #[expect(clippy::too_many_arguments)]
     pub async fn spawn(
         store: Store,
         blockchain: Arc<Blockchain>,
@@ -324,6 +344,7 @@ impl L1Committer {
         sequencer_state: SequencerState,
         genesis: Genesis,
         checkpoints_dir: PathBuf,
+        batch_size_controller: Option<Arc<Mutex<BatchSizeController>>>,
     ) -> Result<GenServerHandle<L1Committer>, CommitterError> {
         let state = Self::new(
             &cfg.l1_committer,
@@ -336,6 +357,7 @@ impl L1Committer {
             sequencer_state,
             genesis,
             checkpoints_dir,
+            batch_size_controller,
         )
         .await?;
         // NOTE: we spawn as blocking due to `generate_blobs_bundle` and
@@ -719,6 +741,19 @@ impl L1Committer {
         Ok(Some(batch))
     }
 
+    /// Gas limit of the next batch, as tuned from the provers' feedback if enabled.
+    fn batch_gas_limit(&self) -> Option<u64> {
+        match &self.batch_size_controller {
+            Some(controller) => Some(
+                controller
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .batch_gas(),
+            ),
+            None => self.batch_gas_limit,
+        }
+    }
+
     async fn prepare_batch_from_block(
         &self,
         mut last_added_block_number: BlockNumber,
@@ -761,9 +796,19 @@ impl L1Committer {
         #[cfg(feature = "metrics")]
         let mut batch_gas_used = 0_u64;
 
+        let batch_gas_limit = self.batch_gas_limit();
+
         info!("Preparing batch from block {first_block_of_batch}, {batch_number}");
 
         loop {
+            // The prover only commits block summaries for batches this size
+            if self.publish_block_summaries && acc_blocks.len() >= MAX_BLOCK_SUMMARIES {
+                debug!(
+                    "Block summaries limit reached. Any remaining blocks will be processed in the next batch"
+                );
+                break;
+            }
+
             let block_to_commit_number = last_added_block_number + 1;
 
             // Get potential block to include in the batch
@@ -868,7 +913,7 @@ impl L1Committer {
             let current_block_gas_used = potential_batch_block.header.gas_used;
 
             // Check if adding this block would exceed the batch gas limit
-            if self.batch_gas_limit.is_some_and(|batch_gas_limit| {
+            if batch_gas_limit.is_some_and(|batch_gas_limit| {
                 acc_gas_used + current_block_gas_used > batch_gas_limit
             }) {
                 debug!(
@@ -1178,44 +1223,34 @@ impl L1Committer {
         let (blob_commitment, blob_proof) = if self.validium {
             ([0; 48], [0; 48])
         } else {
-            let BlobsBundle {
-                commitments,
-                proofs,
-                blobs,
-                ..
-            } = &batch.blobs_bundle;
-
-            let l1_fork = get_l1_active_fork(&self.eth_client, self.osaka_activation_time)
-                .await
-                .map_err(CommitterError::EthClientError)?;
-
-            let commitment = commitments
+            let commitment = batch
+                .blobs_bundle
+                .commitments
                 .last()
                 .cloned()
                 .ok_or_else(|| CommitterError::MissingBlob(batch.number))?;
 
-            // The prover takes a single proof even for Osaka type proofs, so if
-            // the committer generated Osaka type proofs (cell proofs), we need
-            // to create a BlobsBundle from the blobs specifying a pre-Osaka
-            // fork to get a single proof for the entire blob.
-            // If we are pre-Osaka, we already have a single proof in the
-            // previously generated bundle
-            let proof = if l1_fork < Fork::Osaka {
-                proofs
-                    .first()
-                    .cloned()
-                    .ok_or_else(|| CommitterError::MissingBlob(batch.number))?
-            } else {
-                BlobsBundle::create_from_blobs(blobs, Some(0))?
-                    .proofs
-                    .first()
-                    .cloned()
-                    .ok_or_else(|| CommitterError::MissingBlob(batch.number))?
-            };
+            // The prover takes a single proof for the entire blob, so if the
+            // committer generated Osaka type proofs (cell proofs) we convert
+            // the bundle back to one proof per blob. The commitment is the
+            // same for both versions.
+            let proof = batch
+                .blobs_bundle
+                .clone()
+                .into_version(0)?
+                .proofs
+                .first()
+                .cloned()
+                .ok_or_else(|| CommitterError::MissingBlob(batch.number))?;
 
             (commitment, proof)
         };
 
+        let ordering_commitment = match &self.ordering_commitments_dir {
+            Some(dir) => read_ordering_commitment(dir, batch.number)?,
+            None => None,
+        };
+
         let prover_input = ProverInputData {
             blocks,
             execution_witness: batch_witness,
@@ -1228,11 +1263,78 @@ impl L1Committer {
                 .config
                 .native_token_scale_factor()
                 .map_err(CommitterError::UnexpectedError)?,
+            ordering_commitment,
+            publish_block_summaries: self.publish_block_summaries,
+            // The based OnChainProposer doesn't check privileged transaction
+            // deadlines against the batch
+            commit_deposit_inclusion: !self.based,
         };
 
         Ok(prover_input)
     }
 
+    /// Returns the stored prover input of a batch that needs a proof. Empty
+    /// batches are verified without one and have no input.
+    async fn stored_prover_input(
+        &self,
+        batch: &Batch,
+    ) -> Result<Option<ProverInputData>, CommitterError> {
+        if batch.is_empty_batch() {
+            return Ok(None);
+        }
+        self.rollup_store
+            .get_prover_input_by_batch_and_version(batch.number, &self.git_commit_hash)
+            .await?
+            .map(Some)
+            .ok_or(CommitterError::MissingProverInput(batch.number))
+    }
+
+    /// Returns the ordering root and signer key id committed with the batch,
+    /// taken from its stored prover input so that they match what the prover
+    /// commits. Batches without an ordering commitment get a zero root.
+    async fn batch_ordering_commitment(
+        &self,
+        batch: &Batch,
+    ) -> Result<(H256, u64), CommitterError> {
+        if self.ordering_commitments_dir.is_none() {
+            return Ok((H256::zero(), 0));
+        }
+        let commitment = self
+            .stored_prover_input(batch)
+            .await?
+            .and_then(|input| input.ordering_commitment);
+        Ok(commitment
+            .map(|commitment| (commitment.root(), commitment.signer_key_id))
+            .unwrap_or_default())
+    }
+
+    /// Returns the hash of the batch's block summaries, computed from the blocks
+    /// of its stored prover input so that it matches what the prover commits.
+    /// Empty batches get a zero hash, as they aren't proven.
+    async fn batch_block_summaries_hash(&self, batch: &Batch) -> Result<H256, CommitterError> {
+        if !self.publish_block_summaries {
+            return Ok(H256::zero());
+        }
+        let Some(input) = self.stored_prover_input(batch).await? else {
+            return Ok(H256::zero());
+        };
+        // Batches are sealed with at most MAX_BLOCK_SUMMARIES blocks while
+        // summaries are published, so the prover always commits them
+        if input.blocks.len() > MAX_BLOCK_SUMMARIES {
+            return Err(CommitterError::TooManyBlocksForSummaries(
+                batch.number,
+                input.blocks.len(),
+            ));
+        }
+        let summaries = input
+            .blocks
+            .iter()
+            .map(BlockSummary::from_block)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| CommitterError::ConversionError(err.to_string()))?;
+        Ok(block_summaries_hash(&summaries))
+    }
+
     /// Creates a checkpoint of the given store at the specified path.
     ///
     /// This function performs the following steps:
@@ -1304,7 +1406,8 @@ impl L1Committer {
 
     async fn send_commitment(&mut self, batch: &Batch) -> Result<H256, CommitterError> {
         let l1_messages_merkle_root = compute_merkle_root(&batch.l1_out_message_hashes);
-        let last_block_hash = get_last_block_hash(&self.store, batch.last_block)?;
+        let last_block_header = get_last_block_header(&self.store, batch.last_block)?;
+        let last_block_hash = last_block_header.hash();
         let commit_hash_bytes = keccak(self.git_commit_hash.as_bytes());
 
         // ── Fixture dump: save committer data for offline testing ──
@@ -1375,8 +1478,13 @@ impl L1Committer {
             Value::FixedBytes(l1_messages_merkle_root.0.to_vec().into()),
             Value::FixedBytes(batch.l1_in_messages_rolling_hash.0.to_vec().into()),
             Value::FixedBytes(last_block_hash.0.to_vec().into()),
-            Value::Uint(U256::from(batch.non_privileged_transactions)),
         ];
+        if !self.based {
+            // The based OnChainProposer doesn't check privileged transaction
+            // deadlines against the batch
+            calldata_values.push(Value::Uint(U256::from(last_block_header.timestamp)));
+        }
+        calldata_values.push(Value::Uint(U256::from(batch.non_privileged_transactions)));
 
         let program_id = self
             .rollup_store
@@ -1468,7 +1576,14 @@ impl L1Committer {
 
             calldata_values.push(Value::FixedBytes(commit_hash_bytes.0.to_vec().into()));
             calldata_values.push(Value::Uint(U256::from(program_type_id)));
+            let (ordering_root, ordering_signer_key_id) =
+                self.batch_ordering_commitment(batch).await?;
+
             calldata_values.push(Value::FixedBytes(public_values_hash.0.to_vec().into()));
+            calldata_values.push(Value::FixedBytes(ordering_root.0.to_vec().into()));
+            calldata_values.push(Value::Uint(U256::from(ordering_signer_key_id)));
+            let block_summaries_hash = self.batch_block_summaries_hash(batch).await?;
+            calldata_values.push(Value::FixedBytes(block_summaries_hash.0.to_vec().into()));
             calldata_values.push(Value::Array(balance_diff_values));
             calldata_values.push(Value::Array(l2_in_message_rolling_hashes_values));
             (COMMIT_FUNCTION_SIGNATURE, calldata_values)
@@ -1793,24 +1908,20 @@ pub fn generate_blobs_bundle(
 
     let blob =
         blobs_bundle::blob_from_bytes(Bytes::from(blob_data)).map_err(CommitterError::from)?;
-    let wrapper_version = if fork <= Fork::Prague { None } else { Some(1) };
-
     Ok((
-        BlobsBundle::create_from_blobs(&vec![blob], wrapper_version)
-            .map_err(CommitterError::from)?,
+        BlobsBundle::create_for_fork(&vec![blob], fork).map_err(CommitterError::from)?,
         blob_size,
     ))
 }
 
-fn get_last_block_hash(
+fn get_last_block_header(
     store: &Store,
     last_block_number: BlockNumber,
-) -> Result<H256, CommitterError> {
+) -> Result<BlockHeader, CommitterError> {
     store
         .get_block_header(last_block_number)?
-        .map(|header| header.hash())
         .ok_or(CommitterError::RetrievalError(
-            "Failed to get last block hash from storage".to_owned(),
+            "Failed to get last block header from storage".to_owned(),
         ))
 }
 
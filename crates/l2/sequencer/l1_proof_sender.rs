@@ -12,6 +12,7 @@ use alloy::signers::local::PrivateKeySigner;
 use ethrex_common::{Address, H256, U256};
 use ethrex_l2_common::{
     calldata::Value,
+    proof_submission::ProofSubmission,
     prover::{BatchProof, ProverType},
 };
 use ethrex_l2_rpc::signer::{Signer, SignerHealth};
@@ -32,6 +33,7 @@ use tracing::{error, info, warn};
 
 use super::{
     configs::AlignedConfig,
+    proof_submission::{L1VerifyTxClient, SubmissionPolicy, SubmissionStep, unix_now},
     utils::{random_duration, send_verify_tx},
 };
 
@@ -78,6 +80,8 @@ pub struct L1ProofSender {
     /// Directory where checkpoints are stored.
     checkpoints_dir: PathBuf,
     aligned_mode: bool,
+    /// When verify transactions are sent, given the L1 fees
+    submission_policy: SubmissionPolicy,
     /// Cached SP1 verifying key for aligned mode
     #[cfg(feature = "sp1")]
     sp1_vk: Option<SP1VerifyingKey>,
@@ -141,6 +145,7 @@ impl L1ProofSender {
             network: aligned_cfg.network.clone(),
             checkpoints_dir,
             aligned_mode: aligned_cfg.aligned_mode,
+            submission_policy: SubmissionPolicy::new(cfg, eth_cfg),
             #[cfg(feature = "sp1")]
             sp1_vk,
         })
@@ -206,6 +211,13 @@ impl L1ProofSender {
             return Ok(());
         }
 
+        metrics!(
+            let queue_depth = self
+                .proof_submission_queue_depth(batch_to_send, last_committed_batch)
+                .await?;
+            METRICS.set_proof_submission_queue_depth(queue_depth.try_into()?);
+        );
+
         // ── Empty batch auto-verification ──
         // A truly empty batch has: 0 non-privileged transactions, no deposits
         // (l1_in_messages_rolling_hash == zero), no withdrawals, no balance
@@ -280,8 +292,12 @@ impl L1ProofSender {
             if self.aligned_mode {
                 self.send_proof_to_aligned(batch_to_send, proofs.values())
                     .await?;
-            } else {
-                self.send_proof_to_contract(batch_to_send, proofs).await?;
+            } else if !self
+                .submit_proof_to_contract(batch_to_send, &proofs)
+                .await?
+            {
+                // Not verified yet, the submission goes on in the next iteration
+                return Ok(());
             }
             self.rollup_store
                 .set_latest_sent_batch_proof(batch_to_send)
@@ -451,6 +467,8 @@ impl L1ProofSender {
         ))
     }
 
+    /// Verifies a batch without waiting for L1 fees to be acceptable, used
+    /// for empty batches.
     pub async fn send_proof_to_contract(
         &self,
         batch_number: u64,
@@ -461,6 +479,150 @@ impl L1ProofSender {
             "Sending batch verification transaction to L1"
         );
 
+        let calldata = self.verify_calldata(batch_number, &proofs)?;
+
+        let send_verify_tx_result = send_verify_tx(
+            calldata,
+            &self.eth_client,
+            self.verify_target_address(),
+            &self.signer,
+        )
+        .await;
+
+        if let Err(error) = send_verify_tx_result.as_ref() {
+            self.delete_invalid_proofs(batch_number, error).await?;
+        }
+
+        let verify_tx_hash = send_verify_tx_result?;
+
+        metrics!(
+            let verify_tx_receipt = self
+                .eth_client
+                .get_transaction_receipt(verify_tx_hash)
+                .await?
+                .ok_or(ProofSenderError::UnexpectedError("no verify tx receipt".to_string()))?;
+            let verify_gas_used = verify_tx_receipt.tx_info.gas_used.try_into()?;
+            METRICS.set_batch_verification_gas(batch_number, verify_gas_used)?;
+        );
+
+        self.rollup_store
+            .store_verify_tx_by_batch(batch_number, verify_tx_hash)
+            .await?;
+
+        info!(
+            ?batch_number,
+            ?verify_tx_hash,
+            "Sent batch verification transaction to L1"
+        );
+
+        Ok(())
+    }
+
+    /// Advances the L1 submission of a batch's proofs according to the
+    /// submission policy. Returns whether the batch got verified.
+    async fn submit_proof_to_contract(
+        &self,
+        batch_number: u64,
+        proofs: &HashMap<ProverType, BatchProof>,
+    ) -> Result<bool, ProofSenderError> {
+        let now = unix_now();
+        let mut submission = self
+            .rollup_store
+            .get_proof_submission_by_batch(batch_number)
+            .await?
+            .unwrap_or_else(|| ProofSubmission::new(now));
+
+        let client = L1VerifyTxClient {
+            eth_client: &self.eth_client,
+            signer: &self.signer,
+            to: self.verify_target_address(),
+            calldata: self.verify_calldata(batch_number, proofs)?,
+        };
+        let step = self
+            .submission_policy
+            .step(&client, &mut submission, now)
+            .await;
+
+        let receipt = match step {
+            Ok(SubmissionStep::Included(receipt)) => receipt,
+            step => {
+                self.rollup_store
+                    .store_proof_submission_by_batch(batch_number, submission.clone())
+                    .await?;
+                match step? {
+                    SubmissionStep::Deferred(fees) => info!(
+                        ?batch_number,
+                        base_fee_per_gas = fees.base_fee_per_gas,
+                        max_priority_fee_per_gas = fees.max_priority_fee_per_gas,
+                        deferred_secs = submission.deferred_time(now),
+                        "L1 fees above the configured limits, deferring batch verification"
+                    ),
+                    SubmissionStep::Pending {
+                        tx_hash,
+                        replaced: true,
+                    } => warn!(
+                        ?batch_number,
+                        ?tx_hash,
+                        "Verify transaction not included in time, replaced it with higher fees"
+                    ),
+                    SubmissionStep::Pending {
+                        tx_hash,
+                        replaced: false,
+                    } => info!(
+                        ?batch_number,
+                        ?tx_hash,
+                        "Waiting for batch verification transaction to be included"
+                    ),
+                    SubmissionStep::Failed {
+                        error,
+                        next_attempt_at,
+                    } => {
+                        self.delete_invalid_proofs(batch_number, &error).await?;
+                        warn!(
+                            ?batch_number,
+                            failed_attempts = submission.failed_attempts,
+                            retry_in_secs = next_attempt_at.saturating_sub(now),
+                            "Failed to send batch verification transaction: {error}"
+                        );
+                    }
+                    SubmissionStep::BackingOff { .. } | SubmissionStep::Included(_) => {}
+                }
+                return Ok(false);
+            }
+        };
+
+        self.rollup_store
+            .delete_proof_submission_by_batch(batch_number)
+            .await?;
+        self.rollup_store
+            .store_verify_tx_by_batch(batch_number, receipt.tx_hash)
+            .await?;
+
+        metrics!(
+            METRICS.set_batch_verification_gas(batch_number, receipt.gas_used.try_into()?)?;
+            METRICS.set_batch_proof_submission_deferral(
+                batch_number,
+                submission.deferred_time(now).try_into()?,
+            )?;
+            METRICS.add_proof_submission_cost((receipt.cost() / 1_000_000_000).try_into()?);
+        );
+
+        info!(
+            ?batch_number,
+            verify_tx_hash = ?receipt.tx_hash,
+            cost_wei = receipt.cost(),
+            deferred_secs = submission.deferred_time(now),
+            "Batch verification transaction included in L1"
+        );
+
+        Ok(true)
+    }
+
+    fn verify_calldata(
+        &self,
+        batch_number: u64,
+        proofs: &HashMap<ProverType, BatchProof>,
+    ) -> Result<Vec<u8>, ProofSenderError> {
         let calldata_values = [
             &[Value::Uint(U256::from(batch_number))],
             proofs
@@ -484,19 +646,25 @@ impl L1ProofSender {
         ]
         .concat();
 
-        let calldata = encode_calldata(VERIFY_FUNCTION_SIGNATURE, &calldata_values)?;
+        Ok(encode_calldata(
+            VERIFY_FUNCTION_SIGNATURE,
+            &calldata_values,
+        )?)
+    }
 
+    fn verify_target_address(&self) -> Address {
         // Based won't have timelock address until we implement it on it. For the meantime if it's None (only happens in based) we use the OCP
-        let target_address = self
-            .timelock_address
-            .unwrap_or(self.on_chain_proposer_address);
-
-        let send_verify_tx_result =
-            send_verify_tx(calldata, &self.eth_client, target_address, &self.signer).await;
+        self.timelock_address
+            .unwrap_or(self.on_chain_proposer_address)
+    }
 
-        if let Err(EthClientError::RpcRequestError(RpcRequestError::RPCError { message, .. })) =
-            send_verify_tx_result.as_ref()
-        {
+    /// Deletes the proofs the contract rejected, so they are generated again.
+    async fn delete_invalid_proofs(
+        &self,
+        batch_number: u64,
+        error: &EthClientError,
+    ) -> Result<(), ProofSenderError> {
+        if let EthClientError::RpcRequestError(RpcRequestError::RPCError { message, .. }) = error {
             if message.contains("Invalid TDX proof") {
                 warn!("Deleting invalid TDX proof");
                 self.rollup_store
@@ -514,32 +682,34 @@ impl L1ProofSender {
                     .await?;
             }
         }
-
-        let verify_tx_hash = send_verify_tx_result?;
-
-        metrics!(
-            let verify_tx_receipt = self
-                .eth_client
-                .get_transaction_receipt(verify_tx_hash)
-                .await?
-                .ok_or(ProofSenderError::UnexpectedError("no verify tx receipt".to_string()))?;
-            let verify_gas_used = verify_tx_receipt.tx_info.gas_used.try_into()?;
-            METRICS.set_batch_verification_gas(batch_number, verify_gas_used)?;
-        );
-
-        self.rollup_store
-            .store_verify_tx_by_batch(batch_number, verify_tx_hash)
-            .await?;
-
-        info!(
-            ?batch_number,
-            ?verify_tx_hash,
-            "Sent batch verification transaction to L1"
-        );
-
         Ok(())
     }
 
+    /// Number of consecutive batches from `batch_to_send` whose proofs are all
+    /// stored and wait to be verified.
+    #[cfg(feature = "metrics")]
+    async fn proof_submission_queue_depth(
+        &self,
+        batch_to_send: u64,
+        last_committed_batch: u64,
+    ) -> Result<u64, ProofSenderError> {
+        let mut depth = 0;
+        for batch_number in batch_to_send..=last_committed_batch {
+            for proof_type in &self.needed_proof_types {
+                if self
+                    .rollup_store
+                    .get_proof_by_batch_and_type(batch_number, *proof_type)
+                    .await?
+                    .is_none()
+                {
+                    return Ok(depth);
+                }
+            }
+            depth += 1;
+        }
+        Ok(depth)
+    }
+
     async fn health(&self) -> CallResponse<Self> {
         let rpc_healthcheck = self.eth_client.test_urls().await;
         let signer_status = self.signer.health().await;
@@ -3,11 +3,9 @@ use crate::sequencer::errors::L1WatcherError;
 use crate::{EthConfig, L1WatcherConfig, SequencerConfig};
 use ethereum_types::{Address, H256, U256};
 use ethrex_blockchain::{Blockchain, BlockchainType};
+use ethrex_common::types::events::{L2_MESSAGE_EVENT, PRIVILEGED_TX_SENT_EVENT};
 use ethrex_common::types::{Log, PrivilegedL2Transaction, Transaction, TxKind};
-use ethrex_common::utils::keccak;
-use ethrex_l2_common::messages::{
-    L2MESSAGE_EVENT_SELECTOR, L2Message, MESSENGER_ADDRESS, get_l2_message_hash,
-};
+use ethrex_l2_common::messages::{L2Message, MESSENGER_ADDRESS, get_l2_message_hash};
 use ethrex_l2_common::sequencer_state::{SequencerState, SequencerStatus};
 use ethrex_l2_sdk::privileged_data::PrivilegedTransactionData;
 use ethrex_l2_sdk::{get_last_fetched_l1_block, get_pending_l1_messages, get_pending_l2_messages};
@@ -175,8 +173,7 @@ impl L1Watcher {
 
     async fn get_logs_l1(&mut self) -> Result<Vec<RpcLog>, L1WatcherError> {
         // Matches the event PrivilegedTxSent from ICommonBridge.sol
-        let topic =
-            keccak(b"PrivilegedTxSent(address,address,address,uint256,uint256,uint256,bytes)");
+        let topic = PRIVILEGED_TX_SENT_EVENT.topic;
         if self.last_block_fetched_l1.is_zero() {
             self.last_block_fetched_l1 =
                 get_last_fetched_l1_block(&self.eth_client, self.bridge_address)
@@ -434,7 +431,7 @@ impl L1Watcher {
 
     async fn get_logs_l2(&mut self) -> Result<Vec<(L2Message, u64)>, L1WatcherError> {
         info!("Getting L2 logs");
-        let topics = vec![*L2MESSAGE_EVENT_SELECTOR, self.chain_id_topic];
+        let topics = vec![L2_MESSAGE_EVENT.topic, self.chain_id_topic];
         // We don't need to delay L2 logs
         let block_delay = 0;
         let mut acc_logs = Vec::new();
@@ -1,7 +1,8 @@
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use crate::sequencer::admin_server::start_api;
+use crate::sequencer::batch_size_controller::{BatchSizeController, BatchSizeControllerConfig};
 use crate::sequencer::errors::SequencerError;
 use crate::sequencer::state_updater::StateUpdater;
 use crate::{BlockFetcher, SequencerConfig};
@@ -26,6 +27,7 @@ use tracing::{error, info};
 use utils::get_needed_proof_types;
 
 mod admin_server;
+pub mod batch_size_controller;
 pub mod block_producer;
 pub mod l1_committer;
 pub mod l1_proof_sender;
@@ -34,6 +36,7 @@ pub mod l1_watcher;
 #[cfg(feature = "metrics")]
 pub mod metrics;
 pub mod proof_coordinator;
+pub mod proof_submission;
 pub use ethrex_l2_common::sequencer_state::{SequencerState, SequencerStatus};
 pub mod state_updater;
 
@@ -78,6 +81,38 @@ pub async fn start_l2(
         }
     }
 
+    let batch_size_controller = match (
+        cfg.l1_committer.min_batch_gas,
+        cfg.l1_committer.max_batch_gas,
+    ) {
+        (Some(min_batch_gas), Some(max_batch_gas)) => {
+            let block_gas_limit = cfg.block_producer.block_gas_limit;
+            if min_batch_gas < block_gas_limit || max_batch_gas < min_batch_gas {
+                error!(
+                    "The batch gas bounds ({min_batch_gas}..={max_batch_gas}) must be ordered and at or above the block gas limit ({block_gas_limit})."
+                );
+                return Err(errors::SequencerError::GasLimitError);
+            }
+            let config = BatchSizeControllerConfig {
+                min_batch_gas,
+                max_batch_gas,
+                proving_interval_ms: cfg.l1_committer.commit_time_ms,
+                target_proving_time_percentage: cfg.l1_committer.target_proving_time_percentage,
+                hysteresis_percentage: cfg.l1_committer.batch_gas_hysteresis_percentage,
+            };
+            let initial_batch_gas = cfg.l1_committer.batch_gas_limit.unwrap_or(max_batch_gas);
+            info!(
+                ?config,
+                initial_batch_gas, "Tuning the batch gas limit from proving feedback"
+            );
+            Some(Arc::new(Mutex::new(BatchSizeController::new(
+                config,
+                initial_batch_gas,
+            ))))
+        }
+        _ => None,
+    };
+
     info!("Starting Sequencer in {initial_status} mode");
 
     let shared_state = SequencerState::from(initial_status);
@@ -126,6 +161,7 @@ pub async fn start_l2(
         shared_state.clone(),
         genesis,
         checkpoints_dir.clone(),
+        batch_size_controller.clone(),
     )
     .await
     .inspect_err(|err| {
@@ -135,6 +171,7 @@ pub async fn start_l2(
         rollup_store.clone(),
         cfg.clone(),
         needed_proof_types.clone(),
+        batch_size_controller,
     )
     .await
     .inspect_err(|err| {
@@ -1,10 +1,15 @@
 use crate::SequencerConfig;
+use crate::sequencer::batch_size_controller::BatchSizeController;
 use crate::sequencer::errors::{ConnectionHandlerError, ProofCoordinatorError};
 use crate::sequencer::setup::{prepare_quote_prerequisites, register_tdx_key};
 use crate::sequencer::utils::get_git_commit_hash;
 use bytes::Bytes;
-use ethrex_common::Address;
-use ethrex_l2_common::prover::{BatchProof, ProofData, ProofFormat, ProverType};
+use ethrex_common::{Address, H256};
+use ethrex_guest_program::l2::{BlockSummary, block_summaries_hash, decode_block_summaries_hash};
+use ethrex_l2_common::prover::{
+    BatchProof, CachedInput, ProofData, ProofFormat, ProverCapabilities, ProverInputData,
+    ProverType, ProvingFeedback, prover_input_hash,
+};
 use ethrex_metrics::metrics;
 use ethrex_rpc::clients::eth::EthClient;
 use ethrex_storage_rollup::StoreRollup;
@@ -12,7 +17,7 @@ use secp256k1::SecretKey;
 use spawned_concurrency::messages::Unused;
 use spawned_concurrency::tasks::{CastResponse, GenServer, GenServerHandle};
 use std::net::{IpAddr, SocketAddr};
-use std::sync::Arc;
+use std::sync::{Arc, PoisonError};
 use tokio::{
     io::{AsyncReadExt, AsyncWriteExt},
     net::{TcpListener, TcpStream},
@@ -53,6 +58,11 @@ pub struct ProofCoordinator {
     qpl_tool_path: Option<String>,
     /// Which guest program to assign to batches.
     guest_program_id: String,
+    /// Fed with the provers' feedback to tune the batch gas limit, if enabled.
+    batch_size_controller: Option<Arc<std::sync::Mutex<BatchSizeController>>>,
+    /// Proof type whose proving times tune the batch gas limit: the slowest
+    /// of the needed ones, since it's the one batches wait for.
+    feedback_proof_type: Option<ProverType>,
 }
 
 impl ProofCoordinator {
@@ -60,6 +70,7 @@ impl ProofCoordinator {
         config: &SequencerConfig,
         rollup_store: StoreRollup,
         needed_proof_types: Vec<ProverType>,
+        batch_size_controller: Option<Arc<std::sync::Mutex<BatchSizeController>>>,
     ) -> Result<Self, ProofCoordinatorError> {
         let eth_client = EthClient::new_with_config(
             config.eth.rpc_url.clone(),
@@ -81,6 +92,7 @@ impl ProofCoordinator {
             ))?
             .to_string();
 
+        let feedback_proof_type = slowest_proof_type(&needed_proof_types);
         Ok(Self {
             listen_ip: config.proof_coordinator.listen_ip,
             port: config.proof_coordinator.listen_port,
@@ -96,6 +108,8 @@ impl ProofCoordinator {
             request_timestamp: Arc::new(Mutex::new(HashMap::new())),
             qpl_tool_path: config.proof_coordinator.qpl_tool_path.clone(),
             guest_program_id: config.proof_coordinator.guest_program_id.clone(),
+            batch_size_controller,
+            feedback_proof_type,
         })
     }
 
@@ -103,8 +117,14 @@ impl ProofCoordinator {
         rollup_store: StoreRollup,
         cfg: SequencerConfig,
         needed_proof_types: Vec<ProverType>,
+        batch_size_controller: Option<Arc<std::sync::Mutex<BatchSizeController>>>,
     ) -> Result<(), ProofCoordinatorError> {
-        let state = Self::new(&cfg, rollup_store, needed_proof_types)?;
+        let state = Self::new(
+            &cfg,
+            rollup_store,
+            needed_proof_types,
+            batch_size_controller,
+        )?;
         let listener =
             Arc::new(TcpListener::bind(format!("{}:{}", state.listen_ip, state.port)).await?);
         let mut proof_coordinator = ProofCoordinator::start(state);
@@ -140,12 +160,66 @@ impl ProofCoordinator {
         }
     }
 
+    fn proof_format(&self) -> ProofFormat {
+        if self.aligned {
+            ProofFormat::Compressed
+        } else {
+            ProofFormat::Groth16
+        }
+    }
+
+    async fn handle_capabilities(
+        &self,
+        stream: &mut TcpStream,
+        capabilities: ProverCapabilities,
+    ) -> Result<(), ProofCoordinatorError> {
+        info!(
+            "Capabilities received (protocol v{}, backends: {:?}, formats: {:?})",
+            capabilities.protocol_version(),
+            capabilities.backends(),
+            capabilities.proof_formats()
+        );
+        for program in capabilities.programs() {
+            debug!(
+                program_id = %program.program_id,
+                version = %program.version,
+                elf_hash = ?program.elf_hash.map(hex::encode),
+                build_commit = ?program.build_commit,
+                "Prover supports program"
+            );
+        }
+
+        // Only the configured guest program is ever assigned, so the prover is
+        // useful to us only if one of its needed backends can prove it in the
+        // format we request.
+        let format = self.proof_format();
+        let assignable = capabilities.backends().iter().any(|prover_type| {
+            self.needed_proof_types.contains(prover_type)
+                && capabilities.can_prove(*prover_type, &self.guest_program_id, format)
+        });
+        let assignable_programs = if assignable {
+            vec![self.guest_program_id.clone()]
+        } else {
+            info!(
+                "Prover can't handle program '{}' with a needed backend in {format:?} format",
+                self.guest_program_id
+            );
+            Vec::new()
+        };
+
+        send_response(stream, &ProofData::capabilities_ack(assignable_programs)).await?;
+        info!("CapabilitiesACK sent");
+        Ok(())
+    }
+
     async fn handle_request(
         &self,
         stream: &mut TcpStream,
         commit_hash: String,
         prover_type: ProverType,
         supported_programs: &[String],
+        capabilities: Option<&ProverCapabilities>,
+        cached_inputs: &[CachedInput],
     ) -> Result<(), ProofCoordinatorError> {
         info!("BatchRequest received from {prover_type} prover");
 
@@ -222,11 +296,7 @@ impl ProofCoordinator {
             return Ok(());
         };
 
-        let format = if self.aligned {
-            ProofFormat::Compressed
-        } else {
-            ProofFormat::Groth16
-        };
+        let format = self.proof_format();
         metrics!(
             // First request starts a timer until a proof is received. The elapsed time will be
             // the estimated proving time.
@@ -251,6 +321,17 @@ impl ProofCoordinator {
             return Ok(());
         }
 
+        // Provers that negotiated capabilities only get work they can handle.
+        if let Some(capabilities) = capabilities
+            && !capabilities.can_prove(prover_type, &program_id, format)
+        {
+            debug!(
+                "Prover can't prove program '{program_id}' with {prover_type} in {format:?} format, skipping"
+            );
+            send_response(stream, &ProofData::empty_batch_response()).await?;
+            return Ok(());
+        }
+
         // Store program_id early so the L1 committer can look it up before
         // the proof is submitted.  Previously this was only stored on proof
         // submission, which caused a race: the committer would fall back to
@@ -263,20 +344,99 @@ impl ProofCoordinator {
             warn!("Failed to store program_id early for batch {batch_to_prove}: {e}");
         }
 
-        let response =
-            ProofData::batch_response_with_program(batch_to_prove, input, format, program_id);
+        // Leave the input out if the prover already holds this exact one.
+        let input_hash = self
+            .stored_input_hash(batch_to_prove, &commit_hash, &input)
+            .await?;
+        let input = if cached_inputs.contains(&CachedInput {
+            batch_number: batch_to_prove,
+            input_hash,
+        }) {
+            debug!("Prover already holds the input for batch {batch_to_prove}");
+            None
+        } else {
+            Some(input)
+        };
+        let response = ProofData::batch_response_with_input_hash(
+            batch_to_prove,
+            input,
+            input_hash,
+            format,
+            program_id,
+        );
         send_response(stream, &response).await?;
         info!("BatchResponse sent for batch number: {batch_to_prove}");
 
         Ok(())
     }
 
+    /// Serves the input of a batch ahead of its assignment, so provers can
+    /// prefetch the next batches while they prove the current one.
+    async fn handle_input_request(
+        &self,
+        stream: &mut TcpStream,
+        commit_hash: String,
+        batch_number: u64,
+        cached_hash: Option<H256>,
+    ) -> Result<(), ProofCoordinatorError> {
+        debug!("InputRequest received for batch number: {batch_number}");
+
+        let Some(input) = self
+            .rollup_store
+            .get_prover_input_by_batch_and_version(batch_number, &commit_hash)
+            .await?
+        else {
+            send_response(stream, &ProofData::input_response(batch_number, None, None)).await?;
+            return Ok(());
+        };
+
+        let input_hash = self
+            .stored_input_hash(batch_number, &commit_hash, &input)
+            .await?;
+        let input = (cached_hash != Some(input_hash)).then_some(input);
+        send_response(
+            stream,
+            &ProofData::input_response(batch_number, input, Some(input_hash)),
+        )
+        .await?;
+        debug!("InputResponse sent for batch number: {batch_number}");
+
+        Ok(())
+    }
+
+    /// Hash advertised to provers for the input of `batch_number`, persisted
+    /// when the input was stored. Inputs stored before hashes were persisted
+    /// are hashed here once.
+    async fn stored_input_hash(
+        &self,
+        batch_number: u64,
+        prover_version: &str,
+        input: &ProverInputData,
+    ) -> Result<H256, ProofCoordinatorError> {
+        if let Some(input_hash) = self
+            .rollup_store
+            .get_prover_input_hash_by_batch_and_version(batch_number, prover_version)
+            .await?
+        {
+            return Ok(input_hash);
+        }
+        let encoded = input.encode().map_err(|e| {
+            ProofCoordinatorError::Custom(format!("Failed to encode prover input: {e}"))
+        })?;
+        let input_hash = prover_input_hash(&encoded);
+        self.rollup_store
+            .store_prover_input_hash_by_batch_and_version(batch_number, prover_version, input_hash)
+            .await?;
+        Ok(input_hash)
+    }
+
     async fn handle_submit(
         &self,
         stream: &mut TcpStream,
         batch_number: u64,
         batch_proof: BatchProof,
         program_id: &str,
+        feedback: Option<ProvingFeedback>,
     ) -> Result<(), ProofCoordinatorError> {
         info!("ProofSubmit received for batch number: {batch_number} (program: {program_id})");
 
@@ -309,6 +469,8 @@ impl ProofCoordinator {
                 METRICS.set_batch_proving_time(batch_number, proving_time)?;
                 let _ = request_timestamps.remove(&batch_number);
             );
+            self.check_block_summaries(batch_number, &batch_proof)
+                .await?;
             // If not, store it
             self.rollup_store
                 .store_proof_by_batch_and_type(batch_number, prover_type, batch_proof)
@@ -316,6 +478,13 @@ impl ProofCoordinator {
             self.rollup_store
                 .store_program_id_by_batch(batch_number, program_id)
                 .await?;
+            // Only the first proof of the type batches wait for says how long
+            // proving takes.
+            if let Some(feedback) = feedback
+                && self.feedback_proof_type == Some(prover_type)
+            {
+                self.observe_feedback(batch_number, feedback).await?;
+            }
         }
         let response = ProofData::proof_submit_ack(batch_number);
         send_response(stream, &response).await?;
@@ -323,6 +492,108 @@ impl ProofCoordinator {
         Ok(())
     }
 
+    /// Checks the block summaries hash published by a proof, if any, against
+    /// the blocks of the batch's input. The OnChainProposer rejects a proof
+    /// whose hash doesn't match the one committed with the batch.
+    async fn check_block_summaries(
+        &self,
+        batch_number: u64,
+        batch_proof: &BatchProof,
+    ) -> Result<(), ProofCoordinatorError> {
+        let Some(proven_hash) = decode_block_summaries_hash(&batch_proof.public_values()) else {
+            return Ok(());
+        };
+        let Some(input) = self
+            .rollup_store
+            .get_prover_input_by_batch_and_version(batch_number, &self.git_commit_hash)
+            .await?
+        else {
+            debug!(
+                batch_number,
+                "No stored input to check the block summaries against"
+            );
+            return Ok(());
+        };
+        let summaries = input
+            .blocks
+            .iter()
+            .map(BlockSummary::from_block)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| ProofCoordinatorError::InternalError(err.to_string()))?;
+        if block_summaries_hash(&summaries) != proven_hash {
+            warn!(
+                batch_number,
+                ?proven_hash,
+                blocks = summaries.len(),
+                "Proof published block summaries that don't match the batch"
+            );
+            return Ok(());
+        }
+        let gas_used = summaries.iter().fold(0u64, |total, summary| {
+            total.saturating_add(summary.gas_used)
+        });
+        info!(
+            batch_number,
+            blocks = summaries.len(),
+            gas_used,
+            "Proof published block summaries"
+        );
+        Ok(())
+    }
+
+    /// Feeds a proof's feedback to the batch size controller, logging its decision.
+    /// Feeds the proving time of an accepted proof to the batch size
+    /// controller. The gas used and the backlog come from the coordinator's
+    /// own view of the batch, not from what the prover reports.
+    async fn observe_feedback(
+        &self,
+        batch_number: u64,
+        feedback: ProvingFeedback,
+    ) -> Result<(), ProofCoordinatorError> {
+        let Some(controller) = &self.batch_size_controller else {
+            return Ok(());
+        };
+        let Some(input) = self
+            .rollup_store
+            .get_prover_input_by_batch_and_version(batch_number, &self.git_commit_hash)
+            .await?
+        else {
+            debug!(
+                batch_number,
+                "No stored input to size the proving feedback against"
+            );
+            return Ok(());
+        };
+        let gas_used = input
+            .blocks
+            .iter()
+            .fold(0u64, |gas, block| gas.saturating_add(block.header.gas_used));
+        let latest_batch = self.rollup_store.get_batch_number().await?.unwrap_or(0);
+        let feedback = ProvingFeedback {
+            gas_used,
+            queue_depth: latest_batch.saturating_sub(batch_number),
+            ..feedback
+        };
+        let decision = controller
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .observe(feedback);
+        info!(
+            batch_number,
+            gas_used = feedback.gas_used,
+            cycles = ?feedback.cycles,
+            proving_time_ms = feedback.proving_time_ms,
+            queue_depth = feedback.queue_depth,
+            target_proving_time_ms = decision.target_proving_time_ms,
+            previous_batch_gas = decision.previous_batch_gas,
+            batch_gas = decision.batch_gas,
+            "Batch gas limit {}: {}",
+            if decision.changed() { "adjusted" } else { "kept" },
+            decision.reason
+        );
+        Ok(())
+    }
+
     async fn handle_setup(
         &self,
         stream: &mut TcpStream,
@@ -393,6 +664,19 @@ impl GenServer for ProofCoordinator {
     }
 }
 
+/// Slowest of `proof_types`, the one a batch waits for before it can be
+/// verified.
+fn slowest_proof_type(proof_types: &[ProverType]) -> Option<ProverType> {
+    proof_types
+        .iter()
+        .copied()
+        .max_by_key(|prover_type| match prover_type {
+            ProverType::Exec => 0,
+            ProverType::TDX => 1,
+            ProverType::RISC0 | ProverType::SP1 => 2,
+        })
+}
+
 #[derive(Clone)]
 struct ConnectionHandler {
     proof_coordinator: ProofCoordinator,
@@ -428,29 +712,66 @@ impl ConnectionHandler {
         if let Some(mut stream) = Arc::into_inner(stream) {
             stream.read_to_end(&mut buffer).await?;
 
-            let data: Result<ProofData, _> = serde_json::from_slice(&buffer);
-            match data {
+            match ProofData::decode(&buffer) {
                 Ok(ProofData::BatchRequest {
                     commit_hash,
                     prover_type,
                     supported_programs,
+                    capabilities,
+                    cached_inputs,
                 }) => {
                     if let Err(e) = self
                         .proof_coordinator
-                        .handle_request(&mut stream, commit_hash, prover_type, &supported_programs)
+                        .handle_request(
+                            &mut stream,
+                            commit_hash,
+                            prover_type,
+                            &supported_programs,
+                            capabilities.as_ref(),
+                            &cached_inputs,
+                        )
                         .await
                     {
                         error!("Failed to handle BatchRequest: {e}");
                     }
                 }
+                Ok(ProofData::InputRequest {
+                    commit_hash,
+                    batch_number,
+                    input_hash,
+                }) => {
+                    if let Err(e) = self
+                        .proof_coordinator
+                        .handle_input_request(&mut stream, commit_hash, batch_number, input_hash)
+                        .await
+                    {
+                        error!("Failed to handle InputRequest: {e}");
+                    }
+                }
+                Ok(ProofData::Capabilities { capabilities }) => {
+                    if let Err(e) = self
+                        .proof_coordinator
+                        .handle_capabilities(&mut stream, capabilities)
+                        .await
+                    {
+                        error!("Failed to handle Capabilities: {e}");
+                    }
+                }
                 Ok(ProofData::ProofSubmit {
                     batch_number,
                     batch_proof,
                     program_id,
+                    feedback,
                 }) => {
                     if let Err(e) = self
                         .proof_coordinator
-                        .handle_submit(&mut stream, batch_number, batch_proof, &program_id)
+                        .handle_submit(
+                            &mut stream,
+                            batch_number,
+                            batch_proof,
+                            &program_id,
+                            feedback,
+                        )
                         .await
                     {
                         error!("Failed to handle ProofSubmit: {e}");
@@ -473,6 +794,10 @@ impl ConnectionHandler {
                 }
                 Err(e) => {
                     warn!("Failed to parse request: {e}");
+                    if let Err(e) = send_response(&mut stream, &ProofData::protocol_error(e)).await
+                    {
+                        error!("Failed to send ProtocolError: {e}");
+                    }
                 }
             }
             debug!("Connection closed");
@@ -0,0 +1,393 @@
+//! Scheduling of the L1 verification transactions of batches whose proofs are
+//! complete.
+//!
+//! Each call to [`SubmissionPolicy::step`] advances the submission of one
+//! batch without blocking: it defers sending while L1 fees are above the
+//! configured limits (until the deadline), backs off exponentially after
+//! failures and replaces transactions that aren't included in time with
+//! higher fees. The state it works on is persisted in the rollup store, so
+//! the queue survives restarts.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ethrex_common::{Address, H256, types::TxType};
+use ethrex_l2_common::proof_submission::{PendingVerifyTx, ProofSubmission};
+use ethrex_l2_rpc::signer::Signer;
+use ethrex_l2_sdk::{build_generic_tx, send_generic_transaction};
+use ethrex_rpc::{
+    EthClient,
+    clients::{EthClientError, Overrides},
+    types::block_identifier::{BlockIdentifier, BlockTag},
+};
+use rand::Rng;
+
+use super::configs::{EthConfig, ProofCoordinatorConfig};
+
+/// Current L1 fees, in wei.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct L1Fees {
+    pub base_fee_per_gas: u64,
+    pub max_priority_fee_per_gas: u64,
+}
+
+/// Receipt of an included verification transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VerifyTxReceipt {
+    pub tx_hash: H256,
+    pub succeeded: bool,
+    pub gas_used: u64,
+    pub effective_gas_price: u64,
+}
+
+impl VerifyTxReceipt {
+    /// Fee paid for the transaction, in wei.
+    pub fn cost(&self) -> u128 {
+        u128::from(self.gas_used) * u128::from(self.effective_gas_price)
+    }
+}
+
+/// Access to L1 needed to submit the verification transaction of a batch.
+#[allow(async_fn_in_trait)]
+pub trait VerifyTxClient {
+    async fn current_fees(&self) -> Result<L1Fees, EthClientError>;
+
+    /// Signs and sends the verification transaction with the given fees,
+    /// returning its hash and nonce. Uses the account's next nonce when
+    /// `nonce` is `None`.
+    async fn send_verify_tx(
+        &self,
+        nonce: Option<u64>,
+        max_fee_per_gas: u64,
+        max_priority_fee_per_gas: u64,
+    ) -> Result<(H256, u64), EthClientError>;
+
+    async fn get_receipt(&self, tx_hash: H256) -> Result<Option<VerifyTxReceipt>, EthClientError>;
+}
+
+/// [`VerifyTxClient`] sending a batch's verification calldata through an [`EthClient`].
+pub struct L1VerifyTxClient<'a> {
+    pub eth_client: &'a EthClient,
+    pub signer: &'a Signer,
+    pub to: Address,
+    pub calldata: Vec<u8>,
+}
+
+impl VerifyTxClient for L1VerifyTxClient<'_> {
+    async fn current_fees(&self) -> Result<L1Fees, EthClientError> {
+        let latest_block = self
+            .eth_client
+            .get_block_by_number(BlockIdentifier::Tag(BlockTag::Latest), false)
+            .await?;
+        let max_priority_fee_per_gas = self
+            .eth_client
+            .get_max_priority_fee()
+            .await?
+            .try_into()
+            .map_err(|_| {
+                EthClientError::InternalError(
+                    "Failed to convert max_priority_fee_per_gas to a u64".to_owned(),
+                )
+            })?;
+        Ok(L1Fees {
+            base_fee_per_gas: latest_block.header.base_fee_per_gas.unwrap_or_default(),
+            max_priority_fee_per_gas,
+        })
+    }
+
+    async fn send_verify_tx(
+        &self,
+        nonce: Option<u64>,
+        max_fee_per_gas: u64,
+        max_priority_fee_per_gas: u64,
+    ) -> Result<(H256, u64), EthClientError> {
+        let verify_tx = build_generic_tx(
+            self.eth_client,
+            TxType::EIP1559,
+            self.to,
+            self.signer.address(),
+            self.calldata.clone().into(),
+            Overrides {
+                nonce,
+                max_fee_per_gas: Some(max_fee_per_gas),
+                max_priority_fee_per_gas: Some(max_priority_fee_per_gas),
+                ..Default::default()
+            },
+        )
+        .await?;
+        let nonce = verify_tx.nonce.ok_or(EthClientError::InternalError(
+            "verify transaction has no nonce".to_owned(),
+        ))?;
+        let tx_hash = send_generic_transaction(self.eth_client, verify_tx, self.signer).await?;
+        Ok((tx_hash, nonce))
+    }
+
+    async fn get_receipt(&self, tx_hash: H256) -> Result<Option<VerifyTxReceipt>, EthClientError> {
+        Ok(self
+            .eth_client
+            .get_transaction_receipt(tx_hash)
+            .await?
+            .map(|receipt| VerifyTxReceipt {
+                tx_hash,
+                succeeded: receipt.receipt.status,
+                gas_used: receipt.tx_info.gas_used,
+                effective_gas_price: receipt.tx_info.effective_gas_price,
+            }))
+    }
+}
+
+/// Outcome of a [`SubmissionPolicy::step`].
+#[derive(Debug)]
+pub enum SubmissionStep {
+    /// L1 fees are above the configured limits, nothing was sent.
+    Deferred(L1Fees),
+    /// A previous attempt failed and its backoff hasn't elapsed.
+    BackingOff { next_attempt_at: u64 },
+    /// A verification transaction is waiting to be included. `replaced` is
+    /// set when this step sent it to replace a stuck one.
+    Pending { tx_hash: H256, replaced: bool },
+    /// Sending failed or the transaction reverted. Retried after the backoff.
+    Failed {
+        error: EthClientError,
+        next_attempt_at: u64,
+    },
+    /// The verification transaction was included.
+    Included(VerifyTxReceipt),
+}
+
+/// When and how to send the verification transactions. Times are in seconds.
+#[derive(Debug, Clone)]
+pub struct SubmissionPolicy {
+    /// Submission is deferred while the L1 base fee is above this, in wei.
+    pub max_base_fee_per_gas: Option<u64>,
+    /// Submission is deferred while the suggested priority fee is above this, in wei.
+    pub max_priority_fee_per_gas: Option<u64>,
+    /// Time after the proofs are complete from which fees no longer defer the
+    /// submission, so the batch is verified within the proving window.
+    pub deadline: u64,
+    /// Time without inclusion after which a transaction is replaced.
+    pub stuck_timeout: u64,
+    /// Fee increase of replacement transactions.
+    pub fee_bump_percentage: u64,
+    /// Cap on `max_fee_per_gas` that holds even after the deadline.
+    pub maximum_allowed_max_fee_per_gas: u64,
+    pub backoff_factor: u64,
+    pub min_retry_delay: u64,
+    pub max_retry_delay: u64,
+}
+
+impl SubmissionPolicy {
+    pub fn new(cfg: &ProofCoordinatorConfig, eth_cfg: &EthConfig) -> Self {
+        Self {
+            max_base_fee_per_gas: cfg.max_base_fee_per_gas,
+            max_priority_fee_per_gas: cfg.max_priority_fee_per_gas,
+            deadline: cfg.submission_deadline_secs,
+            stuck_timeout: cfg.stuck_tx_timeout_secs,
+            fee_bump_percentage: cfg.fee_bump_percentage,
+            maximum_allowed_max_fee_per_gas: eth_cfg.maximum_allowed_max_fee_per_gas,
+            backoff_factor: eth_cfg.backoff_factor,
+            min_retry_delay: eth_cfg.min_retry_delay,
+            max_retry_delay: eth_cfg.max_retry_delay,
+        }
+    }
+
+    /// Advances the submission of a batch, updating its state.
+    ///
+    /// Errors are only returned when L1 can't be queried; failures to send
+    /// are reported as [`SubmissionStep::Failed`].
+    pub async fn step<C: VerifyTxClient>(
+        &self,
+        client: &C,
+        submission: &mut ProofSubmission,
+        now: u64,
+    ) -> Result<SubmissionStep, EthClientError> {
+        if let Some(pending) = submission.pending_tx.clone() {
+            return self.step_pending(client, submission, pending, now).await;
+        }
+
+        if now < submission.next_attempt_at {
+            return Ok(SubmissionStep::BackingOff {
+                next_attempt_at: submission.next_attempt_at,
+            });
+        }
+
+        let fees = client.current_fees().await?;
+        if self.defers(submission, fees, now) {
+            submission.deferred_since.get_or_insert(now);
+            return Ok(SubmissionStep::Deferred(fees));
+        }
+        if let Some(since) = submission.deferred_since.take() {
+            submission.deferred_secs = submission
+                .deferred_secs
+                .saturating_add(now.saturating_sub(since));
+        }
+
+        let max_priority_fee_per_gas = fees.max_priority_fee_per_gas;
+        let max_fee_per_gas = fees
+            .base_fee_per_gas
+            .saturating_mul(2)
+            .saturating_add(max_priority_fee_per_gas);
+        let (max_fee_per_gas, max_priority_fee_per_gas) =
+            self.cap_fees(max_fee_per_gas, max_priority_fee_per_gas);
+
+        match client
+            .send_verify_tx(None, max_fee_per_gas, max_priority_fee_per_gas)
+            .await
+        {
+            Ok((tx_hash, nonce)) => {
+                submission.pending_tx = Some(PendingVerifyTx {
+                    nonce,
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas,
+                    tx_hashes: vec![tx_hash],
+                    sent_at: now,
+                });
+                Ok(SubmissionStep::Pending {
+                    tx_hash,
+                    replaced: false,
+                })
+            }
+            Err(error) => Ok(self.fail(submission, error, now)),
+        }
+    }
+
+    async fn step_pending<C: VerifyTxClient>(
+        &self,
+        client: &C,
+        submission: &mut ProofSubmission,
+        mut pending: PendingVerifyTx,
+        now: u64,
+    ) -> Result<SubmissionStep, EthClientError> {
+        // Any of the transactions sent with the nonce may have been included
+        for tx_hash in &pending.tx_hashes {
+            let Some(receipt) = client.get_receipt(*tx_hash).await? else {
+                continue;
+            };
+            submission.pending_tx = None;
+            if receipt.succeeded {
+                submission.failed_attempts = 0;
+                return Ok(SubmissionStep::Included(receipt));
+            }
+            let error = EthClientError::Custom(format!("verify transaction {tx_hash:#x} reverted"));
+            return Ok(self.fail(submission, error, now));
+        }
+
+        let latest_tx_hash = pending.tx_hashes.last().copied().unwrap_or_default();
+        if now.saturating_sub(pending.sent_at) < self.stuck_timeout
+            || now < submission.next_attempt_at
+        {
+            return Ok(SubmissionStep::Pending {
+                tx_hash: latest_tx_hash,
+                replaced: false,
+            });
+        }
+
+        // Stuck or dropped: replace it with higher fees, unless they are over
+        // the limits and the deadline hasn't passed
+        let fees = client.current_fees().await?;
+        if self.defers(submission, fees, now) {
+            return Ok(SubmissionStep::Pending {
+                tx_hash: latest_tx_hash,
+                replaced: false,
+            });
+        }
+        let max_priority_fee_per_gas = self
+            .bump(pending.max_priority_fee_per_gas)
+            .max(fees.max_priority_fee_per_gas);
+        let max_fee_per_gas = self.bump(pending.max_fee_per_gas).max(
+            fees.base_fee_per_gas
+                .saturating_mul(2)
+                .saturating_add(max_priority_fee_per_gas),
+        );
+        let (max_fee_per_gas, max_priority_fee_per_gas) =
+            self.cap_fees(max_fee_per_gas, max_priority_fee_per_gas);
+        if max_fee_per_gas <= pending.max_fee_per_gas {
+            // Already at the fee cap, a replacement would be rejected
+            return Ok(SubmissionStep::Pending {
+                tx_hash: latest_tx_hash,
+                replaced: false,
+            });
+        }
+
+        match client
+            .send_verify_tx(
+                Some(pending.nonce),
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            )
+            .await
+        {
+            Ok((tx_hash, _)) => {
+                pending.max_fee_per_gas = max_fee_per_gas;
+                pending.max_priority_fee_per_gas = max_priority_fee_per_gas;
+                pending.tx_hashes.push(tx_hash);
+                pending.sent_at = now;
+                submission.pending_tx = Some(pending);
+                Ok(SubmissionStep::Pending {
+                    tx_hash,
+                    replaced: true,
+                })
+            }
+            // The pending transactions stay tracked, one of them may still be included
+            Err(error) => Ok(self.fail(submission, error, now)),
+        }
+    }
+
+    /// Whether the fees defer the submission at `now`.
+    fn defers(&self, submission: &ProofSubmission, fees: L1Fees, now: u64) -> bool {
+        let past_deadline = now >= submission.queued_at.saturating_add(self.deadline);
+        let base_fee_too_high = self
+            .max_base_fee_per_gas
+            .is_some_and(|max| fees.base_fee_per_gas > max);
+        let priority_fee_too_high = self
+            .max_priority_fee_per_gas
+            .is_some_and(|max| fees.max_priority_fee_per_gas > max);
+        !past_deadline && (base_fee_too_high || priority_fee_too_high)
+    }
+
+    fn bump(&self, fee: u64) -> u64 {
+        fee.saturating_mul(100 + self.fee_bump_percentage) / 100
+    }
+
+    fn cap_fees(&self, max_fee_per_gas: u64, max_priority_fee_per_gas: u64) -> (u64, u64) {
+        let max_fee_per_gas = max_fee_per_gas.min(self.maximum_allowed_max_fee_per_gas);
+        (
+            max_fee_per_gas,
+            max_priority_fee_per_gas.min(max_fee_per_gas),
+        )
+    }
+
+    fn fail(
+        &self,
+        submission: &mut ProofSubmission,
+        error: EthClientError,
+        now: u64,
+    ) -> SubmissionStep {
+        submission.failed_attempts = submission.failed_attempts.saturating_add(1);
+        submission.next_attempt_at = now.saturating_add(self.backoff(submission.failed_attempts));
+        SubmissionStep::Failed {
+            error,
+            next_attempt_at: submission.next_attempt_at,
+        }
+    }
+
+    /// Delay before the next attempt after `failed_attempts` failures: grows
+    /// exponentially, with a random jitter of up to half of it so that
+    /// retries don't synchronize.
+    pub fn backoff(&self, failed_attempts: u64) -> u64 {
+        let exponent = u32::try_from(failed_attempts).unwrap_or(u32::MAX);
+        let delay = self.backoff_factor.saturating_pow(exponent).clamp(
+            self.min_retry_delay,
+            self.max_retry_delay.max(self.min_retry_delay),
+        );
+        let half = delay / 2;
+        delay - half + rand::thread_rng().gen_range(0..=half)
+    }
+}
+
+/// Current unix time in seconds.
+pub fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|now| now.as_secs())
+        .unwrap_or_default()
+}
@@ -9,7 +9,10 @@ use ethrex_common::{
         fee_config::FeeConfig,
     },
 };
-use ethrex_l2_common::prover::{BatchProof, ProverInputData, ProverType};
+use ethrex_l2_common::{
+    proof_submission::ProofSubmission,
+    prover::{BatchProof, ProverInputData, ProverType},
+};
 
 use crate::error::RollupStoreError;
 
@@ -186,6 +189,21 @@ pub trait StoreEngineRollup: Debug + Send + Sync {
         prover_version: &str,
     ) -> Result<Option<ProverInputData>, RollupStoreError>;
 
+    /// Hash of the encoded prover input, computed when the input is stored.
+    async fn get_prover_input_hash_by_batch_and_version(
+        &self,
+        batch_number: u64,
+        prover_version: &str,
+    ) -> Result<Option<H256>, RollupStoreError>;
+
+    /// Stores the hash of a prover input stored before hashes were persisted.
+    async fn store_prover_input_hash_by_batch_and_version(
+        &self,
+        batch_number: u64,
+        prover_version: &str,
+        input_hash: H256,
+    ) -> Result<(), RollupStoreError>;
+
     async fn store_fee_config_by_block(
         &self,
         block_number: BlockNumber,
@@ -207,4 +225,21 @@ pub trait StoreEngineRollup: Debug + Send + Sync {
         &self,
         batch_number: u64,
     ) -> Result<Option<String>, RollupStoreError>;
+
+    /// Stores the L1 submission state of a batch's proofs.
+    async fn store_proof_submission_by_batch(
+        &self,
+        batch_number: u64,
+        submission: ProofSubmission,
+    ) -> Result<(), RollupStoreError>;
+
+    async fn get_proof_submission_by_batch(
+        &self,
+        batch_number: u64,
+    ) -> Result<Option<ProofSubmission>, RollupStoreError>;
+
+    async fn delete_proof_submission_by_batch(
+        &self,
+        batch_number: u64,
+    ) -> Result<(), RollupStoreError>;
 }
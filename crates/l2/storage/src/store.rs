@@ -12,7 +12,10 @@ use ethrex_common::{
         batch::Batch, fee_config::FeeConfig,
     },
 };
-use ethrex_l2_common::prover::{BatchProof, ProverInputData, ProverType};
+use ethrex_l2_common::{
+    proof_submission::ProofSubmission,
+    prover::{BatchProof, ProverInputData, ProverType},
+};
 use tracing::info;
 
 #[derive(Debug, Clone)]
@@ -207,14 +210,14 @@ impl Store {
                     .to_owned(),
             ))?;
 
-        let blobs_bundle = BlobsBundle::create_from_blobs(
+        let blobs_bundle = BlobsBundle::create_for_fork(
             // Currently validium mode doesn't generate blobs, so no one will be stored
             // TODO: If/When that behaviour change, this should throw error on None
             &self
                 .get_blobs_by_batch(batch_number)
                 .await?
                 .unwrap_or_default(),
-                if fork <= Fork::Prague { None } else { Some(1) },
+            fork,
         ).map_err(|e| {
             RollupStoreError::Custom(format!("Failed to create blobs bundle from blob while getting batch from database: {e}. This is a bug"))
         })?;
@@ -456,6 +459,29 @@ impl Store {
             .await
     }
 
+    /// Hash of the encoded prover input, computed when the input is stored.
+    /// Inputs stored before hashes were persisted have none.
+    pub async fn get_prover_input_hash_by_batch_and_version(
+        &self,
+        batch_number: u64,
+        prover_version: &str,
+    ) -> Result<Option<H256>, RollupStoreError> {
+        self.engine
+            .get_prover_input_hash_by_batch_and_version(batch_number, prover_version)
+            .await
+    }
+
+    pub async fn store_prover_input_hash_by_batch_and_version(
+        &self,
+        batch_number: u64,
+        prover_version: &str,
+        input_hash: H256,
+    ) -> Result<(), RollupStoreError> {
+        self.engine
+            .store_prover_input_hash_by_batch_and_version(batch_number, prover_version, input_hash)
+            .await
+    }
+
     pub async fn store_fee_config_by_block(
         &self,
         block_number: BlockNumber,
@@ -488,4 +514,35 @@ impl Store {
     ) -> Result<Option<String>, RollupStoreError> {
         self.engine.get_program_id_by_batch(batch_number).await
     }
+
+    /// Stores the L1 submission state of a batch's proofs
+    pub async fn store_proof_submission_by_batch(
+        &self,
+        batch_number: u64,
+        submission: ProofSubmission,
+    ) -> Result<(), RollupStoreError> {
+        self.engine
+            .store_proof_submission_by_batch(batch_number, submission)
+            .await
+    }
+
+    /// Returns the L1 submission state of a batch's proofs, if they are queued
+    pub async fn get_proof_submission_by_batch(
+        &self,
+        batch_number: u64,
+    ) -> Result<Option<ProofSubmission>, RollupStoreError> {
+        self.engine
+            .get_proof_submission_by_batch(batch_number)
+            .await
+    }
+
+    /// Removes a batch from the proof submission queue
+    pub async fn delete_proof_submission_by_batch(
+        &self,
+        batch_number: u64,
+    ) -> Result<(), RollupStoreError> {
+        self.engine
+            .delete_proof_submission_by_batch(batch_number)
+            .await
+    }
 }
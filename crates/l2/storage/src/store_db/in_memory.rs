@@ -12,7 +12,10 @@ use ethrex_common::{
         fee_config::FeeConfig,
     },
 };
-use ethrex_l2_common::prover::{BatchProof, ProverInputData, ProverType};
+use ethrex_l2_common::{
+    proof_submission::ProofSubmission,
+    prover::{BatchProof, ProverInputData, ProverType, prover_input_hash},
+};
 
 use crate::api::StoreEngineRollup;
 
@@ -57,10 +60,14 @@ struct StoreInner {
     verify_txs: HashMap<u64, H256>,
     /// Map of (batch_number, prover_version) to serialized prover input data
     batch_prover_input: HashMap<(u64, String), Vec<u8>>,
+    /// Map of (batch_number, prover_version) to the hash of the serialized prover input
+    batch_prover_input_hash: HashMap<(u64, String), H256>,
     /// Map of block number to FeeConfig
     fee_config_by_block: HashMap<BlockNumber, FeeConfig>,
     /// Map of batch number to guest program ID
     program_id_by_batch: HashMap<u64, String>,
+    /// Map of batch number to the L1 submission state of its proofs
+    proof_submissions: HashMap<u64, ProofSubmission>,
 }
 
 impl Store {
@@ -335,6 +342,12 @@ impl StoreEngineRollup for Store {
         store
             .batch_prover_input
             .retain(|(batch, _), _| *batch <= batch_number);
+        store
+            .batch_prover_input_hash
+            .retain(|(batch, _), _| *batch <= batch_number);
+        store
+            .proof_submissions
+            .retain(|batch, _| *batch <= batch_number);
         Ok(())
     }
 
@@ -420,13 +433,17 @@ impl StoreEngineRollup for Store {
         prover_version: &str,
         prover_input: ProverInputData,
     ) -> Result<(), RollupStoreError> {
-        let witness_bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&prover_input)
-            .map_err(|e| RollupStoreError::Custom(format!("Failed to serialize witness: {}", e)))?
-            .to_vec();
+        let witness_bytes = prover_input
+            .encode()
+            .map_err(|e| RollupStoreError::Custom(format!("Failed to serialize witness: {}", e)))?;
 
-        self.inner()?
-            .batch_prover_input
-            .insert((batch_number, prover_version.to_string()), witness_bytes);
+        let input_hash = prover_input_hash(&witness_bytes);
+        let mut inner = self.inner()?;
+        let key = (batch_number, prover_version.to_string());
+        inner
+            .batch_prover_input_hash
+            .insert(key.clone(), input_hash);
+        inner.batch_prover_input.insert(key, witness_bytes);
 
         Ok(())
     }
@@ -445,16 +462,39 @@ impl StoreEngineRollup for Store {
             return Ok(None);
         };
 
-        let prover_input = rkyv::from_bytes::<ProverInputData, rkyv::rancor::Error>(&witness_bytes)
-            .map_err(|e| {
-                RollupStoreError::Custom(format!(
-                    "Failed to deserialize prover input for batch {batch_number} and version {prover_version}: {e}",
-                ))
-            })?;
+        let prover_input = ProverInputData::decode(&witness_bytes).map_err(|e| {
+            RollupStoreError::Custom(format!(
+                "Failed to deserialize prover input for batch {batch_number} and version {prover_version}: {e}",
+            ))
+        })?;
 
         Ok(Some(prover_input))
     }
 
+    async fn get_prover_input_hash_by_batch_and_version(
+        &self,
+        batch_number: u64,
+        prover_version: &str,
+    ) -> Result<Option<H256>, RollupStoreError> {
+        Ok(self
+            .inner()?
+            .batch_prover_input_hash
+            .get(&(batch_number, prover_version.to_string()))
+            .copied())
+    }
+
+    async fn store_prover_input_hash_by_batch_and_version(
+        &self,
+        batch_number: u64,
+        prover_version: &str,
+        input_hash: H256,
+    ) -> Result<(), RollupStoreError> {
+        self.inner()?
+            .batch_prover_input_hash
+            .insert((batch_number, prover_version.to_string()), input_hash);
+        Ok(())
+    }
+
     async fn store_fee_config_by_block(
         &self,
         block_number: BlockNumber,
@@ -498,6 +538,32 @@ impl StoreEngineRollup for Store {
             .get(&batch_number)
             .cloned())
     }
+
+    async fn store_proof_submission_by_batch(
+        &self,
+        batch_number: u64,
+        submission: ProofSubmission,
+    ) -> Result<(), RollupStoreError> {
+        self.inner()?
+            .proof_submissions
+            .insert(batch_number, submission);
+        Ok(())
+    }
+
+    async fn get_proof_submission_by_batch(
+        &self,
+        batch_number: u64,
+    ) -> Result<Option<ProofSubmission>, RollupStoreError> {
+        Ok(self.inner()?.proof_submissions.get(&batch_number).cloned())
+    }
+
+    async fn delete_proof_submission_by_batch(
+        &self,
+        batch_number: u64,
+    ) -> Result<(), RollupStoreError> {
+        self.inner()?.proof_submissions.remove(&batch_number);
+        Ok(())
+    }
 }
 
 impl Debug for Store {
@@ -10,7 +10,10 @@ use ethrex_common::{
         batch::Batch, fee_config::FeeConfig,
     },
 };
-use ethrex_l2_common::prover::{BatchProof, ProverInputData, ProverType};
+use ethrex_l2_common::{
+    proof_submission::ProofSubmission,
+    prover::{BatchProof, ProverInputData, ProverType, prover_input_hash},
+};
 
 use libsql::{
     Builder, Connection, Row, Rows, Transaction, Value,
@@ -32,7 +35,7 @@ impl Debug for SQLStore {
     }
 }
 
-const DB_SCHEMA: [&str; 21] = [
+const DB_SCHEMA: [&str; 23] = [
     "CREATE TABLE IF NOT EXISTS blocks (block_number INT PRIMARY KEY, batch INT)",
     "CREATE TABLE IF NOT EXISTS l1_messages (batch INT, idx INT, message_hash BLOB, PRIMARY KEY (batch, idx))",
     "CREATE TABLE IF NOT EXISTS l2_rolling_hashes (batch INT PRIMARY KEY, value BLOB)",
@@ -52,8 +55,10 @@ const DB_SCHEMA: [&str; 21] = [
     "CREATE TABLE IF NOT EXISTS block_signatures (block_hash BLOB PRIMARY KEY, signature BLOB)",
     "CREATE TABLE IF NOT EXISTS batch_signatures (batch INT PRIMARY KEY, signature BLOB)",
     "CREATE TABLE IF NOT EXISTS batch_prover_input (batch INT, prover_version TEXT, prover_input BLOB, PRIMARY KEY (batch, prover_version))",
+    "CREATE TABLE IF NOT EXISTS batch_prover_input_hash (batch INT, prover_version TEXT, input_hash BLOB, PRIMARY KEY (batch, prover_version))",
     "CREATE TABLE IF NOT EXISTS fee_config (block_number INT PRIMARY KEY, fee_config BLOB)",
     "CREATE TABLE IF NOT EXISTS batch_program_id (batch INT PRIMARY KEY, program_id TEXT NOT NULL)",
+    "CREATE TABLE IF NOT EXISTS proof_submissions (batch INT PRIMARY KEY, submission BLOB)",
 ];
 
 impl SQLStore {
@@ -368,16 +373,27 @@ impl SQLStore {
         prover_input: ProverInputData,
         db_tx: Option<&Transaction>,
     ) -> Result<(), RollupStoreError> {
-        let prover_input_bytes = rkyv::to_bytes::<rkyv::rancor::Error>(&prover_input)
-            .map_err(|e| {
-                RollupStoreError::Custom(format!("Failed to serialize prover input: {e}"))
-            })?
-            .to_vec();
+        let prover_input_bytes = prover_input.encode().map_err(|e| {
+            RollupStoreError::Custom(format!("Failed to serialize prover input: {e}"))
+        })?;
 
-        let queries = vec![(
-            "INSERT OR REPLACE INTO batch_prover_input VALUES (?1, ?2, ?3)",
-            (batch_number, prover_version, prover_input_bytes).into_params()?,
-        )];
+        let input_hash = prover_input_hash(&prover_input_bytes);
+
+        let queries = vec![
+            (
+                "INSERT OR REPLACE INTO batch_prover_input VALUES (?1, ?2, ?3)",
+                (batch_number, prover_version, prover_input_bytes).into_params()?,
+            ),
+            (
+                "INSERT OR REPLACE INTO batch_prover_input_hash VALUES (?1, ?2, ?3)",
+                (
+                    batch_number,
+                    prover_version,
+                    Vec::from(input_hash.to_fixed_bytes()),
+                )
+                    .into_params()?,
+            ),
+        ];
 
         self.execute_in_tx(queries, db_tx).await
     }
@@ -838,6 +854,14 @@ impl StoreEngineRollup for SQLStore {
                 "DELETE FROM batch_prover_input WHERE batch > ?1",
                 [batch_number].into_params()?,
             ),
+            (
+                "DELETE FROM batch_prover_input_hash WHERE batch > ?1",
+                [batch_number].into_params()?,
+            ),
+            (
+                "DELETE FROM proof_submissions WHERE batch > ?1",
+                [batch_number].into_params()?,
+            ),
         ];
         self.execute_in_tx(queries, None).await
     }
@@ -1054,18 +1078,52 @@ impl StoreEngineRollup for SQLStore {
         if let Some(row) = rows.next().await? {
             let vec = read_from_row_blob(&row, 0)?;
 
-            let prover_input = rkyv::from_bytes::<ProverInputData, rkyv::rancor::Error>(&vec)
-                .map_err(|e| {
-                    RollupStoreError::Custom(format!(
-                        "Failed to deserialize prover input for batch {batch_number} and version {prover_version}: {e}",
-                    ))
-                })?;
+            let prover_input = ProverInputData::decode(&vec).map_err(|e| {
+                RollupStoreError::Custom(format!(
+                    "Failed to deserialize prover input for batch {batch_number} and version {prover_version}: {e}",
+                ))
+            })?;
 
             return Ok(Some(prover_input));
         }
         Ok(None)
     }
 
+    async fn get_prover_input_hash_by_batch_and_version(
+        &self,
+        batch_number: u64,
+        prover_version: &str,
+    ) -> Result<Option<H256>, RollupStoreError> {
+        let mut rows = self
+            .query(
+                "SELECT input_hash FROM batch_prover_input_hash WHERE batch = ?1 AND prover_version = ?2",
+                (batch_number, prover_version),
+            )
+            .await?;
+        if let Some(row) = rows.next().await? {
+            let vec = read_from_row_blob(&row, 0)?;
+            return Ok(Some(H256::from_slice(&vec)));
+        }
+        Ok(None)
+    }
+
+    async fn store_prover_input_hash_by_batch_and_version(
+        &self,
+        batch_number: u64,
+        prover_version: &str,
+        input_hash: H256,
+    ) -> Result<(), RollupStoreError> {
+        self.execute(
+            "INSERT OR REPLACE INTO batch_prover_input_hash VALUES (?1, ?2, ?3)",
+            (
+                batch_number,
+                prover_version,
+                Vec::from(input_hash.to_fixed_bytes()),
+            ),
+        )
+        .await
+    }
+
     async fn store_fee_config_by_block(
         &self,
         block_number: BlockNumber,
@@ -1133,4 +1191,51 @@ impl StoreEngineRollup for SQLStore {
         }
         Ok(None)
     }
+
+    async fn store_proof_submission_by_batch(
+        &self,
+        batch_number: u64,
+        submission: ProofSubmission,
+    ) -> Result<(), RollupStoreError> {
+        let serialized_submission = bincode::serialize(&submission)?;
+        self.execute_in_tx(
+            vec![(
+                "INSERT OR REPLACE INTO proof_submissions VALUES (?1, ?2)",
+                (batch_number, serialized_submission).into_params()?,
+            )],
+            None,
+        )
+        .await
+    }
+
+    async fn get_proof_submission_by_batch(
+        &self,
+        batch_number: u64,
+    ) -> Result<Option<ProofSubmission>, RollupStoreError> {
+        let mut rows = self
+            .query(
+                "SELECT submission FROM proof_submissions WHERE batch = ?1",
+                vec![batch_number],
+            )
+            .await?;
+        if let Some(row) = rows.next().await? {
+            let vec = read_from_row_blob(&row, 0)?;
+            return Ok(Some(bincode::deserialize(&vec)?));
+        }
+        Ok(None)
+    }
+
+    async fn delete_proof_submission_by_batch(
+        &self,
+        batch_number: u64,
+    ) -> Result<(), RollupStoreError> {
+        self.execute_in_tx(
+            vec![(
+                "DELETE FROM proof_submissions WHERE batch = ?1",
+                [batch_number].into_params()?,
+            )],
+            None,
+        )
+        .await
+    }
 }
@@ -15,6 +15,8 @@ pub async fn get_batch(commit_hash: String) -> Result<(u64, ProgramInput), Strin
         commit_hash: commit_hash.clone(),
         prover_type: ProverType::TDX,
         supported_programs: Vec::new(),
+        capabilities: None,
+        cached_inputs: Vec::new(),
     })
     .await
     .map_err(|e| format!("Failed to get Response: {e}"))?;
@@ -34,12 +36,12 @@ pub async fn get_batch(commit_hash: String) -> Result<(u64, ProgramInput), Strin
                     blob_proof: input.blob_proof,
                     fee_configs: input.fee_configs,
                     native_token_scale_factor: input.native_token_scale_factor,
+                    ordering_commitment: input.ordering_commitment,
+                    publish_block_summaries: input.publish_block_summaries,
+                    commit_deposit_inclusion: input.commit_deposit_inclusion,
                 };
                 #[cfg(not(feature = "l2"))]
-                let input = ProgramInput {
-                    blocks: input.blocks,
-                    execution_witness: input.execution_witness,
-                };
+                let input = ProgramInput::new(input.blocks, input.execution_witness);
                 Ok((batch_number, input))
             }
             _ => Err("No blocks to prove.".to_owned()),
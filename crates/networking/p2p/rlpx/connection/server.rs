@@ -995,7 +995,15 @@ async fn handle_incoming_message(
             if let Some(eth) = &state.negotiated_eth_capability {
                 let mut receipts = Vec::new();
                 for hash in block_hashes.iter() {
-                    receipts.push(state.storage.get_receipts_for_block(hash).await?);
+                    match state.storage.get_receipts_for_block(hash).await {
+                        Ok(block_receipts) => receipts.push(block_receipts),
+                        // The response ends at the first pruned block, so the peer asks someone else
+                        Err(StoreError::PrunedHistory(number)) => {
+                            trace!(peer=%state.node, "Not serving pruned receipts of block {number}");
+                            break;
+                        }
+                        Err(err) => return Err(err.into()),
+                    }
                 }
                 let response = match eth.version {
                     68 => Message::Receipts68(Receipts68::new(id, receipts)),
@@ -1086,10 +1094,7 @@ async fn handle_incoming_message(
                 #[cfg(not(feature = "l2"))]
                 let is_l2_mode = false;
                 if let Err(error) = msg.handle(&state.node, &state.blockchain, is_l2_mode).await {
-                    if matches!(
-                        error,
-                        ethrex_blockchain::error::MempoolError::BlobsBundleError(_)
-                    ) {
+                    if error.is_invalid_blobs_bundle() {
                         warn!(
                             peer=%state.node,
                             reason=%error,
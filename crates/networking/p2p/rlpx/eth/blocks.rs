@@ -269,6 +269,11 @@ impl GetBlockBodies {
                 Ok(None) => {
                     continue;
                 }
+                // The response ends at the first pruned block, so the peer asks someone else
+                Err(StoreError::PrunedHistory(number)) => {
+                    trace!("Not serving pruned block body {number}");
+                    break;
+                }
                 Err(err) => {
                     error!(
                         "Error accessing DB while building block bodies response for peer: {err}"
@@ -287,7 +287,7 @@ impl PooledTransactions {
                     .add_blob_transaction_to_pool(itx.tx, itx.blobs_bundle)
                     .await
                 {
-                    if matches!(e, MempoolError::BlobsBundleError(_)) {
+                    if e.is_invalid_blobs_bundle() {
                         return Err(e);
                     }
                     debug!(
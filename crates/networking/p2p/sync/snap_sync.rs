@@ -236,7 +236,17 @@ pub async fn sync_cycle_snap(
         };
     }
 
-    snap_sync(peers, &store, &mut block_sync_state, datadir).await?;
+    // Snap sync writes the state straight into the trie tables, which the account filter
+    // doesn't follow, so it's rebuilt from the synced state afterwards
+    let account_filter_enabled = store.disable_account_filter()?;
+    let result = snap_sync(peers, &store, &mut block_sync_state, datadir).await;
+    if account_filter_enabled {
+        info!("Rebuilding the account filter");
+        let store = store.clone();
+        let accounts = tokio::task::spawn_blocking(move || store.enable_account_filter()).await??;
+        info!("Account filter rebuilt for {accounts} accounts");
+    }
+    result?;
 
     store.clear_snap_state().await?;
     snap_enabled.store(false, Ordering::Relaxed);
@@ -6,6 +6,7 @@ use ethrex_common::types::{Block, BlockBody, BlockHash, BlockNumber, Fork};
 use ethrex_common::{H256, U256};
 use ethrex_p2p::sync::SyncMode;
 use ethrex_rlp::error::RLPDecodeError;
+use ethrex_storage::error::StoreError;
 use serde_json::Value;
 use tokio::sync::oneshot;
 use tracing::{debug, error, info, warn};
@@ -608,7 +609,12 @@ impl RpcHandler for GetPayloadBodiesByHashV1Request {
         }
         let mut bodies = Vec::new();
         for hash in self.hashes.iter() {
-            bodies.push(context.storage.get_block_body_by_hash(*hash).await?)
+            // The spec returns null for bodies the node no longer holds
+            let body = match context.storage.get_block_body_by_hash(*hash).await {
+                Err(StoreError::PrunedHistory(_)) => None,
+                body => body?,
+            };
+            bodies.push(body)
         }
         build_payload_body_response(bodies)
     }
@@ -830,11 +836,14 @@ fn validate_execution_payload_v3(payload: &ExecutionPayload) -> Result<(), RpcEr
 #[inline]
 fn validate_execution_payload_v4(payload: &ExecutionPayload) -> Result<(), RpcErr> {
     // This method follows the same specification as `engine_newPayloadV4` additionally
-    // rejects payload without block access list
+    // rejects payload without block access list or slot number
 
     if payload.block_access_list.is_none() {
         return Err(RpcErr::WrongParam("block_access_list".to_string()));
     }
+    if payload.slot_number.is_none() {
+        return Err(RpcErr::WrongParam("slot_number".to_string()));
+    }
 
     validate_execution_payload_v3(payload)?;
 
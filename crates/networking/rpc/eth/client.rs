@@ -6,7 +6,7 @@ use ethrex_common::serde_utils;
 use ethrex_common::types::Fork;
 use ethrex_common::types::ForkBlobSchedule;
 use ethrex_common::types::ForkId;
-use ethrex_vm::{precompiles_for_fork, system_contracts::system_contracts_for_fork};
+use ethrex_vm::{precompiles_for_fork, system_contracts::system_contracts_for_chain};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tracing::debug;
@@ -159,7 +159,7 @@ async fn get_config_for_fork(
         H32::zero()
     };
     let mut system_contracts = BTreeMap::new();
-    for contract in system_contracts_for_fork(fork) {
+    for contract in system_contracts_for_chain(&chain_config, fork) {
         system_contracts.insert(contract.name.to_string(), contract.address);
     }
 
@@ -4,21 +4,29 @@ use crate::{
     eth::block,
     rpc::{RpcApiContext, RpcHandler},
     types::{
-        block_identifier::{BlockIdentifier, BlockIdentifierOrHash},
+        block_identifier::{BlockIdentifier, BlockIdentifierOrHash, BlockTag},
         transaction::{RpcTransaction, SendRawTransactionRequest},
     },
     utils::RpcErr,
 };
-use ethrex_blockchain::{Blockchain, vm::StoreVmDatabase};
+use ethrex_blockchain::{Blockchain, payload::pending_block_randao, vm::StoreVmDatabase};
 use ethrex_common::{
     H256, U256,
-    types::{AccessListEntry, BlockHash, BlockHeader, BlockNumber, GenericTransaction, TxKind},
+    constants::POST_OSAKA_GAS_LIMIT_CAP,
+    types::{
+        AccessListEntry, BlockHash, BlockHeader, BlockNumber, Fork, GenericTransaction,
+        NormalizationContext, TxKind,
+    },
 };
 
 use ethrex_rlp::encode::RLPEncode;
 use ethrex_storage::Store;
 
-use ethrex_vm::{ExecutionResult, backends::levm::get_max_allowed_gas_limit};
+use ethrex_vm::{
+    DEFAULT_SIMULATION_OUTPUT_CAP, EvmError, ExecutionResult, RevertReason, RpcErrorPayload,
+    backends::levm::{effective_gas_limit, get_max_allowed_gas_limit},
+    intrinsic_gas,
+};
 use serde::Serialize;
 
 use serde_json::Value;
@@ -26,7 +34,6 @@ use tracing::debug;
 
 pub const ESTIMATE_ERROR_RATIO: f64 = 0.015;
 pub const CALL_STIPEND: u64 = 2_300; // Free gas given at beginning of call.
-pub const TRANSACTION_GAS: u64 = 21_000; // Per transaction not creating a contract. NOTE: Not payable on data of calls between transactions.
 
 pub struct CallRequest {
     transaction: GenericTransaction,
@@ -109,13 +116,30 @@ impl RpcHandler for CallRequest {
             // Block not found
             _ => return Ok(Value::Null),
         };
+        let transaction =
+            normalize_transaction(&self.transaction, &header, &context.storage).await?;
+        let randao_override = match &block {
+            BlockIdentifierOrHash::Identifier(block) => {
+                pending_randao_override(block, &header, &context.storage, &context.blockchain)
+                    .await?
+            }
+            BlockIdentifierOrHash::Hash(_) => None,
+        };
         // Run transaction
         let result = simulate_tx(
-            &self.transaction,
+            &transaction,
             &header,
+            randao_override,
             context.storage,
             context.blockchain,
         )?;
+        if let Some(truncated) = result.truncated_output() {
+            let limit = DEFAULT_SIMULATION_OUTPUT_CAP;
+            return Err(RpcErr::Internal(format!(
+                "Call output of {} bytes exceeds the {limit} bytes limit, hash {:#x}",
+                truncated.len, truncated.hash
+            )));
+        }
         serde_json::to_value(format!("0x{:#x}", result.output()))
             .map_err(|error| RpcErr::Internal(error.to_string()))
     }
@@ -349,11 +373,14 @@ impl RpcHandler for CreateAccessListRequest {
             _ => return Ok(Value::Null),
         };
 
+        let transaction =
+            normalize_transaction(&self.transaction, &header, &context.storage).await?;
+
         let vm_db = StoreVmDatabase::new(context.storage.clone(), header.clone())?;
         let mut vm = context.blockchain.new_evm(vm_db)?;
 
         // Run transaction and obtain access list
-        let (gas_used, access_list, error) = vm.create_access_list(&self.transaction, &header)?;
+        let (gas_used, access_list, error) = vm.create_access_list(&transaction, &header)?;
         let result = AccessListResult {
             access_list: access_list
                 .into_iter()
@@ -450,19 +477,14 @@ impl RpcHandler for EstimateGasRequest {
         };
 
         let current_fork = chain_config.fork(block_header.timestamp);
+        let randao_override =
+            pending_randao_override(&block, &block_header, storage, blockchain).await?;
 
-        let transaction = match self.transaction.nonce {
-            Some(_nonce) => self.transaction.clone(),
-            None => {
-                let transaction_nonce = storage
-                    .get_nonce_by_account_address(block_header.number, self.transaction.from)
-                    .await?;
-
-                let mut cloned_transaction = self.transaction.clone();
-                cloned_transaction.nonce = transaction_nonce;
-                cloned_transaction
-            }
-        };
+        // A gas limit above the cap is rejected instead of estimating with a lower one
+        let transaction = normalize_transaction(&self.transaction, &block_header, storage).await?;
+        let gas_limit = transaction
+            .gas
+            .unwrap_or_else(|| get_max_allowed_gas_limit(block_header.gas_limit, current_fork));
 
         // If the transaction is a plain value transfer, short circuit estimation.
         if let TxKind::Call(address) = transaction.to {
@@ -471,27 +493,31 @@ impl RpcHandler for EstimateGasRequest {
                 .await?;
             let code = account_info.map(|info| storage.get_account_code(info.code_hash));
             if code.is_none() {
+                // Calldata, access lists and the Prague floor add to the 21000 of a transfer
+                let transfer_gas = intrinsic_gas((&transaction).into(), current_fork)
+                    .map_err(EvmError::from)?
+                    .min_gas_limit();
                 let mut value_transfer_transaction = transaction.clone();
-                value_transfer_transaction.gas = Some(TRANSACTION_GAS);
+                value_transfer_transaction.gas = Some(transfer_gas);
                 let result: Result<ExecutionResult, RpcErr> = simulate_tx(
                     &value_transfer_transaction,
                     &block_header,
+                    randao_override,
                     storage.clone(),
                     blockchain.clone(),
                 );
                 if let Ok(ExecutionResult::Success { .. }) = result {
-                    return serde_json::to_value(format!("{TRANSACTION_GAS:#x}"))
+                    return serde_json::to_value(format!("{transfer_gas:#x}"))
                         .map_err(|error| RpcErr::Internal(error.to_string()));
                 }
             }
         }
 
         // Prepare binary search
-        let highest_gas_limit = get_max_allowed_gas_limit(block_header.gas_limit, current_fork);
-        let mut highest_gas_limit = match transaction.gas {
-            Some(gas) => gas.min(highest_gas_limit),
-            None => highest_gas_limit,
-        };
+        let mut highest_gas_limit = gas_limit.min(get_max_allowed_gas_limit(
+            block_header.gas_limit,
+            current_fork,
+        ));
 
         if transaction.gas_price != 0 {
             highest_gas_limit = recap_with_account_balances(
@@ -503,15 +529,30 @@ impl RpcHandler for EstimateGasRequest {
             .await?;
         }
 
+        // Whether the estimate is bound by the EIP-7825 cap rather than by the block or the sender
+        let bound_by_cap =
+            current_fork >= Fork::Osaka && highest_gas_limit == POST_OSAKA_GAS_LIMIT_CAP;
+
         // Check whether the execution is possible
         let mut transaction = transaction.clone();
         transaction.gas = Some(highest_gas_limit);
-        let result = simulate_tx(
+        let result = match simulate_tx(
             &transaction,
             &block_header,
+            randao_override,
             storage.clone(),
             blockchain.clone(),
-        )?;
+        ) {
+            Err(RpcErr::Halt { reason, gas_used }) if bound_by_cap => {
+                return Err(RpcErr::Halt {
+                    reason: format!(
+                        "{reason}, gas required exceeds the transaction gas limit cap of {POST_OSAKA_GAS_LIMIT_CAP}"
+                    ),
+                    gas_used,
+                });
+            }
+            result => result?,
+        };
 
         let gas_used = result.gas_used();
         let gas_refunded = result.gas_refunded();
@@ -537,6 +578,7 @@ impl RpcHandler for EstimateGasRequest {
             let result = simulate_tx(
                 &transaction,
                 &block_header,
+                randao_override,
                 storage.clone(),
                 blockchain.clone(),
             );
@@ -548,6 +590,13 @@ impl RpcHandler for EstimateGasRequest {
             middle_gas_limit = (highest_gas_limit + lowest_gas_limit) / 2;
         }
 
+        // The estimate is clamped rather than measured, let the caller know
+        if bound_by_cap && highest_gas_limit == POST_OSAKA_GAS_LIMIT_CAP {
+            return Err(RpcErr::Vm(RpcErrorPayload::gas_limit_cap_reached(
+                POST_OSAKA_GAS_LIMIT_CAP,
+            )));
+        }
+
         serde_json::to_value(format!("{highest_gas_limit:#x}"))
             .map_err(|error| RpcErr::Internal(error.to_string()))
     }
@@ -569,24 +618,82 @@ async fn recap_with_account_balances(
     Ok(highest_gas_limit.min(account_gas.as_u64()))
 }
 
+/// Fills the missing fields of a transaction executed on top of `block_header` and validates the given ones,
+/// so calls, estimations and access lists resolve them the same way
+async fn normalize_transaction(
+    transaction: &GenericTransaction,
+    block_header: &BlockHeader,
+    storage: &Store,
+) -> Result<GenericTransaction, RpcErr> {
+    let nonce = match transaction.nonce {
+        Some(nonce) => nonce,
+        None => storage
+            .get_nonce_by_account_address(block_header.number, transaction.from)
+            .await?
+            .unwrap_or_default(),
+    };
+    let chain_config = storage.get_chain_config();
+    let ctx = NormalizationContext {
+        chain_id: chain_config.chain_id,
+        base_fee: block_header.base_fee_per_gas.unwrap_or_default(),
+        nonce,
+        fork: chain_config.fork(block_header.timestamp),
+    };
+    let mut transaction = transaction.clone();
+    transaction
+        .normalize(&ctx)
+        .map_err(|err| RpcErr::BadParams(err.to_string()))?;
+    let gas_limit = effective_gas_limit(transaction.gas, block_header.gas_limit, ctx.fork)
+        .map_err(|err| RpcErr::Vm(RpcErrorPayload::from(&err)))?;
+    transaction.gas = Some(gas_limit);
+    Ok(transaction)
+}
+
+/// PREVRANDAO to simulate with on top of `block_header`, resolved from `block`.
+/// While no pending block is stored the pending tag resolves to the latest
+/// block, so its simulations get the value the next block will be built with.
+async fn pending_randao_override(
+    block: &BlockIdentifier,
+    block_header: &BlockHeader,
+    storage: &Store,
+    blockchain: &Blockchain,
+) -> Result<Option<H256>, RpcErr> {
+    if !matches!(block, BlockIdentifier::Tag(BlockTag::Pending))
+        || storage.get_pending_block_number().await?.is_some()
+    {
+        return Ok(None);
+    }
+    Ok(Some(pending_block_randao(
+        block_header,
+        &blockchain.options.r#type,
+    )))
+}
+
 fn simulate_tx(
     transaction: &GenericTransaction,
     block_header: &BlockHeader,
+    randao_override: Option<H256>,
     storage: Store,
     blockchain: Arc<Blockchain>,
 ) -> Result<ExecutionResult, RpcErr> {
     let vm_db = StoreVmDatabase::new(storage, block_header.clone())?;
     let mut vm = blockchain.new_evm(vm_db)?;
 
-    match vm.simulate_tx_from_generic(transaction, block_header)? {
-        ExecutionResult::Revert {
-            gas_used: _,
-            output,
-        } => Err(RpcErr::Revert {
-            data: format!("0x{output:#x}"),
+    let result = vm.simulate_tx_from_generic(
+        transaction,
+        block_header,
+        randao_override,
+        Some(DEFAULT_SIMULATION_OUTPUT_CAP),
+    )?;
+    match result.revert_reason() {
+        None => Ok(result),
+        Some(RevertReason::Halt(reason)) => Err(RpcErr::Halt {
+            reason,
+            gas_used: result.gas_used(),
+        }),
+        Some(_) => Err(RpcErr::Revert {
+            data: format!("0x{:#x}", result.output()),
         }),
-        ExecutionResult::Halt { reason, gas_used } => Err(RpcErr::Halt { reason, gas_used }),
-        success => Ok(success),
     }
 }
 
@@ -646,3 +753,96 @@ fn get_transaction_data(rpc_req_params: &Option<Vec<Value>>) -> Result<Vec<u8>,
         .ok_or(RpcErr::BadParams("Params are note 0x prefixed".to_owned()))?;
     hex::decode(str_data).map_err(|error| RpcErr::BadParams(error.to_string()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{TEST_GENESIS, default_context_with_storage};
+    use bytes::Bytes;
+    use ethrex_blockchain::{BlockchainOptions, BlockchainType, L2Config};
+    use ethrex_common::{
+        Address,
+        types::{Genesis, GenesisAccount},
+    };
+    use ethrex_storage::EngineType;
+    use hex_literal::hex;
+    use serde_json::json;
+
+    const GENESIS_RANDAO: H256 = H256::repeat_byte(0x42);
+
+    /// Returns PREVRANDAO.
+    const RANDAO_CODE: [u8; 9] = hex!("4460005260206000f3");
+
+    async fn context_with_randao_contract(
+        blockchain_type: BlockchainType,
+    ) -> (RpcApiContext, Address) {
+        let contract = Address::repeat_byte(0x99);
+        let mut genesis: Genesis = serde_json::from_str(TEST_GENESIS).unwrap();
+        genesis.mix_hash = GENESIS_RANDAO;
+        genesis.alloc.insert(
+            contract,
+            GenesisAccount {
+                code: Bytes::from_static(&RANDAO_CODE),
+                storage: Default::default(),
+                balance: U256::zero(),
+                nonce: 1,
+            },
+        );
+        let mut storage = Store::new("", EngineType::InMemory).unwrap();
+        storage.add_initial_state(genesis).await.unwrap();
+
+        let mut context = default_context_with_storage(storage.clone()).await;
+        context.blockchain = Arc::new(Blockchain::new(
+            storage,
+            BlockchainOptions {
+                r#type: blockchain_type,
+                ..Default::default()
+            },
+        ));
+        (context, contract)
+    }
+
+    async fn call_randao(context: &RpcApiContext, contract: Address, block: &str) -> Value {
+        let params = Some(vec![
+            json!({ "to": format!("{contract:#x}") }),
+            json!(block),
+        ]);
+        CallRequest::parse(&params)
+            .unwrap()
+            .handle(context.clone())
+            .await
+            .unwrap()
+    }
+
+    fn randao_output(randao: H256) -> Value {
+        json!(format!(
+            "0x{:#x}",
+            Bytes::copy_from_slice(randao.as_bytes())
+        ))
+    }
+
+    #[tokio::test]
+    async fn pending_call_sees_the_randao_the_l2_builds_with() {
+        let (context, contract) =
+            context_with_randao_contract(BlockchainType::L2(L2Config::default())).await;
+
+        assert_eq!(
+            call_randao(&context, contract, "pending").await,
+            randao_output(H256::zero())
+        );
+        assert_eq!(
+            call_randao(&context, contract, "latest").await,
+            randao_output(GENESIS_RANDAO)
+        );
+    }
+
+    #[tokio::test]
+    async fn pending_call_keeps_the_parent_randao_on_l1() {
+        let (context, contract) = context_with_randao_contract(BlockchainType::L1).await;
+
+        assert_eq!(
+            call_randao(&context, contract, "pending").await,
+            randao_output(GENESIS_RANDAO)
+        );
+    }
+}
@@ -365,6 +365,7 @@ fn get_error_kind(err: &RpcErr) -> &'static str {
         RpcErr::InvalidForkChoiceState(_) => "InvalidForkChoiceState",
         RpcErr::InvalidPayloadAttributes(_) => "InvalidPayloadAttributes",
         RpcErr::UnknownPayload(_) => "UnknownPayload",
+        RpcErr::PrunedHistory(_) => "PrunedHistory",
     }
 }
 
@@ -908,10 +909,11 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::test_utils::default_context_with_storage;
+    use crate::test_utils::{add_eip1559_tx_blocks, default_context_with_storage, setup_store};
     use ethrex_common::{
         H160,
-        types::{ChainConfig, Genesis},
+        constants::POST_OSAKA_GAS_LIMIT_CAP,
+        types::{ChainConfig, Genesis, GenesisAccount},
     };
     use ethrex_crypto::keccak::keccak_hash;
     use ethrex_storage::{EngineType, Store};
@@ -1036,6 +1038,95 @@ mod tests {
         assert_eq!(response.to_string(), expected_response.to_string());
     }
 
+    #[tokio::test]
+    async fn estimate_gas_rejects_gas_above_cap_after_osaka() {
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"eth_estimateGas","params":[{"from":"0x0c2c51a0990aee1d73c1228de158688341557508","to":"0x0100000000000000000000000000000000000000","value":"0xa","gas":"0x1000001"},"0x00"]}"#;
+        let request: RpcRequest = serde_json::from_str(body).unwrap();
+        for (osaka_time, exceeds_cap) in [(None, false), (Some(0), true)] {
+            let mut storage =
+                Store::new("temp.db", EngineType::InMemory).expect("Failed to create test DB");
+            let mut genesis = read_execution_api_genesis_file();
+            genesis.config.osaka_time = osaka_time;
+            storage
+                .add_initial_state(genesis)
+                .await
+                .expect("Failed to add genesis block to DB");
+            let context = default_context_with_storage(storage).await;
+            let result = map_http_requests(&request, context).await;
+            if exceeds_cap {
+                let metadata = RpcErrorMetadata::from(result.unwrap_err());
+                assert_eq!(metadata.code, ethrex_vm::rpc_error::GAS_LIMIT_EXCEEDED);
+            } else {
+                assert_eq!(result.unwrap(), serde_json::json!("0x5208"));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn estimate_gas_flags_estimate_clamped_to_cap_after_osaka() {
+        // GAS, PUSH3 cap - 22000, GT, PUSH1 0x0a, JUMPI, STOP, JUMPDEST, INVALID
+        // Only succeeds with a gas limit close to the cap.
+        let code = Bytes::from(hex::decode("5a62ffaa1011600a57005bfe").unwrap());
+        let contract = H160::from_low_u64_be(0xca9);
+        let body = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"eth_estimateGas","params":[{{"from":"0x0c2c51a0990aee1d73c1228de158688341557508","to":"{contract:#x}"}},"0x00"]}}"#
+        );
+        let request: RpcRequest = serde_json::from_str(&body).unwrap();
+        for (osaka_time, bound_by_cap) in [(None, false), (Some(0), true)] {
+            let mut storage =
+                Store::new("temp.db", EngineType::InMemory).expect("Failed to create test DB");
+            let mut genesis = read_execution_api_genesis_file();
+            genesis.config.osaka_time = osaka_time;
+            genesis.alloc.insert(
+                contract,
+                GenesisAccount {
+                    code: code.clone(),
+                    storage: Default::default(),
+                    balance: Default::default(),
+                    nonce: 1,
+                },
+            );
+            storage
+                .add_initial_state(genesis)
+                .await
+                .expect("Failed to add genesis block to DB");
+            let context = default_context_with_storage(storage).await;
+            let result = map_http_requests(&request, context).await;
+            if bound_by_cap {
+                let metadata = RpcErrorMetadata::from(result.unwrap_err());
+                assert_eq!(metadata.code, ethrex_vm::rpc_error::GAS_LIMIT_CAP_REACHED);
+                assert_eq!(
+                    metadata.data,
+                    Some(format!("0x{POST_OSAKA_GAS_LIMIT_CAP:064x}"))
+                );
+            } else {
+                // Without the cap the estimate goes past it
+                let estimate: String = serde_json::from_value(result.unwrap()).unwrap();
+                let estimate = u64::from_str_radix(estimate.trim_start_matches("0x"), 16).unwrap();
+                assert!(estimate > POST_OSAKA_GAS_LIMIT_CAP - 1000);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn call_above_cap_maps_to_gas_limit_exceeded_code() {
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"eth_call","params":[{"from":"0x0c2c51a0990aee1d73c1228de158688341557508","to":"0x0100000000000000000000000000000000000000","value":"0xa","gas":"0x1000001"},"0x00"]}"#;
+        let request: RpcRequest = serde_json::from_str(body).unwrap();
+        let mut storage =
+            Store::new("temp.db", EngineType::InMemory).expect("Failed to create test DB");
+        let mut genesis = read_execution_api_genesis_file();
+        genesis.config.osaka_time = Some(0);
+        storage
+            .add_initial_state(genesis)
+            .await
+            .expect("Failed to add genesis block to DB");
+        let context = default_context_with_storage(storage).await;
+        let error = map_http_requests(&request, context).await.unwrap_err();
+        let metadata = RpcErrorMetadata::from(error);
+        assert_eq!(metadata.code, ethrex_vm::rpc_error::GAS_LIMIT_EXCEEDED);
+        assert_eq!(metadata.data, None);
+    }
+
     fn example_chain_config() -> ChainConfig {
         ChainConfig {
             chain_id: 3151908_u64,
@@ -1061,6 +1152,36 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn pruned_history_is_reported() {
+        let storage = setup_store().await;
+        add_eip1559_tx_blocks(&storage, 3, 1).await;
+        storage.prune_receipts_before(3).await.unwrap();
+        let context = default_context_with_storage(storage.clone()).await;
+
+        let pruned_receipts =
+            r#"{"jsonrpc":"2.0","method":"eth_getBlockReceipts","params":["0x2"],"id":1}"#;
+        let request: RpcRequest = serde_json::from_str(pruned_receipts).unwrap();
+        let result = map_http_requests(&request, context.clone()).await;
+        let response = rpc_response(request.id, result).unwrap();
+        assert_eq!(response["error"]["code"], 4444);
+
+        storage.prune_bodies_before(3).await.unwrap();
+        for (number, pruned) in [("0x0", false), ("0x1", true), ("0x2", true), ("0x3", false)] {
+            let body = format!(
+                r#"{{"jsonrpc":"2.0","method":"eth_getBlockByNumber","params":["{number}",true],"id":1}}"#
+            );
+            let request: RpcRequest = serde_json::from_str(&body).unwrap();
+            let result = map_http_requests(&request, context.clone()).await;
+            let response = rpc_response(request.id, result).unwrap();
+            if pruned {
+                assert_eq!(response["error"]["code"], 4444, "block {number}");
+            } else {
+                assert!(response["result"].is_object(), "block {number}");
+            }
+        }
+    }
+
     #[tokio::test]
     async fn net_version_test() {
         let body = r#"{"jsonrpc":"2.0","method":"net_version","params":[],"id":67}"#;
@@ -1,7 +1,11 @@
 use std::time::Duration;
 
 use ethrex_common::H256;
-use ethrex_common::{serde_utils, tracing::CallTrace, types::BlockNumber};
+use ethrex_common::{
+    serde_utils,
+    tracing::{CallTrace, NativePrestate},
+    types::BlockNumber,
+};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -41,6 +45,7 @@ struct TraceConfig {
 enum TracerType {
     #[default]
     CallTracer,
+    PrestateTracer,
 }
 
 #[derive(Deserialize, Default)]
@@ -52,6 +57,41 @@ struct CallTracerConfig {
     with_log: bool,
 }
 
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct PrestateTracerConfig {
+    #[serde(default)]
+    diff_mode: bool,
+    /// Not part of geth's config, outputs the native format which also includes the
+    /// block hashes read and the chain config
+    #[serde(default)]
+    native: bool,
+}
+
+impl PrestateTracerConfig {
+    fn parse(tracer_config: &Option<Value>) -> Result<Self, RpcErr> {
+        let config: Self = if let Some(value) = tracer_config {
+            serde_json::from_value(value.clone())?
+        } else {
+            Self::default()
+        };
+        if config.diff_mode {
+            return Err(RpcErr::BadParams(
+                "diffMode is not supported by the prestateTracer".to_owned(),
+            ));
+        }
+        Ok(config)
+    }
+
+    fn output(&self, prestate: NativePrestate) -> Result<Value, serde_json::Error> {
+        if self.native {
+            serde_json::to_value(prestate)
+        } else {
+            serde_json::to_value(prestate.accounts)
+        }
+    }
+}
+
 type BlockTrace<TxTrace> = Vec<BlockTraceComponent<TxTrace>>;
 
 #[derive(Serialize)]
@@ -96,7 +136,6 @@ impl RpcHandler for TraceTransactionRequest {
     ) -> Result<serde_json::Value, crate::utils::RpcErr> {
         let reexec = self.trace_config.reexec.unwrap_or(DEFAULT_REEXEC);
         let timeout = self.trace_config.timeout.unwrap_or(DEFAULT_TIMEOUT);
-        // This match will make more sense once we support other tracers
         match self.trace_config.tracer {
             TracerType::CallTracer => {
                 // Parse tracer config now that we know the type
@@ -118,6 +157,15 @@ impl RpcHandler for TraceTransactionRequest {
                     .map_err(|err| RpcErr::Internal(err.to_string()))?;
                 Ok(serde_json::to_value(call_trace)?)
             }
+            TracerType::PrestateTracer => {
+                let config = PrestateTracerConfig::parse(&self.trace_config.tracer_config)?;
+                let prestate = context
+                    .blockchain
+                    .trace_transaction_prestate(self.tx_hash, reexec, timeout)
+                    .await
+                    .map_err(|err| RpcErr::Internal(err.to_string()))?;
+                Ok(config.output(prestate)?)
+            }
         }
     }
 }
@@ -153,7 +201,6 @@ impl RpcHandler for TraceBlockByNumberRequest {
             .ok_or(RpcErr::Internal("Block not Found".to_string()))?;
         let reexec = self.trace_config.reexec.unwrap_or(DEFAULT_REEXEC);
         let timeout = self.trace_config.timeout.unwrap_or(DEFAULT_TIMEOUT);
-        // This match will make more sense once we support other tracers
         match self.trace_config.tracer {
             TracerType::CallTracer => {
                 // Parse tracer config now that we know the type
@@ -178,6 +225,21 @@ impl RpcHandler for TraceBlockByNumberRequest {
                     call_traces.into_iter().rev().map(Into::into).collect();
                 Ok(serde_json::to_value(block_trace)?)
             }
+            TracerType::PrestateTracer => {
+                let config = PrestateTracerConfig::parse(&self.trace_config.tracer_config)?;
+                let prestates = context
+                    .blockchain
+                    .trace_block_prestate(block, reexec, timeout)
+                    .await
+                    .map_err(|err| RpcErr::Internal(err.to_string()))?;
+                // We need to show transactions from newest to oldest
+                let block_trace = prestates
+                    .into_iter()
+                    .rev()
+                    .map(|(tx_hash, prestate)| Ok((tx_hash, config.output(prestate)?).into()))
+                    .collect::<Result<BlockTrace<Value>, serde_json::Error>>()?;
+                Ok(serde_json::to_value(block_trace)?)
+            }
         }
     }
 }
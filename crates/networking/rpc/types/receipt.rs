@@ -1,7 +1,7 @@
 use ethrex_common::{
     Address, Bloom, Bytes, H256, U256,
     constants::GAS_PER_BLOB,
-    evm::calculate_create_address,
+    evm::compute_create_address,
     serde_utils,
     types::{
         BlockHash, BlockHeader, BlockNumber, Log, Receipt, Transaction, TxKind, TxType,
@@ -192,7 +192,7 @@ impl RpcReceiptTxInfo {
             _ => (None, None),
         };
         let (contract_address, to) = match transaction.to() {
-            TxKind::Create => (Some(calculate_create_address(from, nonce)), None),
+            TxKind::Create => (Some(compute_create_address(from, nonce)), None),
             TxKind::Call(addr) => (None, Some(addr)),
         };
         Ok(Self {
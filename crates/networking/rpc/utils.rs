@@ -8,7 +8,7 @@
 
 use ethrex_common::U256;
 use ethrex_storage::error::StoreError;
-use ethrex_vm::EvmError;
+use ethrex_vm::{EvmError, RpcErrorPayload, rpc_error};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
@@ -24,6 +24,8 @@ use ethrex_blockchain::error::MempoolError;
 /// - `-32000`: Generic server error
 /// - `-38001` to `-38005`: Engine API specific errors
 /// - `3`: Execution reverted/halted
+///
+/// Execution errors get their codes from [`ethrex_vm::rpc_error`].
 #[derive(Debug, thiserror::Error)]
 pub enum RpcErr {
     #[error("Method not found: {0}")]
@@ -42,8 +44,8 @@ pub enum RpcErr {
     UnsupportedFork(String),
     #[error("Internal Error: {0}")]
     Internal(String),
-    #[error("Vm execution error: {0}")]
-    Vm(String),
+    #[error("Vm execution error: {}", .0.message)]
+    Vm(RpcErrorPayload),
     #[error("execution reverted: data={data}")]
     Revert { data: String },
     #[error("execution halted: reason={reason}, gas_used={gas_used}")]
@@ -56,6 +58,8 @@ pub enum RpcErr {
     InvalidPayloadAttributes(String),
     #[error("Unknown payload: {0}")]
     UnknownPayload(String),
+    #[error("Pruned history unavailable: {0}")]
+    PrunedHistory(String),
 }
 
 impl From<RpcErr> for RpcErrorMetadata {
@@ -101,15 +105,9 @@ impl From<RpcErr> for RpcErrorMetadata {
                 data: None,
                 message: format!("Internal Error: {context}"),
             },
-            RpcErr::Vm(context) => RpcErrorMetadata {
-                code: -32015,
-                data: None,
-                message: format!("Vm execution error: {context}"),
-            },
+            RpcErr::Vm(payload) => payload.into(),
             RpcErr::Revert { data } => RpcErrorMetadata {
-                // This code (3) was hand-picked to match hive tests.
-                // Could not find proper documentation about it.
-                code: 3,
+                code: rpc_error::EXECUTION_ERROR,
                 data: Some(data.clone()),
                 message: format!(
                     "execution reverted: {}",
@@ -118,13 +116,7 @@ impl From<RpcErr> for RpcErrorMetadata {
                     ))
                 ),
             },
-            RpcErr::Halt { reason, gas_used } => RpcErrorMetadata {
-                // Just copy the `Revert` error code.
-                // Haven't found an example of this one yet.
-                code: 3,
-                data: None,
-                message: format!("execution halted: reason={reason}, gas_used={gas_used}"),
-            },
+            RpcErr::Halt { reason, gas_used } => RpcErrorPayload::halt(&reason, gas_used).into(),
             RpcErr::AuthenticationError(auth_error) => match auth_error {
                 AuthenticationError::InvalidIssuedAtClaim => RpcErrorMetadata {
                     code: -32000,
@@ -157,6 +149,12 @@ impl From<RpcErr> for RpcErrorMetadata {
                 data: None,
                 message: format!("Unknown payload: {context}"),
             },
+            // Same code geth uses for pruned history
+            RpcErr::PrunedHistory(context) => RpcErrorMetadata {
+                code: 4444,
+                data: None,
+                message: format!("pruned history unavailable: {context}"),
+            },
         }
     }
 }
@@ -331,16 +329,30 @@ pub enum RpcResponse {
     Error(RpcErrorResponse),
 }
 
-/// Failure to read from DB will always constitute an internal error
+/// Failure to read from DB will always constitute an internal error, except
+/// for reads of pruned history
 impl From<StoreError> for RpcErr {
     fn from(value: StoreError) -> Self {
-        RpcErr::Internal(value.to_string())
+        match value {
+            StoreError::PrunedHistory(_) => RpcErr::PrunedHistory(value.to_string()),
+            _ => RpcErr::Internal(value.to_string()),
+        }
     }
 }
 
 impl From<EvmError> for RpcErr {
     fn from(value: EvmError) -> Self {
-        RpcErr::Vm(value.to_string())
+        RpcErr::Vm(RpcErrorPayload::from(&value))
+    }
+}
+
+impl From<RpcErrorPayload> for RpcErrorMetadata {
+    fn from(payload: RpcErrorPayload) -> Self {
+        RpcErrorMetadata {
+            code: payload.code,
+            data: payload.data.map(|data| format!("0x{}", hex::encode(data))),
+            message: payload.message,
+        }
     }
 }
 
@@ -0,0 +1,83 @@
+use std::{
+    fmt,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use ethrex_common::H256;
+use ethrex_trie::Nibbles;
+use fastbloom::AtomicBloomFilter;
+use rustc_hash::FxBuildHasher;
+
+const FALSE_POSITIVE_RATE: f64 = 0.01;
+
+/// Accounts a filter is sized for at least, so that one built on a small state doesn't
+/// saturate as soon as the state grows.
+const MIN_EXPECTED_ACCOUNTS: usize = 1 << 16;
+
+/// Length of the trie path of an account leaf: 64 nibbles plus the leaf flag.
+pub(crate) const ACCOUNT_LEAF_PATH_LEN: usize = 65;
+
+/// Bloom filter over the hashed addresses of the accounts held by the store,
+/// used to tell an account doesn't exist without walking the state trie.
+///
+/// Accounts are only ever added, so an account that was deleted is a false
+/// positive: the filter can rule out an account but never confirm one.
+pub(crate) struct AccountFilter {
+    bloom: AtomicBloomFilter<FxBuildHasher>,
+    /// Whether every existing account was inserted. Until then nothing can be
+    /// ruled out.
+    ready: AtomicBool,
+}
+
+impl fmt::Debug for AccountFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AccountFilter")
+            .field("bloom", &"AtomicBloomFilter")
+            .field("ready", &self.is_ready())
+            .finish()
+    }
+}
+
+impl AccountFilter {
+    /// Creates a filter for a state holding `accounts` accounts, with room for it to grow
+    /// by half before the false positive rate degrades.
+    pub(crate) fn new(accounts: usize) -> Self {
+        let expected_accounts = accounts
+            .saturating_add(accounts / 2)
+            .max(MIN_EXPECTED_ACCOUNTS);
+        Self {
+            bloom: AtomicBloomFilter::with_false_pos(FALSE_POSITIVE_RATE)
+                .hasher(FxBuildHasher)
+                .expected_items(expected_accounts),
+            ready: AtomicBool::new(false),
+        }
+    }
+
+    pub(crate) fn insert(&self, hashed_address: H256) {
+        self.bloom.insert(hashed_address.as_bytes());
+    }
+
+    /// Inserts the account of a leaf path of the state trie, other paths are ignored.
+    pub(crate) fn insert_path(&self, path: &Nibbles) {
+        if let Some(hashed_address) = account_of_path(path) {
+            self.insert(hashed_address);
+        }
+    }
+
+    pub(crate) fn may_contain(&self, hashed_address: H256) -> bool {
+        !self.is_ready() || self.bloom.contains(hashed_address.as_bytes())
+    }
+
+    pub(crate) fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn set_ready(&self) {
+        self.ready.store(true, Ordering::Release);
+    }
+}
+
+/// Hashed address of the account at a leaf path of the state trie, `None` for other paths.
+pub(crate) fn account_of_path(path: &Nibbles) -> Option<H256> {
+    (path.len() == ACCOUNT_LEAF_PATH_LEN).then(|| H256::from_slice(&path.to_bytes()))
+}
@@ -48,4 +48,6 @@ pub enum StoreError {
     NotFoundDBVersion { expected: u64 },
     #[error("Incompatible DB Version: found v{found}, expected v{expected}")]
     IncompatibleDBVersion { found: u64, expected: u64 },
+    #[error("History of block {0} has been pruned")]
+    PrunedHistory(u64),
 }
@@ -166,6 +166,15 @@ impl TrieLayerCache {
         self.bloom = filter;
     }
 
+    /// Keys holding a value in any layer, deleted ones excluded.
+    pub fn keys(&self) -> impl Iterator<Item = &[u8]> {
+        self.layers
+            .values()
+            .flat_map(|layer| layer.nodes.iter())
+            .filter(|(_, value)| !value.is_empty())
+            .map(|(key, _)| key.as_slice())
+    }
+
     pub fn commit(&mut self, state_root: H256) -> Option<Vec<(Vec<u8>, Vec<u8>)>> {
         let mut layers_to_commit = vec![];
         let mut current_state_root = state_root;
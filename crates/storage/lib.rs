@@ -64,6 +64,7 @@
 //! The store maintains a cache layer (`TrieLayerCache`) for efficient state access
 //! without requiring full trie traversal for recent blocks.
 
+mod account_filter;
 pub mod api;
 pub mod backend;
 pub mod error;
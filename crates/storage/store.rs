@@ -2,8 +2,9 @@
 use crate::backend::rocksdb::RocksDBBackend;
 use crate::{
     STORE_METADATA_FILENAME, STORE_SCHEMA_VERSION,
+    account_filter::{AccountFilter, account_of_path},
     api::{
-        StorageBackend, StorageReadView,
+        StorageBackend, StorageReadView, StorageWriteBatch,
         tables::{
             ACCOUNT_CODE_METADATA, ACCOUNT_CODES, ACCOUNT_FLATKEYVALUE, ACCOUNT_TRIE_NODES,
             BLOCK_NUMBERS, BODIES, CANONICAL_BLOCK_HASHES, CHAIN_DATA, EXECUTION_WITNESSES,
@@ -45,6 +46,7 @@ use std::{
     collections::{BTreeMap, HashMap, hash_map::Entry},
     fmt::Debug,
     io::Write,
+    ops::Range,
     path::{Path, PathBuf},
     sync::{
         Arc, Mutex, RwLock,
@@ -57,6 +59,13 @@ use tracing::{debug, error, info};
 /// Maximum number of execution witnesses to keep in the database
 pub const MAX_WITNESSES: u64 = 128;
 
+/// Number of blocks pruned per write transaction when pruning bodies or receipts
+const PRUNE_BATCH_SIZE: u64 = 1024;
+
+/// Deletes the data of a single block, reading any needed keys from the given view
+type PruneBlockFn =
+    fn(&dyn StorageReadView, &mut dyn StorageWriteBatch, BlockHash) -> Result<(), StoreError>;
+
 // We use one constant for in-memory and another for on-disk backends.
 // This is due to tests requiring state older than 128 blocks.
 // TODO: unify these
@@ -183,6 +192,10 @@ pub struct Store {
     /// Uses FxHashMap for efficient lookups, much smaller than code cache.
     code_metadata_cache: Arc<Mutex<rustc_hash::FxHashMap<H256, CodeMetadata>>>,
 
+    /// Filter of the accounts held by the store, set when enabled through
+    /// [`Store::enable_account_filter`].
+    account_filter: Arc<RwLock<Option<Arc<AccountFilter>>>>,
+
     background_threads: Arc<ThreadList>,
 }
 
@@ -389,6 +402,143 @@ impl Store {
         .map_err(|e| StoreError::Custom(format!("Task panicked: {}", e)))?
     }
 
+    /// Deletes the bodies of canonical blocks numbered below `block_number`,
+    /// along with the locations of their transactions.
+    ///
+    /// The genesis block, headers, the canonical hash index and receipts are
+    /// preserved. Reading a pruned body returns [`StoreError::PrunedHistory`].
+    /// Each call resumes from where the previous one stopped.
+    /// Returns the number of block numbers processed.
+    pub async fn prune_bodies_before(&self, block_number: BlockNumber) -> Result<u64, StoreError> {
+        self.prune_canonical_before(
+            block_number,
+            ChainDataIndex::BodiesPrunedBefore,
+            |read_view, txn, block_hash| {
+                let hash_key = block_hash.encode_to_vec();
+                let Some(body) = read_view
+                    .get(BODIES, &hash_key)?
+                    .map(|bytes| BlockBodyRLP::from_bytes(bytes).to())
+                    .transpose()?
+                else {
+                    return Ok(());
+                };
+                for transaction in &body.transactions {
+                    let mut composite_key = Vec::with_capacity(64);
+                    composite_key.extend_from_slice(transaction.hash().as_bytes());
+                    composite_key.extend_from_slice(block_hash.as_bytes());
+                    txn.delete(TRANSACTION_LOCATIONS, &composite_key)?;
+                }
+                txn.delete(BODIES, &hash_key)
+            },
+        )
+        .await
+    }
+
+    /// Deletes the receipts of canonical blocks numbered below `block_number`.
+    ///
+    /// The genesis block, headers, the canonical hash index and bodies are
+    /// preserved. Reading pruned receipts returns [`StoreError::PrunedHistory`].
+    /// Each call resumes from where the previous one stopped.
+    /// Returns the number of block numbers processed.
+    pub async fn prune_receipts_before(
+        &self,
+        block_number: BlockNumber,
+    ) -> Result<u64, StoreError> {
+        self.prune_canonical_before(
+            block_number,
+            ChainDataIndex::ReceiptsPrunedBefore,
+            |read_view, txn, block_hash| {
+                let mut index = 0u64;
+                loop {
+                    let key = (block_hash, index).encode_to_vec();
+                    if read_view.get(RECEIPTS, &key)?.is_none() {
+                        return Ok(());
+                    }
+                    txn.delete(RECEIPTS, &key)?;
+                    index += 1;
+                }
+            },
+        )
+        .await
+    }
+
+    /// Applies `prune` to every canonical block from the `progress` marker up to
+    /// `block_number` (exclusive), committing in batches of [`PRUNE_BATCH_SIZE`] blocks.
+    /// The marker is updated in the same transaction as the deletions. The
+    /// genesis block is never pruned.
+    async fn prune_canonical_before(
+        &self,
+        block_number: BlockNumber,
+        progress: ChainDataIndex,
+        prune: PruneBlockFn,
+    ) -> Result<u64, StoreError> {
+        let backend = self.backend.clone();
+        tokio::task::spawn_blocking(move || {
+            let progress_key = chain_data_key(progress);
+            let start = read_pruned_before(backend.begin_read()?.as_ref(), progress)?.max(1);
+
+            let mut from = start;
+            while from < block_number {
+                let to = from.saturating_add(PRUNE_BATCH_SIZE).min(block_number);
+                let read_view = backend.begin_read()?;
+                let mut txn = backend.begin_write()?;
+                for number in from..to {
+                    let Some(block_hash) = read_view
+                        .get(CANONICAL_BLOCK_HASHES, number.to_le_bytes().as_slice())?
+                        .map(|bytes| H256::decode(bytes.as_slice()))
+                        .transpose()?
+                    else {
+                        continue;
+                    };
+                    prune(read_view.as_ref(), txn.as_mut(), block_hash)?;
+                }
+                txn.put(CHAIN_DATA, &progress_key, &to.to_le_bytes())?;
+                txn.commit()?;
+                from = to;
+            }
+
+            Ok(from.saturating_sub(start))
+        })
+        .await
+        .map_err(|e| StoreError::Custom(format!("Task panicked: {}", e)))?
+    }
+
+    /// Returns [`StoreError::PrunedHistory`] if the data tracked by `progress`
+    /// was pruned for the canonical block `block_hash`.
+    ///
+    /// Only called once the data is found missing, to tell pruned blocks apart
+    /// from unknown ones.
+    async fn check_not_pruned(
+        &self,
+        progress: ChainDataIndex,
+        block_hash: BlockHash,
+    ) -> Result<(), StoreError> {
+        let Some(block_number) = self.get_block_number(block_hash).await? else {
+            return Ok(());
+        };
+        if self.get_canonical_block_hash_sync(block_number)? != Some(block_hash) {
+            return Ok(());
+        }
+        self.check_number_not_pruned(progress, block_number)
+    }
+
+    /// Like [`Store::check_not_pruned`], for a canonical block number.
+    fn check_number_not_pruned(
+        &self,
+        progress: ChainDataIndex,
+        block_number: BlockNumber,
+    ) -> Result<(), StoreError> {
+        // Genesis is never pruned
+        if block_number == 0 {
+            return Ok(());
+        }
+        let pruned_before = read_pruned_before(self.backend.begin_read()?.as_ref(), progress)?;
+        if block_number < pruned_before {
+            return Err(StoreError::PrunedHistory(block_number));
+        }
+        Ok(())
+    }
+
     /// Obtain canonical block bodies in from..=to
     pub async fn get_block_bodies(
         &self,
@@ -458,16 +608,23 @@ impl Store {
         .map_err(|e| StoreError::Custom(format!("Task panicked: {}", e)))?
     }
 
-    /// Obtain any block body using the hash
+    /// Obtain any block body using the hash.
+    /// Returns [`StoreError::PrunedHistory`] if the body was pruned.
     pub async fn get_block_body_by_hash(
         &self,
         block_hash: BlockHash,
     ) -> Result<Option<BlockBody>, StoreError> {
-        self.read_async(BODIES, block_hash.encode_to_vec())
+        let body = self
+            .read_async(BODIES, block_hash.encode_to_vec())
             .await?
             .map(|bytes| BlockBodyRLP::from_bytes(bytes).to())
             .transpose()
-            .map_err(StoreError::from)
+            .map_err(StoreError::from)?;
+        if body.is_none() {
+            self.check_not_pruned(ChainDataIndex::BodiesPrunedBefore, block_hash)
+                .await?;
+        }
+        Ok(body)
     }
 
     pub fn get_block_header_by_hash(
@@ -643,6 +800,7 @@ impl Store {
     }
 
     /// Obtain receipt for a canonical block represented by the block number.
+    /// Returns [`StoreError::PrunedHistory`] if the block's receipts were pruned.
     pub async fn get_receipt(
         &self,
         block_number: BlockNumber,
@@ -652,7 +810,11 @@ impl Store {
         let Some(block_hash) = self.get_canonical_block_hash(block_number).await? else {
             return Ok(None);
         };
-        self.get_receipt_by_block_hash(block_hash, index).await
+        let receipt = self.get_receipt_by_block_hash(block_hash, index).await?;
+        if receipt.is_none() {
+            self.check_number_not_pruned(ChainDataIndex::ReceiptsPrunedBefore, block_number)?;
+        }
+        Ok(receipt)
     }
 
     /// Obtain receipt by block hash and index
@@ -699,6 +861,7 @@ impl Store {
             hash: code_hash,
             bytecode,
             jump_targets: <Vec<_>>::decode(targets)?,
+            eof: Default::default(),
         };
 
         // insert into cache and evict if needed
@@ -1042,6 +1205,10 @@ impl Store {
             }
         }
 
+        if receipts.is_empty() {
+            self.check_not_pruned(ChainDataIndex::ReceiptsPrunedBefore, *block_hash)
+                .await?;
+        }
         Ok(receipts)
     }
 
@@ -1131,6 +1298,34 @@ impl Store {
         .map_err(StoreError::from)
     }
 
+    /// Get the canonical block hashes for the block numbers in `range`, reading
+    /// all of them from a single read view.
+    /// Numbers without a canonical block are left out of the result.
+    pub fn get_canonical_block_hashes_range_sync(
+        &self,
+        range: Range<BlockNumber>,
+    ) -> Result<Vec<(BlockNumber, BlockHash)>, StoreError> {
+        let last = self.latest_block_header.get();
+        let txn = self.backend.begin_read()?;
+        let mut hashes = Vec::new();
+        for block_number in range {
+            let hash = if last.number == block_number {
+                Some(last.hash())
+            } else {
+                txn.get(
+                    CANONICAL_BLOCK_HASHES,
+                    block_number.to_le_bytes().as_slice(),
+                )?
+                .map(|bytes| H256::decode(bytes.as_slice()))
+                .transpose()?
+            };
+            if let Some(hash) = hash {
+                hashes.push((block_number, hash));
+            }
+        }
+        Ok(hashes)
+    }
+
     /// CAUTION: This method writes directly to the underlying database, bypassing any caching layer.
     /// For updating the state after block execution, use [`Self::store_block_updates`].
     pub async fn write_storage_trie_nodes_batch(
@@ -1460,6 +1655,7 @@ impl Store {
             last_computed_flatkeyvalue: Arc::new(RwLock::new(last_written)),
             account_code_cache: Arc::new(Mutex::new(CodeCache::default())),
             code_metadata_cache: Arc::new(Mutex::new(rustc_hash::FxHashMap::default())),
+            account_filter: Arc::new(RwLock::new(None)),
             background_threads: Default::default(),
         };
         let backend_clone = store.backend.clone();
@@ -1484,6 +1680,7 @@ impl Store {
         let backend = store.backend.clone();
         let flatkeyvalue_control_tx = store.flatkeyvalue_control_tx.clone();
         let trie_cache = store.trie_cache.clone();
+        let account_filter = store.account_filter.clone();
         /*
             When a block is executed, the write of the bottom-most diff layer to disk is done in the background through this thread.
             This is to improve block execution times, since it's not necessary when executing the next block to have this layer flushed to disk.
@@ -1514,6 +1711,7 @@ impl Store {
                             backend.as_ref(),
                             &flatkeyvalue_control_tx,
                             &trie_cache,
+                            &account_filter,
                             trie_update,
                         )
                         .inspect_err(|err| error!("apply_trie_updates failed: {err}"));
@@ -2084,6 +2282,51 @@ impl Store {
         address: Address,
         storage_key: H256,
     ) -> Result<Option<U256>, StoreError> {
+        let Some(storage_trie) = self.storage_trie_at_root(state_root, address)? else {
+            return Ok(None);
+        };
+
+        let hashed_key = hash_key_fixed(&storage_key);
+        storage_trie
+            .get(&hashed_key)?
+            .map(|rlp| U256::decode(&rlp).map_err(StoreError::RLPDecode))
+            .transpose()
+    }
+
+    /// Batched version of [`Store::get_storage_at_root`].
+    ///
+    /// The account lookup and the storage trie are resolved once and shared by
+    /// all `storage_keys`, instead of walking the state trie for every slot.
+    /// Values are returned in the same order as the keys.
+    pub fn get_storage_values_at_root(
+        &self,
+        state_root: H256,
+        address: Address,
+        storage_keys: &[H256],
+    ) -> Result<Vec<Option<U256>>, StoreError> {
+        let Some(storage_trie) = self.storage_trie_at_root(state_root, address)? else {
+            return Ok(vec![None; storage_keys.len()]);
+        };
+
+        storage_keys
+            .iter()
+            .map(|storage_key| {
+                let hashed_key = hash_key_fixed(storage_key);
+                storage_trie
+                    .get(&hashed_key)?
+                    .map(|rlp| U256::decode(&rlp).map_err(StoreError::RLPDecode))
+                    .transpose()
+            })
+            .collect()
+    }
+
+    /// Opens the storage trie of `address` under `state_root`.
+    /// Returns `None` if the account doesn't exist.
+    fn storage_trie_at_root(
+        &self,
+        state_root: H256,
+        address: Address,
+    ) -> Result<Option<Trie>, StoreError> {
         let account_hash = hash_address_fixed(&address);
 
         // Pre-acquire shared resources once for both trie opens
@@ -2120,12 +2363,7 @@ impl Store {
             cache,
             last_written,
         )?;
-
-        let hashed_key = hash_key_fixed(&storage_key);
-        storage_trie
-            .get(&hashed_key)?
-            .map(|rlp| U256::decode(&rlp).map_err(StoreError::RLPDecode))
-            .transpose()
+        Ok(Some(storage_trie))
     }
 
     pub fn get_chain_config(&self) -> ChainConfig {
@@ -2223,6 +2461,97 @@ impl Store {
         self.get_account_state_from_trie(&state_trie, address)
     }
 
+    /// Enables the account filter, letting [`Store::account_may_exist`] rule out accounts
+    /// that don't exist without walking the state trie. Returns the number of accounts
+    /// found, which the filter is sized from.
+    ///
+    /// Scans every account on disk and in the in-memory diff layers twice, once to size the
+    /// filter and once to fill it, so it's meant to be called once the state is in place,
+    /// e.g. at startup. From then on the accounts of stored blocks are added as their trie
+    /// updates are applied. Calling it again rebuilds the filter from the current state.
+    ///
+    /// Snap sync writes the state without going through those updates, so it must disable
+    /// the filter while it runs, see [`Store::disable_account_filter`].
+    pub fn enable_account_filter(&self) -> Result<usize, StoreError> {
+        let mut accounts = 0;
+        self.for_each_account(|_| accounts += 1)?;
+
+        // Set before the scan so that the accounts of blocks stored meanwhile are added too
+        let filter = Arc::new(AccountFilter::new(accounts));
+        *self
+            .account_filter
+            .write()
+            .map_err(|_| StoreError::LockError)? = Some(filter.clone());
+        self.for_each_account(|hashed_address| filter.insert(hashed_address))?;
+
+        filter.set_ready();
+        Ok(accounts)
+    }
+
+    /// Disables the account filter, returning whether it was enabled.
+    ///
+    /// Writers that bypass the trie updates of stored blocks, like snap sync, must call it
+    /// before writing any account and enable the filter again once they're done.
+    pub fn disable_account_filter(&self) -> Result<bool, StoreError> {
+        Ok(self
+            .account_filter
+            .write()
+            .map_err(|_| StoreError::LockError)?
+            .take()
+            .is_some())
+    }
+
+    /// Whether `address` may exist in a state held by the store. Always true unless the
+    /// account filter is enabled, see [`Store::enable_account_filter`].
+    pub fn account_may_exist(&self, address: Address) -> bool {
+        // A poisoned lock can't rule anything out
+        let Ok(filter) = self.account_filter.read() else {
+            return true;
+        };
+        filter
+            .as_ref()
+            .is_none_or(|filter| filter.may_contain(hash_address_fixed(&address)))
+    }
+
+    /// Calls `visit` with the hashed address of every account on disk and in the in-memory
+    /// diff layers. An account found in both may be visited twice.
+    fn for_each_account(&self, mut visit: impl FnMut(H256)) -> Result<(), StoreError> {
+        // Layers are read before the disk: one that's gone by now was written to disk first
+        let trie_cache = self
+            .trie_cache
+            .read()
+            .map_err(|_| StoreError::LockError)?
+            .clone();
+        for key in trie_cache.keys() {
+            if let Some(hashed_address) = account_of_path(&Nibbles::from_hex(key.to_vec())) {
+                visit(hashed_address);
+            }
+        }
+
+        let read_view = self.backend.begin_read()?;
+        if let Some(root) = read_view.get(ACCOUNT_TRIE_NODES, &[])? {
+            let state_root = Node::decode(&root)?.compute_hash().finalize();
+            let state_trie = Trie::open(
+                Box::new(BackendTrieDB::new_for_accounts_with_view(
+                    self.backend.clone(),
+                    read_view,
+                    self.last_written()?,
+                )?),
+                state_root,
+            );
+            // A node that can't be read would leave accounts out, so it must fail the scan
+            let mut accounts = state_trie.into_iter();
+            while let Some((path, node)) = accounts.try_next()? {
+                if let Node::Leaf(_) = node
+                    && let Some(hashed_address) = account_of_path(&path)
+                {
+                    visit(hashed_address);
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub fn get_account_state_from_trie(
         &self,
         state_trie: &Trie,
@@ -2737,6 +3066,7 @@ fn apply_trie_updates(
     backend: &dyn StorageBackend,
     fkv_ctl: &SyncSender<FKVGeneratorControlMessage>,
     trie_cache: &Arc<RwLock<Arc<TrieLayerCache>>>,
+    account_filter: &RwLock<Option<Arc<AccountFilter>>>,
     trie_update: TrieUpdate,
 ) -> Result<(), StoreError> {
     let TrieUpdate {
@@ -2747,6 +3077,19 @@ fn apply_trie_updates(
         storage_updates,
     } = trie_update;
 
+    // Accounts must be in the filter before the next block can read them
+    let account_filter = account_filter
+        .read()
+        .map_err(|_| StoreError::LockError)?
+        .clone();
+    if let Some(filter) = account_filter {
+        for (path, value) in &account_updates {
+            if !value.is_empty() {
+                filter.insert_path(path);
+            }
+        }
+    }
+
     // Phase 1: update the in-memory diff-layers only, then notify block production.
     let new_layer = storage_updates
         .into_iter()
@@ -3056,6 +3399,23 @@ fn chain_data_key(index: ChainDataIndex) -> Vec<u8> {
     (index as u8).encode_to_vec()
 }
 
+/// Reads the pruning progress marker `progress`, 0 if nothing was pruned yet.
+fn read_pruned_before(
+    read_view: &dyn StorageReadView,
+    progress: ChainDataIndex,
+) -> Result<BlockNumber, StoreError> {
+    read_view
+        .get(CHAIN_DATA, &chain_data_key(progress))?
+        .map(|bytes| -> Result<BlockNumber, StoreError> {
+            let array: [u8; 8] = bytes
+                .try_into()
+                .map_err(|_| StoreError::Custom("Invalid BlockNumber bytes".to_string()))?;
+            Ok(BlockNumber::from_le_bytes(array))
+        })
+        .transpose()
+        .map(Option::unwrap_or_default)
+}
+
 fn snap_state_key(index: SnapStateIndex) -> Vec<u8> {
     (index as u8).encode_to_vec()
 }
@@ -8,6 +8,10 @@ pub enum ChainDataIndex {
     SafeBlockNumber = 3,
     LatestBlockNumber = 4,
     PendingBlockNumber = 5,
+    /// Canonical block bodies below this number have been pruned
+    BodiesPrunedBefore = 6,
+    /// Canonical block receipts below this number have been pruned
+    ReceiptsPrunedBefore = 7,
 }
 
 impl From<u8> for ChainDataIndex {
@@ -25,6 +29,12 @@ impl From<u8> for ChainDataIndex {
             x if x == ChainDataIndex::PendingBlockNumber as u8 => {
                 ChainDataIndex::PendingBlockNumber
             }
+            x if x == ChainDataIndex::BodiesPrunedBefore as u8 => {
+                ChainDataIndex::BodiesPrunedBefore
+            }
+            x if x == ChainDataIndex::ReceiptsPrunedBefore as u8 => {
+                ChainDataIndex::ReceiptsPrunedBefore
+            }
             _ => panic!("Invalid value when casting to ChainDataIndex: {value}"),
         }
     }
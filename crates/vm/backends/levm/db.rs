@@ -55,6 +55,20 @@ impl LevmDatabase for DatabaseLogger {
         self.store.as_ref().get_storage_value(address, key)
     }
 
+    fn get_storage_values(
+        &self,
+        address: CoreAddress,
+        keys: &[CoreH256],
+    ) -> Result<Vec<CoreU256>, DatabaseError> {
+        self.state_accessed
+            .lock()
+            .map_err(|_| DatabaseError::Custom("Could not lock mutex".to_string()))?
+            .entry(address)
+            .or_default()
+            .extend_from_slice(keys);
+        self.store.as_ref().get_storage_values(address, keys)
+    }
+
     fn get_block_hash(&self, block_number: u64) -> Result<CoreH256, DatabaseError> {
         let block_hash = self.store.as_ref().get_block_hash(block_number)?;
         self.block_hashes_accessed
@@ -82,6 +96,10 @@ impl LevmDatabase for DatabaseLogger {
     fn get_code_metadata(&self, code_hash: CoreH256) -> Result<CodeMetadata, DatabaseError> {
         self.store.get_code_metadata(code_hash)
     }
+
+    fn keccak(&self, data: &[u8]) -> [u8; 32] {
+        self.store.keccak(data)
+    }
 }
 
 impl LevmDatabase for DynVmDatabase {
@@ -105,6 +123,20 @@ impl LevmDatabase for DynVmDatabase {
         )
     }
 
+    fn get_storage_values(
+        &self,
+        address: CoreAddress,
+        keys: &[CoreH256],
+    ) -> Result<Vec<ethrex_common::U256>, DatabaseError> {
+        Ok(
+            <dyn VmDatabase>::get_storage_slots(self.as_ref(), address, keys)
+                .map_err(|e| DatabaseError::Custom(e.to_string()))?
+                .into_iter()
+                .map(Option::unwrap_or_default)
+                .collect(),
+        )
+    }
+
     fn get_block_hash(&self, block_number: u64) -> Result<CoreH256, DatabaseError> {
         <dyn VmDatabase>::get_block_hash(self.as_ref(), block_number)
             .map_err(|e| DatabaseError::Custom(e.to_string()))
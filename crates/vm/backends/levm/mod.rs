@@ -2,17 +2,14 @@ pub mod db;
 mod tracing;
 
 use super::BlockExecutionResult;
-use crate::system_contracts::{
-    BEACON_ROOTS_ADDRESS, CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS, HISTORY_STORAGE_ADDRESS,
-    PRAGUE_SYSTEM_CONTRACTS, SYSTEM_ADDRESS, WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS,
-};
+use crate::system_contracts::{SYSTEM_ADDRESS, extra_system_call_calldata};
 use crate::{EvmError, ExecutionResult};
 use bytes::Bytes;
 use ethrex_common::types::block_access_list::BlockAccessList;
 use ethrex_common::types::fee_config::FeeConfig;
 use ethrex_common::types::{AuthorizationTuple, EIP7702Transaction};
 use ethrex_common::{
-    Address, U256,
+    Address, H256, U256,
     types::{
         AccessList, AccountUpdate, Block, BlockHeader, EIP1559Transaction, Fork, GWEI_TO_WEI,
         GenericTransaction, INITIAL_BASE_FEE, Receipt, Transaction, TxKind, Withdrawal,
@@ -20,12 +17,13 @@ use ethrex_common::{
     },
 };
 use ethrex_levm::EVMConfig;
+use ethrex_levm::access_sets::AccessSets;
 use ethrex_levm::call_frame::Stack;
 use ethrex_levm::constants::{
     POST_OSAKA_GAS_LIMIT_CAP, STACK_LIMIT, SYS_CALL_GAS_LIMIT, TX_BASE_COST,
 };
-use ethrex_levm::db::Database;
 use ethrex_levm::db::gen_db::GeneralizedDatabase;
+use ethrex_levm::db::{CachingDatabase, Database};
 use ethrex_levm::errors::{InternalError, TxValidationError};
 #[cfg(feature = "perf_opcode_timings")]
 use ethrex_levm::timings::{OPCODE_TIMINGS, PRECOMPILES_TIMINGS};
@@ -53,19 +51,19 @@ use std::sync::mpsc::Sender;
 pub struct LEVM;
 
 /// Checks that adding `tx_gas_limit` to `block_gas_used` doesn't exceed `block_gas_limit`.
-/// NOTE: Message must contain "Gas allowance exceeded" and "Block gas used overflow"
-/// as literal substrings for the EELS exception mapper (see execution-specs ethrex.py).
-/// Can be simplified once we update the mapper regexes.
 fn check_gas_limit(
     block_gas_used: u64,
     tx_gas_limit: u64,
     block_gas_limit: u64,
 ) -> Result<(), EvmError> {
     if block_gas_used + tx_gas_limit > block_gas_limit {
-        return Err(EvmError::Transaction(format!(
-            "Gas allowance exceeded: Block gas used overflow: \
-             used {block_gas_used} + tx limit {tx_gas_limit} > block limit {block_gas_limit}"
-        )));
+        return Err(EvmError::TxValidation(
+            TxValidationError::BlockGasUsedOverflow {
+                block_gas_used,
+                tx_gas_limit,
+                block_gas_limit,
+            },
+        ));
     }
     Ok(())
 }
@@ -179,9 +177,22 @@ impl LEVM {
         block: &Block,
         db: &mut GeneralizedDatabase,
         vm_type: VMType,
+        warm_cache: Option<&Arc<CachingDatabase>>,
         merkleizer: Sender<Vec<AccountUpdate>>,
         queue_length: &AtomicUsize,
     ) -> Result<(BlockExecutionResult, Option<BlockAccessList>), EvmError> {
+        // A warmer populating a cache the executor doesn't read from is wasted work.
+        if let Some(cache) = warm_cache {
+            let shared = cache.is_store(&db.store);
+            if !shared {
+                ::tracing::warn!(
+                    "Block {} is executed on a store that doesn't share the warmer's cache",
+                    block.header.number
+                );
+            }
+            debug_assert!(shared, "block executor doesn't read through the warm cache");
+        }
+
         let chain_config = db.store.get_chain_config()?;
         let record_bal = chain_config.is_amsterdam_activated(block.header.timestamp);
 
@@ -316,20 +327,45 @@ impl LEVM {
     /// in parallel. This approach (inspired by Nethermind's per-sender prewarmer)
     /// improves warmup accuracy by avoiding nonce mismatches within sender groups.
     ///
-    /// The `store` parameter should be a `CachingDatabase`-wrapped store so that
-    /// parallel workers can benefit from shared caching. The same cache should
-    /// be used by the sequential execution phase.
+    /// Parallel workers read through `cache` (see [`CachingDatabase::warmer`])
+    /// and share what they load.  The sequential execution phase must read
+    /// through the same cache, e.g. one obtained from [`crate::Evm::warm_cache`],
+    /// for the warmup to pay off.  The handle is returned so its
+    /// [`CachingDatabase::stats`] can be inspected once the block is executed.
     pub fn warm_block(
         block: &Block,
-        store: Arc<dyn Database>,
+        cache: Arc<CachingDatabase>,
         vm_type: VMType,
-    ) -> Result<(), EvmError> {
+    ) -> Result<Arc<CachingDatabase>, EvmError> {
+        let store = cache.warmer();
         let mut db = GeneralizedDatabase::new(store.clone());
 
         let txs_with_sender = block.body.get_transactions_with_sender().map_err(|error| {
             EvmError::Transaction(format!("Couldn't recover addresses with error: {error}"))
         })?;
 
+        // Access lists declare the slots each transaction will touch, so load
+        // them up-front with one batched storage read per account instead of
+        // one trie walk per slot during execution.
+        let mut access_list_slots: FxHashMap<Address, Vec<H256>> = FxHashMap::default();
+        for (tx, _) in &txs_with_sender {
+            for (address, keys) in tx.access_list() {
+                access_list_slots
+                    .entry(*address)
+                    .or_default()
+                    .extend_from_slice(keys);
+            }
+        }
+        access_list_slots
+            .into_par_iter()
+            .for_each(|(address, mut keys)| {
+                keys.sort_unstable();
+                keys.dedup();
+                // Errors are ignored like in the rest of the warming, the
+                // sequential execution will surface them if they persist.
+                let _ = store.get_storage_values(address, &keys);
+            });
+
         // Group transactions by sender for sequential execution within groups
         let mut sender_groups: FxHashMap<Address, Vec<&Transaction>> = FxHashMap::default();
         for (tx, sender) in &txs_with_sender {
@@ -372,7 +408,7 @@ impl LEVM {
                 ))
             })?;
         }
-        Ok(())
+        Ok(cache)
     }
 
     fn send_state_transitions_tx(
@@ -451,6 +487,20 @@ impl LEVM {
         vm.execute().map_err(VMError::into)
     }
 
+    /// Like [`LEVM::execute_tx`], also returning the state the transaction read and wrote.
+    pub fn execute_tx_with_access_sets(
+        tx: &Transaction,
+        tx_sender: Address,
+        block_header: &BlockHeader,
+        db: &mut GeneralizedDatabase,
+        vm_type: VMType,
+    ) -> Result<(ExecutionReport, AccessSets), EvmError> {
+        let env = Self::setup_env(tx, tx_sender, block_header, db, vm_type)?;
+        let mut vm = VM::new(env, db, tx, LevmCallTracer::disabled(), vm_type)?;
+
+        vm.execute_with_access_sets().map_err(VMError::into)
+    }
+
     // Like execute_tx but allows reusing the stack pool
     fn execute_tx_in_block(
         // The transaction to execute.
@@ -482,6 +532,10 @@ impl LEVM {
         tx: &GenericTransaction,
         // The block header for the current block.
         block_header: &BlockHeader,
+        // PREVRANDAO to expose instead of the header's, for reproducible simulations.
+        randao_override: Option<H256>,
+        // Bytes of output to keep in the result, all of them when `None`.
+        output_cap: Option<usize>,
         db: &mut GeneralizedDatabase,
         vm_type: VMType,
     ) -> Result<ExecutionResult, EvmError> {
@@ -489,13 +543,19 @@ impl LEVM {
 
         env.block_gas_limit = i64::MAX as u64; // disable block gas limit
 
+        if let Some(randao) = randao_override {
+            env.prev_randao = Some(randao);
+        }
+
         adjust_disabled_base_fee(&mut env);
 
         let mut vm = vm_from_generic(tx, env, db, vm_type)?;
 
-        vm.execute()
-            .map(|value| value.into())
-            .map_err(VMError::into)
+        let mut result: ExecutionResult = vm.execute().map_err(EvmError::from)?.into();
+        if let Some(cap) = output_cap {
+            result.cap_output(cap);
+        }
+        Ok(result)
     }
 
     pub fn get_state_transitions(
@@ -553,11 +613,16 @@ impl LEVM {
             EvmError::Header("parent_beacon_block_root field is missing".to_string())
         })?;
 
+        let chain_config = db.store.get_chain_config()?;
+        let Some(beacon_roots_address) = chain_config.system_contracts.beacon_roots else {
+            return Ok(());
+        };
+
         generic_system_contract_levm(
             block_header,
             Bytes::copy_from_slice(beacon_root.as_bytes()),
             db,
-            BEACON_ROOTS_ADDRESS.address,
+            beacon_roots_address,
             SYSTEM_ADDRESS,
             vm_type,
         )?;
@@ -575,38 +640,86 @@ impl LEVM {
             ));
         }
 
+        let chain_config = db.store.get_chain_config()?;
+        let Some(history_storage_address) = chain_config.system_contracts.history_storage else {
+            return Ok(());
+        };
+
         generic_system_contract_levm(
             block_header,
             Bytes::copy_from_slice(block_header.parent_hash.as_bytes()),
             db,
-            HISTORY_STORAGE_ADDRESS.address,
+            history_storage_address,
             SYSTEM_ADDRESS,
             vm_type,
         )?;
         Ok(())
     }
+
+    /// Runs the extra system calls of the chain config in order, with the same
+    /// semantics as the protocol system calls. Calls not yet active at the
+    /// block's timestamp are skipped.
+    pub fn apply_extra_system_calls(
+        block_header: &BlockHeader,
+        db: &mut GeneralizedDatabase,
+        vm_type: VMType,
+    ) -> Result<(), EvmError> {
+        let chain_config = db.store.get_chain_config()?;
+
+        for system_call in chain_config
+            .system_contracts
+            .extra
+            .iter()
+            .filter(|system_call| system_call.activation_time <= block_header.timestamp)
+        {
+            let report = generic_system_contract_levm(
+                block_header,
+                extra_system_call_calldata(system_call.input, block_header),
+                db,
+                system_call.address,
+                SYSTEM_ADDRESS,
+                vm_type,
+            )?;
+
+            if let TxResult::Revert(vm_error) = report.result {
+                return Err(EvmError::SystemContractCallFailed(format!(
+                    "REVERT in extra system call to {:#x} with error: {vm_error:?}",
+                    system_call.address
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns `None` if the withdrawal request system call is disabled in the chain config.
     pub(crate) fn read_withdrawal_requests(
         block_header: &BlockHeader,
         db: &mut GeneralizedDatabase,
         vm_type: VMType,
-    ) -> Result<ExecutionReport, EvmError> {
+    ) -> Result<Option<ExecutionReport>, EvmError> {
         if let VMType::L2(_) = vm_type {
             return Err(EvmError::InvalidEVM(
                 "read_withdrawal_requests should not be called for L2 VM".to_string(),
             ));
         }
 
+        let chain_config = db.store.get_chain_config()?;
+        let Some(withdrawal_request_address) = chain_config.system_contracts.withdrawal_request
+        else {
+            return Ok(None);
+        };
+
         let report = generic_system_contract_levm(
             block_header,
             Bytes::new(),
             db,
-            WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS.address,
+            withdrawal_request_address,
             SYSTEM_ADDRESS,
             vm_type,
         )?;
 
         match report.result {
-            TxResult::Success => Ok(report),
+            TxResult::Success => Ok(Some(report)),
             // EIP-7002 specifies that a failed system call invalidates the entire block.
             TxResult::Revert(vm_error) => Err(EvmError::SystemContractCallFailed(format!(
                 "REVERT when reading withdrawal requests with error: {vm_error:?}. According to EIP-7002, the revert of this system call invalidates the block.",
@@ -614,28 +727,36 @@ impl LEVM {
         }
     }
 
+    /// Returns `None` if the consolidation request system call is disabled in the chain config.
     pub(crate) fn dequeue_consolidation_requests(
         block_header: &BlockHeader,
         db: &mut GeneralizedDatabase,
         vm_type: VMType,
-    ) -> Result<ExecutionReport, EvmError> {
+    ) -> Result<Option<ExecutionReport>, EvmError> {
         if let VMType::L2(_) = vm_type {
             return Err(EvmError::InvalidEVM(
                 "dequeue_consolidation_requests should not be called for L2 VM".to_string(),
             ));
         }
 
+        let chain_config = db.store.get_chain_config()?;
+        let Some(consolidation_request_address) =
+            chain_config.system_contracts.consolidation_request
+        else {
+            return Ok(None);
+        };
+
         let report = generic_system_contract_levm(
             block_header,
             Bytes::new(),
             db,
-            CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS.address,
+            consolidation_request_address,
             SYSTEM_ADDRESS,
             vm_type,
         )?;
 
         match report.result {
-            TxResult::Success => Ok(report),
+            TxResult::Success => Ok(Some(report)),
             // EIP-7251 specifies that a failed system call invalidates the entire block.
             TxResult::Revert(vm_error) => Err(EvmError::SystemContractCallFailed(format!(
                 "REVERT when dequeuing consolidation requests with error: {vm_error:?}. According to EIP-7251, the revert of this system call invalidates the block.",
@@ -677,15 +798,24 @@ impl LEVM {
         db: &mut GeneralizedDatabase,
         vm_type: VMType,
     ) -> Result<(), EvmError> {
-        let chain_config = db.store.get_chain_config()?;
-        let block_header = &block.header;
-        let fork = chain_config.fork(block_header.timestamp);
-
         // TODO: I don't like deciding the behavior based on the VMType here.
         if let VMType::L2(_) = vm_type {
             return Ok(());
         }
 
+        Self::apply_system_calls(&block.header, db, vm_type)
+    }
+
+    /// Runs the system calls done before executing the block's transactions:
+    /// the protocol ones followed by the chain's extra system calls.
+    pub fn apply_system_calls(
+        block_header: &BlockHeader,
+        db: &mut GeneralizedDatabase,
+        vm_type: VMType,
+    ) -> Result<(), EvmError> {
+        let chain_config = db.store.get_chain_config()?;
+        let fork = chain_config.fork(block_header.timestamp);
+
         if block_header.parent_beacon_block_root.is_some() && fork >= Fork::Cancun {
             Self::beacon_root_contract_call(block_header, db, vm_type)?;
         }
@@ -694,7 +824,8 @@ impl LEVM {
             //eip 2935: stores parent block hash in system contract
             Self::process_block_hash_history(block_header, db, vm_type)?;
         }
-        Ok(())
+
+        Self::apply_extra_system_calls(block_header, db, vm_type)
     }
 }
 
@@ -736,9 +867,9 @@ pub fn generic_system_contract_levm(
     // The error that should be returned for the relevant contracts is indicated in the following:
     // https://github.com/ethereum/EIPs/blob/master/EIPS/eip-7002.md#empty-code-failure
     // https://github.com/ethereum/EIPs/blob/master/EIPS/eip-7251.md#empty-code-failure
-    if PRAGUE_SYSTEM_CONTRACTS
-        .iter()
-        .any(|contract| contract.address == contract_address)
+    if chain_config
+        .system_contracts
+        .is_request_predeploy(contract_address)
         && db.get_account_code(contract_address)?.bytecode.is_empty()
     {
         return Err(EvmError::SystemContractCallFailed(format!(
@@ -809,11 +940,11 @@ pub fn extract_all_requests_levm(
     }
 
     let withdrawals_data: Vec<u8> = LEVM::read_withdrawal_requests(header, db, vm_type)?
-        .output
-        .into();
+        .map(|report| report.output.into())
+        .unwrap_or_default();
     let consolidation_data: Vec<u8> = LEVM::dequeue_consolidation_requests(header, db, vm_type)?
-        .output
-        .into();
+        .map(|report| report.output.into())
+        .unwrap_or_default();
 
     let deposits = Requests::from_deposit_receipts(chain_config.deposit_contract_address, receipts)
         .ok_or(EvmError::InvalidDepositRequest)?;
@@ -929,9 +1060,7 @@ fn env_from_generic(
 
     Ok(Environment {
         origin: tx.from.0.into(),
-        gas_limit: tx
-            .gas
-            .unwrap_or(get_max_allowed_gas_limit(header.gas_limit, config.fork)), // Ensure tx doesn't fail due to gas limit
+        gas_limit: effective_gas_limit(tx.gas, header.gas_limit, config.fork)?,
         config,
         block_number: header.number.into(),
         coinbase: header.coinbase,
@@ -999,10 +1128,34 @@ fn vm_from_generic<'a>(
     VM::new(env, db, &tx, LevmCallTracer::disabled(), vm_type)
 }
 
+/// Highest gas limit a transaction can be included with: the block gas limit,
+/// also capped from Osaka on (https://eips.ethereum.org/EIPS/eip-7825).
 pub fn get_max_allowed_gas_limit(block_gas_limit: u64, fork: Fork) -> u64 {
     if fork >= Fork::Osaka {
-        POST_OSAKA_GAS_LIMIT_CAP
+        POST_OSAKA_GAS_LIMIT_CAP.min(block_gas_limit)
     } else {
         block_gas_limit
     }
 }
+
+/// Gas limit to run a transaction with, the one given or the highest allowed if there's none.
+/// From Osaka on a gas limit above the EIP-7825 cap is an error, since the transaction
+/// couldn't be included anyway.
+/// Shared by simulations, gas estimation, access list creation and mempool validation
+/// so they agree on which gas limits are valid.
+pub fn effective_gas_limit(
+    tx_gas: Option<u64>,
+    block_gas_limit: u64,
+    fork: Fork,
+) -> Result<u64, TxValidationError> {
+    match tx_gas {
+        Some(gas_limit) if fork >= Fork::Osaka && gas_limit > POST_OSAKA_GAS_LIMIT_CAP => {
+            Err(TxValidationError::GasLimitAboveCap {
+                gas_limit,
+                cap: POST_OSAKA_GAS_LIMIT_CAP,
+            })
+        }
+        Some(gas_limit) => Ok(gas_limit),
+        None => Ok(get_max_allowed_gas_limit(block_gas_limit, fork)),
+    }
+}
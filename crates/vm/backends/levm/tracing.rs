@@ -1,5 +1,8 @@
 use ethrex_common::types::{Block, Transaction};
-use ethrex_common::{tracing::CallTrace, types::BlockHeader};
+use ethrex_common::{
+    tracing::{CallTrace, NativePrestate},
+    types::BlockHeader,
+};
 use ethrex_levm::vm::VMType;
 use ethrex_levm::{db::gen_db::GeneralizedDatabase, tracing::LevmCallTracer, vm::VM};
 
@@ -74,4 +77,32 @@ impl LEVM {
         // We only return the top call because a transaction only has one call with subcalls
         Ok(vec![callframe])
     }
+
+    /// Run transaction with the prestate tracer activated.
+    /// Returns the state the transaction read, as it was before executing it.
+    pub fn trace_tx_prestate(
+        db: &mut GeneralizedDatabase,
+        block_header: &BlockHeader,
+        tx: &Transaction,
+        vm_type: VMType,
+    ) -> Result<NativePrestate, EvmError> {
+        db.enable_prestate_tracing();
+        let env = Self::setup_env(
+            tx,
+            tx.sender().map_err(|error| {
+                EvmError::Transaction(format!("Couldn't recover addresses with error: {error}"))
+            })?,
+            block_header,
+            db,
+            vm_type,
+        )?;
+        let mut vm = VM::new(env, db, tx, LevmCallTracer::disabled(), vm_type)?;
+
+        let result = vm.execute();
+        // Take the trace even if execution failed so the tracer doesn't outlive the transaction
+        let prestate = db.take_prestate();
+        result?;
+
+        prestate?.ok_or(EvmError::Custom("Missing prestate trace".to_string()))
+    }
 }
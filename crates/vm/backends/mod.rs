@@ -7,14 +7,19 @@ use crate::execution_result::ExecutionResult;
 use ethrex_common::types::block_access_list::BlockAccessList;
 use ethrex_common::types::requests::Requests;
 use ethrex_common::types::{
-    AccessList, AccountUpdate, Block, BlockHeader, Fork, GenericTransaction, Receipt, Transaction,
+    AccessList, AccountUpdate, Block, BlockHeader, GenericTransaction, Receipt, Transaction,
     Withdrawal,
 };
-use ethrex_common::{Address, types::fee_config::FeeConfig};
+use ethrex_common::{Address, H256, types::fee_config::FeeConfig};
+pub use ethrex_levm::access_sets::{AccessSets, ReadSet, WriteSet};
 pub use ethrex_levm::call_frame::CallFrameBackup;
+pub use ethrex_levm::coverage::{CoverageMap, CoverageReport, merge_coverage};
+use ethrex_levm::db::KeccakCacheLimits;
 use ethrex_levm::db::gen_db::GeneralizedDatabase;
-pub use ethrex_levm::db::{CachingDatabase, Database as LevmDatabase};
+pub use ethrex_levm::db::{CacheStats, CachingDatabase, Database as LevmDatabase};
+use ethrex_levm::errors::ExecutionReport;
 use ethrex_levm::vm::VMType;
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::sync::atomic::AtomicUsize;
 use std::sync::mpsc::Sender;
@@ -42,10 +47,12 @@ impl Evm {
         }
     }
 
+    /// Creates a new L2 EVM instance, failing if `fee_config` can't be charged on the chain.
     pub fn new_for_l2(
         db: impl VmDatabase + 'static,
         fee_config: FeeConfig,
     ) -> Result<Self, EvmError> {
+        fee_config.validate(&db.get_chain_config()?)?;
         let wrapped_db: DynVmDatabase = Box::new(db);
 
         let evm = Evm {
@@ -74,6 +81,29 @@ impl Evm {
         }
     }
 
+    /// Wraps the store in a [`CachingDatabase`] and returns its handle.
+    ///
+    /// Pass the handle to [`LEVM::warm_block`] and to
+    /// [`Evm::execute_block_pipeline`] so execution reads what the warmer loaded.
+    pub fn warm_cache(&mut self) -> Arc<CachingDatabase> {
+        self.install_warm_cache(CachingDatabase::new(self.db.store.clone()))
+    }
+
+    /// Like [`Evm::warm_cache`], also memoizing the KECCAK256 of repeated
+    /// preimages for the whole block, see [`ethrex_levm::db::KeccakCache`].
+    pub fn warm_cache_with_keccak(&mut self) -> Arc<CachingDatabase> {
+        self.install_warm_cache(CachingDatabase::with_keccak_limits(
+            self.db.store.clone(),
+            KeccakCacheLimits::default(),
+        ))
+    }
+
+    fn install_warm_cache(&mut self, cache: CachingDatabase) -> Arc<CachingDatabase> {
+        let cache = Arc::new(cache);
+        self.db.store = cache.clone();
+        cache
+    }
+
     /// Execute a block and return the execution result.
     ///
     /// Also records and returns the Block Access List (EIP-7928) for Amsterdam+ forks.
@@ -94,10 +124,18 @@ impl Evm {
     pub fn execute_block_pipeline(
         &mut self,
         block: &Block,
+        warm_cache: Option<&Arc<CachingDatabase>>,
         merkleizer: Sender<Vec<AccountUpdate>>,
         queue_length: &AtomicUsize,
     ) -> Result<(BlockExecutionResult, Option<BlockAccessList>), EvmError> {
-        LEVM::execute_block_pipeline(block, &mut self.db, self.vm_type, merkleizer, queue_length)
+        LEVM::execute_block_pipeline(
+            block,
+            &mut self.db,
+            self.vm_type,
+            warm_cache,
+            merkleizer,
+            queue_length,
+        )
     }
 
     /// Wraps [LEVM::execute_tx].
@@ -133,25 +171,26 @@ impl Evm {
         Ok((receipt, execution_report.gas_spent))
     }
 
+    /// Wraps [LEVM::execute_tx_with_access_sets].
+    /// Executes the transaction and returns its report with the accounts and storage slots it
+    /// read and wrote, without recording a block access list.
+    pub fn execute_tx_with_access_sets(
+        &mut self,
+        tx: &Transaction,
+        block_header: &BlockHeader,
+        sender: Address,
+    ) -> Result<(ExecutionReport, AccessSets), EvmError> {
+        LEVM::execute_tx_with_access_sets(tx, sender, block_header, &mut self.db, self.vm_type)
+    }
+
     pub fn undo_last_tx(&mut self) -> Result<(), EvmError> {
         LEVM::undo_last_tx(&mut self.db)
     }
 
-    /// Wraps [LEVM::beacon_root_contract_call], [LEVM::process_block_hash_history].
+    /// Wraps [LEVM::apply_system_calls].
     /// This function is used to run/apply all the system contracts to the state.
     pub fn apply_system_calls(&mut self, block_header: &BlockHeader) -> Result<(), EvmError> {
-        let chain_config = self.db.store.get_chain_config()?;
-        let fork = chain_config.fork(block_header.timestamp);
-
-        if block_header.parent_beacon_block_root.is_some() && fork >= Fork::Cancun {
-            LEVM::beacon_root_contract_call(block_header, &mut self.db, self.vm_type)?;
-        }
-
-        if fork >= Fork::Prague {
-            LEVM::process_block_hash_history(block_header, &mut self.db, self.vm_type)?;
-        }
-
-        Ok(())
+        LEVM::apply_system_calls(block_header, &mut self.db, self.vm_type)
     }
 
     /// Wraps the [LEVM::get_state_transitions] which gathers the information from a [CacheDB].
@@ -190,12 +229,60 @@ impl Evm {
         self.db.set_bal_index(index);
     }
 
+    /// Enables incremental BAL recording for a payload under construction (EIP-7928).
+    pub fn begin_payload_bal(&mut self) {
+        self.db.begin_payload_bal();
+    }
+
+    /// Starts recording the BAL contributions of the transaction at `index`.
+    pub fn record_tx(&mut self, index: u16) {
+        self.db.record_tx(index);
+    }
+
+    /// Retracts the BAL contributions of the transaction at `index`.
+    pub fn remove_tx(&mut self, index: u16) {
+        self.db.remove_tx(index);
+    }
+
+    /// Builds the payload's Block Access List if incremental recording was enabled.
+    pub fn finalize_payload_bal(&mut self) -> Option<BlockAccessList> {
+        self.db.finalize_payload_bal()
+    }
+
+    /// Starts recording the bytecode executed by every following transaction, see
+    /// [`CoverageMap`].
+    pub fn enable_coverage(&mut self) {
+        self.db.enable_coverage();
+    }
+
+    /// Takes the coverage recorded since it was enabled or last taken, by code hash.
+    /// Combine the coverage of many calls with [`merge_coverage`].
+    pub fn take_coverage(&mut self) -> HashMap<H256, CoverageMap> {
+        self.db.take_coverage()
+    }
+
+    /// Executes `tx` on top of `header` without committing it.
+    ///
+    /// `randao_override` replaces the PREVRANDAO value seen by the transaction;
+    /// when `None` the header's `prev_randao` is used.
+    ///
+    /// `output_cap` bounds the bytes of output kept in the result, see
+    /// [`ExecutionResult::cap_output`]; when `None` the whole output is kept.
     pub fn simulate_tx_from_generic(
         &mut self,
         tx: &GenericTransaction,
         header: &BlockHeader,
+        randao_override: Option<H256>,
+        output_cap: Option<usize>,
     ) -> Result<ExecutionResult, EvmError> {
-        LEVM::simulate_tx_from_generic(tx, header, &mut self.db, self.vm_type)
+        LEVM::simulate_tx_from_generic(
+            tx,
+            header,
+            randao_override,
+            output_cap,
+            &mut self.db,
+            self.vm_type,
+        )
     }
 
     pub fn create_access_list(
@@ -212,6 +299,8 @@ impl Evm {
                     gas_refunded: _,
                     logs: _,
                     output: _,
+                    truncated_output: _,
+                    l2_fees: _,
                 },
                 access_list,
             ) => Ok((gas_used, access_list, None)),
@@ -219,6 +308,8 @@ impl Evm {
                 ExecutionResult::Revert {
                     gas_used,
                     output: _,
+                    truncated_output: _,
+                    l2_fees: _,
                 },
                 access_list,
             ) => Ok((
@@ -226,9 +317,12 @@ impl Evm {
                 access_list,
                 Some("Transaction Reverted".to_string()),
             )),
-            (ExecutionResult::Halt { reason, gas_used }, access_list) => {
-                Ok((gas_used, access_list, Some(reason)))
-            }
+            (
+                ExecutionResult::Halt {
+                    reason, gas_used, ..
+                },
+                access_list,
+            ) => Ok((gas_used, access_list, Some(reason))),
         }
     }
 }
@@ -8,6 +8,19 @@ use ethrex_common::{
 pub trait VmDatabase: Send + Sync + DynClone {
     fn get_account_state(&self, address: Address) -> Result<Option<AccountState>, EvmError>;
     fn get_storage_slot(&self, address: Address, key: H256) -> Result<Option<U256>, EvmError>;
+    /// Reads several storage slots of the same account, in the order of `keys`.
+    ///
+    /// Implementors backed by a trie should override this to share the
+    /// account and storage trie lookups across keys.
+    fn get_storage_slots(
+        &self,
+        address: Address,
+        keys: &[H256],
+    ) -> Result<Vec<Option<U256>>, EvmError> {
+        keys.iter()
+            .map(|key| self.get_storage_slot(address, *key))
+            .collect()
+    }
     fn get_block_hash(&self, block_number: u64) -> Result<H256, EvmError>;
     fn get_chain_config(&self) -> Result<ChainConfig, EvmError>;
     fn get_account_code(&self, code_hash: H256) -> Result<Code, EvmError>;
@@ -1,10 +1,15 @@
-use ethrex_levm::errors::{DatabaseError as LevmDatabaseError, InternalError, VMError};
+use ethrex_common::types::fee_config::FeeConfigError;
+use ethrex_levm::errors::{
+    DatabaseError as LevmDatabaseError, InternalError, TxValidationError, VMError,
+};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
 pub enum EvmError {
     #[error("Invalid Transaction: {0}")]
     Transaction(String),
+    #[error("Invalid Transaction: {0}")]
+    TxValidation(TxValidationError),
     #[error("Invalid Header: {0}")]
     Header(String),
     #[error("DB error: {0}")]
@@ -19,15 +24,17 @@ pub enum EvmError {
     InvalidDepositRequest,
     #[error("System call failed: {0}")]
     SystemContractCallFailed(String),
+    #[error("Invalid fee config: {0}")]
+    InvalidFeeConfig(#[from] FeeConfigError),
 }
 
 impl From<VMError> for EvmError {
     fn from(value: VMError) -> Self {
-        if value.should_propagate() {
-            EvmError::Custom(value.to_string())
-        } else {
+        match value {
+            VMError::TxValidation(err) => EvmError::TxValidation(err),
+            other if other.should_propagate() => EvmError::Custom(other.to_string()),
             // If an error is not internal it means it is a transaction validation error.
-            EvmError::Transaction(value.to_string())
+            other => EvmError::Transaction(other.to_string()),
         }
     }
 }
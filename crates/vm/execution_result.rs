@@ -1,6 +1,17 @@
 use bytes::Bytes;
 use ethrex_common::types::Log;
+use ethrex_common::utils::keccak;
+use ethrex_common::{Address, H256, U256};
 use ethrex_levm::errors::{ExecutionReport as LevmExecutionReport, TxResult};
+use ethrex_levm::hooks::L2FeeBreakdown;
+
+/// Selector of `Error(string)`, the revert data of `require` and `revert("...")`.
+const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+/// Selector of `Panic(uint256)`, the revert data of failed asserts and checked arithmetic.
+const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Default of the output cap of simulations, see [`ExecutionResult::cap_output`].
+pub const DEFAULT_SIMULATION_OUTPUT_CAP: usize = 8 * 1024 * 1024;
 
 #[derive(Debug)]
 pub enum ExecutionResult {
@@ -9,14 +20,25 @@ pub enum ExecutionResult {
         gas_refunded: u64,
         logs: Vec<Log>,
         output: Bytes,
+        /// Set when `output` was cut to the cap of a simulation.
+        truncated_output: Option<TruncatedOutput>,
+        /// Itemized fees, only for non-privileged L2 transactions.
+        l2_fees: Option<L2FeeBreakdown>,
     },
     /// Reverted by `REVERT` opcode
-    Revert { gas_used: u64, output: Bytes },
+    Revert {
+        gas_used: u64,
+        output: Bytes,
+        /// Set when `output` was cut to the cap of a simulation.
+        truncated_output: Option<TruncatedOutput>,
+        l2_fees: Option<L2FeeBreakdown>,
+    },
     /// Reverted for other reasons, spends all gas.
     Halt {
         reason: String,
         /// Halting will spend all the gas, which will be equal to gas_limit.
         gas_used: u64,
+        l2_fees: Option<L2FeeBreakdown>,
     },
 }
 
@@ -32,11 +54,25 @@ impl ExecutionResult {
         }
     }
     pub fn logs(&self) -> Vec<Log> {
+        self.logs_slice().to_vec()
+    }
+
+    fn logs_slice(&self) -> &[Log] {
         match self {
-            ExecutionResult::Success { logs, .. } => logs.clone(),
-            _ => vec![],
+            ExecutionResult::Success { logs, .. } => logs,
+            _ => &[],
         }
     }
+
+    /// Logs emitted by the transaction that `filter` selects, in emission order.
+    pub fn logs_matching<'a>(&'a self, filter: &'a LogFilter) -> impl Iterator<Item = &'a Log> {
+        self.logs_slice().iter().filter(|log| filter.matches(log))
+    }
+
+    /// First log of `event` emitted by the transaction, split into its parameters.
+    pub fn first_log_decoded(&self, event: &AbiEvent) -> Option<DecodedLog<'_>> {
+        self.logs_slice().iter().find_map(|log| event.decode(log))
+    }
     pub fn gas_refunded(&self) -> u64 {
         match self {
             ExecutionResult::Success { gas_refunded, .. } => *gas_refunded,
@@ -44,6 +80,13 @@ impl ExecutionResult {
         }
     }
 
+    pub fn l2_fees(&self) -> Option<L2FeeBreakdown> {
+        match self {
+            ExecutionResult::Success { l2_fees, .. } => *l2_fees,
+            ExecutionResult::Revert { l2_fees, .. } => *l2_fees,
+            ExecutionResult::Halt { l2_fees, .. } => *l2_fees,
+        }
+    }
     pub fn output(&self) -> Bytes {
         match self {
             ExecutionResult::Success { output, .. } => output.clone(),
@@ -51,6 +94,184 @@ impl ExecutionResult {
             ExecutionResult::Halt { .. } => Bytes::new(),
         }
     }
+
+    /// Length and hash of the full output if it was cut to the cap of a simulation.
+    pub fn truncated_output(&self) -> Option<TruncatedOutput> {
+        match self {
+            ExecutionResult::Success {
+                truncated_output, ..
+            }
+            | ExecutionResult::Revert {
+                truncated_output, ..
+            } => *truncated_output,
+            ExecutionResult::Halt { .. } => None,
+        }
+    }
+
+    /// Keeps at most `cap` bytes of the output, recording the length and hash of the full
+    /// output when it's longer. Consensus execution never caps the output.
+    pub fn cap_output(&mut self, cap: usize) {
+        let (ExecutionResult::Success {
+            output,
+            truncated_output,
+            ..
+        }
+        | ExecutionResult::Revert {
+            output,
+            truncated_output,
+            ..
+        }) = self
+        else {
+            return;
+        };
+        if output.len() <= cap {
+            return;
+        }
+        *truncated_output = Some(TruncatedOutput {
+            len: output.len(),
+            hash: keccak(output.as_ref()),
+        });
+        // Copied so that the full output is freed instead of kept alive by a slice of it
+        *output = Bytes::copy_from_slice(&output[..cap]);
+    }
+
+    /// Why the transaction failed, `None` if it succeeded.
+    pub fn revert_reason(&self) -> Option<RevertReason> {
+        match self {
+            ExecutionResult::Success { .. } => None,
+            ExecutionResult::Revert { output, .. } => Some(RevertReason::decode(output)),
+            ExecutionResult::Halt { reason, .. } => Some(RevertReason::Halt(reason.clone())),
+        }
+    }
+}
+
+/// Length and hash of an output cut by [`ExecutionResult::cap_output`], so that callers
+/// can tell the output was truncated and still compare it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TruncatedOutput {
+    pub len: usize,
+    pub hash: H256,
+}
+
+/// Why a transaction failed, decoded from its revert data when it follows the Solidity
+/// conventions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RevertReason {
+    /// `Error(string)`, raised by `require` and `revert("...")`.
+    Error(String),
+    /// `Panic(uint256)`, raised by failed asserts, checked arithmetic and bad indices.
+    Panic(U256),
+    /// Custom errors and any other revert data, empty for a bare `revert()`.
+    Custom(Bytes),
+    /// Halted by an exceptional condition, such as running out of gas.
+    Halt(String),
+}
+
+impl RevertReason {
+    /// Decodes the data returned by `REVERT`. Data not encoding an `Error` or a `Panic`
+    /// is kept as is.
+    pub fn decode(output: &Bytes) -> Self {
+        let (selector, args) = output.split_at(output.len().min(4));
+        if selector == ERROR_SELECTOR {
+            if let Some(message) = decode_abi_string(args) {
+                return RevertReason::Error(message);
+            }
+        } else if selector == PANIC_SELECTOR && args.len() == 32 {
+            return RevertReason::Panic(U256::from_big_endian(args));
+        }
+        RevertReason::Custom(output.clone())
+    }
+}
+
+/// Decodes the ABI encoding of a single `string`, `None` if malformed.
+fn decode_abi_string(args: &[u8]) -> Option<String> {
+    let word = |offset: usize| -> Option<usize> {
+        let word = args.get(offset..offset.checked_add(32)?)?;
+        let word = u64::try_from(U256::from_big_endian(word)).ok()?;
+        usize::try_from(word).ok()
+    };
+    let offset = word(0)?;
+    let len = word(offset)?;
+    let start = offset.checked_add(32)?;
+    let bytes = args.get(start..start.checked_add(len)?)?;
+    String::from_utf8(bytes.to_vec()).ok()
+}
+
+/// Selects logs by emitter and topics, with the semantics of an `eth_getLogs` filter.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LogFilter {
+    /// Emitters to select, any when empty.
+    pub addresses: Vec<Address>,
+    /// Topics by position, each matching any of its values or anything when empty.
+    pub topics: Vec<Vec<H256>>,
+}
+
+impl LogFilter {
+    pub fn matches(&self, log: &Log) -> bool {
+        if !self.addresses.is_empty() && !self.addresses.contains(&log.address) {
+            return false;
+        }
+        self.topics.iter().enumerate().all(|(position, topics)| {
+            topics.is_empty()
+                || log
+                    .topics
+                    .get(position)
+                    .is_some_and(|topic| topics.contains(topic))
+        })
+    }
+}
+
+impl From<&AbiEvent> for LogFilter {
+    fn from(event: &AbiEvent) -> Self {
+        LogFilter {
+            addresses: vec![],
+            topics: vec![vec![event.topic()]],
+        }
+    }
+}
+
+/// A Solidity event, by its signature and number of indexed parameters. For instance
+/// `Transfer(address,address,uint256)` has its first 2 parameters indexed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbiEvent {
+    pub signature: String,
+    pub indexed: usize,
+}
+
+impl AbiEvent {
+    pub fn new(signature: impl Into<String>, indexed: usize) -> Self {
+        Self {
+            signature: signature.into(),
+            indexed,
+        }
+    }
+
+    /// First topic of the logs of the event, the hash of its signature.
+    pub fn topic(&self) -> H256 {
+        keccak(self.signature.as_bytes())
+    }
+
+    /// Splits `log` into the parameters of the event, `None` if it's not a log of it.
+    pub fn decode<'a>(&self, log: &'a Log) -> Option<DecodedLog<'a>> {
+        let (topic, indexed) = log.topics.split_first()?;
+        if *topic != self.topic() || indexed.len() != self.indexed || log.data.len() % 32 != 0 {
+            return None;
+        }
+        Some(DecodedLog {
+            address: log.address,
+            indexed,
+            data: log.data.chunks_exact(32).map(H256::from_slice).collect(),
+        })
+    }
+}
+
+/// Parameters of a log of an [`AbiEvent`], as 32-byte words. Indexed parameters of dynamic
+/// types are the hash of their value, the rest are the head words of their ABI encoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedLog<'a> {
+    pub address: Address,
+    pub indexed: &'a [H256],
+    pub data: Vec<H256>,
 }
 
 impl From<LevmExecutionReport> for ExecutionResult {
@@ -61,17 +282,22 @@ impl From<LevmExecutionReport> for ExecutionResult {
                 gas_refunded: val.gas_refunded,
                 logs: val.logs,
                 output: val.output,
+                truncated_output: None,
+                l2_fees: val.l2_fees,
             },
             TxResult::Revert(error) => {
                 if error.is_revert_opcode() {
                     ExecutionResult::Revert {
                         gas_used: val.gas_used,
                         output: val.output,
+                        truncated_output: None,
+                        l2_fees: val.l2_fees,
                     }
                 } else {
                     ExecutionResult::Halt {
                         reason: error.to_string(),
                         gas_used: val.gas_used,
+                        l2_fees: val.l2_fees,
                     }
                 }
             }
@@ -0,0 +1,137 @@
+//! Opt-in recording of the state read and written by a single transaction.
+//!
+//! Enabled by executing with [`VM::execute_with_access_sets`]. Reads are recorded by the
+//! [`GeneralizedDatabase`] as accounts and storage slots are loaded, writes are derived from the
+//! transaction backup by comparing the original values it holds with the final ones. Unlike the
+//! BAL recorder nothing is ordered by block access index and no values are kept.
+
+use std::collections::BTreeSet;
+
+use ethrex_common::{Address, H256};
+
+use crate::{
+    db::gen_db::GeneralizedDatabase,
+    errors::{ExecutionReport, InternalError, VMError},
+    hooks::backup_hook::BackupHook,
+    vm::VM,
+};
+
+/// State loaded by a transaction, whether it then modified it or not.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReadSet {
+    /// Accounts loaded, for their balance, nonce, code or as the owner of a storage slot.
+    pub accounts: BTreeSet<Address>,
+    /// Storage slots read, including the ones read by SSTORE.
+    pub storage: BTreeSet<(Address, H256)>,
+}
+
+/// State a transaction changed. Values written back to what they were are not included.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WriteSet {
+    pub balances: BTreeSet<Address>,
+    pub nonces: BTreeSet<Address>,
+    pub codes: BTreeSet<Address>,
+    pub storage: BTreeSet<(Address, H256)>,
+}
+
+impl WriteSet {
+    /// Accounts whose balance, nonce or code changed.
+    pub fn accounts(&self) -> BTreeSet<Address> {
+        self.balances
+            .iter()
+            .chain(&self.nonces)
+            .chain(&self.codes)
+            .copied()
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.balances.is_empty()
+            && self.nonces.is_empty()
+            && self.codes.is_empty()
+            && self.storage.is_empty()
+    }
+
+    /// True if this writes an account or slot that `other` read or wrote.
+    fn overlaps(&self, other: &AccessSets) -> bool {
+        let other_accounts = other.writes.accounts();
+        let account_overlaps = |address: &Address| {
+            other.reads.accounts.contains(address) || other_accounts.contains(address)
+        };
+        let slot_overlaps = |slot: &(Address, H256)| {
+            other.reads.storage.contains(slot) || other.writes.storage.contains(slot)
+        };
+        self.accounts().iter().any(account_overlaps) || self.storage.iter().any(slot_overlaps)
+    }
+}
+
+/// State read and written by a transaction, see [`VM::execute_with_access_sets`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccessSets {
+    pub reads: ReadSet,
+    pub writes: WriteSet,
+}
+
+impl AccessSets {
+    /// True if the transactions of `self` and `other` may not commute, i.e. one of them changes
+    /// an account or storage slot the other reads or changes.
+    pub fn conflicts_with(&self, other: &AccessSets) -> bool {
+        self.writes.overlaps(other) || other.writes.overlaps(self)
+    }
+}
+
+impl GeneralizedDatabase {
+    /// Changes made by the last transaction, taken from its backup.
+    /// It only works if the `BackupHook` was enabled during the transaction execution.
+    pub fn tx_write_set(&self) -> Result<WriteSet, InternalError> {
+        let backup = self.tx_backup.as_ref().ok_or_else(|| {
+            InternalError::Custom(
+                "Transaction backup not found. Was BackupHook enabled?".to_string(),
+            )
+        })?;
+        let mut writes = WriteSet::default();
+        for (address, original) in &backup.original_accounts_info {
+            let current = self
+                .current_accounts_state
+                .get(address)
+                .ok_or(InternalError::AccountNotFound)?;
+            if current.info.balance != original.info.balance {
+                writes.balances.insert(*address);
+            }
+            if current.info.nonce != original.info.nonce {
+                writes.nonces.insert(*address);
+            }
+            if current.info.code_hash != original.info.code_hash {
+                writes.codes.insert(*address);
+            }
+        }
+        for (address, slots) in &backup.original_account_storage_slots {
+            let current = self
+                .current_accounts_state
+                .get(address)
+                .ok_or(InternalError::AccountNotFound)?;
+            for (key, original) in slots {
+                // Slots of destroyed accounts are dropped, they read as zero
+                let value = current.storage.get(key).copied().unwrap_or_default();
+                if value != *original {
+                    writes.storage.insert((*address, *key));
+                }
+            }
+        }
+        Ok(writes)
+    }
+}
+
+impl<'a> VM<'a> {
+    /// Executes the transaction and returns the state it read and wrote along with the report.
+    pub fn execute_with_access_sets(&mut self) -> Result<(ExecutionReport, AccessSets), VMError> {
+        self.add_hook(BackupHook::default());
+        self.db.read_set = Some(ReadSet::default());
+        let result = self.execute();
+        // Take the reads even if execution failed so recording doesn't outlive the transaction
+        let reads = self.db.read_set.take().unwrap_or_default();
+        let report = result?;
+        let writes = self.db.tx_write_set()?;
+        Ok((report, AccessSets { reads, writes }))
+    }
+}
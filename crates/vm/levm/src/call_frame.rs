@@ -259,6 +259,13 @@ pub struct CallFrame {
     pub is_static: bool,
     /// Call stack current depth
     pub depth: usize,
+    /// Set when a frame running the same code (`code_address`) on the same storage (`to`) was
+    /// already in the call stack when this frame was pushed. Holds how many frames up the
+    /// closest one is, 1 being the parent.
+    ///
+    /// A proxy delegating to its implementation is not reentrant: the storage is the same but
+    /// the code isn't.
+    pub reentrancy: Option<usize>,
     /// This is set to true if the function that created this callframe is CREATE or CREATE2
     pub is_create: bool,
     /// Everytime we want to write an account during execution of a callframe we store the pre-write state so that we can restore if it reverts
@@ -269,12 +276,17 @@ pub struct CallFrame {
     pub ret_size: usize,
     /// If true then transfer value from caller to callee
     pub should_transfer_value: bool,
+    /// Code hash the executed opcodes are recorded under, set on the first opcode when
+    /// coverage is enabled.
+    pub coverage_key: Option<H256>,
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, Default)]
 pub struct CallFrameBackup {
     pub original_accounts_info: HashMap<Address, LevmAccount>,
     pub original_account_storage_slots: HashMap<Address, HashMap<H256, U256>>,
+    /// Hashes of the codes first inserted into the database's code cache, removed on restore.
+    pub inserted_codes: Vec<H256>,
     /// BAL checkpoint for EIP-7928 - used to restore state changes on revert
     /// while preserving touched_addresses.
     pub bal_checkpoint: Option<BlockAccessListCheckpoint>,
@@ -301,6 +313,7 @@ impl CallFrameBackup {
     pub fn clear(&mut self) {
         self.original_accounts_info.clear();
         self.original_account_storage_slots.clear();
+        self.inserted_codes.clear();
         self.bal_checkpoint = None;
     }
 
@@ -309,6 +322,7 @@ impl CallFrameBackup {
             .extend(other.original_account_storage_slots);
         self.original_accounts_info
             .extend(other.original_accounts_info);
+        self.inserted_codes.extend(other.inserted_codes);
         // Don't extend bal_checkpoint - it's specific to each call frame
     }
 }
@@ -349,6 +363,7 @@ impl CallFrame {
             calldata,
             is_static,
             depth,
+            reentrancy: None,
             should_transfer_value,
             is_create,
             ret_offset,
@@ -359,6 +374,7 @@ impl CallFrame {
             output: Bytes::default(),
             pc: 0,
             sub_return_data: Bytes::default(),
+            coverage_key: None,
         }
     }
 
@@ -400,8 +416,26 @@ impl CallFrame {
 
 impl<'a> VM<'a> {
     /// Adds current calframe to call_frames, sets current call frame to the passed callframe.
+    /// Also annotates the new callframe with its reentrancy distance and tracks the max depth.
     #[inline(always)]
-    pub fn add_callframe(&mut self, new_call_frame: CallFrame) {
+    pub fn add_callframe(&mut self, mut new_call_frame: CallFrame) {
+        if self.call_frames.is_empty() {
+            // The initial frame isn't pushed through here, register it before its first call
+            let initial = &self.current_call_frame;
+            self.active_frames
+                .insert((initial.to, initial.code_address), (1, initial.depth));
+        }
+        let active = self
+            .active_frames
+            .entry((new_call_frame.to, new_call_frame.code_address))
+            .or_insert((0, new_call_frame.depth));
+        if active.0 > 0 {
+            new_call_frame.reentrancy = Some(new_call_frame.depth.saturating_sub(active.1));
+        }
+        *active = (active.0.saturating_add(1), new_call_frame.depth);
+        self.max_depth = self.max_depth.max(new_call_frame.depth);
+        self.tracer.annotate_reentrancy(new_call_frame.reentrancy);
+
         self.call_frames.push(new_call_frame);
         #[allow(unsafe_code, reason = "just pushed, so the vec is not empty")]
         unsafe {
@@ -418,6 +452,22 @@ impl<'a> VM<'a> {
 
         std::mem::swap(&mut new, &mut self.current_call_frame);
 
+        let key = (new.to, new.code_address);
+        match new.reentrancy {
+            // The next innermost frame with the same code and storage becomes the closest one
+            Some(distance) => {
+                if let Some(active) = self.active_frames.get_mut(&key) {
+                    *active = (
+                        active.0.saturating_sub(1),
+                        new.depth.saturating_sub(distance),
+                    );
+                }
+            }
+            None => {
+                self.active_frames.remove(&key);
+            }
+        }
+
         Ok(new)
     }
 
@@ -465,6 +515,11 @@ impl<'a> VM<'a> {
             }
         }
 
+        self.current_call_frame
+            .call_frame_backup
+            .inserted_codes
+            .extend_from_slice(&child_call_frame_backup.inserted_codes);
+
         Ok(())
     }
 
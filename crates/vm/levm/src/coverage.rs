@@ -0,0 +1,305 @@
+//! Opt-in recording of the bytecode executed by the VM, for audits and differential fuzzing.
+//!
+//! Enabled with [`GeneralizedDatabase::enable_coverage`], so that coverage accumulates over
+//! every transaction executed against the same database. When disabled the interpreter loop
+//! only checks a local bool per opcode, when enabled it sets one bit per opcode.
+
+use std::{
+    collections::{HashMap, hash_map::Entry},
+    ops::Range,
+};
+
+use bitvec::vec::BitVec;
+use bytes::Bytes;
+use ethrex_common::{H256, types::Code, utils::keccak};
+use serde::Serialize;
+
+use crate::{db::gen_db::GeneralizedDatabase, vm::VM};
+
+const STOP: u8 = 0x00;
+const JUMP: u8 = 0x56;
+const JUMPI: u8 = 0x57;
+const JUMPDEST: u8 = 0x5b;
+const PUSH1: u8 = 0x60;
+const PUSH32: u8 = 0x7f;
+const RETURN: u8 = 0xf3;
+const REVERT: u8 = 0xfd;
+const INVALID: u8 = 0xfe;
+const SELFDESTRUCT: u8 = 0xff;
+
+/// Program counters executed in a bytecode.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoverageMap {
+    bytecode: Bytes,
+    /// One bit per byte of the bytecode, set at the offsets of the executed opcodes.
+    covered: BitVec,
+}
+
+impl CoverageMap {
+    pub fn new(bytecode: Bytes) -> Self {
+        let covered = BitVec::repeat(false, bytecode.len());
+        Self { bytecode, covered }
+    }
+
+    pub fn bytecode(&self) -> &Bytes {
+        &self.bytecode
+    }
+
+    /// Marks the opcode at `pc` as executed. The implicit STOP past the end of the code is
+    /// not recorded.
+    #[inline]
+    pub fn record(&mut self, pc: usize) {
+        if let Some(mut bit) = self.covered.get_mut(pc) {
+            *bit = true;
+        }
+    }
+
+    pub fn is_covered(&self, pc: usize) -> bool {
+        self.covered.get(pc).is_some_and(|bit| *bit)
+    }
+
+    /// Adds the opcodes executed in `other`, which must be coverage of the same bytecode.
+    pub fn merge(&mut self, other: &CoverageMap) {
+        for pc in other.covered.iter_ones() {
+            self.record(pc);
+        }
+    }
+
+    /// Offsets of the instructions of the bytecode, push data excluded.
+    pub fn instructions(&self) -> Vec<usize> {
+        decode_instructions(&self.bytecode)
+            .into_iter()
+            .map(|instruction| instruction.start)
+            .collect()
+    }
+
+    /// Executed instructions, as ranges of offsets joining adjacent instructions. Ranges
+    /// include the push data of the instructions.
+    pub fn covered_ranges(&self) -> Vec<Range<usize>> {
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for instruction in decode_instructions(&self.bytecode) {
+            if !self.is_covered(instruction.start) {
+                continue;
+            }
+            match ranges.last_mut() {
+                Some(last) if last.end == instruction.start => last.end = instruction.end,
+                _ => ranges.push(instruction),
+            }
+        }
+        ranges
+    }
+
+    /// Basic blocks of the bytecode, as ranges of offsets.
+    ///
+    /// A block starts at the first instruction, at each JUMPDEST and after each JUMPI, and
+    /// ends with the instruction leaving it. Code after an unconditional jump or halt that
+    /// isn't a JUMPDEST can't be reached and is left out, such as the metadata Solidity
+    /// appends to contracts.
+    pub fn basic_blocks(&self) -> Vec<Range<usize>> {
+        let mut blocks = Vec::new();
+        let mut start = Some(0);
+        for instruction in decode_instructions(&self.bytecode) {
+            let opcode = self
+                .bytecode
+                .get(instruction.start)
+                .copied()
+                .unwrap_or(STOP);
+            if opcode == JUMPDEST {
+                if let Some(start) = start.filter(|start| *start < instruction.start) {
+                    blocks.push(start..instruction.start);
+                }
+                start = Some(instruction.start);
+            }
+            let Some(block_start) = start else {
+                continue;
+            };
+            match opcode {
+                JUMPI => {
+                    blocks.push(block_start..instruction.end);
+                    start = Some(instruction.end);
+                }
+                JUMP | STOP | RETURN | REVERT | INVALID | SELFDESTRUCT => {
+                    blocks.push(block_start..instruction.end);
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(start) = start.filter(|start| *start < self.bytecode.len()) {
+            blocks.push(start..self.bytecode.len());
+        }
+        blocks
+    }
+
+    /// Summary of the coverage of the bytecode with hash `code_hash`. A basic block is
+    /// covered when its first instruction was executed.
+    pub fn report(&self, code_hash: H256) -> CoverageReport {
+        let instructions = self.instructions();
+        let basic_blocks = self.basic_blocks();
+        let covered_basic_blocks = basic_blocks
+            .iter()
+            .filter(|block| self.is_covered(block.start))
+            .count();
+        CoverageReport {
+            code_hash,
+            code_size: self.bytecode.len(),
+            covered_ranges: self
+                .covered_ranges()
+                .into_iter()
+                .map(|range| (range.start, range.end))
+                .collect(),
+            covered_instructions: instructions
+                .iter()
+                .filter(|pc| self.is_covered(**pc))
+                .count(),
+            instructions: instructions.len(),
+            basic_blocks: basic_blocks.len(),
+            covered_basic_blocks,
+            basic_block_percentage: percentage(covered_basic_blocks, basic_blocks.len()),
+        }
+    }
+}
+
+/// Coverage summary of a bytecode, the entries of [`coverage_to_json`].
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CoverageReport {
+    pub code_hash: H256,
+    pub code_size: usize,
+    /// Executed instructions as `[start, end)` offsets, see [`CoverageMap::covered_ranges`].
+    pub covered_ranges: Vec<(usize, usize)>,
+    pub instructions: usize,
+    pub covered_instructions: usize,
+    pub basic_blocks: usize,
+    pub covered_basic_blocks: usize,
+    /// Percentage of the basic blocks covered, 100 for code without blocks.
+    pub basic_block_percentage: f64,
+}
+
+/// Adds the coverage in `other` to `coverage`, e.g. to combine the coverage of many blocks.
+pub fn merge_coverage(
+    coverage: &mut HashMap<H256, CoverageMap>,
+    other: HashMap<H256, CoverageMap>,
+) {
+    for (code_hash, map) in other {
+        match coverage.entry(code_hash) {
+            Entry::Occupied(mut entry) => entry.get_mut().merge(&map),
+            Entry::Vacant(entry) => {
+                entry.insert(map);
+            }
+        }
+    }
+}
+
+/// Reports of every bytecode, sorted by code hash.
+pub fn coverage_reports(coverage: &HashMap<H256, CoverageMap>) -> Vec<CoverageReport> {
+    let mut reports: Vec<CoverageReport> = coverage
+        .iter()
+        .map(|(code_hash, map)| map.report(*code_hash))
+        .collect();
+    reports.sort_by_key(|report| report.code_hash);
+    reports
+}
+
+/// Exports the coverage as a JSON array of [`CoverageReport`]s, sorted by code hash.
+pub fn coverage_to_json(
+    coverage: &HashMap<H256, CoverageMap>,
+) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(&coverage_reports(coverage))
+}
+
+/// Exports the coverage as an lcov tracefile, with one record per bytecode named after its
+/// code hash. Instructions are the lines, numbered by their offset.
+pub fn coverage_to_lcov(coverage: &HashMap<H256, CoverageMap>) -> String {
+    let mut code_hashes: Vec<&H256> = coverage.keys().collect();
+    code_hashes.sort();
+    let mut lcov = String::new();
+    for code_hash in code_hashes {
+        let Some(map) = coverage.get(code_hash) else {
+            continue;
+        };
+        let instructions = map.instructions();
+        let mut hit: usize = 0;
+        lcov.push_str(&format!("SF:{code_hash:#x}\n"));
+        for pc in &instructions {
+            let covered = map.is_covered(*pc);
+            hit = hit.saturating_add(usize::from(covered));
+            lcov.push_str(&format!("DA:{pc},{}\n", u8::from(covered)));
+        }
+        lcov.push_str(&format!(
+            "LF:{}\nLH:{hit}\nend_of_record\n",
+            instructions.len()
+        ));
+    }
+    lcov
+}
+
+/// Offsets of each instruction of `bytecode`, push data included. The push data of the
+/// last instruction can be cut short by the end of the code.
+fn decode_instructions(bytecode: &[u8]) -> Vec<Range<usize>> {
+    let mut instructions = Vec::new();
+    let mut pc: usize = 0;
+    while let Some(opcode) = bytecode.get(pc).copied() {
+        let push_size = if (PUSH1..=PUSH32).contains(&opcode) {
+            usize::from(opcode.saturating_sub(PUSH1)).saturating_add(1)
+        } else {
+            0
+        };
+        let end = pc
+            .saturating_add(1)
+            .saturating_add(push_size)
+            .min(bytecode.len());
+        instructions.push(pc..end);
+        pc = end;
+    }
+    instructions
+}
+
+fn percentage(part: usize, total: usize) -> f64 {
+    if total == 0 {
+        return 100.0;
+    }
+    let to_f64 = |n: usize| f64::from(u32::try_from(n).unwrap_or(u32::MAX));
+    to_f64(part) * 100.0 / to_f64(total)
+}
+
+/// Key of the coverage of `code`. Init codes carry a bogus hash, so theirs is computed.
+fn coverage_key(code: &Code) -> H256 {
+    if code.hash.is_zero() {
+        keccak(&code.bytecode)
+    } else {
+        code.hash
+    }
+}
+
+impl GeneralizedDatabase {
+    /// Starts recording the bytecode executed against the database, see [`CoverageMap`].
+    pub fn enable_coverage(&mut self) {
+        self.coverage.get_or_insert_with(Default::default);
+    }
+
+    /// Takes the coverage recorded so far, by code hash. Recording goes on if enabled.
+    pub fn take_coverage(&mut self) -> HashMap<H256, CoverageMap> {
+        self.coverage
+            .as_mut()
+            .map(|coverage| std::mem::take(coverage).into_iter().collect())
+            .unwrap_or_default()
+    }
+}
+
+impl<'a> VM<'a> {
+    /// Marks the opcode at the program counter of the current frame as executed.
+    #[inline]
+    pub(crate) fn record_coverage(&mut self) {
+        let Some(coverage) = self.db.coverage.as_mut() else {
+            return;
+        };
+        let frame = &mut self.current_call_frame;
+        let code_hash = *frame
+            .coverage_key
+            .get_or_insert_with(|| coverage_key(&frame.bytecode));
+        coverage
+            .entry(code_hash)
+            .or_insert_with(|| CoverageMap::new(frame.bytecode.bytecode.clone()))
+            .record(frame.pc);
+    }
+}
@@ -1,20 +1,28 @@
 use std::sync::Arc;
 
+use bytes::Bytes;
 use ethrex_common::Address;
 use ethrex_common::H256;
 use ethrex_common::U256;
+use ethrex_common::constants::EMPTY_KECCACK_HASH;
+use ethrex_common::tracing::{NativePrestate, PrestateAccount};
 use ethrex_common::types::Account;
 use ethrex_common::types::Code;
 use ethrex_common::types::CodeMetadata;
-use ethrex_common::types::block_access_list::{BlockAccessList, BlockAccessListRecorder};
+use ethrex_common::types::block_access_list::{
+    BlockAccessList, BlockAccessListRecorder, PayloadBalBuilder,
+};
 use ethrex_common::utils::ZERO_U256;
 
 use super::Database;
+use crate::access_sets::ReadSet;
 use crate::account::AccountStatus;
 use crate::account::LevmAccount;
 use crate::call_frame::CallFrameBackup;
+use crate::coverage::CoverageMap;
 use crate::errors::InternalError;
 use crate::errors::VMError;
+use crate::tracing::PrestateTracer;
 use crate::utils::account_to_levm_account;
 use crate::utils::restore_cache_state;
 use crate::vm::VM;
@@ -34,6 +42,15 @@ pub struct GeneralizedDatabase {
     pub tx_backup: Option<CallFrameBackup>,
     /// Optional BAL recorder for EIP-7928 Block Access List recording.
     pub bal_recorder: Option<BlockAccessListRecorder>,
+    /// Per-index BAL contributions while building a payload. When set, `bal_recorder` only
+    /// holds the contributions of the index currently being recorded.
+    pub payload_bal: Option<PayloadBalBuilder>,
+    /// Optional recorder of the state read by a transaction, for the prestate tracer.
+    pub prestate_tracer: Option<PrestateTracer>,
+    /// Optional recorder of the accounts and slots read by a transaction, see `VM::execute_with_access_sets`.
+    pub read_set: Option<ReadSet>,
+    /// Optional recorder of the executed bytecode, see [`GeneralizedDatabase::enable_coverage`].
+    pub coverage: Option<FxHashMap<H256, CoverageMap>>,
 }
 
 impl GeneralizedDatabase {
@@ -46,6 +63,10 @@ impl GeneralizedDatabase {
             codes: Default::default(),
             code_metadata: Default::default(),
             bal_recorder: None,
+            payload_bal: None,
+            prestate_tracer: None,
+            read_set: None,
+            coverage: None,
         }
     }
 
@@ -58,6 +79,7 @@ impl GeneralizedDatabase {
     /// Disables BAL recording.
     pub fn disable_bal_recording(&mut self) {
         self.bal_recorder = None;
+        self.payload_bal = None;
     }
 
     /// Sets the current block access index for BAL recording per EIP-7928 spec (uint16).
@@ -74,11 +96,100 @@ impl GeneralizedDatabase {
         self.bal_recorder.take().map(|recorder| recorder.build())
     }
 
+    /// Enables incremental BAL recording for a payload under construction.
+    /// Recording starts at index 0 (pre-execution phase); each transaction is then
+    /// started with `record_tx` and can be retracted with `remove_tx`.
+    pub fn begin_payload_bal(&mut self) {
+        self.payload_bal = Some(PayloadBalBuilder::new());
+        self.bal_recorder = Some(BlockAccessListRecorder::new());
+    }
+
+    /// Starts recording the transaction at block access index `index`.
+    /// Outside of payload building this is equivalent to `set_bal_index`.
+    pub fn record_tx(&mut self, index: u16) {
+        let Some(builder) = self.payload_bal.as_mut() else {
+            self.set_bal_index(index);
+            return;
+        };
+        if let Some(recorder) = self.bal_recorder.take() {
+            builder.push(recorder);
+        }
+        let mut recorder = BlockAccessListRecorder::new();
+        recorder.set_block_access_index(index);
+        self.bal_recorder = Some(recorder);
+    }
+
+    /// Retracts the BAL contributions of the transaction at block access index `index`,
+    /// e.g. when it is evicted from the payload. Its state changes must be undone separately.
+    pub fn remove_tx(&mut self, index: u16) {
+        let Some(builder) = self.payload_bal.as_mut() else {
+            return;
+        };
+        builder.remove(index);
+        if let Some(recorder) = self.bal_recorder.as_mut()
+            && recorder.current_index() == index
+        {
+            *recorder = BlockAccessListRecorder::new();
+            recorder.set_block_access_index(index);
+        }
+    }
+
+    /// Ends incremental BAL recording and builds the payload's BlockAccessList.
+    /// Returns None if `begin_payload_bal` was not called.
+    pub fn finalize_payload_bal(&mut self) -> Option<BlockAccessList> {
+        let mut builder = self.payload_bal.take()?;
+        if let Some(recorder) = self.bal_recorder.take() {
+            builder.push(recorder);
+        }
+        Some(builder.build())
+    }
+
     /// Returns a mutable reference to the BAL recorder if enabled.
     pub fn bal_recorder_mut(&mut self) -> Option<&mut BlockAccessListRecorder> {
         self.bal_recorder.as_mut()
     }
 
+    /// Starts recording the state read by the transactions executed from now on, see [`PrestateTracer`].
+    pub fn enable_prestate_tracing(&mut self) {
+        self.prestate_tracer = Some(PrestateTracer::default());
+    }
+
+    /// Stops prestate tracing and returns the state read while it was enabled, if it was.
+    pub fn take_prestate(&mut self) -> Result<Option<NativePrestate>, InternalError> {
+        let Some(tracer) = self.prestate_tracer.take() else {
+            return Ok(None);
+        };
+        let mut prestate = NativePrestate {
+            chain_config: self.store.get_chain_config()?,
+            block_hashes: tracer.block_hashes,
+            ..Default::default()
+        };
+        for (address, account) in tracer.accounts {
+            let code = if account.info.code_hash == *EMPTY_KECCACK_HASH {
+                Bytes::new()
+            } else {
+                self.get_code(account.info.code_hash)?.bytecode.clone()
+            };
+            if account.has_storage {
+                prestate.accounts_with_storage.insert(address);
+            }
+            prestate.accounts.insert(
+                address,
+                PrestateAccount {
+                    balance: account.info.balance,
+                    nonce: account.info.nonce,
+                    code,
+                    storage: account
+                        .storage
+                        .into_iter()
+                        .map(|(key, value)| (key, H256::from(value.to_big_endian())))
+                        .collect(),
+                },
+            );
+        }
+        Ok(Some(prestate))
+    }
+
     /// Only used within Levm Runner, where the accounts already have all the storage pre-loaded, not used in real case scenarios.
     pub fn new_with_account_state(
         store: Arc<dyn Database>,
@@ -101,6 +212,10 @@ impl GeneralizedDatabase {
             codes,
             code_metadata: Default::default(),
             bal_recorder: None,
+            payload_bal: None,
+            prestate_tracer: None,
+            read_set: None,
+            coverage: None,
         }
     }
 
@@ -108,18 +223,26 @@ impl GeneralizedDatabase {
     /// Loads account
     /// If it's the first time it's loaded store it in `initial_accounts_state` and also cache it in `current_accounts_state` for making changes to it
     fn load_account(&mut self, address: Address) -> Result<&mut LevmAccount, InternalError> {
-        match self.current_accounts_state.entry(address) {
-            Entry::Occupied(entry) => Ok(entry.into_mut()),
+        let account = match self.current_accounts_state.entry(address) {
+            Entry::Occupied(entry) => entry.into_mut(),
             Entry::Vacant(entry) => {
                 if let Some(account) = self.initial_accounts_state.get(&address) {
-                    return Ok(entry.insert(account.clone()));
+                    entry.insert(account.clone())
+                } else {
+                    let state = self.store.get_account_state(address)?;
+                    let account = LevmAccount::from(state);
+                    self.initial_accounts_state.insert(address, account.clone());
+                    entry.insert(account)
                 }
-                let state = self.store.get_account_state(address)?;
-                let account = LevmAccount::from(state);
-                self.initial_accounts_state.insert(address, account.clone());
-                Ok(entry.insert(account))
             }
+        };
+        if let Some(tracer) = self.prestate_tracer.as_mut() {
+            tracer.record_account(address, account);
+        }
+        if let Some(reads) = self.read_set.as_mut() {
+            reads.accounts.insert(address);
         }
+        Ok(account)
     }
 
     /// Gets reference of an account
@@ -188,8 +311,6 @@ impl GeneralizedDatabase {
 
     /// Convenience method to get code length by address (optimized for EXTCODESIZE).
     pub fn get_code_length(&mut self, address: Address) -> Result<usize, InternalError> {
-        use ethrex_common::constants::EMPTY_KECCACK_HASH;
-
         let code_hash = self.get_account(address)?.info.code_hash;
         if code_hash == *EMPTY_KECCACK_HASH {
             return Ok(0);
@@ -232,9 +353,16 @@ impl GeneralizedDatabase {
     }
 
     /// Undoes the last transaction by restoring the cache state to the state before the transaction.
+    /// This includes the status of the accounts it destroyed and the codes it deployed.
+    /// While building a payload, the BAL contributions of the transaction are retracted too.
     pub fn undo_last_transaction(&mut self) -> Result<(), VMError> {
         let tx_backup = self.get_tx_backup()?;
         restore_cache_state(self, tx_backup)?;
+        if self.payload_bal.is_some()
+            && let Some(index) = self.bal_recorder.as_ref().map(|r| r.current_index())
+        {
+            self.remove_tx(index);
+        }
         Ok(())
     }
 
@@ -570,10 +698,19 @@ impl<'a> VM<'a> {
             recorder.record_code_change(address, new_bytecode.bytecode.clone());
         }
 
+        // The account may have been delegated or undelegated
+        self.delegations.remove(&address);
+
         let acc = self.get_account_mut(address)?;
         let code_hash = new_bytecode.hash;
         acc.info.code_hash = new_bytecode.hash;
-        self.db.codes.entry(code_hash).or_insert(new_bytecode);
+        if let Entry::Vacant(entry) = self.db.codes.entry(code_hash) {
+            entry.insert(new_bytecode);
+            self.current_call_frame
+                .call_frame_backup
+                .inserted_codes
+                .push(code_hash);
+        }
         Ok(())
     }
 
@@ -658,24 +795,35 @@ impl<'a> VM<'a> {
         address: Address,
         key: H256,
     ) -> Result<U256, InternalError> {
-        if let Some(account) = self.db.current_accounts_state.get(&address) {
-            if let Some(value) = account.storage.get(&key) {
-                return Ok(*value);
-            }
-            // If the account was destroyed and then created then we cannot rely on the DB to obtain storage values
-            if account.status == AccountStatus::DestroyedModified {
-                return Ok(U256::zero());
-            }
-        } else {
+        let cached = match self.db.current_accounts_state.get(&address) {
+            Some(account) => match account.storage.get(&key) {
+                Some(value) => Some(*value),
+                // If the account was destroyed and then created then we cannot rely on the DB to obtain storage values
+                None if account.status == AccountStatus::DestroyedModified => Some(U256::zero()),
+                None => None,
+            },
             // When requesting storage of an account we should've previously requested and cached the account
-            return Err(InternalError::AccountNotFound);
-        }
+            None => return Err(InternalError::AccountNotFound),
+        };
 
-        let value = self.db.get_value_from_database(address, key)?;
+        let value = match cached {
+            Some(value) => value,
+            None => {
+                let value = self.db.get_value_from_database(address, key)?;
 
-        // Update the account with the fetched value
-        let account = self.get_account_mut(address)?;
-        account.storage.insert(key, value);
+                // Update the account with the fetched value
+                let account = self.get_account_mut(address)?;
+                account.storage.insert(key, value);
+                value
+            }
+        };
+
+        if let Some(tracer) = self.db.prestate_tracer.as_mut() {
+            tracer.record_storage(address, key, value);
+        }
+        if let Some(reads) = self.db.read_set.as_mut() {
+            reads.storage.insert((address, key));
+        }
 
         Ok(value)
     }
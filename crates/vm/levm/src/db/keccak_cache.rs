@@ -0,0 +1,187 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{PoisonError, RwLock, RwLockReadGuard};
+
+use ethrex_crypto::keccak::keccak_hash;
+use rustc_hash::FxHashMap;
+
+/// Bytes held by a cached 32-byte preimage and its hash.
+const WORD_ENTRY_BYTES: usize = 64;
+/// Bytes held by a cached 64-byte preimage and its hash.
+const PAIR_ENTRY_BYTES: usize = 96;
+/// Independently locked parts of a [`KeccakCache`], so that the executor and
+/// the warmer threads rarely wait on each other when caching new preimages.
+/// Must be 16, shards are picked by 4 bits of the preimage.
+const SHARDS: usize = 16;
+
+/// Bounds of a [`KeccakCache`]. Once either is reached new preimages are
+/// hashed without being cached. Zero disables the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeccakCacheLimits {
+    pub max_entries: usize,
+    /// Bytes of preimages and hashes held.
+    pub max_bytes: usize,
+}
+
+impl KeccakCacheLimits {
+    /// Limits of a cache that never holds anything.
+    pub const DISABLED: Self = Self {
+        max_entries: 0,
+        max_bytes: 0,
+    };
+}
+
+impl Default for KeccakCacheLimits {
+    fn default() -> Self {
+        Self {
+            max_entries: 65_536,
+            // 8 MiB
+            max_bytes: 8_388_608,
+        }
+    }
+}
+
+/// Keccak256 of the 32 and 64-byte preimages hashed during a block.
+///
+/// Mapping slots are the hash of a 64-byte (key, slot) preimage and array
+/// slots of a 32-byte one, so contracts hash the same few preimages in every
+/// transaction that touches the same accounts. Other sizes are rarely repeated
+/// and aren't cached, see [`KeccakCache::caches_size`].
+///
+/// Entries are spread over several shards, each behind its own lock. The
+/// limits apply to the whole cache.
+pub struct KeccakCache {
+    limits: KeccakCacheLimits,
+    shards: [RwLock<Shard>; SHARDS],
+    entries: AtomicUsize,
+    bytes: AtomicUsize,
+}
+
+#[derive(Default)]
+struct Shard {
+    words: FxHashMap<[u8; 32], [u8; 32]>,
+    pairs: FxHashMap<[u8; 64], [u8; 32]>,
+}
+
+impl KeccakCache {
+    pub fn new(limits: KeccakCacheLimits) -> Self {
+        Self {
+            limits,
+            shards: Default::default(),
+            entries: AtomicUsize::new(0),
+            bytes: AtomicUsize::new(0),
+        }
+    }
+
+    /// Whether preimages of `size` bytes are cached.
+    pub fn caches_size(size: usize) -> bool {
+        size == 32 || size == 64
+    }
+
+    /// Whether the limits let the cache hold anything.
+    pub fn is_enabled(&self) -> bool {
+        self.limits.max_entries > 0 && self.limits.max_bytes > 0
+    }
+
+    /// Returns the hash of `data` and whether it was cached, or `None` if
+    /// the cache is disabled or preimages of its size aren't cached.
+    pub fn hash(&self, data: &[u8]) -> Option<([u8; 32], bool)> {
+        if !self.is_enabled() {
+            return None;
+        }
+        if let Ok(word) = <[u8; 32]>::try_from(data) {
+            let shard = self.shard(&word);
+            let cached = read(shard).words.get(&word).copied();
+            if let Some(hash) = cached {
+                return Some((hash, true));
+            }
+            let hash = keccak_hash(word);
+            self.insert(shard, WORD_ENTRY_BYTES, |shard| {
+                shard.words.insert(word, hash).is_none()
+            });
+            return Some((hash, false));
+        }
+        if let Ok(pair) = <[u8; 64]>::try_from(data) {
+            let shard = self.shard(&pair);
+            let cached = read(shard).pairs.get(&pair).copied();
+            if let Some(hash) = cached {
+                return Some((hash, true));
+            }
+            let hash = keccak_hash(pair);
+            self.insert(shard, PAIR_ENTRY_BYTES, |shard| {
+                shard.pairs.insert(pair, hash).is_none()
+            });
+            return Some((hash, false));
+        }
+        None
+    }
+
+    /// Number of cached preimages.
+    pub fn len(&self) -> usize {
+        self.entries.load(Ordering::Relaxed)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Bytes of preimages and hashes held.
+    pub fn size_bytes(&self) -> usize {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    /// Shard holding `preimage`, picked from a mix of all its bytes since
+    /// mapping keys are mostly left-padded addresses.
+    fn shard(&self, preimage: &[u8]) -> &RwLock<Shard> {
+        let folded = preimage
+            .chunks_exact(8)
+            .fold(0u64, |acc, chunk| {
+                let mut word = [0u8; 8];
+                word.copy_from_slice(chunk);
+                acc.rotate_left(5) ^ u64::from_le_bytes(word)
+            })
+            .wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        // The top 4 bits of the mix, the best mixed ones
+        let index = usize::from(folded.to_be_bytes()[0] >> 4);
+        #[allow(clippy::indexing_slicing, reason = "index is below 16")]
+        let shard = &self.shards[index];
+        shard
+    }
+
+    /// Runs `insert`, which adds an entry of `entry_bytes` to `shard` unless
+    /// another thread cached it first, if the cache has room for it.
+    ///
+    /// Room is reserved before taking the shard's write lock, so a full cache
+    /// never locks.
+    fn insert(
+        &self,
+        shard: &RwLock<Shard>,
+        entry_bytes: usize,
+        insert: impl FnOnce(&mut Shard) -> bool,
+    ) {
+        if !reserve(&self.entries, 1, self.limits.max_entries) {
+            return;
+        }
+        if !reserve(&self.bytes, entry_bytes, self.limits.max_bytes) {
+            self.entries.fetch_sub(1, Ordering::Relaxed);
+            return;
+        }
+        let inserted = insert(&mut shard.write().unwrap_or_else(PoisonError::into_inner));
+        if !inserted {
+            self.entries.fetch_sub(1, Ordering::Relaxed);
+            self.bytes.fetch_sub(entry_bytes, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Adds `amount` to `counter` unless that takes it above `max`.
+fn reserve(counter: &AtomicUsize, amount: usize, max: usize) -> bool {
+    counter
+        .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+            current.checked_add(amount).filter(|new| *new <= max)
+        })
+        .is_ok()
+}
+
+fn read(shard: &RwLock<Shard>) -> RwLockReadGuard<'_, Shard> {
+    shard.read().unwrap_or_else(PoisonError::into_inner)
+}
@@ -3,23 +3,45 @@ use ethrex_common::{
     Address, H256, U256,
     types::{AccountState, ChainConfig, Code, CodeMetadata},
 };
+use ethrex_crypto::keccak::keccak_hash;
 use rustc_hash::FxHashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
 
 pub mod gen_db;
+pub mod keccak_cache;
+pub mod prestate;
+
+pub use keccak_cache::{KeccakCache, KeccakCacheLimits};
 
 // Type aliases for cache storage maps
-type AccountCache = FxHashMap<Address, AccountState>;
-type StorageCache = FxHashMap<(Address, H256), U256>;
-type CodeCache = FxHashMap<H256, Code>;
+type AccountCache = FxHashMap<Address, Cached<AccountState>>;
+type StorageCache = FxHashMap<(Address, H256), Cached<U256>>;
+type CodeCache = FxHashMap<H256, Cached<Code>>;
 
 pub trait Database: Send + Sync {
     fn get_account_state(&self, address: Address) -> Result<AccountState, DatabaseError>;
     fn get_storage_value(&self, address: Address, key: H256) -> Result<U256, DatabaseError>;
+    /// Batched version of [`Database::get_storage_value`] for slots of the same account.
+    /// Values are returned in the order of `keys`.
+    fn get_storage_values(
+        &self,
+        address: Address,
+        keys: &[H256],
+    ) -> Result<Vec<U256>, DatabaseError> {
+        keys.iter()
+            .map(|key| self.get_storage_value(address, *key))
+            .collect()
+    }
     fn get_block_hash(&self, block_number: u64) -> Result<H256, DatabaseError>;
     fn get_chain_config(&self) -> Result<ChainConfig, DatabaseError>;
     fn get_account_code(&self, code_hash: H256) -> Result<Code, DatabaseError>;
     fn get_code_metadata(&self, code_hash: H256) -> Result<CodeMetadata, DatabaseError>;
+    /// Keccak256 of `data`, for the KECCAK256 opcode. A [`CachingDatabase`] built with
+    /// keccak limits memoizes it for the whole block.
+    fn keccak(&self, data: &[u8]) -> [u8; 32] {
+        keccak_hash(data)
+    }
 }
 
 /// A database wrapper that caches state lookups for parallel pre-warming.
@@ -28,6 +50,19 @@ pub trait Database: Send + Sync {
 /// the sequential execution phase to reuse warmed state. Reduces redundant
 /// database/trie lookups when multiple transactions touch the same accounts.
 ///
+/// Warming workers read through [`CachingDatabase::warmer`], the executor
+/// reads through the `CachingDatabase` itself, so [`CachingDatabase::stats`]
+/// can tell how many executor reads the warmer saved.
+///
+/// When built with [`CachingDatabase::with_keccak_limits`], it also memoizes
+/// the KECCAK256 of the preimages contracts hash over and over, see
+/// [`KeccakCache`].
+///
+/// Accounts that don't exist and empty slots are cached like any other value
+/// and counted apart in [`CacheStats::negative_hits`]. They can't go stale: the
+/// cache holds the state before the block and the executor keeps its own writes,
+/// so an account created later in the block is read from there.
+///
 /// Thread-safe via RwLock - optimized for read-heavy concurrent access.
 ///
 /// This caching database is inspired by reth's overlay/proof worker cache.
@@ -39,18 +74,162 @@ pub struct CachingDatabase {
     storage: RwLock<StorageCache>,
     /// Cached contract code
     code: RwLock<CodeCache>,
+    keccak: KeccakCache,
+    counters: Counters,
+}
+
+/// A cached value and whether a warmer loaded it.
+#[derive(Clone, Copy)]
+struct Cached<T> {
+    value: T,
+    warmed: bool,
+    /// Whether the value stands for missing state: an account that doesn't exist or an
+    /// empty slot. Proving absence walks the trie like any other read.
+    negative: bool,
+}
+
+/// Who is reading from a [`CachingDatabase`].
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Reader {
+    Executor,
+    Warmer,
+}
+
+#[derive(Default)]
+struct Counters {
+    executor_reads: AtomicU64,
+    executor_hits: AtomicU64,
+    warmer_hits: AtomicU64,
+    warmer_reads: AtomicU64,
+    negative_hits: AtomicU64,
+    keccak_lookups: AtomicU64,
+    keccak_hits: AtomicU64,
+}
+
+/// Read statistics of a [`CachingDatabase`].
+///
+/// Account, storage and code reads are counted alike, a batched storage read
+/// counts once per slot.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Reads made by the executor.
+    pub executor_reads: u64,
+    /// Executor reads served from the cache.
+    pub executor_hits: u64,
+    /// Executor reads served from entries loaded by the warmer.
+    pub warmer_hits: u64,
+    /// Reads made by warming workers.
+    pub warmer_reads: u64,
+    /// Executor hits on accounts that don't exist and empty slots.
+    pub negative_hits: u64,
+    /// Preimages the executor hashed through the keccak cache.
+    pub keccak_lookups: u64,
+    /// Executor hashes served from the keccak cache, including preimages the
+    /// warmer hashed.
+    pub keccak_hits: u64,
+}
+
+impl CacheStats {
+    /// Executor reads that had to go to the underlying database.
+    pub fn executor_misses(&self) -> u64 {
+        self.executor_reads.saturating_sub(self.executor_hits)
+    }
+
+    /// Percentage of the executor's cacheable hashes served from the keccak cache.
+    pub fn keccak_hit_rate_percent(&self) -> u64 {
+        self.keccak_hits
+            .saturating_mul(100)
+            .checked_div(self.keccak_lookups)
+            .unwrap_or(0)
+    }
+
+    /// Whether a warmer read anything through this cache.
+    pub fn warmed(&self) -> bool {
+        self.warmer_reads > 0
+    }
 }
 
 impl CachingDatabase {
+    /// Caches state lookups only, the keccak cache is disabled.
     pub fn new(inner: Arc<dyn Database>) -> Self {
+        Self::with_keccak_limits(inner, KeccakCacheLimits::DISABLED)
+    }
+
+    /// Like [`CachingDatabase::new`], also memoizing KECCAK256 within `keccak_limits`.
+    pub fn with_keccak_limits(inner: Arc<dyn Database>, keccak_limits: KeccakCacheLimits) -> Self {
         Self {
             inner,
             accounts: RwLock::new(FxHashMap::default()),
             storage: RwLock::new(FxHashMap::default()),
             code: RwLock::new(FxHashMap::default()),
+            keccak: KeccakCache::new(keccak_limits),
+            counters: Counters::default(),
+        }
+    }
+
+    pub fn keccak_cache(&self) -> &KeccakCache {
+        &self.keccak
+    }
+
+    /// View of this cache for warming workers.
+    ///
+    /// Entries loaded through it are shared with the executor and counted as
+    /// warmer hits when the executor reads them.
+    pub fn warmer(self: &Arc<Self>) -> Arc<dyn Database> {
+        Arc::new(WarmerDatabase(self.clone()))
+    }
+
+    /// Whether `store` is this cache, i.e. reads through it share the
+    /// entries loaded by the warmer.
+    pub fn is_store(self: &Arc<Self>, store: &Arc<dyn Database>) -> bool {
+        std::ptr::addr_eq(Arc::as_ptr(self), Arc::as_ptr(store))
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        let counters = &self.counters;
+        CacheStats {
+            executor_reads: counters.executor_reads.load(Ordering::Relaxed),
+            executor_hits: counters.executor_hits.load(Ordering::Relaxed),
+            warmer_hits: counters.warmer_hits.load(Ordering::Relaxed),
+            warmer_reads: counters.warmer_reads.load(Ordering::Relaxed),
+            negative_hits: counters.negative_hits.load(Ordering::Relaxed),
+            keccak_lookups: counters.keccak_lookups.load(Ordering::Relaxed),
+            keccak_hits: counters.keccak_hits.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Counts a read by `reader`, `cached` being the entry it found if any.
+    fn record<T>(&self, reader: Reader, cached: Option<&Cached<T>>) {
+        let counters = &self.counters;
+        if reader == Reader::Warmer {
+            counters.warmer_reads.fetch_add(1, Ordering::Relaxed);
+            return;
+        }
+        counters.executor_reads.fetch_add(1, Ordering::Relaxed);
+        if let Some(cached) = cached {
+            counters.executor_hits.fetch_add(1, Ordering::Relaxed);
+            if cached.warmed {
+                counters.warmer_hits.fetch_add(1, Ordering::Relaxed);
+            }
+            if cached.negative {
+                counters.negative_hits.fetch_add(1, Ordering::Relaxed);
+            }
         }
     }
 
+    fn hash(&self, data: &[u8], reader: Reader) -> [u8; 32] {
+        let Some((hash, cached)) = self.keccak.hash(data) else {
+            return keccak_hash(data);
+        };
+        if reader == Reader::Executor {
+            self.counters.keccak_lookups.fetch_add(1, Ordering::Relaxed);
+            if cached {
+                self.counters.keccak_hits.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        hash
+    }
+
     fn read_accounts(&self) -> Result<RwLockReadGuard<'_, AccountCache>, DatabaseError> {
         self.accounts.read().map_err(poison_error_to_db_error)
     }
@@ -74,68 +253,163 @@ impl CachingDatabase {
     fn write_code(&self) -> Result<RwLockWriteGuard<'_, CodeCache>, DatabaseError> {
         self.code.write().map_err(poison_error_to_db_error)
     }
-}
 
-fn poison_error_to_db_error<T>(err: PoisonError<T>) -> DatabaseError {
-    DatabaseError::Custom(format!("Cache lock poisoned: {err}"))
-}
-
-impl Database for CachingDatabase {
-    fn get_account_state(&self, address: Address) -> Result<AccountState, DatabaseError> {
+    fn account_state(
+        &self,
+        address: Address,
+        reader: Reader,
+    ) -> Result<AccountState, DatabaseError> {
         // Check cache first
-        if let Some(state) = self.read_accounts()?.get(&address).copied() {
-            return Ok(state);
+        let cached = self.read_accounts()?.get(&address).copied();
+        self.record(reader, cached.as_ref());
+        if let Some(cached) = cached {
+            return Ok(cached.value);
         }
 
         // Cache miss: query underlying database
         let state = self.inner.get_account_state(address)?;
 
-        // Populate cache (AccountState is Copy, no clone needed)
-        self.write_accounts()?.insert(address, state);
+        // Populate cache (AccountState is Copy, no clone needed). Keep the
+        // first entry so a concurrent load doesn't change who loaded it.
+        self.write_accounts()?.entry(address).or_insert(Cached {
+            value: state,
+            warmed: reader == Reader::Warmer,
+            negative: state == AccountState::default(),
+        });
 
         Ok(state)
     }
 
-    fn get_storage_value(&self, address: Address, key: H256) -> Result<U256, DatabaseError> {
+    fn storage_value(
+        &self,
+        address: Address,
+        key: H256,
+        reader: Reader,
+    ) -> Result<U256, DatabaseError> {
         // Check cache first
-        if let Some(value) = self.read_storage()?.get(&(address, key)).copied() {
-            return Ok(value);
+        let cached = self.read_storage()?.get(&(address, key)).copied();
+        self.record(reader, cached.as_ref());
+        if let Some(cached) = cached {
+            return Ok(cached.value);
         }
 
         // Cache miss: query underlying database
         let value = self.inner.get_storage_value(address, key)?;
 
         // Populate cache (U256 is Copy, no clone needed)
-        self.write_storage()?.insert((address, key), value);
+        self.write_storage()?
+            .entry((address, key))
+            .or_insert(Cached {
+                value,
+                warmed: reader == Reader::Warmer,
+                negative: value.is_zero(),
+            });
 
         Ok(value)
     }
 
-    fn get_block_hash(&self, block_number: u64) -> Result<H256, DatabaseError> {
-        // Block hashes don't benefit much from caching here
-        // (they're already cached in StoreVmDatabase)
-        self.inner.get_block_hash(block_number)
-    }
+    fn storage_values(
+        &self,
+        address: Address,
+        keys: &[H256],
+        reader: Reader,
+    ) -> Result<Vec<U256>, DatabaseError> {
+        // Serve what we can from the cache and remember which positions missed
+        let mut values = Vec::with_capacity(keys.len());
+        let mut missing_keys = Vec::new();
+        let mut missing_positions = Vec::new();
+        {
+            let storage = self.read_storage()?;
+            for (position, key) in keys.iter().enumerate() {
+                let cached = storage.get(&(address, *key));
+                self.record(reader, cached);
+                if cached.is_none() {
+                    missing_keys.push(*key);
+                    missing_positions.push(position);
+                }
+                values.push(cached.map(|cached| cached.value).unwrap_or_default());
+            }
+        }
+        if missing_keys.is_empty() {
+            return Ok(values);
+        }
 
-    fn get_chain_config(&self) -> Result<ChainConfig, DatabaseError> {
-        // Chain config is constant, no need to cache
-        self.inner.get_chain_config()
+        // Cache misses: query underlying database in a single batch
+        let fetched = self.inner.get_storage_values(address, &missing_keys)?;
+
+        let mut storage = self.write_storage()?;
+        for ((position, key), value) in missing_positions.into_iter().zip(missing_keys).zip(fetched)
+        {
+            storage.entry((address, key)).or_insert(Cached {
+                value,
+                warmed: reader == Reader::Warmer,
+                negative: value.is_zero(),
+            });
+            if let Some(slot) = values.get_mut(position) {
+                *slot = value;
+            }
+        }
+
+        Ok(values)
     }
 
-    fn get_account_code(&self, code_hash: H256) -> Result<Code, DatabaseError> {
+    fn account_code(&self, code_hash: H256, reader: Reader) -> Result<Code, DatabaseError> {
         // Check cache first
-        if let Some(code) = self.read_code()?.get(&code_hash).cloned() {
-            return Ok(code);
+        let cached = self.read_code()?.get(&code_hash).cloned();
+        self.record(reader, cached.as_ref());
+        if let Some(cached) = cached {
+            return Ok(cached.value);
         }
 
         // Cache miss: query underlying database
         let code = self.inner.get_account_code(code_hash)?;
 
         // Populate cache (Code contains Bytes which is ref-counted, clone is cheap)
-        self.write_code()?.insert(code_hash, code.clone());
+        self.write_code()?.entry(code_hash).or_insert(Cached {
+            value: code.clone(),
+            warmed: reader == Reader::Warmer,
+            negative: false,
+        });
 
         Ok(code)
     }
+}
+
+fn poison_error_to_db_error<T>(err: PoisonError<T>) -> DatabaseError {
+    DatabaseError::Custom(format!("Cache lock poisoned: {err}"))
+}
+
+impl Database for CachingDatabase {
+    fn get_account_state(&self, address: Address) -> Result<AccountState, DatabaseError> {
+        self.account_state(address, Reader::Executor)
+    }
+
+    fn get_storage_value(&self, address: Address, key: H256) -> Result<U256, DatabaseError> {
+        self.storage_value(address, key, Reader::Executor)
+    }
+
+    fn get_storage_values(
+        &self,
+        address: Address,
+        keys: &[H256],
+    ) -> Result<Vec<U256>, DatabaseError> {
+        self.storage_values(address, keys, Reader::Executor)
+    }
+
+    fn get_block_hash(&self, block_number: u64) -> Result<H256, DatabaseError> {
+        // Block hashes don't benefit much from caching here
+        // (they're already cached in StoreVmDatabase)
+        self.inner.get_block_hash(block_number)
+    }
+
+    fn get_chain_config(&self) -> Result<ChainConfig, DatabaseError> {
+        // Chain config is constant, no need to cache
+        self.inner.get_chain_config()
+    }
+
+    fn get_account_code(&self, code_hash: H256) -> Result<Code, DatabaseError> {
+        self.account_code(code_hash, Reader::Executor)
+    }
 
     fn get_code_metadata(&self, code_hash: H256) -> Result<CodeMetadata, DatabaseError> {
         // Delegate directly to the underlying database.
@@ -143,4 +417,49 @@ impl Database for CachingDatabase {
         // so we don't need to duplicate caching here.
         self.inner.get_code_metadata(code_hash)
     }
+
+    fn keccak(&self, data: &[u8]) -> [u8; 32] {
+        self.hash(data, Reader::Executor)
+    }
+}
+
+/// Warming workers' view of a [`CachingDatabase`], see [`CachingDatabase::warmer`].
+struct WarmerDatabase(Arc<CachingDatabase>);
+
+impl Database for WarmerDatabase {
+    fn get_account_state(&self, address: Address) -> Result<AccountState, DatabaseError> {
+        self.0.account_state(address, Reader::Warmer)
+    }
+
+    fn get_storage_value(&self, address: Address, key: H256) -> Result<U256, DatabaseError> {
+        self.0.storage_value(address, key, Reader::Warmer)
+    }
+
+    fn get_storage_values(
+        &self,
+        address: Address,
+        keys: &[H256],
+    ) -> Result<Vec<U256>, DatabaseError> {
+        self.0.storage_values(address, keys, Reader::Warmer)
+    }
+
+    fn get_block_hash(&self, block_number: u64) -> Result<H256, DatabaseError> {
+        self.0.get_block_hash(block_number)
+    }
+
+    fn get_chain_config(&self) -> Result<ChainConfig, DatabaseError> {
+        self.0.get_chain_config()
+    }
+
+    fn get_account_code(&self, code_hash: H256) -> Result<Code, DatabaseError> {
+        self.0.account_code(code_hash, Reader::Warmer)
+    }
+
+    fn get_code_metadata(&self, code_hash: H256) -> Result<CodeMetadata, DatabaseError> {
+        self.0.get_code_metadata(code_hash)
+    }
+
+    fn keccak(&self, data: &[u8]) -> [u8; 32] {
+        self.0.hash(data, Reader::Warmer)
+    }
 }
@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use ethrex_common::{
+    Address, H256, U256,
+    tracing::{NativePrestate, PrestateTrace},
+    types::{AccountState, ChainConfig, Code, CodeMetadata},
+};
+use rustc_hash::FxHashMap;
+
+use super::{Database, gen_db::GeneralizedDatabase};
+use crate::errors::DatabaseError;
+
+/// In-memory [`Database`] holding only the state recorded by the prestate tracer.
+/// Accounts and storage slots it doesn't know of are empty.
+pub struct PrestateDatabase {
+    prestate: NativePrestate,
+    codes: FxHashMap<H256, Code>,
+}
+
+impl PrestateDatabase {
+    pub fn new(prestate: NativePrestate) -> Self {
+        let codes = prestate
+            .accounts
+            .values()
+            .filter(|account| !account.code.is_empty())
+            .map(|account| {
+                let code = Code::from_bytecode(account.code.clone());
+                (code.hash, code)
+            })
+            .collect();
+        Self { prestate, codes }
+    }
+}
+
+impl Database for PrestateDatabase {
+    fn get_account_state(&self, address: Address) -> Result<AccountState, DatabaseError> {
+        let Some(account) = self.prestate.accounts.get(&address) else {
+            return Ok(AccountState::default());
+        };
+        let mut state = AccountState {
+            nonce: account.nonce,
+            balance: account.balance,
+            ..Default::default()
+        };
+        if !account.code.is_empty() {
+            state.code_hash = Code::from_bytecode(account.code.clone()).hash;
+        }
+        // The storage root is unknown, any non-empty root tells the VM the account has storage
+        if self.prestate.accounts_with_storage.contains(&address) {
+            state.storage_root = H256::zero();
+        }
+        Ok(state)
+    }
+
+    fn get_storage_value(&self, address: Address, key: H256) -> Result<U256, DatabaseError> {
+        Ok(self
+            .prestate
+            .accounts
+            .get(&address)
+            .and_then(|account| account.storage.get(&key))
+            .map(|value| U256::from_big_endian(value.as_bytes()))
+            .unwrap_or_default())
+    }
+
+    fn get_block_hash(&self, block_number: u64) -> Result<H256, DatabaseError> {
+        self.prestate
+            .block_hashes
+            .get(&block_number)
+            .copied()
+            .ok_or_else(|| {
+                DatabaseError::Custom(format!("Block hash {block_number} is not in the prestate"))
+            })
+    }
+
+    fn get_chain_config(&self) -> Result<ChainConfig, DatabaseError> {
+        Ok(self.prestate.chain_config)
+    }
+
+    fn get_account_code(&self, code_hash: H256) -> Result<Code, DatabaseError> {
+        Ok(self.codes.get(&code_hash).cloned().unwrap_or_default())
+    }
+
+    fn get_code_metadata(&self, code_hash: H256) -> Result<CodeMetadata, DatabaseError> {
+        let length = self
+            .codes
+            .get(&code_hash)
+            .map(|code| code.bytecode.len())
+            .unwrap_or_default();
+        Ok(CodeMetadata {
+            length: length
+                .try_into()
+                .map_err(|_| DatabaseError::Custom("Code length doesn't fit in u64".to_string()))?,
+        })
+    }
+}
+
+impl GeneralizedDatabase {
+    /// Builds a database holding only the state in a prestate trace, enough to re-execute the traced transaction.
+    /// Accepts both the native format and geth's `prestateTracer` output, the latter lacks the block hashes
+    /// and the chain config, which is left as default.
+    pub fn from_prestate(json: &str) -> Result<Self, DatabaseError> {
+        let prestate = serde_json::from_str::<NativePrestate>(json)
+            .or_else(|_| serde_json::from_str::<PrestateTrace>(json).map(NativePrestate::from))
+            .map_err(|err| DatabaseError::Custom(format!("Invalid prestate: {err}")))?;
+        Ok(Self::new(Arc::new(PrestateDatabase::new(prestate))))
+    }
+}
@@ -54,6 +54,8 @@ pub struct Environment {
 pub struct EVMConfig {
     pub fork: Fork,
     pub blob_schedule: ForkBlobSchedule,
+    /// Whether EOF containers may be deployed, see `ChainConfig::eof_time`.
+    pub eof: bool,
 }
 
 impl EVMConfig {
@@ -61,6 +63,7 @@ impl EVMConfig {
         EVMConfig {
             fork,
             blob_schedule,
+            eof: false,
         }
     }
 
@@ -71,7 +74,10 @@ impl EVMConfig {
             .get_fork_blob_schedule(block_header.timestamp)
             .unwrap_or_else(|| EVMConfig::canonical_values(fork));
 
-        EVMConfig::new(fork, blob_schedule)
+        EVMConfig {
+            eof: chain_config.is_eof_activated(block_header.timestamp),
+            ..EVMConfig::new(fork, blob_schedule)
+        }
     }
 
     /// This function is used for running the EF tests. If you don't
@@ -124,6 +130,7 @@ impl Default for EVMConfig {
         EVMConfig {
             fork,
             blob_schedule: Self::canonical_values(fork),
+            eof: false,
         }
     }
 }
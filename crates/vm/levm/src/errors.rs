@@ -2,11 +2,14 @@ use bytes::Bytes;
 use derive_more::derive::Display;
 use ethrex_common::{
     Address, H256, U256,
-    types::{FakeExponentialError, Log},
+    types::{FakeExponentialError, Log, blobs::BlobTxValidationError},
 };
 use serde::{Deserialize, Serialize};
 use thiserror;
 
+use crate::gas_breakdown::GasBreakdownByCategory;
+use crate::hooks::l2_hook::L2FeeBreakdown;
+
 #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error, Serialize, Deserialize, Display)]
 pub enum VMError {
     /// Errors that break execution, they shouldn't ever happen. Contains subcategory `DatabaseError`.
@@ -44,6 +47,33 @@ impl From<PrecompileError> for VMError {
     }
 }
 
+impl From<BlobTxValidationError> for VMError {
+    fn from(err: BlobTxValidationError) -> Self {
+        let tx_validation_error = match err {
+            BlobTxValidationError::PreFork => TxValidationError::Type3TxPreFork,
+            BlobTxValidationError::ZeroBlobs => TxValidationError::Type3TxZeroBlobs,
+            BlobTxValidationError::InvalidVersionedHash { .. } => {
+                TxValidationError::Type3TxInvalidBlobVersionedHash
+            }
+            BlobTxValidationError::BlobCountExceeded {
+                max_blob_count,
+                actual_blob_count,
+            } => TxValidationError::Type3TxBlobCountExceeded {
+                max_blob_count,
+                actual_blob_count,
+            },
+            BlobTxValidationError::InsufficientMaxFeePerBlobGas {
+                base_fee_per_blob_gas,
+                tx_max_fee_per_blob_gas,
+            } => TxValidationError::InsufficientMaxFeePerBlobGas {
+                base_fee_per_blob_gas,
+                tx_max_fee_per_blob_gas,
+            },
+        };
+        VMError::TxValidation(tx_validation_error)
+    }
+}
+
 /// Useful to use ? in try_into, specially when slicing with known bounds to fixed size arrays,
 /// which is a error that never really happens.
 impl From<std::array::TryFromSliceError> for VMError {
@@ -64,6 +94,8 @@ pub enum ExceptionalHalt {
     OpcodeNotAllowedInStaticContext,
     #[error("Invalid Contract Prefix")]
     InvalidContractPrefix,
+    #[error("Invalid EOF Container")]
+    InvalidEofContainer,
     #[error("Very Large Number")]
     VeryLargeNumber,
     #[error("Invalid Opcode")]
@@ -146,6 +178,17 @@ pub enum TxValidationError {
         "Transaction gas limit exceeds maximum. Transaction hash: {tx_hash}, transaction gas limit: {tx_gas_limit}"
     )]
     TxMaxGasLimitExceeded { tx_hash: H256, tx_gas_limit: u64 },
+    #[error("Gas limit {gas_limit} exceeds the transaction gas limit cap of {cap}")]
+    GasLimitAboveCap { gas_limit: u64, cap: u64 },
+    /// The mapper matches "Gas allowance exceeded" and "Block gas used overflow" as literal substrings.
+    #[error(
+        "Gas allowance exceeded: Block gas used overflow: used {block_gas_used} + tx limit {tx_gas_limit} > block limit {block_gas_limit}"
+    )]
+    BlockGasUsedOverflow {
+        block_gas_used: u64,
+        tx_gas_limit: u64,
+        block_gas_limit: u64,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, thiserror::Error, Serialize, Deserialize)]
@@ -246,6 +289,15 @@ pub struct ExecutionReport {
     pub gas_refunded: u64,
     pub output: Bytes,
     pub logs: Vec<Log>,
+    /// Deepest call frame depth reached, 0 when no nested call or create was made.
+    #[serde(default)]
+    pub max_depth: usize,
+    /// Gas charged per category, present only if enabled on the VM before execution.
+    #[serde(default)]
+    pub gas_breakdown: Option<GasBreakdownByCategory>,
+    /// Itemized fees, present only for non-privileged L2 transactions.
+    #[serde(default)]
+    pub l2_fees: Option<L2FeeBreakdown>,
 }
 
 impl ExecutionReport {
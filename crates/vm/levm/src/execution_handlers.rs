@@ -7,7 +7,10 @@ use crate::{
 };
 
 use bytes::Bytes;
-use ethrex_common::types::{Code, Fork};
+use ethrex_common::types::{
+    Code, Fork,
+    eof::{ContainerKind, EOF_MAGIC, EofContainer},
+};
 
 impl<'a> VM<'a> {
     pub fn handle_precompile_result(
@@ -46,7 +49,9 @@ impl<'a> VM<'a> {
     pub fn handle_opcode_result(&mut self) -> Result<ContextResult, VMError> {
         // On successful create check output validity
         if self.is_create()? {
+            let gas_remaining_before = self.current_call_frame.gas_remaining;
             let validate_create = self.validate_contract_creation();
+            self.record_code_deposit_gas(gas_remaining_before, validate_create.is_ok());
 
             if let Err(error) = validate_create {
                 if error.should_propagate() {
@@ -169,9 +174,13 @@ impl<'a> VM<'a> {
             .ok_or(InternalError::Overflow)?;
 
         // Revert Scenarios
-        // 1. If the first byte of code is 0xEF
+        // 1. If the first byte of code is 0xEF, unless it's a valid EOF container and EOF is enabled
         if code.first().is_some_and(|v| v == &EOF_PREFIX) {
-            return Err(ExceptionalHalt::InvalidContractPrefix.into());
+            if !self.env.config.eof || !code.starts_with(&EOF_MAGIC) {
+                return Err(ExceptionalHalt::InvalidContractPrefix.into());
+            }
+            EofContainer::from_bytes(code, ContainerKind::Runtime)
+                .map_err(|_| ExceptionalHalt::InvalidEofContainer)?;
         }
 
         // 2. If the code_length > MAX_CODE_SIZE
@@ -0,0 +1,149 @@
+//! Opt-in accounting of the gas charged by a transaction, bucketed by category.
+//!
+//! Enabled with [`VM::enable_gas_breakdown`]. When disabled the interpreter loop only checks
+//! a local bool per opcode.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    errors::{InternalError, OpcodeResult, VMError},
+    memory,
+    opcodes::Opcode,
+    vm::VM,
+};
+
+/// Gas charged during a transaction, bucketed by what it paid for.
+///
+/// The charged buckets add up to the gas consumed before refunds and before the EIP-7623
+/// calldata floor is applied.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GasBreakdownByCategory {
+    /// Intrinsic gas: base cost, calldata, access list, contract creation and authorizations.
+    pub intrinsic: u64,
+    /// Gas charged by opcodes not covered by the other categories.
+    pub compute: u64,
+    /// Memory expansion, whichever opcode triggered it.
+    pub memory: u64,
+    /// SLOAD, SSTORE, TLOAD, TSTORE and the code deposit of contract creations.
+    pub storage: u64,
+    /// CALL and CREATE families and SELFDESTRUCT, including precompile execution.
+    /// Gas forwarded to a child frame is attributed to the child's opcodes and the value
+    /// transfer stipend is deducted.
+    pub calls: u64,
+    /// Gas left in frames that halted exceptionally, which is consumed entirely.
+    pub halted: u64,
+    /// Refund applied at the end of the transaction, all of it from SSTORE clears.
+    pub refunded: u64,
+}
+
+impl GasBreakdownByCategory {
+    /// Total gas charged, before refunds.
+    pub fn charged(&self) -> u64 {
+        self.intrinsic
+            .saturating_add(self.compute)
+            .saturating_add(self.memory)
+            .saturating_add(self.storage)
+            .saturating_add(self.calls)
+            .saturating_add(self.halted)
+    }
+
+    fn bucket_mut(&mut self, opcode: u8) -> &mut u64 {
+        match Opcode::from(opcode) {
+            Opcode::SLOAD | Opcode::SSTORE | Opcode::TLOAD | Opcode::TSTORE => &mut self.storage,
+            Opcode::CREATE
+            | Opcode::CALL
+            | Opcode::CALLCODE
+            | Opcode::DELEGATECALL
+            | Opcode::CREATE2
+            | Opcode::STATICCALL
+            | Opcode::SELFDESTRUCT => &mut self.calls,
+            _ => &mut self.compute,
+        }
+    }
+}
+
+/// State of the executing frame right before an opcode runs.
+#[derive(Debug, Clone, Copy)]
+pub struct GasSnapshot {
+    gas_remaining: i64,
+    memory_len: usize,
+    frames: usize,
+}
+
+impl<'a> VM<'a> {
+    /// Starts bucketing the gas charged by the transaction, see [`GasBreakdownByCategory`].
+    /// Must be called before executing it.
+    pub fn enable_gas_breakdown(&mut self) {
+        self.gas_breakdown = Some(GasBreakdownByCategory::default());
+    }
+
+    pub(crate) fn gas_snapshot(&self) -> GasSnapshot {
+        GasSnapshot {
+            gas_remaining: self.current_call_frame.gas_remaining,
+            memory_len: self.current_call_frame.memory.len(),
+            frames: self.call_frames.len(),
+        }
+    }
+
+    /// Attributes the gas charged by `opcode` since `snapshot` was taken.
+    pub(crate) fn record_opcode_gas(
+        &mut self,
+        opcode: u8,
+        snapshot: GasSnapshot,
+        result: &Result<OpcodeResult, VMError>,
+    ) -> Result<(), VMError> {
+        let Some(breakdown) = self.gas_breakdown.as_mut() else {
+            return Ok(());
+        };
+
+        if let Err(error) = result {
+            if !error.should_propagate() && !error.is_revert_opcode() {
+                // The frame consumes all its gas, whatever the opcode had charged.
+                breakdown.halted = breakdown
+                    .halted
+                    .saturating_add(u64::try_from(snapshot.gas_remaining).unwrap_or_default());
+                return Ok(());
+            }
+        }
+
+        // If a child frame was pushed the opcode's frame is now its parent, and the gas
+        // forwarded to the child is accounted for by the child's own opcodes.
+        let (frame, forwarded) = if self.call_frames.len() > snapshot.frames {
+            let parent = self.call_frames.last().ok_or(InternalError::CallFrame)?;
+            #[expect(clippy::as_conversions, reason = "gas limit fits in i64 (EIP-7825)")]
+            (parent, self.current_call_frame.gas_limit as i64)
+        } else {
+            (&self.current_call_frame, 0)
+        };
+
+        let charged = snapshot
+            .gas_remaining
+            .saturating_sub(frame.gas_remaining)
+            .saturating_sub(forwarded);
+        let charged = u64::try_from(charged).unwrap_or_default();
+        let memory = memory::expansion_cost(frame.memory.len(), snapshot.memory_len)?;
+
+        breakdown.memory = breakdown.memory.saturating_add(memory);
+        let bucket = breakdown.bucket_mut(opcode);
+        *bucket = bucket.saturating_add(charged.saturating_sub(memory));
+        Ok(())
+    }
+
+    /// Attributes the gas charged outside opcodes when a contract creation returns: the code
+    /// deposit on success, or all the frame's gas if the returned code is rejected.
+    pub(crate) fn record_code_deposit_gas(&mut self, gas_remaining_before: i64, deposited: bool) {
+        let Some(breakdown) = self.gas_breakdown.as_mut() else {
+            return;
+        };
+        if deposited {
+            let cost = gas_remaining_before.saturating_sub(self.current_call_frame.gas_remaining);
+            breakdown.storage = breakdown
+                .storage
+                .saturating_add(u64::try_from(cost).unwrap_or_default());
+        } else {
+            breakdown.halted = breakdown
+                .halted
+                .saturating_add(u64::try_from(gas_remaining_before).unwrap_or_default());
+        }
+    }
+}
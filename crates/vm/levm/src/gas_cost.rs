@@ -1,13 +1,15 @@
 use crate::{
     call_frame::CallFrame,
-    constants::{WORD_SIZE, WORD_SIZE_IN_BYTES_U64},
+    constants::{PER_EMPTY_ACCOUNT_COST, TX_BASE_COST, WORD_SIZE, WORD_SIZE_IN_BYTES_U64},
     errors::{ExceptionalHalt, InternalError, PrecompileError, VMError},
     memory,
 };
 use ExceptionalHalt::OutOfGas;
-use bytes::Bytes;
 /// Contains the gas costs of the EVM instructions
-use ethrex_common::{U256, types::Fork};
+use ethrex_common::{
+    U256,
+    types::{Fork, GenericTransaction, Transaction, TxKind},
+};
 use malachite::base::num::logic::traits::*;
 use malachite::{Natural, base::num::basic::traits::Zero as _};
 
@@ -164,6 +166,8 @@ pub const CREATE_BASE_COST: u64 = 32000;
 // Calldata costs
 pub const CALLDATA_COST_ZERO_BYTE: u64 = 4;
 pub const CALLDATA_COST_NON_ZERO_BYTE: u64 = 16;
+// Before EIP-2028
+pub const CALLDATA_COST_NON_ZERO_BYTE_PRE_ISTANBUL: u64 = 68;
 pub const STANDARD_TOKEN_COST: u64 = 4;
 
 // Blob gas costs
@@ -572,22 +576,158 @@ pub fn selfdestruct(
         .ok_or(OutOfGas.into())
 }
 
-pub fn tx_calldata(calldata: &Bytes) -> Result<u64, VMError> {
-    // This cost applies both for call and create
-    // 4 gas for each zero byte in the transaction data 16 gas for each non-zero byte in the transaction.
-    let mut calldata_cost: u64 = 0;
-    for byte in calldata {
-        calldata_cost = if *byte != 0 {
-            calldata_cost
-                .checked_add(CALLDATA_COST_NON_ZERO_BYTE)
-                .ok_or(OutOfGas)?
-        } else {
-            calldata_cost
-                .checked_add(CALLDATA_COST_ZERO_BYTE)
-                .ok_or(OutOfGas)?
+/// Fields of a transaction its intrinsic gas depends on, see [`intrinsic_gas`].
+///
+/// Built from a [`Transaction`] or a [`GenericTransaction`], so that transactions can be
+/// priced before being signed, e.g. when estimating their gas.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IntrinsicGasView<'a> {
+    /// Calldata, or init code of contract creations.
+    pub data: &'a [u8],
+    pub is_create: bool,
+    /// Entries of the access list, repeated addresses included.
+    pub access_list_addresses: usize,
+    /// Storage keys of the access list, over all its entries.
+    pub access_list_storage_keys: usize,
+    /// Authorization tuples of EIP-7702 transactions.
+    pub authorization_list_len: usize,
+}
+
+impl<'a> From<&'a Transaction> for IntrinsicGasView<'a> {
+    fn from(tx: &'a Transaction) -> Self {
+        Self {
+            data: tx.data(),
+            is_create: tx.is_contract_creation(),
+            access_list_addresses: tx.access_list().len(),
+            access_list_storage_keys: tx.access_list().iter().fold(0, |keys, (_, entry_keys)| {
+                keys.saturating_add(entry_keys.len())
+            }),
+            authorization_list_len: tx.authorization_list().map_or(0, |list| list.len()),
+        }
+    }
+}
+
+impl<'a> From<&'a GenericTransaction> for IntrinsicGasView<'a> {
+    fn from(tx: &'a GenericTransaction) -> Self {
+        Self {
+            data: &tx.input,
+            is_create: matches!(tx.to, TxKind::Create),
+            access_list_addresses: tx.access_list.len(),
+            access_list_storage_keys: tx.access_list.iter().fold(0, |keys, entry| {
+                keys.saturating_add(entry.storage_keys.len())
+            }),
+            authorization_list_len: tx.authorization_list.as_ref().map_or(0, Vec::len),
+        }
+    }
+}
+
+/// Intrinsic gas of a transaction, charged before executing it, by component.
+/// Section 6.2 of the Yellow Paper.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IntrinsicGas {
+    /// [`TX_BASE_COST`], charged on every transaction.
+    pub base: u64,
+    /// 4 gas per zero byte of data and 16 per non-zero byte, 68 before Istanbul (EIP-2028).
+    pub calldata: u64,
+    /// 2400 gas per entry and 1900 per storage key of the access list (EIP-2930).
+    pub access_list: u64,
+    /// 25000 gas per authorization tuple (EIP-7702).
+    pub auth_list: u64,
+    /// 32000 gas for contract creations since Homestead (EIP-2), plus 2 per word of init
+    /// code since Shanghai (EIP-3860).
+    pub creation: u64,
+    /// Least gas a transaction uses since Prague (EIP-7623), 21000 plus 10 per calldata
+    /// token, where zero bytes are 1 token and non-zero bytes 4. Zero before Prague.
+    pub floor: u64,
+    /// Sum of the components but the floor, which is charged as gas used instead of the
+    /// execution gas when higher.
+    pub total: u64,
+}
+
+impl IntrinsicGas {
+    /// Lowest gas limit of a valid transaction, which must cover the floor since Prague.
+    pub fn min_gas_limit(&self) -> u64 {
+        self.total.max(self.floor)
+    }
+}
+
+/// Intrinsic gas of `tx` on `fork`. This is what the default hook charges, so that the
+/// mempool and the RPC can tell the gas a transaction needs without executing it.
+pub fn intrinsic_gas(tx: IntrinsicGasView<'_>, fork: Fork) -> Result<IntrinsicGas, VMError> {
+    let data_len = u64::try_from(tx.data.len()).map_err(|_| InternalError::TypeConversion)?;
+    let non_zero_bytes = u64::try_from(tx.data.iter().filter(|byte| **byte != 0).count())
+        .map_err(|_| InternalError::TypeConversion)?;
+    let zero_bytes = data_len
+        .checked_sub(non_zero_bytes)
+        .ok_or(InternalError::Underflow)?;
+
+    let non_zero_byte_cost = if fork >= Fork::Istanbul {
+        CALLDATA_COST_NON_ZERO_BYTE
+    } else {
+        CALLDATA_COST_NON_ZERO_BYTE_PRE_ISTANBUL
+    };
+    let calldata = cost(zero_bytes, CALLDATA_COST_ZERO_BYTE)?
+        .checked_add(cost(non_zero_bytes, non_zero_byte_cost)?)
+        .ok_or(OutOfGas)?;
+
+    let access_list = cost(tx.access_list_addresses, ACCESS_LIST_ADDRESS_COST)?
+        .checked_add(cost(
+            tx.access_list_storage_keys,
+            ACCESS_LIST_STORAGE_KEY_COST,
+        )?)
+        .ok_or(OutOfGas)?;
+
+    let auth_list = cost(tx.authorization_list_len, PER_EMPTY_ACCOUNT_COST)?;
+
+    let mut creation: u64 = 0;
+    if tx.is_create {
+        // https://eips.ethereum.org/EIPS/eip-2#specification
+        if fork >= Fork::Homestead {
+            creation = CREATE_BASE_COST;
+        }
+        // https://eips.ethereum.org/EIPS/eip-3860
+        if fork >= Fork::Shanghai {
+            let words = cost(tx.data.len().div_ceil(WORD_SIZE), INIT_CODE_WORD_COST)?;
+            creation = creation.checked_add(words).ok_or(OutOfGas)?;
         }
     }
-    Ok(calldata_cost)
+
+    // https://eips.ethereum.org/EIPS/eip-7623
+    let floor = if fork >= Fork::Prague {
+        let tokens = non_zero_bytes
+            .checked_mul(STANDARD_TOKEN_COST)
+            .and_then(|tokens| tokens.checked_add(zero_bytes))
+            .ok_or(OutOfGas)?;
+        cost(tokens, TOTAL_COST_FLOOR_PER_TOKEN)?
+            .checked_add(TX_BASE_COST)
+            .ok_or(OutOfGas)?
+    } else {
+        0
+    };
+
+    let total = [calldata, access_list, auth_list, creation]
+        .into_iter()
+        .try_fold(TX_BASE_COST, u64::checked_add)
+        .ok_or(OutOfGas)?;
+
+    Ok(IntrinsicGas {
+        base: TX_BASE_COST,
+        calldata,
+        access_list,
+        auth_list,
+        creation,
+        floor,
+        total,
+    })
+}
+
+/// Cost of `count` items of `unit_cost` each.
+fn cost(count: impl TryInto<u64>, unit_cost: u64) -> Result<u64, VMError> {
+    count
+        .try_into()
+        .map_err(|_| InternalError::TypeConversion)?
+        .checked_mul(unit_cost)
+        .ok_or(OutOfGas.into())
 }
 
 fn address_access_cost(
@@ -2,7 +2,6 @@ use crate::{
     account::LevmAccount,
     constants::*,
     errors::{ContextResult, InternalError, TxValidationError, VMError},
-    gas_cost::{self, STANDARD_TOKEN_COST, TOTAL_COST_FLOOR_PER_TOKEN},
     hooks::hook::Hook,
     utils::*,
     vm::VM,
@@ -11,7 +10,7 @@ use crate::{
 use bytes::Bytes;
 use ethrex_common::{
     Address, H256, U256,
-    types::{Code, Fork},
+    types::{Code, Fork, blobs},
 };
 
 pub const MAX_REFUND_QUOTIENT: u64 = 5;
@@ -296,9 +295,16 @@ pub fn delete_self_destruct_accounts(vm: &mut VM<'_>) -> Result<(), VMError> {
     // Delete the accounts
     for address in vm.substate.iter_selfdestruct() {
         let account_to_remove = vm.db.get_account_mut(*address)?;
-        vm.current_call_frame
-            .call_frame_backup
-            .backup_account_info(*address, account_to_remove)?;
+        let backup = &mut vm.current_call_frame.call_frame_backup;
+        backup.backup_account_info(*address, account_to_remove)?;
+        // Keep the storage that's wiped below so the transaction can be undone
+        let storage_backup = backup
+            .original_account_storage_slots
+            .entry(*address)
+            .or_default();
+        for (key, value) in &account_to_remove.storage {
+            storage_backup.entry(*key).or_insert(*value);
+        }
 
         *account_to_remove = LevmAccount::default();
         account_to_remove.mark_destroyed();
@@ -314,27 +320,14 @@ pub fn delete_self_destruct_accounts(vm: &mut VM<'_>) -> Result<(), VMError> {
 
 pub fn validate_min_gas_limit(vm: &mut VM<'_>) -> Result<(), VMError> {
     // check for gas limit is grater or equal than the minimum required
-    let calldata = vm.current_call_frame.calldata.clone();
-    let intrinsic_gas: u64 = vm.get_intrinsic_gas()?;
+    let intrinsic_gas = vm.intrinsic_gas()?;
 
-    if vm.current_call_frame.gas_limit < intrinsic_gas {
+    if vm.current_call_frame.gas_limit < intrinsic_gas.total {
         return Err(TxValidationError::IntrinsicGasTooLow.into());
     }
 
-    // calldata_cost = tokens_in_calldata * 4
-    let calldata_cost: u64 = gas_cost::tx_calldata(&calldata)?;
-
-    // same as calculated in gas_used()
-    let tokens_in_calldata: u64 = calldata_cost / STANDARD_TOKEN_COST;
-
-    // floor_cost_by_tokens = TX_BASE_COST + TOTAL_COST_FLOOR_PER_TOKEN * tokens_in_calldata
-    let floor_cost_by_tokens = tokens_in_calldata
-        .checked_mul(TOTAL_COST_FLOOR_PER_TOKEN)
-        .ok_or(InternalError::Overflow)?
-        .checked_add(TX_BASE_COST)
-        .ok_or(InternalError::Overflow)?;
-
-    if vm.current_call_frame.gas_limit < floor_cost_by_tokens {
+    // The EIP-7623 floor, TX_BASE_COST + TOTAL_COST_FLOOR_PER_TOKEN * tokens_in_calldata
+    if vm.current_call_frame.gas_limit < intrinsic_gas.floor {
         return Err(TxValidationError::IntrinsicGasBelowFloorGasCost.into());
     }
 
@@ -345,14 +338,7 @@ pub fn validate_max_fee_per_blob_gas(
     vm: &mut VM<'_>,
     tx_max_fee_per_blob_gas: U256,
 ) -> Result<(), VMError> {
-    let base_fee_per_blob_gas = vm.env.base_blob_fee_per_gas;
-    if tx_max_fee_per_blob_gas < base_fee_per_blob_gas {
-        return Err(TxValidationError::InsufficientMaxFeePerBlobGas {
-            base_fee_per_blob_gas,
-            tx_max_fee_per_blob_gas,
-        }
-        .into());
-    }
+    blobs::validate_max_fee_per_blob_gas(tx_max_fee_per_blob_gas, vm.env.base_blob_fee_per_gas)?;
     Ok(())
 }
 
@@ -378,29 +364,10 @@ pub fn validate_sufficient_max_fee_per_gas(vm: &mut VM<'_>) -> Result<(), TxVali
 
 pub fn validate_4844_tx(vm: &mut VM<'_>) -> Result<(), VMError> {
     // (11) TYPE_3_TX_PRE_FORK
-    if vm.env.config.fork < Fork::Cancun {
-        return Err(TxValidationError::Type3TxPreFork.into());
-    }
-
-    let blob_hashes = &vm.env.tx_blob_hashes;
-
     // (12) TYPE_3_TX_ZERO_BLOBS
-    if blob_hashes.is_empty() {
-        return Err(TxValidationError::Type3TxZeroBlobs.into());
-    }
-
     // (13) TYPE_3_TX_INVALID_BLOB_VERSIONED_HASH
-    for blob_hash in blob_hashes {
-        let blob_hash = blob_hash.as_bytes();
-        if blob_hash
-            .first()
-            .is_some_and(|first_byte| !VALID_BLOB_PREFIXES.contains(first_byte))
-        {
-            return Err(TxValidationError::Type3TxInvalidBlobVersionedHash.into());
-        }
-    }
-
     // (14) TYPE_3_TX_BLOB_COUNT_EXCEEDED
+    // Shared with the mempool so both agree on which blob transactions are valid
     let max_blob_count = vm
         .env
         .config
@@ -408,21 +375,11 @@ pub fn validate_4844_tx(vm: &mut VM<'_>) -> Result<(), VMError> {
         .max
         .try_into()
         .map_err(|_| InternalError::TypeConversion)?;
-    let blob_count = blob_hashes.len();
-    if blob_count > max_blob_count {
-        return Err(TxValidationError::Type3TxBlobCountExceeded {
-            max_blob_count,
-            actual_blob_count: blob_count,
-        }
-        .into());
-    }
-    if vm.env.config.fork >= Fork::Osaka && blob_count > MAX_BLOB_COUNT_TX {
-        return Err(TxValidationError::Type3TxBlobCountExceeded {
-            max_blob_count: MAX_BLOB_COUNT_TX,
-            actual_blob_count: blob_count,
-        }
-        .into());
-    }
+    blobs::validate_blob_versioned_hashes(
+        &vm.env.tx_blob_hashes,
+        vm.env.config.fork,
+        max_blob_count,
+    )?;
 
     // (15) TYPE_3_TX_CONTRACT_CREATION
     // NOTE: This will never happen, since the EIP-4844 tx (type 3) does not have a TxKind field
@@ -589,7 +546,11 @@ pub fn set_bytecode_and_code_address(vm: &mut VM<'_>) -> Result<(), VMError> {
         }
 
         let (is_delegation, _eip7702_gas_consumed, code_address, bytecode) =
-            eip7702_get_code(vm.db, &mut vm.substate, to)?;
+            vm.eip7702_get_code(to)?;
+
+        if is_delegation {
+            vm.tracer.annotate_delegation(code_address, true);
+        }
 
         // If EIP-7702 delegation, also record the delegation target (code source) in BAL
         if is_delegation && let Some(recorder) = vm.db.bal_recorder.as_mut() {
@@ -15,6 +15,7 @@ use ethrex_common::{
     },
 };
 use ethrex_rlp::encode::RLPEncode;
+use serde::{Deserialize, Serialize};
 
 pub const COMMON_BRIDGE_L2_ADDRESS: Address = H160([
     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
@@ -25,6 +26,40 @@ pub struct L2Hook {
     pub fee_config: FeeConfig,
 }
 
+/// Itemized fees charged to the sender of a non-privileged L2 transaction.
+///
+/// The items add up to [`L2FeeBreakdown::total`], which is exactly what the sender paid
+/// for gas after the unused gas was refunded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct L2FeeBreakdown {
+    /// Execution gas, after refunds, that the base, priority and operator fees are charged on.
+    pub execution_gas: u64,
+    /// `execution_gas * base_fee_per_gas`, paid to the base fee vault or burned.
+    pub base_fee: U256,
+    /// Tip paid to the coinbase, net of the operator fee.
+    pub priority_fee: U256,
+    /// `execution_gas * operator_fee_per_gas`, paid to the operator fee vault.
+    pub operator_fee: U256,
+    /// `l1_gas * gas_price`, paid to the L1 fee vault.
+    pub l1_data_fee: U256,
+    /// Gas charged for posting the transaction to L1.
+    pub l1_gas: u64,
+    /// Encoded transaction size the L1 data fee was computed on.
+    pub l1_data_size: u64,
+    /// L1 blob gas price the L1 data fee was computed with.
+    pub l1_fee_per_blob_gas: u64,
+}
+
+impl L2FeeBreakdown {
+    /// Total fee paid by the sender.
+    pub fn total(&self) -> Option<U256> {
+        self.base_fee
+            .checked_add(self.priority_fee)?
+            .checked_add(self.operator_fee)?
+            .checked_add(self.l1_data_fee)
+    }
+}
+
 impl Hook for L2Hook {
     fn prepare_execution(&mut self, vm: &mut VM<'_>) -> Result<(), crate::errors::VMError> {
         if vm.env.is_privileged {
@@ -109,6 +144,13 @@ fn finalize_non_privileged_execution(
 
     default_hook::delete_self_destruct_accounts(vm)?;
 
+    vm.l2_fees = Some(compute_fee_breakdown(
+        vm,
+        execution_gas,
+        l1_gas,
+        fee_config,
+    )?);
+
     if let Some(l1_fee_config) = fee_config.l1_fee_config {
         pay_to_l1_fee_vault(vm, l1_gas, l1_fee_config)?;
     }
@@ -134,6 +176,44 @@ fn finalize_non_privileged_execution(
     Ok(())
 }
 
+/// Itemizes the fees charged by [`finalize_non_privileged_execution`], using the same
+/// formulas as the payments themselves.
+fn compute_fee_breakdown(
+    vm: &VM<'_>,
+    execution_gas: u64,
+    l1_gas: u64,
+    fee_config: &FeeConfig,
+) -> Result<L2FeeBreakdown, InternalError> {
+    let per_gas = |price: U256, gas: u64| {
+        U256::from(gas)
+            .checked_mul(price)
+            .ok_or(InternalError::Overflow)
+    };
+    let operator_fee_per_gas = fee_config
+        .operator_fee_config
+        .map(|config| U256::from(config.operator_fee_per_gas))
+        .unwrap_or_default();
+    let priority_fee_per_gas = compute_priority_fee_per_gas(vm, &fee_config.operator_fee_config)?;
+    let l1_fee_config = fee_config.l1_fee_config;
+    let l1_data_size = match l1_fee_config {
+        Some(_) => u64::try_from(vm.tx.length()).map_err(|_| InternalError::Overflow)?,
+        None => 0,
+    };
+
+    Ok(L2FeeBreakdown {
+        execution_gas,
+        base_fee: per_gas(vm.env.base_fee_per_gas, execution_gas)?,
+        priority_fee: per_gas(priority_fee_per_gas, execution_gas)?,
+        operator_fee: per_gas(operator_fee_per_gas, execution_gas)?,
+        l1_data_fee: per_gas(vm.env.gas_price, l1_gas)?,
+        l1_gas,
+        l1_data_size,
+        l1_fee_per_blob_gas: l1_fee_config
+            .map(|config| config.l1_fee_per_blob_gas)
+            .unwrap_or_default(),
+    })
+}
+
 fn validate_sufficient_max_fee_per_gas_l2(
     vm: &VM<'_>,
     operator_fee_config: &Option<OperatorFeeConfig>,
@@ -317,6 +397,7 @@ fn prepare_execution_privileged(vm: &mut VM<'_>) -> Result<(), crate::errors::VM
             hash: H256::zero(),
             bytecode: vec![Opcode::INVALID.into()].into(),
             jump_targets: Vec::new(),
+            eof: Default::default(),
         })?;
         return Ok(());
     }
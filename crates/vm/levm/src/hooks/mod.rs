@@ -4,4 +4,4 @@ pub mod hook;
 pub mod l2_hook;
 
 pub use default_hook::DefaultHook;
-pub use l2_hook::L2Hook;
+pub use l2_hook::{L2FeeBreakdown, L2Hook};
@@ -64,13 +64,16 @@
 //! }
 //! ```
 
+pub mod access_sets;
 pub mod call_frame;
 pub mod constants;
+pub mod coverage;
 pub mod db;
 pub mod debug;
 pub mod environment;
 pub mod errors;
 pub mod execution_handlers;
+pub mod gas_breakdown;
 pub mod gas_cost;
 pub mod hooks;
 pub mod memory;
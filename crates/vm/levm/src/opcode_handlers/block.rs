@@ -32,6 +32,9 @@ impl<'a> VM<'a> {
             .map_err(|_err| ExceptionalHalt::VeryLargeNumber)?;
 
         let block_hash = self.db.store.get_block_hash(block_number)?;
+        if let Some(tracer) = self.db.prestate_tracer.as_mut() {
+            tracer.record_block_hash(block_number, block_hash);
+        }
         self.current_call_frame
             .stack
             .push(u256_from_big_endian_const(block_hash.to_fixed_bytes()))?;
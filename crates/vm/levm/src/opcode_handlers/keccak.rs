@@ -1,4 +1,5 @@
 use crate::{
+    db::KeccakCache,
     errors::{OpcodeResult, VMError},
     gas_cost,
     memory::calculate_memory_size,
@@ -25,7 +26,15 @@ impl<'a> VM<'a> {
             size,
         )?)?;
 
-        let hash = keccak_hash(current_call_frame.memory.load_range(offset, size)?);
+        let preimage = current_call_frame.memory.load_range(offset, size)?;
+        // Only the sizes the keccak cache holds go through the database, which
+        // memoizes them across the block when it's a CachingDatabase with the
+        // keccak cache enabled
+        let hash = if KeccakCache::caches_size(size) {
+            self.db.store.keccak(&preimage)
+        } else {
+            keccak_hash(preimage)
+        };
         current_call_frame.stack.push(u256_from_big_endian(&hash))?;
 
         Ok(OpcodeResult::Continue)
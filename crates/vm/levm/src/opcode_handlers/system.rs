@@ -11,9 +11,17 @@ use crate::{
     vm::VM,
 };
 use bytes::Bytes;
-use ethrex_common::{Address, H256, U256, evm::calculate_create_address, types::Fork};
 use ethrex_common::{
-    tracing::CallType::{self, CALL, CALLCODE, DELEGATECALL, SELFDESTRUCT, STATICCALL},
+    Address, H256, U256,
+    evm::{compute_create_address, compute_create2_address},
+    types::Fork,
+    utils::keccak,
+};
+use ethrex_common::{
+    tracing::{
+        CallType::{self, CALL, CALLCODE, DELEGATECALL, SELFDESTRUCT, STATICCALL},
+        CreateFailure,
+    },
     types::Code,
 };
 
@@ -67,7 +75,7 @@ impl<'a> VM<'a> {
 
         // CHECK EIP7702
         let (is_delegation_7702, eip7702_gas_consumed, code_address, bytecode) =
-            eip7702_get_code(self.db, &mut self.substate, callee)?;
+            self.eip7702_get_code(callee)?;
 
         // GAS
         let (new_memory_size, gas_left, account_is_empty, address_was_cold) = self
@@ -128,6 +136,9 @@ impl<'a> VM<'a> {
         let data = self.get_calldata(args_offset, args_size)?;
 
         self.tracer.enter(CALL, from, to, value, gas_limit, &data);
+        if is_delegation_7702 {
+            self.tracer.annotate_delegation(code_address, false);
+        }
 
         self.generic_call(
             gas_limit,
@@ -187,7 +198,7 @@ impl<'a> VM<'a> {
 
         // CHECK EIP7702
         let (is_delegation_7702, eip7702_gas_consumed, code_address, bytecode) =
-            eip7702_get_code(self.db, &mut self.substate, address)?;
+            self.eip7702_get_code(address)?;
 
         // GAS
         let (new_memory_size, gas_left, _account_is_empty, address_was_cold) = self
@@ -243,6 +254,9 @@ impl<'a> VM<'a> {
 
         self.tracer
             .enter(CALLCODE, from, code_address, value, gas_limit, &data);
+        if is_delegation_7702 {
+            self.tracer.annotate_delegation(code_address, false);
+        }
 
         self.generic_call(
             gas_limit,
@@ -320,7 +334,7 @@ impl<'a> VM<'a> {
 
         // CHECK EIP7702
         let (is_delegation_7702, eip7702_gas_consumed, code_address, bytecode) =
-            eip7702_get_code(self.db, &mut self.substate, address)?;
+            self.eip7702_get_code(address)?;
 
         // GAS
         let (new_memory_size, gas_left, _account_is_empty, address_was_cold) = self
@@ -372,6 +386,9 @@ impl<'a> VM<'a> {
         // In this trace the `from` is the current contract, we don't want the `from` to be, for example, the EOA that sent the transaction
         self.tracer
             .enter(DELEGATECALL, to, code_address, value, gas_limit, &data);
+        if is_delegation_7702 {
+            self.tracer.annotate_delegation(code_address, false);
+        }
 
         self.generic_call(
             gas_limit,
@@ -428,7 +445,7 @@ impl<'a> VM<'a> {
 
         // CHECK EIP7702
         let (is_delegation_7702, eip7702_gas_consumed, code_address, bytecode) =
-            eip7702_get_code(self.db, &mut self.substate, address)?;
+            self.eip7702_get_code(address)?;
 
         // GAS
         let (new_memory_size, gas_left, _account_is_empty, address_was_cold) = self
@@ -478,6 +495,9 @@ impl<'a> VM<'a> {
 
         self.tracer
             .enter(STATICCALL, from, to, value, gas_limit, &data);
+        if is_delegation_7702 {
+            self.tracer.annotate_delegation(code_address, false);
+        }
 
         self.generic_call(
             gas_limit,
@@ -738,6 +758,11 @@ impl<'a> VM<'a> {
         // Validations that can cause out of gas.
         // 1. [EIP-3860] - Cant exceed init code max size
         if code_size_in_memory > INIT_CODE_MAX_SIZE && self.env.config.fork >= Fork::Shanghai {
+            self.tracer
+                .annotate_create_failure(CreateFailure::InitCodeSizeExceeded {
+                    size: code_size_in_memory,
+                    max_size: INIT_CODE_MAX_SIZE,
+                });
             return Err(ExceptionalHalt::OutOfGas.into());
         }
 
@@ -769,8 +794,10 @@ impl<'a> VM<'a> {
 
         // Calculate create address
         let new_address = match salt {
-            Some(salt) => calculate_create2_address(deployer, &code, salt)?,
-            None => calculate_create_address(deployer, deployer_nonce),
+            Some(salt) => {
+                compute_create2_address(deployer, H256(salt.to_big_endian()), keccak(&code))
+            }
+            None => compute_create_address(deployer, deployer_nonce),
         };
 
         // Log CREATE in tracer
@@ -793,12 +820,24 @@ impl<'a> VM<'a> {
         // 2. Depth limit has been reached
         // 3. Sender nonce is max.
         let checks = [
-            (deployer_balance < value, "OutOfFund"),
-            (new_depth > 1024, "MaxDepth"),
-            (deployer_nonce == u64::MAX, "MaxNonce"),
+            (
+                deployer_balance < value,
+                "OutOfFund",
+                CreateFailure::InsufficientBalance {
+                    balance: deployer_balance,
+                    value,
+                },
+            ),
+            (new_depth > 1024, "MaxDepth", CreateFailure::DepthLimit),
+            (
+                deployer_nonce == u64::MAX,
+                "MaxNonce",
+                CreateFailure::NonceOverflow,
+            ),
         ];
-        for (condition, reason) in checks {
+        for (condition, reason, failure) in checks {
             if condition {
+                self.tracer.annotate_create_failure(failure);
                 self.early_revert_message_call(gas_limit, reason.to_string())?;
                 return Ok(OpcodeResult::Continue);
             }
@@ -819,6 +858,10 @@ impl<'a> VM<'a> {
         let new_account = self.get_account_mut(new_address)?;
         if new_account.create_would_collide() {
             self.current_call_frame.stack.push(FAIL)?;
+            self.tracer
+                .annotate_create_failure(CreateFailure::AddressCollision {
+                    address: new_address,
+                });
             self.tracer
                 .exit_early(gas_limit, Some("CreateAccExists".to_string()))?;
             return Ok(OpcodeResult::Continue);
@@ -1212,6 +1255,10 @@ impl<'a> VM<'a> {
                 // If revert we have to copy the return_data
                 if err.is_revert_opcode() {
                     parent_call_frame.sub_return_data = ctx_result.output.clone();
+                    self.tracer
+                        .annotate_create_failure(CreateFailure::InitCodeReverted {
+                            data: ctx_result.output.clone(),
+                        });
                 }
 
                 parent_call_frame.stack.push(FAIL)?;
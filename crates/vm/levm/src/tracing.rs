@@ -1,13 +1,16 @@
 use crate::{
+    account::LevmAccount,
     errors::{ContextResult, InternalError, TxResult, VMError},
     vm::VM,
 };
 use bytes::Bytes;
 use ethrex_common::{
-    Address, U256,
-    tracing::{CallLog, CallTraceFrame, CallType},
+    Address, H256, U256,
+    tracing::{CallLog, CallTraceFrame, CallType, CreateFailure},
     types::Log,
 };
+use rustc_hash::FxHashMap;
+use std::collections::BTreeMap;
 
 /// Geth's callTracer (https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers)
 /// Use `LevmCallTracer::disabled()` when tracing is not wanted.
@@ -74,6 +77,36 @@ impl LevmCallTracer {
         self.callframes.push(callframe);
     }
 
+    /// Records the reentrancy distance computed by the VM for the call being entered.
+    pub fn annotate_reentrancy(&mut self, reentrancy: Option<usize>) {
+        if !self.active || self.only_top_call {
+            return;
+        }
+        if let Some(callframe) = self.callframes.last_mut() {
+            callframe.reentrancy = reentrancy;
+        }
+    }
+
+    /// Records the account whose code runs for the call being entered, when the callee delegates to it (EIP-7702).
+    pub fn annotate_delegation(&mut self, delegated_to: Address, is_top_call: bool) {
+        if !self.active || (self.only_top_call && !is_top_call) {
+            return;
+        }
+        if let Some(callframe) = self.callframes.last_mut() {
+            callframe.delegated_to = Some(delegated_to);
+        }
+    }
+
+    /// Records why the CREATE or CREATE2 of the current frame failed, before exiting it.
+    pub fn annotate_create_failure(&mut self, failure: CreateFailure) {
+        if !self.active || self.only_top_call {
+            return;
+        }
+        if let Some(callframe) = self.callframes.last_mut() {
+            callframe.create_failure = Some(failure);
+        }
+    }
+
     /// Exits trace call.
     /// Has no validations because it's a private method.
     fn exit(
@@ -201,3 +234,37 @@ impl<'a> VM<'a> {
             .ok_or(InternalError::CallFrame.into())
     }
 }
+
+/// Geth's prestateTracer (https://geth.ethereum.org/docs/developers/evm-tracing/built-in-tracers#prestate-tracer)
+/// Records the first read of each account, storage slot and block hash, before the transaction modifies them.
+/// It lives in the `GeneralizedDatabase`, see `GeneralizedDatabase::enable_prestate_tracing`.
+#[derive(Debug, Default, Clone)]
+pub struct PrestateTracer {
+    /// Accounts as they were first read, their storage only holds the slots read.
+    pub accounts: FxHashMap<Address, LevmAccount>,
+    pub block_hashes: BTreeMap<u64, H256>,
+}
+
+impl PrestateTracer {
+    /// Registers an account load, only the first one is kept.
+    pub fn record_account(&mut self, address: Address, account: &LevmAccount) {
+        self.accounts.entry(address).or_insert_with(|| LevmAccount {
+            info: account.info.clone(),
+            storage: Default::default(),
+            has_storage: account.has_storage,
+            status: account.status.clone(),
+        });
+    }
+
+    /// Registers a storage read, only the first one is kept.
+    /// The account must have been loaded before, which always happens before reading its storage.
+    pub fn record_storage(&mut self, address: Address, key: H256, value: U256) {
+        if let Some(account) = self.accounts.get_mut(&address) {
+            account.storage.entry(key).or_insert(value);
+        }
+    }
+
+    pub fn record_block_hash(&mut self, block_number: u64, block_hash: H256) {
+        self.block_hashes.insert(block_number, block_hash);
+    }
+}
@@ -6,21 +6,18 @@ use crate::{
     db::gen_db::GeneralizedDatabase,
     errors::{ExceptionalHalt, InternalError, TxValidationError, VMError},
     gas_cost::{
-        self, ACCESS_LIST_ADDRESS_COST, ACCESS_LIST_STORAGE_KEY_COST, BLOB_GAS_PER_BLOB,
-        COLD_ADDRESS_ACCESS_COST, CREATE_BASE_COST, STANDARD_TOKEN_COST,
-        TOTAL_COST_FLOOR_PER_TOKEN, WARM_ADDRESS_ACCESS_COST,
+        self, BLOB_GAS_PER_BLOB, COLD_ADDRESS_ACCESS_COST, IntrinsicGas, WARM_ADDRESS_ACCESS_COST,
     },
     vm::{Substate, VM},
 };
-use ExceptionalHalt::OutOfGas;
 use bytes::Bytes;
 use ethrex_common::constants::SYSTEM_ADDRESS;
 use ethrex_common::types::Log;
 use ethrex_common::{
     Address, H256, U256,
-    evm::calculate_create_address,
-    types::{Account, Code, Fork, Transaction, fake_exponential, tx_fields::*},
-    utils::{keccak, u256_to_big_endian},
+    evm::compute_create_address,
+    types::{Account, Code, Transaction, fake_exponential, tx_fields::*},
+    utils::u256_to_big_endian,
 };
 use ethrex_common::{types::TxKind, utils::u256_from_big_endian_const};
 use ethrex_rlp;
@@ -39,35 +36,6 @@ pub fn address_to_word(address: Address) -> U256 {
     u256_from_big_endian_const(word)
 }
 
-/// Calculates the address of a new contract using the CREATE2 opcode as follows
-///
-/// initialization_code = memory[offset:offset+size]
-///
-/// address = keccak256(0xff || sender_address || salt || keccak256(initialization_code))[12:]
-pub fn calculate_create2_address(
-    sender_address: Address,
-    initialization_code: &Bytes,
-    salt: U256,
-) -> Result<Address, InternalError> {
-    let init_code_hash = keccak(initialization_code);
-
-    let generated_address = Address::from_slice(
-        keccak(
-            [
-                &[0xff],
-                sender_address.as_bytes(),
-                &salt.to_big_endian(),
-                init_code_hash.as_bytes(),
-            ]
-            .concat(),
-        )
-        .as_bytes()
-        .get(12..)
-        .ok_or(InternalError::Slicing)?,
-    );
-    Ok(generated_address)
-}
-
 // ================== Backup related functions =======================
 
 /// Restore the state of the cache to the state it in the callframe backup.
@@ -79,6 +47,9 @@ pub fn restore_cache_state(
     for (address, account) in callframe_backup.original_accounts_info {
         if let Some(current_account) = db.current_accounts_state.get_mut(&address) {
             current_account.info = account.info;
+            // Undoes a SELFDESTRUCT, which also wipes the storage backed up below
+            current_account.status = account.status;
+            current_account.has_storage = account.has_storage;
         }
     }
 
@@ -96,6 +67,12 @@ pub fn restore_cache_state(
         }
     }
 
+    // Codes deployed by the reverted changes are no longer referenced
+    for code_hash in callframe_backup.inserted_codes {
+        db.codes.remove(&code_hash);
+        db.code_metadata.remove(&code_hash);
+    }
+
     // Restore BAL recorder to checkpoint (but keep touched_addresses per EIP-7928)
     if let Some(checkpoint) = callframe_backup.bal_checkpoint
         && let Some(recorder) = db.bal_recorder.as_mut()
@@ -325,47 +302,64 @@ pub fn eip7702_recover_address(
     Ok(Some(Address::from_slice(&authority_address_bytes)))
 }
 
-/// Gets code of an account, returning early if it's not a delegated account, otherwise
-/// Returns tuple (is_delegated, eip7702_cost, code_address, code).
-/// Notice that it also inserts the delegated account to the "accessed accounts" set.
-///
-/// Where:
-/// - `is_delegated`: True if account is a delegated account.
-/// - `eip7702_cost`: Cost of accessing the delegated account (if any)
-/// - `code_address`: Code address (if delegated, returns the delegated address)
-/// - `code`: Bytecode of the code_address, what the EVM will execute.
-pub fn eip7702_get_code(
-    db: &mut GeneralizedDatabase,
-    accrued_substate: &mut Substate,
-    address: Address,
-) -> Result<(bool, u64, Address, Code), VMError> {
-    // Address is the delgated address
-    let bytecode = db.get_account_code(address)?;
-
-    // If the Address doesn't have a delegation code
-    // return false meaning that is not a delegation
-    // return the same address given
-    // return the bytecode of the given address
-    if !code_has_delegation(&bytecode.bytecode)? {
-        return Ok((false, 0, address, bytecode.clone()));
+/// Where the code run for an account comes from, as defined by EIP-7702.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DelegationTarget {
+    /// The account's own code runs.
+    NotDelegated,
+    /// The account's code is a delegation designation, the code of this address runs instead.
+    Delegated(Address),
+}
+
+impl<'a> VM<'a> {
+    /// Resolves the delegation designation of an account, parsing it only once per transaction.
+    /// The cached target is dropped when the account's code changes, e.g. by an authorization of the transaction.
+    pub fn resolve_delegation(&mut self, address: Address) -> Result<DelegationTarget, VMError> {
+        if let Some(target) = self.delegations.get(&address) {
+            return Ok(*target);
+        }
+        let bytecode = &self.db.get_account_code(address)?.bytecode;
+        let target = if code_has_delegation(bytecode)? {
+            DelegationTarget::Delegated(get_authorized_address_from_code(bytecode)?)
+        } else {
+            DelegationTarget::NotDelegated
+        };
+        self.delegations.insert(address, target);
+        Ok(target)
     }
 
-    // Here the address has a delegation code
-    // The delegation code has the authorized address
-    let auth_address = get_authorized_address_from_code(&bytecode.bytecode)?;
+    /// Gets code of an account, returning early if it's not a delegated account, otherwise
+    /// Returns tuple (is_delegated, eip7702_cost, code_address, code).
+    /// Notice that it also inserts the delegated account to the "accessed accounts" set.
+    ///
+    /// Where:
+    /// - `is_delegated`: True if account is a delegated account.
+    /// - `eip7702_cost`: Cost of accessing the delegated account (if any)
+    /// - `code_address`: Code address (if delegated, returns the delegated address)
+    /// - `code`: Bytecode of the code_address, what the EVM will execute.
+    ///
+    /// Only the first delegation is followed. If the delegated account is delegated too, its designation
+    /// is returned as the code to run, which halts on the 0xef prefix, so chains and loops are never followed.
+    pub fn eip7702_get_code(
+        &mut self,
+        address: Address,
+    ) -> Result<(bool, u64, Address, Code), VMError> {
+        let DelegationTarget::Delegated(auth_address) = self.resolve_delegation(address)? else {
+            let bytecode = self.db.get_account_code(address)?.clone();
+            return Ok((false, 0, address, bytecode));
+        };
 
-    let access_cost = if accrued_substate.add_accessed_address(auth_address) {
-        WARM_ADDRESS_ACCESS_COST
-    } else {
-        COLD_ADDRESS_ACCESS_COST
-    };
+        let access_cost = if self.substate.add_accessed_address(auth_address) {
+            WARM_ADDRESS_ACCESS_COST
+        } else {
+            COLD_ADDRESS_ACCESS_COST
+        };
 
-    let authorized_bytecode = db.get_account_code(auth_address)?.clone();
+        let authorized_bytecode = self.db.get_account_code(auth_address)?.clone();
 
-    Ok((true, access_cost, auth_address, authorized_bytecode))
-}
+        Ok((true, access_cost, auth_address, authorized_bytecode))
+    }
 
-impl<'a> VM<'a> {
     /// Sets the account code as the EIP7702 determines.
     pub fn eip7702_set_access_code(&mut self) -> Result<(), VMError> {
         let mut refunded_gas: u64 = 0;
@@ -469,108 +463,27 @@ impl<'a> VM<'a> {
             .increase_consumed_gas(intrinsic_gas)
             .map_err(|_| TxValidationError::IntrinsicGasTooLow)?;
 
+        if let Some(breakdown) = self.gas_breakdown.as_mut() {
+            breakdown.intrinsic = intrinsic_gas;
+        }
+
         Ok(())
     }
 
     // ==================== Gas related functions =======================
-    pub fn get_intrinsic_gas(&self) -> Result<u64, VMError> {
-        // Intrinsic Gas = Calldata cost + Create cost + Base cost + Access list cost
-        let mut intrinsic_gas: u64 = 0;
-
-        // Calldata Cost
-        // 4 gas for each zero byte in the transaction data 16 gas for each non-zero byte in the transaction.
-        let calldata_cost = gas_cost::tx_calldata(&self.current_call_frame.calldata)?;
-
-        intrinsic_gas = intrinsic_gas.checked_add(calldata_cost).ok_or(OutOfGas)?;
-
-        // Base Cost
-        intrinsic_gas = intrinsic_gas.checked_add(TX_BASE_COST).ok_or(OutOfGas)?;
-
-        // Create Cost
-        if self.is_create()? {
-            // https://eips.ethereum.org/EIPS/eip-2#specification
-            intrinsic_gas = intrinsic_gas
-                .checked_add(CREATE_BASE_COST)
-                .ok_or(OutOfGas)?;
-
-            // https://eips.ethereum.org/EIPS/eip-3860
-            if self.env.config.fork >= Fork::Shanghai {
-                let number_of_words = &self.current_call_frame.calldata.len().div_ceil(WORD_SIZE);
-                let double_number_of_words: u64 = number_of_words
-                    .checked_mul(2)
-                    .ok_or(OutOfGas)?
-                    .try_into()
-                    .map_err(|_| InternalError::TypeConversion)?;
-
-                intrinsic_gas = intrinsic_gas
-                    .checked_add(double_number_of_words)
-                    .ok_or(OutOfGas)?;
-            }
-        }
-
-        // Access List Cost
-        let mut access_lists_cost: u64 = 0;
-        for (_, keys) in self.tx.access_list() {
-            access_lists_cost = access_lists_cost
-                .checked_add(ACCESS_LIST_ADDRESS_COST)
-                .ok_or(OutOfGas)?;
-            for _ in keys {
-                access_lists_cost = access_lists_cost
-                    .checked_add(ACCESS_LIST_STORAGE_KEY_COST)
-                    .ok_or(OutOfGas)?;
-            }
-        }
-
-        intrinsic_gas = intrinsic_gas
-            .checked_add(access_lists_cost)
-            .ok_or(OutOfGas)?;
-
-        // Authorization List Cost
-        // `unwrap_or_default` will return an empty vec when the `authorization_list` field is None.
-        // If the vec is empty, the len will be 0, thus the authorization_list_cost is 0.
-        let amount_of_auth_tuples = match self.tx.authorization_list() {
-            None => 0,
-            Some(list) => list
-                .len()
-                .try_into()
-                .map_err(|_| InternalError::TypeConversion)?,
-        };
-        let authorization_list_cost = PER_EMPTY_ACCOUNT_COST
-            .checked_mul(amount_of_auth_tuples)
-            .ok_or(InternalError::Overflow)?;
-
-        intrinsic_gas = intrinsic_gas
-            .checked_add(authorization_list_cost)
-            .ok_or(OutOfGas)?;
+    /// Intrinsic gas of the transaction, by component. See [`gas_cost::intrinsic_gas`].
+    pub fn intrinsic_gas(&self) -> Result<IntrinsicGas, VMError> {
+        gas_cost::intrinsic_gas(self.tx.into(), self.env.config.fork)
+    }
 
-        Ok(intrinsic_gas)
+    /// Gas consumed by the transaction before the execution of the opcodes.
+    pub fn get_intrinsic_gas(&self) -> Result<u64, VMError> {
+        Ok(self.intrinsic_gas()?.total)
     }
 
-    /// Calculates the minimum gas to be consumed in the transaction.
+    /// Calculates the minimum gas to be consumed in the transaction, the EIP-7623 floor.
     pub fn get_min_gas_used(&self) -> Result<u64, VMError> {
-        // If the transaction is a CREATE transaction, the calldata is emptied and the bytecode is assigned.
-        let calldata = if self.is_create()? {
-            &self.current_call_frame.bytecode.bytecode
-        } else {
-            &self.current_call_frame.calldata
-        };
-
-        // tokens_in_calldata = nonzero_bytes_in_calldata * 4 + zero_bytes_in_calldata
-        // tx_calldata = nonzero_bytes_in_calldata * 16 + zero_bytes_in_calldata * 4
-        // this is actually tokens_in_calldata * STANDARD_TOKEN_COST
-        // see it in https://eips.ethereum.org/EIPS/eip-7623
-        let tokens_in_calldata: u64 = gas_cost::tx_calldata(calldata)? / STANDARD_TOKEN_COST;
-
-        // min_gas_used = TX_BASE_COST + TOTAL_COST_FLOOR_PER_TOKEN * tokens_in_calldata
-        let mut min_gas_used: u64 = tokens_in_calldata
-            .checked_mul(TOTAL_COST_FLOOR_PER_TOKEN)
-            .ok_or(InternalError::Overflow)?;
-
-        min_gas_used = min_gas_used
-            .checked_add(TX_BASE_COST)
-            .ok_or(InternalError::Overflow)?;
-
-        Ok(min_gas_used)
+        Ok(self.intrinsic_gas()?.floor)
     }
 
     /// Gets transaction callee, calculating create address if it's a "Create" transaction.
@@ -591,7 +504,7 @@ impl<'a> VM<'a> {
             TxKind::Create => {
                 let sender_nonce = db.get_account(env.origin)?.info.nonce;
 
-                let created_address = calculate_create_address(env.origin, sender_nonce);
+                let created_address = compute_create_address(env.origin, sender_nonce);
 
                 substate.add_accessed_address(created_address);
                 substate.add_created_account(created_address);
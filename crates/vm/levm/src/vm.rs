@@ -5,9 +5,11 @@ use crate::{
     debug::DebugMode,
     environment::Environment,
     errors::{ContextResult, ExecutionReport, InternalError, OpcodeResult, VMError},
+    gas_breakdown::GasBreakdownByCategory,
     hooks::{
         backup_hook::BackupHook,
         hook::{Hook, get_hooks},
+        l2_hook::L2FeeBreakdown,
     },
     memory::Memory,
     opcodes::OpCodeFn,
@@ -15,6 +17,7 @@ use crate::{
         self, SIZE_PRECOMPILES_CANCUN, SIZE_PRECOMPILES_PRAGUE, SIZE_PRECOMPILES_PRE_CANCUN,
     },
     tracing::LevmCallTracer,
+    utils::DelegationTarget,
 };
 use bytes::Bytes;
 use ethrex_common::{
@@ -43,6 +46,25 @@ pub enum VMType {
     L2(FeeConfig),
 }
 
+/// Outcome of a call to [`VM::execute_bounded`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExecutionStatus {
+    /// The transaction finished executing.
+    Finished(ExecutionReport),
+    /// The step budget ran out at an opcode boundary. The VM keeps all of its state and
+    /// execution resumes with the next call to [`VM::execute_bounded`].
+    Paused,
+}
+
+/// Progress of a transaction executed with [`VM::execute_bounded`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum BoundedExecution {
+    #[default]
+    NotStarted,
+    Running,
+    Finished,
+}
+
 /// Execution substate that tracks changes during transaction execution.
 ///
 /// The substate maintains all information that may need to be reverted if a
@@ -410,8 +432,21 @@ pub struct VM<'a> {
     pub stack_pool: Vec<Stack>,
     /// VM type (L1 or L2 with fee config).
     pub vm_type: VMType,
+    /// Deepest call frame depth reached during execution.
+    pub max_depth: usize,
+    /// Frames in the call stack by storage and code address: how many run that code on that
+    /// storage and the depth of the innermost one. Used to annotate reentrant frames.
+    pub(crate) active_frames: FxHashMap<(Address, Address), (usize, usize)>,
+    /// Gas charged per category, only tracked when enabled with [`VM::enable_gas_breakdown`].
+    pub gas_breakdown: Option<GasBreakdownByCategory>,
+    /// Fees charged by the L2 hook, set for non-privileged L2 transactions.
+    pub l2_fees: Option<L2FeeBreakdown>,
     /// Opcode dispatch table, built dynamically per fork.
     pub(crate) opcode_table: [OpCodeFn<'a>; 256],
+    /// Progress of the transaction when executed with [`VM::execute_bounded`].
+    bounded_execution: BoundedExecution,
+    /// EIP-7702 delegation targets resolved during the transaction, see [`VM::resolve_delegation`].
+    pub(crate) delegations: FxHashMap<Address, DelegationTarget>,
 }
 
 impl<'a> VM<'a> {
@@ -459,7 +494,13 @@ impl<'a> VM<'a> {
                 Memory::default(),
             ),
             env,
+            max_depth: 0,
+            active_frames: FxHashMap::default(),
+            gas_breakdown: None,
+            l2_fees: None,
             opcode_table: VM::build_opcode_table(fork),
+            bounded_execution: BoundedExecution::NotStarted,
+            delegations: FxHashMap::default(),
         };
 
         let call_type = if is_create {
@@ -485,12 +526,65 @@ impl<'a> VM<'a> {
         Ok(vm)
     }
 
-    fn add_hook(&mut self, hook: impl Hook + 'static) {
+    pub(crate) fn add_hook(&mut self, hook: impl Hook + 'static) {
         self.hooks.push(Rc::new(RefCell::new(hook)));
     }
 
     /// Executes a whole external transaction. Performing validations at the beginning.
     pub fn execute(&mut self) -> Result<ExecutionReport, VMError> {
+        let context_result = match self.start_execution()? {
+            Some(context_result) => context_result,
+            None => self.run_execution()?,
+        };
+
+        self.finalize_execution(context_result)
+    }
+
+    /// Executes an external transaction for at most `max_steps` opcodes, so that callers can
+    /// interleave long executions with other work.
+    ///
+    /// Returns [`ExecutionStatus::Paused`] when the budget runs out before the transaction
+    /// finishes; calling this method again resumes execution where it stopped. Pausing only
+    /// happens at opcode boundaries, at any call depth, so the final report is the same as
+    /// the one returned by [`VM::execute`]. Validations and precompiles called by the
+    /// transaction run atomically and don't count towards the budget.
+    pub fn execute_bounded(&mut self, max_steps: u64) -> Result<ExecutionStatus, VMError> {
+        match self.bounded_execution {
+            BoundedExecution::NotStarted => {
+                self.bounded_execution = BoundedExecution::Running;
+                let started = match self.start_execution()? {
+                    Some(context_result) => Some(context_result),
+                    None if self.is_precompile_call() => Some(self.run_execution()?),
+                    None => None,
+                };
+                if let Some(context_result) = started {
+                    return self.finish_bounded_execution(context_result);
+                }
+            }
+            BoundedExecution::Running => {}
+            BoundedExecution::Finished => {
+                return Err(InternalError::msg("Bounded execution already finished").into());
+            }
+        }
+
+        match self.run_opcodes::<true>(max_steps)? {
+            Some(context_result) => self.finish_bounded_execution(context_result),
+            None => Ok(ExecutionStatus::Paused),
+        }
+    }
+
+    fn finish_bounded_execution(
+        &mut self,
+        context_result: ContextResult,
+    ) -> Result<ExecutionStatus, VMError> {
+        self.bounded_execution = BoundedExecution::Finished;
+        let report = self.finalize_execution(context_result)?;
+        Ok(ExecutionStatus::Finished(report))
+    }
+
+    /// Validates the transaction and prepares the initial call frame. Returns the result of
+    /// the transaction if it finished without running any code.
+    fn start_execution(&mut self) -> Result<Option<ContextResult>, VMError> {
         if let Err(e) = self.prepare_execution() {
             // Restore cache to state previous to this Tx execution because this Tx is invalid.
             self.restore_cache_state()?;
@@ -511,27 +605,28 @@ impl<'a> VM<'a> {
         if self.is_create()? {
             // Create contract, reverting the Tx if address is already occupied.
             if let Some(context_result) = self.handle_create_transaction()? {
-                let report = self.finalize_execution(context_result)?;
-                return Ok(report);
+                return Ok(Some(context_result));
             }
         }
 
         self.substate.push_backup();
-        let context_result = self.run_execution()?;
 
-        let report = self.finalize_execution(context_result)?;
+        Ok(None)
+    }
 
-        Ok(report)
+    /// True if the current call frame executes a precompile instead of bytecode.
+    fn is_precompile_call(&self) -> bool {
+        precompiles::is_precompile(
+            &self.current_call_frame.to,
+            self.env.config.fork,
+            self.vm_type,
+        )
     }
 
     /// Main execution loop.
     pub fn run_execution(&mut self) -> Result<ContextResult, VMError> {
         #[expect(clippy::as_conversions, reason = "remaining gas conversion")]
-        if precompiles::is_precompile(
-            &self.current_call_frame.to,
-            self.env.config.fork,
-            self.vm_type,
-        ) {
+        if self.is_precompile_call() {
             let call_frame = &mut self.current_call_frame;
 
             let mut gas_remaining = call_frame.gas_remaining as u64;
@@ -543,17 +638,62 @@ impl<'a> VM<'a> {
                 self.env.config.fork,
             );
 
+            if let Some(breakdown) = self.gas_breakdown.as_mut() {
+                breakdown.calls = breakdown.calls.saturating_add(
+                    (call_frame.gas_remaining as u64).saturating_sub(gas_remaining),
+                );
+            }
             call_frame.gas_remaining = gas_remaining as i64;
 
             return result;
         }
 
+        self.run_opcodes::<false>(0)?
+            .ok_or_else(|| InternalError::msg("Unbounded execution paused").into())
+    }
+
+    /// Runs opcodes until the initial call frame returns. When `BOUNDED`, it stops after
+    /// `max_steps` opcodes and returns `None`, leaving the VM ready to resume.
+    ///
+    /// Coverage and gas breakdown tracking are picked once here, so the loop of an
+    /// uninstrumented execution doesn't check for them on every opcode.
+    fn run_opcodes<const BOUNDED: bool>(
+        &mut self,
+        max_steps: u64,
+    ) -> Result<Option<ContextResult>, VMError> {
+        match (self.db.coverage.is_some(), self.gas_breakdown.is_some()) {
+            (false, false) => self.opcode_loop::<BOUNDED, false, false>(max_steps),
+            (true, false) => self.opcode_loop::<BOUNDED, true, false>(max_steps),
+            (false, true) => self.opcode_loop::<BOUNDED, false, true>(max_steps),
+            (true, true) => self.opcode_loop::<BOUNDED, true, true>(max_steps),
+        }
+    }
+
+    /// The loop of [`VM::run_opcodes`]. `TRACK_COVERAGE` and `TRACK_GAS` must match
+    /// whether coverage and the gas breakdown are enabled.
+    fn opcode_loop<const BOUNDED: bool, const TRACK_COVERAGE: bool, const TRACK_GAS: bool>(
+        &mut self,
+        max_steps: u64,
+    ) -> Result<Option<ContextResult>, VMError> {
         #[cfg(feature = "perf_opcode_timings")]
         let mut timings = crate::timings::OPCODE_TIMINGS.lock().expect("poison");
 
+        let mut steps: u64 = 0;
+
         loop {
+            if BOUNDED {
+                if steps >= max_steps {
+                    return Ok(None);
+                }
+                steps = steps.saturating_add(1);
+            }
+
+            if TRACK_COVERAGE {
+                self.record_coverage();
+            }
             let opcode = self.current_call_frame.next_opcode();
             self.advance_pc(1)?;
+            let gas_snapshot = TRACK_GAS.then(|| self.gas_snapshot());
 
             #[cfg(feature = "perf_opcode_timings")]
             let opcode_time_start = std::time::Instant::now();
@@ -645,6 +785,10 @@ impl<'a> VM<'a> {
                 timings.update(opcode, time);
             }
 
+            if let Some(snapshot) = gas_snapshot {
+                self.record_opcode_gas(opcode, snapshot, &op_result)?;
+            }
+
             let result = match op_result {
                 Ok(OpcodeResult::Continue) => continue,
                 Ok(OpcodeResult::Halt) => self.handle_opcode_result()?,
@@ -654,7 +798,7 @@ impl<'a> VM<'a> {
             // Return the ExecutionReport if the executed callframe was the first one.
             if self.is_initial_call_frame() {
                 self.handle_state_backup(&result)?;
-                return Ok(result);
+                return Ok(Some(result));
             }
 
             // Handle interaction between child and parent callframe.
@@ -728,6 +872,12 @@ impl<'a> VM<'a> {
             gas_refunded: self.substate.refunded_gas,
             output: std::mem::take(&mut ctx_result.output),
             logs,
+            max_depth: self.max_depth,
+            gas_breakdown: self.gas_breakdown.map(|breakdown| GasBreakdownByCategory {
+                refunded: self.substate.refunded_gas,
+                ..breakdown
+            }),
+            l2_fees: self.l2_fees,
         };
 
         Ok(report)
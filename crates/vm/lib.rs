@@ -1,6 +1,8 @@
 mod db;
 mod errors;
 mod execution_result;
+pub mod rpc_error;
+pub mod simulation;
 pub mod tracing;
 mod witness_db;
 
@@ -9,7 +11,13 @@ pub mod backends;
 pub use backends::{BlockExecutionResult, Evm};
 pub use db::{DynVmDatabase, VmDatabase};
 pub use errors::EvmError;
+pub use ethrex_levm::gas_cost::{IntrinsicGas, IntrinsicGasView, intrinsic_gas};
 pub use ethrex_levm::precompiles::precompiles_for_fork;
-pub use execution_result::ExecutionResult;
+pub use execution_result::{
+    AbiEvent, DEFAULT_SIMULATION_OUTPUT_CAP, DecodedLog, ExecutionResult, LogFilter, RevertReason,
+    TruncatedOutput,
+};
+pub use rpc_error::RpcErrorPayload;
+pub use simulation::{SimulatedBlock, SimulatedHeader, SimulationChain};
 pub use witness_db::GuestProgramStateWrapper;
 pub mod system_contracts;
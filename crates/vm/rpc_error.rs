@@ -0,0 +1,138 @@
+//! Canonical mapping of execution errors to JSON-RPC errors.
+//!
+//! Every [`EvmError`], [`VMError`] and [`TxValidationError`] variant maps to a
+//! stable code and message, so the RPC layer never needs to inspect error
+//! messages. The matches deliberately have no wildcard arm: adding a variant
+//! doesn't compile until it's given a mapping here.
+
+use bytes::Bytes;
+use ethrex_common::U256;
+use ethrex_levm::errors::{TxValidationError, VMError};
+
+use crate::EvmError;
+
+/// The execution reverted or halted. The revert data, if any, is in the error data.
+pub const EXECUTION_ERROR: i32 = 3;
+/// The transaction failed validation for a reason without a more specific code.
+pub const TRANSACTION_REJECTED: i32 = -32003;
+/// The transaction nonce is lower than the sender's.
+pub const NONCE_TOO_LOW: i32 = -32010;
+/// The sender can't pay for the transaction's gas and value.
+pub const INSUFFICIENT_FUNDS: i32 = -32011;
+/// The transaction gas limit is above the cap, the block gas limit or what's left of it.
+pub const GAS_LIMIT_EXCEEDED: i32 = -32012;
+/// The blob transaction is invalid.
+pub const BLOB_VALIDATION_ERROR: i32 = -32013;
+/// The gas estimate was clamped to the EIP-7825 transaction gas limit cap.
+pub const GAS_LIMIT_CAP_REACHED: i32 = -32014;
+/// The VM failed for a reason unrelated to the transaction.
+pub const VM_ERROR: i32 = -32015;
+/// The node failed to read the state.
+pub const INTERNAL_ERROR: i32 = -32603;
+
+/// Code, message and optional data of the JSON-RPC error an execution error maps to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcErrorPayload {
+    pub code: i32,
+    pub message: String,
+    pub data: Option<Bytes>,
+}
+
+impl RpcErrorPayload {
+    fn new(code: i32, message: impl ToString) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+            data: None,
+        }
+    }
+
+    /// A reverted execution, `output` is passed through as the error data.
+    pub fn revert(output: Bytes) -> Self {
+        Self {
+            code: EXECUTION_ERROR,
+            message: "execution reverted".to_string(),
+            data: Some(output),
+        }
+    }
+
+    /// An execution halted by an exceptional condition.
+    pub fn halt(reason: &str, gas_used: u64) -> Self {
+        Self::new(
+            EXECUTION_ERROR,
+            format!("execution halted: reason={reason}, gas_used={gas_used}"),
+        )
+    }
+
+    /// A gas estimate that couldn't be narrowed below the transaction gas limit cap, so the
+    /// transaction may need more gas than it can be given. The clamped estimate is passed as
+    /// a 32-byte big-endian word in the error data.
+    pub fn gas_limit_cap_reached(cap: u64) -> Self {
+        Self {
+            code: GAS_LIMIT_CAP_REACHED,
+            message: format!("gas required reaches the transaction gas limit cap of {cap}"),
+            data: Some(Bytes::copy_from_slice(&U256::from(cap).to_big_endian())),
+        }
+    }
+}
+
+impl From<&EvmError> for RpcErrorPayload {
+    fn from(error: &EvmError) -> Self {
+        match error {
+            EvmError::TxValidation(error) => error.into(),
+            EvmError::Transaction(_) => Self::new(TRANSACTION_REJECTED, error),
+            EvmError::DB(_) | EvmError::InvalidFeeConfig(_) => Self::new(INTERNAL_ERROR, error),
+            EvmError::Header(_)
+            | EvmError::Precompile(_)
+            | EvmError::InvalidEVM(_)
+            | EvmError::Custom(_)
+            | EvmError::InvalidDepositRequest
+            | EvmError::SystemContractCallFailed(_) => Self::new(VM_ERROR, error),
+        }
+    }
+}
+
+impl From<&VMError> for RpcErrorPayload {
+    fn from(error: &VMError) -> Self {
+        match error {
+            VMError::TxValidation(error) => error.into(),
+            VMError::Internal(_) => Self::new(INTERNAL_ERROR, error),
+            VMError::ExceptionalHalt(halt) => Self::new(EXECUTION_ERROR, halt),
+            VMError::RevertOpcode => Self::revert(Bytes::new()),
+        }
+    }
+}
+
+impl From<&TxValidationError> for RpcErrorPayload {
+    fn from(error: &TxValidationError) -> Self {
+        let code = match error {
+            TxValidationError::NonceMismatch { expected, actual } if actual < expected => {
+                NONCE_TOO_LOW
+            }
+            TxValidationError::InsufficientAccountFunds => INSUFFICIENT_FUNDS,
+            TxValidationError::GasAllowanceExceeded { .. }
+            | TxValidationError::TxMaxGasLimitExceeded { .. }
+            | TxValidationError::GasLimitAboveCap { .. }
+            | TxValidationError::BlockGasUsedOverflow { .. } => GAS_LIMIT_EXCEEDED,
+            TxValidationError::InsufficientMaxFeePerBlobGas { .. }
+            | TxValidationError::Type3TxPreFork
+            | TxValidationError::Type3TxZeroBlobs
+            | TxValidationError::Type3TxInvalidBlobVersionedHash
+            | TxValidationError::Type3TxBlobCountExceeded { .. }
+            | TxValidationError::Type3TxContractCreation => BLOB_VALIDATION_ERROR,
+            TxValidationError::NonceMismatch { .. }
+            | TxValidationError::NonceIsMax
+            | TxValidationError::SenderNotEOA(_)
+            | TxValidationError::InitcodeSizeExceeded { .. }
+            | TxValidationError::PriorityGreaterThanMaxFeePerGas { .. }
+            | TxValidationError::IntrinsicGasTooLow
+            | TxValidationError::IntrinsicGasBelowFloorGasCost
+            | TxValidationError::InsufficientMaxFeePerGas
+            | TxValidationError::Type4TxPreFork
+            | TxValidationError::Type4TxAuthorizationListIsEmpty
+            | TxValidationError::Type4TxContractCreation
+            | TxValidationError::GasLimitPriceProductOverflow => TRANSACTION_REJECTED,
+        };
+        Self::new(code, error)
+    }
+}
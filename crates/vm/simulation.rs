@@ -0,0 +1,415 @@
+//! Execution of hypothetical chains of blocks on top of a real state.
+//!
+//! A [`SimulationChain`] starts at a base block and executes successive
+//! simulated blocks without writing to the store.  The state after each block
+//! is kept as a layer holding only that block's changes on top of its parent,
+//! so forking the simulation at any height shares every layer below it.
+
+use std::sync::Arc;
+
+use ethrex_common::constants::{
+    DEFAULT_OMMERS_HASH, DEFAULT_REQUESTS_HASH, EMPTY_TRIE_HASH, GAS_PER_BLOB,
+};
+use ethrex_common::types::{
+    AccountState, AccountUpdate, Block, BlockBody, BlockHeader, ChainConfig, Code, CodeMetadata,
+    FeeForecaster, Transaction, Withdrawal, compute_receipts_root, compute_transactions_root,
+    compute_withdrawals_root, merge_updates,
+};
+use ethrex_common::{Address, H256, U256};
+use ethrex_levm::db::gen_db::GeneralizedDatabase;
+use ethrex_levm::errors::DatabaseError;
+use ethrex_levm::vm::VMType;
+use rustc_hash::{FxHashMap, FxHashSet};
+
+use crate::backends::levm::LEVM;
+use crate::backends::{BlockExecutionResult, Evm, LevmDatabase};
+use crate::errors::EvmError;
+
+/// Storage root reported for simulated accounts that gained storage.
+///
+/// Simulated states don't maintain tries, LEVM only checks whether the root
+/// is the empty trie hash.
+const SIMULATED_STORAGE_ROOT: H256 = H256::repeat_byte(0x5e);
+
+/// Header fields of a block built by [`SimulationChain::execute_next`].
+///
+/// Fields left as `None` are derived from the parent with the same rules as
+/// [`FeeForecaster`].
+#[derive(Debug, Clone, Default)]
+pub struct SimulatedHeader {
+    pub coinbase: Address,
+    pub prev_randao: H256,
+    /// Defaults to one forecaster block time after the parent.
+    pub timestamp: Option<u64>,
+    /// Defaults to the parent's gas limit.
+    pub gas_limit: Option<u64>,
+    /// Defaults to the EIP-1559 base fee following the parent.
+    pub base_fee_per_gas: Option<u64>,
+    pub withdrawals: Option<Vec<Withdrawal>>,
+}
+
+/// A block executed by a [`SimulationChain`].
+pub struct SimulatedBlock {
+    pub block: Block,
+    pub result: BlockExecutionResult,
+    /// State changes made by this block alone.
+    pub account_updates: Vec<AccountUpdate>,
+    state: Arc<SimulatedState>,
+}
+
+impl core::fmt::Debug for SimulatedBlock {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SimulatedBlock")
+            .field("block", &self.block)
+            .field("result", &self.result)
+            .field("account_updates", &self.account_updates)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A chain of hypothetical blocks executed on top of a base block.
+///
+/// Nothing is written to the underlying store.  Simulated blocks have no
+/// state root (it's left as the parent's), but their hashes are served to
+/// `BLOCKHASH` and the EIP-2935 history contract of later simulated blocks.
+#[derive(Clone)]
+pub struct SimulationChain {
+    base: BlockHeader,
+    base_state: Arc<dyn LevmDatabase>,
+    vm_type: VMType,
+    chain_config: ChainConfig,
+    forecaster: FeeForecaster,
+    blocks: Vec<Arc<SimulatedBlock>>,
+}
+
+impl SimulationChain {
+    /// Starts a simulation on top of `base`, reading its state from `store`.
+    pub fn new(
+        store: Arc<dyn LevmDatabase>,
+        base: BlockHeader,
+        vm_type: VMType,
+    ) -> Result<Self, EvmError> {
+        let chain_config = store.get_chain_config()?;
+        Ok(Self {
+            base,
+            base_state: store,
+            vm_type,
+            chain_config,
+            forecaster: FeeForecaster::new(chain_config),
+            blocks: Vec::new(),
+        })
+    }
+
+    /// Starts a simulation on the store `evm` reads from.
+    ///
+    /// Changes cached in `evm` but not yet committed are not part of the
+    /// simulated state.
+    pub fn from_evm(evm: &Evm, base: BlockHeader) -> Result<Self, EvmError> {
+        Self::new(evm.db.store.clone(), base, evm.vm_type)
+    }
+
+    /// Overrides the forecaster used to derive headers (block time, elasticity).
+    pub fn with_fee_forecaster(mut self, forecaster: FeeForecaster) -> Self {
+        self.forecaster = forecaster;
+        self
+    }
+
+    /// Header of the last simulated block, or of the base block.
+    pub fn head(&self) -> &BlockHeader {
+        self.blocks
+            .last()
+            .map(|block| &block.block.header)
+            .unwrap_or(&self.base)
+    }
+
+    /// Simulated blocks, in execution order.
+    pub fn blocks(&self) -> impl Iterator<Item = &SimulatedBlock> {
+        self.blocks.iter().map(AsRef::as_ref)
+    }
+
+    /// Simulated block at height `number`.
+    pub fn block(&self, number: u64) -> Option<&SimulatedBlock> {
+        self.blocks
+            .iter()
+            .find(|block| block.block.header.number == number)
+            .map(AsRef::as_ref)
+    }
+
+    /// Cumulative state changes of all simulated blocks relative to the base.
+    pub fn state_diff(&self) -> Vec<AccountUpdate> {
+        merge_updates(
+            self.blocks
+                .iter()
+                .map(|block| block.account_updates.clone())
+                .collect(),
+        )
+    }
+
+    /// State after the last simulated block.
+    pub fn state(&self) -> Arc<dyn LevmDatabase> {
+        match self.blocks.last() {
+            Some(block) => block.state.clone(),
+            None => self.base_state.clone(),
+        }
+    }
+
+    /// An [`Evm`] reading the state after the last simulated block, e.g. to
+    /// inspect accounts or simulate transactions on top of it.
+    pub fn evm(&self) -> Evm {
+        Evm {
+            db: GeneralizedDatabase::new(self.state()),
+            vm_type: self.vm_type,
+        }
+    }
+
+    /// Independent copy of the simulation truncated at height `number`.
+    ///
+    /// Blocks up to `number` are shared with `self`, blocks executed on either
+    /// chain afterwards are not visible to the other.
+    pub fn fork_at(&self, number: u64) -> Result<Self, EvmError> {
+        if number < self.base.number || number > self.head().number {
+            return Err(EvmError::Custom(format!(
+                "Can't fork simulation at block {number}, it spans blocks {} to {}",
+                self.base.number,
+                self.head().number
+            )));
+        }
+        let mut fork = self.clone();
+        fork.blocks
+            .retain(|block| block.block.header.number <= number);
+        Ok(fork)
+    }
+
+    /// Header of the next block, filling in `fields` left unset.
+    ///
+    /// `gas_used` is zero and the state root is the parent's, the header is
+    /// completed by [`SimulationChain::execute_next`] after execution.
+    pub fn next_header(
+        &self,
+        fields: &SimulatedHeader,
+        transactions: &[Transaction],
+    ) -> BlockHeader {
+        let parent = self.head();
+        let timestamp = fields
+            .timestamp
+            .unwrap_or_else(|| self.forecaster.next_timestamp(parent));
+        let blob_gas_used = transactions
+            .iter()
+            .map(|tx| tx.blob_versioned_hashes().len() as u64 * u64::from(GAS_PER_BLOB))
+            .sum();
+
+        BlockHeader {
+            parent_hash: parent.hash(),
+            ommers_hash: *DEFAULT_OMMERS_HASH,
+            coinbase: fields.coinbase,
+            state_root: parent.state_root,
+            transactions_root: compute_transactions_root(transactions),
+            number: parent.number + 1,
+            gas_limit: fields.gas_limit.unwrap_or(parent.gas_limit),
+            timestamp,
+            prev_randao: fields.prev_randao,
+            base_fee_per_gas: self
+                .chain_config
+                .is_london_activated(parent.number + 1)
+                .then(|| {
+                    fields
+                        .base_fee_per_gas
+                        .unwrap_or_else(|| self.forecaster.next_base_fee(parent))
+                }),
+            withdrawals_root: self
+                .chain_config
+                .is_shanghai_activated(timestamp)
+                .then(|| compute_withdrawals_root(fields.withdrawals.as_deref().unwrap_or(&[]))),
+            blob_gas_used: self
+                .chain_config
+                .is_cancun_activated(timestamp)
+                .then_some(blob_gas_used),
+            excess_blob_gas: self.forecaster.next_excess_blob_gas(parent),
+            parent_beacon_block_root: self
+                .chain_config
+                .is_cancun_activated(timestamp)
+                .then_some(H256::zero()),
+            requests_hash: self
+                .chain_config
+                .is_prague_activated(timestamp)
+                .then_some(*DEFAULT_REQUESTS_HASH),
+            ..Default::default()
+        }
+    }
+
+    /// Builds the next block from `transactions`, deriving its header with
+    /// [`SimulationChain::next_header`], and executes it.
+    pub fn execute_next(
+        &mut self,
+        transactions: Vec<Transaction>,
+        fields: SimulatedHeader,
+    ) -> Result<&SimulatedBlock, EvmError> {
+        let header = self.next_header(&fields, &transactions);
+        let body = BlockBody {
+            transactions,
+            ommers: Vec::new(),
+            withdrawals: fields.withdrawals,
+        };
+        let block = Block::new(header, body);
+        let (result, account_updates) = self.run(&block)?;
+
+        // Complete the header so the next block derives its fees from it.
+        let Block { header, body } = block;
+        let header = BlockHeader {
+            hash: Default::default(),
+            gas_used: result.block_gas_used,
+            receipts_root: compute_receipts_root(&result.receipts),
+            ..header
+        };
+        self.push(Block::new(header, body), result, account_updates)
+    }
+
+    /// Executes a block with a caller-provided header on top of the head.
+    pub fn execute_block(&mut self, block: Block) -> Result<&SimulatedBlock, EvmError> {
+        let head = self.head();
+        if block.header.number != head.number + 1 || block.header.parent_hash != head.hash() {
+            return Err(EvmError::Header(format!(
+                "Simulated block {} doesn't extend head {} ({:#x})",
+                block.header.number,
+                head.number,
+                head.hash()
+            )));
+        }
+        let (result, account_updates) = self.run(&block)?;
+        self.push(block, result, account_updates)
+    }
+
+    fn run(&self, block: &Block) -> Result<(BlockExecutionResult, Vec<AccountUpdate>), EvmError> {
+        let mut db = GeneralizedDatabase::new(self.state());
+        let (result, _) = LEVM::execute_block(block, &mut db, self.vm_type)?;
+        let account_updates = LEVM::get_state_transitions(&mut db)?;
+        Ok((result, account_updates))
+    }
+
+    fn push(
+        &mut self,
+        block: Block,
+        result: BlockExecutionResult,
+        account_updates: Vec<AccountUpdate>,
+    ) -> Result<&SimulatedBlock, EvmError> {
+        let state = SimulatedState::new(self.state(), &block.header, &account_updates)?;
+        let index = self.blocks.len();
+        self.blocks.push(Arc::new(SimulatedBlock {
+            block,
+            result,
+            account_updates,
+            state: Arc::new(state),
+        }));
+        Ok(&self.blocks[index])
+    }
+}
+
+/// State after a simulated block: the block's changes on top of its parent.
+struct SimulatedState {
+    parent: Arc<dyn LevmDatabase>,
+    block_number: u64,
+    block_hash: H256,
+    accounts: FxHashMap<Address, AccountState>,
+    storage: FxHashMap<Address, FxHashMap<H256, U256>>,
+    /// Accounts whose storage was wiped, unset slots read as zero.
+    cleared_storage: FxHashSet<Address>,
+    codes: FxHashMap<H256, Code>,
+}
+
+impl SimulatedState {
+    fn new(
+        parent: Arc<dyn LevmDatabase>,
+        header: &BlockHeader,
+        updates: &[AccountUpdate],
+    ) -> Result<Self, EvmError> {
+        let mut accounts = FxHashMap::default();
+        let mut storage = FxHashMap::default();
+        let mut cleared_storage = FxHashSet::default();
+        let mut codes = FxHashMap::default();
+
+        for update in updates {
+            if update.removed {
+                accounts.insert(update.address, AccountState::default());
+                cleared_storage.insert(update.address);
+                continue;
+            }
+
+            let mut account = parent.get_account_state(update.address)?;
+            if update.removed_storage {
+                account.storage_root = *EMPTY_TRIE_HASH;
+                cleared_storage.insert(update.address);
+            }
+            if let Some(info) = &update.info {
+                account.balance = info.balance;
+                account.nonce = info.nonce;
+                account.code_hash = info.code_hash;
+                if let Some(code) = &update.code {
+                    codes.insert(info.code_hash, code.clone());
+                }
+            }
+            if account.storage_root == *EMPTY_TRIE_HASH
+                && update.added_storage.values().any(|value| !value.is_zero())
+            {
+                account.storage_root = SIMULATED_STORAGE_ROOT;
+            }
+            accounts.insert(update.address, account);
+            storage.insert(update.address, update.added_storage.clone());
+        }
+
+        Ok(Self {
+            parent,
+            block_number: header.number,
+            block_hash: header.hash(),
+            accounts,
+            storage,
+            cleared_storage,
+            codes,
+        })
+    }
+}
+
+impl LevmDatabase for SimulatedState {
+    fn get_account_state(&self, address: Address) -> Result<AccountState, DatabaseError> {
+        match self.accounts.get(&address) {
+            Some(account) => Ok(account.clone()),
+            None => self.parent.get_account_state(address),
+        }
+    }
+
+    fn get_storage_value(&self, address: Address, key: H256) -> Result<U256, DatabaseError> {
+        if let Some(value) = self.storage.get(&address).and_then(|slots| slots.get(&key)) {
+            return Ok(*value);
+        }
+        if self.cleared_storage.contains(&address) {
+            return Ok(U256::zero());
+        }
+        self.parent.get_storage_value(address, key)
+    }
+
+    fn get_block_hash(&self, block_number: u64) -> Result<H256, DatabaseError> {
+        if block_number == self.block_number {
+            return Ok(self.block_hash);
+        }
+        self.parent.get_block_hash(block_number)
+    }
+
+    fn get_chain_config(&self) -> Result<ChainConfig, DatabaseError> {
+        self.parent.get_chain_config()
+    }
+
+    fn get_account_code(&self, code_hash: H256) -> Result<Code, DatabaseError> {
+        match self.codes.get(&code_hash) {
+            Some(code) => Ok(code.clone()),
+            None => self.parent.get_account_code(code_hash),
+        }
+    }
+
+    fn get_code_metadata(&self, code_hash: H256) -> Result<CodeMetadata, DatabaseError> {
+        match self.codes.get(&code_hash) {
+            Some(code) => Ok(CodeMetadata {
+                length: code.bytecode.len() as u64,
+            }),
+            None => self.parent.get_code_metadata(code_hash),
+        }
+    }
+}
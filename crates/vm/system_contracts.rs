@@ -1,4 +1,12 @@
-use ethrex_common::{H160, types::Fork, types::Fork::*};
+use bytes::Bytes;
+use ethrex_common::{
+    H160, H256,
+    constants::{
+        DEFAULT_BEACON_ROOTS_ADDRESS, DEFAULT_CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS,
+        DEFAULT_HISTORY_STORAGE_ADDRESS, DEFAULT_WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS,
+    },
+    types::{BlockHeader, ChainConfig, Fork, Fork::*, MAX_EXTRA_SYSTEM_CALLS, SystemCallInput},
+};
 
 pub use ethrex_common::constants::SYSTEM_ADDRESS;
 
@@ -18,37 +26,25 @@ pub const DEPOSIT_CONTRACT_ADDRESS: SystemContract = SystemContract {
 };
 
 pub const BEACON_ROOTS_ADDRESS: SystemContract = SystemContract {
-    address: H160([
-        0x00, 0x0F, 0x3D, 0xF6, 0xD7, 0x32, 0x80, 0x7E, 0xF1, 0x31, 0x9F, 0xB7, 0xB8, 0xBB, 0x85,
-        0x22, 0xD0, 0xBE, 0xAC, 0x02,
-    ]),
+    address: DEFAULT_BEACON_ROOTS_ADDRESS,
     name: "BEACON_ROOTS_ADDRESS",
     active_since_fork: Paris,
 };
 
 pub const HISTORY_STORAGE_ADDRESS: SystemContract = SystemContract {
-    address: H160([
-        0x00, 0x00, 0xF9, 0x08, 0x27, 0xF1, 0xC5, 0x3A, 0x10, 0xCB, 0x7A, 0x02, 0x33, 0x5B, 0x17,
-        0x53, 0x20, 0x00, 0x29, 0x35,
-    ]),
+    address: DEFAULT_HISTORY_STORAGE_ADDRESS,
     name: "HISTORY_STORAGE_ADDRESS",
     active_since_fork: Prague,
 };
 
 pub const WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS: SystemContract = SystemContract {
-    address: H160([
-        0x00, 0x00, 0x09, 0x61, 0xEF, 0x48, 0x0E, 0xB5, 0x5E, 0x80, 0xD1, 0x9A, 0xD8, 0x35, 0x79,
-        0xA6, 0x4C, 0x00, 0x70, 0x02,
-    ]),
+    address: DEFAULT_WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS,
     name: "WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS",
     active_since_fork: Prague,
 };
 
 pub const CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS: SystemContract = SystemContract {
-    address: H160([
-        0x00, 0x00, 0xBB, 0xDD, 0xC7, 0xCE, 0x48, 0x86, 0x42, 0xFB, 0x57, 0x9F, 0x8B, 0x00, 0xF3,
-        0xA5, 0x90, 0x00, 0x72, 0x51,
-    ]),
+    address: DEFAULT_CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS,
     name: "CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS",
     active_since_fork: Prague,
 };
@@ -67,7 +63,74 @@ pub fn system_contracts_for_fork(fork: Fork) -> impl Iterator<Item = SystemContr
         .filter(move |system_contract| system_contract.active_since_fork <= fork)
 }
 
+/// Like [`system_contracts_for_fork`], but with the addresses configured in `chain_config`.
+/// System calls disabled by the chain config are left out.
+pub fn system_contracts_for_chain(
+    chain_config: &ChainConfig,
+    fork: Fork,
+) -> impl Iterator<Item = SystemContract> {
+    let configured = chain_config.system_contracts;
+    [
+        (BEACON_ROOTS_ADDRESS, configured.beacon_roots),
+        (HISTORY_STORAGE_ADDRESS, configured.history_storage),
+        (
+            DEPOSIT_CONTRACT_ADDRESS,
+            Some(chain_config.deposit_contract_address),
+        ),
+        (
+            WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS,
+            configured.withdrawal_request,
+        ),
+        (
+            CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS,
+            configured.consolidation_request,
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(system_contract, address)| {
+        address.map(|address| SystemContract {
+            address,
+            ..system_contract
+        })
+    })
+    .chain(
+        configured
+            .extra
+            .iter()
+            .zip(EXTRA_SYSTEM_CALL_NAMES)
+            .map(|(system_call, name)| SystemContract {
+                address: system_call.address,
+                name,
+                active_since_fork: chain_config.fork(system_call.activation_time),
+            }),
+    )
+    .filter(move |system_contract| system_contract.active_since_fork <= fork)
+}
+
 pub const PRAGUE_SYSTEM_CONTRACTS: [SystemContract; 2] = [
     WITHDRAWAL_REQUEST_PREDEPLOY_ADDRESS,
     CONSOLIDATION_REQUEST_PREDEPLOY_ADDRESS,
 ];
+
+/// Names under which the chain's extra system calls are reported, in order.
+const EXTRA_SYSTEM_CALL_NAMES: [&str; MAX_EXTRA_SYSTEM_CALLS] = [
+    "EXTRA_SYSTEM_CALL_0",
+    "EXTRA_SYSTEM_CALL_1",
+    "EXTRA_SYSTEM_CALL_2",
+    "EXTRA_SYSTEM_CALL_3",
+];
+
+/// Builds the calldata of an extra system call from the header of the block
+/// being executed.
+pub fn extra_system_call_calldata(input: SystemCallInput, block_header: &BlockHeader) -> Bytes {
+    let word = match input {
+        SystemCallInput::Empty => return Bytes::new(),
+        SystemCallInput::ParentHash => block_header.parent_hash,
+        SystemCallInput::ParentBeaconBlockRoot => {
+            block_header.parent_beacon_block_root.unwrap_or_default()
+        }
+        SystemCallInput::BlockNumber => H256::from_low_u64_be(block_header.number),
+        SystemCallInput::Timestamp => H256::from_low_u64_be(block_header.timestamp),
+    };
+    Bytes::copy_from_slice(word.as_bytes())
+}
@@ -1,5 +1,5 @@
 use crate::backends::levm::LEVM;
-use ethrex_common::tracing::CallTrace;
+use ethrex_common::tracing::{CallTrace, NativePrestate};
 use ethrex_common::types::Block;
 
 use crate::{Evm, EvmError};
@@ -34,6 +34,25 @@ impl Evm {
         )
     }
 
+    /// Runs a single tx with the prestate tracer and outputs the state it read before executing.
+    /// Assumes that the received state already contains changes from previous blocks and other
+    /// transactions within its block.
+    pub fn trace_tx_prestate(
+        &mut self,
+        block: &Block,
+        tx_index: usize,
+    ) -> Result<NativePrestate, EvmError> {
+        let tx = block
+            .body
+            .transactions
+            .get(tx_index)
+            .ok_or(EvmError::Custom(
+                "Missing Transaction for Trace".to_string(),
+            ))?;
+
+        LEVM::trace_tx_prestate(&mut self.db, &block.header, tx, self.vm_type)
+    }
+
     /// Reruns the given block, saving the changes on the state, doesn't output any results or receipts.
     /// If the optional argument `stop_index` is set, the run will stop just before executing the transaction at that index
     /// and won't process the withdrawals afterwards.
@@ -0,0 +1,114 @@
+use ethrex_blockchain::vm::StoreVmDatabase;
+use ethrex_common::types::BlockHeader;
+use ethrex_storage::Store;
+use ethrex_vm::VmDatabase;
+
+use super::test_store;
+
+#[tokio::test]
+async fn block_hash_window_boundaries() {
+    let store = test_store().await;
+    let genesis = store.get_block_header(0).unwrap().unwrap();
+    let chain = add_headers(&store, &genesis, 300).await;
+    make_canonical(&store, &chain).await;
+    let head = chain.last().unwrap().clone();
+
+    let vm_db = StoreVmDatabase::new(store.clone(), head.clone()).unwrap();
+
+    // Oldest and newest ancestors reachable by BLOCKHASH from the block after `head`
+    let oldest = &chain[(head.number - 256) as usize];
+    assert_eq!(oldest.number, head.number - 255);
+    assert_eq!(vm_db.get_block_hash(oldest.number).unwrap(), oldest.hash());
+    assert_eq!(vm_db.get_block_hash(head.number).unwrap(), head.hash());
+
+    // The first lookup loads the whole window
+    let cache = vm_db.block_hash_cache.lock().unwrap();
+    assert_eq!(cache.len(), 256);
+    assert_eq!(cache.keys().next(), Some(&oldest.number));
+    drop(cache);
+
+    // Blocks right outside the window are still served from the store
+    let outside = &chain[(head.number - 257) as usize];
+    assert_eq!(
+        vm_db.get_block_hash(outside.number).unwrap(),
+        outside.hash()
+    );
+    assert_eq!(vm_db.get_block_hash(0).unwrap(), genesis.hash());
+}
+
+#[tokio::test]
+async fn block_hash_window_near_genesis() {
+    let store = test_store().await;
+    let genesis = store.get_block_header(0).unwrap().unwrap();
+    let chain = add_headers(&store, &genesis, 10).await;
+    make_canonical(&store, &chain).await;
+    let head = chain.last().unwrap().clone();
+
+    let vm_db = StoreVmDatabase::new(store.clone(), head.clone()).unwrap();
+
+    assert_eq!(vm_db.get_block_hash(0).unwrap(), genesis.hash());
+    assert_eq!(vm_db.get_block_hash(5).unwrap(), chain[4].hash());
+    assert_eq!(vm_db.block_hash_cache.lock().unwrap().len(), 11);
+}
+
+#[tokio::test]
+async fn block_hash_lookups_follow_reorg() {
+    let store = test_store().await;
+    let genesis = store.get_block_header(0).unwrap().unwrap();
+    let chain_a = add_headers(&store, &genesis, 10).await;
+    make_canonical(&store, &chain_a).await;
+
+    // Fork off block 5 with a longer chain that is not canonical yet
+    let chain_b = add_headers(&store, &chain_a[4], 8).await;
+    let tip_a = chain_a.last().unwrap().clone();
+    let tip_b = chain_b.last().unwrap().clone();
+    assert_ne!(chain_a[6].hash(), chain_b[1].hash());
+
+    // Executing on top of the side chain must see its own ancestors
+    let vm_db = StoreVmDatabase::new(store.clone(), tip_b.clone()).unwrap();
+    assert_eq!(vm_db.get_block_hash(7).unwrap(), chain_b[1].hash());
+    assert_eq!(vm_db.get_block_hash(3).unwrap(), chain_a[2].hash());
+
+    // Reorg to the side chain
+    make_canonical(&store, &chain_b).await;
+
+    let vm_db = StoreVmDatabase::new(store.clone(), tip_b.clone()).unwrap();
+    assert_eq!(vm_db.get_block_hash(7).unwrap(), chain_b[1].hash());
+    assert_eq!(vm_db.get_block_hash(13).unwrap(), tip_b.hash());
+
+    // The old branch is no longer canonical, so its window is not taken from the canonical hashes
+    let vm_db = StoreVmDatabase::new(store.clone(), tip_a.clone()).unwrap();
+    assert_eq!(vm_db.get_block_hash(7).unwrap(), chain_a[6].hash());
+    assert_eq!(vm_db.get_block_hash(10).unwrap(), tip_a.hash());
+}
+
+/// Stores `count` empty headers on top of `parent`, all sharing its state root.
+async fn add_headers(store: &Store, parent: &BlockHeader, count: u64) -> Vec<BlockHeader> {
+    let mut headers: Vec<BlockHeader> = Vec::new();
+    for _ in 0..count {
+        let parent = headers.last().unwrap_or(parent);
+        headers.push(BlockHeader {
+            number: parent.number + 1,
+            parent_hash: parent.hash(),
+            state_root: parent.state_root,
+            timestamp: parent.timestamp + 12,
+            ..Default::default()
+        });
+    }
+    store.add_block_headers(headers.clone()).await.unwrap();
+    headers
+}
+
+async fn make_canonical(store: &Store, headers: &[BlockHeader]) {
+    let head = headers.last().unwrap();
+    store
+        .forkchoice_update(
+            headers.iter().map(|h| (h.number, h.hash())).collect(),
+            head.number,
+            head.hash(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+}
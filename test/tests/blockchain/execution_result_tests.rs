@@ -0,0 +1,237 @@
+use bytes::Bytes;
+use ethrex_blockchain::{Blockchain, vm::StoreVmDatabase};
+use ethrex_common::{
+    Address, H256, U256,
+    types::{GenericTransaction, GenesisAccount, TxKind},
+    utils::keccak,
+};
+use ethrex_storage::Store;
+use ethrex_vm::{AbiEvent, ExecutionResult, LogFilter, RevertReason, TruncatedOutput};
+
+use super::{store_with_genesis, test_genesis};
+
+const TRANSFER: &str = "Transfer(address,address,uint256)";
+const OUTPUT_LEN: usize = 1024;
+
+#[tokio::test]
+async fn logs_matching_selects_by_address_and_topics() {
+    let (store, contract) = test_store().await;
+    let result = simulate(&store, contract, None);
+    let transfer = AbiEvent::new(TRANSFER, 2).topic();
+
+    let all = LogFilter::default();
+    assert_eq!(result.logs_matching(&all).count(), 3);
+
+    let transfers = LogFilter::from(&AbiEvent::new(TRANSFER, 2));
+    let matched: Vec<_> = result.logs_matching(&transfers).collect();
+    assert_eq!(matched.len(), 2);
+    assert_eq!(matched[0].topics[1], word(1));
+    assert_eq!(matched[1].topics[1], word(3));
+
+    // An empty position matches any topic, values of a position match any of them
+    let to_four = LogFilter {
+        addresses: vec![contract],
+        topics: vec![vec![transfer], vec![], vec![word(2), word(4)]],
+    };
+    assert_eq!(result.logs_matching(&to_four).count(), 2);
+    let from_three = LogFilter {
+        topics: vec![vec![], vec![word(3)]],
+        ..Default::default()
+    };
+    assert_eq!(result.logs_matching(&from_three).count(), 1);
+
+    let other_emitter = LogFilter {
+        addresses: vec![Address::from_low_u64_be(1)],
+        ..Default::default()
+    };
+    assert_eq!(result.logs_matching(&other_emitter).count(), 0);
+}
+
+#[tokio::test]
+async fn first_log_decoded_splits_parameters() {
+    let (store, contract) = test_store().await;
+    let result = simulate(&store, contract, None);
+
+    let decoded = result
+        .first_log_decoded(&AbiEvent::new(TRANSFER, 2))
+        .unwrap();
+    assert_eq!(decoded.address, contract);
+    assert_eq!(decoded.indexed, &[word(1), word(2)]);
+    assert_eq!(decoded.data, vec![word(42)]);
+
+    // Same signature with another number of indexed parameters is another event
+    assert!(
+        result
+            .first_log_decoded(&AbiEvent::new(TRANSFER, 3))
+            .is_none()
+    );
+    assert!(
+        result
+            .first_log_decoded(&AbiEvent::new("Approval(address,address,uint256)", 2))
+            .is_none()
+    );
+}
+
+#[tokio::test]
+async fn output_over_the_cap_is_truncated_and_hashed() {
+    let (store, contract) = test_store().await;
+    let full = simulate(&store, contract, None);
+    assert_eq!(full.output().len(), OUTPUT_LEN);
+    assert_eq!(full.truncated_output(), None);
+
+    let capped = simulate(&store, contract, Some(100));
+    assert!(capped.is_success());
+    assert_eq!(capped.output(), full.output().slice(..100));
+    assert_eq!(
+        capped.truncated_output(),
+        Some(TruncatedOutput {
+            len: OUTPUT_LEN,
+            hash: keccak(full.output()),
+        })
+    );
+    // Logs are kept whole
+    assert_eq!(capped.logs(), full.logs());
+
+    let at_cap = simulate(&store, contract, Some(OUTPUT_LEN));
+    assert_eq!(at_cap.output(), full.output());
+    assert_eq!(at_cap.truncated_output(), None);
+}
+
+#[test]
+fn revert_reason_is_decoded_for_every_outcome() {
+    let revert = |output: Vec<u8>| ExecutionResult::Revert {
+        gas_used: 0,
+        output: Bytes::from(output),
+        truncated_output: None,
+        l2_fees: None,
+    };
+
+    // Error("nope")
+    let mut error = hex::decode("08c379a0").unwrap();
+    error.extend_from_slice(word(0x20).as_bytes());
+    error.extend_from_slice(word(4).as_bytes());
+    error.extend_from_slice(&[b'n', b'o', b'p', b'e']);
+    error.extend_from_slice(&[0; 28]);
+    assert_eq!(
+        revert(error).revert_reason(),
+        Some(RevertReason::Error("nope".to_string()))
+    );
+
+    // Panic(0x11), an arithmetic overflow
+    let mut panic = hex::decode("4e487b71").unwrap();
+    panic.extend_from_slice(word(0x11).as_bytes());
+    assert_eq!(
+        revert(panic).revert_reason(),
+        Some(RevertReason::Panic(U256::from(0x11)))
+    );
+
+    // Custom errors and malformed data are kept raw
+    let custom = hex::decode("deadbeef").unwrap();
+    assert_eq!(
+        revert(custom.clone()).revert_reason(),
+        Some(RevertReason::Custom(Bytes::from(custom)))
+    );
+    let truncated_error = hex::decode("08c379a000").unwrap();
+    assert_eq!(
+        revert(truncated_error.clone()).revert_reason(),
+        Some(RevertReason::Custom(Bytes::from(truncated_error)))
+    );
+
+    let halt = ExecutionResult::Halt {
+        reason: "OutOfGas".to_string(),
+        gas_used: 0,
+        l2_fees: None,
+    };
+    assert_eq!(
+        halt.revert_reason(),
+        Some(RevertReason::Halt("OutOfGas".to_string()))
+    );
+}
+
+#[test]
+fn cap_output_leaves_halts_and_short_outputs_alone() {
+    let mut halt = ExecutionResult::Halt {
+        reason: "OutOfGas".to_string(),
+        gas_used: 0,
+        l2_fees: None,
+    };
+    halt.cap_output(0);
+    assert_eq!(halt.truncated_output(), None);
+
+    let mut revert = ExecutionResult::Revert {
+        gas_used: 0,
+        output: Bytes::from_static(&[1, 2, 3]),
+        truncated_output: None,
+        l2_fees: None,
+    };
+    revert.cap_output(3);
+    assert_eq!(revert.truncated_output(), None);
+    revert.cap_output(1);
+    assert_eq!(revert.output(), Bytes::from_static(&[1]));
+    assert_eq!(revert.truncated_output().unwrap().len, 3);
+}
+
+// ==================== Helpers ====================
+
+fn word(value: u64) -> H256 {
+    H256::from_low_u64_be(value)
+}
+
+/// Logs `Transfer(1, 2, 42)`, an unrelated log and `Transfer(3, 4, 42)`, then returns
+/// `OUTPUT_LEN` bytes of memory.
+fn multi_log_code() -> Bytes {
+    let transfer_log = |from: u8, to: u8| {
+        let mut code = vec![0x60, to, 0x60, from, 0x7f];
+        code.extend_from_slice(AbiEvent::new(TRANSFER, 2).topic().as_bytes());
+        // PUSH1 32, PUSH1 0, LOG3
+        code.extend_from_slice(&[0x60, 0x20, 0x60, 0x00, 0xa3]);
+        code
+    };
+    // PUSH1 42, PUSH1 0, MSTORE
+    let mut code = vec![0x60, 0x2a, 0x60, 0x00, 0x52];
+    code.extend(transfer_log(1, 2));
+    // PUSH1 7, PUSH1 0, PUSH1 0, LOG1
+    code.extend_from_slice(&[0x60, 0x07, 0x60, 0x00, 0x60, 0x00, 0xa1]);
+    code.extend(transfer_log(3, 4));
+    // PUSH2 OUTPUT_LEN, PUSH1 0, RETURN
+    code.extend_from_slice(&[0x61, 0x04, 0x00, 0x60, 0x00, 0xf3]);
+    Bytes::from(code)
+}
+
+/// Calls `contract` on top of the genesis block, keeping at most `output_cap` bytes of
+/// output.
+fn simulate(store: &Store, contract: Address, output_cap: Option<usize>) -> ExecutionResult {
+    let header = store.get_block_header(0).unwrap().unwrap();
+    let blockchain = Blockchain::default_with_store(store.clone());
+    let vm_db = StoreVmDatabase::new(store.clone(), header.clone()).unwrap();
+    let mut vm = blockchain.new_evm(vm_db).unwrap();
+    let tx = GenericTransaction {
+        to: TxKind::Call(contract),
+        gas: Some(200_000),
+        ..Default::default()
+    };
+
+    let result = vm
+        .simulate_tx_from_generic(&tx, &header, None, output_cap)
+        .unwrap();
+    assert!(result.is_success());
+    result
+}
+
+async fn test_store() -> (Store, Address) {
+    let mut genesis = test_genesis();
+
+    let contract = Address::from_low_u64_be(0x10c5);
+    genesis.alloc.insert(
+        contract,
+        GenesisAccount {
+            code: multi_log_code(),
+            storage: Default::default(),
+            balance: U256::zero(),
+            nonce: 1,
+        },
+    );
+
+    let store = store_with_genesis(genesis).await;
+    (store, contract)
+}
@@ -0,0 +1,170 @@
+//! Tests for the EIP-7825 transaction gas limit cap across the Prague -> Osaka boundary.
+//!
+//! Key behaviors tested:
+//! - `effective_gas_limit` only caps from Osaka on, and errors instead of truncating
+//! - Simulations and access list creation reject a gas limit above the cap after Osaka
+//! - Simulations without a gas limit run with the capped one after Osaka
+
+use bytes::Bytes;
+use ethrex_blockchain::{Blockchain, vm::StoreVmDatabase};
+use ethrex_common::{
+    Address, U256,
+    constants::POST_OSAKA_GAS_LIMIT_CAP,
+    types::{BlockHeader, Fork, GenericTransaction, GenesisAccount, TxKind},
+};
+use ethrex_levm::errors::TxValidationError;
+use ethrex_storage::Store;
+use ethrex_vm::{
+    Evm, EvmError, ExecutionResult,
+    backends::levm::{effective_gas_limit, get_max_allowed_gas_limit},
+};
+
+use super::{store_with_genesis, test_genesis};
+
+// GAS, PUSH1 0, MSTORE, PUSH1 32, PUSH1 0, RETURN
+const RETURN_GAS_LEFT_CODE: &str = "5a60005260206000f3";
+
+const BLOCK_GAS_LIMIT: u64 = 30_000_000;
+const ABOVE_CAP: u64 = POST_OSAKA_GAS_LIMIT_CAP + 1;
+
+#[test]
+fn effective_gas_limit_caps_only_from_osaka() {
+    // Prague: the block gas limit is the default, explicit gas limits are kept
+    assert_eq!(
+        effective_gas_limit(None, BLOCK_GAS_LIMIT, Fork::Prague),
+        Ok(BLOCK_GAS_LIMIT)
+    );
+    assert_eq!(
+        effective_gas_limit(Some(ABOVE_CAP), BLOCK_GAS_LIMIT, Fork::Prague),
+        Ok(ABOVE_CAP)
+    );
+
+    // Osaka: the cap is the default and an explicit gas limit above it is an error
+    assert_eq!(
+        effective_gas_limit(None, BLOCK_GAS_LIMIT, Fork::Osaka),
+        Ok(POST_OSAKA_GAS_LIMIT_CAP)
+    );
+    assert_eq!(
+        effective_gas_limit(Some(POST_OSAKA_GAS_LIMIT_CAP), BLOCK_GAS_LIMIT, Fork::Osaka),
+        Ok(POST_OSAKA_GAS_LIMIT_CAP)
+    );
+    assert_eq!(
+        effective_gas_limit(Some(ABOVE_CAP), BLOCK_GAS_LIMIT, Fork::Osaka),
+        Err(TxValidationError::GasLimitAboveCap {
+            gas_limit: ABOVE_CAP,
+            cap: POST_OSAKA_GAS_LIMIT_CAP,
+        })
+    );
+}
+
+#[test]
+fn max_allowed_gas_limit_never_exceeds_block_gas_limit() {
+    assert_eq!(
+        get_max_allowed_gas_limit(10_000_000, Fork::Prague),
+        10_000_000
+    );
+    assert_eq!(
+        get_max_allowed_gas_limit(10_000_000, Fork::Osaka),
+        10_000_000
+    );
+    assert_eq!(
+        get_max_allowed_gas_limit(BLOCK_GAS_LIMIT, Fork::Osaka),
+        POST_OSAKA_GAS_LIMIT_CAP
+    );
+}
+
+#[tokio::test]
+async fn simulation_rejects_gas_above_cap_after_osaka() {
+    let (store, contract) = test_store(None).await;
+    let result = simulate(&store, contract, Some(ABOVE_CAP));
+    assert!(result.unwrap().is_success());
+
+    let (store, contract) = test_store(Some(0)).await;
+    let result = simulate(&store, contract, Some(ABOVE_CAP));
+    assert!(matches!(
+        result,
+        Err(EvmError::TxValidation(
+            TxValidationError::GasLimitAboveCap { .. }
+        ))
+    ));
+}
+
+#[tokio::test]
+async fn simulation_without_gas_limit_uses_cap_after_osaka() {
+    let (store, contract) = test_store(None).await;
+    let gas_left = gas_left(simulate(&store, contract, None).unwrap());
+    assert!(gas_left > POST_OSAKA_GAS_LIMIT_CAP);
+
+    let (store, contract) = test_store(Some(0)).await;
+    let gas_left = gas_left(simulate(&store, contract, None).unwrap());
+    assert!(gas_left < POST_OSAKA_GAS_LIMIT_CAP);
+}
+
+#[tokio::test]
+async fn access_list_creation_rejects_gas_above_cap_after_osaka() {
+    let (store, contract) = test_store(None).await;
+    let (mut vm, header) = new_evm(&store);
+    assert!(
+        vm.create_access_list(&call(contract, Some(ABOVE_CAP)), &header)
+            .is_ok()
+    );
+
+    let (store, contract) = test_store(Some(0)).await;
+    let (mut vm, header) = new_evm(&store);
+    assert!(matches!(
+        vm.create_access_list(&call(contract, Some(ABOVE_CAP)), &header),
+        Err(EvmError::TxValidation(
+            TxValidationError::GasLimitAboveCap { .. }
+        ))
+    ));
+}
+
+// ==================== Helpers ====================
+
+fn call(contract: Address, gas: Option<u64>) -> GenericTransaction {
+    GenericTransaction {
+        to: TxKind::Call(contract),
+        gas,
+        ..Default::default()
+    }
+}
+
+fn new_evm(store: &Store) -> (Evm, BlockHeader) {
+    let header = store.get_block_header(0).unwrap().unwrap();
+    let blockchain = Blockchain::default_with_store(store.clone());
+    let vm_db = StoreVmDatabase::new(store.clone(), header.clone()).unwrap();
+    (blockchain.new_evm(vm_db).unwrap(), header)
+}
+
+fn simulate(
+    store: &Store,
+    contract: Address,
+    gas: Option<u64>,
+) -> Result<ExecutionResult, EvmError> {
+    let (mut vm, header) = new_evm(store);
+    vm.simulate_tx_from_generic(&call(contract, gas), &header, None, None)
+}
+
+fn gas_left(result: ExecutionResult) -> u64 {
+    U256::from_big_endian(&result.output()).as_u64()
+}
+
+/// Genesis store with a contract returning the gas left, Osaka activates at `osaka_time` if given.
+async fn test_store(osaka_time: Option<u64>) -> (Store, Address) {
+    let mut genesis = test_genesis();
+    genesis.config.osaka_time = osaka_time;
+
+    let contract = Address::from_low_u64_be(0x6a5);
+    genesis.alloc.insert(
+        contract,
+        GenesisAccount {
+            code: Bytes::from(hex::decode(RETURN_GAS_LEFT_CODE).unwrap()),
+            storage: Default::default(),
+            balance: U256::zero(),
+            nonce: 1,
+        },
+    );
+
+    let store = store_with_genesis(genesis).await;
+    (store, contract)
+}
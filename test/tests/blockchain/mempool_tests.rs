@@ -1,5 +1,5 @@
 use ethrex_blockchain::Blockchain;
-use ethrex_blockchain::constants::MAX_INITCODE_SIZE;
+use ethrex_blockchain::constants::{MAX_INITCODE_SIZE, POST_OSAKA_GAS_LIMIT_CAP};
 use ethrex_blockchain::constants::{
     TX_ACCESS_LIST_ADDRESS_GAS, TX_ACCESS_LIST_STORAGE_KEY_GAS, TX_CREATE_GAS_COST,
     TX_DATA_NON_ZERO_GAS, TX_DATA_NON_ZERO_GAS_EIP2028, TX_DATA_ZERO_GAS_COST, TX_GAS_COST,
@@ -168,7 +168,7 @@ fn transaction_create_intrinsic_gas_pre_shanghai() {
 
 #[test]
 fn transaction_create_intrinsic_gas_post_shanghai() {
-    let (config, header) = build_basic_config_and_header(false, true);
+    let (config, header) = build_basic_config_and_header(true, true);
 
     let n_words: u64 = 10;
     let n_bytes: u64 = 32 * n_words - 3; // Test word rounding
@@ -186,8 +186,9 @@ fn transaction_create_intrinsic_gas_post_shanghai() {
     };
 
     let tx = Transaction::EIP1559Transaction(tx);
-    let expected_gas_cost =
-        TX_CREATE_GAS_COST + n_bytes * TX_DATA_NON_ZERO_GAS + n_words * TX_INIT_CODE_WORD_GAS_COST;
+    let expected_gas_cost = TX_CREATE_GAS_COST
+        + n_bytes * TX_DATA_NON_ZERO_GAS_EIP2028
+        + n_words * TX_INIT_CODE_WORD_GAS_COST;
     let intrinsic_gas = transaction_intrinsic_gas(&tx, &header, &config).expect("Intrinsic gas");
     assert_eq!(intrinsic_gas, expected_gas_cost);
 }
@@ -221,6 +222,34 @@ fn transaction_intrinsic_gas_access_list() {
     assert_eq!(intrinsic_gas, expected_gas_cost);
 }
 
+#[test]
+fn transaction_intrinsic_gas_covers_floor_post_prague() {
+    let (mut config, header) = build_basic_config_and_header(true, true);
+    config.cancun_time = Some(0);
+    config.prague_time = Some(0);
+
+    let n_bytes: u64 = 100;
+
+    let tx = EIP1559Transaction {
+        nonce: 3,
+        max_priority_fee_per_gas: 0,
+        max_fee_per_gas: 0,
+        gas_limit: 100_000,
+        to: TxKind::Call(Address::from_low_u64_be(1)), // Normal tx
+        value: U256::zero(),                           // Value zero
+        data: Bytes::from(vec![0x1_u8; n_bytes as usize]), // Calldata
+        access_list: Default::default(),               // No access list
+        ..Default::default()
+    };
+
+    let tx = Transaction::EIP1559Transaction(tx);
+    // EIP-7623: 4 tokens per non-zero byte, 10 gas per token
+    let floor_gas_cost = TX_GAS_COST + n_bytes * 4 * 10;
+    assert!(floor_gas_cost > TX_GAS_COST + n_bytes * TX_DATA_NON_ZERO_GAS_EIP2028);
+    let intrinsic_gas = transaction_intrinsic_gas(&tx, &header, &config).expect("Intrinsic gas");
+    assert_eq!(intrinsic_gas, floor_gas_cost);
+}
+
 #[tokio::test]
 async fn transaction_with_big_init_code_in_shanghai_fails() {
     let (config, header) = build_basic_config_and_header(false, true);
@@ -275,6 +304,43 @@ async fn transaction_with_gas_limit_higher_than_of_the_block_should_fail() {
     ));
 }
 
+#[tokio::test]
+async fn transaction_with_gas_limit_above_cap_fails_only_after_osaka() {
+    for (osaka_time, exceeds_cap) in [(10, false), (1, true)] {
+        let (mut config, header) = build_basic_config_and_header(true, true);
+        config.osaka_time = Some(osaka_time);
+
+        let store = setup_storage(config, header).await.expect("Storage setup");
+        let blockchain = Blockchain::default_with_store(store);
+
+        // The tip above the fee cap makes validation fail right after the gas limit checks
+        let tx = EIP1559Transaction {
+            nonce: 3,
+            max_priority_fee_per_gas: 101,
+            max_fee_per_gas: 100,
+            gas_limit: POST_OSAKA_GAS_LIMIT_CAP + 1,
+            to: TxKind::Call(Address::from_low_u64_be(1)),
+            ..Default::default()
+        };
+
+        let tx = Transaction::EIP1559Transaction(tx);
+        let validation = blockchain
+            .validate_transaction(&tx, Address::random())
+            .await;
+        if exceeds_cap {
+            assert!(matches!(
+                validation,
+                Err(MempoolError::TxMaxGasLimitExceededError(_, _))
+            ));
+        } else {
+            assert!(matches!(
+                validation,
+                Err(MempoolError::TxTipAboveFeeCapError)
+            ));
+        }
+    }
+}
+
 #[tokio::test]
 async fn transaction_with_priority_fee_higher_than_gas_fee_should_fail() {
     let (config, header) = build_basic_config_and_header(false, false);
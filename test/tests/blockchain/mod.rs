@@ -1,2 +1,44 @@
+mod block_hash_window_tests;
+mod execution_result_tests;
+mod gas_limit_cap_tests;
 mod mempool_tests;
+mod randao_override_tests;
+mod simulation_chain_tests;
+mod slot_number_tests;
 mod smoke_tests;
+mod system_contracts_tests;
+
+use std::{fs::File, io::BufReader, path::PathBuf};
+
+use ethrex_common::types::Genesis;
+use ethrex_storage::{EngineType, Store};
+
+/// Returns the workspace root directory.
+/// Uses CARGO_MANIFEST_DIR (which points to test/) and goes up one level.
+pub fn workspace_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..")
+}
+
+/// Loads the genesis fixture shared by the blockchain tests.
+pub fn test_genesis() -> Genesis {
+    let file = File::open(workspace_root().join("fixtures/genesis/execution-api.json"))
+        .expect("Failed to open genesis file");
+    let reader = BufReader::new(file);
+    serde_json::from_reader(reader).expect("Failed to deserialize genesis file")
+}
+
+/// Builds an in-memory store initialized with `genesis`.
+pub async fn store_with_genesis(genesis: Genesis) -> Store {
+    let mut store =
+        Store::new("store.db", EngineType::InMemory).expect("Failed to build DB for testing");
+    store
+        .add_initial_state(genesis)
+        .await
+        .expect("Failed to add genesis state");
+    store
+}
+
+/// Builds an in-memory store initialized with the unmodified genesis fixture.
+pub async fn test_store() -> Store {
+    store_with_genesis(test_genesis()).await
+}
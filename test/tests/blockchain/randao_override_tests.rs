@@ -0,0 +1,97 @@
+use bytes::Bytes;
+use ethrex_blockchain::{
+    Blockchain, BlockchainType, L2Config, payload::pending_block_randao, vm::StoreVmDatabase,
+};
+use ethrex_common::{
+    Address, H256, U256,
+    types::{BlockHeader, GenericTransaction, GenesisAccount, TxKind},
+};
+use ethrex_storage::Store;
+
+use super::{store_with_genesis, test_genesis};
+
+// PREVRANDAO, PUSH1 0, MSTORE, PUSH1 32, PUSH1 0, RETURN
+const RETURN_PREVRANDAO_CODE: &str = "4460005260206000f3";
+
+#[tokio::test]
+async fn randao_override_is_deterministic() {
+    let (store, contract) = test_store().await;
+    let header = store.get_block_header(0).unwrap().unwrap();
+    let seed = H256::from_low_u64_be(0x5eed);
+
+    let first = simulate_prevrandao(&store, &header, contract, Some(seed));
+    let second = simulate_prevrandao(&store, &header, contract, Some(seed));
+
+    assert_eq!(first, seed);
+    assert_eq!(first, second);
+}
+
+#[tokio::test]
+async fn no_randao_override_uses_header_value() {
+    let (store, contract) = test_store().await;
+    let mut header = store.get_block_header(0).unwrap().unwrap();
+    header.prev_randao = H256::from_low_u64_be(0xdead);
+
+    assert_eq!(
+        simulate_prevrandao(&store, &header, contract, None),
+        header.prev_randao
+    );
+}
+
+#[tokio::test]
+async fn pending_block_randao_matches_payload_builder() {
+    let parent = BlockHeader {
+        prev_randao: H256::from_low_u64_be(0xabc),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        pending_block_randao(&parent, &BlockchainType::L1),
+        parent.prev_randao
+    );
+    assert_eq!(
+        pending_block_randao(&parent, &BlockchainType::L2(L2Config::default())),
+        H256::zero()
+    );
+}
+
+/// Calls `contract` on top of `header` and returns the PREVRANDAO it observed.
+fn simulate_prevrandao(
+    store: &Store,
+    header: &BlockHeader,
+    contract: Address,
+    randao_override: Option<H256>,
+) -> H256 {
+    let blockchain = Blockchain::default_with_store(store.clone());
+    let vm_db = StoreVmDatabase::new(store.clone(), header.clone()).unwrap();
+    let mut vm = blockchain.new_evm(vm_db).unwrap();
+    let tx = GenericTransaction {
+        to: TxKind::Call(contract),
+        gas: Some(100_000),
+        ..Default::default()
+    };
+
+    let result = vm
+        .simulate_tx_from_generic(&tx, header, randao_override, None)
+        .unwrap();
+    assert!(result.is_success());
+    H256::from_slice(&result.output())
+}
+
+async fn test_store() -> (Store, Address) {
+    let mut genesis = test_genesis();
+
+    let contract = Address::from_low_u64_be(0x4a4d);
+    genesis.alloc.insert(
+        contract,
+        GenesisAccount {
+            code: Bytes::from(hex::decode(RETURN_PREVRANDAO_CODE).unwrap()),
+            storage: Default::default(),
+            balance: U256::zero(),
+            nonce: 1,
+        },
+    );
+
+    let store = store_with_genesis(genesis).await;
+    (store, contract)
+}
@@ -0,0 +1,229 @@
+use bytes::Bytes;
+use ethrex_blockchain::vm::StoreVmDatabase;
+use ethrex_common::{
+    Address, H256, U256,
+    types::{
+        AccountUpdate, BlockHeader, EIP1559Transaction, FeeForecaster, GenesisAccount, Transaction,
+        TxKind,
+    },
+};
+use ethrex_l2_rpc::signer::{LocalSigner, Signable, Signer};
+use ethrex_storage::Store;
+use ethrex_vm::{Evm, SimulatedHeader, SimulationChain};
+use rand::rngs::OsRng;
+use secp256k1::SecretKey;
+
+use super::{store_with_genesis, test_genesis};
+
+// PUSH1 0, SLOAD, PUSH1 1, ADD, PUSH1 0, SSTORE, STOP
+const COUNTER_CODE: &str = "60005460010160005500";
+
+struct Setup {
+    store: Store,
+    genesis: BlockHeader,
+    chain_id: u64,
+    signer: Signer,
+    counter: Address,
+}
+
+#[tokio::test]
+async fn simulates_consecutive_blocks() {
+    let setup = setup().await;
+    let chain = simulate_three_blocks(&setup).await;
+
+    let numbers: Vec<u64> = chain.blocks().map(|b| b.block.header.number).collect();
+    assert_eq!(numbers, vec![1, 2, 3]);
+    assert_eq!(chain.head().number, 3);
+
+    for (n, block) in (1u64..).zip(chain.blocks()) {
+        assert_eq!(block.result.receipts.len(), 1);
+        assert!(block.result.receipts[0].succeeded);
+        assert_eq!(block.block.header.gas_used, block.result.block_gas_used);
+        assert_eq!(counter_in_updates(&setup, &block.account_updates), Some(n));
+    }
+
+    // Each header links to its parent and derives its base fee from it.
+    let forecaster = FeeForecaster::new(setup.store.get_chain_config());
+    let mut parent = setup.genesis.clone();
+    for block in chain.blocks() {
+        let header = &block.block.header;
+        assert_eq!(header.parent_hash, parent.hash());
+        assert_eq!(
+            header.base_fee_per_gas,
+            Some(forecaster.next_base_fee(&parent))
+        );
+        parent = header.clone();
+    }
+
+    assert_eq!(counter(&chain, &setup), 3);
+    assert_eq!(nonce(&chain, &setup), 3);
+    assert_eq!(counter_in_updates(&setup, &chain.state_diff()), Some(3));
+
+    // The store is left at the base state.
+    let mut base = base_evm(&setup);
+    base.db.get_account(setup.counter).unwrap();
+    assert_eq!(
+        base.db
+            .get_storage_value(setup.counter, H256::zero())
+            .unwrap(),
+        U256::zero()
+    );
+}
+
+#[tokio::test]
+async fn forked_branches_do_not_contaminate_each_other() {
+    let setup = setup().await;
+    let mut main = simulate_three_blocks(&setup).await;
+    let mut fork = main.fork_at(2).unwrap();
+
+    assert_eq!(fork.head().number, 2);
+    assert_eq!(
+        fork.head().hash(),
+        main.block(2).unwrap().block.header.hash()
+    );
+    assert_eq!(counter(&fork, &setup), 2);
+
+    // Block 3 on the fork increments the counter twice.
+    let txs = vec![counter_call(&setup, 2).await, counter_call(&setup, 3).await];
+    fork.execute_next(txs, SimulatedHeader::default()).unwrap();
+    assert_eq!(counter(&fork, &setup), 4);
+    assert_eq!(nonce(&fork, &setup), 4);
+    assert_ne!(
+        fork.block(3).unwrap().block.header.hash(),
+        main.block(3).unwrap().block.header.hash()
+    );
+
+    // The main chain still sees its own block 3 only.
+    assert_eq!(counter(&main, &setup), 3);
+    assert_eq!(nonce(&main, &setup), 3);
+    assert_eq!(main.block(3).unwrap().block.body.transactions.len(), 1);
+
+    // Extending the main chain doesn't leak into the fork either.
+    let tx = counter_call(&setup, 3).await;
+    main.execute_next(vec![tx], SimulatedHeader::default())
+        .unwrap();
+    assert_eq!(counter(&main, &setup), 4);
+    assert_eq!(main.head().number, 4);
+    assert_eq!(fork.head().number, 3);
+    assert!(fork.block(4).is_none());
+    assert_eq!(counter_in_updates(&setup, &fork.state_diff()), Some(4));
+    assert_eq!(counter_in_updates(&setup, &main.state_diff()), Some(4));
+    assert_eq!(nonce(&fork, &setup), 4);
+    assert_eq!(nonce(&main, &setup), 4);
+}
+
+#[tokio::test]
+async fn rejects_blocks_not_extending_the_head() {
+    let setup = setup().await;
+    let mut chain = simulate_three_blocks(&setup).await;
+    assert!(chain.fork_at(4).is_err());
+
+    let mut fork = chain.fork_at(1).unwrap();
+    let block = chain.block(3).unwrap().block.clone();
+    assert!(fork.execute_block(block.clone()).is_err());
+
+    // The same block is accepted on the branch it was built on.
+    let mut replay = chain.fork_at(2).unwrap();
+    replay.execute_block(block).unwrap();
+    assert_eq!(counter(&replay, &setup), 3);
+
+    let tx = counter_call(&setup, 3).await;
+    chain
+        .execute_next(vec![tx], SimulatedHeader::default())
+        .unwrap();
+    assert_eq!(counter(&chain, &setup), 4);
+}
+
+async fn simulate_three_blocks(setup: &Setup) -> SimulationChain {
+    let mut chain = SimulationChain::from_evm(&base_evm(setup), setup.genesis.clone()).unwrap();
+    for nonce in 0..3 {
+        let tx = counter_call(setup, nonce).await;
+        chain
+            .execute_next(vec![tx], SimulatedHeader::default())
+            .unwrap();
+    }
+    chain
+}
+
+fn base_evm(setup: &Setup) -> Evm {
+    let vm_db = StoreVmDatabase::new(setup.store.clone(), setup.genesis.clone()).unwrap();
+    Evm::new_for_l1(vm_db)
+}
+
+fn counter(chain: &SimulationChain, setup: &Setup) -> u64 {
+    let mut evm = chain.evm();
+    evm.db.get_account(setup.counter).unwrap();
+    evm.db
+        .get_storage_value(setup.counter, H256::zero())
+        .unwrap()
+        .as_u64()
+}
+
+fn nonce(chain: &SimulationChain, setup: &Setup) -> u64 {
+    chain
+        .evm()
+        .db
+        .get_account(setup.signer.address())
+        .unwrap()
+        .info
+        .nonce
+}
+
+fn counter_in_updates(setup: &Setup, updates: &[AccountUpdate]) -> Option<u64> {
+    updates
+        .iter()
+        .find(|update| update.address == setup.counter)
+        .and_then(|update| update.added_storage.get(&H256::zero()))
+        .map(U256::as_u64)
+}
+
+async fn counter_call(setup: &Setup, nonce: u64) -> Transaction {
+    let mut tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+        chain_id: setup.chain_id,
+        nonce,
+        to: TxKind::Call(setup.counter),
+        data: Bytes::new(),
+        gas_limit: 100_000,
+        max_fee_per_gas: 10_000_000_000,
+        max_priority_fee_per_gas: 1_000_000_000,
+        ..Default::default()
+    });
+    tx.sign_inplace(&setup.signer).await.unwrap();
+    tx
+}
+
+async fn setup() -> Setup {
+    let mut genesis = test_genesis();
+
+    let signer: Signer = LocalSigner::new(SecretKey::new(&mut OsRng)).into();
+    genesis.alloc.insert(
+        signer.address(),
+        GenesisAccount {
+            code: Bytes::new(),
+            storage: Default::default(),
+            balance: U256::from(10).pow(U256::from(20)),
+            nonce: 0,
+        },
+    );
+    let counter = Address::from_low_u64_be(0xc0);
+    genesis.alloc.insert(
+        counter,
+        GenesisAccount {
+            code: Bytes::from(hex::decode(COUNTER_CODE).unwrap()),
+            storage: Default::default(),
+            balance: U256::zero(),
+            nonce: 1,
+        },
+    );
+    let chain_id = genesis.config.chain_id;
+
+    let store = store_with_genesis(genesis).await;
+    let genesis = store.get_block_header(0).unwrap().unwrap();
+    Setup {
+        store,
+        genesis,
+        chain_id,
+        signer,
+        counter,
+    }
+}
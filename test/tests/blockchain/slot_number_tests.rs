@@ -0,0 +1,135 @@
+use bytes::Bytes;
+use ethrex_blockchain::{
+    Blockchain,
+    error::ChainError,
+    payload::{BuildPayloadArgs, create_payload},
+};
+use ethrex_common::{
+    Address, H160, H256, InvalidBlockError, U256,
+    types::{
+        Block, DEFAULT_BUILDER_GAS_CEIL, EIP1559Transaction, ELASTICITY_MULTIPLIER, Genesis,
+        GenesisAccount, InvalidBlockHeaderError, Transaction, TxKind,
+    },
+};
+use ethrex_l2_rpc::signer::{LocalSigner, Signable, Signer};
+use rand::rngs::OsRng;
+use secp256k1::SecretKey;
+
+use super::{store_with_genesis, test_genesis};
+
+// SLOTNUM, PUSH1 0, SSTORE, STOP
+const STORE_SLOTNUM_CODE: &str = "4b60005500";
+
+const SLOT_NUMBER: u64 = 0x5107;
+
+struct Setup {
+    genesis: Genesis,
+    signer: Signer,
+    contract: Address,
+}
+
+#[tokio::test]
+async fn amsterdam_block_using_slotnum_is_imported() {
+    let setup = setup();
+    let block = build_block(&setup, Some(SLOT_NUMBER)).await;
+    assert_eq!(block.header.slot_number, Some(SLOT_NUMBER));
+
+    // Import the block on a node that didn't build it
+    let store = store_with_genesis(setup.genesis.clone()).await;
+    let blockchain = Blockchain::default_with_store(store.clone());
+    blockchain.add_block(block.clone()).unwrap();
+
+    let stored = store
+        .get_storage_at_root(block.header.state_root, setup.contract, H256::zero())
+        .unwrap();
+    assert_eq!(stored, Some(U256::from(SLOT_NUMBER)));
+}
+
+#[tokio::test]
+async fn amsterdam_block_without_slot_number_is_rejected() {
+    let setup = setup();
+    let mut block = build_block(&setup, Some(SLOT_NUMBER)).await;
+    block.header.slot_number = None;
+    block.header.hash = Default::default();
+
+    let store = store_with_genesis(setup.genesis.clone()).await;
+    let blockchain = Blockchain::default_with_store(store);
+    let result = blockchain.add_block(block);
+
+    assert!(matches!(
+        result,
+        Err(ChainError::InvalidBlock(InvalidBlockError::InvalidHeader(
+            InvalidBlockHeaderError::SlotNumberNotPresent
+        )))
+    ));
+}
+
+/// Builds a block on top of genesis with a transaction storing SLOTNUM.
+async fn build_block(setup: &Setup, slot_number: Option<u64>) -> Block {
+    let store = store_with_genesis(setup.genesis.clone()).await;
+    let parent = store.get_block_header(0).unwrap().unwrap();
+    let blockchain = Blockchain::default_with_store(store.clone());
+
+    let mut tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+        chain_id: setup.genesis.config.chain_id,
+        nonce: 0,
+        to: TxKind::Call(setup.contract),
+        data: Bytes::new(),
+        gas_limit: 200_000,
+        max_fee_per_gas: 10_000_000_000,
+        max_priority_fee_per_gas: 1_000_000_000,
+        ..Default::default()
+    });
+    tx.sign_inplace(&setup.signer).await.unwrap();
+    blockchain.add_transaction_to_pool(tx).await.unwrap();
+
+    let args = BuildPayloadArgs {
+        parent: parent.hash(),
+        timestamp: parent.timestamp + 12,
+        fee_recipient: H160::random(),
+        random: H256::random(),
+        withdrawals: Some(Vec::new()),
+        beacon_root: Some(H256::random()),
+        slot_number,
+        version: 4,
+        elasticity_multiplier: ELASTICITY_MULTIPLIER,
+        gas_ceil: DEFAULT_BUILDER_GAS_CEIL,
+    };
+    let block = create_payload(&args, &store, Bytes::new()).unwrap();
+    let block = blockchain.build_payload(block).unwrap().payload;
+    assert_eq!(block.body.transactions.len(), 1);
+    block
+}
+
+fn setup() -> Setup {
+    let mut genesis = test_genesis();
+    genesis.config.osaka_time = Some(0);
+    genesis.config.amsterdam_time = Some(0);
+
+    let signer: Signer = LocalSigner::new(SecretKey::new(&mut OsRng)).into();
+    genesis.alloc.insert(
+        signer.address(),
+        GenesisAccount {
+            code: Bytes::new(),
+            storage: Default::default(),
+            balance: U256::from(10).pow(U256::from(20)),
+            nonce: 0,
+        },
+    );
+    let contract = Address::from_low_u64_be(0x5107);
+    genesis.alloc.insert(
+        contract,
+        GenesisAccount {
+            code: Bytes::from(hex::decode(STORE_SLOTNUM_CODE).unwrap()),
+            storage: Default::default(),
+            balance: U256::zero(),
+            nonce: 1,
+        },
+    );
+
+    Setup {
+        genesis,
+        signer,
+        contract,
+    }
+}
@@ -1,5 +1,3 @@
-use std::{fs::File, io::BufReader, path::PathBuf};
-
 use bytes::Bytes;
 use ethrex_blockchain::{
     Blockchain,
@@ -12,7 +10,9 @@ use ethrex_common::{
     H160, H256,
     types::{Block, BlockHeader, DEFAULT_BUILDER_GAS_CEIL, ELASTICITY_MULTIPLIER},
 };
-use ethrex_storage::{EngineType, Store};
+use ethrex_storage::Store;
+
+use super::test_store;
 
 #[tokio::test]
 async fn test_small_to_long_reorg() {
@@ -305,26 +305,3 @@ async fn new_block(store: &Store, parent: &BlockHeader) -> Block {
     let result = blockchain.build_payload(block).unwrap();
     result.payload
 }
-
-fn workspace_root() -> PathBuf {
-    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..")
-}
-
-async fn test_store() -> Store {
-    // Get genesis
-    let file = File::open(workspace_root().join("fixtures/genesis/execution-api.json"))
-        .expect("Failed to open genesis file");
-    let reader = BufReader::new(file);
-    let genesis = serde_json::from_reader(reader).expect("Failed to deserialize genesis file");
-
-    // Build store with genesis
-    let mut store =
-        Store::new("store.db", EngineType::InMemory).expect("Failed to build DB for testing");
-
-    store
-        .add_initial_state(genesis)
-        .await
-        .expect("Failed to add genesis state");
-
-    store
-}
@@ -0,0 +1,190 @@
+use bytes::Bytes;
+use ethrex_blockchain::{
+    Blockchain,
+    payload::{BuildPayloadArgs, create_payload},
+};
+use ethrex_common::{
+    Address, H160, H256, U256,
+    constants::{DEFAULT_BEACON_ROOTS_ADDRESS, DEFAULT_HISTORY_STORAGE_ADDRESS, SYSTEM_ADDRESS},
+    types::{
+        Block, DEFAULT_BUILDER_GAS_CEIL, ELASTICITY_MULTIPLIER, ExtraSystemCallConfig,
+        ExtraSystemCalls, Genesis, GenesisAccount, SystemCallInput,
+    },
+};
+use ethrex_storage::Store;
+
+use super::{store_with_genesis, test_genesis};
+
+// Ring buffer length used by both EIP-4788 and EIP-2935 contracts
+const HISTORY_BUFFER_LENGTH: u64 = 8191;
+
+#[tokio::test]
+async fn default_system_contracts_keep_mainnet_behavior() {
+    let store = test_store(|_| {}).await;
+    let genesis_hash = store.get_block_header(0).unwrap().unwrap().hash();
+    let block = build_and_add_block(&store).await;
+
+    assert_eq!(
+        beacon_root_stored(&store, &block, DEFAULT_BEACON_ROOTS_ADDRESS),
+        block.header.parent_beacon_block_root
+    );
+    assert_eq!(
+        block_hash_stored(&store, &block, DEFAULT_HISTORY_STORAGE_ADDRESS),
+        Some(genesis_hash)
+    );
+}
+
+#[tokio::test]
+async fn relocated_beacon_roots_contract_is_called() {
+    let relocated = Address::from_low_u64_be(0xbeac02);
+    let store = test_store(|genesis| {
+        let beacon_roots = genesis.alloc[&DEFAULT_BEACON_ROOTS_ADDRESS].clone();
+        genesis.alloc.insert(relocated, beacon_roots);
+        genesis.config.system_contracts.beacon_roots = Some(relocated);
+    })
+    .await;
+    let block = build_and_add_block(&store).await;
+
+    assert_eq!(
+        beacon_root_stored(&store, &block, relocated),
+        block.header.parent_beacon_block_root
+    );
+    assert_eq!(
+        beacon_root_stored(&store, &block, DEFAULT_BEACON_ROOTS_ADDRESS),
+        None
+    );
+}
+
+#[tokio::test]
+async fn disabled_history_storage_call_is_skipped() {
+    let store = test_store(|genesis| {
+        genesis.config.system_contracts.history_storage = None;
+    })
+    .await;
+    let block = build_and_add_block(&store).await;
+
+    assert_eq!(
+        block_hash_stored(&store, &block, DEFAULT_HISTORY_STORAGE_ADDRESS),
+        None
+    );
+    // The other system calls are unaffected
+    assert_eq!(
+        beacon_root_stored(&store, &block, DEFAULT_BEACON_ROOTS_ADDRESS),
+        block.header.parent_beacon_block_root
+    );
+}
+
+#[tokio::test]
+async fn configured_extra_system_call_runs() {
+    let recorder = Address::from_low_u64_be(0xe0);
+    let store = test_store(|genesis| {
+        add_recorder(genesis, recorder);
+        genesis.config.system_contracts.extra = extra_calls(recorder, 0);
+    })
+    .await;
+    let block = build_and_add_block(&store).await;
+
+    // Called from the system address with the block number as calldata
+    assert_eq!(
+        read_slot(&store, &block, recorder, 0),
+        Some(H256::from_low_u64_be(block.header.number))
+    );
+    assert_eq!(
+        read_slot(&store, &block, recorder, 1),
+        Some(H256::from(SYSTEM_ADDRESS))
+    );
+}
+
+#[tokio::test]
+async fn extra_system_call_waits_for_its_activation_time() {
+    let recorder = Address::from_low_u64_be(0xe0);
+    let store = test_store(|genesis| {
+        add_recorder(genesis, recorder);
+        genesis.config.system_contracts.extra = extra_calls(recorder, u64::MAX);
+    })
+    .await;
+    let block = build_and_add_block(&store).await;
+
+    assert_eq!(read_slot(&store, &block, recorder, 0), None);
+    assert_eq!(read_slot(&store, &block, recorder, 1), None);
+}
+
+/// Deploys a contract that stores its first calldata word in slot 0 and its
+/// caller in slot 1.
+fn add_recorder(genesis: &mut Genesis, address: Address) {
+    // PUSH1 0 CALLDATALOAD PUSH1 0 SSTORE CALLER PUSH1 1 SSTORE STOP
+    let code = [
+        0x60, 0x00, 0x35, 0x60, 0x00, 0x55, 0x33, 0x60, 0x01, 0x55, 0x00,
+    ];
+    genesis.alloc.insert(
+        address,
+        GenesisAccount {
+            code: Bytes::copy_from_slice(&code),
+            storage: Default::default(),
+            balance: U256::zero(),
+            nonce: 1,
+        },
+    );
+}
+
+fn extra_calls(address: Address, activation_time: u64) -> ExtraSystemCalls {
+    ExtraSystemCalls::try_from(vec![ExtraSystemCallConfig {
+        address,
+        activation_time,
+        input: SystemCallInput::BlockNumber,
+    }])
+    .unwrap()
+}
+
+/// Reads the EIP-4788 root stored for `block`'s timestamp, if any.
+fn beacon_root_stored(store: &Store, block: &Block, address: Address) -> Option<H256> {
+    let root_slot = block.header.timestamp % HISTORY_BUFFER_LENGTH + HISTORY_BUFFER_LENGTH;
+    read_slot(store, block, address, root_slot)
+}
+
+/// Reads the EIP-2935 parent hash stored for `block`, if any.
+fn block_hash_stored(store: &Store, block: &Block, address: Address) -> Option<H256> {
+    let slot = (block.header.number - 1) % HISTORY_BUFFER_LENGTH;
+    read_slot(store, block, address, slot)
+}
+
+fn read_slot(store: &Store, block: &Block, address: Address, slot: u64) -> Option<H256> {
+    store
+        .get_storage_at_root(
+            block.header.state_root,
+            address,
+            H256::from_low_u64_be(slot),
+        )
+        .unwrap()
+        .filter(|value| !value.is_zero())
+        .map(|value| H256(value.to_big_endian()))
+}
+
+async fn build_and_add_block(store: &Store) -> Block {
+    let parent = store.get_block_header(0).unwrap().unwrap();
+    let args = BuildPayloadArgs {
+        parent: parent.hash(),
+        timestamp: parent.timestamp + 12,
+        fee_recipient: H160::random(),
+        random: H256::random(),
+        withdrawals: Some(Vec::new()),
+        beacon_root: Some(H256::random()),
+        slot_number: None,
+        version: 1,
+        elasticity_multiplier: ELASTICITY_MULTIPLIER,
+        gas_ceil: DEFAULT_BUILDER_GAS_CEIL,
+    };
+
+    let blockchain = Blockchain::default_with_store(store.clone());
+    let block = create_payload(&args, store, Bytes::new()).unwrap();
+    let block = blockchain.build_payload(block).unwrap().payload;
+    blockchain.add_block(block.clone()).unwrap();
+    block
+}
+
+async fn test_store(customize: impl FnOnce(&mut Genesis)) -> Store {
+    let mut genesis = test_genesis();
+    customize(&mut genesis);
+
+    store_with_genesis(genesis).await
+}
@@ -0,0 +1,69 @@
+use clap::Parser as _;
+use ethrex::{
+    cli::Options,
+    initializers::{get_network, init_account_filter, init_store},
+};
+use ethrex_common::Address;
+use ethrex_storage::Store;
+use std::path::PathBuf;
+
+fn genesis_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../fixtures/genesis/kurtosis.json")
+}
+
+fn parse_options(extra_args: &[&str]) -> Options {
+    let genesis_path = genesis_path();
+    let args = [
+        "ethrex",
+        "--datadir",
+        "memory",
+        "--network",
+        genesis_path.to_str().expect("genesis path is valid UTF-8"),
+    ];
+    Options::try_parse_from(args.iter().chain(extra_args)).expect("parse options")
+}
+
+/// Initializes the store as the node does at startup
+async fn init_node_store(opts: &Options) -> Store {
+    let genesis = get_network(opts).get_genesis().expect("load genesis");
+    let store = init_store(&opts.datadir, genesis)
+        .await
+        .expect("init store");
+    init_account_filter(opts, &store)
+        .await
+        .expect("init account filter");
+    store
+}
+
+fn fresh_accounts() -> impl Iterator<Item = Address> {
+    (0..100).map(|i| Address::from_low_u64_be(0xf4e5_0000 + i))
+}
+
+#[tokio::test]
+async fn account_filter_is_off_by_default() {
+    let opts = parse_options(&[]);
+    assert!(!opts.account_filter);
+
+    let store = init_node_store(&opts).await;
+    assert!(fresh_accounts().all(|address| store.account_may_exist(address)));
+}
+
+#[tokio::test]
+async fn account_filter_is_built_at_startup() {
+    let opts = parse_options(&["--account-filter"]);
+    assert!(opts.account_filter);
+    let genesis = get_network(&opts).get_genesis().expect("load genesis");
+
+    let store = init_node_store(&opts).await;
+    assert!(
+        genesis
+            .alloc
+            .keys()
+            .all(|address| store.account_may_exist(*address))
+    );
+    // Absent accounts are ruled out but for false positives
+    let ruled_out = fresh_accounts()
+        .filter(|address| !store.account_may_exist(*address))
+        .count();
+    assert!(ruled_out > 90);
+}
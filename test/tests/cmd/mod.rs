@@ -1 +1,2 @@
 mod decode_tests;
+mod initializers_tests;
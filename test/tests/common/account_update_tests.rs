@@ -0,0 +1,301 @@
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256,
+    types::{Account, AccountInfo, AccountUpdate, Code, diff_states, merge_updates},
+};
+use proptest::{collection::btree_map, collection::vec, option, prelude::*};
+use rustc_hash::FxHashMap;
+use std::collections::BTreeMap;
+
+// ==================== State model ====================
+
+/// Minimal state that account updates are applied to, with the same semantics as the
+/// state trie: removed accounts are deleted, zero-valued slots are deleted.
+#[derive(Default, Clone, Debug, PartialEq)]
+struct World {
+    accounts: BTreeMap<Address, (AccountInfo, BTreeMap<H256, U256>)>,
+    codes: BTreeMap<H256, Code>,
+}
+
+impl World {
+    fn apply(&mut self, updates: &[AccountUpdate]) {
+        for update in updates {
+            if update.removed {
+                self.accounts.remove(&update.address);
+                continue;
+            }
+            let (info, storage) = self.accounts.entry(update.address).or_default();
+            if update.removed_storage {
+                storage.clear();
+            }
+            if let Some(new_info) = &update.info {
+                *info = new_info.clone();
+                if let Some(code) = &update.code {
+                    self.codes.insert(new_info.code_hash, code.clone());
+                }
+            }
+            for (key, value) in &update.added_storage {
+                if value.is_zero() {
+                    storage.remove(key);
+                } else {
+                    storage.insert(*key, *value);
+                }
+            }
+        }
+    }
+
+    fn from_accounts(accounts: &FxHashMap<Address, Account>) -> Self {
+        let mut world = World::default();
+        for (address, account) in accounts {
+            let storage = account
+                .storage
+                .iter()
+                .filter(|(_, value)| !value.is_zero())
+                .map(|(key, value)| (*key, *value))
+                .collect();
+            world
+                .accounts
+                .insert(*address, (account.info.clone(), storage));
+            // Empty code is never written to the state
+            if !account.code.bytecode.is_empty() {
+                world
+                    .codes
+                    .insert(account.info.code_hash, account.code.clone());
+            }
+        }
+        world
+    }
+
+    /// Code of every existing account, which must be known to the state.
+    fn account_codes(&self) -> Vec<Option<&Code>> {
+        self.accounts
+            .values()
+            .map(|(info, _)| self.codes.get(&info.code_hash))
+            .collect()
+    }
+}
+
+fn address(n: u64) -> Address {
+    Address::from_low_u64_be(n)
+}
+
+fn key(n: u64) -> H256 {
+    H256::from_low_u64_be(n)
+}
+
+fn code(n: u8) -> Code {
+    if n == 0 {
+        Code::default()
+    } else {
+        Code::from_bytecode(Bytes::from(vec![0x60, n, 0x00]))
+    }
+}
+
+fn info(nonce: u64, balance: u64, code_index: u8) -> AccountInfo {
+    AccountInfo {
+        code_hash: code(code_index).hash,
+        balance: U256::from(balance),
+        nonce,
+    }
+}
+
+fn storage_update(address: u64, slots: &[(u64, u64)]) -> AccountUpdate {
+    AccountUpdate {
+        added_storage: slots
+            .iter()
+            .map(|(slot, value)| (key(*slot), U256::from(*value)))
+            .collect(),
+        ..AccountUpdate::new(self::address(address))
+    }
+}
+
+/// Every account exists, with code and storage, so removals matter.
+fn initial_world() -> World {
+    let mut world = World::default();
+    let updates: Vec<AccountUpdate> = (0..3)
+        .map(|n| AccountUpdate {
+            info: Some(info(1, 100, 1)),
+            code: Some(code(1)),
+            ..storage_update(n, &[(0, 1), (1, 2)])
+        })
+        .collect();
+    world.apply(&updates);
+    world
+}
+
+// ==================== Strategies ====================
+
+/// Updates shaped like the ones LEVM emits: the code comes along whenever the info
+/// points to non-empty code.
+fn update_strategy() -> impl Strategy<Value = AccountUpdate> {
+    (
+        0..3u64,
+        prop::bool::weighted(0.15),
+        prop::bool::weighted(0.2),
+        option::of((0..3u64, 0..3u64, 0..3u8)),
+        btree_map(0..4u64, 0..3u64, 0..3),
+    )
+        .prop_map(|(address, removed, removed_storage, new_info, slots)| {
+            let slots: Vec<(u64, u64)> = slots.into_iter().collect();
+            let mut update = storage_update(address, &slots);
+            update.removed = removed;
+            update.removed_storage = removed_storage;
+            if let Some((nonce, balance, code_index)) = new_info {
+                update.info = Some(info(nonce, balance, code_index));
+                update.code = (code_index != 0).then(|| code(code_index));
+            }
+            update
+        })
+}
+
+fn accounts_strategy() -> impl Strategy<Value = FxHashMap<Address, Account>> {
+    btree_map(
+        0..4u64,
+        (0..3u64, 0..3u64, 0..3u8, btree_map(0..4u64, 1..3u64, 0..3)),
+        0..4,
+    )
+    .prop_map(|accounts| {
+        accounts
+            .into_iter()
+            .map(|(n, (nonce, balance, code_index, slots))| {
+                let storage = slots
+                    .into_iter()
+                    .map(|(slot, value)| (key(slot), U256::from(value)))
+                    .collect();
+                let account = Account::new(U256::from(balance), code(code_index), nonce, storage);
+                (address(n), account)
+            })
+            .collect()
+    })
+}
+
+// ==================== Properties ====================
+
+proptest! {
+    #[test]
+    fn proptest_merged_updates_match_sequential_application(
+        batches in vec(vec(update_strategy(), 0..4), 0..6)
+    ) {
+        let mut sequential = initial_world();
+        for batch in &batches {
+            sequential.apply(batch);
+        }
+        let mut merged = initial_world();
+        merged.apply(&merge_updates(batches));
+
+        prop_assert_eq!(&sequential.accounts, &merged.accounts);
+        prop_assert_eq!(sequential.account_codes(), merged.account_codes());
+    }
+
+    #[test]
+    fn proptest_merge_updates_keeps_first_appearance_order(
+        batches in vec(vec(update_strategy(), 0..4), 0..6)
+    ) {
+        let mut expected: Vec<Address> = Vec::new();
+        for update in batches.iter().flatten() {
+            if !expected.contains(&update.address) {
+                expected.push(update.address);
+            }
+        }
+        let merged: Vec<Address> = merge_updates(batches).iter().map(|u| u.address).collect();
+        prop_assert_eq!(merged, expected);
+    }
+
+    #[test]
+    fn proptest_diff_states_turns_before_into_after(
+        before in accounts_strategy(),
+        after in accounts_strategy(),
+    ) {
+        let mut world = World::from_accounts(&before);
+        world.apply(&diff_states(&before, &after));
+        let expected = World::from_accounts(&after);
+
+        prop_assert_eq!(&world.accounts, &expected.accounts);
+        prop_assert_eq!(world.account_codes(), expected.account_codes());
+    }
+}
+
+// ==================== Merge semantics ====================
+
+#[test]
+fn merge_removal_discards_earlier_changes() {
+    let mut update = AccountUpdate {
+        info: Some(info(2, 5, 1)),
+        code: Some(code(1)),
+        ..storage_update(0, &[(0, 7)])
+    };
+    update.merge(AccountUpdate::removed(address(0)));
+    assert_eq!(update, AccountUpdate::removed(address(0)));
+}
+
+#[test]
+fn merge_recreated_account_clears_storage() {
+    let mut update = AccountUpdate::removed(address(0));
+    update.merge(storage_update(0, &[(3, 1)]));
+
+    assert!(!update.removed);
+    assert!(update.removed_storage);
+    assert_eq!(update.info, Some(AccountInfo::default()));
+    assert_eq!(
+        update.added_storage,
+        FxHashMap::from_iter([(key(3), U256::one())])
+    );
+}
+
+#[test]
+fn merge_storage_cleared_then_written_drops_earlier_writes() {
+    let mut update = storage_update(0, &[(0, 7), (1, 8)]);
+    update.merge(AccountUpdate {
+        removed_storage: true,
+        ..storage_update(0, &[(1, 9)])
+    });
+
+    assert!(update.removed_storage);
+    assert_eq!(
+        update.added_storage,
+        FxHashMap::from_iter([(key(1), U256::from(9))])
+    );
+}
+
+#[test]
+fn merge_drops_code_superseded_by_new_code_hash() {
+    let mut update = AccountUpdate {
+        info: Some(info(1, 0, 1)),
+        code: Some(code(1)),
+        ..AccountUpdate::new(address(0))
+    };
+    update.merge(AccountUpdate {
+        info: Some(info(1, 0, 2)),
+        ..AccountUpdate::new(address(0))
+    });
+    assert_eq!(update.code, None);
+
+    let mut update = AccountUpdate {
+        info: Some(info(1, 0, 1)),
+        code: Some(code(1)),
+        ..AccountUpdate::new(address(0))
+    };
+    update.merge(AccountUpdate {
+        info: Some(info(2, 0, 1)),
+        ..AccountUpdate::new(address(0))
+    });
+    assert_eq!(update.code, Some(code(1)));
+}
+
+#[test]
+fn diff_states_skips_unchanged_accounts_and_sorts_by_address() {
+    let account = Account::new(U256::one(), code(1), 1, FxHashMap::default());
+    let before: FxHashMap<Address, Account> =
+        [(address(2), account.clone()), (address(1), account.clone())]
+            .into_iter()
+            .collect();
+    let mut after = before.clone();
+    after.remove(&address(1));
+    after.insert(address(0), account);
+
+    let updated: Vec<(Address, bool)> = diff_states(&before, &after)
+        .iter()
+        .map(|update| (update.address, update.removed))
+        .collect();
+    assert_eq!(updated, vec![(address(0), false), (address(1), true)]);
+}
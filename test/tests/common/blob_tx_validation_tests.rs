@@ -0,0 +1,290 @@
+#[cfg(feature = "c-kzg")]
+use ethrex_common::types::{
+    BYTES_PER_BLOB, BlobsBundle, BlobsBundleError, CELLS_PER_EXT_BLOB,
+    blobs_bundle::blob_from_bytes,
+};
+use ethrex_common::{
+    H256, U256,
+    types::{
+        BlockHeader, ChainConfig, EIP4844Transaction, FeeForecaster, Fork, GAS_PER_BLOB,
+        blobs::{
+            BlobTxValidationError, MAX_BLOB_COUNT_TX, next_blob_base_fee, validate_blob_tx,
+            validate_blob_versioned_hashes, validate_max_fee_per_blob_gas,
+        },
+    },
+};
+
+/// Versioned hash of the commitment to the zero blob (EIP-4844 test vectors).
+#[cfg(feature = "c-kzg")]
+const ZERO_BLOB_VERSIONED_HASH: &str =
+    "010657f37554c781402a22917dee2f75def7ab966d7b770905398eba3c444014";
+
+fn hash_with_version(version: u8) -> H256 {
+    let mut hash = H256::repeat_byte(0xab);
+    hash.0[0] = version;
+    hash
+}
+
+fn cancun_config() -> ChainConfig {
+    ChainConfig {
+        shanghai_time: Some(0),
+        cancun_time: Some(0),
+        ..Default::default()
+    }
+}
+
+/// Timestamp of the block built on top of [`parent_header_for_excess`].
+const CHILD_TIMESTAMP: u64 = 24;
+
+/// Parent header whose child has the given excess blob gas under the Cancun schedule.
+fn parent_header_for_excess(excess_blob_gas: u64) -> BlockHeader {
+    let target_blob_gas = 3 * GAS_PER_BLOB as u64;
+    BlockHeader {
+        timestamp: 12,
+        excess_blob_gas: Some(excess_blob_gas + target_blob_gas),
+        blob_gas_used: Some(0),
+        ..Default::default()
+    }
+}
+
+fn blob_tx(blob_versioned_hashes: Vec<H256>, max_fee_per_blob_gas: u64) -> EIP4844Transaction {
+    EIP4844Transaction {
+        max_fee_per_blob_gas: max_fee_per_blob_gas.into(),
+        blob_versioned_hashes,
+        ..Default::default()
+    }
+}
+
+// ==================== Blob base fee ====================
+
+#[test]
+fn next_blob_base_fee_matches_eip4844_vectors() {
+    let config = cancun_config();
+    for (excess_blob_gas, expected_fee) in
+        [(0, 1), (2314057, 1), (2314058, 2), (10 * 1024 * 1024, 23)]
+    {
+        let parent = parent_header_for_excess(excess_blob_gas);
+        assert_eq!(
+            next_blob_base_fee(&parent, CHILD_TIMESTAMP, &config),
+            Some(U256::from(expected_fee)),
+            "excess blob gas {excess_blob_gas}"
+        );
+    }
+}
+
+#[test]
+fn next_blob_base_fee_is_zero_before_cancun() {
+    let config = ChainConfig {
+        shanghai_time: Some(0),
+        ..Default::default()
+    };
+    let parent = parent_header_for_excess(10 * 1024 * 1024);
+    assert_eq!(next_blob_base_fee(&parent, CHILD_TIMESTAMP, &config), None);
+}
+
+#[test]
+fn next_blob_base_fee_uses_the_child_fork() {
+    // Cancun activates with the child block, so its blob fee is already charged
+    let config = ChainConfig {
+        shanghai_time: Some(0),
+        cancun_time: Some(CHILD_TIMESTAMP),
+        ..Default::default()
+    };
+    let parent = parent_header_for_excess(0);
+    assert_eq!(
+        next_blob_base_fee(&parent, CHILD_TIMESTAMP, &config),
+        Some(U256::one())
+    );
+    assert_eq!(
+        FeeForecaster::new(config).next_blob_base_fee(&parent),
+        next_blob_base_fee(&parent, CHILD_TIMESTAMP, &config)
+    );
+}
+
+// ==================== Versioned hashes ====================
+
+#[test]
+fn versioned_hashes_rejected_before_cancun() {
+    assert_eq!(
+        validate_blob_versioned_hashes(&[hash_with_version(1)], Fork::Shanghai, 6),
+        Err(BlobTxValidationError::PreFork)
+    );
+}
+
+#[test]
+fn versioned_hashes_rejects_zero_blobs() {
+    assert_eq!(
+        validate_blob_versioned_hashes(&[], Fork::Cancun, 6),
+        Err(BlobTxValidationError::ZeroBlobs)
+    );
+}
+
+#[test]
+fn versioned_hashes_rejects_unknown_version() {
+    let hashes = [hash_with_version(1), hash_with_version(0)];
+    assert_eq!(
+        validate_blob_versioned_hashes(&hashes, Fork::Cancun, 6),
+        Err(BlobTxValidationError::InvalidVersionedHash {
+            index: 1,
+            version: 0
+        })
+    );
+}
+
+#[test]
+fn versioned_hashes_respects_per_block_max() {
+    let hashes = vec![hash_with_version(1); 7];
+    assert_eq!(
+        validate_blob_versioned_hashes(&hashes, Fork::Cancun, 6),
+        Err(BlobTxValidationError::BlobCountExceeded {
+            max_blob_count: 6,
+            actual_blob_count: 7
+        })
+    );
+    assert!(validate_blob_versioned_hashes(&hashes, Fork::Prague, 9).is_ok());
+}
+
+#[test]
+fn versioned_hashes_respects_per_tx_max_from_osaka() {
+    let hashes = vec![hash_with_version(1); MAX_BLOB_COUNT_TX + 1];
+    assert_eq!(
+        validate_blob_versioned_hashes(&hashes, Fork::Osaka, 9),
+        Err(BlobTxValidationError::BlobCountExceeded {
+            max_blob_count: MAX_BLOB_COUNT_TX,
+            actual_blob_count: MAX_BLOB_COUNT_TX + 1
+        })
+    );
+}
+
+// ==================== Fees ====================
+
+#[test]
+fn max_fee_per_blob_gas_must_cover_base_fee() {
+    assert!(validate_max_fee_per_blob_gas(U256::from(23), U256::from(23)).is_ok());
+    assert_eq!(
+        validate_max_fee_per_blob_gas(U256::from(22), U256::from(23)),
+        Err(BlobTxValidationError::InsufficientMaxFeePerBlobGas {
+            base_fee_per_blob_gas: U256::from(23),
+            tx_max_fee_per_blob_gas: U256::from(22),
+        })
+    );
+}
+
+#[test]
+fn validate_blob_tx_checks_fee_against_next_block() {
+    let config = cancun_config();
+    let parent = parent_header_for_excess(10 * 1024 * 1024);
+    let hashes = vec![hash_with_version(1)];
+
+    assert!(
+        validate_blob_tx(
+            &blob_tx(hashes.clone(), 23),
+            &parent,
+            CHILD_TIMESTAMP,
+            &config
+        )
+        .is_ok()
+    );
+    let err =
+        validate_blob_tx(&blob_tx(hashes, 22), &parent, CHILD_TIMESTAMP, &config).unwrap_err();
+    assert!(matches!(
+        err,
+        BlobTxValidationError::InsufficientMaxFeePerBlobGas { .. }
+    ));
+    assert!(!err.is_invalid_sidecar());
+}
+
+// ==================== Blobs bundle ====================
+
+#[cfg(feature = "c-kzg")]
+fn zero_blob_bundle() -> BlobsBundle {
+    // The commitment and proof of the zero blob are both the point at infinity
+    let mut point_at_infinity = [0u8; 48];
+    point_at_infinity[0] = 0xc0;
+    BlobsBundle {
+        blobs: vec![[0; BYTES_PER_BLOB]],
+        commitments: vec![point_at_infinity],
+        proofs: vec![point_at_infinity],
+        version: 0,
+    }
+}
+
+#[cfg(feature = "c-kzg")]
+fn zero_blob_versioned_hash() -> H256 {
+    H256::from_slice(&hex::decode(ZERO_BLOB_VERSIONED_HASH).unwrap())
+}
+
+#[test]
+#[cfg(feature = "c-kzg")]
+fn bundle_accepts_zero_blob_vector() {
+    let bundle = zero_blob_bundle();
+    assert_eq!(
+        bundle.generate_versioned_hashes(),
+        vec![zero_blob_versioned_hash()]
+    );
+    let tx = blob_tx(vec![zero_blob_versioned_hash()], 1);
+    assert!(bundle.validate(&tx, Fork::Prague).is_ok());
+}
+
+#[test]
+#[cfg(feature = "c-kzg")]
+fn bundle_accepts_cell_proofs_from_osaka() {
+    let blobs = vec![blob_from_bytes("Hello, world!".as_bytes().into()).unwrap()];
+    let bundle = BlobsBundle::create_from_blobs(&blobs, Some(1)).unwrap();
+    assert_eq!(bundle.proofs.len(), CELLS_PER_EXT_BLOB);
+    let tx = blob_tx(bundle.generate_versioned_hashes(), 1);
+
+    assert!(bundle.validate(&tx, Fork::Osaka).is_ok());
+    assert!(matches!(
+        bundle.validate(&tx, Fork::Prague),
+        Err(BlobsBundleError::InvalidBlobVersionForFork)
+    ));
+}
+
+#[test]
+#[cfg(feature = "c-kzg")]
+fn bundle_rejects_version_0_from_osaka() {
+    let tx = blob_tx(vec![zero_blob_versioned_hash()], 1);
+    assert!(matches!(
+        zero_blob_bundle().validate(&tx, Fork::Osaka),
+        Err(BlobsBundleError::InvalidBlobVersionForFork)
+    ));
+}
+
+#[test]
+#[cfg(feature = "c-kzg")]
+fn bundle_rejects_length_mismatch() {
+    let tx = blob_tx(
+        vec![zero_blob_versioned_hash(), zero_blob_versioned_hash()],
+        1,
+    );
+    assert!(matches!(
+        zero_blob_bundle().validate(&tx, Fork::Prague),
+        Err(BlobsBundleError::BlobsBundleWrongLen)
+    ));
+}
+
+#[test]
+#[cfg(feature = "c-kzg")]
+fn bundle_rejects_commitment_not_matching_versioned_hash() {
+    let tx = blob_tx(vec![hash_with_version(1)], 1);
+    assert!(matches!(
+        zero_blob_bundle().validate(&tx, Fork::Prague),
+        Err(BlobsBundleError::BlobVersionedHashesError)
+    ));
+}
+
+#[test]
+#[cfg(feature = "c-kzg")]
+fn bundle_rejects_invalid_proof() {
+    // A valid commitment for a different blob than the one in the bundle
+    let blobs = vec![blob_from_bytes("Hello, world!".as_bytes().into()).unwrap()];
+    let mut bundle = BlobsBundle::create_from_blobs(&blobs, None).unwrap();
+    bundle.blobs = vec![[0; BYTES_PER_BLOB]];
+    let tx = blob_tx(bundle.generate_versioned_hashes(), 1);
+
+    assert!(matches!(
+        bundle.validate(&tx, Fork::Prague),
+        Err(BlobsBundleError::BlobToCommitmentAndProofError | BlobsBundleError::Kzg(_))
+    ));
+}
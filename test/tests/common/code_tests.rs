@@ -0,0 +1,48 @@
+use bytes::Bytes;
+use ethrex_common::{H256, types::Code};
+
+fn jump_targets(bytecode: &[u8]) -> Vec<u32> {
+    Code::from_bytecode(Bytes::copy_from_slice(bytecode)).jump_targets
+}
+
+#[test]
+fn jumpdests_are_collected_in_order() {
+    // JUMPDEST, STOP, JUMPDEST
+    assert_eq!(jump_targets(&[0x5b, 0x00, 0x5b]), vec![0, 2]);
+}
+
+#[test]
+fn push_data_is_not_a_jumpdest() {
+    // PUSH1 0x5b, JUMPDEST
+    assert_eq!(jump_targets(&[0x60, 0x5b, 0x5b]), vec![2]);
+
+    // PUSH32 with every byte 0x5b, JUMPDEST
+    let mut bytecode = vec![0x7f];
+    bytecode.extend_from_slice(&[0x5b; 32]);
+    bytecode.push(0x5b);
+    assert_eq!(jump_targets(&bytecode), vec![33]);
+}
+
+#[test]
+fn truncated_push_data_is_not_a_jumpdest() {
+    // STOP, PUSH3 with a single 0x5b data byte before the end of the code
+    assert_eq!(jump_targets(&[0x00, 0x62, 0x5b]), Vec::<u32>::new());
+}
+
+#[test]
+fn push0_has_no_data() {
+    // PUSH0, JUMPDEST
+    assert_eq!(jump_targets(&[0x5f, 0x5b]), vec![1]);
+}
+
+#[test]
+fn unchecked_constructor_computes_same_jump_targets() {
+    let bytecode = Bytes::from_static(&[0x5b, 0x61, 0x5b, 0x5b, 0x5b, 0x56]);
+    let unchecked = Code::from_bytecode_unchecked(bytecode.clone(), H256::zero());
+
+    assert_eq!(unchecked.jump_targets, vec![0, 4]);
+    assert_eq!(
+        unchecked.jump_targets,
+        Code::from_bytecode(bytecode).jump_targets
+    );
+}
@@ -0,0 +1,616 @@
+//! EOF container validation, with vectors ported from the EOF validation
+//! tests of execution-spec-tests (`eof_tests/`) and evmone.
+
+use bytes::Bytes;
+use ethrex_common::types::{
+    Code,
+    eof::{ContainerKind, EofContainer, EofError},
+};
+
+const MINIMAL: &str = "ef0001 010004 0200010001 ff0000 00 00800000 00";
+
+fn hex(vector: &str) -> Bytes {
+    Bytes::from(hex::decode(vector.replace(' ', "")).unwrap())
+}
+
+fn validate(bytecode: &Bytes, kind: ContainerKind) -> Result<(), EofError> {
+    EofContainer::from_bytes(bytecode, kind).map(|_| ())
+}
+
+fn validate_runtime(vector: &str) -> Result<(), EofError> {
+    validate(&hex(vector), ContainerKind::Runtime)
+}
+
+/// Builds a container from `(inputs, outputs, max_stack_increase, code)` sections.
+fn container(
+    sections: &[(u8, u8, u16, &str)],
+    subcontainers: &[&Bytes],
+    data: &str,
+    data_size: u16,
+) -> Bytes {
+    let codes: Vec<Vec<u8>> = sections
+        .iter()
+        .map(|(_, _, _, code)| hex(code).to_vec())
+        .collect();
+    let mut bytes = vec![0xef, 0x00, 0x01, 0x01];
+    bytes.extend_from_slice(&(sections.len() as u16 * 4).to_be_bytes());
+    bytes.push(0x02);
+    bytes.extend_from_slice(&(sections.len() as u16).to_be_bytes());
+    for code in &codes {
+        bytes.extend_from_slice(&(code.len() as u16).to_be_bytes());
+    }
+    if !subcontainers.is_empty() {
+        bytes.push(0x03);
+        bytes.extend_from_slice(&(subcontainers.len() as u16).to_be_bytes());
+        for subcontainer in subcontainers {
+            bytes.extend_from_slice(&(subcontainer.len() as u32).to_be_bytes());
+        }
+    }
+    bytes.push(0xff);
+    bytes.extend_from_slice(&data_size.to_be_bytes());
+    bytes.push(0x00);
+    for (inputs, outputs, max_stack_increase, _) in sections {
+        bytes.extend_from_slice(&[*inputs, *outputs]);
+        bytes.extend_from_slice(&max_stack_increase.to_be_bytes());
+    }
+    for code in codes {
+        bytes.extend(code);
+    }
+    for subcontainer in subcontainers {
+        bytes.extend_from_slice(subcontainer);
+    }
+    bytes.extend_from_slice(&hex(data));
+    Bytes::from(bytes)
+}
+
+/// Container with a single non-returning code section.
+fn single_section(max_stack_increase: u16, code: &str) -> Bytes {
+    container(&[(0, 0x80, max_stack_increase, code)], &[], "", 0)
+}
+
+/// Initcode deploying its only subcontainer with RETURNCONTRACT.
+fn initcode(runtime: &Bytes) -> Bytes {
+    container(&[(0, 0x80, 2, "5f5f ee00")], &[runtime], "", 0)
+}
+
+#[test]
+fn valid_headers() {
+    for vector in [
+        MINIMAL,
+        // INVALID as the only instruction
+        "ef0001 010004 0200010001 ff0000 00 00800000 fe",
+        // Data section
+        "ef0001 010004 0200010001 ff0002 00 00800000 fe aabb",
+        // Two code sections
+        "ef0001 010008 02000200040001 ff0000 00 00800000 00000000 e3000100 e4",
+    ] {
+        assert_eq!(validate_runtime(vector), Ok(()), "{vector}");
+    }
+}
+
+#[test]
+fn invalid_headers() {
+    for (vector, error) in [
+        ("", EofError::IncompleteHeader),
+        ("ef", EofError::IncompleteHeader),
+        (
+            "ef01 01 010004 0200010001 ff0000 00 00800000 00",
+            EofError::InvalidMagic,
+        ),
+        (
+            "ef00 00 010004 0200010001 ff0000 00 00800000 00",
+            EofError::InvalidVersion,
+        ),
+        (
+            "ef00 02 010004 0200010001 ff0000 00 00800000 00",
+            EofError::InvalidVersion,
+        ),
+        (
+            "ef0001 010004 0200010001 ff0000",
+            EofError::IncompleteHeader,
+        ),
+        ("ef0001 010004 0200010001 ff00", EofError::IncompleteHeader),
+        (
+            "ef0001 020004 0200010001 ff0000 00 00800000 00",
+            EofError::MissingTypeHeader,
+        ),
+        (
+            "ef0001 010004 ff0001 0001 ff0000 00 00800000 00",
+            EofError::MissingCodeHeader,
+        ),
+        (
+            "ef0001 010004 0200010001 fe0000 00 00800000 00",
+            EofError::MissingDataHeader,
+        ),
+        (
+            "ef0001 010004 0200010001 ff0000 01 00800000 00",
+            EofError::MissingTerminator,
+        ),
+        (
+            "ef0001 010004 020000 ff0000 00 00800000 00",
+            EofError::ZeroSectionSize,
+        ),
+        (
+            "ef0001 010004 0200010000 ff0000 00 00800000",
+            EofError::ZeroSectionSize,
+        ),
+        (
+            "ef0001 010008 0200010001 ff0000 00 00800000 00",
+            EofError::InvalidTypeSectionSize,
+        ),
+        (
+            "ef0001 010000 0200010001 ff0000 00 00800000 00",
+            EofError::InvalidTypeSectionSize,
+        ),
+        (
+            "ef0001 010004 020401 ff0000 00",
+            EofError::TooManyCodeSections,
+        ),
+        (
+            "ef0001 010004 0200010001 030000 ff0000 00 00800000 00",
+            EofError::ZeroSectionSize,
+        ),
+        (
+            "ef0001 010004 0200010001 030101 ff0000 00",
+            EofError::TooManyContainerSections,
+        ),
+        // Missing or trailing body bytes
+        (
+            "ef0001 010004 0200010001 ff0000 00 00800000",
+            EofError::InvalidSectionBodiesSize,
+        ),
+        (
+            "ef0001 010004 0200010001 ff0000 00 008000",
+            EofError::InvalidSectionBodiesSize,
+        ),
+        (
+            "ef0001 010004 0200010001 ff0000 00 00800000 00 aa",
+            EofError::InvalidSectionBodiesSize,
+        ),
+        (
+            "ef0001 010004 0200010001 ff0001 00 00800000 00 aabb",
+            EofError::InvalidSectionBodiesSize,
+        ),
+        // Types of the first section
+        (
+            "ef0001 010004 0200010001 ff0000 00 01800000 00",
+            EofError::InvalidFirstSectionType,
+        ),
+        (
+            "ef0001 010004 0200010001 ff0000 00 00000000 00",
+            EofError::InvalidFirstSectionType,
+        ),
+        (
+            "ef0001 010004 0200010001 ff0000 00 00800400 00",
+            EofError::MaxStackIncreaseAboveLimit,
+        ),
+    ] {
+        assert_eq!(validate_runtime(vector), Err(error), "{vector}");
+    }
+}
+
+#[test]
+fn top_level_data_must_not_be_truncated() {
+    let bytecode = hex("ef0001 010004 0200010001 ff0002 00 00800000 fe aa");
+    let container = EofContainer::parse(&bytecode).unwrap();
+    assert!(container.is_data_truncated());
+    assert_eq!(container.data.as_ref(), [0xaa]);
+    assert_eq!(container.data_size, 2);
+    assert_eq!(
+        container.validate(ContainerKind::Runtime),
+        Err(EofError::TruncatedData)
+    );
+}
+
+#[test]
+fn parses_sections() {
+    let runtime = hex(MINIMAL);
+    let bytecode = container(
+        &[(0, 0x80, 2, "5f5f ee00"), (1, 0, 1, "50 e4")],
+        &[&runtime],
+        "0102",
+        2,
+    );
+    let container = EofContainer::parse(&bytecode).unwrap();
+    assert_eq!(container.types.len(), 2);
+    assert_eq!(container.types[1].inputs, 1);
+    assert!(container.types[1].is_returning());
+    assert!(!container.types[0].is_returning());
+    assert_eq!(container.code_sections[1].as_ref(), [0x50, 0xe4]);
+    assert_eq!(container.container_sections, vec![runtime]);
+    assert_eq!(container.data.as_ref(), [0x01, 0x02]);
+}
+
+#[test]
+fn deprecated_opcodes_are_rejected() {
+    // CALLCODE, SELFDESTRUCT, JUMP, JUMPI, PC, CREATE, CREATE2, CODESIZE,
+    // CODECOPY, EXTCODESIZE, EXTCODECOPY, EXTCODEHASH, GAS, CALL,
+    // DELEGATECALL, STATICCALL, and undefined opcodes
+    for opcode in [
+        0xf2, 0xff, 0x56, 0x57, 0x58, 0xf0, 0xf5, 0x38, 0x39, 0x3b, 0x3c, 0x3f, 0x5a, 0xf1, 0xf4,
+        0xfa, 0x0c, 0x21, 0xef,
+    ] {
+        let code = format!("{opcode:02x}00");
+        assert_eq!(
+            validate(&single_section(0, &code), ContainerKind::Runtime),
+            Err(EofError::UndefinedInstruction { opcode, offset: 0 }),
+            "{opcode:#04x}"
+        );
+    }
+    // JUMPDEST is kept as NOP
+    assert_eq!(
+        validate(&single_section(0, "5b00"), ContainerKind::Runtime),
+        Ok(())
+    );
+}
+
+#[test]
+fn truncated_immediates_are_rejected() {
+    for code in [
+        "60",
+        "6100",
+        "7f00",
+        "e000",
+        "e1",
+        "e200",
+        "e201000000",
+        "e300",
+        "d100",
+    ] {
+        assert_eq!(
+            validate(&single_section(0, code), ContainerKind::Runtime),
+            Err(EofError::TruncatedImmediate(0)),
+            "{code}"
+        );
+    }
+}
+
+#[test]
+fn code_must_end_with_terminating_instruction() {
+    assert_eq!(
+        validate(&single_section(1, "5f"), ContainerKind::Runtime),
+        Err(EofError::NoTerminatingInstruction(0))
+    );
+    // RJUMPI falls through past the end
+    assert_eq!(
+        validate(&single_section(1, "5f e1fffc"), ContainerKind::Runtime),
+        Err(EofError::NoTerminatingInstruction(0))
+    );
+    for code in ["00", "fe", "5f5f f3", "5f5f fd", "e0fffd"] {
+        let max_stack_increase = if code.starts_with("5f") { 2 } else { 0 };
+        assert_eq!(
+            validate(
+                &single_section(max_stack_increase, code),
+                ContainerKind::Runtime
+            ),
+            Ok(()),
+            "{code}"
+        );
+    }
+}
+
+#[test]
+fn rjump_targets_are_validated() {
+    // RJUMP 0, RJUMPI forward, RJUMPV with two cases
+    for (max_stack_increase, code) in [
+        (0, "e00000 00"),
+        (1, "5f e10001 00 00"),
+        (1, "5f e201 0000 0001 00 00"),
+        // Backward jump to the start of a loop
+        (1, "5f e1fffc 00"),
+    ] {
+        assert_eq!(
+            validate(
+                &single_section(max_stack_increase, code),
+                ContainerKind::Runtime
+            ),
+            Ok(()),
+            "{code}"
+        );
+    }
+    for (max_stack_increase, code) in [
+        // Past the end
+        (0, "e00001 00"),
+        // Before the start
+        (0, "e0fffc 00"),
+        // Into PUSH1 data
+        (0, "e00001 6000 00"),
+        // Into its own immediate
+        (0, "e0ffff 00"),
+        // RJUMPV case into immediate data
+        (1, "5f e201 0000 fffe 00"),
+    ] {
+        let error = validate(
+            &single_section(max_stack_increase, code),
+            ContainerKind::Runtime,
+        )
+        .unwrap_err();
+        assert!(
+            matches!(error, EofError::InvalidRjumpDestination(_)),
+            "{code}: {error}"
+        );
+    }
+}
+
+#[test]
+fn stack_heights_are_validated() {
+    assert_eq!(
+        validate(&single_section(0, "50 00"), ContainerKind::Runtime),
+        Err(EofError::StackUnderflow(0))
+    );
+    assert_eq!(
+        validate(&single_section(1, "5f 01 00"), ContainerKind::Runtime),
+        Err(EofError::StackUnderflow(1))
+    );
+    // Declared max stack increase must match the computed one
+    assert_eq!(
+        validate(&single_section(0, "5f 00"), ContainerKind::Runtime),
+        Err(EofError::InvalidMaxStackIncrease(0))
+    );
+    assert_eq!(
+        validate(&single_section(2, "5f 00"), ContainerKind::Runtime),
+        Err(EofError::InvalidMaxStackIncrease(0))
+    );
+    // Backward jump with a different height
+    assert_eq!(
+        validate(&single_section(1, "5f e0fffc"), ContainerKind::Runtime),
+        Err(EofError::StackHeightMismatch(1))
+    );
+    // Code skipped by RJUMP
+    assert_eq!(
+        validate(&single_section(0, "e00001 00 00"), ContainerKind::Runtime),
+        Err(EofError::UnreachableInstructions(3))
+    );
+    // Forward branches merge into a range of heights
+    assert_eq!(
+        validate(
+            &single_section(2, "5f e10002 5f5f 00"),
+            ContainerKind::Runtime
+        ),
+        Ok(())
+    );
+    assert_eq!(
+        validate(
+            &single_section(1, "5f e10001 5f 50 00"),
+            ContainerKind::Runtime
+        ),
+        Err(EofError::StackUnderflow(5))
+    );
+    // DUPN, SWAPN and EXCHANGE read their depth from the immediate
+    assert_eq!(
+        validate(&single_section(3, "5f5f e601 00"), ContainerKind::Runtime),
+        Ok(())
+    );
+    assert_eq!(
+        validate(&single_section(2, "5f5f e701 00"), ContainerKind::Runtime),
+        Err(EofError::StackUnderflow(2))
+    );
+    assert_eq!(
+        validate(&single_section(3, "5f5f5f e800 00"), ContainerKind::Runtime),
+        Ok(())
+    );
+}
+
+#[test]
+fn stack_overflow_is_rejected() {
+    let code = format!("{}00", "5f".repeat(1024));
+    assert_eq!(
+        validate(&single_section(1023, &code), ContainerKind::Runtime),
+        Err(EofError::StackOverflow(1023))
+    );
+    assert_eq!(
+        validate(&single_section(1023, &code[2..]), ContainerKind::Runtime),
+        Ok(())
+    );
+}
+
+#[test]
+fn functions_are_validated() {
+    // CALLF into a returning section, RETF
+    let valid = container(&[(0, 0x80, 0, "e30001 00"), (0, 0, 0, "e4")], &[], "", 0);
+    assert_eq!(validate(&valid, ContainerKind::Runtime), Ok(()));
+
+    for (sections, error) in [
+        (
+            vec![(0, 0x80, 0, "e30002 00"), (0, 0, 0, "e4")],
+            EofError::InvalidCodeSectionIndex(0),
+        ),
+        (
+            vec![(0, 0x80, 0, "00"), (0, 0, 0, "e4")],
+            EofError::UnreachableCodeSections(1),
+        ),
+        (
+            vec![(0, 0x80, 0, "e30001 00"), (0, 0x80, 0, "00")],
+            EofError::CallfToNonReturning(0),
+        ),
+        // Declared returning but never returns
+        (
+            vec![(0, 0x80, 0, "e30001 00"), (0, 0, 0, "00")],
+            EofError::InvalidNonReturningFlag(1),
+        ),
+        // RETF in a non-returning section
+        (
+            vec![(0, 0x80, 0, "e4")],
+            EofError::InvalidNonReturningFlag(0),
+        ),
+        // RETF with the wrong number of outputs
+        (
+            vec![(0, 0x80, 1, "e30001 50 00"), (0, 1, 0, "e4")],
+            EofError::StackHeightMismatch(0),
+        ),
+        // Not enough inputs for CALLF
+        (
+            vec![(0, 0x80, 0, "e30001 00"), (1, 1, 0, "e4")],
+            EofError::StackUnderflow(0),
+        ),
+    ] {
+        let bytecode = container(&sections, &[], "", 0);
+        assert_eq!(validate(&bytecode, ContainerKind::Runtime), Err(error));
+    }
+}
+
+#[test]
+fn jumpf_is_validated() {
+    // JUMPF into a non-returning section
+    let bytecode = container(&[(0, 0x80, 0, "e50001"), (0, 0x80, 0, "00")], &[], "", 0);
+    assert_eq!(validate(&bytecode, ContainerKind::Runtime), Ok(()));
+
+    // Tail call into a returning section with the same outputs
+    let bytecode = container(
+        &[
+            (0, 0x80, 1, "5f e30001 50 00"),
+            (1, 1, 0, "e50002"),
+            (1, 1, 0, "e4"),
+        ],
+        &[],
+        "",
+        0,
+    );
+    assert_eq!(validate(&bytecode, ContainerKind::Runtime), Ok(()));
+
+    // Non-returning section jumping into a returning one
+    let bytecode = container(&[(0, 0x80, 0, "e50001"), (0, 0, 0, "e4")], &[], "", 0);
+    assert_eq!(
+        validate(&bytecode, ContainerKind::Runtime),
+        Err(EofError::JumpfDestinationIncompatibleOutputs(0))
+    );
+
+    // Returning section jumping into one with more outputs
+    let bytecode = container(
+        &[
+            (0, 0x80, 0, "e30001 00"),
+            (0, 0, 0, "e50002"),
+            (0, 1, 1, "5f e4"),
+        ],
+        &[],
+        "",
+        0,
+    );
+    assert_eq!(
+        validate(&bytecode, ContainerKind::Runtime),
+        Err(EofError::JumpfDestinationIncompatibleOutputs(0))
+    );
+}
+
+#[test]
+fn dataloadn_is_bounded_by_declared_data_size() {
+    let data = "00".repeat(32);
+    let bytecode = container(&[(0, 0x80, 1, "d10000 00")], &[], &data, 32);
+    assert_eq!(validate(&bytecode, ContainerKind::Runtime), Ok(()));
+
+    let bytecode = container(&[(0, 0x80, 1, "d10001 00")], &[], &data, 32);
+    assert_eq!(
+        validate(&bytecode, ContainerKind::Runtime),
+        Err(EofError::InvalidDataloadnIndex(0))
+    );
+}
+
+#[test]
+fn subcontainers_are_validated() {
+    let runtime = hex(MINIMAL);
+    let initcode = initcode(&runtime);
+    assert_eq!(validate(&initcode, ContainerKind::Initcode), Ok(()));
+    // RETURNCONTRACT is only allowed in initcode
+    assert_eq!(
+        validate(&initcode, ContainerKind::Runtime),
+        Err(EofError::IncompatibleContainerKind(2))
+    );
+    // STOP and RETURN are only allowed in runtime code
+    assert_eq!(
+        validate(&runtime, ContainerKind::Initcode),
+        Err(EofError::IncompatibleContainerKind(0))
+    );
+
+    // EOFCREATE of an initcode container
+    let factory = container(&[(0, 0x80, 4, "5f5f5f5f ec00 50 00")], &[&initcode], "", 0);
+    assert_eq!(validate(&factory, ContainerKind::Runtime), Ok(()));
+
+    // EOFCREATE of a runtime container
+    let bytecode = container(&[(0, 0x80, 4, "5f5f5f5f ec00 50 00")], &[&runtime], "", 0);
+    assert_eq!(
+        validate(&bytecode, ContainerKind::Runtime),
+        Err(EofError::InvalidSubcontainer {
+            index: 0,
+            error: Box::new(EofError::IncompatibleContainerKind(0)),
+        })
+    );
+
+    // Subcontainer index out of bounds
+    let bytecode = container(&[(0, 0x80, 4, "5f5f5f5f ec01 50 00")], &[&initcode], "", 0);
+    assert_eq!(
+        validate(&bytecode, ContainerKind::Runtime),
+        Err(EofError::InvalidContainerSectionIndex(4))
+    );
+
+    // Unreferenced subcontainer
+    let bytecode = container(&[(0, 0x80, 0, "00")], &[&runtime], "", 0);
+    assert_eq!(
+        validate(&bytecode, ContainerKind::Runtime),
+        Err(EofError::OrphanSubcontainer(0))
+    );
+
+    // Same subcontainer deployed and created
+    let bytecode = container(
+        &[(0, 0x80, 4, "5f5f5f5f ec00 50 5f5f ee00")],
+        &[&runtime],
+        "",
+        0,
+    );
+    assert_eq!(
+        validate(&bytecode, ContainerKind::Initcode),
+        Err(EofError::AmbiguousSubcontainer(0))
+    );
+}
+
+#[test]
+fn only_deployed_subcontainers_may_have_truncated_data() {
+    // Aux data is appended to the container returned by RETURNCONTRACT
+    let truncated = container(&[(0, 0x80, 0, "00")], &[], "", 2);
+    assert_eq!(
+        validate(&initcode(&truncated), ContainerKind::Initcode),
+        Ok(())
+    );
+
+    let truncated_initcode = container(&[(0, 0x80, 2, "5f5f ee00")], &[&hex(MINIMAL)], "", 2);
+    let bytecode = container(
+        &[(0, 0x80, 4, "5f5f5f5f ec00 50 00")],
+        &[&truncated_initcode],
+        "",
+        0,
+    );
+    assert_eq!(
+        validate(&bytecode, ContainerKind::Runtime),
+        Err(EofError::InvalidSubcontainer {
+            index: 0,
+            error: Box::new(EofError::TruncatedData),
+        })
+    );
+}
+
+#[test]
+fn code_caches_parsed_container() {
+    let code = Code::from_bytecode(hex(MINIMAL));
+    assert!(code.jump_targets.is_empty());
+    let container = code.parse_eof().unwrap().unwrap();
+    assert_eq!(container.code_sections[0].as_ref(), [0x00]);
+    assert!(std::ptr::eq(container, code.parse_eof().unwrap().unwrap()));
+
+    // The cache doesn't take part in comparisons
+    assert_eq!(code, Code::from_bytecode(hex(MINIMAL)));
+
+    let invalid = Code::from_bytecode(hex("ef0001 010004 0200010001 ff0000 00 00800000 5b"));
+    assert_eq!(
+        invalid.parse_eof(),
+        Some(Err(&EofError::NoTerminatingInstruction(0)))
+    );
+
+    // Legacy code, including code starting with 0xEF but not the EOF magic
+    let legacy = Code::from_bytecode(Bytes::from_static(&[0x5b, 0x00]));
+    assert!(legacy.parse_eof().is_none());
+    assert_eq!(legacy.jump_targets, vec![0]);
+    assert!(
+        Code::from_bytecode(Bytes::from_static(&[0xef, 0x01, 0x00]))
+            .parse_eof()
+            .is_none()
+    );
+}
@@ -0,0 +1,280 @@
+//! Decoding of the protocol events of `ethrex_common::types::events`.
+//!
+//! The deposit fixture follows the layout the beacon deposit contract emits: five
+//! `bytes` values with fixed sizes, amount and index in little endian.
+
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256,
+    types::{
+        Log, Receipt, TxType,
+        events::{
+            DEPOSIT_EVENT, KNOWN_EVENTS, L1_MESSAGE_EVENT, L1MessageEvent, L2_MESSAGE_EVENT,
+            L2MessageEvent, PRIVILEGED_TX_SENT_EVENT, PrivilegedTxSentEvent, ProtocolEvent,
+            WITHDRAWAL_INITIATED_EVENT, WithdrawalInitiatedEvent, decode_known_events,
+        },
+        requests::{Deposit, Requests},
+    },
+    utils::keccak,
+};
+
+const DEPOSIT_CONTRACT: Address = Address([0xdc; 20]);
+
+#[test]
+fn topics_are_the_hash_of_the_signatures() {
+    for event in KNOWN_EVENTS {
+        assert_eq!(event.topic, keccak(event.signature), "{}", event.name);
+        assert!(event.signature.starts_with(event.name));
+    }
+}
+
+#[test]
+fn deposit_receipt_is_decoded() {
+    let receipt = receipt(vec![deposit_log(32_000_000_000, 7)]);
+
+    let deposit = Deposit::from_log(&receipt.logs[0]).unwrap();
+    assert_eq!(deposit.pub_key, [0x11; 48]);
+    assert_eq!(deposit.withdrawal_credentials, [0x22; 32]);
+    assert_eq!(deposit.amount, 32_000_000_000);
+    assert_eq!(deposit.signature, [0x33; 96]);
+    assert_eq!(deposit.index, 7);
+
+    let Some(Requests::Deposit(deposits)) =
+        Requests::from_deposit_receipts(DEPOSIT_CONTRACT, &[receipt])
+    else {
+        panic!("deposit receipt should be parsed");
+    };
+    assert_eq!(deposits.len(), 1);
+    assert_eq!(deposits[0].index, 7);
+}
+
+#[test]
+fn deposit_with_wrong_layout_is_rejected() {
+    let mut log = deposit_log(1, 0);
+    log.data = log.data.slice(..575);
+    assert!(Deposit::from_log(&log).is_none());
+    // A malformed deposit fails the whole block's requests
+    assert!(Requests::from_deposit_receipts(DEPOSIT_CONTRACT, &[receipt(vec![log])]).is_none());
+}
+
+#[test]
+fn l1_message_is_decoded() {
+    let log = l1_message_log(address_word(0xaa));
+    assert_eq!(
+        L1MessageEvent::from_log(&log),
+        Some(L1MessageEvent {
+            from: Address::repeat_byte(0xaa),
+            data_hash: H256::repeat_byte(0xd4),
+            message_id: U256::from(9),
+        })
+    );
+}
+
+#[test]
+fn l2_message_is_decoded() {
+    let log = l2_message_log(&[0xca, 0xfe]);
+    assert_eq!(
+        L2MessageEvent::from_log(&log),
+        Some(L2MessageEvent {
+            dest_chain_id: U256::from(65_536_999),
+            from: Address::repeat_byte(0x01),
+            to: Address::repeat_byte(0x02),
+            value: U256::from(100),
+            gas_limit: U256::from(21_000),
+            tx_id: U256::from(3),
+            data: Bytes::from_static(&[0xca, 0xfe]),
+        })
+    );
+    let empty = L2MessageEvent::from_log(&l2_message_log(&[])).unwrap();
+    assert!(empty.data.is_empty());
+}
+
+#[test]
+fn withdrawal_and_privileged_tx_are_decoded() {
+    let withdrawal = Log {
+        address: Address::from_low_u64_be(0xffff),
+        topics: vec![
+            WITHDRAWAL_INITIATED_EVENT.topic,
+            address_word(0x0a),
+            address_word(0x0b),
+            word(5),
+        ],
+        data: Bytes::new(),
+    };
+    assert_eq!(
+        WithdrawalInitiatedEvent::from_log(&withdrawal),
+        Some(WithdrawalInitiatedEvent {
+            sender: Address::repeat_byte(0x0a),
+            receiver: Address::repeat_byte(0x0b),
+            amount: U256::from(5),
+        })
+    );
+
+    let mut data = Vec::new();
+    for value in [
+        address_word(0x01),
+        address_word(0x02),
+        word(4),
+        word(50),
+        word(90_000),
+    ] {
+        data.extend_from_slice(value.as_bytes());
+    }
+    data.extend_from_slice(dynamic_bytes(6 * 32, &[0xab]).as_slice());
+    let privileged = Log {
+        address: Address::repeat_byte(0xb1),
+        topics: vec![PRIVILEGED_TX_SENT_EVENT.topic, address_word(0x0c)],
+        data: Bytes::from(data),
+    };
+    let event = PrivilegedTxSentEvent::from_log(&privileged).unwrap();
+    assert_eq!(event.l1_from, Address::repeat_byte(0x0c));
+    assert_eq!(event.to, Address::repeat_byte(0x02));
+    assert_eq!(event.transaction_id, U256::from(4));
+    assert_eq!(event.gas_limit, U256::from(90_000));
+    assert_eq!(event.data, Bytes::from_static(&[0xab]));
+}
+
+#[test]
+fn near_misses_are_not_decoded() {
+    let valid = l1_message_log(address_word(0xaa));
+    assert!(L1_MESSAGE_EVENT.matches(&valid));
+
+    // Same topics under another event
+    assert!(L2_MESSAGE_EVENT.decode(&valid).is_none());
+    let mut wrong_topic = valid.clone();
+    wrong_topic.topics[0] = keccak("L1Message(address,bytes32)");
+    assert!(L1MessageEvent::from_log(&wrong_topic).is_none());
+
+    // Indexed parameters must match the topic count exactly
+    let mut extra_topic = valid.clone();
+    extra_topic.topics.push(H256::zero());
+    assert!(L1MessageEvent::from_log(&extra_topic).is_none());
+    let mut missing_topic = valid.clone();
+    missing_topic.topics.pop();
+    assert!(L1MessageEvent::from_log(&missing_topic).is_none());
+
+    // Addresses with dirty upper bytes are not valid ABI
+    let mut dirty = address_word(0xaa);
+    dirty.0[0] = 1;
+    assert!(L1MessageEvent::from_log(&l1_message_log(dirty)).is_none());
+
+    // Truncated data and out of bounds `bytes`
+    let l2_message = l2_message_log(&[0xca, 0xfe]);
+    let truncated = Log {
+        data: l2_message.data.slice(..6 * 32),
+        ..l2_message.clone()
+    };
+    assert!(L2MessageEvent::from_log(&truncated).is_none());
+    let mut data = l2_message.data.to_vec();
+    data[5 * 32..6 * 32].copy_from_slice(word(u64::MAX).as_bytes());
+    let bad_offset = Log {
+        data: Bytes::from(data),
+        ..l2_message
+    };
+    assert!(L2MessageEvent::from_log(&bad_offset).is_none());
+}
+
+#[test]
+fn known_events_of_a_receipt_are_decoded_in_order() {
+    let unrelated = Log {
+        address: Address::repeat_byte(0x99),
+        topics: vec![keccak("Transfer(address,address,uint256)")],
+        data: Bytes::new(),
+    };
+    let receipt = receipt(vec![
+        l2_message_log(&[]),
+        unrelated,
+        deposit_log(1, 0),
+        l1_message_log(address_word(0xaa)),
+    ]);
+
+    let events = decode_known_events(&receipt);
+    assert_eq!(events.len(), 3);
+    assert!(matches!(events[0].event, ProtocolEvent::L2Message(_)));
+    assert_eq!(events[0].log_index, 0);
+    assert!(matches!(events[1].event, ProtocolEvent::Deposit(_)));
+    assert_eq!(events[1].emitter, DEPOSIT_CONTRACT);
+    assert_eq!(events[1].log_index, 2);
+    assert!(matches!(events[2].event, ProtocolEvent::L1Message(_)));
+    assert_eq!(events[2].log_index, 3);
+}
+
+// ==================== Helpers ====================
+
+fn word(value: u64) -> H256 {
+    H256::from_low_u64_be(value)
+}
+
+fn address_word(byte: u8) -> H256 {
+    H256::from(Address::repeat_byte(byte))
+}
+
+fn receipt(logs: Vec<Log>) -> Receipt {
+    Receipt::new(TxType::EIP1559, true, 21_000, logs)
+}
+
+/// The offset word of `value`, then its length word and `value` padded to a whole word.
+fn dynamic_bytes(offset: u64, value: &[u8]) -> Vec<u8> {
+    let mut encoded = word(offset).as_bytes().to_vec();
+    encoded.extend_from_slice(word(value.len() as u64).as_bytes());
+    encoded.extend_from_slice(value);
+    encoded.resize(encoded.len().next_multiple_of(32), 0);
+    encoded
+}
+
+fn deposit_log(amount: u64, index: u64) -> Log {
+    let values: [&[u8]; 5] = [
+        &[0x11; 48],
+        &[0x22; 32],
+        &amount.to_le_bytes(),
+        &[0x33; 96],
+        &index.to_le_bytes(),
+    ];
+    let mut head = Vec::new();
+    let mut tail = Vec::new();
+    for value in values {
+        let offset = (5 * 32 + tail.len()) as u64;
+        head.extend_from_slice(word(offset).as_bytes());
+        // `dynamic_bytes` starts with the offset word, which goes in the head
+        tail.extend_from_slice(&dynamic_bytes(offset, value)[32..]);
+    }
+    head.extend(tail);
+    assert_eq!(head.len(), 576);
+    Log {
+        address: DEPOSIT_CONTRACT,
+        topics: vec![DEPOSIT_EVENT.topic],
+        data: Bytes::from(head),
+    }
+}
+
+fn l1_message_log(from: H256) -> Log {
+    Log {
+        address: Address::from_low_u64_be(0xfffe),
+        topics: vec![
+            L1_MESSAGE_EVENT.topic,
+            from,
+            H256::repeat_byte(0xd4),
+            word(9),
+        ],
+        data: Bytes::new(),
+    }
+}
+
+fn l2_message_log(calldata: &[u8]) -> Log {
+    let mut data = Vec::new();
+    for value in [
+        address_word(0x01),
+        address_word(0x02),
+        word(100),
+        word(21_000),
+        word(3),
+    ] {
+        data.extend_from_slice(value.as_bytes());
+    }
+    data.extend(dynamic_bytes(6 * 32, calldata));
+    Log {
+        address: Address::from_low_u64_be(0xfffe),
+        topics: vec![L2_MESSAGE_EVENT.topic, word(65_536_999)],
+        data: Bytes::from(data),
+    }
+}
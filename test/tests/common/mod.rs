@@ -1,6 +1,12 @@
+mod account_update_tests;
 mod base64_tests;
+mod blob_tx_validation_tests;
 #[cfg(feature = "c-kzg")]
 mod blobs_bundle_tests;
+mod code_tests;
+mod eof_tests;
+mod event_tests;
+mod post_execution_validation_tests;
 mod rkyv_utils_tests;
 mod serde_utils_tests;
 mod utils_tests;
@@ -0,0 +1,225 @@
+use bytes::Bytes;
+use ethrex_common::{
+    Address, Bloom, CommitmentMismatch, H256, InvalidBlockError, U256,
+    types::{
+        BlockHeader, ChainConfig, Log, Receipt, TxType,
+        block_access_list::{AccountChanges, BalanceChange, BlockAccessList},
+        compute_logs_bloom, compute_receipts_root,
+        requests::{EncodedRequests, Requests, compute_requests_hash},
+    },
+    validate_post_execution_commitments,
+};
+use hex_literal::hex;
+
+/// EIP-7685: sha256 of nothing, as no request type has data.
+const EMPTY_REQUESTS_HASH: H256 = H256(hex!(
+    "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+));
+
+/// EIP-7928: keccak256(rlp([])).
+const EMPTY_BAL_HASH: H256 = H256(hex!(
+    "1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347"
+));
+
+const GAS_USED: u64 = 21_000;
+
+fn amsterdam_config() -> ChainConfig {
+    ChainConfig {
+        prague_time: Some(0),
+        amsterdam_time: Some(0),
+        ..Default::default()
+    }
+}
+
+fn receipts() -> Vec<Receipt> {
+    let log = Log {
+        address: Address::repeat_byte(0x42),
+        topics: vec![H256::repeat_byte(0x01)],
+        data: Bytes::from_static(b"data"),
+    };
+    vec![Receipt::new(TxType::EIP1559, true, GAS_USED, vec![log])]
+}
+
+fn block_access_list() -> BlockAccessList {
+    BlockAccessList::from_accounts(vec![
+        AccountChanges::new(Address::repeat_byte(0x42))
+            .with_balance_changes(vec![BalanceChange::new(1, U256::from(10))]),
+    ])
+}
+
+/// A header committing to [`receipts`], no requests and [`block_access_list`].
+fn header() -> BlockHeader {
+    let receipts = receipts();
+    BlockHeader {
+        gas_used: GAS_USED,
+        receipts_root: compute_receipts_root(&receipts),
+        logs_bloom: compute_logs_bloom(&receipts),
+        requests_hash: Some(EMPTY_REQUESTS_HASH),
+        block_access_list_hash: Some(block_access_list().compute_hash()),
+        ..Default::default()
+    }
+}
+
+fn validate(header: &BlockHeader, bal: Option<&BlockAccessList>) -> Result<(), InvalidBlockError> {
+    validate_post_execution_commitments(
+        header,
+        &amsterdam_config(),
+        GAS_USED,
+        &receipts(),
+        &[],
+        bal,
+        1,
+    )
+}
+
+fn mismatch(result: Result<(), InvalidBlockError>) -> CommitmentMismatch {
+    match result {
+        Err(InvalidBlockError::CommitmentMismatch(mismatch)) => mismatch,
+        other => panic!("expected a commitment mismatch, got {other:?}"),
+    }
+}
+
+#[test]
+fn empty_requests_hash_vector() {
+    assert_eq!(compute_requests_hash(&[]), EMPTY_REQUESTS_HASH);
+    // Request types without data don't contribute to the hash
+    let empty_requests: Vec<EncodedRequests> = [
+        Requests::Deposit(vec![]),
+        Requests::Withdrawal(vec![]),
+        Requests::Consolidation(vec![]),
+    ]
+    .iter()
+    .map(Requests::encode)
+    .collect();
+    assert_eq!(compute_requests_hash(&empty_requests), EMPTY_REQUESTS_HASH);
+}
+
+#[test]
+fn empty_bal_hash_vector() {
+    assert_eq!(BlockAccessList::new().compute_hash(), EMPTY_BAL_HASH);
+}
+
+#[test]
+fn matching_commitments_are_valid() {
+    assert!(validate(&header(), Some(&block_access_list())).is_ok());
+}
+
+#[test]
+fn empty_block_commitments_are_valid() {
+    let header = BlockHeader {
+        receipts_root: compute_receipts_root(&[]),
+        requests_hash: Some(EMPTY_REQUESTS_HASH),
+        block_access_list_hash: Some(EMPTY_BAL_HASH),
+        ..Default::default()
+    };
+    let result = validate_post_execution_commitments(
+        &header,
+        &amsterdam_config(),
+        0,
+        &[],
+        &[],
+        Some(&BlockAccessList::new()),
+        0,
+    );
+    assert!(result.is_ok());
+}
+
+#[test]
+fn gas_used_mismatch() {
+    let header = BlockHeader {
+        gas_used: GAS_USED + 1,
+        ..header()
+    };
+    let mismatch = mismatch(validate(&header, None));
+    assert_eq!(mismatch.field(), "gas_used");
+    assert_eq!(
+        mismatch,
+        CommitmentMismatch::GasUsed {
+            expected: GAS_USED + 1,
+            actual: GAS_USED,
+        }
+    );
+}
+
+#[test]
+fn receipts_root_mismatch() {
+    let header = BlockHeader {
+        receipts_root: H256::zero(),
+        ..header()
+    };
+    let mismatch = mismatch(validate(&header, None));
+    assert_eq!(mismatch.field(), "receipts_root");
+    assert_eq!(
+        mismatch,
+        CommitmentMismatch::ReceiptsRoot {
+            expected: H256::zero(),
+            actual: compute_receipts_root(&receipts()),
+        }
+    );
+}
+
+#[test]
+fn logs_bloom_mismatch() {
+    let header = BlockHeader {
+        logs_bloom: Bloom::zero(),
+        ..header()
+    };
+    let mismatch = mismatch(validate(&header, None));
+    assert_eq!(mismatch.field(), "logs_bloom");
+    assert_eq!(
+        mismatch,
+        CommitmentMismatch::LogsBloom {
+            expected: Bloom::zero(),
+            actual: compute_logs_bloom(&receipts()),
+        }
+    );
+}
+
+#[test]
+fn requests_hash_mismatch() {
+    let header = BlockHeader {
+        requests_hash: None,
+        ..header()
+    };
+    let mismatch = mismatch(validate(&header, None));
+    assert_eq!(mismatch.field(), "requests_hash");
+    assert_eq!(
+        mismatch,
+        CommitmentMismatch::RequestsHash {
+            expected: None,
+            actual: EMPTY_REQUESTS_HASH,
+        }
+    );
+}
+
+#[test]
+fn bal_hash_mismatch() {
+    let header = BlockHeader {
+        block_access_list_hash: Some(EMPTY_BAL_HASH),
+        ..header()
+    };
+    let mismatch = mismatch(validate(&header, Some(&block_access_list())));
+    assert_eq!(mismatch.field(), "bal_hash");
+    assert_eq!(
+        mismatch,
+        CommitmentMismatch::BlockAccessListHash {
+            expected: Some(EMPTY_BAL_HASH),
+            actual: block_access_list().compute_hash(),
+        }
+    );
+    // Without a recorded BAL there is nothing to check against
+    assert!(validate(&header, None).is_ok());
+}
+
+#[test]
+fn mismatch_reports_field_and_values() {
+    let header = BlockHeader {
+        gas_used: 1,
+        ..header()
+    };
+    let message = validate(&header, None).unwrap_err().to_string();
+    assert_eq!(
+        message,
+        "gas_used mismatch: header has 1, execution used 21000"
+    );
+}
@@ -0,0 +1,174 @@
+//! Tests for the batch gas limit tuning from proving feedback.
+//!
+//! Key behaviors tested:
+//! - Batches converge to the gas the prover can prove in the target proving time
+//! - Proving times within the hysteresis band don't resize batches
+//! - A single report at most halves or doubles the limit, which stays within its bounds
+//! - Batches don't grow while the prover has a backlog
+//! - Reports without gas or proving time are ignored
+
+use ethrex_l2::sequencer::batch_size_controller::{
+    BatchSizeController, BatchSizeControllerConfig, DecisionReason,
+};
+use ethrex_l2_common::prover::ProvingFeedback;
+
+const MIN_BATCH_GAS: u64 = 1_000_000;
+const MAX_BATCH_GAS: u64 = 100_000_000;
+/// 80% of a 10s proving interval.
+const TARGET_PROVING_TIME_MS: u64 = 8_000;
+
+// ==================== Helpers ====================
+
+fn config() -> BatchSizeControllerConfig {
+    BatchSizeControllerConfig {
+        min_batch_gas: MIN_BATCH_GAS,
+        max_batch_gas: MAX_BATCH_GAS,
+        proving_interval_ms: 10_000,
+        target_proving_time_percentage: 80,
+        hysteresis_percentage: 10,
+    }
+}
+
+fn feedback(gas_used: u64, proving_time_ms: u64, queue_depth: u64) -> ProvingFeedback {
+    ProvingFeedback {
+        gas_used,
+        cycles: None,
+        proving_time_ms,
+        queue_depth,
+    }
+}
+
+/// Feedback of a prover proving `gas_per_ms` gas per millisecond a batch filled up to the limit.
+fn full_batch(controller: &BatchSizeController, gas_per_ms: u64) -> ProvingFeedback {
+    let gas_used = controller.batch_gas();
+    feedback(gas_used, gas_used / gas_per_ms, 0)
+}
+
+// ==================== Tests ====================
+
+#[test]
+fn converges_to_prover_capacity() {
+    let mut controller = BatchSizeController::new(config(), 30_000_000);
+    assert_eq!(config().target_proving_time_ms(), TARGET_PROVING_TIME_MS);
+
+    // The prover proves 1000 gas per ms, so 8M gas in the target proving time
+    let first = controller.observe(full_batch(&controller, 1_000));
+    assert_eq!(first.reason, DecisionReason::SlowProving);
+    assert_eq!(first.previous_batch_gas, 30_000_000);
+    assert_eq!(first.batch_gas, 15_000_000);
+    assert_eq!(first.feedback.proving_time_ms, 30_000);
+    assert_eq!(first.target_proving_time_ms, TARGET_PROVING_TIME_MS);
+
+    let second = controller.observe(full_batch(&controller, 1_000));
+    assert_eq!(second.reason, DecisionReason::SlowProving);
+    assert_eq!(second.batch_gas, 8_000_000);
+
+    for _ in 0..5 {
+        let decision = controller.observe(full_batch(&controller, 1_000));
+        assert_eq!(decision.reason, DecisionReason::WithinBand);
+        assert!(!decision.changed());
+    }
+    assert_eq!(controller.batch_gas(), 8_000_000);
+
+    // The prover gets twice as fast
+    let mut reasons = vec![];
+    while reasons.len() < 4 {
+        reasons.push(controller.observe(full_batch(&controller, 2_000)).reason);
+    }
+    assert_eq!(
+        reasons,
+        [
+            DecisionReason::FastProving,
+            DecisionReason::WithinBand,
+            DecisionReason::WithinBand,
+            DecisionReason::WithinBand,
+        ]
+    );
+    assert_eq!(controller.batch_gas(), 16_000_000);
+}
+
+#[test]
+fn proving_time_within_band_keeps_batch_gas() {
+    let mut controller = BatchSizeController::new(config(), 8_000_000);
+
+    // Noise of up to 10% around the target, on batches of the current size
+    for proving_time_ms in [7_200, 8_800, 7_600, 8_400, 8_000, 7_201, 8_799] {
+        let decision = controller.observe(feedback(8_000_000, proving_time_ms, 0));
+        assert_eq!(decision.reason, DecisionReason::WithinBand);
+        assert_eq!(decision.batch_gas, 8_000_000);
+    }
+
+    let decision = controller.observe(feedback(8_000_000, 8_801, 0));
+    assert_eq!(decision.reason, DecisionReason::SlowProving);
+    assert!(decision.batch_gas < 8_000_000);
+}
+
+#[test]
+fn adjustments_are_limited_per_step_and_by_bounds() {
+    let mut controller = BatchSizeController::new(config(), 8_000_000);
+
+    // 100 times slower than the target only halves the limit
+    let decision = controller.observe(feedback(8_000_000, 800_000, 0));
+    assert_eq!(decision.batch_gas, 4_000_000);
+
+    // 100 times faster only doubles it
+    let decision = controller.observe(feedback(4_000_000, 80, 0));
+    assert_eq!(decision.batch_gas, 8_000_000);
+
+    let mut controller = BatchSizeController::new(config(), 1_500_000);
+    let decision = controller.observe(feedback(1_500_000, 800_000, 0));
+    assert_eq!(decision.batch_gas, MIN_BATCH_GAS);
+
+    let mut controller = BatchSizeController::new(config(), 80_000_000);
+    let decision = controller.observe(feedback(80_000_000, 80, 0));
+    assert_eq!(decision.batch_gas, MAX_BATCH_GAS);
+
+    // The initial limit is clamped too
+    let controller = BatchSizeController::new(config(), u64::MAX);
+    assert_eq!(controller.batch_gas(), MAX_BATCH_GAS);
+}
+
+#[test]
+fn backlog_prevents_growth() {
+    let mut controller = BatchSizeController::new(config(), 8_000_000);
+
+    let decision = controller.observe(feedback(8_000_000, 2_000, 3));
+    assert_eq!(decision.reason, DecisionReason::Backlog);
+    assert!(!decision.changed());
+
+    // A slow prover with a backlog still gets smaller batches
+    let decision = controller.observe(feedback(8_000_000, 16_000, 3));
+    assert_eq!(decision.reason, DecisionReason::SlowProving);
+    assert_eq!(decision.batch_gas, 4_000_000);
+
+    // Once the backlog is gone, batches grow again
+    let decision = controller.observe(feedback(4_000_000, 2_000, 0));
+    assert_eq!(decision.reason, DecisionReason::FastProving);
+    assert_eq!(decision.batch_gas, 8_000_000);
+}
+
+#[test]
+fn partial_batches_dont_move_the_limit_the_wrong_way() {
+    let mut controller = BatchSizeController::new(config(), 8_000_000);
+
+    // A small batch proven slowly never grows the limit
+    let decision = controller.observe(feedback(1_000_000, 9_000, 0));
+    assert_eq!(decision.reason, DecisionReason::SlowProving);
+    assert_eq!(decision.batch_gas, 4_000_000);
+
+    // A small batch proven quickly never shrinks it
+    let decision = controller.observe(feedback(1_000_000, 2_000, 0));
+    assert_eq!(decision.reason, DecisionReason::FastProving);
+    assert_eq!(decision.batch_gas, 4_000_000);
+}
+
+#[test]
+fn feedback_without_data_is_ignored() {
+    let mut controller = BatchSizeController::new(config(), 8_000_000);
+
+    for feedback in [feedback(0, 5_000, 0), feedback(8_000_000, 0, 0)] {
+        let decision = controller.observe(feedback);
+        assert_eq!(decision.reason, DecisionReason::NoData);
+        assert_eq!(decision.batch_gas, 8_000_000);
+    }
+}
@@ -1,5 +1,7 @@
+mod batch_size_controller;
 #[cfg(feature = "l2")]
 mod integration_tests;
+mod proof_submission;
 mod sdk;
 #[cfg(feature = "l2")]
 mod shared_bridge;
@@ -0,0 +1,403 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use ethrex_common::H256;
+use ethrex_l2::sequencer::proof_submission::{
+    L1Fees, SubmissionPolicy, SubmissionStep, VerifyTxClient, VerifyTxReceipt,
+};
+use ethrex_l2_common::proof_submission::ProofSubmission;
+use ethrex_rpc::clients::EthClientError;
+
+const GWEI: u64 = 1_000_000_000;
+const VERIFY_GAS: u64 = 300_000;
+
+#[derive(Debug, Clone, Copy)]
+struct SentTx {
+    hash: H256,
+    nonce: u64,
+    max_fee_per_gas: u64,
+    max_priority_fee_per_gas: u64,
+}
+
+#[derive(Default)]
+struct MockL1State {
+    fees: Option<L1Fees>,
+    next_nonce: u64,
+    /// Number of upcoming sends that fail.
+    failing_sends: u64,
+    /// Sent transactions are dropped from the mempool and never included.
+    drop_txs: bool,
+    /// Included transactions revert.
+    revert_txs: bool,
+    sent: Vec<SentTx>,
+    dropped: Vec<H256>,
+    receipts: HashMap<H256, VerifyTxReceipt>,
+}
+
+/// L1 with controllable fees, failing sends and dropped transactions.
+#[derive(Default)]
+struct MockL1(RefCell<MockL1State>);
+
+impl MockL1 {
+    fn with_fees(base_fee_per_gas: u64, max_priority_fee_per_gas: u64) -> Self {
+        let l1 = Self::default();
+        l1.set_fees(base_fee_per_gas, max_priority_fee_per_gas);
+        l1
+    }
+
+    fn set_fees(&self, base_fee_per_gas: u64, max_priority_fee_per_gas: u64) {
+        self.0.borrow_mut().fees = Some(L1Fees {
+            base_fee_per_gas,
+            max_priority_fee_per_gas,
+        });
+    }
+
+    fn sent(&self) -> Vec<SentTx> {
+        self.0.borrow().sent.clone()
+    }
+
+    /// Includes the given transaction in a block.
+    fn include(&self, tx: SentTx) {
+        let mut state = self.0.borrow_mut();
+        let fees = state.fees.unwrap_or(L1Fees {
+            base_fee_per_gas: 0,
+            max_priority_fee_per_gas: 0,
+        });
+        let effective_gas_price = tx
+            .max_fee_per_gas
+            .min(fees.base_fee_per_gas + tx.max_priority_fee_per_gas);
+        let succeeded = !state.revert_txs;
+        state.next_nonce = state.next_nonce.max(tx.nonce + 1);
+        state.receipts.insert(
+            tx.hash,
+            VerifyTxReceipt {
+                tx_hash: tx.hash,
+                succeeded,
+                gas_used: VERIFY_GAS,
+                effective_gas_price,
+            },
+        );
+    }
+
+    /// Includes the latest sent transaction, unless it was dropped.
+    fn mine(&self) {
+        let latest = self.0.borrow().sent.last().copied();
+        if let Some(tx) = latest
+            && !self.0.borrow().dropped.contains(&tx.hash)
+        {
+            self.include(tx);
+        }
+    }
+}
+
+impl VerifyTxClient for MockL1 {
+    async fn current_fees(&self) -> Result<L1Fees, EthClientError> {
+        self.0
+            .borrow()
+            .fees
+            .ok_or(EthClientError::Custom("fees unavailable".to_string()))
+    }
+
+    async fn send_verify_tx(
+        &self,
+        nonce: Option<u64>,
+        max_fee_per_gas: u64,
+        max_priority_fee_per_gas: u64,
+    ) -> Result<(H256, u64), EthClientError> {
+        let mut state = self.0.borrow_mut();
+        if state.failing_sends > 0 {
+            state.failing_sends -= 1;
+            return Err(EthClientError::Custom("connection refused".to_string()));
+        }
+        let nonce = nonce.unwrap_or(state.next_nonce);
+        if nonce < state.next_nonce {
+            return Err(EthClientError::Custom("nonce too low".to_string()));
+        }
+        let hash = H256::from_low_u64_be(state.sent.len() as u64 + 1);
+        state.sent.push(SentTx {
+            hash,
+            nonce,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        });
+        if state.drop_txs {
+            state.dropped.push(hash);
+        }
+        Ok((hash, nonce))
+    }
+
+    async fn get_receipt(&self, tx_hash: H256) -> Result<Option<VerifyTxReceipt>, EthClientError> {
+        Ok(self.0.borrow().receipts.get(&tx_hash).copied())
+    }
+}
+
+fn policy() -> SubmissionPolicy {
+    SubmissionPolicy {
+        max_base_fee_per_gas: Some(50 * GWEI),
+        max_priority_fee_per_gas: Some(5 * GWEI),
+        deadline: 3_600,
+        stuck_timeout: 120,
+        fee_bump_percentage: 20,
+        maximum_allowed_max_fee_per_gas: 500 * GWEI,
+        backoff_factor: 2,
+        min_retry_delay: 8,
+        max_retry_delay: 64,
+    }
+}
+
+#[tokio::test]
+async fn defers_submission_during_fee_spike() {
+    let policy = policy();
+    let l1 = MockL1::with_fees(200 * GWEI, 2 * GWEI);
+    let mut submission = ProofSubmission::new(1_000);
+
+    let step = policy.step(&l1, &mut submission, 1_000).await.unwrap();
+    assert!(matches!(step, SubmissionStep::Deferred(_)));
+    // A priority fee spike also defers
+    l1.set_fees(20 * GWEI, 10 * GWEI);
+    let step = policy.step(&l1, &mut submission, 1_300).await.unwrap();
+    assert!(matches!(step, SubmissionStep::Deferred(_)));
+    assert!(l1.sent().is_empty());
+    assert_eq!(submission.deferred_time(1_400), 400);
+
+    // Fees go back down
+    l1.set_fees(20 * GWEI, 2 * GWEI);
+    let step = policy.step(&l1, &mut submission, 1_600).await.unwrap();
+    assert!(matches!(
+        step,
+        SubmissionStep::Pending {
+            replaced: false,
+            ..
+        }
+    ));
+    assert_eq!(submission.deferred_secs, 600);
+    assert_eq!(submission.deferred_since, None);
+
+    let sent = l1.sent();
+    assert_eq!(sent.len(), 1);
+    assert_eq!(sent[0].max_priority_fee_per_gas, 2 * GWEI);
+    assert_eq!(sent[0].max_fee_per_gas, 42 * GWEI);
+
+    l1.mine();
+    let SubmissionStep::Included(receipt) = policy.step(&l1, &mut submission, 1_612).await.unwrap()
+    else {
+        panic!("verify transaction should be included");
+    };
+    assert_eq!(receipt.tx_hash, sent[0].hash);
+    assert_eq!(
+        receipt.cost(),
+        u128::from(VERIFY_GAS) * u128::from(22 * GWEI)
+    );
+    assert_eq!(submission.pending_tx, None);
+}
+
+#[tokio::test]
+async fn submits_regardless_of_fees_after_deadline() {
+    let policy = policy();
+    let l1 = MockL1::with_fees(200 * GWEI, 10 * GWEI);
+    let mut submission = ProofSubmission::new(1_000);
+
+    let step = policy.step(&l1, &mut submission, 4_599).await.unwrap();
+    assert!(matches!(step, SubmissionStep::Deferred(_)));
+
+    let step = policy.step(&l1, &mut submission, 4_600).await.unwrap();
+    assert!(matches!(step, SubmissionStep::Pending { .. }));
+    assert_eq!(submission.deferred_secs, 1);
+    let sent = l1.sent();
+    assert_eq!(sent[0].max_fee_per_gas, 410 * GWEI);
+
+    // The absolute fee cap still holds
+    l1.set_fees(400 * GWEI, 10 * GWEI);
+    let mut submission = ProofSubmission::new(1_000);
+    policy.step(&l1, &mut submission, 5_000).await.unwrap();
+    let sent = l1.sent();
+    assert_eq!(sent[1].max_fee_per_gas, 500 * GWEI);
+}
+
+#[tokio::test]
+async fn backs_off_after_failed_sends() {
+    let policy = policy();
+    let l1 = MockL1::with_fees(20 * GWEI, 2 * GWEI);
+    l1.0.borrow_mut().failing_sends = 2;
+    let mut submission = ProofSubmission::new(1_000);
+
+    let SubmissionStep::Failed {
+        next_attempt_at, ..
+    } = policy.step(&l1, &mut submission, 1_000).await.unwrap()
+    else {
+        panic!("send should fail");
+    };
+    // 2^1 clamped to the 8 seconds minimum, minus up to half of it as jitter
+    assert!((1_004..=1_008).contains(&next_attempt_at));
+    assert_eq!(submission.failed_attempts, 1);
+
+    // No retries until the backoff elapses
+    let step = policy.step(&l1, &mut submission, 1_003).await.unwrap();
+    assert!(matches!(step, SubmissionStep::BackingOff { .. }));
+
+    let SubmissionStep::Failed {
+        next_attempt_at, ..
+    } = policy.step(&l1, &mut submission, 1_010).await.unwrap()
+    else {
+        panic!("send should fail");
+    };
+    assert!((1_014..=1_018).contains(&next_attempt_at));
+    assert_eq!(submission.failed_attempts, 2);
+
+    let step = policy.step(&l1, &mut submission, 1_020).await.unwrap();
+    assert!(matches!(step, SubmissionStep::Pending { .. }));
+    l1.mine();
+    let step = policy.step(&l1, &mut submission, 1_032).await.unwrap();
+    assert!(matches!(step, SubmissionStep::Included(_)));
+    assert_eq!(submission.failed_attempts, 0);
+}
+
+#[test]
+fn backoff_grows_exponentially_with_jitter() {
+    let policy = policy();
+    for _ in 0..100 {
+        assert!((4..=8).contains(&policy.backoff(1)));
+        assert!((8..=16).contains(&policy.backoff(4)));
+        assert!((16..=32).contains(&policy.backoff(5)));
+        assert!((32..=64).contains(&policy.backoff(6)));
+        assert!((32..=64).contains(&policy.backoff(100)));
+    }
+}
+
+#[tokio::test]
+async fn replaces_dropped_transaction_with_higher_fees() {
+    let policy = policy();
+    let l1 = MockL1::with_fees(20 * GWEI, 2 * GWEI);
+    l1.0.borrow_mut().drop_txs = true;
+    let mut submission = ProofSubmission::new(1_000);
+
+    policy.step(&l1, &mut submission, 1_000).await.unwrap();
+    l1.mine();
+
+    // Not stuck yet
+    let step = policy.step(&l1, &mut submission, 1_119).await.unwrap();
+    assert!(matches!(
+        step,
+        SubmissionStep::Pending {
+            replaced: false,
+            ..
+        }
+    ));
+    assert_eq!(l1.sent().len(), 1);
+
+    l1.0.borrow_mut().drop_txs = false;
+    let step = policy.step(&l1, &mut submission, 1_120).await.unwrap();
+    assert!(matches!(
+        step,
+        SubmissionStep::Pending { replaced: true, .. }
+    ));
+
+    let sent = l1.sent();
+    assert_eq!(sent.len(), 2);
+    assert_eq!(sent[1].nonce, sent[0].nonce);
+    assert_eq!(sent[1].max_fee_per_gas, sent[0].max_fee_per_gas * 120 / 100);
+    assert_eq!(
+        sent[1].max_priority_fee_per_gas,
+        sent[0].max_priority_fee_per_gas * 120 / 100
+    );
+
+    l1.mine();
+    let SubmissionStep::Included(receipt) = policy.step(&l1, &mut submission, 1_132).await.unwrap()
+    else {
+        panic!("replacement should be included");
+    };
+    assert_eq!(receipt.tx_hash, sent[1].hash);
+}
+
+#[tokio::test]
+async fn detects_inclusion_of_replaced_transaction() {
+    let policy = policy();
+    let l1 = MockL1::with_fees(20 * GWEI, 2 * GWEI);
+    let mut submission = ProofSubmission::new(1_000);
+
+    policy.step(&l1, &mut submission, 1_000).await.unwrap();
+    let step = policy.step(&l1, &mut submission, 1_200).await.unwrap();
+    assert!(matches!(
+        step,
+        SubmissionStep::Pending { replaced: true, .. }
+    ));
+
+    // The original transaction is the one that gets included
+    let original = l1.sent()[0];
+    l1.include(original);
+    let SubmissionStep::Included(receipt) = policy.step(&l1, &mut submission, 1_212).await.unwrap()
+    else {
+        panic!("original transaction should be included");
+    };
+    assert_eq!(receipt.tx_hash, original.hash);
+}
+
+#[tokio::test]
+async fn does_not_bump_stuck_transaction_during_fee_spike() {
+    let policy = policy();
+    let l1 = MockL1::with_fees(20 * GWEI, 2 * GWEI);
+    let mut submission = ProofSubmission::new(1_000);
+
+    policy.step(&l1, &mut submission, 1_000).await.unwrap();
+    l1.set_fees(200 * GWEI, 2 * GWEI);
+    let step = policy.step(&l1, &mut submission, 1_200).await.unwrap();
+    assert!(matches!(
+        step,
+        SubmissionStep::Pending {
+            replaced: false,
+            ..
+        }
+    ));
+    assert_eq!(l1.sent().len(), 1);
+
+    // Past the deadline the replacement is sent
+    let step = policy.step(&l1, &mut submission, 4_600).await.unwrap();
+    assert!(matches!(
+        step,
+        SubmissionStep::Pending { replaced: true, .. }
+    ));
+    assert_eq!(l1.sent()[1].max_fee_per_gas, 402_400_000_000);
+}
+
+#[tokio::test]
+async fn does_not_replace_at_fee_cap() {
+    let policy = SubmissionPolicy {
+        maximum_allowed_max_fee_per_gas: 42 * GWEI,
+        ..policy()
+    };
+    let l1 = MockL1::with_fees(20 * GWEI, 2 * GWEI);
+    let mut submission = ProofSubmission::new(1_000);
+
+    policy.step(&l1, &mut submission, 1_000).await.unwrap();
+    let step = policy.step(&l1, &mut submission, 1_200).await.unwrap();
+    assert!(matches!(
+        step,
+        SubmissionStep::Pending {
+            replaced: false,
+            ..
+        }
+    ));
+    assert_eq!(l1.sent().len(), 1);
+}
+
+#[tokio::test]
+async fn retries_reverted_transaction() {
+    let policy = policy();
+    let l1 = MockL1::with_fees(20 * GWEI, 2 * GWEI);
+    l1.0.borrow_mut().revert_txs = true;
+    let mut submission = ProofSubmission::new(1_000);
+
+    policy.step(&l1, &mut submission, 1_000).await.unwrap();
+    l1.mine();
+    let step = policy.step(&l1, &mut submission, 1_012).await.unwrap();
+    assert!(matches!(step, SubmissionStep::Failed { .. }));
+    assert_eq!(submission.pending_tx, None);
+    assert_eq!(submission.failed_attempts, 1);
+
+    l1.0.borrow_mut().revert_txs = false;
+    let step = policy.step(&l1, &mut submission, 1_100).await.unwrap();
+    assert!(matches!(step, SubmissionStep::Pending { .. }));
+    // A new nonce is used, the reverted transaction consumed the previous one
+    let sent = l1.sent();
+    assert_eq!(sent[1].nonce, sent[0].nonce + 1);
+}
@@ -1,5 +1,9 @@
+use std::path::Path;
+
 use anyhow::Result;
-use ethrex_storage_rollup::SQLStore;
+use ethrex_common::H256;
+use ethrex_l2_common::proof_submission::{PendingVerifyTx, ProofSubmission};
+use ethrex_storage_rollup::{EngineTypeRollup, SQLStore, StoreRollup};
 
 #[tokio::test]
 async fn test_schema_tables() -> Result<()> {
@@ -17,6 +21,7 @@ async fn test_schema_tables() -> Result<()> {
         "block_signatures",
         "batch_signatures",
         "batch_prover_input",
+        "proof_submissions",
     ];
     let mut attributes = Vec::new();
     for table in tables {
@@ -64,6 +69,8 @@ async fn test_schema_tables() -> Result<()> {
             ("batch_prover_input", "batch") => "INT",
             ("batch_prover_input", "prover_version") => "TEXT",
             ("batch_prover_input", "prover_input") => "BLOB",
+            ("proof_submissions", "batch") => "INT",
+            ("proof_submissions", "submission") => "BLOB",
             _ => {
                 return Err(anyhow::Error::msg(
                     "unexpected attribute {name} in table {table}",
@@ -74,3 +81,42 @@ async fn test_schema_tables() -> Result<()> {
     }
     Ok(())
 }
+
+#[tokio::test]
+async fn test_proof_submission_roundtrip() -> Result<()> {
+    let submission = ProofSubmission {
+        queued_at: 1_000,
+        failed_attempts: 2,
+        next_attempt_at: 1_200,
+        deferred_since: None,
+        deferred_secs: 60,
+        pending_tx: Some(PendingVerifyTx {
+            nonce: 7,
+            max_fee_per_gas: 30_000_000_000,
+            max_priority_fee_per_gas: 2_000_000_000,
+            tx_hashes: vec![H256::repeat_byte(1), H256::repeat_byte(2)],
+            sent_at: 1_100,
+        }),
+    };
+    for engine_type in [EngineTypeRollup::InMemory, EngineTypeRollup::SQL] {
+        let store = StoreRollup::new(Path::new(":memory:"), engine_type)?;
+        store
+            .store_proof_submission_by_batch(5, submission.clone())
+            .await?;
+        assert_eq!(
+            store.get_proof_submission_by_batch(5).await?,
+            Some(submission.clone())
+        );
+
+        // Reverting drops the submissions of the reverted batches
+        store.revert_to_batch(4).await?;
+        assert_eq!(store.get_proof_submission_by_batch(5).await?, None);
+
+        store
+            .store_proof_submission_by_batch(3, submission.clone())
+            .await?;
+        store.delete_proof_submission_by_batch(3).await?;
+        assert_eq!(store.get_proof_submission_by_batch(3).await?, None);
+    }
+    Ok(())
+}
@@ -0,0 +1,301 @@
+//! Tests for per-transaction access sets in LEVM.
+//!
+//! Key behaviors tested:
+//! - A value transfer writes the balances of both parties and the sender nonce
+//! - Token transfers read and write exactly the balance slots involved
+//! - Contract creations write the nonces, codes and storage of the created accounts
+//! - Values written back to what they were are not part of the write set
+//! - Transactions conflict only if one changes state the other reads or changes
+
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256,
+    evm::compute_create_address,
+    types::{Account, Code, EIP1559Transaction, Fork, Transaction, TxKind},
+};
+use ethrex_levm::{
+    access_sets::{AccessSets, ReadSet, WriteSet},
+    db::gen_db::GeneralizedDatabase,
+    environment::{EVMConfig, Environment},
+    tracing::LevmCallTracer,
+    vm::{VM, VMType},
+};
+use rustc_hash::FxHashMap;
+use std::{collections::BTreeSet, sync::Arc};
+
+use super::TestDatabase;
+
+// ==================== Test Constants ====================
+
+const ALICE: u64 = 0x1000;
+const BOB: u64 = 0x2000;
+const CAROL: u64 = 0x3000;
+const TOKEN: u64 = 0x4000;
+const FACTORY: u64 = 0x5000;
+const GAS_LIMIT: u64 = 1_000_000;
+const COINBASE: u64 = 0xCCC;
+
+/// Minimal token: moves `amount` from the caller's balance slot to the balance slot of `to`,
+/// with calldata `to || amount`. Balances are stored at the slot of the holder's address.
+const TOKEN_CODE: [u8; 22] = [
+    0x33, 0x54, // SLOAD(CALLER)
+    0x60, 0x20, 0x35, 0x90, 0x03, // - amount
+    0x33, 0x55, // SSTORE(CALLER, _)
+    0x60, 0x00, 0x35, 0x54, // SLOAD(to)
+    0x60, 0x20, 0x35, 0x01, // + amount
+    0x60, 0x00, 0x35, 0x55, // SSTORE(to, _)
+    0x00, // STOP
+];
+
+/// Init code storing 1 at slot 0 and deploying STOP.
+const INIT_CODE: [u8; 10] = [0x60, 0x01, 0x60, 0x00, 0x55, 0x60, 0x01, 0x60, 0x00, 0xf3];
+
+// ==================== Helpers ====================
+
+fn address(n: u64) -> Address {
+    Address::from_low_u64_be(n)
+}
+
+/// Slot holding the token balance of `holder`.
+fn balance_slot(holder: u64) -> H256 {
+    H256::from_low_u64_be(holder)
+}
+
+fn set<T: Ord + Clone>(items: &[T]) -> BTreeSet<T> {
+    items.iter().cloned().collect()
+}
+
+/// Runs CREATE with `INIT_CODE` twice.
+fn factory_code() -> Bytes {
+    let mut word = [0u8; 32];
+    word[32 - INIT_CODE.len()..].copy_from_slice(&INIT_CODE);
+    let mut bytecode = vec![0x7f]; // PUSH32 init code
+    bytecode.extend_from_slice(&word);
+    bytecode.extend_from_slice(&[0x60, 0x00, 0x52]); // MSTORE at 0
+    let offset = u8::try_from(32 - INIT_CODE.len()).unwrap();
+    let size = u8::try_from(INIT_CODE.len()).unwrap();
+    for _ in 0..2 {
+        // size, offset, value, CREATE, POP
+        bytecode.extend_from_slice(&[0x60, size, 0x60, offset, 0x60, 0x00, 0xf0, 0x50]);
+    }
+    bytecode.push(0x00); // STOP
+    Bytes::from(bytecode)
+}
+
+fn eoa(balance: u64) -> Account {
+    Account::new(
+        U256::from(balance),
+        Code::default(),
+        0,
+        FxHashMap::default(),
+    )
+}
+
+fn contract(code: Bytes, storage: FxHashMap<H256, U256>) -> Account {
+    Account::new(U256::zero(), Code::from_bytecode(code), 1, storage)
+}
+
+/// Token holding 100 for each of `ALICE`, `BOB` and `CAROL`.
+fn token() -> Account {
+    let storage = [ALICE, BOB, CAROL]
+        .into_iter()
+        .map(|holder| (balance_slot(holder), U256::from(100)))
+        .collect();
+    contract(Bytes::from_static(&TOKEN_CODE), storage)
+}
+
+fn accounts() -> FxHashMap<Address, Account> {
+    [
+        (address(ALICE), eoa(10_000_000_000)),
+        (address(BOB), eoa(10_000_000_000)),
+        (address(CAROL), eoa(0)),
+        (address(TOKEN), token()),
+        (
+            address(FACTORY),
+            contract(factory_code(), FxHashMap::default()),
+        ),
+    ]
+    .into_iter()
+    .collect()
+}
+
+fn environment(sender: u64) -> Environment {
+    let fork = Fork::Prague;
+    Environment {
+        origin: address(sender),
+        gas_limit: GAS_LIMIT,
+        config: EVMConfig::new(fork, EVMConfig::canonical_values(fork)),
+        block_number: U256::from(1),
+        coinbase: address(COINBASE),
+        timestamp: U256::from(1000),
+        prev_randao: Some(H256::zero()),
+        difficulty: U256::zero(),
+        slot_number: U256::zero(),
+        chain_id: U256::from(1),
+        base_fee_per_gas: U256::from(1000),
+        base_blob_fee_per_gas: U256::from(1),
+        gas_price: U256::from(1000),
+        block_excess_blob_gas: None,
+        block_blob_gas_used: None,
+        tx_blob_hashes: vec![],
+        tx_max_priority_fee_per_gas: None,
+        tx_max_fee_per_gas: Some(U256::from(1000)),
+        tx_max_fee_per_blob_gas: None,
+        tx_nonce: 0,
+        block_gas_limit: GAS_LIMIT * 2,
+        is_privileged: false,
+    }
+}
+
+/// Executes a transaction from `sender` to `to` and returns its access sets.
+fn execute(sender: u64, to: u64, value: u64, data: Bytes) -> AccessSets {
+    let mut db =
+        GeneralizedDatabase::new_with_account_state(Arc::new(TestDatabase::default()), accounts());
+    let tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+        to: TxKind::Call(address(to)),
+        value: U256::from(value),
+        data,
+        gas_limit: GAS_LIMIT,
+        max_fee_per_gas: 1000,
+        max_priority_fee_per_gas: 1,
+        ..Default::default()
+    });
+    let mut vm = VM::new(
+        environment(sender),
+        &mut db,
+        &tx,
+        LevmCallTracer::disabled(),
+        VMType::L1,
+    )
+    .unwrap();
+    let (report, access_sets) = vm.execute_with_access_sets().unwrap();
+    assert!(report.is_success());
+    access_sets
+}
+
+/// Token transfer calldata.
+fn token_transfer(to: u64, amount: u64) -> Bytes {
+    let mut data = H256::from_low_u64_be(to).as_bytes().to_vec();
+    data.extend_from_slice(H256::from_low_u64_be(amount).as_bytes());
+    Bytes::from(data)
+}
+
+// ==================== Tests ====================
+
+#[test]
+fn value_transfer_access_sets() {
+    let access_sets = execute(ALICE, CAROL, 1, Bytes::new());
+
+    // The priority fee is 0, the coinbase balance doesn't change
+    assert_eq!(
+        access_sets.writes,
+        WriteSet {
+            balances: set(&[address(ALICE), address(CAROL)]),
+            nonces: set(&[address(ALICE)]),
+            ..Default::default()
+        }
+    );
+    assert!(access_sets.reads.accounts.contains(&address(ALICE)));
+    assert!(access_sets.reads.accounts.contains(&address(CAROL)));
+    assert!(access_sets.reads.storage.is_empty());
+}
+
+#[test]
+fn token_transfer_access_sets() {
+    let access_sets = execute(ALICE, TOKEN, 0, token_transfer(CAROL, 10));
+
+    let slots = set(&[
+        (address(TOKEN), balance_slot(ALICE)),
+        (address(TOKEN), balance_slot(CAROL)),
+    ]);
+    assert_eq!(access_sets.reads.storage, slots);
+    assert_eq!(
+        access_sets.writes,
+        WriteSet {
+            balances: set(&[address(ALICE)]),
+            nonces: set(&[address(ALICE)]),
+            storage: slots,
+            ..Default::default()
+        }
+    );
+    assert!(access_sets.reads.accounts.contains(&address(TOKEN)));
+    // Only the balance slots of the calldata are touched
+    assert!(!access_sets.reads.accounts.contains(&address(CAROL)));
+}
+
+#[test]
+fn unchanged_values_are_not_written() {
+    // Moving 0 tokens to oneself stores the balance slot back with the same value
+    let access_sets = execute(ALICE, TOKEN, 0, token_transfer(ALICE, 0));
+
+    let slot = (address(TOKEN), balance_slot(ALICE));
+    assert_eq!(access_sets.reads.storage, set(&[slot]));
+    assert!(access_sets.writes.storage.is_empty());
+}
+
+#[test]
+fn create_heavy_access_sets() {
+    let access_sets = execute(ALICE, FACTORY, 0, Bytes::new());
+
+    let first = compute_create_address(address(FACTORY), 1);
+    let second = compute_create_address(address(FACTORY), 2);
+    assert_eq!(
+        access_sets.writes,
+        WriteSet {
+            balances: set(&[address(ALICE)]),
+            nonces: set(&[address(ALICE), address(FACTORY), first, second]),
+            codes: set(&[first, second]),
+            storage: set(&[(first, H256::zero()), (second, H256::zero())]),
+        }
+    );
+    assert!(access_sets.reads.accounts.is_superset(&set(&[
+        address(ALICE),
+        address(FACTORY),
+        first,
+        second
+    ])));
+}
+
+#[test]
+fn transactions_conflict_on_shared_state() {
+    let alice_to_carol = execute(ALICE, TOKEN, 0, token_transfer(CAROL, 10));
+    let bob_to_bob = execute(BOB, TOKEN, 0, token_transfer(BOB, 10));
+    let bob_to_carol = execute(BOB, TOKEN, 0, token_transfer(CAROL, 10));
+
+    // Both read the token account but only change their own slots
+    assert!(!alice_to_carol.conflicts_with(&bob_to_bob));
+    assert!(!bob_to_bob.conflicts_with(&alice_to_carol));
+    // Both change the balance of CAROL
+    assert!(alice_to_carol.conflicts_with(&bob_to_carol));
+    assert!(bob_to_carol.conflicts_with(&alice_to_carol));
+}
+
+#[test]
+fn write_conflicts_with_read_of_same_account() {
+    let reader = AccessSets {
+        reads: ReadSet {
+            accounts: set(&[address(CAROL)]),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let writer = AccessSets {
+        writes: WriteSet {
+            balances: set(&[address(CAROL)]),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let other_writer = AccessSets {
+        writes: WriteSet {
+            nonces: set(&[address(BOB)]),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+
+    assert!(reader.conflicts_with(&writer));
+    assert!(writer.conflicts_with(&reader));
+    assert!(!reader.conflicts_with(&other_writer));
+    assert!(!reader.conflicts_with(&reader));
+}
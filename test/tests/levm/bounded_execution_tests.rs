@@ -0,0 +1,311 @@
+//! Tests for step-budgeted execution with `VM::execute_bounded`.
+//!
+//! Key behaviors tested:
+//! - Any step budget yields the same report and state changes as `VM::execute`
+//! - Pausing works inside nested and reverting call frames
+//! - Contract creations and top-level precompile calls can be bounded too
+//! - A finished execution can't be resumed
+
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256,
+    types::{Account, AccountUpdate, Code, EIP1559Transaction, Fork, Transaction, TxKind},
+};
+use ethrex_levm::{
+    db::gen_db::GeneralizedDatabase,
+    environment::{EVMConfig, Environment},
+    errors::ExecutionReport,
+    tracing::LevmCallTracer,
+    vm::{ExecutionStatus, VM, VMType},
+};
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+use super::TestDatabase;
+
+// ==================== Test Constants ====================
+
+const SENDER: u64 = 0x1000;
+const CONTRACT_A: u64 = 0x2000;
+const CONTRACT_B: u64 = 0x3000;
+const CONTRACT_C: u64 = 0x4000;
+const IDENTITY_PRECOMPILE: u64 = 0x04;
+const GAS_LIMIT: u64 = 1_000_000;
+
+const CALL: u8 = 0xf1;
+const STATICCALL: u8 = 0xfa;
+
+// ==================== Bytecode Helpers ====================
+
+/// Stores `i` at slot `i` for `i` in `iterations..=1`, then emits an empty log.
+fn loop_bytecode(iterations: u8) -> Vec<u8> {
+    let mut bytecode = vec![0x60, iterations]; // PUSH1 iterations
+    bytecode.push(0x5b); // JUMPDEST
+    bytecode.extend_from_slice(&[0x80, 0x80, 0x55]); // DUP1, DUP1, SSTORE
+    bytecode.extend_from_slice(&[0x60, 0x01, 0x90, 0x03]); // PUSH1 1, SWAP1, SUB
+    bytecode.extend_from_slice(&[0x80, 0x60, 0x02, 0x57]); // DUP1, PUSH1 2, JUMPI
+    bytecode.push(0x50); // POP
+    bytecode.extend_from_slice(&[0x60, 0x00, 0x60, 0x00, 0xa0]); // LOG0(0, 0)
+    bytecode
+}
+
+/// Calls `target` with 32 zeroed bytes of calldata and discards the result.
+fn call_bytecode(opcode: u8, target: Address) -> Vec<u8> {
+    let mut bytecode = vec![0x60, 0x00, 0x60, 0x00, 0x60, 0x20, 0x60, 0x00]; // retSize, retOffset, argsSize, argsOffset
+    if opcode == CALL {
+        bytecode.extend_from_slice(&[0x60, 0x00]); // value
+    }
+    bytecode.push(0x73); // PUSH20 target
+    bytecode.extend_from_slice(target.as_bytes());
+    bytecode.push(0x5a); // GAS
+    bytecode.push(opcode);
+    bytecode.push(0x50); // POP
+    bytecode
+}
+
+/// Returns a 32-byte word holding 0x2a.
+fn return_bytecode() -> Vec<u8> {
+    vec![
+        0x60, 0x2a, 0x60, 0x00, 0x52, // MSTORE(0, 0x2a)
+        0x60, 0x20, 0x60, 0x00, 0xf3, // RETURN(0, 32)
+    ]
+}
+
+/// Loops, calls a contract that writes storage, a contract that reverts and a precompile.
+fn caller_bytecode() -> Bytes {
+    let mut bytecode = loop_bytecode(5);
+    bytecode.extend(call_bytecode(CALL, Address::from_low_u64_be(CONTRACT_B)));
+    bytecode.extend(call_bytecode(CALL, Address::from_low_u64_be(CONTRACT_C)));
+    bytecode.extend(call_bytecode(
+        STATICCALL,
+        Address::from_low_u64_be(IDENTITY_PRECOMPILE),
+    ));
+    bytecode.extend(return_bytecode());
+    Bytes::from(bytecode)
+}
+
+fn storing_bytecode() -> Bytes {
+    let mut bytecode = loop_bytecode(3);
+    bytecode.push(0x00); // STOP
+    Bytes::from(bytecode)
+}
+
+fn reverting_bytecode() -> Bytes {
+    let mut bytecode = loop_bytecode(2);
+    bytecode.extend_from_slice(&[0x60, 0x00, 0x60, 0x00, 0xfd]); // REVERT(0, 0)
+    Bytes::from(bytecode)
+}
+
+/// Init code that runs a loop and deploys a contract returning 0x2a.
+fn init_code() -> Bytes {
+    let runtime = return_bytecode();
+    let runtime_len = u8::try_from(runtime.len()).unwrap();
+    let mut bytecode = loop_bytecode(4);
+    // CODECOPY(0, runtime_offset, runtime_len), RETURN(0, runtime_len)
+    let runtime_offset = u8::try_from(bytecode.len() + 12).unwrap();
+    bytecode.extend_from_slice(&[0x60, runtime_len, 0x60, runtime_offset, 0x60, 0x00, 0x39]);
+    bytecode.extend_from_slice(&[0x60, runtime_len, 0x60, 0x00, 0xf3]);
+    bytecode.extend(runtime);
+    Bytes::from(bytecode)
+}
+
+// ==================== Execution Helpers ====================
+
+fn contract(code: Bytes) -> Account {
+    Account::new(
+        U256::zero(),
+        Code::from_bytecode(code),
+        0,
+        FxHashMap::default(),
+    )
+}
+
+fn test_db() -> GeneralizedDatabase {
+    let mut accounts: FxHashMap<Address, Account> = [
+        (CONTRACT_A, caller_bytecode()),
+        (CONTRACT_B, storing_bytecode()),
+        (CONTRACT_C, reverting_bytecode()),
+    ]
+    .into_iter()
+    .map(|(address, code)| (Address::from_low_u64_be(address), contract(code)))
+    .collect();
+    accounts.insert(
+        Address::from_low_u64_be(SENDER),
+        Account::new(
+            U256::from(10_000_000_000u64),
+            Code::default(),
+            0,
+            FxHashMap::default(),
+        ),
+    );
+    GeneralizedDatabase::new_with_account_state(Arc::new(TestDatabase::default()), accounts)
+}
+
+fn test_env() -> Environment {
+    let fork = Fork::Prague;
+    Environment {
+        origin: Address::from_low_u64_be(SENDER),
+        gas_limit: GAS_LIMIT,
+        config: EVMConfig::new(fork, EVMConfig::canonical_values(fork)),
+        block_number: U256::from(1),
+        coinbase: Address::from_low_u64_be(0xCCC),
+        timestamp: U256::from(1000),
+        prev_randao: Some(H256::zero()),
+        difficulty: U256::zero(),
+        slot_number: U256::zero(),
+        chain_id: U256::from(1),
+        base_fee_per_gas: U256::from(1000),
+        base_blob_fee_per_gas: U256::from(1),
+        gas_price: U256::from(1000),
+        block_excess_blob_gas: None,
+        block_blob_gas_used: None,
+        tx_blob_hashes: vec![],
+        tx_max_priority_fee_per_gas: None,
+        tx_max_fee_per_gas: Some(U256::from(1000)),
+        tx_max_fee_per_blob_gas: None,
+        tx_nonce: 0,
+        block_gas_limit: GAS_LIMIT * 2,
+        is_privileged: false,
+    }
+}
+
+fn test_tx(to: TxKind, data: Bytes) -> Transaction {
+    Transaction::EIP1559Transaction(EIP1559Transaction {
+        to,
+        data,
+        gas_limit: GAS_LIMIT,
+        max_fee_per_gas: 1000,
+        max_priority_fee_per_gas: 1,
+        ..Default::default()
+    })
+}
+
+fn call_tx(to: u64) -> Transaction {
+    test_tx(TxKind::Call(Address::from_low_u64_be(to)), Bytes::new())
+}
+
+/// State changes sorted by address, so that runs can be compared.
+fn state_transitions(db: &mut GeneralizedDatabase) -> Vec<AccountUpdate> {
+    let mut updates = db.get_state_transitions().unwrap();
+    updates.sort_by_key(|update| update.address);
+    updates
+}
+
+fn execute(tx: &Transaction) -> (ExecutionReport, Vec<AccountUpdate>) {
+    let mut db = test_db();
+    let mut vm = VM::new(
+        test_env(),
+        &mut db,
+        tx,
+        LevmCallTracer::disabled(),
+        VMType::L1,
+    )
+    .unwrap();
+    let report = vm.execute().unwrap();
+    (report, state_transitions(&mut db))
+}
+
+/// Executes `tx` in slices of `max_steps` opcodes. Also returns the number of pauses.
+fn execute_bounded(
+    tx: &Transaction,
+    max_steps: u64,
+) -> (ExecutionReport, Vec<AccountUpdate>, usize) {
+    let mut db = test_db();
+    let mut vm = VM::new(
+        test_env(),
+        &mut db,
+        tx,
+        LevmCallTracer::disabled(),
+        VMType::L1,
+    )
+    .unwrap();
+    let mut pauses = 0;
+    let report = loop {
+        match vm.execute_bounded(max_steps).unwrap() {
+            ExecutionStatus::Finished(report) => break report,
+            ExecutionStatus::Paused => pauses += 1,
+        }
+    };
+    (report, state_transitions(&mut db), pauses)
+}
+
+/// Checks that every budget up to (and past) the number of executed opcodes
+/// produces the same result as unbounded execution.
+fn assert_bounded_matches_unbounded(tx: &Transaction) {
+    let (expected_report, expected_updates) = execute(tx);
+    let (_, _, total_steps) = execute_bounded(tx, 1);
+    assert!(total_steps > 0);
+
+    for max_steps in 1..=u64::try_from(total_steps).unwrap() + 1 {
+        let (report, updates, _) = execute_bounded(tx, max_steps);
+        assert_eq!(report, expected_report, "budget of {max_steps} steps");
+        assert_eq!(updates, expected_updates, "budget of {max_steps} steps");
+    }
+}
+
+// ==================== Tests ====================
+
+#[test]
+fn bounded_call_matches_unbounded_at_every_budget() {
+    let tx = call_tx(CONTRACT_A);
+    assert_bounded_matches_unbounded(&tx);
+
+    let (report, _) = execute(&tx);
+    assert!(report.is_success());
+    assert_eq!(report.max_depth, 1);
+    assert_eq!(report.logs.len(), 2);
+    assert_eq!(
+        report.output,
+        Bytes::from(U256::from(0x2a).to_big_endian().to_vec())
+    );
+}
+
+#[test]
+fn bounded_create_matches_unbounded_at_every_budget() {
+    let tx = test_tx(TxKind::Create, init_code());
+    assert_bounded_matches_unbounded(&tx);
+
+    let (report, _) = execute(&tx);
+    assert!(report.is_success());
+}
+
+#[test]
+fn bounded_precompile_call_finishes_at_once() {
+    let tx = call_tx(IDENTITY_PRECOMPILE);
+    let (expected_report, expected_updates) = execute(&tx);
+    let (report, updates, pauses) = execute_bounded(&tx, 1);
+
+    assert_eq!(pauses, 0);
+    assert_eq!(report, expected_report);
+    assert_eq!(updates, expected_updates);
+}
+
+#[test]
+fn budget_larger_than_execution_never_pauses() {
+    let tx = call_tx(CONTRACT_A);
+    let (expected_report, _) = execute(&tx);
+    let (report, _, pauses) = execute_bounded(&tx, u64::MAX);
+
+    assert_eq!(pauses, 0);
+    assert_eq!(report, expected_report);
+}
+
+#[test]
+fn finished_execution_cannot_be_resumed() {
+    let mut db = test_db();
+    let tx = call_tx(CONTRACT_A);
+    let mut vm = VM::new(
+        test_env(),
+        &mut db,
+        &tx,
+        LevmCallTracer::disabled(),
+        VMType::L1,
+    )
+    .unwrap();
+
+    assert!(matches!(
+        vm.execute_bounded(u64::MAX).unwrap(),
+        ExecutionStatus::Finished(_)
+    ));
+    assert!(vm.execute_bounded(u64::MAX).is_err());
+}
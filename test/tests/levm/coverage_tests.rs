@@ -0,0 +1,285 @@
+//! Tests for bytecode coverage recording in LEVM.
+//!
+//! Key behaviors tested:
+//! - Only the executed branch of a conditional jump is covered
+//! - Coverage accumulates over the transactions executed against the same database
+//! - Coverage taken from separate runs merges into the accumulated coverage
+//! - Basic blocks split at JUMPDEST and JUMPI and skip unreachable code
+//! - Init code is recorded under the hash of its bytecode
+//! - Nothing is recorded unless enabled
+//! - JSON and lcov exports
+
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256,
+    types::{Account, Code, EIP1559Transaction, Fork, Transaction, TxKind},
+    utils::keccak,
+};
+use ethrex_levm::{
+    coverage::{CoverageMap, coverage_to_json, coverage_to_lcov, merge_coverage},
+    db::gen_db::GeneralizedDatabase,
+    environment::{EVMConfig, Environment},
+    tracing::LevmCallTracer,
+    vm::{VM, VMType},
+};
+use rustc_hash::FxHashMap;
+use std::{collections::HashMap, sync::Arc};
+
+use super::TestDatabase;
+
+// ==================== Test Constants ====================
+
+const SENDER: u64 = 0x1000;
+const BRANCHY: u64 = 0x2000;
+const GAS_LIMIT: u64 = 1_000_000;
+const COINBASE: u64 = 0xCCC;
+
+/// Jumps to the second branch when the first calldata word is not zero. Ends with
+/// unreachable bytes, like the metadata Solidity appends to contracts.
+const BRANCHY_CODE: [u8; 18] = [
+    0x60, 0x00, 0x35, // 0x00: CALLDATALOAD(0)
+    0x60, 0x0a, 0x57, // 0x03: JUMPI(0x0a, _)
+    0x60, 0x01, 0x50, 0x00, // 0x06: PUSH1 1, POP, STOP
+    0x5b, 0x60, 0x02, 0x50, 0x00, // 0x0a: JUMPDEST, PUSH1 2, POP, STOP
+    0xfe, 0x12, 0x34, // 0x0f: INVALID and data
+];
+
+/// Init code running the first three instructions of `BRANCHY_CODE` and deploying nothing.
+const INIT_CODE: [u8; 4] = [0x60, 0x00, 0x35, 0x00];
+
+// ==================== Helpers ====================
+
+fn address(n: u64) -> Address {
+    Address::from_low_u64_be(n)
+}
+
+fn branchy_hash() -> H256 {
+    keccak(BRANCHY_CODE)
+}
+
+fn accounts() -> FxHashMap<Address, Account> {
+    [
+        (
+            address(SENDER),
+            Account::new(
+                U256::from(10_000_000_000u64),
+                Code::default(),
+                0,
+                FxHashMap::default(),
+            ),
+        ),
+        (
+            address(BRANCHY),
+            Account::new(
+                U256::zero(),
+                Code::from_bytecode(Bytes::from_static(&BRANCHY_CODE)),
+                1,
+                FxHashMap::default(),
+            ),
+        ),
+    ]
+    .into_iter()
+    .collect()
+}
+
+fn new_db(coverage: bool) -> GeneralizedDatabase {
+    let mut db =
+        GeneralizedDatabase::new_with_account_state(Arc::new(TestDatabase::default()), accounts());
+    if coverage {
+        db.enable_coverage();
+    }
+    db
+}
+
+fn environment(nonce: u64) -> Environment {
+    let fork = Fork::Prague;
+    Environment {
+        origin: address(SENDER),
+        gas_limit: GAS_LIMIT,
+        config: EVMConfig::new(fork, EVMConfig::canonical_values(fork)),
+        block_number: U256::from(1),
+        coinbase: address(COINBASE),
+        timestamp: U256::from(1000),
+        prev_randao: Some(H256::zero()),
+        difficulty: U256::zero(),
+        slot_number: U256::zero(),
+        chain_id: U256::from(1),
+        base_fee_per_gas: U256::from(1000),
+        base_blob_fee_per_gas: U256::from(1),
+        gas_price: U256::from(1000),
+        block_excess_blob_gas: None,
+        block_blob_gas_used: None,
+        tx_blob_hashes: vec![],
+        tx_max_priority_fee_per_gas: None,
+        tx_max_fee_per_gas: Some(U256::from(1000)),
+        tx_max_fee_per_blob_gas: None,
+        tx_nonce: nonce,
+        block_gas_limit: GAS_LIMIT * 2,
+        is_privileged: false,
+    }
+}
+
+/// Executes a transaction with `nonce` and commits it, so that the next one can follow.
+fn execute(db: &mut GeneralizedDatabase, nonce: u64, to: TxKind, data: Bytes) {
+    let tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+        nonce,
+        to,
+        data,
+        gas_limit: GAS_LIMIT,
+        max_fee_per_gas: 1000,
+        max_priority_fee_per_gas: 1,
+        ..Default::default()
+    });
+    let mut vm = VM::new(
+        environment(nonce),
+        db,
+        &tx,
+        LevmCallTracer::disabled(),
+        VMType::L1,
+    )
+    .unwrap();
+    let report = vm.execute().unwrap();
+    assert!(report.is_success());
+}
+
+/// Calls `BRANCHY` taking the jump when `jump` is set.
+fn call_branchy(db: &mut GeneralizedDatabase, nonce: u64, jump: bool) {
+    let word = H256::from_low_u64_be(u64::from(jump));
+    execute(
+        db,
+        nonce,
+        TxKind::Call(address(BRANCHY)),
+        Bytes::copy_from_slice(word.as_bytes()),
+    );
+}
+
+// ==================== Tests ====================
+
+#[test]
+fn basic_blocks_split_at_jumps_and_skip_unreachable_code() {
+    let map = CoverageMap::new(Bytes::from_static(&BRANCHY_CODE));
+    assert_eq!(map.basic_blocks(), vec![0x00..0x06, 0x06..0x0a, 0x0a..0x0f]);
+    assert_eq!(
+        map.instructions(),
+        vec![
+            0x00, 0x02, 0x03, 0x05, 0x06, 0x08, 0x09, 0x0a, 0x0b, 0x0d, 0x0e, 0x0f, 0x10, 0x11
+        ]
+    );
+}
+
+#[test]
+fn fall_through_covers_first_branch() {
+    let mut db = new_db(true);
+    call_branchy(&mut db, 0, false);
+
+    let coverage = db.take_coverage();
+    assert_eq!(coverage.len(), 1);
+    let report = coverage[&branchy_hash()].report(branchy_hash());
+    assert_eq!(report.covered_ranges, vec![(0x00, 0x0a)]);
+    assert_eq!(report.instructions, 14);
+    assert_eq!(report.covered_instructions, 7);
+    assert_eq!(report.basic_blocks, 3);
+    assert_eq!(report.covered_basic_blocks, 2);
+}
+
+#[test]
+fn taken_jump_covers_second_branch() {
+    let mut db = new_db(true);
+    call_branchy(&mut db, 0, true);
+
+    let coverage = db.take_coverage();
+    let report = coverage[&branchy_hash()].report(branchy_hash());
+    assert_eq!(report.covered_ranges, vec![(0x00, 0x06), (0x0a, 0x0f)]);
+    assert_eq!(report.covered_instructions, 8);
+    assert_eq!(report.covered_basic_blocks, 2);
+}
+
+#[test]
+fn coverage_accumulates_across_transactions() {
+    let mut db = new_db(true);
+    call_branchy(&mut db, 0, false);
+    call_branchy(&mut db, 1, true);
+
+    let coverage = db.take_coverage();
+    let report = coverage[&branchy_hash()].report(branchy_hash());
+    assert_eq!(report.covered_ranges, vec![(0x00, 0x0f)]);
+    assert_eq!(report.covered_instructions, 11);
+    assert_eq!(report.covered_basic_blocks, 3);
+    assert_eq!(report.basic_block_percentage, 100.0);
+
+    // Taking the coverage resets it, recording goes on
+    assert!(db.take_coverage().is_empty());
+    call_branchy(&mut db, 2, false);
+    assert_eq!(db.take_coverage().len(), 1);
+}
+
+#[test]
+fn merge_combines_separate_runs() {
+    let mut fall_through = new_db(true);
+    call_branchy(&mut fall_through, 0, false);
+    let mut jump = new_db(true);
+    call_branchy(&mut jump, 0, true);
+    let mut both = new_db(true);
+    call_branchy(&mut both, 0, false);
+    call_branchy(&mut both, 1, true);
+
+    let mut merged = fall_through.take_coverage();
+    merge_coverage(&mut merged, jump.take_coverage());
+    assert_eq!(merged, both.take_coverage());
+}
+
+#[test]
+fn init_code_is_keyed_by_its_bytecode_hash() {
+    let mut db = new_db(true);
+    execute(&mut db, 0, TxKind::Create, Bytes::from_static(&INIT_CODE));
+
+    let coverage = db.take_coverage();
+    let init_code_hash = keccak(INIT_CODE);
+    assert_eq!(coverage.keys().collect::<Vec<_>>(), vec![&init_code_hash]);
+    let report = coverage[&init_code_hash].report(init_code_hash);
+    assert_eq!(report.covered_ranges, vec![(0x00, 0x04)]);
+}
+
+#[test]
+fn disabled_coverage_records_nothing() {
+    let mut db = new_db(false);
+    call_branchy(&mut db, 0, true);
+    assert!(db.coverage.is_none());
+    assert!(db.take_coverage().is_empty());
+}
+
+#[test]
+fn json_export_lists_reports() {
+    let mut db = new_db(true);
+    call_branchy(&mut db, 0, true);
+    let json = coverage_to_json(&db.take_coverage()).unwrap();
+
+    let reports: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let report = &reports[0];
+    assert_eq!(
+        report["code_hash"],
+        serde_json::json!(format!("{:#x}", branchy_hash()))
+    );
+    assert_eq!(report["code_size"], 18);
+    assert_eq!(
+        report["covered_ranges"],
+        serde_json::json!([[0, 6], [10, 15]])
+    );
+    assert_eq!(report["basic_blocks"], 3);
+    assert_eq!(report["covered_basic_blocks"], 2);
+}
+
+#[test]
+fn lcov_export_has_a_line_per_instruction() {
+    let mut coverage = HashMap::new();
+    let mut map = CoverageMap::new(Bytes::from_static(&INIT_CODE));
+    map.record(0x00);
+    map.record(0x02);
+    coverage.insert(keccak(INIT_CODE), map);
+
+    let expected = format!(
+        "SF:{:#x}\nDA:0,1\nDA:2,1\nDA:3,0\nLF:3\nLH:2\nend_of_record\n",
+        keccak(INIT_CODE)
+    );
+    assert_eq!(coverage_to_lcov(&coverage), expected);
+}
@@ -0,0 +1,383 @@
+//! Tests for CREATE/CREATE2 address computation and failure diagnostics in LEVM.
+//!
+//! Key behaviors tested:
+//! - The shared address helpers match known CREATE and CREATE2 vectors, and the addresses LEVM deploys to
+//! - Failed creations record a structured cause in the call trace: address collision, init code size limit,
+//!   insufficient balance for the endowment, depth limit and init code revert (with the revert data)
+//! - An existing account only collides if it has code, a nonzero nonce or storage, not if it only holds balance
+//! - Successful creations carry no failure
+
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256,
+    evm::{compute_create_address, compute_create2_address},
+    tracing::{CallTraceFrame, CreateFailure},
+    types::{Account, Code, EIP1559Transaction, Fork, Transaction, TxKind},
+    utils::keccak,
+};
+use ethrex_levm::{
+    constants::INIT_CODE_MAX_SIZE,
+    db::gen_db::GeneralizedDatabase,
+    environment::{EVMConfig, Environment},
+    errors::ExecutionReport,
+    tracing::LevmCallTracer,
+    vm::{VM, VMType},
+};
+use rustc_hash::FxHashMap;
+use std::{str::FromStr, sync::Arc};
+
+use super::TestDatabase;
+
+// ==================== Test Constants ====================
+
+const SENDER: u64 = 0x1000;
+const FACTORY: u64 = 0x2000;
+const GAS_LIMIT: u64 = 1_000_000;
+const SALT: u64 = 0x5a17;
+
+/// Init code returning empty runtime code: STOP
+const EMPTY_INIT_CODE: [u8; 1] = [0x00];
+/// Init code reverting with the word 0x2a: MSTORE(0, 0x2a), REVERT(0, 32)
+const REVERTING_INIT_CODE: [u8; 10] = [0x60, 0x2a, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xfd];
+
+// ==================== Bytecode Helpers ====================
+
+fn address(n: u64) -> Address {
+    Address::from_low_u64_be(n)
+}
+
+/// Writes `init_code` (up to 32 bytes) at the end of the first memory word, then runs
+/// CREATE, or CREATE2 with `SALT` if `create2`, sending `value`. The result is stored at slot 0.
+fn create_bytecode(init_code: &[u8], value: u8, create2: bool) -> Bytes {
+    let size = u8::try_from(init_code.len()).unwrap();
+    let mut word = [0u8; 32];
+    word[32 - init_code.len()..].copy_from_slice(init_code);
+    let mut bytecode = vec![0x7f]; // PUSH32 init code
+    bytecode.extend_from_slice(&word);
+    bytecode.extend_from_slice(&[0x60, 0x00, 0x52]); // MSTORE at 0
+    if create2 {
+        bytecode.push(0x61); // PUSH2 salt
+        bytecode.extend_from_slice(&u16::try_from(SALT).unwrap().to_be_bytes());
+    }
+    bytecode.extend_from_slice(&[0x60, size, 0x60, 32 - size, 0x60, value]); // size, offset, value
+    bytecode.push(if create2 { 0xf5 } else { 0xf0 });
+    bytecode.extend_from_slice(&[0x60, 0x00, 0x55, 0x00]); // SSTORE(0, result), STOP
+    Bytes::from(bytecode)
+}
+
+/// Runs CREATE with an init code one byte over the EIP-3860 limit.
+fn oversized_create_bytecode() -> Bytes {
+    let size = u32::try_from(INIT_CODE_MAX_SIZE + 1).unwrap().to_be_bytes();
+    let mut bytecode = vec![0x62]; // PUSH3 size
+    bytecode.extend_from_slice(&size[1..]);
+    bytecode.extend_from_slice(&[0x60, 0x00, 0x60, 0x00, 0xf0, 0x00]); // offset, value, CREATE, STOP
+    Bytes::from(bytecode)
+}
+
+/// Address `create_bytecode(init_code, _, true)` deploys to.
+fn create2_target(init_code: &[u8]) -> Address {
+    compute_create2_address(
+        address(FACTORY),
+        H256::from_low_u64_be(SALT),
+        keccak(init_code),
+    )
+}
+
+// ==================== Execution Helpers ====================
+
+fn database(accounts: Vec<(Address, Account)>) -> GeneralizedDatabase {
+    let mut accounts: FxHashMap<Address, Account> = accounts.into_iter().collect();
+    accounts.insert(
+        address(SENDER),
+        Account::new(
+            U256::from(10_000_000_000u64),
+            Code::default(),
+            0,
+            FxHashMap::default(),
+        ),
+    );
+    GeneralizedDatabase::new_with_account_state(Arc::new(TestDatabase::default()), accounts)
+}
+
+/// Factory account running `bytecode`, with nonce 1 and `balance`.
+fn factory(bytecode: Bytes, balance: u64) -> (Address, Account) {
+    let account = Account::new(
+        U256::from(balance),
+        Code::from_bytecode(bytecode),
+        1,
+        FxHashMap::default(),
+    );
+    (address(FACTORY), account)
+}
+
+fn environment() -> Environment {
+    let fork = Fork::Prague;
+    Environment {
+        origin: address(SENDER),
+        gas_limit: GAS_LIMIT,
+        config: EVMConfig::new(fork, EVMConfig::canonical_values(fork)),
+        block_number: U256::from(1),
+        coinbase: Address::from_low_u64_be(0xCCC),
+        timestamp: U256::from(1000),
+        prev_randao: Some(H256::zero()),
+        difficulty: U256::zero(),
+        slot_number: U256::zero(),
+        chain_id: U256::from(1),
+        base_fee_per_gas: U256::from(1000),
+        base_blob_fee_per_gas: U256::from(1),
+        gas_price: U256::from(1000),
+        block_excess_blob_gas: None,
+        block_blob_gas_used: None,
+        tx_blob_hashes: vec![],
+        tx_max_priority_fee_per_gas: None,
+        tx_max_fee_per_gas: Some(U256::from(1000)),
+        tx_max_fee_per_blob_gas: None,
+        tx_nonce: 0,
+        block_gas_limit: GAS_LIMIT * 2,
+        is_privileged: false,
+    }
+}
+
+fn transaction() -> Transaction {
+    Transaction::EIP1559Transaction(EIP1559Transaction {
+        to: TxKind::Call(address(FACTORY)),
+        gas_limit: GAS_LIMIT,
+        max_fee_per_gas: 1000,
+        max_priority_fee_per_gas: 1,
+        ..Default::default()
+    })
+}
+
+/// Executes a transaction from `SENDER` to `FACTORY`, with the top call at `depth`.
+/// Returns the report, the top call trace and the value at slot 0 of `FACTORY`.
+fn execute_at_depth(
+    accounts: Vec<(Address, Account)>,
+    depth: usize,
+) -> (ExecutionReport, CallTraceFrame, U256) {
+    let mut db = database(accounts);
+    let tx = transaction();
+    let mut vm = VM::new(
+        environment(),
+        &mut db,
+        &tx,
+        LevmCallTracer::new(false, false),
+        VMType::L1,
+    )
+    .unwrap();
+    vm.current_call_frame.depth = depth;
+    let report = vm.execute().unwrap();
+    let trace = vm.tracer.callframes.pop().unwrap();
+    let stored = vm
+        .db
+        .get_account(address(FACTORY))
+        .unwrap()
+        .storage
+        .get(&H256::zero())
+        .copied()
+        .unwrap_or_default();
+    (report, trace, stored)
+}
+
+fn execute(accounts: Vec<(Address, Account)>) -> (ExecutionReport, CallTraceFrame, U256) {
+    execute_at_depth(accounts, 0)
+}
+
+/// Runs a CREATE2 of `EMPTY_INIT_CODE` whose target is already `existing`.
+/// Returns the create frame and the value pushed by CREATE2.
+fn create2_over(existing: Account) -> (CallTraceFrame, U256) {
+    let (report, mut trace, stored) = execute(vec![
+        factory(create_bytecode(&EMPTY_INIT_CODE, 0, true), 0),
+        (create2_target(&EMPTY_INIT_CODE), existing),
+    ]);
+    assert!(report.is_success());
+    (trace.calls.remove(0), stored)
+}
+
+// ==================== Address Helpers ====================
+
+#[test]
+fn create_address_matches_known_vector() {
+    let sender = Address::from_str("0x6ac7ea33f8831ea9dcc53393aaa88b25a785dbf0").unwrap();
+
+    assert_eq!(
+        compute_create_address(sender, 0),
+        Address::from_str("0xcd234a471b72ba2f1ccf0a70fcaba648a5eecd8d").unwrap()
+    );
+    assert_eq!(
+        compute_create_address(sender, 1),
+        Address::from_str("0x343c43a37d37dff08ae8c4a11544c718abb4fcf8").unwrap()
+    );
+}
+
+#[test]
+fn create2_address_matches_eip1014_vectors() {
+    // Examples 0 and 1 of EIP-1014
+    assert_eq!(
+        compute_create2_address(Address::zero(), H256::zero(), keccak([0x00])),
+        Address::from_str("0x4D1A2e2bB4F88F0250f26Ffff098B0b30B26BF38").unwrap()
+    );
+    let deployer = Address::from_str("0xdeadbeef00000000000000000000000000000000").unwrap();
+    assert_eq!(
+        compute_create2_address(deployer, H256::zero(), keccak([0x00])),
+        Address::from_str("0xB928f69Bb1D91Cd65274e3c79d8986362984fDA3").unwrap()
+    );
+}
+
+#[test]
+fn created_contracts_are_deployed_at_computed_addresses() {
+    let (_, trace, stored) = execute(vec![factory(
+        create_bytecode(&EMPTY_INIT_CODE, 0, false),
+        0,
+    )]);
+    let expected = compute_create_address(address(FACTORY), 1);
+    assert_eq!(trace.calls[0].to, expected);
+    assert_eq!(stored, U256::from_big_endian(expected.as_bytes()));
+    assert_eq!(trace.calls[0].create_failure, None);
+
+    let (_, trace, stored) = execute(vec![factory(create_bytecode(&EMPTY_INIT_CODE, 0, true), 0)]);
+    let expected = create2_target(&EMPTY_INIT_CODE);
+    assert_eq!(trace.calls[0].to, expected);
+    assert_eq!(stored, U256::from_big_endian(expected.as_bytes()));
+    assert_eq!(trace.calls[0].create_failure, None);
+}
+
+// ==================== Failure Causes ====================
+
+#[test]
+fn collision_with_code_is_reported() {
+    let existing = Account::new(
+        U256::zero(),
+        Code::from_bytecode(Bytes::from_static(&[0x00])),
+        0,
+        FxHashMap::default(),
+    );
+    let (create, result) = create2_over(existing);
+
+    assert_eq!(result, U256::zero());
+    assert_eq!(
+        create.create_failure,
+        Some(CreateFailure::AddressCollision {
+            address: create2_target(&EMPTY_INIT_CODE)
+        })
+    );
+}
+
+#[test]
+fn collision_with_nonce_is_reported() {
+    let existing = Account::new(U256::zero(), Code::default(), 1, FxHashMap::default());
+    let (create, result) = create2_over(existing);
+
+    assert_eq!(result, U256::zero());
+    assert!(matches!(
+        create.create_failure,
+        Some(CreateFailure::AddressCollision { .. })
+    ));
+}
+
+#[test]
+fn collision_with_storage_only_is_reported() {
+    let storage = FxHashMap::from_iter([(H256::zero(), U256::one())]);
+    let existing = Account::new(U256::zero(), Code::default(), 0, storage);
+    let (create, result) = create2_over(existing);
+
+    assert_eq!(result, U256::zero());
+    assert!(matches!(
+        create.create_failure,
+        Some(CreateFailure::AddressCollision { .. })
+    ));
+}
+
+#[test]
+fn account_with_only_balance_does_not_collide() {
+    let existing = Account::new(U256::from(7), Code::default(), 0, FxHashMap::default());
+    let (create, result) = create2_over(existing);
+
+    let target = create2_target(&EMPTY_INIT_CODE);
+    assert_eq!(result, U256::from_big_endian(target.as_bytes()));
+    assert_eq!(create.create_failure, None);
+    assert!(create.error.is_none());
+}
+
+#[test]
+fn empty_account_does_not_collide() {
+    let existing = Account::new(U256::zero(), Code::default(), 0, FxHashMap::default());
+    let (create, result) = create2_over(existing);
+
+    assert_ne!(result, U256::zero());
+    assert_eq!(create.create_failure, None);
+}
+
+#[test]
+fn init_code_size_limit_is_reported_on_the_halting_frame() {
+    let (report, trace, _) = execute(vec![factory(oversized_create_bytecode(), 0)]);
+
+    // The CREATE halts the frame executing it, no create frame is entered
+    assert!(!report.is_success());
+    assert!(trace.calls.is_empty());
+    assert_eq!(
+        trace.create_failure,
+        Some(CreateFailure::InitCodeSizeExceeded {
+            size: INIT_CODE_MAX_SIZE + 1,
+            max_size: INIT_CODE_MAX_SIZE,
+        })
+    );
+}
+
+#[test]
+fn insufficient_balance_is_reported() {
+    let (report, trace, result) = execute(vec![factory(
+        create_bytecode(&EMPTY_INIT_CODE, 2, false),
+        1,
+    )]);
+
+    assert!(report.is_success());
+    assert_eq!(result, U256::zero());
+    assert_eq!(
+        trace.calls[0].create_failure,
+        Some(CreateFailure::InsufficientBalance {
+            balance: U256::one(),
+            value: U256::from(2),
+        })
+    );
+}
+
+#[test]
+fn depth_limit_is_reported() {
+    let (report, trace, result) = execute_at_depth(
+        vec![factory(create_bytecode(&EMPTY_INIT_CODE, 0, false), 0)],
+        1024,
+    );
+
+    assert!(report.is_success());
+    assert_eq!(result, U256::zero());
+    assert_eq!(
+        trace.calls[0].create_failure,
+        Some(CreateFailure::DepthLimit)
+    );
+}
+
+#[test]
+fn init_code_revert_is_reported_with_revert_data() {
+    let (report, trace, result) = execute(vec![factory(
+        create_bytecode(&REVERTING_INIT_CODE, 0, true),
+        0,
+    )]);
+
+    assert!(report.is_success());
+    assert_eq!(result, U256::zero());
+    let create = &trace.calls[0];
+    assert_eq!(
+        create.create_failure,
+        Some(CreateFailure::InitCodeReverted {
+            data: Bytes::copy_from_slice(H256::from_low_u64_be(0x2a).as_bytes()),
+        })
+    );
+    assert!(create.error.is_some());
+    assert_eq!(
+        create.create_failure.as_ref().unwrap().to_string(),
+        format!(
+            "init code reverted with 0x{}",
+            hex::encode(H256::from_low_u64_be(0x2a))
+        )
+    );
+}
@@ -0,0 +1,269 @@
+//! Tests for EIP-7702 delegation resolution in LEVM.
+//!
+//! Key behaviors tested:
+//! - Calling a delegated account runs the target's code on the account's state
+//! - Call traces record the delegation target, for the transaction and for inner calls
+//! - The delegation target access is charged on top of the callee access
+//! - Chained delegations are not followed: the target's designation runs as code and halts
+//! - Resolved delegations are refreshed when the account's code changes in the transaction
+
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256,
+    tracing::CallTraceFrame,
+    types::{Account, Code, EIP1559Transaction, Fork, Transaction, TxKind},
+};
+use ethrex_levm::{
+    constants::SET_CODE_DELEGATION_BYTES,
+    db::gen_db::GeneralizedDatabase,
+    environment::{EVMConfig, Environment},
+    errors::ExecutionReport,
+    gas_cost::COLD_ADDRESS_ACCESS_COST,
+    tracing::LevmCallTracer,
+    utils::DelegationTarget,
+    vm::{VM, VMType},
+};
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+use super::TestDatabase;
+
+// ==================== Test Constants ====================
+
+const SENDER: u64 = 0x1000;
+const CALLER: u64 = 0x2000;
+const TARGET: u64 = 0x3000;
+const DELEGATED: u64 = 0x4000;
+const DELEGATED_TO_DELEGATED: u64 = 0x5000;
+const OTHER_TARGET: u64 = 0x6000;
+const GAS_LIMIT: u64 = 1_000_000;
+
+// ==================== Bytecode Helpers ====================
+
+fn address(n: u64) -> Address {
+    Address::from_low_u64_be(n)
+}
+
+/// Delegation designation pointing to `target`.
+fn designation(target: u64) -> Bytes {
+    Bytes::from([&SET_CODE_DELEGATION_BYTES[..], address(target).as_bytes()].concat())
+}
+
+/// Stores 1 at slot 0 of the account it runs for.
+fn store_bytecode() -> Bytes {
+    Bytes::from(vec![0x60, 0x01, 0x60, 0x00, 0x55, 0x00]) // SSTORE(0, 1), STOP
+}
+
+/// Calls `target` without value nor calldata and discards the result.
+fn call_bytecode(target: u64) -> Bytes {
+    let mut bytecode = vec![0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00]; // retSize, retOffset, argsSize, argsOffset, value
+    bytecode.push(0x73); // PUSH20 target
+    bytecode.extend_from_slice(address(target).as_bytes());
+    bytecode.extend_from_slice(&[0x5a, 0xf1, 0x50, 0x00]); // GAS, CALL, POP, STOP
+    Bytes::from(bytecode)
+}
+
+// ==================== Execution Helpers ====================
+
+fn database(accounts: Vec<(u64, Bytes)>) -> GeneralizedDatabase {
+    let mut accounts: FxHashMap<Address, Account> = accounts
+        .into_iter()
+        .map(|(n, code)| {
+            let account = Account::new(
+                U256::zero(),
+                Code::from_bytecode(code),
+                1,
+                FxHashMap::default(),
+            );
+            (address(n), account)
+        })
+        .collect();
+    accounts.insert(
+        address(SENDER),
+        Account::new(
+            U256::from(10_000_000_000u64),
+            Code::default(),
+            0,
+            FxHashMap::default(),
+        ),
+    );
+    GeneralizedDatabase::new_with_account_state(Arc::new(TestDatabase::default()), accounts)
+}
+
+fn environment() -> Environment {
+    let fork = Fork::Prague;
+    Environment {
+        origin: address(SENDER),
+        gas_limit: GAS_LIMIT,
+        config: EVMConfig::new(fork, EVMConfig::canonical_values(fork)),
+        block_number: U256::from(1),
+        coinbase: Address::from_low_u64_be(0xCCC),
+        timestamp: U256::from(1000),
+        prev_randao: Some(H256::zero()),
+        difficulty: U256::zero(),
+        slot_number: U256::zero(),
+        chain_id: U256::from(1),
+        base_fee_per_gas: U256::from(1000),
+        base_blob_fee_per_gas: U256::from(1),
+        gas_price: U256::from(1000),
+        block_excess_blob_gas: None,
+        block_blob_gas_used: None,
+        tx_blob_hashes: vec![],
+        tx_max_priority_fee_per_gas: None,
+        tx_max_fee_per_gas: Some(U256::from(1000)),
+        tx_max_fee_per_blob_gas: None,
+        tx_nonce: 0,
+        block_gas_limit: GAS_LIMIT * 2,
+        is_privileged: false,
+    }
+}
+
+fn transaction(to: u64) -> Transaction {
+    Transaction::EIP1559Transaction(EIP1559Transaction {
+        to: TxKind::Call(address(to)),
+        gas_limit: GAS_LIMIT,
+        max_fee_per_gas: 1000,
+        max_priority_fee_per_gas: 1,
+        ..Default::default()
+    })
+}
+
+/// Executes a transaction from `SENDER` to `to`.
+/// Returns the report, the top call trace and the value at slot 0 of `DELEGATED`.
+fn execute(to: u64, accounts: Vec<(u64, Bytes)>) -> (ExecutionReport, CallTraceFrame, U256) {
+    let mut db = database(accounts);
+    let tx = transaction(to);
+    let mut vm = VM::new(
+        environment(),
+        &mut db,
+        &tx,
+        LevmCallTracer::new(false, false),
+        VMType::L1,
+    )
+    .unwrap();
+    let report = vm.execute().unwrap();
+    assert!(report.is_success());
+    let trace = vm.tracer.callframes.pop().unwrap();
+    let stored = vm
+        .db
+        .get_account(address(DELEGATED))
+        .unwrap()
+        .storage
+        .get(&H256::zero())
+        .copied()
+        .unwrap_or_default();
+    (report, trace, stored)
+}
+
+// ==================== Tests ====================
+
+#[test]
+fn call_into_delegated_account_runs_target_code() {
+    let (_, trace, stored) = execute(
+        CALLER,
+        vec![
+            (CALLER, call_bytecode(DELEGATED)),
+            (DELEGATED, designation(TARGET)),
+            (TARGET, store_bytecode()),
+        ],
+    );
+
+    assert_eq!(stored, U256::one());
+    let call = &trace.calls[0];
+    assert_eq!(call.to, address(DELEGATED));
+    assert_eq!(call.delegated_to, Some(address(TARGET)));
+    assert!(call.error.is_none());
+    assert_eq!(trace.delegated_to, None);
+}
+
+#[test]
+fn transaction_to_delegated_account_is_annotated() {
+    let (_, trace, stored) = execute(
+        DELEGATED,
+        vec![(DELEGATED, designation(TARGET)), (TARGET, store_bytecode())],
+    );
+
+    assert_eq!(stored, U256::one());
+    assert_eq!(trace.to, address(DELEGATED));
+    assert_eq!(trace.delegated_to, Some(address(TARGET)));
+}
+
+#[test]
+fn delegated_call_charges_target_access() {
+    let (delegated, _, _) = execute(
+        CALLER,
+        vec![
+            (CALLER, call_bytecode(DELEGATED)),
+            (DELEGATED, designation(TARGET)),
+            (TARGET, store_bytecode()),
+        ],
+    );
+    let (direct, _, _) = execute(
+        CALLER,
+        vec![(CALLER, call_bytecode(TARGET)), (TARGET, store_bytecode())],
+    );
+
+    // Both run the same code, but the delegated call also loads the cold target
+    assert_eq!(
+        delegated.gas_used - direct.gas_used,
+        COLD_ADDRESS_ACCESS_COST
+    );
+}
+
+#[test]
+fn chained_delegation_is_not_followed() {
+    let (_, trace, stored) = execute(
+        CALLER,
+        vec![
+            (CALLER, call_bytecode(DELEGATED)),
+            (DELEGATED, designation(DELEGATED_TO_DELEGATED)),
+            (DELEGATED_TO_DELEGATED, designation(TARGET)),
+            (TARGET, store_bytecode()),
+        ],
+    );
+
+    // The designation of the first target runs as code, halting on its 0xef prefix
+    assert_eq!(stored, U256::zero());
+    let call = &trace.calls[0];
+    assert_eq!(call.delegated_to, Some(address(DELEGATED_TO_DELEGATED)));
+    assert!(call.error.is_some());
+}
+
+#[test]
+fn resolved_delegation_is_refreshed_when_code_changes() {
+    let mut db = database(vec![
+        (DELEGATED, designation(TARGET)),
+        (TARGET, store_bytecode()),
+    ]);
+    let tx = transaction(DELEGATED);
+    let mut vm = VM::new(
+        environment(),
+        &mut db,
+        &tx,
+        LevmCallTracer::disabled(),
+        VMType::L1,
+    )
+    .unwrap();
+    let delegated = address(DELEGATED);
+
+    assert_eq!(
+        vm.resolve_delegation(delegated).unwrap(),
+        DelegationTarget::Delegated(address(TARGET))
+    );
+
+    // An authorization of the transaction rewrites the delegation
+    vm.update_account_bytecode(delegated, Code::from_bytecode(designation(OTHER_TARGET)))
+        .unwrap();
+    assert_eq!(
+        vm.resolve_delegation(delegated).unwrap(),
+        DelegationTarget::Delegated(address(OTHER_TARGET))
+    );
+
+    // An authorization to the zero address clears it
+    vm.update_account_bytecode(delegated, Code::from_bytecode(Bytes::new()))
+        .unwrap();
+    assert_eq!(
+        vm.resolve_delegation(delegated).unwrap(),
+        DelegationTarget::NotDelegated
+    );
+}
@@ -13,88 +13,21 @@
 use bytes::Bytes;
 use ethrex_common::{
     Address, H256, U256,
-    constants::{EMPTY_TRIE_HASH, SYSTEM_ADDRESS},
-    types::{
-        Account, AccountState, ChainConfig, Code, CodeMetadata, EIP1559Transaction, Fork, Log,
-        Transaction, TxKind,
-    },
+    constants::SYSTEM_ADDRESS,
+    types::{Account, Code, EIP1559Transaction, Fork, Log, Transaction, TxKind},
 };
 use ethrex_levm::{
     constants::{SELFDESTRUCT_EVENT_TOPIC, TRANSFER_EVENT_TOPIC},
-    db::{Database, gen_db::GeneralizedDatabase},
+    db::gen_db::GeneralizedDatabase,
     environment::{EVMConfig, Environment},
-    errors::{DatabaseError, ExecutionReport},
+    errors::ExecutionReport,
     tracing::LevmCallTracer,
     vm::{VM, VMType},
 };
 use rustc_hash::FxHashMap;
 use std::sync::Arc;
 
-// ==================== Test Database Implementation ====================
-
-/// A simple in-memory database for testing
-struct TestDatabase {
-    accounts: FxHashMap<Address, Account>,
-}
-
-impl TestDatabase {
-    fn new() -> Self {
-        Self {
-            accounts: FxHashMap::default(),
-        }
-    }
-}
-
-impl Database for TestDatabase {
-    fn get_account_state(&self, address: Address) -> Result<AccountState, DatabaseError> {
-        Ok(self
-            .accounts
-            .get(&address)
-            .map(|acc| AccountState {
-                nonce: acc.info.nonce,
-                balance: acc.info.balance,
-                storage_root: *EMPTY_TRIE_HASH,
-                code_hash: acc.info.code_hash,
-            })
-            .unwrap_or_default())
-    }
-
-    fn get_storage_value(&self, address: Address, key: H256) -> Result<U256, DatabaseError> {
-        Ok(self
-            .accounts
-            .get(&address)
-            .and_then(|acc| acc.storage.get(&key).copied())
-            .unwrap_or_default())
-    }
-
-    fn get_block_hash(&self, _block_number: u64) -> Result<H256, DatabaseError> {
-        Ok(H256::zero())
-    }
-
-    fn get_chain_config(&self) -> Result<ChainConfig, DatabaseError> {
-        Ok(ChainConfig::default())
-    }
-
-    fn get_account_code(&self, code_hash: H256) -> Result<Code, DatabaseError> {
-        for acc in self.accounts.values() {
-            if acc.info.code_hash == code_hash {
-                return Ok(acc.code.clone());
-            }
-        }
-        Ok(Code::default())
-    }
-
-    fn get_code_metadata(&self, code_hash: H256) -> Result<CodeMetadata, DatabaseError> {
-        for acc in self.accounts.values() {
-            if acc.info.code_hash == code_hash {
-                return Ok(CodeMetadata {
-                    length: acc.code.bytecode.len() as u64,
-                });
-            }
-        }
-        Ok(CodeMetadata { length: 0 })
-    }
-}
+use super::TestDatabase;
 
 // ==================== Test Constants ====================
 
@@ -171,7 +104,7 @@ impl TestBuilder {
     }
 
     fn execute(self) -> ExecutionReport {
-        let test_db = TestDatabase::new();
+        let test_db = TestDatabase::default();
         let accounts_map: FxHashMap<Address, Account> = self.accounts.into_iter().collect();
         let mut db = GeneralizedDatabase::new_with_account_state(Arc::new(test_db), accounts_map);
 
@@ -982,7 +915,7 @@ fn test_created_contract_selfdestruct_to_self_emits_selfdestruct_log() {
 
     // The child contract address is deterministic based on factory address and nonce
     // Factory nonce is 1, so child = keccak256(rlp([factory, 1]))[12..]
-    let child_address = ethrex_common::evm::calculate_create_address(factory, 1);
+    let child_address = ethrex_common::evm::compute_create_address(factory, 1);
 
     // Init code that selfdestructs to itself (the child address)
     let init_code = selfdestruct_init_code(child_address);
@@ -1046,7 +979,7 @@ fn test_eth_received_after_selfdestruct_emits_closure_log() {
     let call_value = U256::from(500);
 
     // The child contract address
-    let child_address = ethrex_common::evm::calculate_create_address(factory, 1);
+    let child_address = ethrex_common::evm::compute_create_address(factory, 1);
 
     // Init code that selfdestructs to beneficiary (transferring away all balance)
     let init_code = selfdestruct_init_code(beneficiary);
@@ -1121,8 +1054,8 @@ fn test_closure_logs_lexicographical_order() {
 
     // Calculate child addresses based on factory nonce
     // First CREATE uses nonce 1, second uses nonce 2
-    let child1 = ethrex_common::evm::calculate_create_address(factory, 1);
-    let child2 = ethrex_common::evm::calculate_create_address(factory, 2);
+    let child1 = ethrex_common::evm::compute_create_address(factory, 1);
+    let child2 = ethrex_common::evm::compute_create_address(factory, 2);
 
     // Determine which address is lower (lexicographically first)
     let (lower_addr, higher_addr) = if child1 < child2 {
@@ -36,6 +36,9 @@ fn test_execution_report_has_both_gas_fields() {
         gas_refunded: 4800, // The refund amount
         output: Bytes::new(),
         logs: vec![],
+        max_depth: 0,
+        gas_breakdown: None,
+        l2_fees: None,
     };
 
     // Verify both fields are present and different
@@ -0,0 +1,157 @@
+//! Tests for deploy-time validation of EOF containers in LEVM.
+//!
+//! Key behaviors tested:
+//! - 0xEF-prefixed code is rejected unless EOF is enabled
+//! - With EOF enabled, valid containers are deployed and invalid ones rejected
+//! - The EOF gate follows `ChainConfig::eof_time`
+
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256,
+    types::{
+        Account, BlockHeader, ChainConfig, Code, EIP1559Transaction, Fork, Transaction, TxKind,
+    },
+};
+use ethrex_levm::{
+    db::gen_db::GeneralizedDatabase,
+    environment::{EVMConfig, Environment},
+    errors::{ExceptionalHalt, ExecutionReport, TxResult, VMError},
+    tracing::LevmCallTracer,
+    vm::{VM, VMType},
+};
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+use super::TestDatabase;
+
+// ==================== Test Constants ====================
+
+const SENDER: u64 = 0x1000;
+const GAS_LIMIT: u64 = 1_000_000;
+
+/// Minimal valid container: a single code section with STOP.
+const EOF_MINIMAL: &str = "ef0001 010004 0200010001 ff0000 00 00800000 00";
+/// Same container ending with NOP instead of STOP.
+const EOF_NO_TERMINATOR: &str = "ef0001 010004 0200010001 ff0000 00 00800000 5b";
+/// Initcode returning `EOF_MINIMAL` with RETURNCONTRACT.
+const EOF_INITCODE: &str = "ef0001 010004 0200010004 030001 00000014 ff0000 00 00800002 5f5fee00";
+
+// ==================== Helpers ====================
+
+fn eof(vector: &str) -> Vec<u8> {
+    hex::decode(vector.replace(' ', "")).unwrap()
+}
+
+/// Init code that returns `runtime` as the code to deploy.
+fn init_code(runtime: &[u8]) -> Bytes {
+    let runtime_len = u8::try_from(runtime.len()).unwrap();
+    // CODECOPY(0, 12, runtime_len), RETURN(0, runtime_len)
+    let mut bytecode = vec![0x60, runtime_len, 0x60, 0x0c, 0x60, 0x00, 0x39];
+    bytecode.extend_from_slice(&[0x60, runtime_len, 0x60, 0x00, 0xf3]);
+    bytecode.extend_from_slice(runtime);
+    Bytes::from(bytecode)
+}
+
+fn deploy(runtime: &[u8], eof: bool) -> ExecutionReport {
+    let sender = Address::from_low_u64_be(SENDER);
+    let accounts = FxHashMap::from_iter([(
+        sender,
+        Account::new(
+            U256::from(10_000_000_000u64),
+            Code::default(),
+            0,
+            FxHashMap::default(),
+        ),
+    )]);
+    let mut db =
+        GeneralizedDatabase::new_with_account_state(Arc::new(TestDatabase::default()), accounts);
+
+    let fork = Fork::Prague;
+    let env = Environment {
+        origin: sender,
+        gas_limit: GAS_LIMIT,
+        config: EVMConfig {
+            eof,
+            ..EVMConfig::new(fork, EVMConfig::canonical_values(fork))
+        },
+        block_number: U256::from(1),
+        coinbase: Address::from_low_u64_be(0xCCC),
+        timestamp: U256::from(1000),
+        prev_randao: Some(H256::zero()),
+        difficulty: U256::zero(),
+        slot_number: U256::zero(),
+        chain_id: U256::from(1),
+        base_fee_per_gas: U256::from(1000),
+        base_blob_fee_per_gas: U256::from(1),
+        gas_price: U256::from(1000),
+        block_excess_blob_gas: None,
+        block_blob_gas_used: None,
+        tx_blob_hashes: vec![],
+        tx_max_priority_fee_per_gas: None,
+        tx_max_fee_per_gas: Some(U256::from(1000)),
+        tx_max_fee_per_blob_gas: None,
+        tx_nonce: 0,
+        block_gas_limit: GAS_LIMIT * 2,
+        is_privileged: false,
+    };
+    let tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+        to: TxKind::Create,
+        data: init_code(runtime),
+        gas_limit: GAS_LIMIT,
+        max_fee_per_gas: 1000,
+        max_priority_fee_per_gas: 1,
+        ..Default::default()
+    });
+
+    let mut vm = VM::new(env, &mut db, &tx, LevmCallTracer::disabled(), VMType::L1).unwrap();
+    vm.execute().unwrap()
+}
+
+fn halted_with(report: &ExecutionReport, halt: ExceptionalHalt) -> bool {
+    report.result == TxResult::Revert(VMError::ExceptionalHalt(halt))
+}
+
+// ==================== Tests ====================
+
+#[test]
+fn eof_code_is_rejected_when_disabled() {
+    let report = deploy(&eof(EOF_MINIMAL), false);
+    assert!(halted_with(&report, ExceptionalHalt::InvalidContractPrefix));
+}
+
+#[test]
+fn valid_eof_code_is_deployed_when_enabled() {
+    let report = deploy(&eof(EOF_MINIMAL), true);
+    assert_eq!(report.result, TxResult::Success);
+}
+
+#[test]
+fn invalid_eof_code_is_rejected_when_enabled() {
+    let report = deploy(&eof(EOF_NO_TERMINATOR), true);
+    assert!(halted_with(&report, ExceptionalHalt::InvalidEofContainer));
+
+    // 0xEF without the EOF magic is still an invalid prefix
+    let report = deploy(&[0xef, 0x01, 0x00], true);
+    assert!(halted_with(&report, ExceptionalHalt::InvalidContractPrefix));
+
+    // Initcode can't be deployed as runtime code
+    let initcode = [eof(EOF_INITCODE), eof(EOF_MINIMAL)].concat();
+    let report = deploy(&initcode, true);
+    assert!(halted_with(&report, ExceptionalHalt::InvalidEofContainer));
+}
+
+#[test]
+fn eof_gate_follows_chain_config() {
+    let config = ChainConfig {
+        prague_time: Some(0),
+        eof_time: Some(10),
+        ..Default::default()
+    };
+    let header = |timestamp| BlockHeader {
+        timestamp,
+        ..Default::default()
+    };
+    assert!(!EVMConfig::new_from_chain_config(&config, &header(9)).eof);
+    assert!(EVMConfig::new_from_chain_config(&config, &header(10)).eof);
+    assert!(!EVMConfig::new_from_chain_config(&ChainConfig::default(), &header(10)).eof);
+}
@@ -0,0 +1,231 @@
+//! Tests for the per-category gas breakdown LEVM reports when enabled.
+//!
+//! Key behaviors tested:
+//! - The breakdown is absent unless enabled on the VM
+//! - Intrinsic, compute, memory expansion and storage gas land in their own buckets
+//! - CALL is charged net of the gas forwarded to the callee
+//! - Exceptional halts report the gas they burn
+//! - Code deposit is charged to storage
+//! - The charged buckets minus the refund match the gas spent
+
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256,
+    types::{Account, Code, EIP1559Transaction, Fork, Transaction, TxKind},
+};
+use ethrex_levm::{
+    db::gen_db::GeneralizedDatabase,
+    environment::{EVMConfig, Environment},
+    errors::ExecutionReport,
+    gas_breakdown::GasBreakdownByCategory,
+    tracing::LevmCallTracer,
+    vm::{VM, VMType},
+};
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+use super::TestDatabase;
+
+// ==================== Test Constants ====================
+
+const SENDER: u64 = 0x1000;
+const CONTRACT: u64 = 0x2000;
+const EMPTY_ACCOUNT: u64 = 0x3000;
+const GAS_LIMIT: u64 = 1_000_000;
+const TX_BASE_COST: u64 = 21_000;
+
+// ==================== Execution Helpers ====================
+
+/// Executes a transaction from `SENDER` with `CONTRACT` holding `code`.
+fn execute(to: TxKind, data: Bytes, code: Bytes, enable_breakdown: bool) -> ExecutionReport {
+    let sender = Address::from_low_u64_be(SENDER);
+    let mut accounts = FxHashMap::default();
+    accounts.insert(
+        sender,
+        Account::new(
+            U256::from(10_000_000_000u64),
+            Code::default(),
+            0,
+            FxHashMap::default(),
+        ),
+    );
+    accounts.insert(
+        Address::from_low_u64_be(CONTRACT),
+        Account::new(
+            U256::zero(),
+            Code::from_bytecode(code),
+            0,
+            FxHashMap::default(),
+        ),
+    );
+    let mut db =
+        GeneralizedDatabase::new_with_account_state(Arc::new(TestDatabase::default()), accounts);
+
+    let fork = Fork::Prague;
+    let env = Environment {
+        origin: sender,
+        gas_limit: GAS_LIMIT,
+        config: EVMConfig::new(fork, EVMConfig::canonical_values(fork)),
+        block_number: U256::from(1),
+        coinbase: Address::from_low_u64_be(0xCCC),
+        timestamp: U256::from(1000),
+        prev_randao: Some(H256::zero()),
+        difficulty: U256::zero(),
+        slot_number: U256::zero(),
+        chain_id: U256::from(1),
+        base_fee_per_gas: U256::from(1000),
+        base_blob_fee_per_gas: U256::from(1),
+        gas_price: U256::from(1000),
+        block_excess_blob_gas: None,
+        block_blob_gas_used: None,
+        tx_blob_hashes: vec![],
+        tx_max_priority_fee_per_gas: None,
+        tx_max_fee_per_gas: Some(U256::from(1000)),
+        tx_max_fee_per_blob_gas: None,
+        tx_nonce: 0,
+        block_gas_limit: GAS_LIMIT * 2,
+        is_privileged: false,
+    };
+
+    let tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+        to,
+        data,
+        gas_limit: GAS_LIMIT,
+        max_fee_per_gas: 1000,
+        max_priority_fee_per_gas: 1,
+        ..Default::default()
+    });
+
+    let mut vm = VM::new(env, &mut db, &tx, LevmCallTracer::disabled(), VMType::L1).unwrap();
+    if enable_breakdown {
+        vm.enable_gas_breakdown();
+    }
+    vm.execute().unwrap()
+}
+
+/// Calls `CONTRACT` with the gas breakdown enabled and returns it along with the report.
+fn call(code: Vec<u8>) -> (ExecutionReport, GasBreakdownByCategory) {
+    let report = execute(
+        TxKind::Call(Address::from_low_u64_be(CONTRACT)),
+        Bytes::new(),
+        Bytes::from(code),
+        true,
+    );
+    let breakdown = report.gas_breakdown.unwrap();
+    assert_eq!(
+        breakdown.charged() - breakdown.refunded,
+        report.gas_spent,
+        "breakdown doesn't add up to the gas spent: {breakdown:?}"
+    );
+    (report, breakdown)
+}
+
+// ==================== Tests ====================
+
+#[test]
+fn breakdown_is_absent_unless_enabled() {
+    let report = execute(
+        TxKind::Call(Address::from_low_u64_be(CONTRACT)),
+        Bytes::new(),
+        Bytes::from(vec![0x00]),
+        false,
+    );
+
+    assert!(report.gas_breakdown.is_none());
+}
+
+#[test]
+fn plain_call_only_pays_intrinsic_gas() {
+    let (_, breakdown) = call(vec![0x00]); // STOP
+
+    assert_eq!(
+        breakdown,
+        GasBreakdownByCategory {
+            intrinsic: TX_BASE_COST,
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn arithmetic_is_charged_to_compute() {
+    // PUSH1 1, PUSH1 2, ADD, POP, STOP
+    let (_, breakdown) = call(vec![0x60, 0x01, 0x60, 0x02, 0x01, 0x50, 0x00]);
+
+    assert_eq!(breakdown.compute, 3 + 3 + 3 + 2);
+    assert_eq!(breakdown.memory, 0);
+    assert_eq!(breakdown.storage, 0);
+}
+
+#[test]
+fn memory_expansion_is_split_from_the_opcode() {
+    // PUSH1 0x2a, PUSH1 0x20, MSTORE, STOP: expands memory to two words
+    let (_, breakdown) = call(vec![0x60, 0x2a, 0x60, 0x20, 0x52, 0x00]);
+
+    assert_eq!(breakdown.memory, 2 * 3);
+    assert_eq!(breakdown.compute, 3 + 3 + 3);
+}
+
+#[test]
+fn storage_access_is_charged_to_storage() {
+    // PUSH1 1, PUSH1 0, SSTORE, PUSH1 0, SLOAD, POP, STOP
+    let (_, breakdown) = call(vec![
+        0x60, 0x01, 0x60, 0x00, 0x55, 0x60, 0x00, 0x54, 0x50, 0x00,
+    ]);
+
+    // Cold SSTORE setting a zero slot, then warm SLOAD
+    assert_eq!(breakdown.storage, 2_100 + 20_000 + 100);
+    assert_eq!(breakdown.compute, 3 * 3 + 2);
+    assert_eq!(breakdown.refunded, 0);
+}
+
+#[test]
+fn cleared_slot_refund_is_reported() {
+    // PUSH1 1, PUSH1 0, SSTORE, PUSH1 0, PUSH1 0, SSTORE, STOP
+    let (report, breakdown) = call(vec![
+        0x60, 0x01, 0x60, 0x00, 0x55, 0x60, 0x00, 0x60, 0x00, 0x55, 0x00,
+    ]);
+
+    assert_eq!(breakdown.storage, 2_100 + 20_000 + 100);
+    // The 19_900 refund is capped at a fifth of the gas charged
+    assert_eq!(breakdown.refunded, breakdown.charged() / 5);
+    assert_eq!(breakdown.refunded, report.gas_refunded);
+}
+
+#[test]
+fn call_is_charged_net_of_forwarded_gas() {
+    let mut code = vec![0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00]; // ret, args, value
+    code.push(0x73); // PUSH20
+    code.extend_from_slice(Address::from_low_u64_be(EMPTY_ACCOUNT).as_bytes());
+    code.extend_from_slice(&[0x5a, 0xf1, 0x50, 0x00]); // GAS, CALL, POP, STOP
+    let (_, breakdown) = call(code);
+
+    // Cold account access, the callee has no code so all forwarded gas comes back
+    assert_eq!(breakdown.calls, 2_600);
+    assert_eq!(breakdown.compute, 6 * 3 + 2 + 2);
+}
+
+#[test]
+fn exceptional_halt_reports_burnt_gas() {
+    // PUSH1 1, INVALID
+    let (report, breakdown) = call(vec![0x60, 0x01, 0xfe]);
+
+    assert!(!report.is_success());
+    assert_eq!(breakdown.compute, 3);
+    assert_eq!(breakdown.halted, GAS_LIMIT - TX_BASE_COST - 3);
+    assert_eq!(report.gas_spent, GAS_LIMIT);
+}
+
+#[test]
+fn code_deposit_is_charged_to_storage() {
+    // PUSH1 1, PUSH1 0, RETURN: deploys a single zero byte
+    let init_code = Bytes::from(vec![0x60, 0x01, 0x60, 0x00, 0xf3]);
+    let report = execute(TxKind::Create, init_code, Bytes::new(), true);
+    let breakdown = report.gas_breakdown.unwrap();
+
+    assert!(report.is_success());
+    assert_eq!(breakdown.storage, 200);
+    assert_eq!(breakdown.memory, 3);
+    assert_eq!(breakdown.compute, 3 + 3);
+    assert_eq!(breakdown.charged(), report.gas_spent);
+}
@@ -0,0 +1,538 @@
+//! Tests for the standalone intrinsic gas calculation in LEVM.
+//!
+//! Key behaviors tested:
+//! - Each component matches the EIPs for every transaction type, across forks
+//! - Calldata costs 68 per non-zero byte before Istanbul and creations pay no base cost
+//!   before Homestead
+//! - The EIP-7623 floor only applies since Prague and raises the minimum gas limit
+//! - A `GenericTransaction` is priced like the `Transaction` it converts from
+//! - The default hook charges exactly the standalone intrinsic gas, for randomized
+//!   transactions
+//! - The VM rejects transactions with a gas limit one below the minimum
+
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256,
+    types::{
+        Account, AuthorizationTuple, Code, EIP1559Transaction, EIP2930Transaction,
+        EIP4844Transaction, EIP7702Transaction, Fork, GenericTransaction, LegacyTransaction,
+        Transaction, TxKind,
+    },
+};
+use ethrex_levm::{
+    db::gen_db::GeneralizedDatabase,
+    environment::{EVMConfig, Environment},
+    errors::{ExecutionReport, TxValidationError, VMError},
+    gas_cost::{IntrinsicGas, intrinsic_gas},
+    tracing::LevmCallTracer,
+    vm::{VM, VMType},
+};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+use super::TestDatabase;
+
+// ==================== Test Constants ====================
+
+const SENDER: u64 = 0x1000;
+const RECIPIENT: u64 = 0x2000;
+const COINBASE: u64 = 0xCCC;
+const GAS_PRICE: u64 = 1000;
+const BLOCK_GAS_LIMIT: u64 = 30_000_000;
+
+/// Two zero and three non-zero bytes.
+const CALLDATA: [u8; 5] = [0x00, 0x01, 0x00, 0x02, 0x03];
+
+// ==================== Transaction Helpers ====================
+
+fn recipient() -> TxKind {
+    TxKind::Call(Address::from_low_u64_be(RECIPIENT))
+}
+
+fn access_list(entries: usize, keys_per_entry: usize) -> Vec<(Address, Vec<H256>)> {
+    (0..entries)
+        .map(|i| {
+            (
+                Address::from_low_u64_be(0x3000 + i as u64),
+                (0..keys_per_entry)
+                    .map(|key| H256::from_low_u64_be(key as u64))
+                    .collect(),
+            )
+        })
+        .collect()
+}
+
+fn authorization_list(len: usize) -> Vec<AuthorizationTuple> {
+    (0..len)
+        .map(|i| AuthorizationTuple {
+            chain_id: U256::from(1),
+            address: Address::from_low_u64_be(0x4000 + i as u64),
+            nonce: 0,
+            y_parity: U256::zero(),
+            r_signature: U256::from(1),
+            s_signature: U256::from(1),
+        })
+        .collect()
+}
+
+/// Fields the intrinsic gas depends on, so that any transaction type can be built from
+/// them.
+#[derive(Debug, Clone, Default)]
+struct TxParts {
+    data: Vec<u8>,
+    create: bool,
+    access_list: Vec<(Address, Vec<H256>)>,
+    authorization_list: Vec<AuthorizationTuple>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum TxType {
+    Legacy,
+    EIP2930,
+    EIP1559,
+    EIP4844,
+    EIP7702,
+}
+
+fn build_tx(tx_type: TxType, parts: &TxParts, nonce: u64, gas_limit: u64) -> Transaction {
+    let to = if parts.create {
+        TxKind::Create
+    } else {
+        recipient()
+    };
+    let data = Bytes::from(parts.data.clone());
+    let access_list = parts.access_list.clone();
+    match tx_type {
+        TxType::Legacy => Transaction::LegacyTransaction(LegacyTransaction {
+            nonce,
+            gas_price: U256::from(GAS_PRICE),
+            gas: gas_limit,
+            to,
+            data,
+            ..Default::default()
+        }),
+        TxType::EIP2930 => Transaction::EIP2930Transaction(EIP2930Transaction {
+            chain_id: 1,
+            nonce,
+            gas_price: U256::from(GAS_PRICE),
+            gas_limit,
+            to,
+            data,
+            access_list,
+            ..Default::default()
+        }),
+        TxType::EIP1559 => Transaction::EIP1559Transaction(EIP1559Transaction {
+            chain_id: 1,
+            nonce,
+            max_fee_per_gas: GAS_PRICE,
+            max_priority_fee_per_gas: 1,
+            gas_limit,
+            to,
+            data,
+            access_list,
+            ..Default::default()
+        }),
+        TxType::EIP4844 => Transaction::EIP4844Transaction(EIP4844Transaction {
+            chain_id: 1,
+            nonce,
+            max_fee_per_gas: GAS_PRICE,
+            max_priority_fee_per_gas: 1,
+            gas: gas_limit,
+            to: Address::from_low_u64_be(RECIPIENT),
+            data,
+            access_list,
+            blob_versioned_hashes: vec![H256::from_low_u64_be(1)],
+            ..Default::default()
+        }),
+        TxType::EIP7702 => Transaction::EIP7702Transaction(EIP7702Transaction {
+            chain_id: 1,
+            nonce,
+            max_fee_per_gas: GAS_PRICE,
+            max_priority_fee_per_gas: 1,
+            gas_limit,
+            to: Address::from_low_u64_be(RECIPIENT),
+            data,
+            access_list,
+            authorization_list: parts.authorization_list.clone(),
+            ..Default::default()
+        }),
+    }
+}
+
+// ==================== Execution Helpers ====================
+
+fn new_db() -> GeneralizedDatabase {
+    let mut accounts = FxHashMap::default();
+    accounts.insert(
+        Address::from_low_u64_be(SENDER),
+        Account::new(
+            U256::from(10_000_000_000_000u64),
+            Code::default(),
+            0,
+            FxHashMap::default(),
+        ),
+    );
+    GeneralizedDatabase::new_with_account_state(Arc::new(TestDatabase::default()), accounts)
+}
+
+fn environment(fork: Fork, tx: &Transaction) -> Environment {
+    Environment {
+        origin: Address::from_low_u64_be(SENDER),
+        gas_limit: tx.gas_limit(),
+        config: EVMConfig::new(fork, EVMConfig::canonical_values(fork)),
+        block_number: U256::from(1),
+        coinbase: Address::from_low_u64_be(COINBASE),
+        timestamp: U256::from(1000),
+        prev_randao: Some(H256::zero()),
+        difficulty: U256::zero(),
+        slot_number: U256::zero(),
+        chain_id: U256::from(1),
+        base_fee_per_gas: U256::from(GAS_PRICE),
+        base_blob_fee_per_gas: U256::from(1),
+        gas_price: U256::from(GAS_PRICE),
+        block_excess_blob_gas: None,
+        block_blob_gas_used: None,
+        tx_blob_hashes: vec![],
+        tx_max_priority_fee_per_gas: tx.max_priority_fee().map(U256::from),
+        tx_max_fee_per_gas: tx.max_fee_per_gas().map(U256::from),
+        tx_max_fee_per_blob_gas: None,
+        tx_nonce: tx.nonce(),
+        block_gas_limit: BLOCK_GAS_LIMIT,
+        is_privileged: false,
+    }
+}
+
+/// Executes `tx` on `fork` with the gas breakdown enabled.
+fn execute(
+    db: &mut GeneralizedDatabase,
+    fork: Fork,
+    tx: &Transaction,
+) -> Result<ExecutionReport, VMError> {
+    let mut vm = VM::new(
+        environment(fork, tx),
+        db,
+        tx,
+        LevmCallTracer::disabled(),
+        VMType::L1,
+    )?;
+    vm.enable_gas_breakdown();
+    vm.execute()
+}
+
+// ==================== Expected Values ====================
+
+struct Case {
+    name: &'static str,
+    tx_type: TxType,
+    parts: TxParts,
+    fork: Fork,
+    expected: IntrinsicGas,
+}
+
+fn expected(
+    calldata: u64,
+    access_list: u64,
+    auth_list: u64,
+    creation: u64,
+    floor: u64,
+) -> IntrinsicGas {
+    IntrinsicGas {
+        base: 21_000,
+        calldata,
+        access_list,
+        auth_list,
+        creation,
+        floor,
+        total: 21_000 + calldata + access_list + auth_list + creation,
+    }
+}
+
+/// Expected values worked out from the EIPs, as the EF tests price these transactions.
+fn cases() -> Vec<Case> {
+    let calldata = || TxParts {
+        data: CALLDATA.to_vec(),
+        ..Default::default()
+    };
+    // 33 non-zero bytes, two words of init code
+    let init_code = || TxParts {
+        data: vec![0x01; 33],
+        create: true,
+        ..Default::default()
+    };
+    let with_access_list = || TxParts {
+        data: CALLDATA.to_vec(),
+        access_list: access_list(2, 3),
+        ..Default::default()
+    };
+    // Zero bytes cost 4 gas and a token, non-zero ones 68 or 16 gas and 4 tokens
+    let calldata_frontier = 2 * 4 + 3 * 68;
+    let calldata_istanbul = 2 * 4 + 3 * 16;
+    let calldata_floor = 21_000 + 10 * (2 + 3 * 4);
+    let access_list_cost = 2 * 2400 + 6 * 1900;
+
+    vec![
+        Case {
+            name: "legacy call",
+            tx_type: TxType::Legacy,
+            parts: calldata(),
+            fork: Fork::Frontier,
+            expected: expected(calldata_frontier, 0, 0, 0, 0),
+        },
+        Case {
+            name: "legacy call",
+            tx_type: TxType::Legacy,
+            parts: calldata(),
+            fork: Fork::Istanbul,
+            expected: expected(calldata_istanbul, 0, 0, 0, 0),
+        },
+        Case {
+            name: "legacy call",
+            tx_type: TxType::Legacy,
+            parts: calldata(),
+            fork: Fork::Prague,
+            expected: expected(calldata_istanbul, 0, 0, 0, calldata_floor),
+        },
+        Case {
+            name: "legacy create",
+            tx_type: TxType::Legacy,
+            parts: init_code(),
+            fork: Fork::Frontier,
+            expected: expected(33 * 68, 0, 0, 0, 0),
+        },
+        Case {
+            name: "legacy create",
+            tx_type: TxType::Legacy,
+            parts: init_code(),
+            fork: Fork::Istanbul,
+            expected: expected(33 * 16, 0, 0, 32_000, 0),
+        },
+        Case {
+            name: "legacy create",
+            tx_type: TxType::Legacy,
+            parts: init_code(),
+            fork: Fork::Shanghai,
+            expected: expected(33 * 16, 0, 0, 32_000 + 2 * 2, 0),
+        },
+        Case {
+            name: "legacy create",
+            tx_type: TxType::Legacy,
+            parts: init_code(),
+            fork: Fork::Amsterdam,
+            expected: expected(33 * 16, 0, 0, 32_000 + 2 * 2, 21_000 + 10 * 33 * 4),
+        },
+        Case {
+            name: "access list",
+            tx_type: TxType::EIP2930,
+            parts: with_access_list(),
+            fork: Fork::Berlin,
+            expected: expected(calldata_istanbul, access_list_cost, 0, 0, 0),
+        },
+        Case {
+            name: "dynamic fee",
+            tx_type: TxType::EIP1559,
+            parts: with_access_list(),
+            fork: Fork::Shanghai,
+            expected: expected(calldata_istanbul, access_list_cost, 0, 0, 0),
+        },
+        Case {
+            name: "dynamic fee create",
+            tx_type: TxType::EIP1559,
+            parts: TxParts {
+                access_list: access_list(1, 0),
+                ..init_code()
+            },
+            fork: Fork::Prague,
+            expected: expected(33 * 16, 2400, 0, 32_000 + 2 * 2, 21_000 + 10 * 33 * 4),
+        },
+        Case {
+            name: "blob",
+            tx_type: TxType::EIP4844,
+            parts: with_access_list(),
+            fork: Fork::Cancun,
+            expected: expected(calldata_istanbul, access_list_cost, 0, 0, 0),
+        },
+        Case {
+            name: "blob",
+            tx_type: TxType::EIP4844,
+            parts: calldata(),
+            fork: Fork::Prague,
+            expected: expected(calldata_istanbul, 0, 0, 0, calldata_floor),
+        },
+        Case {
+            name: "set code",
+            tx_type: TxType::EIP7702,
+            parts: TxParts {
+                authorization_list: authorization_list(2),
+                ..calldata()
+            },
+            fork: Fork::Prague,
+            expected: expected(calldata_istanbul, 0, 2 * 25_000, 0, calldata_floor),
+        },
+        Case {
+            name: "set code",
+            tx_type: TxType::EIP7702,
+            parts: TxParts {
+                access_list: access_list(2, 3),
+                authorization_list: authorization_list(1),
+                ..Default::default()
+            },
+            fork: Fork::Amsterdam,
+            expected: expected(0, access_list_cost, 25_000, 0, 21_000),
+        },
+    ]
+}
+
+// ==================== Tests ====================
+
+#[test]
+fn components_match_the_eips_per_tx_type_and_fork() {
+    for case in cases() {
+        let tx = build_tx(case.tx_type, &case.parts, 0, 1_000_000);
+        let intrinsic = intrinsic_gas((&tx).into(), case.fork).unwrap();
+        assert_eq!(
+            intrinsic, case.expected,
+            "{} ({:?}) on {:?}",
+            case.name, case.tx_type, case.fork
+        );
+    }
+}
+
+#[test]
+fn floor_raises_min_gas_limit_since_prague() {
+    // 100 non-zero bytes: 1600 gas of calldata but 400 tokens of floor
+    let parts = TxParts {
+        data: vec![0xff; 100],
+        ..Default::default()
+    };
+    let tx = build_tx(TxType::EIP1559, &parts, 0, 1_000_000);
+
+    let cancun = intrinsic_gas((&tx).into(), Fork::Cancun).unwrap();
+    assert_eq!(cancun.floor, 0);
+    assert_eq!(cancun.min_gas_limit(), 22_600);
+
+    let prague = intrinsic_gas((&tx).into(), Fork::Prague).unwrap();
+    assert_eq!(prague.total, 22_600);
+    assert_eq!(prague.floor, 25_000);
+    assert_eq!(prague.min_gas_limit(), 25_000);
+}
+
+#[test]
+fn generic_transaction_is_priced_like_the_transaction() {
+    for case in cases() {
+        let tx = build_tx(case.tx_type, &case.parts, 0, 1_000_000);
+        let generic = GenericTransaction::from(tx.clone());
+        assert_eq!(
+            intrinsic_gas((&generic).into(), case.fork).unwrap(),
+            intrinsic_gas((&tx).into(), case.fork).unwrap(),
+            "{} ({:?}) on {:?}",
+            case.name,
+            case.tx_type,
+            case.fork
+        );
+    }
+}
+
+#[test]
+fn hook_charges_the_standalone_intrinsic_gas() {
+    let forks = [
+        Fork::Berlin,
+        Fork::London,
+        Fork::Shanghai,
+        Fork::Cancun,
+        Fork::Prague,
+        Fork::Osaka,
+    ];
+    let mut rng = StdRng::seed_from_u64(0x2190);
+    let mut db = new_db();
+
+    for nonce in 0..200 {
+        let fork = forks[rng.gen_range(0..forks.len())];
+        let mut tx_types = vec![TxType::Legacy, TxType::EIP2930];
+        if fork >= Fork::London {
+            tx_types.push(TxType::EIP1559);
+        }
+        if fork >= Fork::Prague {
+            tx_types.push(TxType::EIP7702);
+        }
+        let tx_type = tx_types[rng.gen_range(0..tx_types.len())];
+
+        let mut parts = TxParts {
+            data: (0..rng.gen_range(0..200))
+                .map(|_| {
+                    if rng.gen_bool(0.5) {
+                        0
+                    } else {
+                        rng.gen_range(1..=u8::MAX)
+                    }
+                })
+                .collect(),
+            create: !matches!(tx_type, TxType::EIP7702) && rng.gen_bool(0.3),
+            ..Default::default()
+        };
+        if parts.create {
+            // Init code that halts right away and deploys nothing
+            if let Some(opcode) = parts.data.first_mut() {
+                *opcode = 0x00;
+            }
+        }
+        if !matches!(tx_type, TxType::Legacy) {
+            parts.access_list = access_list(rng.gen_range(0..3), rng.gen_range(0..4));
+        }
+        if matches!(tx_type, TxType::EIP7702) {
+            parts.authorization_list = authorization_list(rng.gen_range(1..=3));
+        }
+
+        let unpriced = build_tx(tx_type, &parts, nonce, 0);
+        let intrinsic = intrinsic_gas((&unpriced).into(), fork).unwrap();
+        let tx = build_tx(tx_type, &parts, nonce, intrinsic.min_gas_limit() + 50_000);
+
+        let report = execute(&mut db, fork, &tx).unwrap();
+        assert_eq!(
+            report.gas_breakdown.unwrap().intrinsic,
+            intrinsic.total,
+            "{tx_type:?} on {fork:?}: {parts:?}"
+        );
+    }
+}
+
+#[test]
+fn vm_rejects_gas_limit_below_min_gas_limit() {
+    let floor_bound = TxParts {
+        data: vec![0xff; 100],
+        ..Default::default()
+    };
+    let intrinsic_bound = TxParts {
+        data: CALLDATA.to_vec(),
+        access_list: access_list(2, 3),
+        ..Default::default()
+    };
+
+    for (parts, fork) in [
+        (&floor_bound, Fork::Prague),
+        (&intrinsic_bound, Fork::Prague),
+        (&floor_bound, Fork::Cancun),
+        (&intrinsic_bound, Fork::Shanghai),
+    ] {
+        let unpriced = build_tx(TxType::EIP1559, parts, 0, 0);
+        let min_gas_limit = intrinsic_gas((&unpriced).into(), fork)
+            .unwrap()
+            .min_gas_limit();
+
+        let tx = build_tx(TxType::EIP1559, parts, 0, min_gas_limit - 1);
+        let result = execute(&mut new_db(), fork, &tx);
+        assert!(
+            matches!(
+                result,
+                Err(VMError::TxValidation(
+                    TxValidationError::IntrinsicGasTooLow
+                        | TxValidationError::IntrinsicGasBelowFloorGasCost
+                ))
+            ),
+            "{fork:?}: {result:?}"
+        );
+
+        let tx = build_tx(TxType::EIP1559, parts, 0, min_gas_limit);
+        assert!(execute(&mut new_db(), fork, &tx).unwrap().is_success());
+    }
+}
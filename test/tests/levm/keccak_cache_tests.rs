@@ -0,0 +1,265 @@
+//! Tests for the block-wide KECCAK256 cache of `CachingDatabase`.
+//!
+//! Key behaviors tested:
+//! - Cached hashes match hashing the preimage, only 32 and 64-byte preimages are cached
+//! - The cache stops growing at its entry and byte bounds
+//! - The cache is only used when requested, `Evm::warm_cache` leaves it disabled
+//! - Executing a block through the cache gives the same receipts and state as without it
+//! - The executor's cacheable hashes and hits are counted, including preimages the warmer hashed
+
+use std::{fs::File, io::BufReader, path::PathBuf};
+
+use bytes::Bytes;
+use ethrex_blockchain::vm::StoreVmDatabase;
+use ethrex_common::{
+    Address, H256, U256,
+    types::{
+        AccountUpdate, Block, BlockBody, EIP1559Transaction, Genesis, GenesisAccount, Receipt,
+        Transaction, TxKind,
+    },
+};
+use ethrex_crypto::keccak::keccak_hash;
+use ethrex_l2_rpc::signer::{LocalSigner, Signable, Signer};
+use ethrex_levm::{
+    db::{KeccakCache, KeccakCacheLimits},
+    vm::VMType,
+};
+use ethrex_storage::{EngineType, Store};
+use ethrex_vm::{Evm, SimulatedHeader, SimulationChain, backends::levm::LEVM};
+use secp256k1::SecretKey;
+
+// Increments the mapping entries of the caller and of the address given in
+// calldata, like the balance updates of a token transfer:
+// mstore(0, caller); mstore(32, 0); s = keccak(0, 64); sstore(s, sload(s) + 1)
+// mstore(0, calldataload(0)); s = keccak(0, 64); sstore(s, sload(s) + 1)
+const MAPPING_CODE: &str = concat!(
+    "33600052",
+    "6000602052",
+    "6040600020",
+    "80546001019055",
+    "600035600052",
+    "6040600020",
+    "80546001019055",
+    "00",
+);
+const SENDERS: u64 = 4;
+const TXS_PER_SENDER: u64 = 2;
+/// Preimages hashed by each transaction.
+const HASHES_PER_TX: u64 = 2;
+
+#[test]
+fn cached_hashes_match_uncached() {
+    let cache = KeccakCache::new(KeccakCacheLimits::default());
+    let word = [0x11; 32];
+    let pair = [0x22; 64];
+
+    assert_eq!(cache.hash(&word), Some((keccak_hash(word), false)));
+    assert_eq!(cache.hash(&word), Some((keccak_hash(word), true)));
+    assert_eq!(cache.hash(&pair), Some((keccak_hash(pair), false)));
+    assert_eq!(cache.hash(&pair), Some((keccak_hash(pair), true)));
+    assert_eq!(cache.len(), 2);
+    assert_eq!(cache.size_bytes(), 64 + 96);
+
+    // Other sizes aren't cached
+    assert_eq!(cache.hash(&[0x33; 31]), None);
+    assert_eq!(cache.hash(&[]), None);
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn cache_is_bounded() {
+    let by_entries = KeccakCache::new(KeccakCacheLimits {
+        max_entries: 2,
+        max_bytes: usize::MAX,
+    });
+    for byte in 0..3 {
+        by_entries.hash(&[byte; 32]);
+    }
+    assert_eq!(by_entries.len(), 2);
+    // Full caches still hash
+    assert_eq!(
+        by_entries.hash(&[2; 32]),
+        Some((keccak_hash([2; 32]), false))
+    );
+    assert_eq!(
+        by_entries.hash(&[0; 32]),
+        Some((keccak_hash([0; 32]), true))
+    );
+
+    let by_bytes = KeccakCache::new(KeccakCacheLimits {
+        max_entries: usize::MAX,
+        max_bytes: 100,
+    });
+    by_bytes.hash(&[0; 64]);
+    by_bytes.hash(&[0; 32]);
+    assert_eq!(by_bytes.len(), 1);
+    assert_eq!(by_bytes.size_bytes(), 96);
+
+    let disabled = KeccakCache::new(KeccakCacheLimits::DISABLED);
+    assert!(!disabled.is_enabled());
+    assert_eq!(disabled.hash(&[0; 32]), None);
+    assert!(disabled.is_empty());
+}
+
+#[tokio::test]
+async fn cached_execution_matches_uncached() {
+    let (store, block) = setup().await;
+
+    let mut uncached = base_evm(&store);
+    let expected = execute(&mut uncached, &block);
+
+    let mut cached = base_evm(&store);
+    let cache = cached.warm_cache_with_keccak();
+    assert_eq!(execute(&mut cached, &block), expected);
+
+    // Every sender hashes its own entry in both its transactions, and all
+    // of them hash the shared recipient's.
+    let stats = cache.stats();
+    let lookups = SENDERS * TXS_PER_SENDER * HASHES_PER_TX;
+    assert_eq!(stats.keccak_lookups, lookups);
+    assert_eq!(stats.keccak_hits, lookups - (SENDERS + 1));
+    assert_eq!(cache.keccak_cache().len(), 5);
+}
+
+#[tokio::test]
+async fn warmer_populates_keccak_cache() {
+    let (store, block) = setup().await;
+
+    let mut uncached = base_evm(&store);
+    let expected = execute(&mut uncached, &block);
+
+    let mut evm = base_evm(&store);
+    let cache = evm.warm_cache_with_keccak();
+    let cache = LEVM::warm_block(&block, cache, VMType::L1).unwrap();
+    assert_eq!(cache.stats().keccak_lookups, 0);
+    assert_eq!(execute(&mut evm, &block), expected);
+
+    let stats = cache.stats();
+    assert_eq!(
+        stats.keccak_lookups,
+        SENDERS * TXS_PER_SENDER * HASHES_PER_TX
+    );
+    assert_eq!(stats.keccak_hits, stats.keccak_lookups);
+    assert_eq!(stats.keccak_hit_rate_percent(), 100);
+}
+
+#[tokio::test]
+async fn warm_cache_does_not_cache_keccak_by_default() {
+    let (store, block) = setup().await;
+
+    let mut uncached = base_evm(&store);
+    let expected = execute(&mut uncached, &block);
+
+    let mut evm = base_evm(&store);
+    let cache = evm.warm_cache();
+    assert_eq!(execute(&mut evm, &block), expected);
+
+    assert!(!cache.keccak_cache().is_enabled());
+    assert!(cache.keccak_cache().is_empty());
+    assert_eq!(cache.stats().keccak_lookups, 0);
+}
+
+// ==================== Helpers ====================
+
+/// Executes `block` and returns its receipts and state transitions, sorted by address.
+fn execute(evm: &mut Evm, block: &Block) -> (Vec<Receipt>, Vec<AccountUpdate>) {
+    let (result, _) = evm.execute_block(block).unwrap();
+    assert!(result.receipts.iter().all(|receipt| receipt.succeeded));
+    let mut updates = evm.get_state_transitions().unwrap();
+    updates.sort_by_key(|update| update.address);
+    (result.receipts, updates)
+}
+
+fn base_evm(store: &Store) -> Evm {
+    let genesis = store.get_block_header(0).unwrap().unwrap();
+    let vm_db = StoreVmDatabase::new(store.clone(), genesis).unwrap();
+    Evm::new_for_l1(vm_db)
+}
+
+fn workspace_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..")
+}
+
+/// Store with the mapping contract and a block where every sender calls it
+/// `TXS_PER_SENDER` times for the same recipient.
+async fn setup() -> (Store, Block) {
+    let file = File::open(workspace_root().join("fixtures/genesis/execution-api.json"))
+        .expect("Failed to open genesis file");
+    let reader = BufReader::new(file);
+    let mut genesis: Genesis =
+        serde_json::from_reader(reader).expect("Failed to deserialize genesis file");
+    let chain_id = genesis.config.chain_id;
+
+    let contract = Address::from_low_u64_be(0xe2c20);
+    genesis.alloc.insert(
+        contract,
+        GenesisAccount {
+            code: Bytes::from(hex::decode(MAPPING_CODE).unwrap()),
+            storage: Default::default(),
+            balance: U256::zero(),
+            nonce: 1,
+        },
+    );
+
+    let signers = signers();
+    for signer in &signers {
+        genesis.alloc.insert(
+            signer.address(),
+            GenesisAccount {
+                code: Bytes::new(),
+                storage: Default::default(),
+                balance: U256::from(10).pow(U256::from(20)),
+                nonce: 0,
+            },
+        );
+    }
+
+    let mut store =
+        Store::new("store.db", EngineType::InMemory).expect("Failed to build DB for testing");
+    store
+        .add_initial_state(genesis)
+        .await
+        .expect("Failed to add genesis state");
+
+    let recipient = H256::from(Address::from_low_u64_be(0xbeef));
+    let mut transactions = Vec::new();
+    for nonce in 0..TXS_PER_SENDER {
+        for signer in &signers {
+            let mut tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+                chain_id,
+                nonce,
+                to: TxKind::Call(contract),
+                data: Bytes::copy_from_slice(recipient.as_bytes()),
+                gas_limit: 200_000,
+                max_fee_per_gas: 10_000_000_000,
+                max_priority_fee_per_gas: 1_000_000_000,
+                ..Default::default()
+            });
+            tx.sign_inplace(signer).await.unwrap();
+            transactions.push(tx);
+        }
+    }
+
+    let genesis_header = store.get_block_header(0).unwrap().unwrap();
+    let header = SimulationChain::from_evm(&base_evm(&store), genesis_header)
+        .unwrap()
+        .next_header(&SimulatedHeader::default(), &transactions);
+    let block = Block::new(
+        header,
+        BlockBody {
+            transactions,
+            ommers: Vec::new(),
+            withdrawals: Some(Vec::new()),
+        },
+    );
+    (store, block)
+}
+
+fn signers() -> Vec<Signer> {
+    (1..=SENDERS)
+        .map(|i| {
+            let key = SecretKey::from_byte_array(&[i as u8; 32]).unwrap();
+            LocalSigner::new(key).into()
+        })
+        .collect()
+}
@@ -0,0 +1,335 @@
+//! Tests for the itemized fees the L2 hook reports on non-privileged transactions.
+//!
+//! Key behaviors tested:
+//! - The breakdown is absent on L1
+//! - The items add up to exactly what the sender paid, for randomized fee configs
+//! - Each vault and the coinbase receive exactly their item
+//! - Any randomized fee config accepted by `FeeConfig::validate` executes a transfer
+
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256,
+    constants::{GAS_PER_BLOB, SYSTEM_ADDRESS},
+    types::{
+        Account, ChainConfig, Code, EIP1559Transaction, Fork, Transaction, TxKind,
+        fee_config::{FeeConfig, L1FeeConfig, OperatorFeeConfig},
+    },
+};
+use ethrex_levm::{
+    db::gen_db::GeneralizedDatabase,
+    environment::{EVMConfig, Environment},
+    errors::ExecutionReport,
+    tracing::LevmCallTracer,
+    vm::{VM, VMType},
+};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+use super::TestDatabase;
+
+// ==================== Test Constants ====================
+
+const SENDER: u64 = 0x1000;
+const CONTRACT: u64 = 0x2000;
+const RECIPIENT: u64 = 0x3000;
+const COINBASE: u64 = 0xCCC;
+const BASE_FEE_VAULT: u64 = 0xB0_0000;
+const OPERATOR_FEE_VAULT: u64 = 0x0F_0000;
+const L1_FEE_VAULT: u64 = 0x1F_0000;
+const GAS_LIMIT: u64 = 1_000_000;
+const INITIAL_BALANCE: u128 = 10u128.pow(30);
+
+/// Sets a slot and clears it again, so the transaction gets a refund.
+/// PUSH1 1, PUSH1 0, SSTORE, PUSH1 0, PUSH1 0, SSTORE, STOP
+const SSTORE_AND_CLEAR: [u8; 11] = [
+    0x60, 0x01, 0x60, 0x00, 0x55, 0x60, 0x00, 0x60, 0x00, 0x55, 0x00,
+];
+
+// ==================== Execution Helpers ====================
+
+struct Execution {
+    report: ExecutionReport,
+    balances: FxHashMap<Address, U256>,
+}
+
+/// Environment of a transaction from `SENDER` paying `gas_price`.
+fn environment(base_fee_per_gas: u64, gas_price: u64) -> Environment {
+    let fork = Fork::Prague;
+    Environment {
+        origin: Address::from_low_u64_be(SENDER),
+        gas_limit: GAS_LIMIT,
+        config: EVMConfig::new(fork, EVMConfig::canonical_values(fork)),
+        block_number: U256::from(1),
+        coinbase: Address::from_low_u64_be(COINBASE),
+        timestamp: U256::from(1000),
+        prev_randao: Some(H256::zero()),
+        difficulty: U256::zero(),
+        slot_number: U256::zero(),
+        chain_id: U256::from(1),
+        base_fee_per_gas: U256::from(base_fee_per_gas),
+        base_blob_fee_per_gas: U256::from(1),
+        gas_price: U256::from(gas_price),
+        block_excess_blob_gas: None,
+        block_blob_gas_used: None,
+        tx_blob_hashes: vec![],
+        tx_max_priority_fee_per_gas: Some(U256::from(gas_price - base_fee_per_gas)),
+        tx_max_fee_per_gas: Some(U256::from(gas_price)),
+        tx_max_fee_per_blob_gas: None,
+        tx_nonce: 0,
+        block_gas_limit: GAS_LIMIT * 2,
+        is_privileged: false,
+    }
+}
+
+/// Executes a call to `CONTRACT` from `SENDER` paying `gas_price`.
+fn execute(vm_type: VMType, base_fee_per_gas: u64, gas_price: u64, calldata: Bytes) -> Execution {
+    let sender = Address::from_low_u64_be(SENDER);
+    let contract = Address::from_low_u64_be(CONTRACT);
+    let mut accounts = FxHashMap::default();
+    accounts.insert(
+        sender,
+        Account::new(
+            U256::from(INITIAL_BALANCE),
+            Code::default(),
+            0,
+            FxHashMap::default(),
+        ),
+    );
+    accounts.insert(
+        contract,
+        Account::new(
+            U256::zero(),
+            Code::from_bytecode(Bytes::from_static(&SSTORE_AND_CLEAR)),
+            0,
+            FxHashMap::default(),
+        ),
+    );
+    let mut db =
+        GeneralizedDatabase::new_with_account_state(Arc::new(TestDatabase::default()), accounts);
+
+    let env = environment(base_fee_per_gas, gas_price);
+
+    let tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+        to: TxKind::Call(contract),
+        data: calldata,
+        gas_limit: GAS_LIMIT,
+        max_fee_per_gas: gas_price,
+        max_priority_fee_per_gas: gas_price - base_fee_per_gas,
+        ..Default::default()
+    });
+
+    let report = VM::new(env, &mut db, &tx, LevmCallTracer::disabled(), vm_type)
+        .unwrap()
+        .execute()
+        .unwrap();
+
+    let balances = [
+        SENDER,
+        COINBASE,
+        BASE_FEE_VAULT,
+        OPERATOR_FEE_VAULT,
+        L1_FEE_VAULT,
+    ]
+    .into_iter()
+    .map(Address::from_low_u64_be)
+    .map(|address| (address, db.get_account(address).unwrap().info.balance))
+    .collect();
+
+    Execution { report, balances }
+}
+
+/// Sends one wei from `SENDER` to `RECIPIENT` paying `gas_price`, returning the report and
+/// the balance of the recipient.
+fn transfer(
+    fee_config: FeeConfig,
+    base_fee_per_gas: u64,
+    gas_price: u64,
+) -> (ExecutionReport, U256) {
+    let sender = Address::from_low_u64_be(SENDER);
+    let recipient = Address::from_low_u64_be(RECIPIENT);
+    let mut accounts = FxHashMap::default();
+    accounts.insert(
+        sender,
+        Account::new(
+            U256::from(INITIAL_BALANCE),
+            Code::default(),
+            0,
+            FxHashMap::default(),
+        ),
+    );
+    let mut db =
+        GeneralizedDatabase::new_with_account_state(Arc::new(TestDatabase::default()), accounts);
+
+    let tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+        to: TxKind::Call(recipient),
+        value: U256::one(),
+        gas_limit: GAS_LIMIT,
+        max_fee_per_gas: gas_price,
+        max_priority_fee_per_gas: gas_price - base_fee_per_gas,
+        ..Default::default()
+    });
+
+    let report = VM::new(
+        environment(base_fee_per_gas, gas_price),
+        &mut db,
+        &tx,
+        LevmCallTracer::disabled(),
+        VMType::L2(fee_config),
+    )
+    .unwrap()
+    .execute()
+    .unwrap_or_else(|err| panic!("{fee_config:?} failed: {err:?}"));
+    let received = db.get_account(recipient).unwrap().info.balance;
+    (report, received)
+}
+
+/// A random vault, sometimes one `FeeConfig::validate` rejects.
+fn random_vault(rng: &mut StdRng) -> Address {
+    match rng.gen_range(0..8) {
+        0 => Address::zero(),
+        1 => Address::from_low_u64_be(rng.gen_range(1..=0xffff)),
+        2 => SYSTEM_ADDRESS,
+        _ => Address::from(rng.r#gen::<[u8; 20]>()),
+    }
+}
+
+fn balance(execution: &Execution, address: u64) -> U256 {
+    execution.balances[&Address::from_low_u64_be(address)]
+}
+
+// ==================== Tests ====================
+
+#[test]
+fn l1_execution_has_no_fee_breakdown() {
+    let execution = execute(VMType::L1, 1000, 1500, Bytes::new());
+    assert!(execution.report.l2_fees.is_none());
+}
+
+#[test]
+fn fee_items_sum_to_sender_payment() {
+    let mut rng = StdRng::seed_from_u64(0x2147);
+
+    for _ in 0..64 {
+        let base_fee_per_gas = rng.gen_range(0..1_000_000_000u64);
+        let operator_fee_per_gas = rng.gen_range(0..1_000_000_000u64);
+        let tip = rng.gen_range(0..1_000_000_000u64);
+        let with_operator_fee = rng.gen_bool(0.5);
+        let with_l1_fee = rng.gen_bool(0.5);
+
+        let fee_config = FeeConfig {
+            base_fee_vault: rng
+                .gen_bool(0.5)
+                .then(|| Address::from_low_u64_be(BASE_FEE_VAULT)),
+            operator_fee_config: with_operator_fee.then(|| OperatorFeeConfig {
+                operator_fee_vault: Address::from_low_u64_be(OPERATOR_FEE_VAULT),
+                operator_fee_per_gas,
+            }),
+            l1_fee_config: with_l1_fee.then(|| L1FeeConfig {
+                l1_fee_vault: Address::from_low_u64_be(L1_FEE_VAULT),
+                l1_fee_per_blob_gas: rng.gen_range(0..1_000_000u64),
+            }),
+        };
+        let operator_fee_per_gas = if with_operator_fee {
+            operator_fee_per_gas
+        } else {
+            0
+        };
+        let gas_price = base_fee_per_gas + operator_fee_per_gas + tip;
+        let calldata: Bytes = (0..rng.gen_range(0..2048))
+            .map(|_| rng.r#gen::<u8>())
+            .collect();
+
+        let execution = execute(
+            VMType::L2(fee_config),
+            base_fee_per_gas,
+            gas_price,
+            calldata,
+        );
+        let fees = execution.report.l2_fees.expect("L2 fees are recorded");
+
+        let paid = U256::from(INITIAL_BALANCE) - balance(&execution, SENDER);
+        assert_eq!(fees.total(), Some(paid), "{fee_config:?}");
+        assert_eq!(
+            paid,
+            U256::from(execution.report.gas_spent) * U256::from(gas_price)
+        );
+        assert_eq!(
+            U256::from(fees.execution_gas + fees.l1_gas),
+            U256::from(execution.report.gas_spent)
+        );
+
+        assert_eq!(balance(&execution, COINBASE), fees.priority_fee);
+        assert_eq!(balance(&execution, OPERATOR_FEE_VAULT), fees.operator_fee);
+        assert_eq!(balance(&execution, L1_FEE_VAULT), fees.l1_data_fee);
+        if fee_config.base_fee_vault.is_some() {
+            assert_eq!(balance(&execution, BASE_FEE_VAULT), fees.base_fee);
+        } else {
+            assert!(balance(&execution, BASE_FEE_VAULT).is_zero());
+        }
+
+        if !with_operator_fee {
+            assert!(fees.operator_fee.is_zero());
+        }
+        if !with_l1_fee {
+            assert_eq!(fees.l1_gas, 0);
+            assert_eq!(fees.l1_data_size, 0);
+        }
+    }
+}
+
+#[test]
+fn valid_fee_configs_execute_transfers() {
+    let mut rng = StdRng::seed_from_u64(0x2188);
+    let chain_config = ChainConfig::default();
+    let max_l1_fee_per_blob_gas = u64::MAX / u64::from(GAS_PER_BLOB);
+    let mut executed = 0;
+
+    for _ in 0..256 {
+        let fee_config = FeeConfig {
+            base_fee_vault: rng.gen_bool(0.5).then(|| random_vault(&mut rng)),
+            operator_fee_config: rng.gen_bool(0.5).then(|| OperatorFeeConfig {
+                operator_fee_vault: random_vault(&mut rng),
+                operator_fee_per_gas: rng.gen_range(0..1_000_000_000_000u64),
+            }),
+            l1_fee_config: rng.gen_bool(0.5).then(|| L1FeeConfig {
+                l1_fee_vault: random_vault(&mut rng),
+                // Mostly realistic fees, sometimes up to the bound enforced by validation
+                l1_fee_per_blob_gas: if rng.gen_bool(0.9) {
+                    rng.gen_range(0..1_000_000u64)
+                } else {
+                    rng.gen_range(0..=max_l1_fee_per_blob_gas + 1)
+                },
+            }),
+        };
+        if fee_config.validate(&chain_config).is_err() {
+            continue;
+        }
+        executed += 1;
+
+        let base_fee_per_gas = rng.gen_range(1..1_000_000_000u64);
+        let operator_fee_per_gas = fee_config
+            .operator_fee_config
+            .map_or(0, |config| config.operator_fee_per_gas);
+        let gas_price = base_fee_per_gas + operator_fee_per_gas + rng.gen_range(0..1_000_000_000);
+
+        let (report, received) = transfer(fee_config, base_fee_per_gas, gas_price);
+        let fees = report.l2_fees.expect("L2 fees are recorded");
+        if report.is_success() {
+            assert_eq!(received, U256::one(), "{fee_config:?}");
+        } else {
+            // Only an L1 fee too high for the gas limit can make the transfer revert
+            assert_eq!(
+                fees.execution_gas + fees.l1_gas,
+                GAS_LIMIT,
+                "{fee_config:?}"
+            );
+            assert!(received.is_zero());
+        }
+    }
+
+    assert!(
+        executed > 64,
+        "only {executed} valid configs were generated"
+    );
+}
@@ -1,7 +1,124 @@
+mod access_sets_tests;
 mod bls12_tests;
+mod bounded_execution_tests;
+mod coverage_tests;
+mod create_failure_tests;
+mod eip7702_tests;
 mod eip7708_tests;
 mod eip7778_tests;
 mod eip7928_tests;
+mod eof_deploy_tests;
+mod gas_breakdown_tests;
+mod intrinsic_gas_tests;
+mod keccak_cache_tests;
+mod l2_fee_breakdown_tests;
 mod memory_tests;
+mod payload_bal_tests;
 mod precompile_tests;
+mod prestate_tests;
+mod reentrancy_tests;
+mod rpc_error_tests;
+mod slotnum_tests;
 mod stack_tests;
+mod storage_batch_tests;
+mod warm_cache_tests;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use ethrex_common::{
+    Address, H256, U256,
+    constants::EMPTY_TRIE_HASH,
+    types::{Account, AccountState, ChainConfig, Code, CodeMetadata},
+};
+use ethrex_levm::{db::Database, errors::DatabaseError};
+use rustc_hash::FxHashMap;
+
+/// In-memory backing database shared by the LEVM tests.
+///
+/// Serves `accounts` and counts the storage slots read from it. Tests that preload every account
+/// in the cache use [`TestDatabase::default`], which holds no accounts.
+#[derive(Default)]
+pub struct TestDatabase {
+    pub accounts: FxHashMap<Address, Account>,
+    pub chain_config: ChainConfig,
+    slot_reads: AtomicUsize,
+}
+
+impl TestDatabase {
+    pub fn new(accounts: FxHashMap<Address, Account>) -> Self {
+        Self {
+            accounts,
+            ..Default::default()
+        }
+    }
+
+    pub fn with_chain_config(mut self, chain_config: ChainConfig) -> Self {
+        self.chain_config = chain_config;
+        self
+    }
+
+    /// Number of storage slots read so far.
+    pub fn slot_reads(&self) -> usize {
+        self.slot_reads.load(Ordering::Relaxed)
+    }
+
+    /// Hash returned for the block `block_number`.
+    pub fn block_hash(block_number: u64) -> H256 {
+        H256::from_low_u64_be(block_number + 0xB000)
+    }
+
+    fn code(&self, code_hash: H256) -> Option<&Code> {
+        self.accounts
+            .values()
+            .find(|account| account.info.code_hash == code_hash)
+            .map(|account| &account.code)
+    }
+}
+
+impl Database for TestDatabase {
+    fn get_account_state(&self, address: Address) -> Result<AccountState, DatabaseError> {
+        let Some(account) = self.accounts.get(&address) else {
+            return Ok(AccountState::default());
+        };
+        Ok(AccountState {
+            nonce: account.info.nonce,
+            balance: account.info.balance,
+            code_hash: account.info.code_hash,
+            // Any root other than the empty one marks the account as having storage
+            storage_root: if account.storage.is_empty() {
+                *EMPTY_TRIE_HASH
+            } else {
+                H256::repeat_byte(0x01)
+            },
+        })
+    }
+
+    fn get_storage_value(&self, address: Address, key: H256) -> Result<U256, DatabaseError> {
+        self.slot_reads.fetch_add(1, Ordering::Relaxed);
+        Ok(self
+            .accounts
+            .get(&address)
+            .and_then(|account| account.storage.get(&key).copied())
+            .unwrap_or_default())
+    }
+
+    fn get_block_hash(&self, block_number: u64) -> Result<H256, DatabaseError> {
+        Ok(Self::block_hash(block_number))
+    }
+
+    fn get_chain_config(&self) -> Result<ChainConfig, DatabaseError> {
+        Ok(self.chain_config)
+    }
+
+    fn get_account_code(&self, code_hash: H256) -> Result<Code, DatabaseError> {
+        Ok(self.code(code_hash).cloned().unwrap_or_default())
+    }
+
+    fn get_code_metadata(&self, code_hash: H256) -> Result<CodeMetadata, DatabaseError> {
+        Ok(CodeMetadata {
+            length: self
+                .code(code_hash)
+                .map_or(0, |code| code.bytecode.len() as u64),
+        })
+    }
+}
@@ -0,0 +1,473 @@
+//! EIP-7928: incremental Block Access List building during payload construction.
+//!
+//! Transactions are added to and evicted from an in-progress payload, and the
+//! resulting BAL and state transitions are checked against the ones of re-executing
+//! the final block.
+
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256,
+    evm::compute_create_address,
+    types::{
+        Account, AccountUpdate, Code, EIP1559Transaction, Fork, Transaction, TxKind,
+        block_access_list::BlockAccessList,
+    },
+};
+use ethrex_levm::{
+    account::AccountStatus,
+    db::gen_db::GeneralizedDatabase,
+    environment::{EVMConfig, Environment},
+    hooks::backup_hook::BackupHook,
+    tracing::LevmCallTracer,
+    vm::{VM, VMType},
+};
+use rustc_hash::FxHashMap;
+use std::{cell::RefCell, rc::Rc, sync::Arc};
+
+use super::TestDatabase;
+
+// ==================== Test Constants ====================
+
+const ALICE: u64 = 0x1000;
+const BOB: u64 = 0x2000;
+const CAROL: u64 = 0x3000;
+const COUNTER: u64 = 0x4000;
+const REVERTER: u64 = 0x5000;
+const CALLER: u64 = 0x6000;
+const COINBASE: u64 = 0xCCC;
+const GAS_LIMIT: u64 = 100_000;
+const DEFAULT_BALANCE: u64 = 10_000_000_000;
+
+// ==================== Helpers ====================
+
+/// Reads slot 1, then increments slot 0.
+fn counter_bytecode() -> Bytes {
+    Bytes::from(vec![
+        0x60, 0x01, 0x54, 0x50, // PUSH1 1, SLOAD, POP
+        0x60, 0x00, 0x54, // PUSH1 0, SLOAD
+        0x60, 0x01, 0x01, // PUSH1 1, ADD
+        0x60, 0x00, 0x55, // PUSH1 0, SSTORE
+        0x00, // STOP
+    ])
+}
+
+fn new_db() -> GeneralizedDatabase {
+    new_db_with([])
+}
+
+/// Like `new_db`, with `extra` accounts added to the state.
+fn new_db_with(extra: impl IntoIterator<Item = (Address, Account)>) -> GeneralizedDatabase {
+    let eoa = || {
+        Account::new(
+            U256::from(DEFAULT_BALANCE),
+            Code::default(),
+            0,
+            FxHashMap::default(),
+        )
+    };
+    let accounts: FxHashMap<Address, Account> = [
+        (Address::from_low_u64_be(ALICE), eoa()),
+        (Address::from_low_u64_be(BOB), eoa()),
+        (
+            Address::from_low_u64_be(COUNTER),
+            Account::new(
+                U256::zero(),
+                Code::from_bytecode(counter_bytecode()),
+                1,
+                FxHashMap::default(),
+            ),
+        ),
+    ]
+    .into_iter()
+    .chain(extra)
+    .collect();
+    let store = TestDatabase::new(accounts.clone());
+    GeneralizedDatabase::new_with_account_state(Arc::new(store), accounts)
+}
+
+#[derive(Clone, Copy)]
+struct TestTx {
+    sender: u64,
+    nonce: u64,
+    to: u64,
+    value: u64,
+}
+
+const ALICE_COUNTER_0: TestTx = TestTx {
+    sender: ALICE,
+    nonce: 0,
+    to: COUNTER,
+    value: 0,
+};
+const ALICE_COUNTER_1: TestTx = TestTx {
+    sender: ALICE,
+    nonce: 1,
+    to: COUNTER,
+    value: 0,
+};
+const BOB_TO_CAROL: TestTx = TestTx {
+    sender: BOB,
+    nonce: 0,
+    to: CAROL,
+    value: 1_000,
+};
+
+/// Stores 1 in slot 0 and deploys a single INVALID opcode.
+fn storing_init_code() -> Bytes {
+    Bytes::from(vec![
+        0x60, 0x01, 0x60, 0x00, 0x55, // PUSH1 1, PUSH1 0, SSTORE
+        0x60, 0xfe, 0x60, 0x00, 0x53, // PUSH1 0xfe, PUSH1 0, MSTORE8
+        0x60, 0x01, 0x60, 0x00, 0xf3, // PUSH1 1, PUSH1 0, RETURN
+    ])
+}
+
+/// Selfdestructs to CAROL, so the created account is deleted at the end of the transaction.
+fn selfdestructing_init_code() -> Bytes {
+    let mut code = vec![0x73]; // PUSH20
+    code.extend_from_slice(Address::from_low_u64_be(CAROL).as_bytes());
+    code.push(0xff); // SELFDESTRUCT
+    Bytes::from(code)
+}
+
+/// Stores 1 in slot 0, deploys a contract with a single INVALID opcode, then reverts.
+fn reverting_bytecode() -> Bytes {
+    Bytes::from(vec![
+        0x60, 0x01, 0x60, 0x00, 0x55, // PUSH1 1, PUSH1 0, SSTORE
+        0x69, // PUSH10, init code deploying a single INVALID opcode:
+        0x60, 0xfe, 0x60, 0x00, 0x53, 0x60, 0x01, 0x60, 0x00, 0xf3, 0x60, 0x00,
+        0x52, // PUSH1 0, MSTORE
+        0x60, 0x0a, 0x60, 0x16, 0x60, 0x00, 0xf0, 0x50, // CREATE(0, 22, 10), POP
+        0x60, 0x00, 0x60, 0x00, 0xfd, // REVERT(0, 0)
+    ])
+}
+
+/// Calls REVERTER and stores the call's success flag in slot 0.
+fn calling_bytecode() -> Bytes {
+    let mut code = vec![
+        0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00, // PUSH1 0 (x5)
+        0x73, // PUSH20
+    ];
+    code.extend_from_slice(Address::from_low_u64_be(REVERTER).as_bytes());
+    code.extend_from_slice(&[
+        0x5a, 0xf1, // GAS, CALL
+        0x60, 0x00, 0x55, // PUSH1 0, SSTORE
+        0x00, // STOP
+    ]);
+    Bytes::from(code)
+}
+
+/// Executes `tx` like the block builder does: touching sender and recipient first,
+/// and keeping a backup so the transaction can be undone.
+fn execute(db: &mut GeneralizedDatabase, tx: TestTx) {
+    let to = Address::from_low_u64_be(tx.to);
+    run(
+        db,
+        tx.sender,
+        tx.nonce,
+        TxKind::Call(to),
+        tx.value,
+        Bytes::new(),
+    );
+}
+
+/// Executes a contract creation from `sender` running `init_code`.
+fn execute_create(db: &mut GeneralizedDatabase, sender: u64, nonce: u64, init_code: Bytes) {
+    run(db, sender, nonce, TxKind::Create, 1_000, init_code);
+}
+
+fn run(db: &mut GeneralizedDatabase, sender: u64, nonce: u64, to: TxKind, value: u64, data: Bytes) {
+    let sender = Address::from_low_u64_be(sender);
+    if let Some(recorder) = db.bal_recorder_mut() {
+        recorder.record_touched_address(sender);
+        if let TxKind::Call(to) = to {
+            recorder.record_touched_address(to);
+        }
+    }
+
+    let fork = Fork::Amsterdam;
+    let env = Environment {
+        origin: sender,
+        gas_limit: GAS_LIMIT,
+        config: EVMConfig::new(fork, EVMConfig::canonical_values(fork)),
+        block_number: U256::from(1),
+        coinbase: Address::from_low_u64_be(COINBASE),
+        timestamp: U256::from(1000),
+        prev_randao: Some(H256::zero()),
+        difficulty: U256::zero(),
+        slot_number: U256::zero(),
+        chain_id: U256::from(1),
+        base_fee_per_gas: U256::from(1000),
+        base_blob_fee_per_gas: U256::from(1),
+        gas_price: U256::from(1001),
+        block_excess_blob_gas: None,
+        block_blob_gas_used: None,
+        tx_blob_hashes: vec![],
+        tx_max_priority_fee_per_gas: Some(U256::from(1)),
+        tx_max_fee_per_gas: Some(U256::from(2000)),
+        tx_max_fee_per_blob_gas: None,
+        tx_nonce: nonce,
+        block_gas_limit: GAS_LIMIT * 10,
+        is_privileged: false,
+    };
+    let tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+        chain_id: 1,
+        nonce,
+        to,
+        value: U256::from(value),
+        data,
+        gas_limit: GAS_LIMIT,
+        max_fee_per_gas: 2000,
+        max_priority_fee_per_gas: 1,
+        ..Default::default()
+    });
+
+    let mut vm = VM::new(env, db, &tx, LevmCallTracer::disabled(), VMType::L1).unwrap();
+    vm.hooks.push(Rc::new(RefCell::new(BackupHook::default())));
+    let report = vm.execute().unwrap();
+    assert!(report.is_success());
+}
+
+/// Evicts the last executed transaction, which also retracts its BAL contributions.
+fn evict(db: &mut GeneralizedDatabase) {
+    db.undo_last_transaction().unwrap();
+}
+
+/// Records the BAL of executing `txs` as a block, like block import does.
+fn reexecute_block(txs: &[TestTx]) -> BlockAccessList {
+    reexecute_block_with_state(txs).0
+}
+
+/// Like `reexecute_block`, also returning the block's state transitions.
+fn reexecute_block_with_state(txs: &[TestTx]) -> (BlockAccessList, Vec<AccountUpdate>) {
+    let mut db = new_db();
+    db.enable_bal_recording();
+    db.set_bal_index(0);
+    for (i, tx) in txs.iter().enumerate() {
+        db.set_bal_index(i as u16 + 1);
+        execute(&mut db, *tx);
+    }
+    let bal = db.take_bal().unwrap();
+    (bal, state_transitions(&mut db))
+}
+
+/// State transitions sorted by address, for comparison.
+fn state_transitions(db: &mut GeneralizedDatabase) -> Vec<AccountUpdate> {
+    let mut updates = db.get_state_transitions().unwrap();
+    updates.sort_by_key(|update| update.address);
+    updates
+}
+
+// ==================== Tests ====================
+
+#[test]
+fn test_payload_bal_without_evictions_matches_block() {
+    let mut db = new_db();
+    db.begin_payload_bal();
+    db.record_tx(1);
+    execute(&mut db, ALICE_COUNTER_0);
+    db.record_tx(2);
+    execute(&mut db, BOB_TO_CAROL);
+    db.record_tx(3);
+    execute(&mut db, ALICE_COUNTER_1);
+
+    let bal = db.finalize_payload_bal().unwrap();
+    assert_eq!(
+        bal,
+        reexecute_block(&[ALICE_COUNTER_0, BOB_TO_CAROL, ALICE_COUNTER_1])
+    );
+}
+
+#[test]
+fn test_payload_bal_evicted_transaction_is_retracted() {
+    let mut db = new_db();
+    db.begin_payload_bal();
+    db.record_tx(1);
+    execute(&mut db, ALICE_COUNTER_0);
+    db.record_tx(2);
+    execute(&mut db, BOB_TO_CAROL);
+    evict(&mut db);
+
+    let bal = db.finalize_payload_bal().unwrap();
+    assert_eq!(bal, reexecute_block(&[ALICE_COUNTER_0]));
+    let addresses: Vec<Address> = bal.accounts().iter().map(|a| a.address).collect();
+    assert!(!addresses.contains(&Address::from_low_u64_be(BOB)));
+    assert!(!addresses.contains(&Address::from_low_u64_be(CAROL)));
+}
+
+#[test]
+fn test_payload_bal_evicted_transaction_re_added_later() {
+    let mut db = new_db();
+    db.begin_payload_bal();
+    db.record_tx(1);
+    execute(&mut db, ALICE_COUNTER_0);
+    db.record_tx(2);
+    execute(&mut db, BOB_TO_CAROL);
+    evict(&mut db);
+    db.record_tx(2);
+    execute(&mut db, ALICE_COUNTER_1);
+    db.record_tx(3);
+    execute(&mut db, BOB_TO_CAROL);
+
+    let bal = db.finalize_payload_bal().unwrap();
+    assert_eq!(
+        bal,
+        reexecute_block(&[ALICE_COUNTER_0, ALICE_COUNTER_1, BOB_TO_CAROL])
+    );
+}
+
+#[test]
+fn test_payload_bal_evicted_storage_write_is_retracted() {
+    let mut db = new_db();
+    db.begin_payload_bal();
+    db.record_tx(1);
+    execute(&mut db, ALICE_COUNTER_0);
+    db.record_tx(2);
+    execute(&mut db, ALICE_COUNTER_1);
+    evict(&mut db);
+    db.record_tx(2);
+    execute(&mut db, BOB_TO_CAROL);
+    db.record_tx(3);
+    execute(&mut db, ALICE_COUNTER_1);
+
+    let bal = db.finalize_payload_bal().unwrap();
+    assert_eq!(
+        bal,
+        reexecute_block(&[ALICE_COUNTER_0, BOB_TO_CAROL, ALICE_COUNTER_1])
+    );
+    assert_eq!(
+        bal.compute_hash(),
+        reexecute_block(&[ALICE_COUNTER_0, BOB_TO_CAROL, ALICE_COUNTER_1]).compute_hash()
+    );
+}
+
+#[test]
+fn test_payload_evicted_contract_creation_is_retracted() {
+    let mut db = new_db();
+    db.begin_payload_bal();
+    db.record_tx(1);
+    execute(&mut db, ALICE_COUNTER_0);
+    db.record_tx(2);
+    execute_create(&mut db, BOB, 0, storing_init_code());
+    let created = compute_create_address(Address::from_low_u64_be(BOB), 0);
+    let deployed_code_hash = db.current_accounts_state[&created].info.code_hash;
+    evict(&mut db);
+    db.record_tx(2);
+    execute(&mut db, BOB_TO_CAROL);
+
+    // The deployed code is gone along with the account that held it
+    assert!(!db.codes.contains_key(&deployed_code_hash));
+    assert!(db.current_accounts_state[&created].is_empty());
+
+    let bal = db.finalize_payload_bal().unwrap();
+    let (expected_bal, expected_updates) =
+        reexecute_block_with_state(&[ALICE_COUNTER_0, BOB_TO_CAROL]);
+    assert_eq!(bal, expected_bal);
+    assert!(!bal.accounts().iter().any(|a| a.address == created));
+    assert_eq!(state_transitions(&mut db), expected_updates);
+}
+
+#[test]
+fn test_payload_evicted_selfdestruct_is_retracted() {
+    let mut db = new_db();
+    db.begin_payload_bal();
+    db.record_tx(1);
+    execute(&mut db, ALICE_COUNTER_0);
+    db.record_tx(2);
+    execute_create(&mut db, BOB, 0, selfdestructing_init_code());
+    let created = compute_create_address(Address::from_low_u64_be(BOB), 0);
+    assert_eq!(
+        db.current_accounts_state[&created].status,
+        AccountStatus::Destroyed
+    );
+    evict(&mut db);
+    db.record_tx(2);
+    execute(&mut db, BOB_TO_CAROL);
+    db.record_tx(3);
+    execute(&mut db, ALICE_COUNTER_1);
+
+    // The account is no longer marked as destroyed
+    assert_ne!(
+        db.current_accounts_state[&created].status,
+        AccountStatus::Destroyed
+    );
+
+    let bal = db.finalize_payload_bal().unwrap();
+    let (expected_bal, expected_updates) =
+        reexecute_block_with_state(&[ALICE_COUNTER_0, BOB_TO_CAROL, ALICE_COUNTER_1]);
+    assert_eq!(bal, expected_bal);
+    assert_eq!(state_transitions(&mut db), expected_updates);
+}
+
+#[test]
+fn test_undo_last_transaction_retracts_payload_bal() {
+    let mut db = new_db();
+    db.begin_payload_bal();
+    db.record_tx(1);
+    execute(&mut db, ALICE_COUNTER_0);
+    db.record_tx(2);
+    execute_create(&mut db, BOB, 0, storing_init_code());
+    // Undoing alone is enough, without retracting the index explicitly
+    db.undo_last_transaction().unwrap();
+
+    let bal = db.finalize_payload_bal().unwrap();
+    assert_eq!(bal, reexecute_block(&[ALICE_COUNTER_0]));
+}
+
+#[test]
+fn test_reverted_call_frame_discards_storage_and_created_code() {
+    let reverter = Address::from_low_u64_be(REVERTER);
+    let caller = Address::from_low_u64_be(CALLER);
+    let slot = H256::zero();
+    let mut db = new_db_with([
+        (
+            reverter,
+            Account::new(
+                U256::zero(),
+                Code::from_bytecode(reverting_bytecode()),
+                1,
+                [(slot, U256::from(7))].into_iter().collect(),
+            ),
+        ),
+        (
+            caller,
+            Account::new(
+                U256::zero(),
+                Code::from_bytecode(calling_bytecode()),
+                1,
+                [(slot, U256::from(5))].into_iter().collect(),
+            ),
+        ),
+    ]);
+    let reverter_before = db.current_accounts_state[&reverter].clone();
+    let created = compute_create_address(reverter, 1);
+    let deployed_code_hash = Code::from_bytecode(Bytes::from_static(&[0xfe])).hash;
+
+    run(&mut db, ALICE, 0, TxKind::Call(caller), 0, Bytes::new());
+
+    // The caller saw the call fail and kept running
+    assert_eq!(
+        db.current_accounts_state[&caller].storage[&slot],
+        U256::zero()
+    );
+
+    // Everything the reverted frame did is gone
+    let reverter_after = &db.current_accounts_state[&reverter];
+    assert_eq!(reverter_after.storage[&slot], U256::from(7));
+    assert_eq!(reverter_after.info, reverter_before.info);
+    assert_eq!(reverter_after.status, reverter_before.status);
+    assert_eq!(reverter_after.has_storage, reverter_before.has_storage);
+    assert!(
+        db.current_accounts_state
+            .get(&created)
+            .is_none_or(|account| account.is_empty())
+    );
+    assert!(!db.codes.contains_key(&deployed_code_hash));
+
+    let updates = state_transitions(&mut db);
+    assert!(updates.iter().any(|update| update.address == caller));
+    assert!(
+        !updates
+            .iter()
+            .any(|update| update.address == reverter || update.address == created)
+    );
+}
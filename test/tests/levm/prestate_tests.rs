@@ -0,0 +1,229 @@
+//! Tests for the prestate tracer and re-execution from its output.
+//!
+//! Key behaviors tested:
+//! - Only the accounts, slots and block hashes read are recorded, as they were before the tx
+//! - Re-executing from the recorded prestate gives the same report and state transitions
+//! - Geth's output format can be loaded back as well
+
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256,
+    tracing::NativePrestate,
+    types::{
+        Account, AccountUpdate, ChainConfig, Code, EIP1559Transaction, Fork, Transaction, TxKind,
+    },
+};
+use ethrex_levm::{
+    db::gen_db::GeneralizedDatabase,
+    environment::{EVMConfig, Environment},
+    errors::{ExecutionReport, TxResult},
+    tracing::LevmCallTracer,
+    vm::{VM, VMType},
+};
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+use super::TestDatabase;
+
+// ==================== Test Constants ====================
+
+const SENDER: u64 = 0x1000;
+const CONTRACT: u64 = 0xC000;
+const CALLEE: u64 = 0xD000;
+const QUERIED: u64 = 0xE000;
+const UNTOUCHED: u64 = 0xF000;
+const GAS_LIMIT: u64 = 1_000_000;
+
+/// Copies slot 0 into slot 1, stores BLOCKHASH(9) in slot 2, calls `CALLEE` and reads the balance of `QUERIED`.
+const CONTRACT_CODE: &str = concat!(
+    "6000 54 6001 55 ",                          // SSTORE(1, SLOAD(0))
+    "6009 40 6002 55 ",                          // SSTORE(2, BLOCKHASH(9))
+    "6000 6000 6000 6000 6000 61d000 5a f1 50 ", // CALL(GAS, CALLEE, 0, 0, 0, 0, 0)
+    "61e000 31 50 ",                             // BALANCE(QUERIED)
+    "00"
+);
+/// Reads slot 5.
+const CALLEE_CODE: &str = "6005 54 50 00";
+
+// ==================== Helpers ====================
+
+fn address(value: u64) -> Address {
+    Address::from_low_u64_be(value)
+}
+
+fn slot(value: u64) -> H256 {
+    H256::from_low_u64_be(value)
+}
+
+fn contract(code: &str, storage: &[(u64, u64)]) -> Account {
+    let code = Bytes::from(hex::decode(code.replace(' ', "")).unwrap());
+    Account::new(
+        U256::zero(),
+        Code::from_bytecode(code),
+        1,
+        storage
+            .iter()
+            .map(|(key, value)| (slot(*key), U256::from(*value)))
+            .collect(),
+    )
+}
+
+fn backing_db() -> TestDatabase {
+    let accounts = FxHashMap::from_iter([
+        (
+            address(SENDER),
+            Account::new(
+                U256::from(10_000_000_000u64),
+                Code::default(),
+                0,
+                FxHashMap::default(),
+            ),
+        ),
+        (
+            address(CONTRACT),
+            contract(CONTRACT_CODE, &[(0, 42), (1, 7), (3, 9)]),
+        ),
+        (address(CALLEE), contract(CALLEE_CODE, &[(5, 11)])),
+        (
+            address(QUERIED),
+            Account::new(U256::from(123), Code::default(), 0, FxHashMap::default()),
+        ),
+        (address(UNTOUCHED), contract("00", &[(0, 1)])),
+    ]);
+    TestDatabase::new(accounts).with_chain_config(ChainConfig {
+        chain_id: 1,
+        prague_time: Some(0),
+        ..Default::default()
+    })
+}
+
+fn execute(db: &mut GeneralizedDatabase) -> ExecutionReport {
+    let fork = Fork::Prague;
+    let env = Environment {
+        origin: address(SENDER),
+        gas_limit: GAS_LIMIT,
+        config: EVMConfig::new(fork, EVMConfig::canonical_values(fork)),
+        block_number: U256::from(10),
+        coinbase: Address::from_low_u64_be(0xCCC),
+        timestamp: U256::from(1000),
+        prev_randao: Some(H256::zero()),
+        difficulty: U256::zero(),
+        slot_number: U256::zero(),
+        chain_id: U256::from(1),
+        base_fee_per_gas: U256::from(1000),
+        base_blob_fee_per_gas: U256::from(1),
+        gas_price: U256::from(1000),
+        block_excess_blob_gas: None,
+        block_blob_gas_used: None,
+        tx_blob_hashes: vec![],
+        tx_max_priority_fee_per_gas: None,
+        tx_max_fee_per_gas: Some(U256::from(1000)),
+        tx_max_fee_per_blob_gas: None,
+        tx_nonce: 0,
+        block_gas_limit: GAS_LIMIT * 2,
+        is_privileged: false,
+    };
+    let tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+        to: TxKind::Call(address(CONTRACT)),
+        gas_limit: GAS_LIMIT,
+        max_fee_per_gas: 1000,
+        max_priority_fee_per_gas: 1,
+        ..Default::default()
+    });
+
+    let mut vm = VM::new(env, db, &tx, LevmCallTracer::disabled(), VMType::L1).unwrap();
+    vm.execute().unwrap()
+}
+
+/// State transitions sorted by address, for comparison.
+fn state_transitions(db: &mut GeneralizedDatabase) -> Vec<AccountUpdate> {
+    let mut updates = db.get_state_transitions().unwrap();
+    updates.sort_by_key(|update| update.address);
+    updates
+}
+
+/// Executes the transaction against the whole state, recording its prestate.
+fn traced_execution() -> (ExecutionReport, Vec<AccountUpdate>, NativePrestate) {
+    let mut db = GeneralizedDatabase::new(Arc::new(backing_db()));
+    db.enable_prestate_tracing();
+    let report = execute(&mut db);
+    let prestate = db.take_prestate().unwrap().unwrap();
+    assert!(db.prestate_tracer.is_none());
+    (report, state_transitions(&mut db), prestate)
+}
+
+// ==================== Tests ====================
+
+#[test]
+fn prestate_records_first_reads_only() {
+    let (report, _, prestate) = traced_execution();
+    assert_eq!(report.result, TxResult::Success);
+
+    assert!(prestate.accounts.contains_key(&address(SENDER)));
+    assert!(!prestate.accounts.contains_key(&address(UNTOUCHED)));
+    assert_eq!(
+        prestate.accounts[&address(QUERIED)].balance,
+        U256::from(123)
+    );
+
+    // Slot 1 is overwritten by the tx, the trace keeps the value it had before
+    let storage = &prestate.accounts[&address(CONTRACT)].storage;
+    assert_eq!(storage[&slot(0)], slot(42));
+    assert_eq!(storage[&slot(1)], slot(7));
+    assert_eq!(storage[&slot(2)], H256::zero());
+    assert!(!storage.contains_key(&slot(3)));
+    assert_eq!(
+        prestate.accounts[&address(CALLEE)].storage[&slot(5)],
+        slot(11)
+    );
+
+    // The sender's balance is the one before paying for gas
+    assert_eq!(
+        prestate.accounts[&address(SENDER)].balance,
+        U256::from(10_000_000_000u64)
+    );
+
+    assert_eq!(
+        prestate.block_hashes.get(&9),
+        Some(&TestDatabase::block_hash(9))
+    );
+    assert!(prestate.accounts_with_storage.contains(&address(CONTRACT)));
+    assert_eq!(prestate.chain_config.chain_id, 1);
+}
+
+#[test]
+fn reexecution_from_prestate_is_identical() {
+    let (report, transitions, prestate) = traced_execution();
+
+    let json = serde_json::to_string(&prestate).unwrap();
+    let mut db = GeneralizedDatabase::from_prestate(&json).unwrap();
+    assert_eq!(execute(&mut db), report);
+    assert_eq!(state_transitions(&mut db), transitions);
+}
+
+#[test]
+fn geth_prestate_format_can_be_loaded() {
+    let (_, _, prestate) = traced_execution();
+
+    let json = serde_json::to_value(&prestate.accounts).unwrap();
+    let contract_json = &json[format!("{:#x}", address(CONTRACT))];
+    assert_eq!(contract_json["nonce"], 1);
+    assert_eq!(
+        contract_json["code"],
+        format!("0x{}", CONTRACT_CODE.replace(' ', ""))
+    );
+    assert!(
+        json[format!("{:#x}", address(QUERIED))]
+            .get("code")
+            .is_none()
+    );
+
+    let mut db = GeneralizedDatabase::from_prestate(&json.to_string()).unwrap();
+    let contract = db.get_account(address(CONTRACT)).unwrap();
+    assert_eq!(contract.info.nonce, 1);
+    assert!(contract.has_storage);
+    assert_eq!(
+        db.get_account(address(QUERIED)).unwrap().info.balance,
+        U256::from(123)
+    );
+}
@@ -0,0 +1,243 @@
+//! Tests for the reentrancy annotations LEVM records on call frames.
+//!
+//! Key behaviors tested:
+//! - Calls into a fresh storage context are not annotated
+//! - A proxy DELEGATECALL into other code is not reentrant, one into its own code is
+//! - Calling back into a proxy while it delegates is reentrant
+//! - A→B→A reports the distance to the outer A frame
+//! - STATICCALL reentrancy is detected like any other call
+//! - The maximum depth reached is exposed in `ExecutionReport`
+
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256,
+    tracing::CallTraceFrame,
+    types::{Account, Code, EIP1559Transaction, Fork, Transaction, TxKind},
+};
+use ethrex_levm::{
+    db::gen_db::GeneralizedDatabase,
+    environment::{EVMConfig, Environment},
+    errors::ExecutionReport,
+    tracing::LevmCallTracer,
+    vm::{VM, VMType},
+};
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+use super::TestDatabase;
+
+// ==================== Test Constants ====================
+
+const SENDER: u64 = 0x1000;
+const CONTRACT_A: u64 = 0x2000;
+const CONTRACT_B: u64 = 0x3000;
+const CONTRACT_C: u64 = 0x4000;
+const GAS_LIMIT: u64 = 1_000_000;
+
+const CALL: u8 = 0xf1;
+const DELEGATECALL: u8 = 0xf4;
+const STATICCALL: u8 = 0xfa;
+
+// ==================== Bytecode Helpers ====================
+
+/// Calls `target` with `args_size` zeroed bytes of calldata and discards the result.
+fn call_bytecode(opcode: u8, target: Address, args_size: u8) -> Vec<u8> {
+    let mut bytecode = vec![0x60, 0x00, 0x60, 0x00, 0x60, args_size, 0x60, 0x00]; // retSize, retOffset, argsSize, argsOffset
+    if opcode == CALL {
+        bytecode.extend_from_slice(&[0x60, 0x00]); // value
+    }
+    bytecode.push(0x73); // PUSH20 target
+    bytecode.extend_from_slice(target.as_bytes());
+    bytecode.push(0x5a); // GAS
+    bytecode.push(opcode);
+    bytecode.push(0x50); // POP
+    bytecode
+}
+
+/// Runs `call` only when invoked without calldata, so that reentrant calls stop the recursion.
+fn guarded(call: Vec<u8>) -> Bytes {
+    let jumpdest = u8::try_from(call.len() + 5).unwrap();
+    let mut bytecode = vec![0x36, 0x60, jumpdest, 0x57]; // CALLDATASIZE, PUSH1 jumpdest, JUMPI
+    bytecode.extend(call);
+    bytecode.push(0x00); // STOP
+    bytecode.extend_from_slice(&[0x5b, 0x00]); // JUMPDEST, STOP
+    Bytes::from(bytecode)
+}
+
+fn stop_bytecode() -> Bytes {
+    Bytes::from(vec![0x00])
+}
+
+// ==================== Execution Helpers ====================
+
+fn contract(code: Bytes) -> Account {
+    Account::new(
+        U256::zero(),
+        Code::from_bytecode(code),
+        0,
+        FxHashMap::default(),
+    )
+}
+
+/// Executes a transaction from `SENDER` to `CONTRACT_A` and returns the report and the top call trace.
+fn execute(contracts: Vec<(u64, Bytes)>) -> (ExecutionReport, CallTraceFrame) {
+    let sender = Address::from_low_u64_be(SENDER);
+    let mut accounts: FxHashMap<Address, Account> = contracts
+        .into_iter()
+        .map(|(address, code)| (Address::from_low_u64_be(address), contract(code)))
+        .collect();
+    accounts.insert(
+        sender,
+        Account::new(
+            U256::from(10_000_000_000u64),
+            Code::default(),
+            0,
+            FxHashMap::default(),
+        ),
+    );
+    let mut db =
+        GeneralizedDatabase::new_with_account_state(Arc::new(TestDatabase::default()), accounts);
+
+    let fork = Fork::Prague;
+    let env = Environment {
+        origin: sender,
+        gas_limit: GAS_LIMIT,
+        config: EVMConfig::new(fork, EVMConfig::canonical_values(fork)),
+        block_number: U256::from(1),
+        coinbase: Address::from_low_u64_be(0xCCC),
+        timestamp: U256::from(1000),
+        prev_randao: Some(H256::zero()),
+        difficulty: U256::zero(),
+        slot_number: U256::zero(),
+        chain_id: U256::from(1),
+        base_fee_per_gas: U256::from(1000),
+        base_blob_fee_per_gas: U256::from(1),
+        gas_price: U256::from(1000),
+        block_excess_blob_gas: None,
+        block_blob_gas_used: None,
+        tx_blob_hashes: vec![],
+        tx_max_priority_fee_per_gas: None,
+        tx_max_fee_per_gas: Some(U256::from(1000)),
+        tx_max_fee_per_blob_gas: None,
+        tx_nonce: 0,
+        block_gas_limit: GAS_LIMIT * 2,
+        is_privileged: false,
+    };
+
+    let tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+        to: TxKind::Call(Address::from_low_u64_be(CONTRACT_A)),
+        data: Bytes::new(),
+        gas_limit: GAS_LIMIT,
+        max_fee_per_gas: 1000,
+        max_priority_fee_per_gas: 1,
+        ..Default::default()
+    });
+
+    let mut vm = VM::new(
+        env,
+        &mut db,
+        &tx,
+        LevmCallTracer::new(false, false),
+        VMType::L1,
+    )
+    .unwrap();
+    let report = vm.execute().unwrap();
+    assert!(report.is_success());
+    let trace = vm.tracer.callframes.pop().unwrap();
+    (report, trace)
+}
+
+// ==================== Tests ====================
+
+#[test]
+fn call_into_new_context_is_not_reentrant() {
+    let b = Address::from_low_u64_be(CONTRACT_B);
+    let (report, trace) = execute(vec![
+        (CONTRACT_A, guarded(call_bytecode(CALL, b, 0))),
+        (CONTRACT_B, stop_bytecode()),
+    ]);
+
+    assert_eq!(trace.reentrancy, None);
+    assert_eq!(trace.calls[0].reentrancy, None);
+    assert_eq!(report.max_depth, 1);
+}
+
+#[test]
+fn delegatecall_into_self_reports_parent() {
+    let a = Address::from_low_u64_be(CONTRACT_A);
+    let (report, trace) = execute(vec![(
+        CONTRACT_A,
+        guarded(call_bytecode(DELEGATECALL, a, 1)),
+    )]);
+
+    assert_eq!(trace.calls[0].reentrancy, Some(1));
+    assert_eq!(report.max_depth, 1);
+}
+
+#[test]
+fn proxy_delegatecall_is_not_reentrant() {
+    let b = Address::from_low_u64_be(CONTRACT_B);
+    let (_, trace) = execute(vec![
+        (CONTRACT_A, guarded(call_bytecode(DELEGATECALL, b, 0))),
+        (CONTRACT_B, stop_bytecode()),
+    ]);
+
+    // B's code runs on A's storage, but A's own code isn't re-entered
+    assert_eq!(trace.calls[0].reentrancy, None);
+}
+
+#[test]
+fn call_back_into_delegating_proxy_reports_distance() {
+    let a = Address::from_low_u64_be(CONTRACT_A);
+    let b = Address::from_low_u64_be(CONTRACT_B);
+    let c = Address::from_low_u64_be(CONTRACT_C);
+    let (report, trace) = execute(vec![
+        (CONTRACT_A, guarded(call_bytecode(DELEGATECALL, b, 0))),
+        (CONTRACT_B, Bytes::from(call_bytecode(CALL, c, 0))),
+        (CONTRACT_C, Bytes::from(call_bytecode(CALL, a, 1))),
+    ]);
+
+    let delegatecall_to_b = &trace.calls[0];
+    assert_eq!(delegatecall_to_b.reentrancy, None);
+    assert_eq!(delegatecall_to_b.calls[0].reentrancy, None);
+    assert_eq!(delegatecall_to_b.calls[0].calls[0].reentrancy, Some(3));
+    assert_eq!(report.max_depth, 3);
+}
+
+#[test]
+fn call_back_into_caller_reports_distance() {
+    let a = Address::from_low_u64_be(CONTRACT_A);
+    let b = Address::from_low_u64_be(CONTRACT_B);
+    let (report, trace) = execute(vec![
+        (CONTRACT_A, guarded(call_bytecode(CALL, b, 0))),
+        (CONTRACT_B, guarded(call_bytecode(CALL, a, 1))),
+    ]);
+
+    let call_to_b = &trace.calls[0];
+    assert_eq!(call_to_b.reentrancy, None);
+    assert_eq!(call_to_b.calls[0].reentrancy, Some(2));
+    assert_eq!(report.max_depth, 2);
+}
+
+#[test]
+fn staticcall_reentrancy_is_detected() {
+    let a = Address::from_low_u64_be(CONTRACT_A);
+    let b = Address::from_low_u64_be(CONTRACT_B);
+    let (report, trace) = execute(vec![
+        (CONTRACT_A, guarded(call_bytecode(STATICCALL, b, 0))),
+        (CONTRACT_B, guarded(call_bytecode(STATICCALL, a, 1))),
+    ]);
+
+    let call_to_b = &trace.calls[0];
+    assert_eq!(call_to_b.reentrancy, None);
+    assert_eq!(call_to_b.calls[0].reentrancy, Some(2));
+    assert_eq!(report.max_depth, 2);
+}
+
+#[test]
+fn max_depth_is_zero_without_nested_calls() {
+    let (report, trace) = execute(vec![(CONTRACT_A, stop_bytecode())]);
+
+    assert!(trace.calls.is_empty());
+    assert_eq!(report.max_depth, 0);
+}
@@ -0,0 +1,144 @@
+//! Tests for the mapping of execution errors to JSON-RPC errors.
+//!
+//! Key behaviors tested:
+//! - Nonce, funds, gas limit and blob validation failures get distinct codes
+//! - Transaction validation errors keep their structure through `EvmError`
+//! - Revert data is passed through as the error data
+//! - The block gas overflow message keeps the substrings the EELS mapper matches
+
+use bytes::Bytes;
+use ethrex_common::{H256, U256};
+use ethrex_levm::errors::{ExceptionalHalt, InternalError, TxValidationError, VMError};
+use ethrex_vm::{
+    EvmError, RpcErrorPayload,
+    rpc_error::{
+        BLOB_VALIDATION_ERROR, EXECUTION_ERROR, GAS_LIMIT_EXCEEDED, INSUFFICIENT_FUNDS,
+        INTERNAL_ERROR, NONCE_TOO_LOW, TRANSACTION_REJECTED, VM_ERROR,
+    },
+};
+
+// ==================== Helpers ====================
+
+fn code(error: TxValidationError) -> i32 {
+    RpcErrorPayload::from(&EvmError::from(VMError::TxValidation(error))).code
+}
+
+// ==================== Tests ====================
+
+#[test]
+fn tx_validation_errors_get_distinct_codes() {
+    assert_eq!(
+        code(TxValidationError::NonceMismatch {
+            expected: 5,
+            actual: 4
+        }),
+        NONCE_TOO_LOW
+    );
+    assert_eq!(
+        code(TxValidationError::NonceMismatch {
+            expected: 5,
+            actual: 6
+        }),
+        TRANSACTION_REJECTED
+    );
+    assert_eq!(
+        code(TxValidationError::InsufficientAccountFunds),
+        INSUFFICIENT_FUNDS
+    );
+    assert_eq!(
+        code(TxValidationError::GasLimitAboveCap {
+            gas_limit: 1 << 25,
+            cap: 1 << 24
+        }),
+        GAS_LIMIT_EXCEEDED
+    );
+    assert_eq!(
+        code(TxValidationError::TxMaxGasLimitExceeded {
+            tx_hash: H256::zero(),
+            tx_gas_limit: 1 << 25
+        }),
+        GAS_LIMIT_EXCEEDED
+    );
+    assert_eq!(
+        code(TxValidationError::InsufficientMaxFeePerBlobGas {
+            base_fee_per_blob_gas: U256::from(2),
+            tx_max_fee_per_blob_gas: U256::one()
+        }),
+        BLOB_VALIDATION_ERROR
+    );
+    assert_eq!(
+        code(TxValidationError::Type3TxZeroBlobs),
+        BLOB_VALIDATION_ERROR
+    );
+    assert_eq!(
+        code(TxValidationError::IntrinsicGasTooLow),
+        TRANSACTION_REJECTED
+    );
+}
+
+#[test]
+fn tx_validation_errors_keep_their_structure() {
+    let error = EvmError::from(VMError::TxValidation(
+        TxValidationError::InsufficientAccountFunds,
+    ));
+    assert!(matches!(
+        error,
+        EvmError::TxValidation(TxValidationError::InsufficientAccountFunds)
+    ));
+    // Same message as when it was flattened into a string
+    assert_eq!(
+        error.to_string(),
+        "Invalid Transaction: Insufficient account funds"
+    );
+
+    let payload = RpcErrorPayload::from(&error);
+    assert_eq!(payload.message, "Insufficient account funds");
+    assert_eq!(payload.data, None);
+}
+
+#[test]
+fn revert_data_is_passed_through() {
+    let output = Bytes::from_static(&[0x08, 0xc3, 0x79, 0xa0, 0x01]);
+    let payload = RpcErrorPayload::revert(output.clone());
+    assert_eq!(payload.code, EXECUTION_ERROR);
+    assert_eq!(payload.message, "execution reverted");
+    assert_eq!(payload.data, Some(output));
+
+    let payload = RpcErrorPayload::from(&VMError::RevertOpcode);
+    assert_eq!(payload.code, EXECUTION_ERROR);
+
+    let payload = RpcErrorPayload::from(&VMError::ExceptionalHalt(ExceptionalHalt::OutOfGas));
+    assert_eq!(payload.code, EXECUTION_ERROR);
+    assert_eq!(payload.message, "Out Of Gas");
+}
+
+#[test]
+fn non_transaction_errors_are_vm_or_internal_errors() {
+    let internal = EvmError::from(VMError::Internal(InternalError::Overflow));
+    assert_eq!(RpcErrorPayload::from(&internal).code, VM_ERROR);
+    assert_eq!(
+        RpcErrorPayload::from(&VMError::Internal(InternalError::Overflow)).code,
+        INTERNAL_ERROR
+    );
+    assert_eq!(
+        RpcErrorPayload::from(&EvmError::DB("missing node".to_string())).code,
+        INTERNAL_ERROR
+    );
+    assert_eq!(
+        RpcErrorPayload::from(&EvmError::Transaction("bad signature".to_string())).code,
+        TRANSACTION_REJECTED
+    );
+}
+
+#[test]
+fn block_gas_overflow_message_matches_eels_mapper() {
+    let error = TxValidationError::BlockGasUsedOverflow {
+        block_gas_used: 29_000_000,
+        tx_gas_limit: 2_000_000,
+        block_gas_limit: 30_000_000,
+    };
+    let message = error.to_string();
+    assert!(message.contains("Gas allowance exceeded"));
+    assert!(message.contains("Block gas used overflow"));
+    assert_eq!(code(error), GAS_LIMIT_EXCEEDED);
+}
@@ -0,0 +1,139 @@
+//! Tests for EIP-7843: SLOTNUM opcode
+//!
+//! Key behaviors tested:
+//! - SLOTNUM pushes the slot number of the environment on Amsterdam
+//! - SLOTNUM costs the base gas, like the other block information opcodes
+//! - SLOTNUM is an invalid opcode before Amsterdam
+
+use bytes::Bytes;
+use ethrex_common::{
+    Address, H256, U256,
+    types::{Account, Code, EIP1559Transaction, Fork, Transaction, TxKind},
+};
+use ethrex_levm::{
+    db::gen_db::GeneralizedDatabase,
+    environment::{EVMConfig, Environment},
+    errors::{ExceptionalHalt, ExecutionReport, TxResult, VMError},
+    tracing::LevmCallTracer,
+    vm::{VM, VMType},
+};
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+use super::TestDatabase;
+
+// ==================== Test Constants ====================
+
+const SENDER: u64 = 0x1000;
+const CONTRACT: u64 = 0x3000;
+const GAS_LIMIT: u64 = 100_000;
+const SLOT_NUMBER: u64 = 0x5107;
+
+// SLOTNUM, PUSH1 0, MSTORE, PUSH1 32, PUSH1 0, RETURN
+const RETURN_SLOTNUM_CODE: [u8; 9] = [0x4b, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3];
+// TIMESTAMP, PUSH1 0, MSTORE, PUSH1 32, PUSH1 0, RETURN
+const RETURN_TIMESTAMP_CODE: [u8; 9] = [0x42, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3];
+
+// ==================== Helpers ====================
+
+/// Calls a contract with `code` on `fork`, with the environment at `SLOT_NUMBER`.
+fn execute(fork: Fork, code: &[u8]) -> ExecutionReport {
+    let sender = Address::from_low_u64_be(SENDER);
+    let contract = Address::from_low_u64_be(CONTRACT);
+    let accounts: FxHashMap<Address, Account> = [
+        (
+            sender,
+            Account::new(
+                U256::from(10_000_000_000u64),
+                Code::default(),
+                0,
+                FxHashMap::default(),
+            ),
+        ),
+        (
+            contract,
+            Account::new(
+                U256::zero(),
+                Code::from_bytecode(Bytes::copy_from_slice(code)),
+                1,
+                FxHashMap::default(),
+            ),
+        ),
+    ]
+    .into_iter()
+    .collect();
+    let test_db = TestDatabase::new(accounts.clone());
+    let mut db = GeneralizedDatabase::new_with_account_state(Arc::new(test_db), accounts);
+
+    let env = Environment {
+        origin: sender,
+        gas_limit: GAS_LIMIT,
+        config: EVMConfig::new(fork, EVMConfig::canonical_values(fork)),
+        block_number: U256::from(1),
+        coinbase: Address::from_low_u64_be(0xCCC),
+        timestamp: U256::from(1000),
+        prev_randao: Some(H256::zero()),
+        difficulty: U256::zero(),
+        slot_number: U256::from(SLOT_NUMBER),
+        chain_id: U256::from(1),
+        base_fee_per_gas: U256::from(1000),
+        base_blob_fee_per_gas: U256::from(1),
+        gas_price: U256::from(1000),
+        block_excess_blob_gas: None,
+        block_blob_gas_used: None,
+        tx_blob_hashes: vec![],
+        tx_max_priority_fee_per_gas: None,
+        tx_max_fee_per_gas: Some(U256::from(1000)),
+        tx_max_fee_per_blob_gas: None,
+        tx_nonce: 0,
+        block_gas_limit: GAS_LIMIT * 2,
+        is_privileged: false,
+    };
+
+    let tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+        to: TxKind::Call(contract),
+        gas_limit: GAS_LIMIT,
+        max_fee_per_gas: 1000,
+        max_priority_fee_per_gas: 1,
+        ..Default::default()
+    });
+
+    let mut vm = VM::new(env, &mut db, &tx, LevmCallTracer::disabled(), VMType::L1).unwrap();
+    vm.execute().unwrap()
+}
+
+// ==================== Tests ====================
+
+#[test]
+fn slotnum_pushes_slot_number_on_amsterdam() {
+    let report = execute(Fork::Amsterdam, &RETURN_SLOTNUM_CODE);
+
+    assert!(matches!(report.result, TxResult::Success));
+    assert_eq!(
+        U256::from_big_endian(&report.output),
+        U256::from(SLOT_NUMBER)
+    );
+}
+
+#[test]
+fn slotnum_costs_base_gas() {
+    // TIMESTAMP costs the base gas, the rest of the code is the same
+    let slotnum = execute(Fork::Amsterdam, &RETURN_SLOTNUM_CODE);
+    let timestamp = execute(Fork::Amsterdam, &RETURN_TIMESTAMP_CODE);
+
+    assert!(matches!(timestamp.result, TxResult::Success));
+    assert_eq!(slotnum.gas_used, timestamp.gas_used);
+}
+
+#[test]
+fn slotnum_is_invalid_before_amsterdam() {
+    let report = execute(Fork::Osaka, &RETURN_SLOTNUM_CODE);
+
+    assert!(matches!(
+        report.result,
+        TxResult::Revert(VMError::ExceptionalHalt(ExceptionalHalt::InvalidOpcode))
+    ));
+    // An exceptional halt consumes all the gas
+    assert_eq!(report.gas_used, GAS_LIMIT);
+    assert!(report.output.is_empty());
+}
@@ -1,7 +1,7 @@
 #![allow(clippy::indexing_slicing, clippy::arithmetic_side_effects)]
 
 use ethrex_common::U256;
-use ethrex_levm::call_frame::Stack;
+use ethrex_levm::{call_frame::Stack, errors::ExceptionalHalt};
 
 /// Helper to setup a stack with specific values
 fn setup_stack_with_values(values: &[u64]) -> Stack {
@@ -108,6 +108,20 @@ fn test_stack_swap_depth_16() {
     assert_eq!(stack.pop1().unwrap(), U256::from(20));
 }
 
+// ==================== Stack Reuse Tests ====================
+
+#[test]
+fn test_stack_clear_reuse() {
+    let mut stack = setup_stack_with_values(&[1, 2, 3]);
+    stack.clear();
+
+    assert!(stack.is_empty());
+    assert_eq!(stack.pop1(), Err(ExceptionalHalt::StackUnderflow));
+    stack.push(U256::from(7)).unwrap();
+    assert_eq!(stack.len(), 1);
+    assert_eq!(stack.pop1().unwrap(), U256::from(7));
+}
+
 // ==================== EIP-8024 DUPN Decode Tests ====================
 
 #[test]
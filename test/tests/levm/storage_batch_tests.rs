@@ -0,0 +1,118 @@
+//! Tests for batched storage reads through the LEVM `Database` trait.
+//!
+//! Key behaviors tested:
+//! - The default `get_storage_values` matches per-slot `get_storage_value` reads
+//! - `CachingDatabase` only forwards cache misses to the inner database
+//! - Slots fetched in a batch are cached for later scalar reads
+
+use ethrex_common::{
+    Address, H256, U256,
+    types::{Account, Code},
+};
+use ethrex_levm::db::{CachingDatabase, Database};
+use rustc_hash::FxHashMap;
+use std::sync::Arc;
+
+use super::TestDatabase;
+
+const ACCOUNT: u64 = 0x1000;
+
+/// Backing database holding `slots` in the storage of `address`.
+fn with_slots(address: Address, slots: impl IntoIterator<Item = (u64, u64)>) -> TestDatabase {
+    let storage = slots
+        .into_iter()
+        .map(|(key, value)| (H256::from_low_u64_be(key), U256::from(value)))
+        .collect();
+    TestDatabase::new(FxHashMap::from_iter([(
+        address,
+        Account::new(U256::zero(), Code::default(), 0, storage),
+    )]))
+}
+
+fn keys(slots: &[u64]) -> Vec<H256> {
+    slots
+        .iter()
+        .map(|slot| H256::from_low_u64_be(*slot))
+        .collect()
+}
+
+#[test]
+fn default_batch_matches_scalar_reads() {
+    let address = Address::from_low_u64_be(ACCOUNT);
+    let db = with_slots(address, [(1, 10), (2, 20), (3, 30)]);
+    let keys = keys(&[3, 4, 1, 1]);
+
+    let batched = db.get_storage_values(address, &keys).unwrap();
+    let scalar: Vec<U256> = keys
+        .iter()
+        .map(|key| db.get_storage_value(address, *key).unwrap())
+        .collect();
+
+    assert_eq!(batched, scalar);
+    assert_eq!(
+        batched,
+        vec![U256::from(30), U256::zero(), U256::from(10), U256::from(10)]
+    );
+}
+
+#[test]
+fn caching_batch_matches_scalar_reads() {
+    let address = Address::from_low_u64_be(ACCOUNT);
+    let slots = (0..64).map(|i| (i, i * 3));
+    let inner = Arc::new(with_slots(address, slots.clone()));
+    let scalar_inner = Arc::new(with_slots(address, slots));
+    let batched_db = CachingDatabase::new(inner);
+    let scalar_db = CachingDatabase::new(scalar_inner);
+    let keys = keys(&[63, 0, 17, 100, 5]);
+
+    let batched = batched_db.get_storage_values(address, &keys).unwrap();
+    let scalar: Vec<U256> = keys
+        .iter()
+        .map(|key| scalar_db.get_storage_value(address, *key).unwrap())
+        .collect();
+
+    assert_eq!(batched, scalar);
+}
+
+#[test]
+fn caching_batch_only_forwards_misses() {
+    let address = Address::from_low_u64_be(ACCOUNT);
+    let inner = Arc::new(with_slots(address, [(1, 10), (2, 20), (3, 30)]));
+    let db = CachingDatabase::new(inner.clone());
+
+    // Warm slot 2 through a scalar read
+    assert_eq!(
+        db.get_storage_value(address, H256::from_low_u64_be(2))
+            .unwrap(),
+        U256::from(20)
+    );
+    assert_eq!(inner.slot_reads(), 1);
+
+    // Only slots 1 and 3 reach the inner database
+    let values = db.get_storage_values(address, &keys(&[1, 2, 3])).unwrap();
+    assert_eq!(values, vec![U256::from(10), U256::from(20), U256::from(30)]);
+    assert_eq!(inner.slot_reads(), 3);
+
+    // Everything is cached now, for both batched and scalar reads
+    db.get_storage_values(address, &keys(&[3, 2, 1])).unwrap();
+    db.get_storage_value(address, H256::from_low_u64_be(3))
+        .unwrap();
+    assert_eq!(inner.slot_reads(), 3);
+}
+
+#[test]
+fn caching_batch_keeps_accounts_separate() {
+    let address = Address::from_low_u64_be(ACCOUNT);
+    let other = Address::from_low_u64_be(ACCOUNT + 1);
+    let inner = Arc::new(with_slots(address, [(1, 10)]));
+    let db = CachingDatabase::new(inner);
+
+    assert_eq!(
+        db.get_storage_values(address, &keys(&[1])).unwrap(),
+        vec![U256::from(10)]
+    );
+    assert_eq!(
+        db.get_storage_values(other, &keys(&[1])).unwrap(),
+        vec![U256::zero()]
+    );
+}
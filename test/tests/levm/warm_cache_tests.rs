@@ -0,0 +1,223 @@
+//! Tests for sharing the block warmer's cache with the sequential executor.
+//!
+//! Key behaviors tested:
+//! - Executor reads served by entries the warmer loaded are counted
+//! - A cache no warmer ran on reports no warmer hits
+//! - The executor's store can be checked against the warm cache handle
+//! - Accounts cached as missing are counted apart and see their creation later in the block
+
+use std::{fs::File, io::BufReader, path::PathBuf, sync::Arc};
+
+use bytes::Bytes;
+use ethrex_blockchain::vm::StoreVmDatabase;
+use ethrex_common::{
+    Address, U256,
+    types::{Block, BlockBody, EIP1559Transaction, Genesis, GenesisAccount, Transaction, TxKind},
+};
+use ethrex_l2_rpc::signer::{LocalSigner, Signable, Signer};
+use ethrex_levm::vm::VMType;
+use ethrex_storage::{EngineType, Store};
+use ethrex_vm::{
+    Evm, SimulatedHeader, SimulationChain,
+    backends::{CachingDatabase, LevmDatabase, levm::LEVM},
+};
+use secp256k1::SecretKey;
+
+// Reads 64 consecutive storage slots starting at the slot given in calldata:
+// for n in (1..=64).rev() { SLOAD(start); start += 1 }
+const SLOAD_LOOP_CODE: &str = "60003560405b8015601a578154509060010190600190036005565b00";
+const SLOTS_PER_TX: u64 = 64;
+const SENDERS: u64 = 4;
+
+struct Setup {
+    store: Store,
+    block: Block,
+}
+
+#[tokio::test]
+async fn warmed_execution_reads_warmer_entries() {
+    let Setup { store, block } = setup().await;
+    let mut evm = base_evm(&store);
+
+    let cache = evm.warm_cache();
+    let cache = LEVM::warm_block(&block, cache, VMType::L1).unwrap();
+    assert!(cache.stats().warmed());
+
+    let (result, _) = evm.execute_block(&block).unwrap();
+    assert!(result.receipts.iter().all(|receipt| receipt.succeeded));
+
+    let stats = cache.stats();
+    assert!(stats.warmer_hits > 0);
+    // Every slot the transactions read was loaded by the warmer.
+    assert!(stats.warmer_hits >= SLOTS_PER_TX * SENDERS);
+    assert!(stats.warmer_hits <= stats.executor_hits);
+}
+
+#[tokio::test]
+async fn unwarmed_cache_has_no_warmer_hits() {
+    let Setup { store, block } = setup().await;
+    let mut evm = base_evm(&store);
+
+    let cache = evm.warm_cache();
+    evm.execute_block(&block).unwrap();
+
+    let stats = cache.stats();
+    assert!(!stats.warmed());
+    assert!(stats.executor_reads > 0);
+    assert_eq!(stats.warmer_hits, 0);
+}
+
+#[tokio::test]
+async fn executor_store_is_checked_against_warm_cache() {
+    let Setup { store, .. } = setup().await;
+    let mut evm = base_evm(&store);
+    let original_store = evm.db.store.clone();
+
+    let cache = evm.warm_cache();
+    assert!(cache.is_store(&evm.db.store));
+    assert!(!cache.is_store(&original_store));
+    assert!(!cache.is_store(&cache.warmer()));
+
+    let other: Arc<dyn LevmDatabase> = Arc::new(CachingDatabase::new(original_store));
+    assert!(!cache.is_store(&other));
+}
+
+#[tokio::test]
+async fn account_created_after_miss_in_block() {
+    let Setup { store, .. } = setup().await;
+    let chain_id = store.get_chain_config().chain_id;
+    let fresh = Address::from_low_u64_be(0xf4e5);
+
+    // Two senders fund the same fresh account
+    let mut transactions = Vec::new();
+    for signer in signers().iter().take(2) {
+        let mut tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+            chain_id,
+            nonce: 0,
+            to: TxKind::Call(fresh),
+            value: U256::one(),
+            gas_limit: 21_000,
+            max_fee_per_gas: 10_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+            ..Default::default()
+        });
+        tx.sign_inplace(signer).await.unwrap();
+        transactions.push(tx);
+    }
+    let block = build_block(&store, transactions);
+    let mut evm = base_evm(&store);
+
+    // The warmer caches the account as missing before the block creates it
+    let cache = evm.warm_cache();
+    let cache = LEVM::warm_block(&block, cache, VMType::L1).unwrap();
+    let (result, _) = evm.execute_block(&block).unwrap();
+    assert!(result.receipts.iter().all(|receipt| receipt.succeeded));
+    assert!(cache.stats().negative_hits > 0);
+
+    let update = evm
+        .get_state_transitions()
+        .unwrap()
+        .into_iter()
+        .find(|update| update.address == fresh)
+        .expect("fresh account is created");
+    assert_eq!(update.info.unwrap().balance, U256::from(2));
+}
+
+fn base_evm(store: &Store) -> Evm {
+    let genesis = store.get_block_header(0).unwrap().unwrap();
+    let vm_db = StoreVmDatabase::new(store.clone(), genesis).unwrap();
+    Evm::new_for_l1(vm_db)
+}
+
+fn workspace_root() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..")
+}
+
+/// Store with a contract holding `SLOTS_PER_TX * SENDERS` slots and a block
+/// where each sender reads its own range of them.
+async fn setup() -> Setup {
+    let file = File::open(workspace_root().join("fixtures/genesis/execution-api.json"))
+        .expect("Failed to open genesis file");
+    let reader = BufReader::new(file);
+    let mut genesis: Genesis =
+        serde_json::from_reader(reader).expect("Failed to deserialize genesis file");
+    let chain_id = genesis.config.chain_id;
+
+    let contract = Address::from_low_u64_be(0x5104d);
+    genesis.alloc.insert(
+        contract,
+        GenesisAccount {
+            code: Bytes::from(hex::decode(SLOAD_LOOP_CODE).unwrap()),
+            storage: (0..SLOTS_PER_TX * SENDERS)
+                .map(|slot| (U256::from(slot), U256::from(slot + 1)))
+                .collect(),
+            balance: U256::zero(),
+            nonce: 1,
+        },
+    );
+
+    let signers = signers();
+    for signer in &signers {
+        genesis.alloc.insert(
+            signer.address(),
+            GenesisAccount {
+                code: Bytes::new(),
+                storage: Default::default(),
+                balance: U256::from(10).pow(U256::from(20)),
+                nonce: 0,
+            },
+        );
+    }
+
+    let mut store =
+        Store::new("store.db", EngineType::InMemory).expect("Failed to build DB for testing");
+    store
+        .add_initial_state(genesis)
+        .await
+        .expect("Failed to add genesis state");
+
+    let mut transactions = Vec::new();
+    for (i, signer) in (0..).zip(&signers) {
+        let start = U256::from(i * SLOTS_PER_TX);
+        let mut tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+            chain_id,
+            nonce: 0,
+            to: TxKind::Call(contract),
+            data: Bytes::from(start.to_big_endian().to_vec()),
+            gas_limit: 500_000,
+            max_fee_per_gas: 10_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+            ..Default::default()
+        });
+        tx.sign_inplace(signer).await.unwrap();
+        transactions.push(tx);
+    }
+
+    let block = build_block(&store, transactions);
+    Setup { store, block }
+}
+
+fn signers() -> Vec<Signer> {
+    (1..=SENDERS)
+        .map(|i| {
+            let key = SecretKey::from_byte_array(&[i as u8; 32]).unwrap();
+            LocalSigner::new(key).into()
+        })
+        .collect()
+}
+
+/// Block with `transactions` on top of genesis.
+fn build_block(store: &Store, transactions: Vec<Transaction>) -> Block {
+    let genesis_header = store.get_block_header(0).unwrap().unwrap();
+    let header = SimulationChain::from_evm(&base_evm(store), genesis_header)
+        .unwrap()
+        .next_header(&SimulatedHeader::default(), &transactions);
+    Block::new(
+        header,
+        BlockBody {
+            transactions,
+            ommers: Vec::new(),
+            withdrawals: Some(Vec::new()),
+        },
+    )
+}
@@ -4,13 +4,13 @@ use ethrex_common::{
     Address, Bloom, H160,
     constants::{EMPTY_KECCACK_HASH, EMPTY_TRIE_HASH},
     types::{
-        AccountState, BlockBody, BlockHeader, ChainConfig, Code, Genesis, Receipt, Transaction,
-        TxType,
+        AccountInfo, AccountState, AccountUpdate, Block, BlockBody, BlockHeader, ChainConfig, Code,
+        Genesis, GenesisAccount, LegacyTransaction, Receipt, Transaction, TxType,
     },
     utils::keccak,
 };
 use ethrex_rlp::{decode::RLPDecode, encode::RLPEncode};
-use ethrex_storage::{EngineType, Store, error::StoreError};
+use ethrex_storage::{EngineType, Store, UpdateBatch, error::StoreError};
 use std::{fs, str::FromStr};
 
 #[tokio::test]
@@ -56,6 +56,12 @@ async fn test_store_suite(engine_type: EngineType) {
     run_test(test_genesis_block, engine_type).await;
     run_test(test_iter_accounts, engine_type).await;
     run_test(test_iter_storage, engine_type).await;
+    run_test(test_storage_values_batch, engine_type).await;
+    run_test(test_account_filter, engine_type).await;
+    run_test(test_prune_bodies_and_receipts, engine_type).await;
+    run_test(test_prune_while_reading_retained_blocks, engine_type).await;
+    run_test(test_prune_transaction_locations, engine_type).await;
+    run_test(test_canonical_block_hashes_range, engine_type).await;
 }
 
 async fn test_iter_accounts(store: Store) {
@@ -124,6 +130,146 @@ async fn test_iter_storage(store: Store) {
     }
 }
 
+async fn test_storage_values_batch(mut store: Store) {
+    const GENESIS_KURTOSIS: &str = include_str!("../../../fixtures/genesis/kurtosis.json");
+    let mut genesis: Genesis =
+        serde_json::from_str(GENESIS_KURTOSIS).expect("deserialize kurtosis.json");
+    let address = Address::from_low_u64_be(0xd157);
+    let storage = (1u64..=200)
+        .map(|i| (U256::from(i), U256::from(i * 7)))
+        .collect();
+    genesis.alloc.insert(
+        address,
+        GenesisAccount {
+            code: Bytes::from_static(&[0x00]),
+            storage,
+            balance: U256::zero(),
+            nonce: 1,
+        },
+    );
+    let state_root = genesis.get_block().header.state_root;
+    store
+        .add_initial_state(genesis)
+        .await
+        .expect("add initial state");
+
+    // Mix present, absent and repeated slots
+    let keys: Vec<H256> = [5u64, 0, 200, 201, 5, 150]
+        .iter()
+        .map(|i| H256::from_low_u64_be(*i))
+        .collect();
+    let batched = store
+        .get_storage_values_at_root(state_root, address, &keys)
+        .unwrap();
+    let scalar: Vec<_> = keys
+        .iter()
+        .map(|key| {
+            store
+                .get_storage_at_root(state_root, address, *key)
+                .unwrap()
+        })
+        .collect();
+    assert_eq!(batched, scalar);
+    assert_eq!(batched[0], Some(U256::from(35)));
+    assert_eq!(batched[1], None);
+    assert_eq!(batched[3], None);
+
+    // Unknown accounts yield no values at all
+    let missing = store
+        .get_storage_values_at_root(state_root, Address::from_low_u64_be(0xdead), &keys)
+        .unwrap();
+    assert_eq!(missing, vec![None; keys.len()]);
+}
+
+async fn test_account_filter(mut store: Store) {
+    const GENESIS_KURTOSIS: &str = include_str!("../../../fixtures/genesis/kurtosis.json");
+    let genesis: Genesis =
+        serde_json::from_str(GENESIS_KURTOSIS).expect("deserialize kurtosis.json");
+    let genesis_block = genesis.get_block();
+    let existing = *genesis.alloc.keys().next().expect("genesis has accounts");
+    let genesis_accounts = genesis.alloc.len();
+    store
+        .add_initial_state(genesis)
+        .await
+        .expect("add initial state");
+    let fresh: Vec<Address> = (0..100)
+        .map(|i| Address::from_low_u64_be(0xf4e5_0000 + i))
+        .collect();
+
+    // Nothing is ruled out until the filter is enabled
+    assert!(
+        fresh
+            .iter()
+            .all(|address| store.account_may_exist(*address))
+    );
+
+    let accounts = store.enable_account_filter().expect("enable filter");
+    assert!(accounts >= genesis_accounts);
+    assert!(store.account_may_exist(existing));
+    // Absent accounts are ruled out but for false positives
+    let ruled_out = fresh
+        .iter()
+        .filter(|address| !store.account_may_exist(**address))
+        .count();
+    assert!(ruled_out > 90);
+
+    // Creating a ruled out account must add it to the filter
+    let created = fresh
+        .iter()
+        .copied()
+        .find(|address| !store.account_may_exist(*address))
+        .expect("some account is ruled out");
+    let mut update = AccountUpdate::new(created);
+    update.info = Some(AccountInfo {
+        balance: U256::from(1),
+        ..Default::default()
+    });
+    let updates = store
+        .apply_account_updates_batch(genesis_block.hash(), &[update])
+        .expect("apply updates")
+        .expect("genesis state exists");
+    let block = Block::new(
+        BlockHeader {
+            number: 1,
+            parent_hash: genesis_block.hash(),
+            state_root: updates.state_trie_hash,
+            ..Default::default()
+        },
+        BlockBody::default(),
+    );
+    store
+        .store_block_updates(UpdateBatch {
+            account_updates: updates.state_updates,
+            storage_updates: updates.storage_updates,
+            blocks: vec![block],
+            receipts: vec![],
+            code_updates: vec![],
+        })
+        .expect("store block");
+
+    assert!(store.account_may_exist(created));
+    let state = store
+        .get_account_state_by_root(updates.state_trie_hash, created)
+        .unwrap()
+        .expect("created account exists");
+    assert_eq!(state.balance, U256::from(1));
+
+    // Disabled, nothing is ruled out; enabled again, it's rebuilt from the stored state
+    assert!(store.disable_account_filter().expect("disable filter"));
+    assert!(
+        fresh
+            .iter()
+            .all(|address| store.account_may_exist(*address))
+    );
+    store.enable_account_filter().expect("rebuild filter");
+    assert!(store.account_may_exist(created));
+    assert!(
+        fresh
+            .iter()
+            .any(|address| !store.account_may_exist(*address))
+    );
+}
+
 async fn test_genesis_block(mut store: Store) {
     const GENESIS_KURTOSIS: &str = include_str!("../../../fixtures/genesis/kurtosis.json");
     const GENESIS_HIVE: &str = include_str!("../../../fixtures/genesis/hive.json");
@@ -179,6 +325,229 @@ async fn test_store_block(store: Store) {
     assert_eq!(stored_body, block_body);
 }
 
+/// Adds `count` canonical blocks, each with the testing body and one receipt per transaction.
+/// Returns the block hashes by number.
+async fn add_canonical_chain(store: &Store, count: u64) -> Vec<H256> {
+    let (header, body) = create_block_for_testing();
+    let receipts: Vec<Receipt> = (0..body.transactions.len() as u64)
+        .map(|index| Receipt {
+            tx_type: TxType::EIP1559,
+            succeeded: true,
+            cumulative_gas_used: 21000 * (index + 1),
+            logs: vec![],
+        })
+        .collect();
+
+    let mut hashes = Vec::new();
+    for number in 0..count {
+        let header = BlockHeader {
+            number,
+            ..header.clone()
+        };
+        let hash = header.hash();
+        store.add_block_header(hash, header).await.unwrap();
+        store.add_block_body(hash, body.clone()).await.unwrap();
+        store.add_receipts(hash, receipts.clone()).await.unwrap();
+        hashes.push(hash);
+    }
+    let head = count - 1;
+    store
+        .forkchoice_update(
+            hashes
+                .iter()
+                .copied()
+                .enumerate()
+                .map(|(n, h)| (n as u64, h))
+                .collect(),
+            head,
+            hashes[head as usize],
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+    hashes
+}
+
+async fn test_canonical_block_hashes_range(store: Store) {
+    let hashes = add_canonical_chain(&store, 5).await;
+
+    let range = store.get_canonical_block_hashes_range_sync(2..8).unwrap();
+    assert_eq!(range, vec![(2, hashes[2]), (3, hashes[3]), (4, hashes[4])]);
+
+    assert!(
+        store
+            .get_canonical_block_hashes_range_sync(3..3)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+async fn test_prune_bodies_and_receipts(store: Store) {
+    let hashes = add_canonical_chain(&store, 10).await;
+
+    // Genesis is kept, so only blocks 1..5 are pruned
+    assert_eq!(store.prune_bodies_before(5).await.unwrap(), 4);
+    for number in 0..10u64 {
+        let body = store.get_block_body(number).await;
+        if number == 0 || number >= 5 {
+            assert!(body.unwrap().is_some(), "body of block {number}");
+        } else {
+            assert!(
+                matches!(body, Err(StoreError::PrunedHistory(n)) if n == number),
+                "body of block {number}"
+            );
+        }
+        // Headers, the canonical index and receipts are untouched
+        assert!(store.get_block_header(number).unwrap().is_some());
+        assert_eq!(
+            store.get_canonical_block_hash(number).await.unwrap(),
+            Some(hashes[number as usize])
+        );
+        assert_eq!(
+            store
+                .get_receipts_for_block(&hashes[number as usize])
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+    }
+
+    assert_eq!(store.prune_receipts_before(3).await.unwrap(), 2);
+    for number in 0..10u64 {
+        let receipts = store.get_receipts_for_block(&hashes[number as usize]).await;
+        if number == 0 || number >= 3 {
+            assert_eq!(receipts.unwrap().len(), 2, "receipts of block {number}");
+        } else {
+            assert!(
+                matches!(receipts, Err(StoreError::PrunedHistory(n)) if n == number),
+                "receipts of block {number}"
+            );
+        }
+    }
+    assert!(matches!(
+        store.get_receipt(1, 0).await,
+        Err(StoreError::PrunedHistory(1))
+    ));
+
+    // Pruning resumes from the previous call
+    assert_eq!(store.prune_bodies_before(5).await.unwrap(), 0);
+    assert_eq!(store.prune_bodies_before(7).await.unwrap(), 2);
+    assert!(matches!(
+        store.get_block_body(6).await,
+        Err(StoreError::PrunedHistory(6))
+    ));
+    assert!(store.get_block_body(7).await.unwrap().is_some());
+}
+
+async fn test_prune_transaction_locations(store: Store) {
+    let (header, _) = create_block_for_testing();
+    let mut blocks = Vec::new();
+    for number in 0..4u64 {
+        let header = BlockHeader {
+            number,
+            ..header.clone()
+        };
+        // A distinct transaction per block, so each location is only reachable through its block
+        let tx = Transaction::LegacyTransaction(LegacyTransaction {
+            nonce: number,
+            ..Default::default()
+        });
+        let hash = header.hash();
+        store
+            .add_transaction_location(tx.hash(), number, hash, 0)
+            .await
+            .unwrap();
+        store.add_block_header(hash, header).await.unwrap();
+        store
+            .add_block_body(
+                hash,
+                BlockBody {
+                    transactions: vec![tx.clone()],
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        blocks.push((hash, tx.hash()));
+    }
+    store
+        .forkchoice_update(
+            blocks
+                .iter()
+                .enumerate()
+                .map(|(n, (hash, _))| (n as u64, *hash))
+                .collect(),
+            3,
+            blocks[3].0,
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(store.prune_bodies_before(3).await.unwrap(), 2);
+    for (number, (block_hash, tx_hash)) in blocks.into_iter().enumerate() {
+        let location = store.get_transaction_location(tx_hash).await.unwrap();
+        if number == 0 || number == 3 {
+            assert_eq!(
+                location,
+                Some((number as u64, block_hash, 0)),
+                "location of the transaction in block {number}"
+            );
+        } else {
+            assert_eq!(
+                location, None,
+                "location of the transaction in block {number}"
+            );
+        }
+    }
+}
+
+async fn test_prune_while_reading_retained_blocks(store: Store) {
+    // Enough blocks for pruning to span several write batches
+    let count = 3000;
+    let retained_from = 2500;
+    let hashes = add_canonical_chain(&store, count).await;
+
+    let pruner = {
+        let store = store.clone();
+        tokio::spawn(async move {
+            store.prune_bodies_before(retained_from).await.unwrap();
+            store.prune_receipts_before(retained_from).await.unwrap();
+        })
+    };
+
+    // Keep reading the inputs needed to replay retained blocks until pruning is done
+    let mut reads = 0;
+    while !pruner.is_finished() || reads == 0 {
+        for number in [retained_from, count - 1] {
+            let body = store.get_block_body(number).await.unwrap();
+            assert!(body.is_some(), "retained body {number} disappeared");
+            let receipts = store
+                .get_receipts_for_block(&hashes[number as usize])
+                .await
+                .unwrap();
+            assert_eq!(receipts.len(), 2, "retained receipts {number} disappeared");
+        }
+        reads += 1;
+        tokio::task::yield_now().await;
+    }
+    pruner.await.unwrap();
+
+    assert!(matches!(
+        store.get_block_body(retained_from - 1).await,
+        Err(StoreError::PrunedHistory(_))
+    ));
+    assert!(matches!(
+        store
+            .get_receipts_for_block(&hashes[retained_from as usize - 1])
+            .await,
+        Err(StoreError::PrunedHistory(_))
+    ));
+}
+
 fn create_block_for_testing() -> (BlockHeader, BlockBody) {
     let block_header = BlockHeader {
         parent_hash: H256::from_str(
@@ -5,7 +5,8 @@ lazy_static::lazy_static! {
 use clap::{ArgGroup, Parser};
 use ethrex::initializers::open_store;
 use ethrex::utils::{default_datadir, init_datadir};
-use ethrex_common::types::{BlockHash, Code};
+use ethrex_common::tracing::{NativePrestate, PrestateAccount};
+use ethrex_common::types::{BlockHash, ChainConfig, Code};
 use ethrex_common::utils::keccak;
 use ethrex_common::{Address, serde_utils};
 use ethrex_common::{BigEndianHash, Bytes, H256, U256, types::BlockNumber};
@@ -19,13 +20,15 @@ use ethrex_rpc::utils::RpcResponse;
 use ethrex_storage::Store;
 use serde::{Deserialize, Serialize};
 use serde_json::{Value, json};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs::File;
 use std::io::{self, Read, Write};
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Instant;
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::UnixStream;
+use tokio::sync::{Mutex, OwnedSemaphorePermit, Semaphore, mpsc};
 use tokio::task::JoinSet;
 use tracing::{debug, info};
 use tracing_subscriber::FmtSubscriber;
@@ -36,6 +39,23 @@ const MAX_ACCOUNTS: usize = 256;
 const BLOCK_HASH_LOOKUP_DEPTH: u64 = 128;
 /// Amount of state dumps to process before updating checkpoint
 const DUMPS_BEFORE_CHECKPOINT: usize = 10;
+/// Name of the file written to the datadir after a partial sync
+const PARTIAL_STATE_MANIFEST: &str = "partial_state.json";
+/// Extension of the manifest written next to an exported prestate file
+const PRESTATE_MANIFEST_EXTENSION: &str = "manifest.json";
+/// Approximate memory taken by a storage slot waiting to be inserted into its trie (key + value)
+const STORAGE_SLOT_SIZE: usize = 64;
+/// Max amount of storage slots inserted into a trie before committing it
+const STORAGE_CHUNK_SLOTS: usize = 16_384;
+/// Default amount of workers building storage tries
+const DEFAULT_STORAGE_WORKERS: usize = 16;
+/// Default memory cap (in MiB) of the storage slots waiting to be inserted into their tries
+const DEFAULT_STORAGE_MEMORY_CAP_MB: usize = 1024;
+
+const PUSH1: u8 = 0x60;
+const PUSH20: u8 = 0x73;
+const PUSH32: u8 = 0x7f;
+const DELEGATECALL: u8 = 0xf4;
 
 #[derive(Deserialize, Debug, Serialize)]
 struct Dump {
@@ -64,6 +84,154 @@ struct DumpAccount {
     hashed_address: Option<H256>,
 }
 
+/// Restricts which dump accounts are written to the state trie
+#[derive(Deserialize, Debug, Serialize, Default, Clone)]
+pub struct AccountFilter {
+    /// Accounts to write to the trie, including the delegatecall targets found so far
+    addresses: BTreeSet<Address>,
+    /// If true, addresses pushed by included contracts that use DELEGATECALL are included too
+    include_delegate_targets: bool,
+    /// Accounts already written to the trie
+    included: BTreeSet<Address>,
+    /// Accounts that are not part of the block's state
+    absent: BTreeSet<Address>,
+}
+
+impl AccountFilter {
+    pub fn new(
+        addresses: impl IntoIterator<Item = Address>,
+        include_delegate_targets: bool,
+    ) -> Self {
+        Self {
+            addresses: addresses.into_iter().collect(),
+            include_delegate_targets,
+            ..Default::default()
+        }
+    }
+
+    /// Continues the filtering done by a previous run, keeping the addresses requested by both
+    fn resume(&mut self, prev_filter: AccountFilter) {
+        self.addresses.extend(prev_filter.addresses);
+        self.included = prev_filter.included;
+        self.absent = prev_filter.absent;
+    }
+
+    /// Returns true if the account should be written to the trie, marking it as included
+    fn include(&mut self, address: Address, dump_account: &DumpAccount) -> bool {
+        if !self.addresses.contains(&address) {
+            return false;
+        }
+        if self.include_delegate_targets {
+            self.addresses.extend(delegate_targets(&dump_account.code));
+        }
+        self.included.insert(address);
+        true
+    }
+
+    /// Requested accounts that weren't found in the dumps processed so far
+    fn missing(&self) -> BTreeSet<Address> {
+        self.addresses
+            .iter()
+            .filter(|address| !self.included.contains(address) && !self.absent.contains(address))
+            .copied()
+            .collect()
+    }
+}
+
+/// Settings of a partial sync, which only writes a subset of the accounts to the state trie
+pub struct PartialSync {
+    pub filter: AccountFilter,
+    /// Path where the manifest of the partial state is written
+    pub manifest_path: PathBuf,
+}
+
+/// Written after a partial sync so that downstream tools know the state is incomplete
+#[derive(Deserialize, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PartialStateManifest {
+    block_number: BlockNumber,
+    /// Hash of the block as received from the archive node
+    block_hash: BlockHash,
+    /// State root of the block as received from the archive node
+    state_root: H256,
+    /// Hash of the stored block, which has `synthetic_state_root` as state root
+    synthetic_block_hash: BlockHash,
+    /// Root of the partial state
+    synthetic_state_root: H256,
+    /// Accounts in the partial state
+    accounts: BTreeSet<Address>,
+}
+
+/// Settings of a prestate export, which writes a few accounts to a prestate file instead of syncing
+pub struct PrestateExport {
+    /// Accounts to export
+    pub addresses: BTreeSet<Address>,
+    /// Storage slots to export, all of them if empty
+    pub slots: BTreeSet<H256>,
+    /// Levels of contracts referenced by the exported storage to export too
+    pub recursion_depth: usize,
+    /// Path where the prestate is written, the manifest is written next to it
+    pub output_path: PathBuf,
+}
+
+/// Written next to an exported prestate so that its origin and contents are known
+#[derive(Deserialize, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrestateExportManifest {
+    block_number: BlockNumber,
+    block_hash: BlockHash,
+    state_root: H256,
+    /// Accounts requested for the export
+    requested: BTreeSet<Address>,
+    /// Contracts found in the storage of the exported accounts, with the depth they were found at
+    referenced: BTreeMap<Address, usize>,
+    /// Requested accounts that are not part of the block's state
+    absent: BTreeSet<Address>,
+    /// Storage slots exported, all of them if empty
+    slots: BTreeSet<H256>,
+}
+
+/// Returns the addresses pushed by `code` if it contains a DELEGATECALL, as they may be its
+/// implementation or libraries. This is a static scan, so it may yield addresses that are never called
+fn delegate_targets(code: &[u8]) -> Vec<Address> {
+    let mut targets = Vec::new();
+    let mut has_delegatecall = false;
+    let mut pc = 0;
+    while let Some(&opcode) = code.get(pc) {
+        match opcode {
+            PUSH1..=PUSH32 => {
+                let push_size = usize::from(opcode - PUSH1) + 1;
+                if opcode == PUSH20
+                    && let Some(address) = code.get(pc + 1..pc + 1 + push_size)
+                {
+                    targets.push(Address::from_slice(address));
+                }
+                pc += push_size;
+            }
+            DELEGATECALL => has_delegatecall = true,
+            _ => {}
+        }
+        pc += 1;
+    }
+    if has_delegatecall {
+        targets
+    } else {
+        Vec::new()
+    }
+}
+
+/// Returns the storage values that look like addresses, such as the implementation of a proxy or a
+/// token. Values below 2^64 are taken for numbers rather than addresses
+fn address_values(storage: &BTreeMap<H256, H256>) -> impl Iterator<Item = Address> + '_ {
+    storage.values().filter_map(|value| {
+        let (padding, address) = value.as_bytes().split_at(12);
+        let is_address =
+            padding.iter().all(|byte| *byte == 0) && value.into_uint() > U256::from(u64::MAX);
+        is_address.then(|| Address::from_slice(address))
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
 pub async fn archive_sync(
     archive_ipc_path: Option<String>,
     block_number: BlockNumber,
@@ -72,6 +240,8 @@ pub async fn archive_sync(
     no_sync: bool,
     checkpoint: Option<String>,
     store: Store,
+    partial: Option<PartialSync>,
+    storage_settings: StorageTrieSettings,
 ) -> eyre::Result<()> {
     let sync_start: Instant = Instant::now();
     // Load checkpoint (if we have one)
@@ -81,6 +251,7 @@ pub async fn archive_sync(
         input_dir.is_some(),
         output_dir.is_some(),
         no_sync,
+        partial.is_some(),
     )?;
     let mut dump_reader = if let Some(ipc_path) = archive_ipc_path {
         DumpReader::new_from_ipc(&ipc_path, block_number, &prev_checkpoint).await?
@@ -93,7 +264,13 @@ pub async fn archive_sync(
     let mut dump_processor = if no_sync {
         DumpProcessor::new_no_sync(dump_writer)
     } else {
-        DumpProcessor::new_sync(dump_writer, store, &prev_checkpoint)
+        DumpProcessor::new_sync(
+            dump_writer,
+            store,
+            partial,
+            storage_settings,
+            &prev_checkpoint,
+        )
     };
     let mut should_continue = true;
     let mut dumps_since_checkpoint = 0;
@@ -106,6 +283,8 @@ pub async fn archive_sync(
             dumps_since_checkpoint += 1;
             if dumps_since_checkpoint >= DUMPS_BEFORE_CHECKPOINT || !should_continue {
                 dumps_since_checkpoint = 0;
+                // The checkpoint's root must only cover accounts whose storage is already written
+                dump_processor.finish_storage_tries().await?;
                 let checkpoint = CheckPoint {
                     processing: dump_processor.get_checkpoint(),
                     reading: dump_reader.get_checkpoint(),
@@ -115,6 +294,11 @@ pub async fn archive_sync(
             }
         }
     }
+    // Accounts requested by a partial sync may belong to dumps processed before they were
+    // requested (e.g. delegatecall targets), so we fetch them separately
+    dump_processor
+        .process_missing_accounts(&mut dump_reader)
+        .await?;
     // Fetch the block itself so we can mark it as canonical
     let rlp_block = dump_reader.read_rlp_block().await?;
     // Fetch the block hashes of the previous `BLOCK_HASH_LOOKUP_DEPTH` blocks
@@ -129,12 +313,112 @@ pub async fn archive_sync(
     Ok(())
 }
 
-/// Adds all dump accounts to the trie on top of the current root, returns the next root
+/// Writes the accounts to export as of the target block to a prestate file, which can be loaded
+/// to replay calls against them without syncing the rest of the state
+pub async fn export_prestate(
+    archive_ipc_path: Option<String>,
+    block_number: BlockNumber,
+    input_dir: Option<String>,
+    chain_config: ChainConfig,
+    export: PrestateExport,
+) -> eyre::Result<()> {
+    let mut dump_reader = if let Some(ipc_path) = archive_ipc_path {
+        DumpReader::new_from_ipc(&ipc_path, block_number, &None).await?
+    } else {
+        let input_dir = input_dir
+            .ok_or_else(|| eyre::Error::msg("Either an IPC path or an input dir is needed"))?;
+        DumpReader::new_from_dir(input_dir, &None)?
+    };
+    let block = Block::decode(&dump_reader.read_rlp_block().await?)?;
+    let block_hash = block.header.hash();
+    // The hash of the target block is needed too, as replays run on top of it
+    let mut block_hashes: BTreeMap<BlockNumber, BlockHash> =
+        dump_reader.read_block_hashes().await?.into_iter().collect();
+    block_hashes.insert(block.header.number, block_hash);
+
+    let mut prestate = NativePrestate {
+        chain_config,
+        block_hashes,
+        ..Default::default()
+    };
+    let mut referenced = BTreeMap::new();
+    let mut absent = BTreeSet::new();
+    let mut requested = export.addresses.clone();
+    let mut pending = export.addresses.clone();
+    for depth in 0..=export.recursion_depth {
+        if pending.is_empty() {
+            break;
+        }
+        let mut accounts = dump_reader.read_accounts(&pending).await?;
+        let mut found = BTreeSet::new();
+        for address in pending {
+            let Some(dump_account) = accounts.remove(&address) else {
+                if depth == 0 {
+                    absent.insert(address);
+                }
+                continue;
+            };
+            if depth > 0 {
+                // Only contracts are pulled in, other values are likely not addresses
+                if dump_account.code.is_empty() {
+                    continue;
+                }
+                referenced.insert(address, depth);
+            }
+            if dump_account.storage_root != *EMPTY_TRIE_HASH {
+                prestate.accounts_with_storage.insert(address);
+            }
+            let account = dump_account.into_prestate_account(&export.slots);
+            found.extend(address_values(&account.storage));
+            prestate.accounts.insert(address, account);
+        }
+        pending = found.difference(&requested).copied().collect();
+        requested.extend(&pending);
+    }
+    info!(
+        "Exporting {} accounts, {} of them referenced by storage",
+        prestate.accounts.len(),
+        referenced.len()
+    );
+
+    let manifest = PrestateExportManifest {
+        block_number: block.header.number,
+        block_hash,
+        state_root: block.header.state_root,
+        requested: export.addresses,
+        referenced,
+        absent,
+        slots: export.slots,
+    };
+    serde_json::to_writer_pretty(File::create(&export.output_path)?, &prestate)?;
+    serde_json::to_writer_pretty(
+        File::create(
+            export
+                .output_path
+                .with_extension(PRESTATE_MANIFEST_EXTENSION),
+        )?,
+        &manifest,
+    )?;
+    Ok(())
+}
+
+/// Adds all dump accounts accepted by the filter (if any) to the trie on top of the current root, returns the next root
+/// Their storage tries are built by the storage pool, which may still be building them when this returns
 /// This could be improved in the future to use an in_memory trie with async db writes
-async fn process_dump(dump: Dump, store: Store, current_root: H256) -> eyre::Result<H256> {
-    let mut storage_tasks = JoinSet::new();
+async fn process_dump(
+    dump: Dump,
+    store: Store,
+    current_root: H256,
+    mut filter: Option<&mut AccountFilter>,
+    storage_pool: &mut StorageTriePool,
+) -> eyre::Result<H256> {
     let mut state_trie = store.open_direct_state_trie(current_root)?;
-    for (address, dump_account) in dump.accounts.into_iter() {
+    let accounts = dump.accounts.into_iter().filter(|(address, dump_account)| {
+        filter
+            .as_deref_mut()
+            .is_none_or(|filter| filter.include(*address, dump_account))
+    });
+    for (address, dump_account) in accounts {
         let hashed_address = dump_account
             .hashed_address
             .unwrap_or_else(|| keccak(address));
@@ -152,32 +436,159 @@ async fn process_dump(dump: Dump, store: Store, current_root: H256) -> eyre::Res
         }
         // Process storage trie if it is not empty
         if dump_account.storage_root != *EMPTY_TRIE_HASH {
-            storage_tasks.spawn(process_dump_storage(
-                dump_account.storage,
-                store.clone(),
-                hashed_address,
-                dump_account.storage_root,
-            ));
+            storage_pool
+                .submit(
+                    hashed_address,
+                    dump_account.storage_root,
+                    dump_account.storage,
+                )
+                .await?;
         }
     }
-    for res in storage_tasks.join_all().await {
-        res?;
-    }
     Ok(state_trie.hash()?)
 }
 
-async fn process_dump_storage(
-    dump_storage: HashMap<H256, U256>,
-    store: Store,
+/// Settings of the workers building the storage tries
+#[derive(Debug, Clone, Copy)]
+pub struct StorageTrieSettings {
+    /// Amount of storage tries built concurrently
+    pub workers: usize,
+    /// Max memory (in bytes) taken by the storage slots waiting to be inserted into their tries
+    /// Reading dumps is paused while it is reached
+    pub memory_cap: usize,
+}
+
+impl Default for StorageTrieSettings {
+    fn default() -> Self {
+        Self {
+            workers: DEFAULT_STORAGE_WORKERS,
+            memory_cap: DEFAULT_STORAGE_MEMORY_CAP_MB * 1024 * 1024,
+        }
+    }
+}
+
+impl StorageTrieSettings {
+    /// Max amount of storage slots waiting to be inserted into their tries
+    fn max_in_flight_slots(&self) -> usize {
+        (self.memory_cap / STORAGE_SLOT_SIZE).max(1)
+    }
+}
+
+/// Storage slots to insert into an account's trie, holding their share of the memory cap until inserted
+struct StorageChunk {
+    slots: Vec<(H256, U256)>,
+    _permit: OwnedSemaphorePermit,
+}
+
+/// Storage trie to build, its slots arrive in chunks which are inserted in order
+struct StorageJob {
     hashed_address: H256,
     storage_root: H256,
+    chunks: mpsc::UnboundedReceiver<StorageChunk>,
+}
+
+/// Fixed amount of workers building the storage tries of the dump accounts
+/// Submitting storage waits while the slots not yet inserted take more than the memory cap
+struct StorageTriePool {
+    settings: StorageTrieSettings,
+    jobs: mpsc::UnboundedSender<StorageJob>,
+    workers: JoinSet<eyre::Result<()>>,
+    /// One permit per storage slot that can wait to be inserted
+    memory: Arc<Semaphore>,
+    peak_in_flight_slots: usize,
+}
+
+impl StorageTriePool {
+    fn spawn(store: Store, settings: StorageTrieSettings) -> Self {
+        let (jobs, receiver) = mpsc::unbounded_channel();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let mut workers = JoinSet::new();
+        for _ in 0..settings.workers.max(1) {
+            workers.spawn(storage_trie_worker(store.clone(), receiver.clone()));
+        }
+        Self {
+            settings,
+            jobs,
+            workers,
+            memory: Arc::new(Semaphore::new(settings.max_in_flight_slots())),
+            peak_in_flight_slots: 0,
+        }
+    }
+
+    /// Queues the storage trie of an account, splitting its storage into chunks
+    /// Waits for memory to be released by the workers if the cap is reached
+    async fn submit(
+        &mut self,
+        hashed_address: H256,
+        storage_root: H256,
+        storage: HashMap<H256, U256>,
+    ) -> eyre::Result<()> {
+        let (chunk_sender, chunks) = mpsc::unbounded_channel();
+        self.jobs
+            .send(StorageJob {
+                hashed_address,
+                storage_root,
+                chunks,
+            })
+            .map_err(|_| eyre::ErrReport::msg("Storage trie workers stopped"))?;
+        let max_in_flight_slots = self.settings.max_in_flight_slots();
+        let chunk_slots = STORAGE_CHUNK_SLOTS.min(max_in_flight_slots);
+        let mut slots = storage.into_iter();
+        loop {
+            let len = slots.len().min(chunk_slots);
+            if len == 0 {
+                return Ok(());
+            }
+            let permit = self
+                .memory
+                .clone()
+                .acquire_many_owned(u32::try_from(len)?)
+                .await?;
+            let in_flight_slots = max_in_flight_slots - self.memory.available_permits();
+            self.peak_in_flight_slots = self.peak_in_flight_slots.max(in_flight_slots);
+            chunk_sender
+                .send(StorageChunk {
+                    slots: slots.by_ref().take(len).collect(),
+                    _permit: permit,
+                })
+                .map_err(|_| eyre::ErrReport::msg("Storage trie workers stopped"))?;
+        }
+    }
+
+    /// Waits for all queued storage tries to be built, failing if any of them doesn't match its root
+    async fn finish(self) -> eyre::Result<()> {
+        drop(self.jobs);
+        for res in self.workers.join_all().await {
+            res?;
+        }
+        Ok(())
+    }
+}
+
+async fn storage_trie_worker(
+    store: Store,
+    jobs: Arc<Mutex<mpsc::UnboundedReceiver<StorageJob>>>,
 ) -> eyre::Result<()> {
-    let mut trie = store.open_direct_storage_trie(hashed_address, *EMPTY_TRIE_HASH)?;
-    for (key, val) in dump_storage {
-        // The key we receive is the preimage of the one stored in the trie
-        trie.insert(keccak(key.0).0.to_vec(), val.encode_to_vec())?;
+    loop {
+        let Some(job) = jobs.lock().await.recv().await else {
+            return Ok(());
+        };
+        process_dump_storage(&store, job).await?;
     }
-    if trie.hash()? != storage_root {
+}
+
+async fn process_dump_storage(store: &Store, mut job: StorageJob) -> eyre::Result<()> {
+    let mut storage_root = *EMPTY_TRIE_HASH;
+    while let Some(chunk) = job.chunks.recv().await {
+        // Reopening the trie after each chunk keeps only the nodes touched by a chunk in memory
+        let mut trie = store.open_direct_storage_trie(job.hashed_address, storage_root)?;
+        for (key, val) in chunk.slots {
+            // The key we receive is the preimage of the one stored in the trie
+            trie.insert(keccak(key.0).0.to_vec(), val.encode_to_vec())?;
+        }
+        storage_root = trie.hash()?;
+    }
+    if storage_root != job.storage_root {
         Err(eyre::ErrReport::msg(
             "Storage root doesn't match the one in the account during archive sync",
         ))
@@ -216,6 +627,21 @@ impl DumpAccount {
             code_hash: self.code_hash,
         }
     }
+
+    /// Converts the account to the prestate format, keeping only the given storage slots unless empty
+    fn into_prestate_account(self, slots: &BTreeSet<H256>) -> PrestateAccount {
+        PrestateAccount {
+            balance: self.balance,
+            nonce: self.nonce,
+            code: self.code,
+            storage: self
+                .storage
+                .into_iter()
+                .filter(|(key, _)| slots.is_empty() || slots.contains(key))
+                .map(|(key, value)| (key, H256::from_uint(&value)))
+                .collect(),
+        }
+    }
 }
 
 fn mseconds_to_readable(mut mseconds: u128) -> String {
@@ -249,6 +675,10 @@ struct DumpProcessor {
     // Current Trie Root + Store. Set to None if state sync is disabled
     sync_state: Option<(H256, Store)>,
     writer: Option<DumpDirWriter>,
+    // Set if only a subset of the accounts is synced
+    partial: Option<PartialSync>,
+    // Workers building the storage tries of the processed accounts. Set to None if state sync is disabled
+    storage_pool: Option<StorageTriePool>,
 }
 
 impl DumpProcessor {
@@ -257,8 +687,17 @@ impl DumpProcessor {
     fn new_sync(
         writer: Option<DumpDirWriter>,
         store: Store,
+        mut partial: Option<PartialSync>,
+        storage_settings: StorageTrieSettings,
         prev_checkpoint: &Option<CheckPoint>,
     ) -> Self {
+        if let Some((partial, prev_filter)) = partial.as_mut().zip(
+            prev_checkpoint
+                .as_ref()
+                .and_then(|checkpoint| checkpoint.processing.filter.clone()),
+        ) {
+            partial.filter.resume(prev_filter);
+        }
         Self {
             state_root: None,
             sync_state: Some((
@@ -266,9 +705,11 @@ impl DumpProcessor {
                     .as_ref()
                     .and_then(|check_point| check_point.processing.current_root)
                     .unwrap_or(*EMPTY_TRIE_HASH),
-                store,
+                store.clone(),
             )),
             writer,
+            partial,
+            storage_pool: Some(StorageTriePool::spawn(store, storage_settings)),
         }
     }
 
@@ -278,6 +719,8 @@ impl DumpProcessor {
             state_root: None,
             sync_state: None,
             writer,
+            partial: None,
+            storage_pool: None,
         }
     }
 
@@ -296,9 +739,13 @@ impl DumpProcessor {
             writer.write_dump(&dump)?;
         }
         // Process dump
-        if let Some((current_root, store)) = self.sync_state.as_mut() {
+        if let Some((current_root, store)) = self.sync_state.as_mut()
+            && let Some(storage_pool) = self.storage_pool.as_mut()
+        {
             let instant = Instant::now();
-            *current_root = process_dump(dump, store.clone(), *current_root).await?;
+            let filter = self.partial.as_mut().map(|partial| &mut partial.filter);
+            *current_root =
+                process_dump(dump, store.clone(), *current_root, filter, storage_pool).await?;
             info!(
                 "Processed Dump of {MAX_ACCOUNTS} accounts in {}",
                 mseconds_to_readable(instant.elapsed().as_millis())
@@ -307,8 +754,47 @@ impl DumpProcessor {
         Ok(should_continue)
     }
 
+    /// Fetch and process the accounts requested by a partial sync that weren't found in the dumps,
+    /// until all requested accounts (including the ones they delegatecall into) are processed
+    async fn process_missing_accounts(&mut self, dump_reader: &mut DumpReader) -> eyre::Result<()> {
+        let (Some((current_root, store)), Some(partial), Some(storage_pool)) = (
+            self.sync_state.as_mut(),
+            self.partial.as_mut(),
+            self.storage_pool.as_mut(),
+        ) else {
+            return Ok(());
+        };
+        loop {
+            let missing = partial.filter.missing();
+            if missing.is_empty() {
+                return Ok(());
+            }
+            let accounts = dump_reader.read_accounts(&missing).await?;
+            partial.filter.absent.extend(
+                missing
+                    .into_iter()
+                    .filter(|address| !accounts.contains_key(address)),
+            );
+            let dump = Dump {
+                state_root: self.state_root.unwrap_or_default(),
+                accounts,
+                next: None,
+            };
+            *current_root = process_dump(
+                dump,
+                store.clone(),
+                *current_root,
+                Some(&mut partial.filter),
+                storage_pool,
+            )
+            .await?;
+        }
+    }
+
     /// Process the incoming RLP-encoded Block by either writing it to a file and/or adding it as head of the canonical chain.
     /// In the later case, the rebuilt state root will be chacked againts the block's state root
+    /// For partial syncs the check is skipped and the block is stored with the partial state root instead,
+    /// writing a manifest of the partial state
     /// Processes the incoming list of block hashes by either writing them to a file and/or marking
     /// them as part of the canonical chain. This will be necessary in order to execute blocks after the target block
     async fn process_rlp_block_and_block_hashes(
@@ -320,12 +806,32 @@ impl DumpProcessor {
             writer.write_rlp_block(&rlp_block)?;
             writer.write_hashes_file(&block_hashes)?;
         }
+        self.finish_storage_tries().await?;
         if let Some((current_root, store)) = self.sync_state.as_ref() {
-            let block = Block::decode(&rlp_block)?;
+            let mut block = Block::decode(&rlp_block)?;
             let block_number = block.header.number;
-            let block_hash = block.hash();
+            let mut block_hash = block.hash();
 
-            if *current_root != block.header.state_root {
+            if let Some(partial) = self.partial.as_ref() {
+                let state_root = block.header.state_root;
+                block.header.state_root = *current_root;
+                block.header.hash = Default::default();
+                let manifest = PartialStateManifest {
+                    block_number,
+                    block_hash,
+                    state_root,
+                    synthetic_block_hash: block.hash(),
+                    synthetic_state_root: *current_root,
+                    accounts: partial.filter.included.clone(),
+                };
+                serde_json::to_writer_pretty(File::create(&partial.manifest_path)?, &manifest)?;
+                info!(
+                    "Partial state of {} accounts synced, manifest written to {}",
+                    manifest.accounts.len(),
+                    partial.manifest_path.display()
+                );
+                block_hash = manifest.synthetic_block_hash;
+            } else if *current_root != block.header.state_root {
                 return Err(eyre::ErrReport::msg(
                     "State root doesn't match the one in the header after archive sync",
                 ));
@@ -340,6 +846,18 @@ impl DumpProcessor {
         Ok(())
     }
 
+    /// Wait for the storage tries of all processed accounts to be built and checked against their storage roots
+    async fn finish_storage_tries(&mut self) -> eyre::Result<()> {
+        if let Some((_, store)) = self.sync_state.as_ref()
+            && let Some(storage_pool) = self.storage_pool.take()
+        {
+            let settings = storage_pool.settings;
+            storage_pool.finish().await?;
+            self.storage_pool = Some(StorageTriePool::spawn(store.clone(), settings));
+        }
+        Ok(())
+    }
+
     fn get_checkpoint(&self) -> ProcessingCheckpoint {
         ProcessingCheckpoint {
             current_root: self
@@ -347,6 +865,7 @@ impl DumpProcessor {
                 .as_ref()
                 .map(|(current_root, _)| *current_root),
             current_file: self.writer.as_ref().map(|writer| writer.current_file),
+            filter: self.partial.as_ref().map(|partial| partial.filter.clone()),
         }
     }
 }
@@ -482,6 +1001,18 @@ impl DumpReader {
         }
     }
 
+    /// Read the given accounts, either by scanning the dump files or by requesting them to the archive node.
+    /// Accounts that are not part of the state are not returned
+    async fn read_accounts(
+        &mut self,
+        addresses: &BTreeSet<Address>,
+    ) -> eyre::Result<HashMap<Address, DumpAccount>> {
+        match self {
+            DumpReader::Dir(dump_dir_reader) => dump_dir_reader.read_accounts(addresses),
+            DumpReader::Ipc(dump_ipc_reader) => dump_ipc_reader.read_accounts(addresses).await,
+        }
+    }
+
     fn get_checkpoint(&self) -> ReadingCheckpoint {
         let mut checkpoint = ReadingCheckpoint::default();
         match self {
@@ -513,6 +1044,27 @@ impl DumpDirReader {
         Ok(serde_json::from_reader(dump_file)?)
     }
 
+    /// Scan all dump files in the directory set at creation for the given accounts
+    fn read_accounts(
+        &self,
+        addresses: &BTreeSet<Address>,
+    ) -> eyre::Result<HashMap<Address, DumpAccount>> {
+        let mut accounts = HashMap::new();
+        for file_number in 0.. {
+            let path = std::path::Path::new(&self.dirname).join(format!("dump_{file_number}.json"));
+            if !path.exists() {
+                break;
+            }
+            let dump: Dump = serde_json::from_reader(File::open(path)?)?;
+            accounts.extend(
+                dump.accounts
+                    .into_iter()
+                    .filter(|(address, _)| addresses.contains(address)),
+            );
+        }
+        Ok(accounts)
+    }
+
     /// Read the rlp block file from the directory set at creation
     fn read_rlp_block(&mut self) -> eyre::Result<Vec<u8>> {
         let mut block_file = File::open(std::path::Path::new(&self.dirname).join("block.rlp"))?;
@@ -563,6 +1115,29 @@ impl DumpIpcReader {
         Ok(dump)
     }
 
+    /// Fetches the given accounts from the archive node it is currently connected to via IPC
+    async fn read_accounts(
+        &mut self,
+        addresses: &BTreeSet<Address>,
+    ) -> eyre::Result<HashMap<Address, DumpAccount>> {
+        let mut accounts = HashMap::new();
+        for address in addresses {
+            // Request a single account starting at the hashed address, which will be a different one if the account doesn't exist
+            let request = &json!({
+            "id": 1,
+            "jsonrpc": "2.0",
+            "method": "debug_accountRange",
+            "params": [format!("{:#x}", self.block_number), format!("{:#x}", keccak(address)), 1, false, false, false]
+            });
+            let response = send_ipc_json_request(&mut self.stream, request).await?;
+            let mut dump: Dump = serde_json::from_value(response)?;
+            if let Some(dump_account) = dump.accounts.remove(address) {
+                accounts.insert(*address, dump_account);
+            }
+        }
+        Ok(accounts)
+    }
+
     /// Fetches the RLP-encoded target blocks from the archive node it is currently connected to via IPC
     async fn read_rlp_block(&mut self) -> eyre::Result<Vec<u8>> {
         // Request block so we can store it and mark it as canonical
@@ -610,6 +1185,8 @@ struct CheckPoint {
 struct ProcessingCheckpoint {
     current_root: Option<H256>,
     current_file: Option<usize>,
+    #[serde(default)]
+    filter: Option<AccountFilter>,
 }
 
 #[derive(Deserialize, Debug, Serialize, Default)]
@@ -625,6 +1202,7 @@ fn load_checkpoint(
     file_input: bool,
     file_output: bool,
     no_sync: bool,
+    partial: bool,
 ) -> Result<Option<CheckPoint>, eyre::Error> {
     let prev_checkpoint: Option<CheckPoint> = match checkpoint {
         Some(checkpoint_filename) if std::path::Path::new(&checkpoint_filename).exists() => {
@@ -652,6 +1230,13 @@ fn load_checkpoint(
                 "Checkpoint file doesn't contain currnet root, try running with --no_sync",
             ));
         }
+        if checkpoint.processing.current_root.is_some()
+            && partial != checkpoint.processing.filter.is_some()
+        {
+            return Err(eyre::Error::msg(
+                "Partial sync flags don't match checkpoint data, --partial must be used in both runs or in none",
+            ));
+        }
         // Warn and request user approval before resuming a sync process with --no_sync flag
         if no_sync && checkpoint.processing.current_root.is_some() {
             println!(
@@ -682,6 +1267,7 @@ fn load_checkpoint(
 
 #[derive(Parser)]
 #[clap(group = ArgGroup::new("input").required(true).args(&["ipc_path", "input_dir"]).multiple(false))]
+#[clap(group = ArgGroup::new("include").args(&["include_addresses", "include_file"]).multiple(true))]
 struct Args {
     #[arg(
         required = true,
@@ -730,6 +1316,132 @@ struct Args {
         long_help = "Receives the name of the file where the checkpoint is/will be located. This checkpoint will be used to resume a previous archive sync process if aborted"
     )]
     pub checkpoint: Option<String>,
+    #[arg(
+        long = "partial",
+        value_name = "PARTIAL",
+        help = "If enabled, only the accounts set with --include_address or --include_file are written to the state",
+        long_help = "If enabled, only the accounts set with --include_address or --include_file are written to the state. As the resulting state root won't match the block's, the block is stored with the partial state root instead, and a manifest of the partial state is written to the datadir",
+        requires = "include",
+        conflicts_with = "no_sync"
+    )]
+    pub partial: bool,
+    #[arg(
+        long = "include_address",
+        alias = "include-address",
+        value_name = "ADDRESS",
+        help = "Account to write to the state on a partial sync. Can be used multiple times",
+        requires = "partial"
+    )]
+    pub include_addresses: Vec<Address>,
+    #[arg(
+        long = "include_file",
+        alias = "include-file",
+        value_name = "INCLUDE_FILE",
+        help = "JSON file with a list of accounts to write to the state on a partial sync",
+        requires = "partial"
+    )]
+    pub include_file: Option<PathBuf>,
+    #[arg(
+        long = "include_delegate_targets",
+        value_name = "INCLUDE_DELEGATE_TARGETS",
+        help = "If enabled, addresses pushed by included contracts that use DELEGATECALL are also included on a partial sync",
+        requires = "partial"
+    )]
+    pub include_delegate_targets: bool,
+    #[arg(
+        long = "storage_workers",
+        value_name = "STORAGE_WORKERS",
+        default_value_t = DEFAULT_STORAGE_WORKERS,
+        help = "Amount of storage tries built concurrently"
+    )]
+    pub storage_workers: usize,
+    #[arg(
+        long = "storage_memory_cap",
+        value_name = "MEGABYTES",
+        default_value_t = DEFAULT_STORAGE_MEMORY_CAP_MB,
+        help = "Max memory (in MiB) taken by storage slots waiting to be written to their tries",
+        long_help = "Max memory (in MiB) taken by storage slots waiting to be written to their tries. Reading state dumps is paused while it is reached, which bounds the memory used when syncing accounts with large storages"
+    )]
+    pub storage_memory_cap: usize,
+    #[arg(
+        long = "export_prestate",
+        alias = "export-prestate",
+        value_name = "PRESTATE_FILE",
+        help = "Writes the accounts set with --address to a prestate file instead of syncing",
+        long_help = "Writes the accounts set with --address, as of the target block, to a prestate file instead of syncing. A manifest with the block and the exported accounts is written next to it, with the `.manifest.json` extension",
+        requires = "addresses",
+        conflicts_with_all = ["output_dir", "no_sync", "checkpoint", "partial"]
+    )]
+    pub export_prestate: Option<PathBuf>,
+    #[arg(
+        long = "address",
+        value_name = "ADDRESS",
+        help = "Account to export with --export_prestate. Can be used multiple times",
+        requires = "export_prestate"
+    )]
+    pub addresses: Vec<Address>,
+    #[arg(
+        long = "slot",
+        value_name = "SLOT",
+        value_parser = parse_slot,
+        help = "Storage slot to export with --export_prestate, decimal or 0x-prefixed hex. Can be used multiple times, all slots are exported if not set",
+        requires = "export_prestate"
+    )]
+    pub slots: Vec<H256>,
+    #[arg(
+        long = "recursion_depth",
+        value_name = "DEPTH",
+        default_value_t = 0,
+        help = "Levels of contracts referenced by address-shaped storage values to export with --export_prestate",
+        requires = "export_prestate"
+    )]
+    pub recursion_depth: usize,
+}
+
+/// Parses a storage slot given as a decimal or 0x-prefixed hex number
+fn parse_slot(slot: &str) -> Result<H256, String> {
+    let slot = match slot.strip_prefix("0x") {
+        Some(hex) => U256::from_str_radix(hex, 16).map_err(|err| err.to_string())?,
+        None => U256::from_dec_str(slot).map_err(|err| err.to_string())?,
+    };
+    Ok(H256::from_uint(&slot))
+}
+
+impl Args {
+    /// Returns the partial sync settings, if enabled
+    fn partial_sync(&self) -> eyre::Result<Option<PartialSync>> {
+        if !self.partial {
+            return Ok(None);
+        }
+        let mut addresses = self.include_addresses.clone();
+        if let Some(include_file) = &self.include_file {
+            let included: Vec<Address> = serde_json::from_reader(File::open(include_file)?)?;
+            addresses.extend(included);
+        }
+        Ok(Some(PartialSync {
+            filter: AccountFilter::new(addresses, self.include_delegate_targets),
+            manifest_path: self.datadir.join(PARTIAL_STATE_MANIFEST),
+        }))
+    }
+
+    /// Returns the prestate export settings, if enabled
+    fn prestate_export(&self) -> Option<PrestateExport> {
+        let output_path = self.export_prestate.clone()?;
+        Some(PrestateExport {
+            addresses: self.addresses.iter().copied().collect(),
+            slots: self.slots.iter().copied().collect(),
+            recursion_depth: self.recursion_depth,
+            output_path,
+        })
+    }
+
+    /// Returns the settings of the workers building the storage tries
+    fn storage_trie_settings(&self) -> StorageTrieSettings {
+        StorageTrieSettings {
+            workers: self.storage_workers,
+            memory_cap: self.storage_memory_cap * 1024 * 1024,
+        }
+    }
 }
 
 #[tokio::main]
@@ -739,6 +1451,18 @@ pub async fn main() -> eyre::Result<()> {
         .expect("setting default subscriber failed");
     init_datadir(&args.datadir);
     let store = open_store(&args.datadir).expect("Failed to open Store");
+    if let Some(export) = args.prestate_export() {
+        return export_prestate(
+            args.ipc_path,
+            args.block_number,
+            args.input_dir,
+            store.get_chain_config(),
+            export,
+        )
+        .await;
+    }
+    let partial = args.partial_sync()?;
+    let storage_settings = args.storage_trie_settings();
     archive_sync(
         args.ipc_path,
         args.block_number,
@@ -747,6 +1471,562 @@ pub async fn main() -> eyre::Result<()> {
         args.no_sync,
         args.checkpoint,
         store,
+        partial,
+        storage_settings,
     )
     .await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethrex_common::types::{
+        BlockBody, BlockHeader, ChainConfig, CodeMetadata, EIP1559Transaction, Fork, Transaction,
+        TxKind, compute_storage_root,
+    };
+    use ethrex_levm::{
+        db::{Database, gen_db::GeneralizedDatabase},
+        environment::{EVMConfig, Environment},
+        errors::DatabaseError,
+        tracing::LevmCallTracer,
+        vm::{VM, VMType},
+    };
+    use ethrex_storage::EngineType;
+    use std::collections::BTreeMap;
+    use std::path::Path;
+
+    const SENDER: u64 = 0x1000;
+    const PROXY: u64 = 0x2000;
+    const IMPLEMENTATION: u64 = 0x3000;
+    const OTHER: u64 = 0x4000;
+    const GAS_LIMIT: u64 = 1_000_000;
+
+    fn address(n: u64) -> Address {
+        Address::from_low_u64_be(n)
+    }
+
+    /// Delegatecalls `implementation` and returns the first word it returns
+    fn proxy_code(implementation: Address) -> Vec<u8> {
+        let mut code = vec![0x60, 0x20, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00]; // retSize, retOffset, argsSize, argsOffset
+        code.push(PUSH20);
+        code.extend_from_slice(implementation.as_bytes());
+        code.extend_from_slice(&[0x5a, DELEGATECALL, 0x50]); // GAS, DELEGATECALL, POP
+        code.extend_from_slice(&[0x60, 0x20, 0x60, 0x00, 0xf3]); // RETURN(0, 32)
+        code
+    }
+
+    /// Returns the value at slot 0
+    fn implementation_code() -> Vec<u8> {
+        vec![
+            0x60, 0x00, 0x54, // SLOAD(0)
+            0x60, 0x00, 0x52, // MSTORE(0)
+            0x60, 0x20, 0x60, 0x00, 0xf3, // RETURN(0, 32)
+        ]
+    }
+
+    fn dump_account(balance: u64, code: Vec<u8>, storage: &[(u64, u64)]) -> DumpAccount {
+        let storage: HashMap<H256, U256> = storage
+            .iter()
+            .map(|(key, value)| (H256::from_low_u64_be(*key), U256::from(*value)))
+            .collect();
+        let storage_root = compute_storage_root(
+            &storage
+                .iter()
+                .map(|(key, value)| (key.into_uint(), *value))
+                .collect::<BTreeMap<_, _>>(),
+        );
+        let code = Bytes::from(code);
+        DumpAccount {
+            balance: U256::from(balance),
+            nonce: 0,
+            storage_root,
+            code_hash: keccak(&code),
+            code,
+            storage,
+            address: None,
+            hashed_address: None,
+        }
+    }
+
+    /// Writes the given dumps, an empty block and its (empty) block hashes to `dir`
+    fn write_input_dir(dir: &Path, dumps: Vec<Vec<(Address, DumpAccount)>>) {
+        let dump_count = dumps.len();
+        for (n, accounts) in dumps.into_iter().enumerate() {
+            let dump = Dump {
+                state_root: H256::repeat_byte(0xaa),
+                accounts: accounts.into_iter().collect(),
+                next: (n + 1 < dump_count).then(|| "next".to_string()),
+            };
+            serde_json::to_writer(
+                File::create(dir.join(format!("dump_{n}.json"))).unwrap(),
+                &dump,
+            )
+            .unwrap();
+        }
+        let block = Block::new(
+            BlockHeader {
+                number: 1,
+                state_root: H256::repeat_byte(0xaa),
+                ..Default::default()
+            },
+            BlockBody::default(),
+        );
+        std::fs::write(dir.join("block.rlp"), block.encode_to_vec()).unwrap();
+        std::fs::write(dir.join("block_hashes.json"), "[]").unwrap();
+    }
+
+    /// Reads accounts from the state at a given root
+    struct StateDatabase {
+        store: Store,
+        state_root: H256,
+    }
+
+    impl Database for StateDatabase {
+        fn get_account_state(&self, address: Address) -> Result<AccountState, DatabaseError> {
+            self.store
+                .get_account_state_by_root(self.state_root, address)
+                .map(Option::unwrap_or_default)
+                .map_err(|err| DatabaseError::Custom(err.to_string()))
+        }
+
+        fn get_storage_value(&self, address: Address, key: H256) -> Result<U256, DatabaseError> {
+            self.store
+                .get_storage_at_root(self.state_root, address, key)
+                .map(Option::unwrap_or_default)
+                .map_err(|err| DatabaseError::Custom(err.to_string()))
+        }
+
+        fn get_block_hash(&self, _block_number: u64) -> Result<H256, DatabaseError> {
+            Ok(H256::zero())
+        }
+
+        fn get_chain_config(&self) -> Result<ChainConfig, DatabaseError> {
+            Ok(ChainConfig::default())
+        }
+
+        fn get_account_code(&self, code_hash: H256) -> Result<Code, DatabaseError> {
+            self.store
+                .get_account_code(code_hash)
+                .map(Option::unwrap_or_default)
+                .map_err(|err| DatabaseError::Custom(err.to_string()))
+        }
+
+        fn get_code_metadata(&self, code_hash: H256) -> Result<CodeMetadata, DatabaseError> {
+            self.store
+                .get_code_metadata(code_hash)
+                .map(|metadata| metadata.unwrap_or(CodeMetadata { length: 0 }))
+                .map_err(|err| DatabaseError::Custom(err.to_string()))
+        }
+    }
+
+    /// Calls `to` from `SENDER` on top of the given state, returning the output
+    fn call(store: Store, state_root: H256, to: Address) -> Bytes {
+        let mut db = GeneralizedDatabase::new(Arc::new(StateDatabase { store, state_root }));
+        call_db(&mut db, to)
+    }
+
+    /// Calls `to` from `SENDER` on top of the given database, returning the output
+    fn call_db(db: &mut GeneralizedDatabase, to: Address) -> Bytes {
+        let fork = Fork::Prague;
+        let env = Environment {
+            origin: address(SENDER),
+            gas_limit: GAS_LIMIT,
+            config: EVMConfig::new(fork, EVMConfig::canonical_values(fork)),
+            block_number: U256::from(2),
+            coinbase: Address::from_low_u64_be(0xCCC),
+            timestamp: U256::from(1000),
+            prev_randao: Some(H256::zero()),
+            difficulty: U256::zero(),
+            slot_number: U256::zero(),
+            chain_id: U256::from(1),
+            base_fee_per_gas: U256::from(1000),
+            base_blob_fee_per_gas: U256::from(1),
+            gas_price: U256::from(1000),
+            block_excess_blob_gas: None,
+            block_blob_gas_used: None,
+            tx_blob_hashes: vec![],
+            tx_max_priority_fee_per_gas: None,
+            tx_max_fee_per_gas: Some(U256::from(1000)),
+            tx_max_fee_per_blob_gas: None,
+            tx_nonce: 0,
+            block_gas_limit: GAS_LIMIT * 2,
+            is_privileged: false,
+        };
+        let tx = Transaction::EIP1559Transaction(EIP1559Transaction {
+            to: TxKind::Call(to),
+            gas_limit: GAS_LIMIT,
+            max_fee_per_gas: 1000,
+            max_priority_fee_per_gas: 1,
+            ..Default::default()
+        });
+        let mut vm = VM::new(env, db, &tx, LevmCallTracer::disabled(), VMType::L1).unwrap();
+        let report = vm.execute().unwrap();
+        assert!(report.is_success());
+        report.output
+    }
+
+    /// Delegatecalls the address at slot 1 and returns the first word it returns
+    fn storage_proxy_code() -> Vec<u8> {
+        let mut code = vec![0x60, 0x20, 0x60, 0x00, 0x60, 0x00, 0x60, 0x00]; // retSize, retOffset, argsSize, argsOffset
+        code.extend_from_slice(&[0x60, 0x01, 0x54]); // SLOAD(1)
+        code.extend_from_slice(&[0x5a, DELEGATECALL, 0x50]); // GAS, DELEGATECALL, POP
+        code.extend_from_slice(&[0x60, 0x20, 0x60, 0x00, 0xf3]); // RETURN(0, 32)
+        code
+    }
+
+    /// Address-shaped address of the implementation of the storage proxy
+    fn far_implementation() -> Address {
+        Address::repeat_byte(0x33)
+    }
+
+    /// Writes a proxy reading its implementation from storage, its implementation, the sender and
+    /// an unrelated account to `dir`
+    fn write_storage_proxy_input_dir(dir: &Path) {
+        let mut proxy = dump_account(0, storage_proxy_code(), &[(0, 42), (2, 7)]);
+        proxy.storage.insert(
+            H256::from_low_u64_be(1),
+            U256::from_big_endian(far_implementation().as_bytes()),
+        );
+        write_input_dir(
+            dir,
+            vec![
+                vec![
+                    (address(PROXY), proxy),
+                    (address(OTHER), dump_account(7, vec![], &[(1, 1)])),
+                ],
+                vec![
+                    (
+                        far_implementation(),
+                        dump_account(0, implementation_code(), &[]),
+                    ),
+                    (address(SENDER), dump_account(10_000_000_000, vec![], &[])),
+                ],
+            ],
+        );
+    }
+
+    #[test]
+    fn address_values_skips_numbers() {
+        let storage = BTreeMap::from([
+            (H256::from_low_u64_be(0), H256::from_low_u64_be(u64::MAX)),
+            (H256::from_low_u64_be(1), H256::from(far_implementation())),
+            (H256::from_low_u64_be(2), H256::repeat_byte(0x33)),
+        ]);
+        assert_eq!(
+            address_values(&storage).collect::<Vec<_>>(),
+            vec![far_implementation()]
+        );
+    }
+
+    #[test]
+    fn parse_slot_accepts_decimal_and_hex() {
+        assert_eq!(parse_slot("10"), Ok(H256::from_low_u64_be(10)));
+        assert_eq!(parse_slot("0x10"), Ok(H256::from_low_u64_be(16)));
+        assert!(parse_slot("0xzz").is_err());
+    }
+
+    #[tokio::test]
+    async fn exported_prestate_replays_calls() {
+        let input_dir = tempfile::tempdir().unwrap();
+        let output_dir = tempfile::tempdir().unwrap();
+        write_storage_proxy_input_dir(input_dir.path());
+        let output_path = output_dir.path().join("prestate.json");
+
+        export_prestate(
+            None,
+            1,
+            Some(input_dir.path().display().to_string()),
+            ChainConfig::default(),
+            PrestateExport {
+                addresses: BTreeSet::from([address(SENDER), address(PROXY), address(0x5000)]),
+                slots: BTreeSet::new(),
+                recursion_depth: 1,
+                output_path: output_path.clone(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let json = std::fs::read_to_string(&output_path).unwrap();
+        let prestate: NativePrestate = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            prestate.accounts.keys().copied().collect::<BTreeSet<_>>(),
+            BTreeSet::from([address(SENDER), address(PROXY), far_implementation()])
+        );
+        assert_eq!(prestate.accounts[&address(PROXY)].storage.len(), 3);
+        assert_eq!(
+            prestate.accounts_with_storage,
+            BTreeSet::from([address(PROXY)])
+        );
+
+        let manifest: PrestateExportManifest = serde_json::from_reader(
+            File::open(output_dir.path().join("prestate.manifest.json")).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(manifest.block_number, 1);
+        assert_eq!(manifest.state_root, H256::repeat_byte(0xaa));
+        assert_eq!(prestate.block_hashes[&1], manifest.block_hash);
+        assert_eq!(
+            manifest.referenced,
+            BTreeMap::from([(far_implementation(), 1)])
+        );
+        assert_eq!(manifest.absent, BTreeSet::from([address(0x5000)]));
+
+        let mut db = GeneralizedDatabase::from_prestate(&json).unwrap();
+        let output = call_db(&mut db, address(PROXY));
+        assert_eq!(U256::from_big_endian(&output), U256::from(42));
+    }
+
+    #[tokio::test]
+    async fn exported_prestate_filters_slots_and_depth() {
+        let input_dir = tempfile::tempdir().unwrap();
+        let output_dir = tempfile::tempdir().unwrap();
+        write_storage_proxy_input_dir(input_dir.path());
+        let output_path = output_dir.path().join("prestate.json");
+
+        export_prestate(
+            None,
+            1,
+            Some(input_dir.path().display().to_string()),
+            ChainConfig::default(),
+            PrestateExport {
+                addresses: BTreeSet::from([address(PROXY)]),
+                slots: BTreeSet::from([H256::from_low_u64_be(0), H256::from_low_u64_be(1)]),
+                recursion_depth: 0,
+                output_path: output_path.clone(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let prestate: NativePrestate =
+            serde_json::from_reader(File::open(&output_path).unwrap()).unwrap();
+        assert_eq!(
+            prestate.accounts.keys().copied().collect::<Vec<_>>(),
+            vec![address(PROXY)]
+        );
+        assert_eq!(
+            prestate.accounts[&address(PROXY)]
+                .storage
+                .keys()
+                .copied()
+                .collect::<Vec<_>>(),
+            vec![H256::from_low_u64_be(0), H256::from_low_u64_be(1)]
+        );
+        let manifest: PrestateExportManifest = serde_json::from_reader(
+            File::open(output_path.with_extension(PRESTATE_MANIFEST_EXTENSION)).unwrap(),
+        )
+        .unwrap();
+        assert!(manifest.referenced.is_empty());
+        assert!(manifest.absent.is_empty());
+    }
+
+    #[test]
+    fn delegate_targets_requires_delegatecall() {
+        let implementation = address(IMPLEMENTATION);
+        assert_eq!(
+            delegate_targets(&proxy_code(implementation)),
+            vec![implementation]
+        );
+
+        // Same pushes, but calling with CALL
+        let code: Vec<u8> = proxy_code(implementation)
+            .into_iter()
+            .map(|byte| if byte == DELEGATECALL { 0xf1 } else { byte })
+            .collect();
+        assert!(delegate_targets(&code).is_empty());
+    }
+
+    #[test]
+    fn delegate_targets_skips_push_data() {
+        // PUSH32 whose data contains what looks like a PUSH20 and a DELEGATECALL
+        let mut code = vec![PUSH32, PUSH20];
+        code.extend_from_slice(&[0x11; 20]);
+        code.extend_from_slice(&[DELEGATECALL; 11]);
+        assert!(delegate_targets(&code).is_empty());
+
+        // Truncated PUSH20 at the end of the code
+        let mut code = vec![DELEGATECALL, PUSH20];
+        code.extend_from_slice(&[0x11; 10]);
+        assert!(delegate_targets(&code).is_empty());
+    }
+
+    #[tokio::test]
+    async fn partial_sync_includes_delegate_targets_and_supports_calls() {
+        let input_dir = tempfile::tempdir().unwrap();
+        let datadir = tempfile::tempdir().unwrap();
+        // The implementation is in a dump processed before the proxy is found
+        write_input_dir(
+            input_dir.path(),
+            vec![
+                vec![
+                    (
+                        address(IMPLEMENTATION),
+                        dump_account(0, implementation_code(), &[]),
+                    ),
+                    (address(OTHER), dump_account(7, vec![], &[(1, 1)])),
+                ],
+                vec![
+                    (
+                        address(PROXY),
+                        dump_account(0, proxy_code(address(IMPLEMENTATION)), &[(0, 42)]),
+                    ),
+                    (address(SENDER), dump_account(10_000_000_000, vec![], &[])),
+                ],
+            ],
+        );
+        let store = Store::new("store.db", EngineType::InMemory).unwrap();
+        let manifest_path = datadir.path().join(PARTIAL_STATE_MANIFEST);
+
+        archive_sync(
+            None,
+            1,
+            None,
+            Some(input_dir.path().display().to_string()),
+            false,
+            None,
+            store.clone(),
+            Some(PartialSync {
+                filter: AccountFilter::new([address(SENDER), address(PROXY)], true),
+                manifest_path: manifest_path.clone(),
+            }),
+            StorageTrieSettings::default(),
+        )
+        .await
+        .unwrap();
+
+        let manifest: PartialStateManifest =
+            serde_json::from_reader(File::open(manifest_path).unwrap()).unwrap();
+        assert_eq!(
+            manifest.accounts,
+            BTreeSet::from([address(SENDER), address(PROXY), address(IMPLEMENTATION)])
+        );
+        assert_eq!(manifest.state_root, H256::repeat_byte(0xaa));
+        assert_ne!(manifest.synthetic_state_root, manifest.state_root);
+
+        // The stored block points to the partial state
+        let header = store.get_block_header(1).unwrap().unwrap();
+        assert_eq!(header.state_root, manifest.synthetic_state_root);
+        assert_eq!(header.hash(), manifest.synthetic_block_hash);
+        assert!(
+            store
+                .get_account_info(1, address(OTHER))
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let output = call(store, manifest.synthetic_state_root, address(PROXY));
+        assert_eq!(U256::from_big_endian(&output), U256::from(42));
+    }
+
+    #[tokio::test]
+    async fn partial_sync_skips_absent_accounts() {
+        let input_dir = tempfile::tempdir().unwrap();
+        let datadir = tempfile::tempdir().unwrap();
+        write_input_dir(
+            input_dir.path(),
+            vec![vec![(address(OTHER), dump_account(7, vec![], &[(1, 1)]))]],
+        );
+        let store = Store::new("store.db", EngineType::InMemory).unwrap();
+        let manifest_path = datadir.path().join(PARTIAL_STATE_MANIFEST);
+
+        archive_sync(
+            None,
+            1,
+            None,
+            Some(input_dir.path().display().to_string()),
+            false,
+            None,
+            store.clone(),
+            Some(PartialSync {
+                filter: AccountFilter::new([address(OTHER), address(SENDER)], false),
+                manifest_path: manifest_path.clone(),
+            }),
+            StorageTrieSettings::default(),
+        )
+        .await
+        .unwrap();
+
+        let manifest: PartialStateManifest =
+            serde_json::from_reader(File::open(manifest_path).unwrap()).unwrap();
+        assert_eq!(manifest.accounts, BTreeSet::from([address(OTHER)]));
+        let info = store.get_account_info(1, address(OTHER)).await.unwrap();
+        assert_eq!(info.map(|info| info.balance), Some(U256::from(7)));
+    }
+
+    #[tokio::test]
+    async fn storage_tries_are_built_within_memory_cap() {
+        const LARGE_STORAGE_SLOTS: u64 = 500_000;
+        const SMALL_ACCOUNTS: u64 = 200;
+        let store = Store::new("store.db", EngineType::InMemory).unwrap();
+        let settings = StorageTrieSettings {
+            workers: 4,
+            memory_cap: 20_000 * STORAGE_SLOT_SIZE,
+        };
+        let mut storage_pool = StorageTriePool::spawn(store.clone(), settings);
+        let large_storage: Vec<(u64, u64)> = (1..=LARGE_STORAGE_SLOTS).map(|n| (n, n)).collect();
+        let accounts = std::iter::once((address(OTHER), dump_account(0, vec![], &large_storage)))
+            .chain((1..=SMALL_ACCOUNTS).map(|n| {
+                (
+                    address(n),
+                    dump_account(0, vec![], &[(n, n + 1), (n + 1, n)]),
+                )
+            }))
+            .collect();
+        let dump = Dump {
+            state_root: H256::zero(),
+            accounts,
+            next: None,
+        };
+
+        let state_root = process_dump(
+            dump,
+            store.clone(),
+            *EMPTY_TRIE_HASH,
+            None,
+            &mut storage_pool,
+        )
+        .await
+        .unwrap();
+        let peak_in_flight_slots = storage_pool.peak_in_flight_slots;
+        // Every storage trie matches its storage root
+        storage_pool.finish().await.unwrap();
+
+        assert!(peak_in_flight_slots > 0);
+        assert!(peak_in_flight_slots <= settings.max_in_flight_slots());
+        for slot in [1, LARGE_STORAGE_SLOTS / 2, LARGE_STORAGE_SLOTS] {
+            let value = store
+                .get_storage_at_root(state_root, address(OTHER), H256::from_low_u64_be(slot))
+                .unwrap();
+            assert_eq!(value, Some(U256::from(slot)));
+        }
+        let value = store
+            .get_storage_at_root(
+                state_root,
+                address(SMALL_ACCOUNTS),
+                H256::from_low_u64_be(SMALL_ACCOUNTS),
+            )
+            .unwrap();
+        assert_eq!(value, Some(U256::from(SMALL_ACCOUNTS + 1)));
+    }
+
+    #[tokio::test]
+    async fn storage_root_mismatch_is_reported() {
+        let store = Store::new("store.db", EngineType::InMemory).unwrap();
+        let mut storage_pool =
+            StorageTriePool::spawn(store.clone(), StorageTrieSettings::default());
+        let mut account = dump_account(0, vec![], &[(1, 1)]);
+        account.storage_root = H256::repeat_byte(0xaa);
+        let dump = Dump {
+            state_root: H256::zero(),
+            accounts: [(address(OTHER), account)].into_iter().collect(),
+            next: None,
+        };
+
+        process_dump(dump, store, *EMPTY_TRIE_HASH, None, &mut storage_pool)
+            .await
+            .unwrap();
+        assert!(storage_pool.finish().await.is_err());
+    }
+}
@@ -10,6 +10,7 @@ use ethrex_blockchain::{
     fork_choice::apply_fork_choice,
 };
 use ethrex_common::{
+    CommitmentMismatch,
     constants::EMPTY_KECCACK_HASH,
     types::{
         Account as CoreAccount, Block as CoreBlock, BlockHeader as CoreBlockHeader,
@@ -197,7 +198,9 @@ fn exception_is_expected(
                 ChainError::InvalidBlock(_)
             ) | (
                 BlockChainExpectedException::BlockException(BlockExpectedException::InvalidRequest),
-                ChainError::InvalidBlock(InvalidBlockError::RequestsHashMismatch)
+                ChainError::InvalidBlock(InvalidBlockError::CommitmentMismatch(
+                    CommitmentMismatch::RequestsHash { .. }
+                ))
             ) | (
                 BlockChainExpectedException::BlockException(
                     BlockExpectedException::SystemContractCallFailed
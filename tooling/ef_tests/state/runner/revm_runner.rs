@@ -615,6 +615,9 @@ pub async fn _ensure_post_state_revm(
                             gas_refunded: 42,
                             logs: vec![],
                             output: Bytes::new(),
+                            max_depth: 0,
+                            gas_breakdown: None,
+                            l2_fees: None,
                         }),
                         //TODO: This is not a TransactionReport because it is REVM
                         error_reason,
@@ -637,6 +640,9 @@ pub async fn _ensure_post_state_revm(
                                 gas_refunded: 42,
                                 logs: vec![],
                                 output: Bytes::new(),
+                                max_depth: 0,
+                                gas_breakdown: None,
+                                l2_fees: None,
                             }),
                             //TODO: This is not a TransactionReport because it is REVM
                             format!("Post-state root mismatch on REVM runner, line: {}", line!())
@@ -10,7 +10,7 @@ use std::{
 use ethrex::{cli::Options, initializers::get_network};
 use ethrex_common::{
     Address, Bytes, H160, H256, U256,
-    evm::calculate_create_address,
+    evm::compute_create_address,
     types::{
         Block, EIP1559Transaction, Genesis, Transaction, TxKind, requests::compute_requests_hash,
     },
@@ -459,7 +459,7 @@ impl Node {
             .await
             .unwrap();
 
-        calculate_create_address(sender_address, nonce)
+        compute_create_address(sender_address, nonce)
     }
 
     pub async fn get_balance(&self, address: H160) -> U256 {